@@ -0,0 +1,122 @@
+//! Stable library facade.
+//! - `Mover`: a validated configuration plus the move and sweep entry points.
+//! - `ConfigBuilder`: fluent construction of the configuration a Mover is built from.
+//! - `MoveOptions`: per-move overrides (duplicate policies, symlink policy, dry run, aria2 GID).
+//! - `MoveReport`: what a single move did (including `MoveTimings`); `MovePlan`: what it
+//!   would do (`Mover::plan`).
+//! - `Error` / `Result`: the typed error returned by every call here.
+//! - `CopyEngine` / `EngineRegistry`: plug in another way of copying file data, selected
+//!   by `copy_engine` in the configuration.
+//!
+//! Notes:
+//! - Items in this module follow semver; within a major version they only gain additions.
+//! - Lower-level pieces (lock primitives, the atomic rename, resume temp names) are only
+//!   exported with the `unstable` feature and may change in any release.
+
+use std::path::{Path, PathBuf};
+
+use crate::config::types::Config;
+use crate::config::validate_and_normalize;
+use crate::fs_ops::{
+    move_entry_timed_on_full, plan_move, resolve_source_path, sweep_download_base,
+};
+
+pub use crate::config::builder::ConfigBuilder;
+pub use crate::config::types::{DestSymlinkPolicy, SymlinkPolicy};
+pub use crate::errors::AriaMoveError as Error;
+pub use crate::fs_ops::{
+    CopyEngine, EngineCapabilities, EngineRegistry, Handler, HandlerAction, MoveOptions, MovePlan,
+    MoveTimings, OnDuplicate, OnFull, OnRedownload, SweepReport,
+};
+
+/// Result type of the stable API.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Outcome of one successful move.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MoveReport {
+    /// The path that was moved (as given or as resolved).
+    pub source: PathBuf,
+    /// Final location under completed_base (or the remote destination).
+    pub destination: PathBuf,
+    /// True if nothing was changed on disk.
+    pub dry_run: bool,
+    /// True if the source was copied and left in place (`copy_only`).
+    pub source_retained: bool,
+    /// Phase timings and bytes copied (throughput via `MoveTimings::throughput_mb_s`).
+    pub timings: MoveTimings,
+}
+
+/// Moves completed downloads according to a validated configuration.
+#[derive(Debug, Clone)]
+pub struct Mover {
+    config: Config,
+}
+
+impl Mover {
+    /// Build the configuration from `builder` and validate it (missing bases are created).
+    pub fn new(builder: ConfigBuilder) -> Result<Self> {
+        Ok(Self {
+            config: builder.build()?,
+        })
+    }
+
+    /// Validate an existing Config (for example one loaded from XML).
+    pub fn from_config(mut config: Config) -> Result<Self> {
+        validate_and_normalize(&mut config)?;
+        Ok(Self { config })
+    }
+
+    /// The validated configuration (bases are canonical).
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Move `source` (file, directory or symlink) into completed_base; a full destination
+    /// is handled per `on_full`.
+    pub fn move_path(&self, source: &Path) -> Result<MoveReport> {
+        self.move_path_with(source, &MoveOptions::default())
+    }
+
+    /// `move_path` with per-move overrides.
+    pub fn move_path_with(&self, source: &Path, opts: &MoveOptions) -> Result<MoveReport> {
+        let (destination, timings) = move_entry_timed_on_full(&self.config, source, opts)?;
+        Ok(self.report(source.to_path_buf(), destination, timings, opts))
+    }
+
+    /// Describe what `move_path` would do (destination, rename or copy, space, locks)
+    /// without touching anything.
+    pub fn plan(&self, source: &Path) -> Result<MovePlan> {
+        Ok(plan_move(&self.config, source)?)
+    }
+
+    /// Pick the newest eligible item in download_base and move it.
+    pub fn move_next(&self) -> Result<MoveReport> {
+        let source = resolve_source_path(&self.config, None)?;
+        let opts = MoveOptions::default();
+        let (destination, timings) = move_entry_timed_on_full(&self.config, &source, &opts)?;
+        Ok(self.report(source, destination, timings, &opts))
+    }
+
+    /// Report (and with `move_items`, move) items left behind in download_base.
+    pub fn sweep(&self, move_items: bool) -> Result<SweepReport> {
+        Ok(sweep_download_base(&self.config, None, move_items)?)
+    }
+
+    fn report(
+        &self,
+        source: PathBuf,
+        destination: PathBuf,
+        timings: MoveTimings,
+        opts: &MoveOptions,
+    ) -> MoveReport {
+        MoveReport {
+            source,
+            destination,
+            dry_run: opts.dry_run.unwrap_or(self.config.dry_run),
+            source_retained: self.config.copy_only,
+            timings,
+        }
+    }
+}
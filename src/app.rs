@@ -2,69 +2,103 @@
 //! Loads/merges config, initializes logging, installs signal handlers, validates paths,
 //! resolves the source, and invokes the appropriate move operation.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use aria_move::AriaMoveError;
 use aria_move::output as out;
 use std::sync::{Arc, Mutex};
-use tracing::{debug, error, info};
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
 
 use aria_move::config::xml::load_config_from_xml;
-use aria_move::config::{LoadResult, load_or_init, validate_and_normalize};
-use aria_move::{Config, LogLevel, default_config_path, move_entry, resolve_source_path, shutdown};
+use aria_move::config::{
+    LoadResult, init_config, load_or_init, validate_and_normalize, validate_read_only,
+};
+use aria_move::fs_ops::{SweepReport, move_entry_on_full, watch_free_space};
+use aria_move::notify::{self, NotifyEvent};
+use aria_move::{
+    Config, LogLevel, MoveOptions, default_config_path, list_candidates, plan_move,
+    resolve_source_path, shutdown, sweep_download_base,
+};
 
-use crate::logging::init_tracing;
-use aria_move::cli::Args;
+use crate::aria2_conf;
+use crate::health;
+use crate::launchd;
+use crate::logging::{bootstrap_dispatch, init_tracing};
+use crate::selftest;
+use aria_move::cli::{
+    Args, AuditCommand, BenchArgs, Command, ConfigCommand, LaunchdCommand, MirrorArgs, WatchArgs,
+    write_completions,
+};
 
 /// Run the CLI application.
 pub fn run(args: Args) -> Result<()> {
+    // Subcommands that don't need config, logging or locks.
+    if let Some(Command::Completions { shell }) = &args.command {
+        write_completions(*shell, &mut std::io::stdout());
+        return Ok(());
+    }
+    let global = &args.global;
+
     // Apply --config early: highest precedence, before template creation or print-config logic
-    if let Some(p) = args.config_path.as_ref() {
-        unsafe { std::env::set_var("ARIA_MOVE_CONFIG", p); }
+    if let Some(p) = global.config_path.as_ref() {
+        unsafe {
+            std::env::set_var("ARIA_MOVE_CONFIG", p);
+        }
+    }
+    // --no-auto-config / machine mode: read-only deployments and aria2 hooks get ConfigMissing
+    // instead of a template (a hook that only wrote a template would exit 0 having moved nothing).
+    if global.no_auto_config || args.machine_mode() {
+        unsafe {
+            std::env::set_var("ARIA_MOVE_NO_TEMPLATE", "1");
+        }
     }
 
-    // Handle --print-config before logging init
-    if args.print_config {
-        if let Ok(cfg_env) = std::env::var("ARIA_MOVE_CONFIG") {
-            out::print_info(&format!(
-                "Using ARIA_MOVE_CONFIG (explicit):\n  {}\n",
-                cfg_env
-            ));
-            out::print_info("To override, unset ARIA_MOVE_CONFIG or set it to another file.");
-            return Ok(());
+    // Console reporter settings must be in place before any config/log messages are printed.
+    if global.no_color {
+        out::set_color_choice(out::ColorChoice::Never);
+    }
+    out::set_debug(global.effective_log_level() == Some(LogLevel::Debug));
+    out::set_quiet(global.quiet);
+    out::set_warn_only(global.effective_log_level() == Some(LogLevel::Warn));
+    if let Some(stream) = global.console_stream {
+        out::set_console_stream(stream);
+    }
+
+    // Config loading logs before `init_tracing`; until then these go to the console at the
+    // level given on the command line.
+    let bootstrap = tracing::dispatcher::set_default(&bootstrap_dispatch(
+        global.effective_log_level(),
+        global.log_filter.as_deref(),
+        global.json,
+    ));
+
+    match &args.command {
+        Some(Command::Config { action }) => return run_config(*action),
+        Some(Command::Launchd { action, interval }) => return run_launchd(*action, interval.get()),
+        Some(Command::Healthcheck { file, max_age }) => {
+            return run_healthcheck(file.as_deref(), max_age.get());
         }
-        match default_config_path() {
-            Ok(p) => {
-                out::print_info(&format!(
-                    "Default aria_move config path:\n  {}\n",
-                    p.display()
-                ));
-                if p.exists() {
-                    out::print_info("A config file already exists at that location.");
-                } else {
-                    out::print_info(
-                        "No config file exists there yet. Run without --print-config to create a template.",
-                    );
-                }
-            }
-            Err(e) => {
-                out::print_error(&format!("Could not determine a default config path: {e}"));
-            }
+        Some(Command::Audit { action }) => return run_audit(action),
+        Some(Command::History { file, limit }) => return run_history(file.as_deref(), *limit),
+        Some(Command::Verify { path }) => return run_verify(path.as_deref()),
+        Some(Command::InstallHook { aria2_conf, check }) => {
+            return run_install_hook(aria2_conf, *check);
         }
+        _ => {}
+    }
+    let margs = args.move_args();
+    let planning = matches!(args.command, Some(Command::Plan(_)));
+    let status = matches!(args.command, Some(Command::Status(_)));
+
+    // Handle --print-config before logging init
+    if margs.print_config {
+        print_config_path();
         return Ok(());
     }
 
     // Create template config if none exists at resolved path (before logging init)
     if let LoadResult::CreatedTemplate(path) = load_or_init()? {
-        out::print_success(&format!(
-            "A template aria_move config was written to: {}",
-            path.display()
-        ));
-        out::print_info(
-            "Edit the file to set `download_base`, `completed_base` and optionally `log_level` and `log_file`. Example:\n\n<config>\n  <download_base>/path/to/incoming</download_base>\n  <completed_base>/path/to/completed</completed_base>\n  <log_level>normal</log_level>\n  <log_file>/path/to/aria_move.log</log_file>\n</config>\n",
-        );
-        out::print_info(
-            "Then re-run this command. To use a different location pass --config or set ARIA_MOVE_CONFIG.",
-        );
+        print_template_created(&path);
         return Ok(());
     }
 
@@ -72,58 +106,326 @@ pub fn run(args: Args) -> Result<()> {
     let mut cfg = Config::default();
 
     // Prefer config file values unless CLI overrides them.
-    if let Some((db, cb, lvl, lf, preserve_metadata, preserve_permissions, disable_locks)) =
-        load_config_from_xml()
-    {
-        if args.download_base.is_none() {
-            cfg.download_base = db;
+    if let Some(loaded) = load_config_from_xml()? {
+        if margs.download_base.is_none() {
+            cfg.download_base = loaded.download_base;
         }
-        if args.completed_base.is_none() {
-            cfg.completed_base = cb;
+        if margs.completed_base.is_none() {
+            cfg.completed_base = loaded.completed_base;
         }
-        if args.log_level.is_none()
-            && let Some(l) = lvl
+        if global.log_level.is_none()
+            && let Some(l) = loaded.log_level
         {
             cfg.log_level = l;
         }
-        if let Some(xml_log) = lf {
+        if let Some(filter) = loaded.log_filter {
+            cfg.log_filter = Some(filter);
+        }
+        cfg.honor_rust_log = loaded.honor_rust_log;
+        cfg.trace_fs_ops = loaded.trace_fs_ops;
+        if let Some(format) = loaded.log_time_format {
+            cfg.log_time_format = format;
+        }
+        cfg.log_utc = loaded.log_utc;
+        if let Some(xml_log) = loaded.log_file {
             cfg.log_file = Some(xml_log);
         }
-        cfg.preserve_metadata = preserve_metadata;
+        if let Some(stream) = loaded.console_stream {
+            cfg.console_stream = stream;
+        }
+        cfg.preserve_metadata = loaded.preserve_metadata;
         // Only set permissions flag if full metadata not requested (XML semantics mirror CLI precedence)
         if !cfg.preserve_metadata {
-            cfg.preserve_permissions = preserve_permissions;
+            cfg.preserve_permissions = loaded.preserve_permissions;
+        }
+        if let Some(mode) = loaded.dest_dir_mode {
+            cfg.dest_dir_mode = Some(mode);
+        }
+        if let Some(mode) = loaded.created_dir_mode {
+            cfg.created_dir_mode = mode;
+        }
+        cfg.preserve_acls = loaded.preserve_acls;
+        cfg.disable_locks = loaded.disable_locks;
+        if let Some(strategy) = loaded.lock_strategy {
+            cfg.lock_strategy = strategy;
+        }
+        if let Some(order) = loaded.copy_order {
+            cfg.copy_order = order;
+        }
+        if let Some(backend) = loaded.copy_backend {
+            cfg.copy_backend = backend;
+        }
+        if let Some(engine) = loaded.copy_engine {
+            cfg.copy_engine = Some(engine);
+        }
+        if let Some(verify) = loaded.resume_verify {
+            cfg.resume_verify = verify;
+        }
+        cfg.partial_dir_moves = loaded.partial_dir_moves;
+        cfg.copy_only = loaded.copy_only;
+        cfg.bt_mode = loaded.bt_mode;
+        if let Some(url) = loaded.aria2_rpc_url {
+            cfg.aria2_rpc_url = url;
+        }
+        cfg.aria2_rpc_secret = loaded.aria2_rpc_secret;
+        if let Some(action) = loaded.rpc_action {
+            cfg.rpc_action = action;
+        }
+        cfg.force_copy = loaded.force_copy;
+        cfg.content_sanity_check = loaded.content_sanity_check;
+        cfg.unnamed_temp = loaded.unnamed_temp;
+        if let Some(policy) = loaded.fsync_policy {
+            cfg.fsync_policy = policy;
+        }
+        if let Some(policy) = loaded.dir_on_duplicate {
+            cfg.dir_on_duplicate = policy;
+        }
+        if let Some(policy) = loaded.merge_on_conflict {
+            cfg.merge_on_conflict = policy;
+        }
+        if let Some(policy) = loaded.symlink_source_policy {
+            cfg.symlink_source_policy = policy;
+        }
+        if let Some(policy) = loaded.dest_symlink_policy {
+            cfg.dest_symlink_policy = policy;
+        }
+        if let Some(required) = loaded.require_source_in_base {
+            cfg.require_source_in_base = required;
+        }
+        if let Some(policy) = loaded.on_redownload {
+            cfg.on_redownload = policy;
+        }
+        if let Some(policy) = loaded.on_full {
+            cfg.on_full = policy;
+        }
+        if let Some(wait) = loaded.on_full_max_wait {
+            cfg.on_full_max_wait = wait;
+        }
+        cfg.overflow_bases = loaded.overflow_bases;
+        if let Some(window) = loaded.recent_window {
+            cfg.recent_window = window;
+        }
+        cfg.resolve_directories = loaded.resolve_directories;
+        cfg.include_hidden = loaded.include_hidden;
+        if let Some(presets) = loaded.partial_extensions_preset {
+            cfg.partial_extensions_preset = presets;
+        }
+        cfg.partial_extensions = loaded.partial_extensions;
+        cfg.tag_destination = loaded.tag_destination;
+        cfg.handlers = loaded.handlers;
+        cfg.notifiers = loaded.notifiers;
+        if let Some(bin) = loaded.rclone_binary {
+            cfg.rclone_binary = bin;
+        }
+        if let Some(hb) = loaded.heartbeat_file {
+            cfg.heartbeat_file = Some(hb);
+        }
+        if let Some(log) = loaded.audit_log {
+            cfg.audit_log = Some(log);
+        }
+        if let Some(dir) = loaded.diagnostics_dir {
+            cfg.diagnostics_dir = Some(dir);
+        }
+        if let Some(mib) = loaded.low_space_warn_mib {
+            cfg.low_space_warn_mib = Some(mib);
+        }
+        if let Some(size) = loaded.space_cushion {
+            cfg.space_cushion = size;
+        }
+        if let Some(user) = loaded.run_as_user {
+            cfg.run_as_user = Some(user);
+        }
+        if let Some(group) = loaded.run_as_group {
+            cfg.run_as_group = Some(group);
+        }
+        if let Some(n) = loaded.max_concurrent_moves {
+            cfg.max_concurrent_moves = n;
+        }
+        if let Some(limit) = loaded.max_runtime {
+            cfg.max_runtime = Some(limit);
+        }
+        if let Some(limit) = loaded.slow_move_warning {
+            cfg.slow_move_warning = Some(limit);
         }
-        cfg.disable_locks = disable_locks;
     }
 
     // Apply CLI overrides (CLI wins)
-    if let Some(db) = args.download_base.as_ref() {
+    if let Some(db) = margs.download_base.as_ref() {
         cfg.download_base = db.clone();
     }
-    if let Some(cb) = args.completed_base.as_ref() {
+    if let Some(cb) = margs.completed_base.as_ref() {
         cfg.completed_base = cb.clone();
     }
-    if let Some(lvl_str) = args.log_level.as_ref() {
-        if let Some(parsed) = LogLevel::parse(lvl_str) {
-            cfg.log_level = parsed;
-        }
-    } else if args.debug {
+    if let Some(level) = global.log_level {
+        cfg.log_level = level;
+    } else if global.debug {
         cfg.log_level = LogLevel::Debug;
     }
-    if args.preserve_metadata {
+    if global.quiet {
+        cfg.log_level = LogLevel::Quiet;
+    }
+    if let Some(filter) = global.log_filter.as_ref() {
+        cfg.log_filter = (!filter.trim().is_empty()).then(|| filter.clone());
+    }
+    if global.honor_rust_log {
+        cfg.honor_rust_log = true;
+    }
+    if global.trace_fs_ops {
+        cfg.trace_fs_ops = true;
+    }
+    aria_move::fs_ops::set_trace_fs_ops(cfg.trace_fs_ops);
+    if let Some(format) = global.log_time_format.as_ref() {
+        cfg.log_time_format = format.clone();
+    }
+    if global.log_utc {
+        cfg.log_utc = true;
+    }
+    if let Some(stream) = global.console_stream {
+        cfg.console_stream = stream;
+    }
+    out::set_console_stream(cfg.console_stream);
+    out::set_warn_only(cfg.log_level == LogLevel::Warn);
+    if margs.preserve_metadata {
         cfg.preserve_metadata = true;
     }
-    if args.preserve_permissions && !cfg.preserve_metadata {
+    if margs.preserve_permissions && !cfg.preserve_metadata {
         cfg.preserve_permissions = true;
     }
-    if args.dry_run {
+    if let Some(mode) = margs.dest_dir_mode {
+        cfg.dest_dir_mode = Some(mode);
+    }
+    if let Some(mode) = margs.created_dir_mode {
+        cfg.created_dir_mode = mode;
+    }
+    if margs.preserve_acls {
+        cfg.preserve_acls = true;
+    }
+    if margs.dry_run {
+        cfg.dry_run = true;
+    }
+    if let Some(order) = margs.copy_order {
+        cfg.copy_order = order;
+    }
+    if let Some(backend) = margs.copy_backend {
+        cfg.copy_backend = backend;
+    }
+    if let Some(engine) = margs.copy_engine.as_ref() {
+        cfg.copy_engine = Some(engine.clone());
+    }
+    if let Some(verify) = margs.resume_verify {
+        cfg.resume_verify = verify;
+    }
+    if let Some(strategy) = margs.lock_strategy {
+        cfg.lock_strategy = strategy;
+    }
+    if margs.partial_dir_moves {
+        cfg.partial_dir_moves = true;
+    }
+    if margs.copy_only {
+        cfg.copy_only = true;
+    }
+    if margs.bt_mode {
+        cfg.bt_mode = true;
+    }
+    if let Some(url) = &margs.aria2_rpc_url {
+        cfg.aria2_rpc_url = url.clone();
+    }
+    if let Some(action) = margs.rpc_action {
+        cfg.rpc_action = action;
+    }
+    if margs.force_copy {
+        cfg.force_copy = true;
+    }
+    if margs.content_sanity_check {
+        cfg.content_sanity_check = true;
+    }
+    if margs.unnamed_temp {
+        cfg.unnamed_temp = true;
+    }
+    if let Some(policy) = margs.fsync_policy {
+        cfg.fsync_policy = policy;
+    }
+    if let Some(policy) = margs.dir_on_duplicate {
+        cfg.dir_on_duplicate = policy;
+    }
+    if let Some(policy) = margs.merge_on_conflict {
+        cfg.merge_on_conflict = policy;
+    }
+    if let Some(policy) = margs.symlink_source_policy {
+        cfg.symlink_source_policy = policy;
+    }
+    if let Some(policy) = margs.dest_symlink_policy {
+        cfg.dest_symlink_policy = policy;
+    }
+    if margs.allow_outside_base {
+        cfg.require_source_in_base = false;
+    }
+    if let Some(policy) = margs.on_redownload {
+        cfg.on_redownload = policy;
+    }
+    if let Some(policy) = margs.on_full {
+        cfg.on_full = policy;
+    }
+    if let Some(wait) = margs.on_full_max_wait {
+        cfg.on_full_max_wait = wait.get();
+    }
+    if !margs.overflow_base.is_empty() {
+        cfg.overflow_bases = margs.overflow_base.clone();
+    }
+    if let Some(window) = margs.recent_window {
+        cfg.recent_window = window.get();
+    }
+    if margs.resolve_directories {
+        cfg.resolve_directories = true;
+    }
+    if margs.include_hidden {
+        cfg.include_hidden = true;
+    }
+    if margs.tag_destination {
+        cfg.tag_destination = true;
+    }
+    if let Some(bin) = margs.rclone_binary.as_ref() {
+        cfg.rclone_binary = bin.clone();
+    }
+    if let Some(hb) = margs.heartbeat_file.as_ref() {
+        cfg.heartbeat_file = Some(hb.clone());
+    }
+    if let Some(log) = margs.audit_log.as_ref() {
+        cfg.audit_log = Some(log.clone());
+    }
+    if let Some(dir) = margs.diagnostics_dir.as_ref() {
+        cfg.diagnostics_dir = Some(dir.clone());
+    }
+    if let Some(mib) = margs.low_space_warn_mib {
+        cfg.low_space_warn_mib = (mib > 0).then_some(mib);
+    }
+    if let Some(size) = margs.space_cushion {
+        cfg.space_cushion = size;
+    }
+    if let Some(user) = margs.run_as_user.as_ref() {
+        cfg.run_as_user = Some(user.clone());
+    }
+    if let Some(group) = margs.run_as_group.as_ref() {
+        cfg.run_as_group = Some(group.clone());
+    }
+    if let Some(n) = margs.max_concurrent_moves {
+        cfg.max_concurrent_moves = n;
+    }
+    if let Some(limit) = margs.max_runtime {
+        cfg.max_runtime = (!limit.get().is_zero()).then(|| limit.get());
+    }
+    if let Some(limit) = margs.slow_move_warning {
+        cfg.slow_move_warning = (!limit.get().is_zero()).then(|| limit.get());
+    }
+    // `plan` and `status` never change anything (this also keeps the heartbeat untouched).
+    if planning || status {
         cfg.dry_run = true;
     }
 
     // Initialize logging and capture the guard so we can drop it on signal
+    drop(bootstrap);
     let guard_opt: Option<tracing_appender::non_blocking::WorkerGuard> =
-        init_tracing(&cfg.log_level, cfg.log_file.as_deref(), args.json).map_err(|e| {
+        init_tracing(&cfg, global.json).map_err(|e| {
             out::print_error(&format!("Failed to initialize logging: {}", e));
             e
         })?;
@@ -132,15 +434,34 @@ pub fn run(args: Args) -> Result<()> {
     let guard_slot = Arc::new(Mutex::new(guard_opt));
     {
         let guard_slot = Arc::clone(&guard_slot);
+        let mut signals = 0u32;
+        // ctrlc covers SIGINT/SIGTERM/SIGHUP and all Windows console events; SIGQUIT joins them.
+        if let Err(e) = aria_move::platform::forward_sigquit_to_sigterm() {
+            warn!(error = %e, "could not install SIGQUIT handler");
+        }
         ctrlc::set_handler(move || {
+            signals += 1;
+            if signals > 1 {
+                // Second signal: do not wait for the copy; remove its temp and exit now.
+                let removed = shutdown::remove_tracked_temps();
+                out::print_warn(&format!(
+                    "Received second interrupt; removed {removed} temp file(s), exiting now"
+                ));
+                std::process::exit(INTERRUPT_EXIT_CODE);
+            }
             shutdown::request();
-            out::print_warn("Received interrupt; shutting down gracefully...");
+            out::print_warn(
+                "Received interrupt; shutting down gracefully (interrupt again to exit now)...",
+            );
             if let Ok(mut g) = guard_slot.lock() {
                 let _ = g.take(); // drop guard here to flush tracing_appender
             }
         })
         .expect("failed to install signal handler");
     }
+    if let Some(limit) = cfg.max_runtime {
+        start_runtime_watchdog(limit, Arc::clone(&guard_slot));
+    }
 
     if shutdown::is_requested() {
         return Ok(());
@@ -150,15 +471,88 @@ pub fn run(args: Args) -> Result<()> {
 
     // Main run (so we can drop guard after)
     let result = (|| -> Result<()> {
-        // Ensure required directories exist and canonicalize paths
-        validate_and_normalize(&mut cfg)?;
+        if cfg.dry_run {
+            // Previews (`plan`, `--dry-run`) validate the same way but create nothing.
+            validate_read_only(&mut cfg)?;
+        } else {
+            // Ensure required directories exist and canonicalize paths
+            validate_and_normalize(&mut cfg)?;
+        }
+
+        // Directories are validated and the log file is open: continue unprivileged if asked.
+        if cfg.run_as_user.is_some() || cfg.run_as_group.is_some() {
+            aria_move::platform::drop_privileges(
+                cfg.run_as_user.as_deref(),
+                cfg.run_as_group.as_deref(),
+            )?;
+            info!(user = ?cfg.run_as_user, group = ?cfg.run_as_group, "Dropped privileges");
+        }
+
+        // OpenBSD: from here on only the configured directories and file I/O syscalls are needed.
+        #[cfg(target_os = "openbsd")]
+        {
+            let allow_exec = cfg.copy_backend == aria_move::CopyBackend::Rsync
+                || cfg.copy_engine.is_some()
+                || aria_move::fs_ops::is_remote(&cfg.completed_base);
+            let mut rw: Vec<&std::path::Path> = vec![&cfg.download_base, &cfg.completed_base];
+            rw.extend(cfg.log_file.as_deref().and_then(|p| p.parent()));
+            rw.extend(cfg.heartbeat_file.as_deref().and_then(|p| p.parent()));
+            rw.extend(cfg.audit_log.as_deref().and_then(|p| p.parent()));
+            rw.extend(cfg.diagnostics_dir.as_deref());
+            rw.retain(|p| !p.as_os_str().is_empty());
+            aria_move::platform::sandbox(&rw, allow_exec).context("pledge/unveil")?;
+        }
+
+        if matches!(args.command, Some(Command::Clean(_))) {
+            return run_clean(&cfg, margs.resolved_source().is_some());
+        }
+        if matches!(args.command, Some(Command::Selftest(_))) {
+            return run_selftest(&cfg, margs.resolved_source().is_some());
+        }
+        if let Some(Command::BenchDest(bench)) = &args.command {
+            return run_bench(&cfg, bench);
+        }
+        if status {
+            return run_status(&cfg, margs.resolved_source().is_some());
+        }
+        if matches!(args.command, Some(Command::Undo(_))) {
+            return run_undo(&cfg, margs.resolved_source().is_some());
+        }
 
         // Headless-friendly: reconcile orphan temps and partial dirs before doing any work
-        if let Err(e) = crate::resume::reconcile(&cfg) {
+        if !planning && let Err(e) = crate::resume::reconcile(&cfg) {
             // Non-fatal: log and continue. This cleanup is best-effort.
             tracing::warn!(error = %e, "resume reconcile step failed; proceeding");
         }
-        let maybe_src_owned = args.resolved_source();
+        if let Some(Command::Mirror(mirror)) = &args.command {
+            return run_mirror(&cfg, mirror);
+        }
+        if let Some(Command::Watch(watch)) = &args.command {
+            return run_watch(&cfg, watch);
+        }
+        // Early warning before moves start failing with InsufficientSpace.
+        if !cfg.dry_run {
+            watch_free_space(&cfg);
+        }
+        if margs.list_candidates {
+            print_candidates(&cfg);
+            return Ok(());
+        }
+
+        let gids = margs.batch_gids();
+        if !planning && !gids.is_empty() {
+            if !margs.gids.is_empty() && margs.resolved_source().is_some() {
+                anyhow::bail!("--gid takes the paths from aria2; give no source path");
+            }
+            return run_gid_batch(&cfg, &gids);
+        }
+
+        let maybe_src_owned = margs.resolved_source();
+        let sweep_requested = !planning && (margs.sweep || margs.sweep_move);
+        if sweep_requested && maybe_src_owned.is_none() {
+            // Sweep-only run: nothing to move first.
+            return run_sweep(&cfg, None, margs.sweep_move).map(drop);
+        }
         // If user explicitly provided a path, allow directories directly, else resolve files.
         // For files under download_base that belong to a multi-file directory (immediate child
         // of download_base), promote the selection to that directory so the entire folder moves.
@@ -169,7 +563,8 @@ pub fn run(args: Args) -> Result<()> {
                     // Heuristic: if path is within download_base, move the top-level folder under download_base
                     // instead of a single file (common for multi-file downloads).
                     let abs_p = dunce::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
-                    let base = dunce::canonicalize(&cfg.download_base).unwrap_or_else(|_| cfg.download_base.clone());
+                    let base = dunce::canonicalize(&cfg.download_base)
+                        .unwrap_or_else(|_| cfg.download_base.clone());
                     if abs_p.starts_with(&base) {
                         // Find the immediate child under base
                         let rel = abs_p.strip_prefix(&base).unwrap_or(&abs_p);
@@ -228,7 +623,16 @@ pub fn run(args: Args) -> Result<()> {
                 return Err(e);
             }
         };
-        match move_entry(&cfg, &src) {
+        if planning {
+            out::print_user(&plan_move(&cfg, &src)?.to_string());
+            return Ok(());
+        }
+        let opts = MoveOptions {
+            gid: margs.aria2_gid().map(str::to_owned),
+            infohash: margs.infohash.clone(),
+            ..MoveOptions::default()
+        };
+        match move_entry_on_full(&cfg, &src, &opts) {
             Ok(dest) => {
                 if cfg.dry_run {
                     out::print_info(&format!(
@@ -236,8 +640,24 @@ pub fn run(args: Args) -> Result<()> {
                         src.display(),
                         dest.display()
                     ));
+                    warn_if_cross_filesystem(&src, &cfg.completed_base);
+                }
+                if cfg.copy_only {
+                    info!(source = %src.display(), dest = %dest.display(), "Copied (source retained)");
+                } else {
+                    info!(source = %src.display(), dest = %dest.display(), "Move completed");
+                }
+                if !cfg.dry_run {
+                    notify::send(
+                        &cfg,
+                        NotifyEvent::Success,
+                        "aria_move: moved",
+                        &format!("{} -> {}", src.display(), dest.display()),
+                    );
+                }
+                if sweep_requested {
+                    run_sweep(&cfg, Some(&src), margs.sweep_move)?;
                 }
-                info!(source = %src.display(), dest = %dest.display(), "Move completed");
                 Ok(())
             }
             Err(e) => {
@@ -257,6 +677,13 @@ pub fn run(args: Args) -> Result<()> {
                         } => {
                             error!(code, kind = "insufficient_space", required = *required, available = *available, dest = %dest.display(), "Move failed")
                         }
+                        AriaMoveError::Interrupted if shutdown::timed_out() => {
+                            error!(
+                                code,
+                                kind = "interrupted",
+                                "Move aborted: max_runtime reached"
+                            )
+                        }
                         AriaMoveError::Interrupted => {
                             error!(code, kind = "interrupted", "Move aborted by user")
                         }
@@ -272,6 +699,42 @@ pub fn run(args: Args) -> Result<()> {
                         AriaMoveError::BaseInvalid(base) => {
                             error!(code, kind = "base_invalid", base = %base.display(), "Move failed")
                         }
+                        AriaMoveError::LockContended(path) => {
+                            error!(code, kind = "lock_contended", path = %path.display(), "Move failed")
+                        }
+                        AriaMoveError::LockTimeout(path) => {
+                            error!(code, kind = "lock_timeout", path = %path.display(), "Move failed")
+                        }
+                        AriaMoveError::CopyFailed {
+                            path,
+                            phase,
+                            context,
+                        } => {
+                            error!(code, kind = "copy_failed", path = %path.display(), %phase, %context, "Move failed")
+                        }
+                        AriaMoveError::VerificationFailed {
+                            path,
+                            expected,
+                            actual,
+                        } => {
+                            error!(code, kind = "verification_failed", path = %path.display(), expected = *expected, actual = *actual, "Move failed")
+                        }
+                        AriaMoveError::ChecksumMismatch {
+                            path,
+                            expected,
+                            actual,
+                        } => {
+                            error!(code, kind = "checksum_mismatch", path = %path.display(), %expected, %actual, "Move failed")
+                        }
+                        AriaMoveError::DestinationUnavailable { dest, context } => {
+                            error!(code, kind = "destination_unavailable", dest = %dest.display(), %context, "Move failed")
+                        }
+                        AriaMoveError::Io { kind, message, .. } => {
+                            error!(code, kind = "io", io_kind = %kind, error = %message, hint = am.hint().unwrap_or(""), "Move failed")
+                        }
+                        AriaMoveError::Other(message) => {
+                            error!(code, kind = "other", error = %message, "Move failed")
+                        }
                         _ => {
                             error!(code, kind = "move_error", error = ?am, "Move failed")
                         }
@@ -279,11 +742,21 @@ pub fn run(args: Args) -> Result<()> {
                 } else {
                     error!(error = ?e, "Move failed");
                 }
+                notify_failure(&cfg, &src, &e);
                 Err(e)
             }
         }
     })();
 
+    // Heartbeat for container health checks: only successful, non-dry runs count.
+    if result.is_ok()
+        && !cfg.dry_run
+        && let Some(hb) = cfg.heartbeat_file.as_deref()
+        && let Err(e) = health::touch(hb)
+    {
+        tracing::warn!(error = %e, path = %hb.display(), "failed to update heartbeat file");
+    }
+
     // Ensure logs are flushed before exit
     if let Ok(mut g) = guard_slot.lock() {
         let _ = g.take();
@@ -291,3 +764,614 @@ pub fn run(args: Args) -> Result<()> {
 
     result
 }
+
+/// Time a run may keep going after `max_runtime` asked it to stop.
+const RUNTIME_GRACE: Duration = Duration::from_secs(30);
+
+/// Exit status of a run that exceeded `max_runtime` (as timeout(1)).
+pub const TIMEOUT_EXIT_CODE: i32 = 124;
+
+/// Exit status after a second interrupt forced an immediate exit (128 + SIGINT, as shells report).
+const INTERRUPT_EXIT_CODE: i32 = 130;
+
+/// `max_runtime`: once `limit` has passed, request a shutdown so in-flight work stops at its
+/// next check (locks released, source kept). A run still going `RUNTIME_GRACE` later is
+/// blocked (e.g. in a call to a hung NFS server): the log is flushed and the process exits.
+/// The source is only removed after a complete copy, so it survives either way; temps left
+/// behind are removed by the next run's startup cleanup.
+fn start_runtime_watchdog(
+    limit: Duration,
+    guard_slot: Arc<Mutex<Option<tracing_appender::non_blocking::WorkerGuard>>>,
+) {
+    std::thread::spawn(move || {
+        std::thread::sleep(limit);
+        warn!(limit_s = limit.as_secs(), "max_runtime reached; stopping");
+        out::print_warn(&format!(
+            "max_runtime of {}s reached; stopping...",
+            limit.as_secs()
+        ));
+        shutdown::request_with_reason(shutdown::REASON_TIMEOUT);
+        std::thread::sleep(RUNTIME_GRACE);
+        error!(
+            grace_s = RUNTIME_GRACE.as_secs(),
+            "still running after max_runtime; exiting"
+        );
+        out::print_error("still running after max_runtime; exiting (source kept)");
+        if let Ok(mut g) = guard_slot.lock() {
+            let _ = g.take();
+        }
+        std::process::exit(TIMEOUT_EXIT_CODE);
+    });
+}
+
+/// `config path` / `--print-config`: report which config file is used.
+fn print_config_path() {
+    if let Ok(cfg_env) = std::env::var("ARIA_MOVE_CONFIG") {
+        out::print_info(&format!(
+            "Using ARIA_MOVE_CONFIG (explicit):\n  {}\n",
+            cfg_env
+        ));
+        out::print_info("To override, unset ARIA_MOVE_CONFIG or set it to another file.");
+        return;
+    }
+    match default_config_path() {
+        Ok(p) => {
+            out::print_info(&format!(
+                "Default aria_move config path:\n  {}\n",
+                p.display()
+            ));
+            if p.exists() {
+                out::print_info("A config file already exists at that location.");
+            } else {
+                out::print_info(
+                    "No config file exists there yet. Run `aria_move config init` to create a template.",
+                );
+            }
+        }
+        Err(e) => {
+            out::print_error(&format!("Could not determine a default config path: {e}"));
+        }
+    }
+}
+
+/// `aria_move launchd <ACTION>`: the agent sweeps download_base of the resolved config.
+fn run_launchd(action: LaunchdCommand, interval: Duration) -> Result<()> {
+    if action == LaunchdCommand::Uninstall {
+        if launchd::uninstall()? {
+            out::print_success(&format!(
+                "Removed {}. Stop the running job with: launchctl bootout gui/$(id -u)/{}",
+                launchd::agent_path()?.display(),
+                launchd::LABEL
+            ));
+        } else {
+            out::print_info("No aria_move LaunchAgent is installed.");
+        }
+        return Ok(());
+    }
+
+    let config = launchd::absolute(&default_config_path()?);
+    let Some(loaded) = load_config_from_xml()? else {
+        anyhow::bail!(
+            "no usable config at {}; run `aria_move config init` and set download_base first",
+            config.display()
+        );
+    };
+    let spec = launchd::AgentSpec {
+        program: std::env::current_exe().context("resolve aria_move executable")?,
+        config,
+        watch: launchd::absolute(&loaded.download_base),
+        // launchd's StartInterval counts whole seconds.
+        interval_secs: interval.as_secs(),
+    };
+    match action {
+        LaunchdCommand::Print => out::print_user(launchd::render_plist(&spec).trim_end()),
+        _ => {
+            let path = launchd::install(&spec)?;
+            out::print_success(&format!("LaunchAgent written to {}", path.display()));
+            out::print_info(&format!(
+                "Load it with: launchctl bootstrap gui/$(id -u) {}",
+                path.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// `aria_move install-hook`: point aria2.conf's hook at this binary, or diagnose it.
+fn run_install_hook(conf_path: &std::path::Path, check: bool) -> Result<()> {
+    let exe = std::env::current_exe().context("resolve aria_move executable")?;
+    let exe = dunce::canonicalize(&exe).unwrap_or(exe);
+    if !check {
+        if aria2_conf::install(conf_path, &exe)? {
+            out::print_success(&format!(
+                "{} now runs {} (previous file kept as {}); restart aria2 to apply",
+                conf_path.display(),
+                exe.display(),
+                aria2_conf::backup_path(conf_path).display()
+            ));
+        } else {
+            out::print_info(&format!(
+                "{} already runs {}",
+                conf_path.display(),
+                exe.display()
+            ));
+        }
+    }
+    let conf = std::fs::read_to_string(conf_path)
+        .with_context(|| format!("read '{}'", conf_path.display()))?;
+    let diagnosis = aria2_conf::diagnose(&conf, &exe);
+    for note in &diagnosis.notes {
+        out::print_info(note);
+    }
+    for problem in &diagnosis.problems {
+        out::print_warn(problem);
+    }
+    if !diagnosis.problems.is_empty() {
+        anyhow::bail!(
+            "{}: {} problem(s) with {}",
+            conf_path.display(),
+            diagnosis.problems.len(),
+            aria2_conf::HOOK_KEY
+        );
+    }
+    if check {
+        out::print_success(&format!("{}: hook looks good", conf_path.display()));
+    }
+    Ok(())
+}
+
+/// `aria_move healthcheck`: non-zero exit (via Err) when the last successful run is too old.
+fn run_healthcheck(file: Option<&std::path::Path>, max_age: Duration) -> Result<()> {
+    let path = match file {
+        Some(p) => p.to_path_buf(),
+        None => load_config_from_xml()?
+            .and_then(|loaded| loaded.heartbeat_file)
+            .context("no heartbeat file: pass --file or set heartbeat_file in the config")?,
+    };
+    let age = health::check(&path, max_age)?;
+    out::print_user(&format!(
+        "healthy: last successful run {}s ago",
+        age.as_secs()
+    ));
+    Ok(())
+}
+
+/// `aria_move audit verify`: JSON report on stdout, non-zero exit (via Err) if the chain is broken.
+fn run_audit(action: &AuditCommand) -> Result<()> {
+    let AuditCommand::Verify { file } = action;
+    let path = match file {
+        Some(p) => p.clone(),
+        None => load_config_from_xml()?
+            .and_then(|loaded| loaded.audit_log)
+            .context("no audit log: pass --file or set audit_log in the config")?,
+    };
+    let report = aria_move::fs_ops::verify_audit_log(&path)?;
+    out::print_user(&serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+/// `aria_move history`: the last `limit` audit records, one JSON object per line.
+fn run_history(file: Option<&std::path::Path>, limit: usize) -> Result<()> {
+    let path = match file {
+        Some(p) => p.to_path_buf(),
+        None => load_config_from_xml()?
+            .and_then(|loaded| loaded.audit_log)
+            .context("no audit log: pass --file or set audit_log in the config")?,
+    };
+    for entry in aria_move::fs_ops::audit_history(&path, limit)? {
+        out::print_user(&serde_json::to_string(&entry)?);
+    }
+    Ok(())
+}
+
+/// `aria_move verify`: JSON report on stdout, non-zero exit (via Err) if a file no longer
+/// matches its sha256 tag.
+fn run_verify(path: Option<&std::path::Path>) -> Result<()> {
+    let path = match path {
+        Some(p) => p.to_path_buf(),
+        None => load_config_from_xml()?
+            .map(|loaded| loaded.completed_base)
+            .context("nothing to verify: pass PATH or set completed_base in the config")?,
+    };
+    let report = aria_move::fs_ops::verify_tags(&path)?;
+    out::print_user(&serde_json::to_string_pretty(&report)?);
+    if !report.mismatched.is_empty() {
+        anyhow::bail!(
+            "{} file(s) no longer match their sha256 tag",
+            report.mismatched.len()
+        );
+    }
+    Ok(())
+}
+
+/// Tell the user a template was written and what to edit.
+fn print_template_created(path: &std::path::Path) {
+    out::print_success(&format!(
+        "A template aria_move config was written to: {}",
+        path.display()
+    ));
+    out::print_info(
+        "Edit the file to set `download_base`, `completed_base` and optionally `log_level` and `log_file`. Example:\n\n<config>\n  <download_base>/path/to/incoming</download_base>\n  <completed_base>/path/to/completed</completed_base>\n  <log_level>normal</log_level>\n  <log_file>/path/to/aria_move.log</log_file>\n</config>\n",
+    );
+    out::print_info(
+        "Then re-run this command. To use a different location pass --config or set ARIA_MOVE_CONFIG.",
+    );
+}
+
+/// `aria_move config <ACTION>`.
+fn run_config(action: ConfigCommand) -> Result<()> {
+    match action {
+        ConfigCommand::Path => print_config_path(),
+        ConfigCommand::Init => match init_config()? {
+            LoadResult::CreatedTemplate(path) => print_template_created(&path),
+            LoadResult::Loaded(_, path) => out::print_info(&format!(
+                "A config file already exists at {}; leaving it unchanged.",
+                path.display()
+            )),
+        },
+    }
+    Ok(())
+}
+
+/// Report (and optionally move) items left behind in download_base.
+fn run_sweep(
+    cfg: &Config,
+    exclude: Option<&std::path::Path>,
+    move_items: bool,
+) -> Result<SweepReport> {
+    let report = sweep_download_base(cfg, exclude, move_items)?;
+    if report.found.is_empty() {
+        out::print_info("Sweep: nothing left behind in download_base");
+        return Ok(report);
+    }
+    if !move_items {
+        for path in &report.found {
+            out::print_info(&format!("Sweep: left behind '{}'", path.display()));
+        }
+        out::print_info("Re-run with --sweep-move to move these items.");
+        return Ok(report);
+    }
+    for (src, dest) in &report.moved {
+        out::print_info(&format!(
+            "Sweep: moved '{}' -> '{}'",
+            src.display(),
+            dest.display()
+        ));
+    }
+    for (src, err) in &report.failed {
+        out::print_warn(&format!(
+            "Sweep: could not move '{}': {}",
+            src.display(),
+            err
+        ));
+    }
+    // One notification per sweep rather than one per leftover.
+    if !cfg.dry_run && !report.moved.is_empty() {
+        let lines: Vec<String> = report
+            .moved
+            .iter()
+            .map(|(src, dest)| format!("{} -> {}", src.display(), dest.display()))
+            .collect();
+        notify::send(
+            cfg,
+            NotifyEvent::Success,
+            &format!("aria_move: sweep moved {} item(s)", lines.len()),
+            &lines.join("\n"),
+        );
+    }
+    if !cfg.dry_run && !report.failed.is_empty() {
+        let lines: Vec<String> = report
+            .failed
+            .iter()
+            .map(|(src, err)| format!("{}: {err}", src.display()))
+            .collect();
+        notify::send(
+            cfg,
+            NotifyEvent::Failure,
+            &format!("aria_move: sweep failed for {} item(s)", lines.len()),
+            &lines.join("\n"),
+        );
+    }
+    Ok(report)
+}
+
+/// Move the downloads of several aria2 GIDs one after another, asking aria2 (JSON-RPC) for
+/// each path. Reported per GID like a sweep; fails if any GID could not be moved.
+fn run_gid_batch(cfg: &Config, gids: &[&str]) -> Result<()> {
+    let mut moved = Vec::new();
+    let mut failed = Vec::new();
+    for &gid in gids {
+        if shutdown::is_requested() {
+            break;
+        }
+        let result = aria_move::aria2_rpc::download_path(cfg, gid)
+            .with_context(|| format!("ask aria2 where {gid} is"))
+            .and_then(|src| {
+                let opts = MoveOptions {
+                    gid: Some(gid.to_owned()),
+                    ..MoveOptions::default()
+                };
+                move_entry_on_full(cfg, &src, &opts).map(|dest| (src, dest))
+            });
+        match result {
+            Ok((src, dest)) => {
+                let verb = if cfg.dry_run { "would move" } else { "moved" };
+                info!(gid, source = %src.display(), dest = %dest.display(), "GID moved");
+                out::print_info(&format!(
+                    "GID {gid}: {verb} '{}' -> '{}'",
+                    src.display(),
+                    dest.display()
+                ));
+                moved.push(format!("{gid}: {} -> {}", src.display(), dest.display()));
+            }
+            Err(e) => {
+                error!(gid, error = %format!("{e:#}"), "GID move failed");
+                out::print_warn(&format!("GID {gid}: could not move: {e:#}"));
+                failed.push(format!("{gid}: {e:#}"));
+            }
+        }
+    }
+    // One notification per batch rather than one per GID.
+    if !cfg.dry_run && !moved.is_empty() {
+        notify::send(
+            cfg,
+            NotifyEvent::Success,
+            &format!("aria_move: moved {} GID(s)", moved.len()),
+            &moved.join("\n"),
+        );
+    }
+    if !cfg.dry_run && !failed.is_empty() {
+        notify::send(
+            cfg,
+            NotifyEvent::Failure,
+            &format!("aria_move: {} GID(s) failed", failed.len()),
+            &failed.join("\n"),
+        );
+    }
+    if !failed.is_empty() {
+        anyhow::bail!(
+            "{} of {} GID(s) could not be moved",
+            failed.len(),
+            gids.len()
+        );
+    }
+    if moved.len() < gids.len() {
+        return Err(AriaMoveError::Interrupted.into());
+    }
+    Ok(())
+}
+
+/// Push a failed move to the notifiers: `low-space` when completed_base is full, else
+/// `failure`. Interrupted and ignored moves are not reported.
+fn notify_failure(cfg: &Config, src: &std::path::Path, e: &anyhow::Error) {
+    if cfg.dry_run {
+        return;
+    }
+    let (event, title) = match e.downcast_ref::<AriaMoveError>() {
+        Some(AriaMoveError::Interrupted | AriaMoveError::Ignored(_)) => return,
+        Some(AriaMoveError::InsufficientSpace { .. }) => {
+            (NotifyEvent::LowSpace, "aria_move: completed_base is full")
+        }
+        _ => (NotifyEvent::Failure, "aria_move: move failed"),
+    };
+    notify::send(cfg, event, title, &format!("{}: {e:#}", src.display()));
+}
+
+/// `aria_move clean`: crash cleanup of both bases, reported as JSON on stdout.
+fn run_clean(cfg: &Config, source_given: bool) -> Result<()> {
+    if source_given {
+        anyhow::bail!("clean takes no source path; it always covers both bases");
+    }
+    let report = aria_move::clean_bases(cfg, cfg.dry_run)?;
+    out::print_user(&serde_json::to_string_pretty(&report)?);
+    if !report.failed.is_empty() {
+        anyhow::bail!("{} cleanup action(s) failed", report.failed.len());
+    }
+    Ok(())
+}
+
+/// `mirror`: one pass, or one pass every `--interval` until interrupted.
+/// With an interval, failed items are logged and retried next pass, every clean pass
+/// refreshes the heartbeat, and every pass checks `low_space_warn_mib`.
+fn run_mirror(cfg: &Config, args: &MirrorArgs) -> Result<()> {
+    if args.common.resolved_source().is_some() {
+        anyhow::bail!("mirror takes no source path; it always covers download_base");
+    }
+    loop {
+        if !cfg.dry_run {
+            watch_free_space(cfg);
+        }
+        let report = aria_move::mirror_download_base(cfg, args.prune)?;
+        out::print_user(&serde_json::to_string_pretty(&report)?);
+        if args.interval.get().is_zero() {
+            if !report.failed.is_empty() {
+                anyhow::bail!("{} item(s) could not be mirrored", report.failed.len());
+            }
+            return Ok(());
+        }
+        if report.failed.is_empty()
+            && !cfg.dry_run
+            && let Some(hb) = cfg.heartbeat_file.as_deref()
+            && let Err(e) = health::touch(hb)
+        {
+            tracing::warn!(error = %e, path = %hb.display(), "failed to update heartbeat file");
+        }
+        if !sleep_unless_interrupted(args.interval.get()) {
+            return Ok(());
+        }
+    }
+}
+
+/// `watch`: a `--sweep-move` pass, then one every `--interval` until interrupted.
+/// Failed items are retried next pass, every clean pass refreshes the heartbeat, and every
+/// pass checks `low_space_warn_mib`.
+fn run_watch(cfg: &Config, args: &WatchArgs) -> Result<()> {
+    if args.common.resolved_source().is_some() {
+        anyhow::bail!("watch takes no source path; it always covers download_base");
+    }
+    loop {
+        if !cfg.dry_run {
+            watch_free_space(cfg);
+        }
+        let report = run_sweep(cfg, None, true)?;
+        if args.interval.get().is_zero() {
+            if !report.failed.is_empty() {
+                anyhow::bail!("{} item(s) could not be moved", report.failed.len());
+            }
+            return Ok(());
+        }
+        if report.failed.is_empty()
+            && !cfg.dry_run
+            && let Some(hb) = cfg.heartbeat_file.as_deref()
+            && let Err(e) = health::touch(hb)
+        {
+            tracing::warn!(error = %e, path = %hb.display(), "failed to update heartbeat file");
+        }
+        if !sleep_unless_interrupted(args.interval.get()) {
+            return Ok(());
+        }
+    }
+}
+
+/// Wait `pause` between passes of `mirror` / `watch`; false once a shutdown was requested.
+fn sleep_unless_interrupted(pause: Duration) -> bool {
+    let next = std::time::Instant::now() + pause;
+    while std::time::Instant::now() < next {
+        if shutdown::is_requested() {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(250));
+    }
+    true
+}
+
+/// `aria_move status`: read-only JSON overview of the bases, the candidates in
+/// download_base, the heartbeat and the last audit record.
+fn run_status(cfg: &Config, has_source: bool) -> Result<()> {
+    if has_source {
+        anyhow::bail!("status takes no source path");
+    }
+    let candidates: Vec<_> = list_candidates(cfg)
+        .iter()
+        .map(|c| {
+            serde_json::json!({
+                "path": c.path,
+                "dir": c.is_dir,
+                "size": c.size,
+                "stability": c.stability.to_string(),
+                "eligible": c.eligible,
+            })
+        })
+        .collect();
+    let heartbeat = cfg.heartbeat_file.as_deref().map(|hb| {
+        serde_json::json!({
+            "path": hb,
+            "age_secs": health::age(hb).ok().map(|age| age.as_secs()),
+        })
+    });
+    let last_move = cfg
+        .audit_log
+        .as_deref()
+        .and_then(|log| aria_move::fs_ops::audit_history(log, 1).ok())
+        .and_then(|mut entries| entries.pop());
+    let report = serde_json::json!({
+        "download_base": cfg.download_base,
+        "completed_base": cfg.completed_base,
+        "completed_free_bytes": aria_move::fsys::free_space_bytes(&cfg.completed_base).ok(),
+        "candidates": candidates,
+        "heartbeat": heartbeat,
+        "last_move": last_move,
+    });
+    out::print_user(&serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+/// `aria_move undo`: reverse the newest move in the audit log, print the JSON report.
+fn run_undo(cfg: &Config, has_source: bool) -> Result<()> {
+    if has_source {
+        anyhow::bail!("undo takes no source path; it reverses the last move in the audit log");
+    }
+    let report = aria_move::fs_ops::undo_last_move(cfg)?;
+    out::print_user(&serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
+
+/// `aria_move selftest`: move and verify sample items, print the JSON report.
+fn run_selftest(cfg: &Config, has_source: bool) -> Result<()> {
+    if has_source {
+        anyhow::bail!("selftest takes no source path");
+    }
+    let report = selftest::run(cfg)?;
+    out::print_user(&serde_json::to_string_pretty(&report)?);
+    out::print_success("Selftest passed: moves into completed_base work");
+    Ok(())
+}
+
+fn run_bench(cfg: &Config, args: &BenchArgs) -> Result<()> {
+    if args.common.resolved_source().is_some() {
+        anyhow::bail!("bench-dest takes no source path");
+    }
+    let report = aria_move::fs_ops::bench_dest(cfg, args.size_mib.max(1) * 1024 * 1024)?;
+    out::print_user(&serde_json::to_string_pretty(&report)?);
+    if cfg.dry_run {
+        return Ok(());
+    }
+    let path = aria_move::fs_ops::save_bench(&report)?;
+    out::print_info(&format!(
+        "Saved to {}; copies between these bases now start with {}",
+        path.display(),
+        report.best
+    ));
+    Ok(())
+}
+
+/// Print what the resolver sees in download_base; the would-be pick is marked with '*'.
+fn print_candidates(cfg: &Config) {
+    let candidates = list_candidates(cfg);
+    if candidates.is_empty() {
+        out::print_info(&format!(
+            "No candidates under {}",
+            cfg.download_base.display()
+        ));
+        return;
+    }
+    let pick = candidates
+        .iter()
+        .position(|c| c.eligible && c.stability == aria_move::fs_ops::Stability::Stable);
+    let now = std::time::SystemTime::now();
+    for (i, c) in candidates.iter().enumerate() {
+        let age = now
+            .duration_since(c.mtime)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let (mark, note) = if Some(i) == pick {
+            ("*", "would be picked")
+        } else {
+            (" ", c.reason())
+        };
+        out::print_info(&format!(
+            "{} {:<4} {:<8} {:>12} B {:>8}s ago  {}  ({})",
+            mark,
+            if c.is_dir { "dir" } else { "file" },
+            c.stability,
+            c.size,
+            age,
+            c.path.display(),
+            note
+        ));
+    }
+}
+
+/// Dry-run hint: a move across filesystems is a full copy, not an instant rename.
+fn warn_if_cross_filesystem(src: &std::path::Path, completed_base: &std::path::Path) {
+    if aria_move::fs_ops::is_remote(completed_base) {
+        return;
+    }
+    if let Ok(false) = aria_move::fs_ops::same_filesystem(src, completed_base) {
+        out::print_warn(&format!(
+            "'{}' is on a different filesystem than '{}'; the move will copy the data",
+            src.display(),
+            completed_base.display()
+        ));
+    }
+}
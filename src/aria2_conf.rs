@@ -0,0 +1,226 @@
+//! aria2.conf inspection and hook installation (`aria_move install-hook`).
+//! - `--check` diagnoses the `on-download-complete=` entry: missing, repeated, quoted,
+//!   relative, not existing or not executable.
+//! - Without `--check`, the entry is pointed at the running binary (added if missing) and
+//!   the file is rewritten in place; the previous version is kept as `<conf>.bak`.
+//!
+//! Notes:
+//! - aria2 runs the value as a program path without a shell, so it must not be quoted:
+//!   quotes (and a trailing space) become part of the path. The entry is written unquoted,
+//!   spaces included.
+//! - aria2 keeps the last of repeated options; a rewrite changes that one and reports the rest.
+//! - A hook pointing somewhere else (e.g. a wrapper script) is only reported, not a problem.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// aria2 option that runs a command when a download completes.
+pub const HOOK_KEY: &str = "on-download-complete";
+
+/// One active (uncommented) `on-download-complete=` line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookEntry {
+    /// Zero-based line index in the file.
+    pub line: usize,
+    /// Value after `=`, untrimmed.
+    pub value: String,
+}
+
+/// Outcome of `diagnose`: `problems` make `--check` fail, `notes` are informational.
+#[derive(Debug, Default)]
+pub struct Diagnosis {
+    pub problems: Vec<String>,
+    pub notes: Vec<String>,
+}
+
+/// Every active `on-download-complete=` entry of `conf`, in file order.
+pub fn find_entries(conf: &str) -> Vec<HookEntry> {
+    conf.lines()
+        .enumerate()
+        .filter_map(|(line, text)| {
+            let (key, value) = text.trim_start().split_once('=')?;
+            (key.trim() == HOOK_KEY).then(|| HookEntry {
+                line,
+                value: value.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Check the hook of `conf` (the effective, i.e. last, entry) against the binary `exe`.
+pub fn diagnose(conf: &str, exe: &Path) -> Diagnosis {
+    let mut d = Diagnosis::default();
+    let entries = find_entries(conf);
+    let Some(entry) = entries.last() else {
+        d.problems.push(format!(
+            "no {HOOK_KEY}= entry; aria2 will not run aria_move"
+        ));
+        return d;
+    };
+    if entries.len() > 1 {
+        d.notes.push(format!(
+            "{HOOK_KEY} is set {} times; aria2 uses the last one (line {})",
+            entries.len(),
+            entry.line + 1
+        ));
+    }
+    let value = entry.value.trim_start();
+    if value.trim().is_empty() {
+        d.problems.push(format!("{HOOK_KEY} is empty"));
+        return d;
+    }
+    if value != value.trim_end() {
+        d.problems.push(format!(
+            "{HOOK_KEY} has trailing whitespace, which aria2 keeps as part of the path"
+        ));
+    }
+    let value = value.trim_end();
+    if is_quoted(value) {
+        d.problems.push(format!(
+            "{HOOK_KEY} is quoted; aria2 does not use a shell, so the quotes become part of the path"
+        ));
+    }
+    let target = Path::new(value.trim_matches(|c| c == '"' || c == '\''));
+    if !target.is_absolute() {
+        d.problems.push(format!(
+            "{HOOK_KEY} is a relative path ('{}'); aria2 resolves it against its own working directory",
+            target.display()
+        ));
+    }
+    match fs::metadata(target) {
+        Err(_) => d
+            .problems
+            .push(format!("'{}' does not exist", target.display())),
+        Ok(meta) if meta.is_dir() => d
+            .problems
+            .push(format!("'{}' is a directory", target.display())),
+        Ok(meta) => {
+            if !is_executable(&meta) {
+                d.problems.push(format!(
+                    "'{}' is not executable (chmod +x)",
+                    target.display()
+                ));
+            }
+            if !same_file(target, exe) {
+                d.notes.push(format!(
+                    "'{}' is not this aria_move ('{}'); fine if it is a wrapper script",
+                    target.display(),
+                    exe.display()
+                ));
+            }
+        }
+    }
+    d
+}
+
+/// `conf` with its effective hook entry pointing at `exe` (appended when there is none).
+/// Returns None when it already does.
+pub fn set_hook(conf: &str, exe: &Path) -> Option<String> {
+    let wanted = format!("{HOOK_KEY}={}", exe.display());
+    let mut lines: Vec<String> = conf.lines().map(str::to_string).collect();
+    match find_entries(conf).last() {
+        Some(entry) if entry.value == exe.display().to_string() => return None,
+        Some(entry) => lines[entry.line] = wanted,
+        None => lines.push(wanted),
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    Some(out)
+}
+
+/// Point the hook of the aria2.conf at `path` to `exe`. Returns whether the file changed.
+pub fn install(path: &Path, exe: &Path) -> Result<bool> {
+    let conf = fs::read_to_string(path).with_context(|| format!("read '{}'", path.display()))?;
+    let Some(updated) = set_hook(&conf, exe) else {
+        return Ok(false);
+    };
+    let backup = backup_path(path);
+    fs::copy(path, &backup).with_context(|| format!("back up to '{}'", backup.display()))?;
+    // Write next to the original (copied first to keep its mode) and rename over it, so
+    // aria2 never reads half a file.
+    let tmp = with_suffix(path, ".aria_move.tmp");
+    fs::copy(path, &tmp).with_context(|| format!("create '{}'", tmp.display()))?;
+    fs::write(&tmp, updated).with_context(|| format!("write '{}'", tmp.display()))?;
+    fs::rename(&tmp, path).with_context(|| format!("replace '{}'", path.display()))?;
+    Ok(true)
+}
+
+/// `<conf>.bak`.
+pub fn backup_path(path: &Path) -> PathBuf {
+    with_suffix(path, ".bak")
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut s = path.as_os_str().to_owned();
+    s.push(suffix);
+    PathBuf::from(s)
+}
+
+fn is_quoted(value: &str) -> bool {
+    value.len() >= 2
+        && ((value.starts_with('"') && value.ends_with('"'))
+            || (value.starts_with('\'') && value.ends_with('\'')))
+}
+
+#[cfg(unix)]
+fn is_executable(meta: &fs::Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    meta.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_meta: &fs::Metadata) -> bool {
+    true
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (dunce::canonicalize(a), dunce::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_active_entries_only() {
+        let conf = "dir=/data\n# on-download-complete=/old\non-download-complete = /a\n  on-download-complete=/b\n";
+        let entries = find_entries(conf);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].line, 2);
+        assert_eq!(entries[1].value, "/b");
+    }
+
+    #[test]
+    fn diagnoses_missing_quoted_and_relative_hooks() {
+        let exe = Path::new("/usr/local/bin/aria_move");
+        assert_eq!(diagnose("dir=/data\n", exe).problems.len(), 1);
+
+        let quoted = diagnose("on-download-complete=\"/opt/my tools/aria_move\"\n", exe);
+        assert!(quoted.problems.iter().any(|p| p.contains("quoted")));
+
+        let relative = diagnose("on-download-complete=bin/aria_move\n", exe);
+        assert!(relative.problems.iter().any(|p| p.contains("relative")));
+    }
+
+    #[test]
+    fn set_hook_replaces_the_last_entry_or_appends() {
+        let exe = Path::new("/opt/my tools/aria_move");
+        let conf = "on-download-complete=/a\ndir=/data\non-download-complete=/b\n";
+        let updated = set_hook(conf, exe).unwrap();
+        assert_eq!(
+            updated,
+            "on-download-complete=/a\ndir=/data\non-download-complete=/opt/my tools/aria_move\n"
+        );
+        assert!(set_hook(&updated, exe).is_none());
+
+        let appended = set_hook("dir=/data", exe).unwrap();
+        assert_eq!(
+            appended,
+            "dir=/data\non-download-complete=/opt/my tools/aria_move\n"
+        );
+    }
+}
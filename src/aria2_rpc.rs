@@ -0,0 +1,305 @@
+//! aria2 JSON-RPC client (`aria2_rpc_url`, `aria2_rpc_secret`).
+//! - `tell_status` asks aria2 (`aria2.tellStatus`) for a download's state by GID: its status
+//!   and, for BitTorrent, whether it is seeding.
+//! - `bt_mode` uses it on hook runs: aria2 calls `on-bt-download-complete` while it keeps
+//!   seeding, with the data complete but still open, so such a download is only copied.
+//! - `download_path` asks for a GID's files, for `--gid` batch runs that only know GIDs.
+//! - `stop` pauses or removes a task (`rpc_action`) and waits until aria2 reports it
+//!   stopped; `resume_from` points a paused task at the moved data and unpauses it.
+//!
+//! Notes:
+//! - Requests run `curl` on PATH, or `ARIA_MOVE_CURL` if set, with the request on stdin (see
+//!   notify.rs), so the secret never appears in the process list. Each call is limited to
+//!   `RPC_TIMEOUT_SECS`.
+//! - The secret is sent as the `token:<secret>` first parameter, as aria2 expects.
+//! - An error reply from aria2 is an `io::Error` wrapping `RpcError`, so callers can tell
+//!   it apart from a failed request (curl, the network, a malformed reply).
+
+use serde_json::{Value, json};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config::types::{Config, RpcAction};
+use crate::notify::{quote, run_curl};
+
+/// Upper bound for one RPC call.
+pub const RPC_TIMEOUT_SECS: u64 = 10;
+
+/// Longest wait for aria2 to report a paused or removed task as stopped.
+pub const STOP_WAIT: Duration = Duration::from_secs(10);
+
+/// Interval between status checks while waiting.
+const STOP_POLL: Duration = Duration::from_millis(100);
+
+/// What aria2 reports about one download.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskStatus {
+    /// active, waiting, paused, error, complete or removed
+    pub status: String,
+    /// True for a BitTorrent download that has finished and is seeding
+    pub seeder: bool,
+}
+
+impl TaskStatus {
+    /// aria2 still serves the files of this download (and holds them open).
+    pub fn is_seeding(&self) -> bool {
+        self.status == "active" && self.seeder
+    }
+}
+
+/// An error reply from aria2 (the JSON-RPC `error` member).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcError {
+    pub method: String,
+    pub message: String,
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.method, self.message)
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+impl RpcError {
+    /// The reply inside `e`, if aria2 answered with an error.
+    pub fn of(e: &io::Error) -> Option<&RpcError> {
+        e.get_ref()?.downcast_ref()
+    }
+
+    /// aria2 does not know the GID ("GID 2089b05ecca3d829 is not found").
+    pub fn is_gid_not_found(&self) -> bool {
+        self.message.starts_with("GID ") && self.message.ends_with(" is not found")
+    }
+}
+
+/// `aria2.tellStatus` for `gid`.
+pub fn tell_status(config: &Config, gid: &str) -> io::Result<TaskStatus> {
+    let result = call(
+        config,
+        "aria2.tellStatus",
+        vec![json!(gid), json!(["status", "seeder"])],
+    )?;
+    let field = |name: &str| result.get(name).and_then(Value::as_str).unwrap_or_default();
+    Ok(TaskStatus {
+        status: field("status").to_string(),
+        seeder: field("seeder") == "true",
+    })
+}
+
+/// Where the download `gid` is on disk: the entry directly below its `dir` that holds its
+/// first file (a multi-file torrent's folder, or the file itself).
+pub fn download_path(config: &Config, gid: &str) -> io::Result<PathBuf> {
+    let result = call(
+        config,
+        "aria2.tellStatus",
+        vec![json!(gid), json!(["dir", "files"])],
+    )?;
+    let first = result
+        .get("files")
+        .and_then(Value::as_array)
+        .and_then(|files| files.first())
+        .and_then(|f| f.get("path"))
+        .and_then(Value::as_str)
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("aria2.tellStatus: {gid} has no files on disk"),
+            )
+        })?;
+    let dir = result.get("dir").and_then(Value::as_str).map(Path::new);
+    Ok(top_level(dir, Path::new(first)))
+}
+
+fn top_level(dir: Option<&Path>, first: &Path) -> PathBuf {
+    let top = dir.and_then(|d| Some((d, first.strip_prefix(d).ok()?.components().next()?)));
+    match top {
+        Some((dir, top)) => dir.join(top),
+        None => first.to_path_buf(),
+    }
+}
+
+/// Pause (`RpcAction::Pause`) or remove (`RpcAction::Remove`) `gid` and wait, up to
+/// `STOP_WAIT`, until aria2 no longer has it active. `RpcAction::None` does nothing.
+/// A removed task that aria2 no longer knows counts as stopped; any other error is returned.
+pub fn stop(config: &Config, gid: &str, action: RpcAction) -> io::Result<()> {
+    let (method, stopped): (&str, &[&str]) = match action {
+        RpcAction::None => return Ok(()),
+        RpcAction::Pause => ("aria2.pause", &["paused"]),
+        RpcAction::Remove => ("aria2.remove", &["removed", "complete", "error"]),
+    };
+    call(config, method, vec![json!(gid)])?;
+    let started = Instant::now();
+    loop {
+        match tell_status(config, gid) {
+            Ok(status) if stopped.contains(&status.status.as_str()) => return Ok(()),
+            // A removed task whose result aria2 has already dropped.
+            Err(e)
+                if action == RpcAction::Remove
+                    && RpcError::of(&e).is_some_and(RpcError::is_gid_not_found) =>
+            {
+                return Ok(());
+            }
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+        if started.elapsed() >= STOP_WAIT {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("{method}: {gid} still not stopped after {STOP_WAIT:?}"),
+            ));
+        }
+        thread::sleep(STOP_POLL);
+    }
+}
+
+/// Unpause the paused `gid`, after pointing its download directory at `dir` when given.
+pub fn resume_from(config: &Config, gid: &str, dir: Option<&Path>) -> io::Result<()> {
+    if let Some(dir) = dir {
+        call(
+            config,
+            "aria2.changeOption",
+            vec![json!(gid), json!({ "dir": dir.to_string_lossy() })],
+        )?;
+    }
+    call(config, "aria2.unpause", vec![json!(gid)]).map(drop)
+}
+
+/// Call `method` with `params` (the secret token is prepended) and return its `result`.
+pub fn call(config: &Config, method: &str, params: Vec<Value>) -> io::Result<Value> {
+    let body = request_body(config, method, params);
+    let out = run_curl(&curl_config(&config.aria2_rpc_url, &body))?;
+    let reply: Value = serde_json::from_slice(&out).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{method}: not a JSON-RPC reply: {e}"),
+        )
+    })?;
+    if let Some(err) = reply.get("error") {
+        let message = err
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown error");
+        return Err(io::Error::other(RpcError {
+            method: method.to_string(),
+            message: message.to_string(),
+        }));
+    }
+    reply
+        .get("result")
+        .cloned()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("{method}: no result")))
+}
+
+fn request_body(config: &Config, method: &str, params: Vec<Value>) -> String {
+    let mut all = Vec::with_capacity(params.len() + 1);
+    if let Some(secret) = &config.aria2_rpc_secret {
+        all.push(json!(format!("token:{secret}")));
+    }
+    all.extend(params);
+    json!({
+        "jsonrpc": "2.0",
+        "id": "aria_move",
+        "method": method,
+        "params": all,
+    })
+    .to_string()
+}
+
+/// curl config (`-K` syntax) that posts `body` to `url`.
+fn curl_config(url: &str, body: &str) -> String {
+    [
+        "silent".to_string(),
+        "show-error".to_string(),
+        format!("max-time = {RPC_TIMEOUT_SECS}"),
+        format!("url = {}", quote(url)),
+        format!("header = {}", quote("Content-Type: application/json")),
+        format!("data-raw = {}", quote(body)),
+        String::new(),
+    ]
+    .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_carries_the_token_first() {
+        let mut cfg = Config::default();
+        let body = request_body(&cfg, "aria2.tellStatus", vec![json!("2089b05ecca3d829")]);
+        let v: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(v["method"], "aria2.tellStatus");
+        assert_eq!(v["params"], json!(["2089b05ecca3d829"]));
+
+        cfg.aria2_rpc_secret = Some("s3cret".into());
+        let body = request_body(&cfg, "aria2.tellStatus", vec![json!("2089b05ecca3d829")]);
+        let v: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(v["params"], json!(["token:s3cret", "2089b05ecca3d829"]));
+
+        let curl = curl_config("http://localhost:6800/jsonrpc", &body);
+        assert!(
+            curl.contains("url = \"http://localhost:6800/jsonrpc\""),
+            "{curl}"
+        );
+        assert!(curl.contains(r#"\"token:s3cret\""#), "{curl}");
+    }
+
+    #[test]
+    fn download_path_is_the_entry_below_dir() {
+        let dir = Path::new("/dl");
+        assert_eq!(
+            top_level(Some(dir), Path::new("/dl/Show S01/e01.mkv")),
+            Path::new("/dl/Show S01")
+        );
+        assert_eq!(
+            top_level(Some(dir), Path::new("/dl/movie.mkv")),
+            Path::new("/dl/movie.mkv")
+        );
+        // A file outside `dir` (or no `dir`) is taken as is.
+        assert_eq!(
+            top_level(Some(dir), Path::new("/other/a.iso")),
+            Path::new("/other/a.iso")
+        );
+        assert_eq!(
+            top_level(None, Path::new("/dl/a.iso")),
+            Path::new("/dl/a.iso")
+        );
+    }
+
+    #[test]
+    fn only_an_unknown_gid_reply_is_gid_not_found() {
+        let reply = |message: &str| {
+            io::Error::other(RpcError {
+                method: "aria2.tellStatus".into(),
+                message: message.into(),
+            })
+        };
+        let e = reply("GID 2089b05ecca3d829 is not found");
+        assert!(RpcError::of(&e).is_some_and(RpcError::is_gid_not_found));
+        assert_eq!(
+            e.to_string(),
+            "aria2.tellStatus: GID 2089b05ecca3d829 is not found"
+        );
+        let e = reply("Unauthorized");
+        assert!(!RpcError::of(&e).is_some_and(RpcError::is_gid_not_found));
+        let e = io::Error::new(io::ErrorKind::NotFound, "curl not found");
+        assert!(RpcError::of(&e).is_none());
+    }
+
+    #[test]
+    fn seeding_needs_an_active_seeder() {
+        let status = |status: &str, seeder| TaskStatus {
+            status: status.into(),
+            seeder,
+        };
+        assert!(status("active", true).is_seeding());
+        assert!(!status("active", false).is_seeding());
+        assert!(!status("complete", true).is_seeding());
+    }
+}
@@ -4,11 +4,26 @@
 //! Notes:
 //! - --source-path takes precedence over the positional SOURCE_PATH (back-compat).
 //! - --debug is a shorthand for --log-level debug.
+//! - Subcommands: `move` (the default), `plan`, `clean`, `config`, `launchd`,
+//!   `healthcheck`, `install-hook`, `selftest`, `completions`.
+//!   Without a subcommand the top-level arguments are a `move`, so the aria2 hook form
+//!   `aria_move GID NUM PATH` keeps working.
+//! - Global options (logging, console, `--config`) go before or after the subcommand.
+//! - Move arguments cannot be combined with a subcommand; a file literally named like a
+//!   subcommand must be passed with `--source-path`.
 
-use clap::{Parser, ValueHint};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueHint};
+use clap_complete::Shell;
+use std::io::Write;
 use std::path::PathBuf;
 
-use crate::config::types::{Config, LogLevel};
+use crate::config::types::{
+    ByteSize, Config, ConsoleStream, CopyBackend, CopyOrder, DestSymlinkPolicy, FsyncPolicy,
+    HumanDuration, LockStrategy, LogLevel, LogTimeFormat, ResumeVerify, RpcAction, SymlinkPolicy,
+    parse_dir_mode,
+};
+use crate::fs_ops::{OnDuplicate, OnFull, OnRedownload};
 
 /// CLI wrapper for aria_move library.
 /// CLI flags override config values (which are loaded from XML if present).
@@ -19,6 +34,144 @@ use crate::config::types::{Config, LogLevel};
     about = "Move completed aria2 downloads safely (Rust)"
 )]
 pub struct Args {
+    /// Optional subcommand; without one, the top-level arguments are an implicit `move`.
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Options shared by every subcommand (logging, console, config file).
+    #[command(flatten)]
+    pub global: GlobalArgs,
+
+    /// Move arguments given without a subcommand (aria2 hook form).
+    #[command(flatten)]
+    pub hook: MoveArgs,
+}
+
+/// Options accepted before or after any subcommand.
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct GlobalArgs {
+    /// Enable debug logging (equivalent to `--log-level debug`).
+    #[arg(
+        short = 'd',
+        long,
+        global = true,
+        help = "Enable debug logging (shorthand for --log-level debug)"
+    )]
+    pub debug: bool,
+
+    /// Quiet mode: only errors reach the console (log level `quiet`).
+    #[arg(
+        short = 'q',
+        long,
+        global = true,
+        conflicts_with = "debug",
+        help = "Suppress all non-error console output (shorthand for --log-level quiet)"
+    )]
+    pub quiet: bool,
+
+    /// Set log level. One of: quiet, warn, normal, info, debug.
+    #[arg(
+        long,
+        global = true,
+        value_name = "LEVEL",
+        help = "Set log level: quiet, warn, normal, info, debug"
+    )]
+    pub log_level: Option<LogLevel>,
+
+    /// Per-module log directives on top of the level, e.g. `aria_move::fs_ops=debug`.
+    #[arg(
+        long,
+        global = true,
+        value_name = "DIRECTIVES",
+        help = "Extra per-module log directives, e.g. aria_move::fs_ops=debug,aria_move::config=warn"
+    )]
+    pub log_filter: Option<String>,
+
+    /// Let a non-empty RUST_LOG replace --log-level and --log-filter.
+    #[arg(
+        long,
+        global = true,
+        help = "Use RUST_LOG (when set) instead of the log level and log filter"
+    )]
+    pub honor_rust_log: bool,
+
+    /// Log each open/rename/fsync/unlink at debug level (path, duration, errno).
+    #[arg(
+        long,
+        global = true,
+        help = "Log every open, rename, fsync and unlink with path, duration and errno (debug level)"
+    )]
+    pub trace_fs_ops: bool,
+
+    /// Log timestamp format: rfc3339, legacy or a strftime format.
+    #[arg(
+        long,
+        global = true,
+        value_name = "FORMAT",
+        help = "Log timestamps: rfc3339 (default), legacy (DD/MM/YY HH:MM:SS) or a strftime format"
+    )]
+    pub log_time_format: Option<LogTimeFormat>,
+
+    /// Write log timestamps in UTC.
+    #[arg(
+        long,
+        global = true,
+        help = "Write log timestamps in UTC instead of local time"
+    )]
+    pub log_utc: bool,
+
+    /// Emit logs in structured JSON (includes timestamp, level, and structured fields).
+    #[arg(long, global = true, help = "Emit logs in structured JSON")]
+    pub json: bool,
+
+    /// Where console logs and status lines go: stdout, stderr or none.
+    #[arg(
+        long,
+        global = true,
+        value_name = "STREAM",
+        help = "Console logs and status lines: stdout (default), stderr, none (scriptable/JSON output stays on stdout)"
+    )]
+    pub console_stream: Option<ConsoleStream>,
+
+    /// Disable colored console output (same effect as setting NO_COLOR).
+    #[arg(
+        long,
+        global = true,
+        help = "Disable colored output (also honors NO_COLOR)"
+    )]
+    pub no_color: bool,
+
+    /// Override config.xml path (highest precedence; overrides ARIA_MOVE_CONFIG and defaults)
+    #[arg(
+        long = "config",
+        global = true,
+        value_name = "PATH",
+        value_hint = ValueHint::FilePath,
+        help = "Path to config.xml (highest precedence; overrides ARIA_MOVE_CONFIG and defaults)"
+    )]
+    pub config_path: Option<PathBuf>,
+
+    /// Never write a template config; a missing config is an error (ARIA_MOVE_NO_TEMPLATE=1).
+    #[arg(
+        long,
+        global = true,
+        help = "Fail instead of writing a template when no config exists (also ARIA_MOVE_NO_TEMPLATE=1)"
+    )]
+    pub no_auto_config: bool,
+
+    /// Machine mode: no template config and no first-run guidance; a missing config is a
+    /// one-line `ConfigMissing` error. Implied for aria2 hook calls without a terminal.
+    #[arg(
+        long,
+        global = true,
+        help = "Machine mode: a missing config is a one-line error instead of a template (implied for aria2 hook calls without a TTY)"
+    )]
+    pub machine: bool,
+}
+
+/// Arguments of `move`, which is also the implicit default (`aria_move GID NUM PATH`).
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct MoveArgs {
     /// Aria2 task id (optional, informational). Ignored for auto-resolution logic.
     pub task_id: Option<String>,
 
@@ -48,18 +201,6 @@ pub struct Args {
     #[arg(long, value_hint = ValueHint::DirPath, help = "Override the completed base directory")]
     pub completed_base: Option<PathBuf>,
 
-    /// Enable debug logging (equivalent to `--log-level debug`).
-    #[arg(
-        short = 'd',
-        long,
-        help = "Enable debug logging (shorthand for --log-level debug)"
-    )]
-    pub debug: bool,
-
-    /// Set log level. One of: quiet, normal, info, debug.
-    #[arg(long, help = "Set log level: quiet, normal, info, debug")]
-    pub log_level: Option<String>,
-
     /// Print where aria_move will look for the config file (or ARIA_MOVE_CONFIG if set), then exit.
     #[arg(
         long,
@@ -88,6 +229,31 @@ pub struct Args {
     )]
     pub preserve_permissions: bool,
 
+    /// Unix: mode for directories created in completed_base (octal, umask-independent).
+    #[arg(
+        long,
+        value_name = "MODE",
+        value_parser = parse_dir_mode,
+        help = "Unix: octal mode for directories created in completed_base, e.g. 0755"
+    )]
+    pub dest_dir_mode: Option<u32>,
+
+    /// Unix: octal mode for missing base directories created at startup (default 0700).
+    #[arg(
+        long,
+        value_name = "MODE",
+        value_parser = parse_dir_mode,
+        help = "Unix: octal mode for missing base directories aria_move creates (default 0700)"
+    )]
+    pub created_dir_mode: Option<u32>,
+
+    /// Linux: copy POSIX ACLs and keep default-ACL inheritance for created directories.
+    #[arg(
+        long,
+        help = "Linux: copy POSIX ACLs; created directories keep their inherited default ACL"
+    )]
+    pub preserve_acls: bool,
+
     /// Disable directory locking (for ZFS/NFS/network shares in containers where flock may fail).
     #[arg(
         long,
@@ -95,23 +261,641 @@ pub struct Args {
     )]
     pub disable_locks: bool,
 
-    /// Emit logs in structured JSON (includes timestamp, level, and structured fields).
-    #[arg(long, help = "Emit logs in structured JSON")]
-    pub json: bool,
+    /// Locking mechanism: auto, flock, lockfile (NFS-safe, TTL expiry), none.
+    #[arg(
+        long,
+        value_name = "STRATEGY",
+        help = "Locking: auto (lockfile on NFS/SMB, else flock), flock, lockfile, none"
+    )]
+    pub lock_strategy: Option<LockStrategy>,
 
-    /// Override config.xml path (highest precedence; overrides ARIA_MOVE_CONFIG and defaults)
+    /// File scheduling order for directory copies: walk, largest-first, smallest-first.
     #[arg(
-        long = "config",
+        long,
+        value_name = "ORDER",
+        help = "Directory copy order: walk, largest-first, smallest-first"
+    )]
+    pub copy_order: Option<CopyOrder>,
+
+    /// Copy implementation used when a rename is not possible: builtin or rsync.
+    #[arg(
+        long,
+        value_name = "BACKEND",
+        help = "Cross-filesystem copy backend: builtin, rsync (delta resume of interrupted files)"
+    )]
+    pub copy_backend: Option<CopyBackend>,
+
+    /// Copy engine by name; overrides --copy-backend.
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Copy engine: builtin, reflink, kernel, streaming, rsync (overrides --copy-backend)"
+    )]
+    pub copy_engine: Option<String>,
+
+    /// How an interrupted temp is checked before the builtin copy resumes it: tail, full, none.
+    #[arg(
+        long,
+        value_name = "MODE",
+        help = "Check before resuming an interrupted copy: tail (last 4 MiB), full, none"
+    )]
+    pub resume_verify: Option<ResumeVerify>,
+
+    /// Keep going when some files of a directory cannot be moved; leave them in place.
+    #[arg(
+        long,
+        help = "On directory copy errors, move what can be moved and leave problem files in place"
+    )]
+    pub partial_dir_moves: bool,
+
+    /// Copy into completed_base but never remove (or rename) the source.
+    #[arg(
+        long,
+        help = "Copy instead of move: the source is left in place (read-only sources, keep seeding)"
+    )]
+    pub copy_only: bool,
+
+    /// aria2 hook form: copy instead of move while aria2 still seeds the GID.
+    #[arg(
+        long,
+        help = "Ask aria2 over JSON-RPC whether the GID is still seeding; if so, copy instead of move"
+    )]
+    pub bt_mode: bool,
+
+    /// aria2 JSON-RPC endpoint for --bt-mode.
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "aria2 JSON-RPC endpoint for --bt-mode (default http://localhost:6800/jsonrpc)"
+    )]
+    pub aria2_rpc_url: Option<String>,
+
+    /// aria2 GIDs to move one after another; their paths are asked from aria2 over JSON-RPC.
+    #[arg(
+        long = "gid",
+        value_name = "GID",
+        value_delimiter = ',',
+        help = "Move the download of this aria2 GID (repeat or comma-separate for a batch; paths come from aria2 RPC)"
+    )]
+    pub gids: Vec<String>,
+
+    /// aria2 hook form: pause or remove the task over JSON-RPC before moving it.
+    #[arg(
+        long,
+        value_name = "ACTION",
+        help = "Before moving, ask aria2 to: none (default), pause (resumed from the new location), remove"
+    )]
+    pub rpc_action: Option<RpcAction>,
+
+    /// Skip the rename fast path; always copy, then remove the source.
+    #[arg(
+        long,
+        help = "Never rename: always copy, then remove the source (FUSE/overlay mounts with broken rename)"
+    )]
+    pub force_copy: bool,
+
+    /// Refuse files that end in an all-zero (preallocated, unwritten) region.
+    #[arg(
+        long,
+        help = "Refuse files whose tail is all zeros (preallocated downloads not fully written)"
+    )]
+    pub content_sanity_check: bool,
+
+    /// Linux: copy into an O_TMPFILE and link it in when done (no visible temp names).
+    #[arg(
+        long,
+        help = "Linux: copy into an unnamed temp file so no .aria_move temp appears (no resume)"
+    )]
+    pub unnamed_temp: bool,
+
+    /// Which fsyncs moves perform: full (files and directories), data (files only), none.
+    #[arg(
+        long,
+        value_name = "POLICY",
+        help = "fsync policy: full (default), data (skip directory syncs), none"
+    )]
+    pub fsync_policy: Option<FsyncPolicy>,
+
+    /// Policy when the destination directory already exists: rename (suffix) or merge.
+    #[arg(
+        long,
+        value_name = "POLICY",
+        help = "When the destination directory exists: rename, merge"
+    )]
+    pub dir_on_duplicate: Option<OnDuplicate>,
+
+    /// Per-file collision policy used inside a directory merge (not for file moves).
+    #[arg(
+        long,
+        value_name = "POLICY",
+        help = "File collisions inside a directory merge: rename, skip, overwrite"
+    )]
+    pub merge_on_conflict: Option<OnDuplicate>,
+
+    /// How to handle a source that is a symlink: error, move-link, move-target.
+    #[arg(
+        long,
+        value_name = "POLICY",
+        help = "Symlink sources: error, move-link, move-target"
+    )]
+    pub symlink_source_policy: Option<SymlinkPolicy>,
+
+    /// What to do when completed_base/<name> is already a symlink: error, replace.
+    #[arg(
+        long,
+        value_name = "POLICY",
+        help = "completed_base/<name> is a symlink: error, replace (removes the link only)"
+    )]
+    pub dest_symlink_policy: Option<DestSymlinkPolicy>,
+
+    /// Move sources that do not lie beneath download_base (`require_source_in_base = false`).
+    #[arg(
+        long,
+        help = "Allow sources outside download_base (turns off require_source_in_base)"
+    )]
+    pub allow_outside_base: bool,
+
+    /// What to do when a torrent moved before (same --infohash) completes again.
+    #[arg(
+        long,
+        value_name = "POLICY",
+        help = "Torrent with a known --infohash completed again: keep-both, skip, replace"
+    )]
+    pub on_redownload: Option<OnRedownload>,
+
+    /// Torrent infohash of this download (hex), recorded with the destination.
+    #[arg(
+        long,
+        value_name = "HASH",
+        help = "Torrent infohash (hex); a repeat completion follows --on-redownload"
+    )]
+    pub infohash: Option<String>,
+
+    /// What to do when completed_base has no room for the item.
+    #[arg(
+        long,
+        value_name = "POLICY",
+        help = "Destination full: fail (default), pause-and-retry, spill"
+    )]
+    pub on_full: Option<OnFull>,
+
+    /// Longest total wait of `--on-full pause-and-retry`.
+    #[arg(
+        long,
+        value_name = "DURATION",
+        help = "Give up pause-and-retry after DURATION, e.g. 90s, 5m, 2h (default 1h)"
+    )]
+    pub on_full_max_wait: Option<HumanDuration>,
+
+    /// Destination for `--on-full spill`; repeat for more, tried in order.
+    #[arg(
+        long,
         value_name = "PATH",
-        value_hint = ValueHint::AnyPath,
-        help = "Path to config.xml (highest precedence; overrides ARIA_MOVE_CONFIG and defaults)"
+        value_hint = ValueHint::DirPath,
+        help = "Overflow destination for --on-full spill (repeatable, tried in order)"
     )]
-    pub config_path: Option<PathBuf>,
+    pub overflow_base: Vec<PathBuf>,
+
+    /// Inactivity before an item counts as complete (used by --sweep and --resolve-directories).
+    #[arg(
+        long,
+        value_name = "DURATION",
+        help = "Items modified within DURATION count as in progress for --sweep and --resolve-directories (default 5m, 0 = no age check)"
+    )]
+    pub recent_window: Option<HumanDuration>,
+
+    /// With no source path, auto-pick the most recent stable top-level directory in download_base.
+    #[arg(
+        long,
+        help = "With no source path, pick the most recent stable directory in download_base"
+    )]
+    pub resolve_directories: bool,
+
+    /// Consider dot-files/dirs when resolving, sweeping and reconciling.
+    #[arg(
+        long,
+        help = "Consider hidden (dot) files and directories; .aria_move.* is always skipped"
+    )]
+    pub include_hidden: bool,
+
+    /// Write provenance xattrs on moved items.
+    #[arg(
+        long,
+        help = "Tag moved items with user.aria_move.{src,gid,moved_at} xattrs (xattrs feature)"
+    )]
+    pub tag_destination: bool,
+
+    /// rclone executable for `rclone:remote:path` destinations.
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_hint = ValueHint::ExecutablePath,
+        help = "rclone executable used for completed_base = rclone:remote:path"
+    )]
+    pub rclone_binary: Option<PathBuf>,
+
+    /// File rewritten after every successful run; see `aria_move healthcheck`.
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_hint = ValueHint::FilePath,
+        help = "Rewrite this file after each successful run (for health checks)"
+    )]
+    pub heartbeat_file: Option<PathBuf>,
+
+    /// Hash-chained log of every move; see `aria_move audit verify`.
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_hint = ValueHint::FilePath,
+        help = "Append a hash-chained record of every move to this file"
+    )]
+    pub audit_log: Option<PathBuf>,
+
+    /// Write a JSON diagnostic bundle here whenever a move fails.
+    #[arg(
+        long,
+        value_name = "DIR",
+        value_hint = ValueHint::DirPath,
+        help = "Write a JSON diagnostic bundle into this directory whenever a move fails"
+    )]
+    pub diagnostics_dir: Option<PathBuf>,
+
+    /// Early warning before moves fail for lack of space.
+    #[arg(
+        long,
+        value_name = "MIB",
+        help = "Warn once when completed_base has less than MIB free (0 = off)"
+    )]
+    pub low_space_warn_mib: Option<u64>,
+
+    /// Free space required beyond an item's size before it is copied.
+    #[arg(
+        long,
+        value_name = "SIZE",
+        help = "Free space required beyond an item's size before copying it, e.g. 64MiB or 1GB (default 4MiB)"
+    )]
+    pub space_cushion: Option<ByteSize>,
+
+    /// When started as root, switch to this user after startup (Unix).
+    #[arg(
+        long,
+        value_name = "USER",
+        help = "When started as root, drop to this user (name or uid) after startup"
+    )]
+    pub run_as_user: Option<String>,
+
+    /// When started as root, switch to this group after startup (Unix).
+    #[arg(
+        long,
+        value_name = "GROUP",
+        help = "Group for --run-as-user (name or gid; default: the user's primary group)"
+    )]
+    pub run_as_group: Option<String>,
+
+    /// Print the entries the resolver considers (resolver order, stability, reason), then exit.
+    #[arg(
+        long,
+        help = "List auto-resolution candidates in download_base and exit"
+    )]
+    pub list_candidates: bool,
+
+    /// After handling the source, report other completed items left in download_base.
+    #[arg(
+        long,
+        help = "After the move, report stable items left behind in download_base"
+    )]
+    pub sweep: bool,
+
+    /// Like --sweep, but also move the leftovers into completed_base.
+    #[arg(long, help = "Like --sweep, but also move the leftovers")]
+    pub sweep_move: bool,
+
+    /// How many leftovers --sweep-move moves at once.
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Move up to N sweep leftovers concurrently (default 1)"
+    )]
+    pub max_concurrent_moves: Option<usize>,
+
+    /// Runtime budget for the whole invocation (hung NFS servers).
+    #[arg(
+        long,
+        alias = "max-runtime-seconds",
+        value_name = "DURATION",
+        help = "Abort the run after DURATION (e.g. 90s, 10m), keeping the source (0 = no limit)"
+    )]
+    pub max_runtime: Option<HumanDuration>,
+
+    /// Warn when a single move takes longer than this (degrading disks, sick mounts).
+    #[arg(
+        long,
+        alias = "slow-move-warning-seconds",
+        value_name = "DURATION",
+        help = "Warn and send a slow-move notification when one move takes longer than DURATION (0 = off)"
+    )]
+    pub slow_move_warning: Option<HumanDuration>,
+}
+
+/// Subcommands. Without one, aria_move runs `move` with the top-level arguments.
+#[derive(Subcommand, Debug, Clone)]
+#[allow(clippy::large_enum_variant)] // parsed once per run
+pub enum Command {
+    /// Move a completed download into completed_base (default; what aria2 hooks run).
+    Move(MoveArgs),
+
+    /// Print what `move` would do (destination, rename or copy, size, free space, locks)
+    /// without touching anything.
+    Plan(MoveArgs),
+
+    /// Remove leftovers of crashed runs in both bases: orphaned `.aria_move.*` temp files,
+    /// stale lock files and partial directory copies; abandoned claimed sources get their
+    /// names back. Prints a JSON report; `--dry-run` only reports.
+    Clean(MoveArgs),
+
+    /// Copy new and changed stable files from download_base into completed_base, leaving
+    /// download_base untouched (one-way sync). Prints a JSON report per pass.
+    Mirror(MirrorArgs),
+
+    /// Move every stable leftover in download_base into completed_base (like `--sweep-move`),
+    /// then again every `--interval` until interrupted.
+    Watch(WatchArgs),
+
+    /// Print a JSON overview without changing anything: both bases, free space in
+    /// completed_base, what the resolver sees in download_base, the heartbeat age and the
+    /// last audit record.
+    Status(MoveArgs),
+
+    /// Move the newest move recorded in the audit log back to its source; repeat to walk
+    /// further back. Prints a JSON report; `--dry-run` only reports.
+    Undo(MoveArgs),
+
+    /// Move a generated sample file and directory into completed_base with the real config
+    /// (the directory through the copy fallback), verify them and remove them again.
+    /// Prints a JSON report; a safe first run for a new deployment.
+    Selftest(MoveArgs),
+
+    /// Measure rename, reflink, kernel copy and streaming between the bases and remember the
+    /// fastest copy engine for later moves. Prints a JSON report.
+    BenchDest(BenchArgs),
+
+    /// Inspect or create the config file.
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+
+    /// Generate or install a macOS LaunchAgent that sweeps download_base when it changes.
+    Launchd {
+        #[command(subcommand)]
+        action: LaunchdCommand,
+
+        /// Also run every DURATION (e.g. 300, 5m), in case a change notification is missed.
+        #[arg(long, value_name = "DURATION", default_value = "5m", global = true)]
+        interval: HumanDuration,
+    },
+
+    /// Exit 0 only if the heartbeat file shows a successful run within `--max-age`.
+    ///
+    /// Example (Dockerfile): `HEALTHCHECK CMD aria_move healthcheck --max-age 900`
+    Healthcheck {
+        /// Heartbeat file to check (default: `heartbeat_file` from the config).
+        #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+        file: Option<PathBuf>,
+
+        /// Maximum age of the last successful run (seconds or e.g. 15m).
+        #[arg(long, value_name = "DURATION", default_value = "15m")]
+        max_age: HumanDuration,
+    },
+
+    /// Point aria2's `on-download-complete=` at this binary, or diagnose it with `--check`.
+    ///
+    /// Example: `aria_move install-hook --aria2-conf ~/.aria2/aria2.conf --check`
+    InstallHook {
+        /// aria2 configuration file to inspect or update.
+        #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+        aria2_conf: PathBuf,
+
+        /// Only report problems (missing, quoted or relative hook, missing exec bit);
+        /// exits non-zero if there are any. Nothing is written.
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Check the audit log.
+    Audit {
+        #[command(subcommand)]
+        action: AuditCommand,
+    },
+
+    /// Print the last records of the audit log, oldest first, one JSON object per line.
+    History {
+        /// Audit log to read (default: `audit_log` from the config).
+        #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+        file: Option<PathBuf>,
+
+        /// Number of records to print.
+        #[arg(short = 'n', long, value_name = "N", default_value_t = 20)]
+        limit: usize,
+    },
+
+    /// Re-hash files and compare them with the sha256 recorded by `tag_destination`
+    /// (needs the `xattrs` feature). Prints a JSON report; fails if any file changed.
+    Verify {
+        /// File or directory to check (default: completed_base from the config).
+        #[arg(value_name = "PATH", value_hint = ValueHint::AnyPath)]
+        path: Option<PathBuf>,
+    },
+
+    /// Print a shell completion script to stdout.
+    ///
+    /// Example: `aria_move completions bash > /etc/bash_completion.d/aria_move`
+    Completions {
+        /// Target shell.
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+/// Arguments of `mirror`.
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct MirrorArgs {
+    /// Bases, copy backend, locking and the other move settings.
+    #[command(flatten)]
+    pub common: MoveArgs,
+
+    /// Also remove items from completed_base that no longer exist in download_base.
+    #[arg(long)]
+    pub prune: bool,
+
+    /// Repeat every DURATION (e.g. 90s, 1h) until interrupted (0 = a single pass).
+    #[arg(long, value_name = "DURATION", default_value = "0")]
+    pub interval: HumanDuration,
+}
+
+/// Arguments of `watch`.
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct WatchArgs {
+    /// Bases, copy backend, locking and the other move settings.
+    #[command(flatten)]
+    pub common: MoveArgs,
+
+    /// Pause between passes (e.g. 30s, 5m; 0 = a single pass).
+    #[arg(long, value_name = "DURATION", default_value = "1m")]
+    pub interval: HumanDuration,
+}
+
+/// Arguments of `bench-dest`.
+#[derive(clap::Args, Debug, Clone, Default)]
+pub struct BenchArgs {
+    /// Bases and the other move settings.
+    #[command(flatten)]
+    pub common: MoveArgs,
+
+    /// Size of the sample file in MiB.
+    #[arg(long, value_name = "MIB", default_value_t = 64)]
+    pub size_mib: u64,
+}
+
+/// `aria_move config <ACTION>`.
+#[derive(Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigCommand {
+    /// Print the config file location (same as `--print-config`).
+    Path,
+    /// Write a commented template config if none exists yet.
+    Init,
+}
+
+/// `aria_move audit <ACTION>`.
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum AuditCommand {
+    /// Validate the hash chain and head of the audit log; fails on edited, removed or
+    /// reordered records and on truncation. Prints a JSON report.
+    Verify {
+        /// Audit log to check (default: `audit_log` from the config).
+        #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath)]
+        file: Option<PathBuf>,
+    },
+}
+
+/// `aria_move launchd <ACTION>`.
+#[derive(Subcommand, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchdCommand {
+    /// Print the LaunchAgent plist to stdout.
+    Print,
+    /// Write the plist to ~/Library/LaunchAgents (then load it with launchctl).
+    Install,
+    /// Remove the plist from ~/Library/LaunchAgents.
+    Uninstall,
 }
 
 impl Args {
-    /// Effective source path: `--source-path` if provided, else positional SOURCE_PATH.
-    #[inline]
+    /// Parse `std::env::args_os()`, exiting with usage on error (see [`Args::try_parse_from`]).
+    pub fn parse() -> Self {
+        Self::parse_from(std::env::args_os())
+    }
+
+    /// Parse `itr`, exiting with usage on error (see [`Args::try_parse_from`]).
+    pub fn parse_from<I, T>(itr: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        Self::try_parse_from(itr).unwrap_or_else(|e| e.exit())
+    }
+
+    /// Parse `itr`, refusing top-level move arguments combined with a subcommand.
+    /// (clap's `args_conflicts_with_subcommands` would also refuse global options such as
+    /// `--config` in front of the subcommand.)
+    pub fn try_parse_from<I, T>(itr: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        let mut cmd = Self::command();
+        let matches = cmd.try_get_matches_from_mut(itr)?;
+        if let Some((name, _)) = matches.subcommand()
+            && let Some(arg) = hook_arg_given(&matches)
+        {
+            return Err(cmd.error(
+                clap::error::ErrorKind::ArgumentConflict,
+                format!("the argument '{arg}' cannot be used with the '{name}' subcommand"),
+            ));
+        }
+        Self::from_arg_matches(&matches).map_err(|e| e.format(&mut cmd))
+    }
+
+    /// Move arguments in effect: those of `move`, `plan`, `clean`, `selftest`, `mirror` or `bench-dest`,
+    /// else the top-level ones.
+    pub fn move_args(&self) -> &MoveArgs {
+        match &self.command {
+            Some(
+                Command::Move(m)
+                | Command::Plan(m)
+                | Command::Clean(m)
+                | Command::Selftest(m)
+                | Command::Status(m)
+                | Command::Undo(m),
+            ) => m,
+            Some(Command::Mirror(m)) => &m.common,
+            Some(Command::Watch(w)) => &w.common,
+            Some(Command::BenchDest(b)) => &b.common,
+            _ => &self.hook,
+        }
+    }
+
+    /// Effective source path of the move (see [`MoveArgs::resolved_source`]).
+    pub fn resolved_source(&self) -> Option<PathBuf> {
+        self.move_args().resolved_source()
+    }
+
+    /// Effective log level derived from the global flags.
+    pub fn effective_log_level(&self) -> Option<LogLevel> {
+        self.global.effective_log_level()
+    }
+
+    /// `--machine`, or an aria2 hook call (`aria_move GID NUM PATH`) whose stdout is not a
+    /// terminal: nobody would read first-run guidance, so a missing config must fail instead.
+    pub fn machine_mode(&self) -> bool {
+        self.global.machine
+            || (self.command.is_none()
+                && self.hook.is_aria2_hook_form()
+                && !atty::is(atty::Stream::Stdout))
+    }
+
+    /// Apply CLI overrides to a loaded Config (in-place). No-ops for unset flags.
+    pub fn apply_overrides(&self, cfg: &mut Config) {
+        if let Some(level) = self.effective_log_level() {
+            cfg.log_level = level;
+        }
+        if let Some(stream) = self.global.console_stream {
+            cfg.console_stream = stream;
+        }
+        self.move_args().apply_overrides(cfg);
+    }
+}
+
+impl GlobalArgs {
+    /// Effective log level derived from flags.
+    /// Precedence: --debug > --quiet > --log-level value > None (use config default).
+    pub fn effective_log_level(&self) -> Option<LogLevel> {
+        if self.debug {
+            return Some(LogLevel::Debug);
+        }
+        if self.quiet {
+            return Some(LogLevel::Quiet);
+        }
+        self.log_level
+    }
+}
+
+impl MoveArgs {
+    /// All three positionals aria2 passes to a hook: GID, file count and path.
+    pub fn is_aria2_hook_form(&self) -> bool {
+        self.task_id.is_some() && self.num_files.is_some() && self.source_path_pos.is_some()
+    }
+
     /// Effective source path.
     ///
     /// Precedence:
@@ -143,6 +927,25 @@ impl Args {
         None
     }
 
+    /// aria2 GID of the download, when invoked in the aria2 hook form
+    /// (`<gid> <num_files> <path>`); a lone positional is a path, not a GID.
+    pub fn aria2_gid(&self) -> Option<&str> {
+        self.num_files.and(self.task_id.as_deref())
+    }
+
+    /// GIDs of a batch run: every `--gid`, or the GID of the aria2 hook form when a wrapper
+    /// passed a comma-separated list. Empty for a single-GID or path run.
+    pub fn batch_gids(&self) -> Vec<&str> {
+        let hook = self.aria2_gid().filter(|g| g.contains(','));
+        self.gids
+            .iter()
+            .map(String::as_str)
+            .chain(hook.into_iter().flat_map(|g| g.split(',')))
+            .map(str::trim)
+            .filter(|g| !g.is_empty())
+            .collect()
+    }
+
     // Removed heuristic helper; we accept single positional as path unconditionally.
     #[inline]
     fn sanitize_path(p: &std::path::Path) -> PathBuf {
@@ -180,16 +983,7 @@ impl Args {
         PathBuf::from(inner)
     }
 
-    /// Effective log level derived from flags.
-    /// Precedence: --debug > --log-level value > None (use config default).
-    pub fn effective_log_level(&self) -> Option<LogLevel> {
-        if self.debug {
-            return Some(LogLevel::Debug);
-        }
-        self.log_level.as_deref().and_then(LogLevel::parse)
-    }
-
-    /// Apply CLI overrides to a loaded Config (in-place). No-ops for unset flags.
+    /// Apply the move flags to a loaded Config (in-place). No-ops for unset flags.
     pub fn apply_overrides(&self, cfg: &mut Config) {
         if let Some(db) = &self.download_base {
             cfg.download_base = db.clone();
@@ -197,9 +991,6 @@ impl Args {
         if let Some(cb) = &self.completed_base {
             cfg.completed_base = cb.clone();
         }
-        if let Some(level) = self.effective_log_level() {
-            cfg.log_level = level;
-        }
         if self.dry_run {
             cfg.dry_run = true;
         }
@@ -209,12 +1000,151 @@ impl Args {
         if self.preserve_permissions {
             cfg.preserve_permissions = true;
         }
+        if let Some(mode) = self.dest_dir_mode {
+            cfg.dest_dir_mode = Some(mode);
+        }
+        if let Some(mode) = self.created_dir_mode {
+            cfg.created_dir_mode = mode;
+        }
+        if self.preserve_acls {
+            cfg.preserve_acls = true;
+        }
         if self.disable_locks {
             cfg.disable_locks = true;
         }
+        if let Some(strategy) = self.lock_strategy {
+            cfg.lock_strategy = strategy;
+        }
+        if let Some(order) = self.copy_order {
+            cfg.copy_order = order;
+        }
+        if let Some(backend) = self.copy_backend {
+            cfg.copy_backend = backend;
+        }
+        if let Some(engine) = self.copy_engine.as_ref() {
+            cfg.copy_engine = Some(engine.clone());
+        }
+        if let Some(verify) = self.resume_verify {
+            cfg.resume_verify = verify;
+        }
+        if self.partial_dir_moves {
+            cfg.partial_dir_moves = true;
+        }
+        if self.copy_only {
+            cfg.copy_only = true;
+        }
+        if self.bt_mode {
+            cfg.bt_mode = true;
+        }
+        if let Some(url) = &self.aria2_rpc_url {
+            cfg.aria2_rpc_url = url.clone();
+        }
+        if let Some(action) = self.rpc_action {
+            cfg.rpc_action = action;
+        }
+        if self.force_copy {
+            cfg.force_copy = true;
+        }
+        if self.content_sanity_check {
+            cfg.content_sanity_check = true;
+        }
+        if self.unnamed_temp {
+            cfg.unnamed_temp = true;
+        }
+        if let Some(policy) = self.fsync_policy {
+            cfg.fsync_policy = policy;
+        }
+        if let Some(policy) = self.dir_on_duplicate {
+            cfg.dir_on_duplicate = policy;
+        }
+        if let Some(policy) = self.merge_on_conflict {
+            cfg.merge_on_conflict = policy;
+        }
+        if let Some(policy) = self.symlink_source_policy {
+            cfg.symlink_source_policy = policy;
+        }
+        if let Some(policy) = self.dest_symlink_policy {
+            cfg.dest_symlink_policy = policy;
+        }
+        if self.allow_outside_base {
+            cfg.require_source_in_base = false;
+        }
+        if let Some(policy) = self.on_redownload {
+            cfg.on_redownload = policy;
+        }
+        if let Some(policy) = self.on_full {
+            cfg.on_full = policy;
+        }
+        if let Some(wait) = self.on_full_max_wait {
+            cfg.on_full_max_wait = wait.get();
+        }
+        if !self.overflow_base.is_empty() {
+            cfg.overflow_bases = self.overflow_base.clone();
+        }
+        if let Some(window) = self.recent_window {
+            cfg.recent_window = window.get();
+        }
+        if self.resolve_directories {
+            cfg.resolve_directories = true;
+        }
+        if self.include_hidden {
+            cfg.include_hidden = true;
+        }
+        if self.tag_destination {
+            cfg.tag_destination = true;
+        }
+        if let Some(bin) = self.rclone_binary.as_ref() {
+            cfg.rclone_binary = bin.clone();
+        }
+        if let Some(hb) = self.heartbeat_file.as_ref() {
+            cfg.heartbeat_file = Some(hb.clone());
+        }
+        if let Some(log) = self.audit_log.as_ref() {
+            cfg.audit_log = Some(log.clone());
+        }
+        if let Some(dir) = self.diagnostics_dir.as_ref() {
+            cfg.diagnostics_dir = Some(dir.clone());
+        }
+        if let Some(mib) = self.low_space_warn_mib {
+            cfg.low_space_warn_mib = (mib > 0).then_some(mib);
+        }
+        if let Some(size) = self.space_cushion {
+            cfg.space_cushion = size;
+        }
+        if let Some(user) = self.run_as_user.as_ref() {
+            cfg.run_as_user = Some(user.clone());
+        }
+        if let Some(group) = self.run_as_group.as_ref() {
+            cfg.run_as_group = Some(group.clone());
+        }
+        if let Some(n) = self.max_concurrent_moves {
+            cfg.max_concurrent_moves = n;
+        }
+        if let Some(limit) = self.max_runtime {
+            cfg.max_runtime = (!limit.get().is_zero()).then(|| limit.get());
+        }
+        if let Some(limit) = self.slow_move_warning {
+            cfg.slow_move_warning = (!limit.get().is_zero()).then(|| limit.get());
+        }
     }
 }
 
 pub fn parse() -> Args {
     Args::parse()
 }
+
+/// First top-level move argument given on the command line (as shown in usage), if any.
+fn hook_arg_given(matches: &ArgMatches) -> Option<String> {
+    let mut hook = <MoveArgs as clap::Args>::augment_args(clap::Command::new("move"));
+    hook.build();
+    hook.get_arguments()
+        .find(|a| matches.value_source(a.get_id().as_str()) == Some(ValueSource::CommandLine))
+        .map(ToString::to_string)
+}
+
+/// Write the completion script for `shell` (flags, enum values and path hints) to `out`.
+pub fn write_completions(shell: Shell, out: &mut dyn Write) {
+    let mut cmd = Args::command();
+    let name = cmd.get_name().to_string();
+    clap_complete::generate(shell, &mut cmd, name, out);
+}
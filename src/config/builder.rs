@@ -0,0 +1,358 @@
+//! Fluent construction of a validated `Config`.
+//! - `Config::builder()` starts from `Config::default()`; each setter overrides one field.
+//! - `build()` runs `validate_and_normalize`, so a built Config always has existing,
+//!   canonical, disjoint bases (or a valid remote completed_base).
+//!
+//! Notes:
+//! - `build()` creates missing base directories, exactly like the CLI does.
+//! - Struct literals and `Config::new` keep working; the public fields may become private in a
+//!   future major release, so library consumers should prefer the builder.
+
+use anyhow::Result;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::types::{
+    ByteSize, Config, ConsoleStream, CopyBackend, CopyOrder, DestSymlinkPolicy, FsyncPolicy,
+    LockStrategy, LogLevel, LogTimeFormat, PartialPreset, ResumeVerify, RpcAction, SymlinkPolicy,
+};
+use super::validate_and_normalize;
+use crate::fs_ops::{Handler, OnDuplicate, OnFull, OnRedownload};
+use crate::notify::Notifier;
+
+impl Config {
+    /// Start building a Config from defaults; finish with `ConfigBuilder::build`.
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+}
+
+/// Builder returned by `Config::builder()`.
+#[derive(Debug, Clone, Default)]
+#[must_use = "call .build() to obtain a validated Config"]
+pub struct ConfigBuilder {
+    cfg: Config,
+}
+
+impl ConfigBuilder {
+    pub fn download_base(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cfg.download_base = path.into();
+        self
+    }
+
+    pub fn completed_base(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cfg.completed_base = path.into();
+        self
+    }
+
+    pub fn log_level(mut self, level: LogLevel) -> Self {
+        self.cfg.log_level = level;
+        self
+    }
+
+    /// Per-module log directives (`EnvFilter` syntax) applied on top of the log level.
+    pub fn log_filter(mut self, directives: Option<String>) -> Self {
+        self.cfg.log_filter = directives;
+        self
+    }
+
+    /// Let a non-empty `RUST_LOG` replace the log level and `log_filter`.
+    pub fn honor_rust_log(mut self, on: bool) -> Self {
+        self.cfg.honor_rust_log = on;
+        self
+    }
+
+    /// Log every low-level filesystem operation at debug level.
+    pub fn trace_fs_ops(mut self, on: bool) -> Self {
+        self.cfg.trace_fs_ops = on;
+        self
+    }
+
+    /// Timestamp format of log lines.
+    pub fn log_time_format(mut self, format: LogTimeFormat) -> Self {
+        self.cfg.log_time_format = format;
+        self
+    }
+
+    /// Write log timestamps in UTC.
+    pub fn log_utc(mut self, on: bool) -> Self {
+        self.cfg.log_utc = on;
+        self
+    }
+
+    /// Log file path; `None` disables file logging.
+    pub fn log_file(mut self, path: Option<PathBuf>) -> Self {
+        self.cfg.log_file = path;
+        self
+    }
+
+    /// Stream for console logs and status lines.
+    pub fn console_stream(mut self, stream: ConsoleStream) -> Self {
+        self.cfg.console_stream = stream;
+        self
+    }
+
+    pub fn dry_run(mut self, on: bool) -> Self {
+        self.cfg.dry_run = on;
+        self
+    }
+
+    pub fn preserve_metadata(mut self, on: bool) -> Self {
+        self.cfg.preserve_metadata = on;
+        self
+    }
+
+    pub fn preserve_permissions(mut self, on: bool) -> Self {
+        self.cfg.preserve_permissions = on;
+        self
+    }
+
+    pub fn dest_dir_mode(mut self, mode: Option<u32>) -> Self {
+        self.cfg.dest_dir_mode = mode;
+        self
+    }
+
+    /// Unix: mode of missing base directories created by `build()` (default 0700).
+    pub fn created_dir_mode(mut self, mode: u32) -> Self {
+        self.cfg.created_dir_mode = mode;
+        self
+    }
+
+    pub fn preserve_acls(mut self, on: bool) -> Self {
+        self.cfg.preserve_acls = on;
+        self
+    }
+
+    pub fn disable_locks(mut self, on: bool) -> Self {
+        self.cfg.disable_locks = on;
+        self
+    }
+
+    pub fn lock_strategy(mut self, strategy: LockStrategy) -> Self {
+        self.cfg.lock_strategy = strategy;
+        self
+    }
+
+    pub fn copy_order(mut self, order: CopyOrder) -> Self {
+        self.cfg.copy_order = order;
+        self
+    }
+
+    pub fn copy_backend(mut self, backend: CopyBackend) -> Self {
+        self.cfg.copy_backend = backend;
+        self
+    }
+
+    pub fn copy_engine(mut self, engine: Option<String>) -> Self {
+        self.cfg.copy_engine = engine;
+        self
+    }
+
+    pub fn resume_verify(mut self, verify: ResumeVerify) -> Self {
+        self.cfg.resume_verify = verify;
+        self
+    }
+
+    pub fn partial_dir_moves(mut self, on: bool) -> Self {
+        self.cfg.partial_dir_moves = on;
+        self
+    }
+
+    pub fn copy_only(mut self, on: bool) -> Self {
+        self.cfg.copy_only = on;
+        self
+    }
+
+    pub fn bt_mode(mut self, on: bool) -> Self {
+        self.cfg.bt_mode = on;
+        self
+    }
+
+    pub fn aria2_rpc(mut self, url: impl Into<String>, secret: Option<String>) -> Self {
+        self.cfg.aria2_rpc_url = url.into();
+        self.cfg.aria2_rpc_secret = secret;
+        self
+    }
+
+    pub fn rpc_action(mut self, action: RpcAction) -> Self {
+        self.cfg.rpc_action = action;
+        self
+    }
+
+    pub fn force_copy(mut self, on: bool) -> Self {
+        self.cfg.force_copy = on;
+        self
+    }
+
+    pub fn content_sanity_check(mut self, on: bool) -> Self {
+        self.cfg.content_sanity_check = on;
+        self
+    }
+
+    pub fn unnamed_temp(mut self, on: bool) -> Self {
+        self.cfg.unnamed_temp = on;
+        self
+    }
+
+    pub fn fsync_policy(mut self, policy: FsyncPolicy) -> Self {
+        self.cfg.fsync_policy = policy;
+        self
+    }
+
+    pub fn dir_on_duplicate(mut self, policy: OnDuplicate) -> Self {
+        self.cfg.dir_on_duplicate = policy;
+        self
+    }
+
+    pub fn merge_on_conflict(mut self, policy: OnDuplicate) -> Self {
+        self.cfg.merge_on_conflict = policy;
+        self
+    }
+
+    pub fn symlink_source_policy(mut self, policy: SymlinkPolicy) -> Self {
+        self.cfg.symlink_source_policy = policy;
+        self
+    }
+
+    pub fn dest_symlink_policy(mut self, policy: DestSymlinkPolicy) -> Self {
+        self.cfg.dest_symlink_policy = policy;
+        self
+    }
+
+    /// Refuse sources outside download_base (and any inside completed_base).
+    pub fn require_source_in_base(mut self, on: bool) -> Self {
+        self.cfg.require_source_in_base = on;
+        self
+    }
+
+    pub fn on_redownload(mut self, policy: OnRedownload) -> Self {
+        self.cfg.on_redownload = policy;
+        self
+    }
+
+    pub fn on_full(mut self, policy: OnFull) -> Self {
+        self.cfg.on_full = policy;
+        self
+    }
+
+    pub fn on_full_max_wait(mut self, wait: Duration) -> Self {
+        self.cfg.on_full_max_wait = wait;
+        self
+    }
+
+    /// Destinations for `on_full = spill`, tried in order.
+    pub fn overflow_bases(mut self, bases: Vec<PathBuf>) -> Self {
+        self.cfg.overflow_bases = bases;
+        self
+    }
+
+    /// Recency window for the sweep; `Duration::ZERO` disables the age check.
+    pub fn recent_window(mut self, window: Duration) -> Self {
+        self.cfg.recent_window = window;
+        self
+    }
+
+    pub fn resolve_directories(mut self, on: bool) -> Self {
+        self.cfg.resolve_directories = on;
+        self
+    }
+
+    pub fn include_hidden(mut self, on: bool) -> Self {
+        self.cfg.include_hidden = on;
+        self
+    }
+
+    /// Download clients whose partial suffixes are recognized (replaces the default set).
+    pub fn partial_extensions_preset(mut self, presets: Vec<PartialPreset>) -> Self {
+        self.cfg.partial_extensions_preset = presets;
+        self
+    }
+
+    /// Extra partial suffixes, on top of the presets.
+    pub fn partial_extensions(mut self, exts: Vec<String>) -> Self {
+        self.cfg.partial_extensions = exts;
+        self
+    }
+
+    pub fn rclone_binary(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cfg.rclone_binary = path.into();
+        self
+    }
+
+    pub fn heartbeat_file(mut self, path: Option<PathBuf>) -> Self {
+        self.cfg.heartbeat_file = path;
+        self
+    }
+
+    pub fn audit_log(mut self, path: Option<PathBuf>) -> Self {
+        self.cfg.audit_log = path;
+        self
+    }
+
+    pub fn diagnostics_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.cfg.diagnostics_dir = dir;
+        self
+    }
+
+    pub fn low_space_warn_mib(mut self, mib: Option<u64>) -> Self {
+        self.cfg.low_space_warn_mib = mib;
+        self
+    }
+
+    /// Free space required beyond an item's size before it is copied (default 4 MiB).
+    pub fn space_cushion(mut self, size: ByteSize) -> Self {
+        self.cfg.space_cushion = size;
+        self
+    }
+
+    pub fn run_as_user(mut self, user: Option<String>) -> Self {
+        self.cfg.run_as_user = user;
+        self
+    }
+
+    pub fn run_as_group(mut self, group: Option<String>) -> Self {
+        self.cfg.run_as_group = group;
+        self
+    }
+
+    /// How many items a sweep moves at once (1 = sequential).
+    pub fn max_concurrent_moves(mut self, n: usize) -> Self {
+        self.cfg.max_concurrent_moves = n;
+        self
+    }
+
+    pub fn max_runtime(mut self, limit: Option<Duration>) -> Self {
+        self.cfg.max_runtime = limit;
+        self
+    }
+
+    /// Warn when a single move takes longer than `limit` (`None` = never).
+    pub fn slow_move_warning(mut self, limit: Option<Duration>) -> Self {
+        self.cfg.slow_move_warning = limit;
+        self
+    }
+
+    pub fn tag_destination(mut self, on: bool) -> Self {
+        self.cfg.tag_destination = on;
+        self
+    }
+
+    /// Per-extension handlers; the first one matching a file name applies.
+    pub fn handlers(mut self, handlers: Vec<Handler>) -> Self {
+        self.cfg.handlers = handlers;
+        self
+    }
+
+    /// Push notifiers; each receives the event types it subscribes to.
+    pub fn notifiers(mut self, notifiers: Vec<Notifier>) -> Self {
+        self.cfg.notifiers = notifiers;
+        self
+    }
+
+    /// Validate and normalize (creating missing base directories) and return the Config.
+    pub fn build(self) -> Result<Config> {
+        let mut cfg = self.cfg;
+        validate_and_normalize(&mut cfg)?;
+        Ok(cfg)
+    }
+}
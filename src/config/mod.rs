@@ -2,50 +2,96 @@
 //! Provides configuration types, default paths, XML loading, and validation.
 //! Re-exports preserve the previous public API for external callers.
 
+pub mod builder;
 pub mod paths;
 pub mod types;
 pub mod xml;
 
 use anyhow::{Context, Result, anyhow};
 use std::fs;
-use std::io::{self, Write};
-use std::path::{Component, Path, PathBuf};
+use std::io;
+use std::path::{Path, PathBuf};
 
+use crate::errors::AriaMoveError;
+
+pub use builder::ConfigBuilder;
 pub use paths::{default_config_path, default_log_path};
-pub use types::{Config, LogLevel};
+pub use types::{
+    Config, CopyBackend, CopyOrder, FsyncPolicy, LockStrategy, LogLevel, ResumeVerify,
+    SymlinkPolicy,
+};
 
 // --- existing/public load_or_init / validate_and_normalize functions remain ---
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)] // returned once per run; boxing would break matchers
 pub enum LoadResult {
     Loaded(types::Config, PathBuf),
     CreatedTemplate(PathBuf),
 }
 
 /// Load config from default path (or ARIA_MOVE_CONFIG). If missing, write a secure template and return CreatedTemplate.
+/// With `ARIA_MOVE_NO_TEMPLATE=1` (`--no-auto-config`) a missing config is `ConfigMissing` instead.
 pub fn load_or_init() -> Result<LoadResult> {
+    let path = default_config_path()?;
+    if !path.exists() && auto_template_disabled() {
+        return Err(AriaMoveError::ConfigMissing(path).into());
+    }
+    init_config()
+}
+
+/// `load_or_init` that always writes the template when the config is missing (`config init`).
+pub fn init_config() -> Result<LoadResult> {
     let path = default_config_path()?;
     if path.exists() {
         return Ok(LoadResult::Loaded(types::Config::default(), path));
     }
 
-    if let Some(parent) = path.parent() {
-        create_secure_dir_all(parent)?;
-    }
-    write_template(&path)?;
+    xml::create_template_config(&path)?;
     Ok(LoadResult::CreatedTemplate(path))
 }
 
+/// True when `ARIA_MOVE_NO_TEMPLATE=1`: never write a template config on first run.
+pub fn auto_template_disabled() -> bool {
+    std::env::var("ARIA_MOVE_NO_TEMPLATE").ok().as_deref() == Some("1")
+}
+
 /// Validate and normalize config paths:
-/// - Ensure directories exist (create if missing) with safe perms
-/// - Reject symlink ancestors (Unix)
+/// - Ensure directories exist (create missing ones with `created_dir_mode`, default 0700)
+/// - Reject symlink / junction ancestors
 /// - Canonicalize final paths back into cfg
 /// - Ensure download_base and completed_base are disjoint (neither equal nor nested)
+/// - The same for each of `overflow_bases`
 pub fn validate_and_normalize(cfg: &mut types::Config) -> Result<()> {
-    ensure_safe_dir(&cfg.download_base)
+    validate(cfg, true)
+}
+
+/// Read-only `validate_and_normalize` for previews (`--dry-run`, `plan`): the same checks,
+/// but missing directories are not created (and no permissions are changed). A missing
+/// base is normalized by canonicalizing its closest existing ancestor.
+pub fn validate_read_only(cfg: &mut types::Config) -> Result<()> {
+    validate(cfg, false)
+}
+
+fn validate(cfg: &mut types::Config, create: bool) -> Result<()> {
+    let create = create.then_some(cfg.created_dir_mode);
+    ensure_safe_dir(&cfg.download_base, create)
         .with_context(|| format!("download_base invalid: {}", cfg.download_base.display()))?;
     cfg.download_base = canonicalize_best_effort(&cfg.download_base)?;
 
-    ensure_safe_dir(&cfg.completed_base)
+    // Remote destinations (sftp://, rclone:) are checked by the uploader, not the local filesystem.
+    if crate::fs_ops::is_remote(&cfg.completed_base) {
+        if crate::fs_ops::RemoteTarget::parse(&cfg.completed_base).is_none()
+            && crate::fs_ops::RcloneTarget::parse(&cfg.completed_base).is_none()
+        {
+            return Err(anyhow!(
+                "completed_base is not a valid sftp:// URL or rclone:remote:path: '{}'",
+                cfg.completed_base.display()
+            ));
+        }
+        return Ok(());
+    }
+
+    ensure_safe_dir(&cfg.completed_base, create)
         .with_context(|| format!("completed_base invalid: {}", cfg.completed_base.display()))?;
     cfg.completed_base = canonicalize_best_effort(&cfg.completed_base)?;
 
@@ -70,6 +116,18 @@ pub fn validate_and_normalize(cfg: &mut types::Config) -> Result<()> {
             cfg.download_base.display()
         ));
     }
+    for base in &mut cfg.overflow_bases {
+        ensure_safe_dir(base, create)
+            .with_context(|| format!("overflow base invalid: {}", base.display()))?;
+        *base = canonicalize_best_effort(base)?;
+        if base.starts_with(&cfg.download_base) || cfg.download_base.starts_with(&*base) {
+            return Err(anyhow!(
+                "overflow base '{}' must not overlap download_base '{}'",
+                base.display(),
+                cfg.download_base.display()
+            ));
+        }
+    }
     Ok(())
 }
 
@@ -82,54 +140,22 @@ pub fn ensure_default_config_exists() -> Result<PathBuf> {
     }
 }
 
-/// Public wrapper that checks whether a path has a symlink ancestor.
-/// Calls the internal has_symlink_ancestor implementation.
-pub fn path_has_symlink_ancestor(path: &Path) -> io::Result<bool> {
-    has_symlink_ancestor(path)
-}
-
-fn write_template(path: &Path) -> io::Result<()> {
-    let template = r#"<!--
-    aria_move configuration (XML)
-
-    Boolean flags (true/false):
-        preserve_metadata      -> copy permissions + timestamps (+ xattrs when feature enabled)
-        preserve_permissions   -> copy only permissions (mode on Unix, readonly on Windows)
-
-    Other fields:
-        download_base          -> directory where new/partial downloads appear
-        completed_base         -> directory where completed items are moved
-        log_level              -> quiet | normal | info | debug
-        log_file               -> path to log file (optional; stdout/stderr still used)
-    recent_window_seconds  -> (removed) automatic recency window no longer configurable via XML
-
-    Notes:
-        - CLI flags override XML values.
-        - Setting preserve_metadata implies permissions; preserve_permissions is ignored if preserve_metadata=true.
-        - IMPORTANT: The default /path/to/incoming and /path/to/completed are placeholders. Edit them before running.
-            When running as root, aria_move will refuse to create these placeholder paths.
--->
-<config>
-    <download_base>/path/to/incoming</download_base>
-    <completed_base>/path/to/completed</completed_base>
-
-    <log_level>normal</log_level>
-    <log_file></log_file>
-
-    <preserve_metadata>false</preserve_metadata>
-    <preserve_permissions>false</preserve_permissions>
-</config>
-"#;
-
-    let mut f = fs::File::create(path)?;
-    f.write_all(template.as_bytes())?;
-    f.sync_all()?;
-    Ok(())
-}
+pub use crate::fsys::path_has_symlink_ancestor;
 
-/// Ensure path exists as a directory, reject symlink ancestors (Unix), and enforce safe perms.
-fn ensure_safe_dir(dir: &Path) -> Result<()> {
+/// Ensure path exists as a directory and reject symlink / junction ancestors. A missing
+/// directory is created with mode `create`; with `None` (read-only validation) it is
+/// accepted as is and only its ancestors are checked.
+fn ensure_safe_dir(dir: &Path, create: Option<u32>) -> Result<()> {
     if !dir.exists() {
+        let Some(mode) = create else {
+            if path_has_symlink_ancestor(dir)? {
+                return Err(anyhow!(
+                    "refusing directory under a symlinked ancestor: {}",
+                    dir.display()
+                ));
+            }
+            return Ok(());
+        };
         // Avoid creating placeholder default paths when user left defaults unchanged and running as root.
         // The built-in template uses '/path/to/incoming' and '/path/to/completed' as sentinel placeholders.
         // If those are still present verbatim and we're root, refuse creation to force explicit configuration.
@@ -139,7 +165,7 @@ fn ensure_safe_dir(dir: &Path) -> Result<()> {
             let is_root = unsafe { libc::geteuid() } == 0;
             let display_str = dir.display().to_string();
             if is_root
-                && (display_str == "/path/to/incoming" || display_str == "/path/to/completed")
+                && (display_str == TEMPLATE_DOWNLOAD_BASE || display_str == TEMPLATE_COMPLETED_BASE)
             {
                 return Err(anyhow!(
                     "Refusing to create placeholder default path '{}' as root; edit config.xml to real paths.",
@@ -147,21 +173,21 @@ fn ensure_safe_dir(dir: &Path) -> Result<()> {
                 ));
             }
         }
-        create_secure_dir_all(dir)
+        create_dir_all_with_mode(dir, mode)
             .with_context(|| format!("create directory '{}'", dir.display()))?;
     } else if !dir.is_dir() {
         return Err(anyhow!("'{}' exists but is not a directory", dir.display()));
     }
 
-    // Reject symlink ancestors (Unix)
+    // Reject symlink / junction ancestors
+    if crate::fsys::is_symlink(dir) || path_has_symlink_ancestor(dir)? {
+        return Err(anyhow!(
+            "refusing directory under a symlinked ancestor: {}",
+            dir.display()
+        ));
+    }
     #[cfg(unix)]
     {
-        if has_symlink_ancestor(dir)? {
-            return Err(anyhow!(
-                "refusing directory under a symlinked ancestor: {}",
-                dir.display()
-            ));
-        }
         // Previously we rejected group/world writable modes (mode & 0o022 != 0) to enforce
         // strict ownership. This was deemed out of scope for aria_move (environment policy).
         // We now allow broader permissions and leave hardening to deployment choices.
@@ -171,57 +197,56 @@ fn ensure_safe_dir(dir: &Path) -> Result<()> {
     Ok(())
 }
 
-fn create_secure_dir_all(dir: &Path) -> io::Result<()> {
-    fs::create_dir_all(dir)?;
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let perms = fs::Permissions::from_mode(0o700);
-        fs::set_permissions(dir, perms)?;
+/// `create_dir_all` that sets `mode` (Unix, independent of the umask) on each directory it
+/// creates; directories that already existed keep their mode.
+fn create_dir_all_with_mode(dir: &Path, mode: u32) -> io::Result<()> {
+    let missing: Vec<&Path> = dir
+        .ancestors()
+        .take_while(|p| !p.as_os_str().is_empty() && fs::symlink_metadata(p).is_err())
+        .collect();
+    for path in missing.into_iter().rev() {
+        match fs::create_dir(path) {
+            Ok(()) => {}
+            // Created concurrently by someone else: not ours to chmod.
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
+        }
     }
+    #[cfg(not(unix))]
+    let _ = mode;
     Ok(())
 }
 
+/// Canonicalize `path`; a path that does not exist (read-only validation) keeps its missing
+/// tail appended to its canonical closest existing ancestor.
 fn canonicalize_best_effort(path: &Path) -> Result<PathBuf> {
+    if fs::symlink_metadata(path).is_err()
+        && let Ok(ancestor) = crate::fsys::existing_ancestor(path)
+    {
+        // A relative path whose first component is missing has "." as its ancestor.
+        let rest = path.strip_prefix(ancestor).unwrap_or(path);
+        return Ok(canonicalize_best_effort(ancestor)?.join(rest));
+    }
     match dunce::canonicalize(path) {
         Ok(p) => Ok(p),
         Err(e) => Err(anyhow!("canonicalize {} failed: {e}", path.display())),
     }
 }
 
-#[cfg(unix)]
-fn has_symlink_ancestor(path: &Path) -> io::Result<bool> {
-    use std::fs::symlink_metadata;
-    // Build up from root, lstat each part; treat non-existent parts as safe.
-    let mut cur = PathBuf::new();
-    for comp in path.components() {
-        match comp {
-            Component::CurDir => continue,
-            Component::ParentDir => cur.push(".."),
-            Component::RootDir | Component::Prefix(_) => cur.push(comp),
-            Component::Normal(p) => {
-                cur.push(p);
-                if cur.exists() {
-                    let meta = symlink_metadata(&cur)?;
-                    if meta.file_type().is_symlink() {
-                        return Ok(true);
-                    }
-                }
-            }
-        }
-    }
-    Ok(false)
-}
-
-#[cfg(windows)]
-fn has_symlink_ancestor(_path: &Path) -> io::Result<bool> {
-    // Not enforced on Windows in this build.
-    Ok(false)
-}
-
 /// Default download base when no config or CLI override is provided.
 /// Historically some users used `/mnt/World` on specific systems; adjust via config or CLI.
 pub const DOWNLOAD_BASE_DEFAULT: &str = "/mnt/World";
 
 /// Default completed base directory used when no config or CLI override is provided.
 pub const COMPLETED_BASE_DEFAULT: &str = "/mnt/World/completed";
+
+/// Placeholder download_base written into a new config template (never created as root).
+pub const TEMPLATE_DOWNLOAD_BASE: &str = "/path/to/incoming";
+
+/// Placeholder completed_base written into a new config template (never created as root).
+pub const TEMPLATE_COMPLETED_BASE: &str = "/path/to/completed";
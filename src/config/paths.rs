@@ -1,7 +1,8 @@
 //! Default path helpers and symlink checks.
 //! - Determines OS-appropriate config/log paths (with `ARIA_MOVE_CONFIG` override for config).
 //! - Keeps config and log file colocated (same directory) for easier discovery.
-//! - Detects symlinked ancestors for safety (avoid writing logs under a symlinked parent).
+//! - `default_bench_path`: where `bench-dest` stores copy-engine measurements (data dir).
+//! - Re-exports the symlinked-ancestor check from `fsys` (avoid writing logs under a symlinked parent).
 //!
 //! Notes:
 //! - These functions only compute paths; they do not create directories/files. Callers must
@@ -23,9 +24,9 @@
 
 use anyhow::{Context, Result, anyhow};
 use dirs::{config_dir, data_dir};
-use std::fs;
-use std::io;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
+
+pub use crate::fsys::path_has_symlink_ancestor;
 
 /// Build "<base>/aria_move/<filename>".
 fn app_path(mut base: PathBuf, filename: &str) -> PathBuf {
@@ -100,12 +101,11 @@ pub fn default_config_path() -> Result<PathBuf> {
 /// If that is unavailable, falls back to $HOME/.local/share/aria_move/aria_move.log.
 pub fn default_log_path() -> Result<PathBuf> {
     // 1) Colocate with config, unless the parent is a system directory like /etc (not writable).
-    if let Ok(cfg_path) = default_config_path() {
-        if let Some(parent) = cfg_path.parent() {
-            if !(cfg!(unix) && parent.starts_with("/etc")) {
-                return Ok(parent.join("aria_move.log"));
-            }
-        }
+    if let Ok(cfg_path) = default_config_path()
+        && let Some(parent) = cfg_path.parent()
+        && !(cfg!(unix) && parent.starts_with("/etc"))
+    {
+        return Ok(parent.join("aria_move.log"));
     }
 
     // 2) data_dir fallback
@@ -132,18 +132,19 @@ pub fn default_log_path() -> Result<PathBuf> {
         .join("aria_move.log"))
 }
 
-/// Return true if any existing ancestor of `path` is a symlink.
-/// Non-existent ancestors are skipped safely.
-pub fn path_has_symlink_ancestor(path: &Path) -> io::Result<bool> {
-    let mut cur = path.parent();
-    while let Some(dir) = cur {
-        if dir.exists() {
-            let meta = fs::symlink_metadata(dir)?;
-            if meta.file_type().is_symlink() {
-                return Ok(true);
-            }
-        }
-        cur = dir.parent();
+/// Return where `bench-dest` stores its measurements: the platform data dir
+/// (`.../aria_move/bench.json`), else the same HOME fallback as the log file.
+pub fn default_bench_path() -> Result<PathBuf> {
+    if let Some(base) = data_dir() {
+        return Ok(app_path(base, "bench.json"));
     }
-    Ok(false)
+    let home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .ok_or_else(|| anyhow!("HOME/USERPROFILE not set for bench file fallback"))?;
+    let base = if cfg!(windows) {
+        PathBuf::from(home).join("AppData").join("Local")
+    } else {
+        PathBuf::from(home).join(".local").join("share")
+    };
+    Ok(app_path(base, "bench.json"))
 }
@@ -1,19 +1,34 @@
 //! Core configuration types.
 //! - Config holds runtime settings with sensible defaults.
-//! - LogLevel represents verbosity with simple parsing helpers.
+//! - LogLevel represents verbosity; `FromStr`/`Display` are its canonical string forms.
+//! - CopyOrder selects the file ordering used by the directory copy fallback.
+//! - CopyBackend selects how file data is copied when a rename is not possible.
+//! - ResumeVerify selects how much of an interrupted temp is checked before it is resumed.
+//! - FsyncPolicy selects which fsyncs file and directory moves perform.
+//! - LockStrategy selects the advisory locking mechanism (flock, lockfile, none).
+//! - PartialPreset names the incomplete-file suffixes of a download client.
+//! - ConsoleStream selects where console logs go.
+//! - LogTimeFormat selects how log timestamps are written.
+//! - ByteSize parses sizes such as `4096`, `500MiB` or `1.5GB` (config and CLI).
+//! - HumanDuration parses durations such as `300`, `90s`, `5m` or `1h30m` (config and CLI).
 
 use std::fmt;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
 use super::paths;
 use super::{COMPLETED_BASE_DEFAULT, DOWNLOAD_BASE_DEFAULT};
+use crate::fs_ops::{Handler, OnDuplicate, OnFull, OnRedownload};
+use crate::notify::Notifier;
 
 /// Program-defined verbosity levels exposed to users/config.
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum LogLevel {
     /// Only errors
     Quiet,
+    /// Warnings and errors, without informational output
+    Warn,
     /// Informational output (default)
     #[default]
     Normal,
@@ -24,15 +39,19 @@ pub enum LogLevel {
 }
 
 impl LogLevel {
-    /// Parse common string names into our LogLevel (case-insensitive).
+    /// Every level, least verbose first.
+    pub const ALL: [LogLevel; 5] = [
+        LogLevel::Quiet,
+        LogLevel::Warn,
+        LogLevel::Normal,
+        LogLevel::Info,
+        LogLevel::Debug,
+    ];
+
+    /// Parse common string names into our LogLevel (case-insensitive); `FromStr` without
+    /// the error message.
     pub fn parse(s: &str) -> Option<Self> {
-        match s.to_ascii_lowercase().as_str() {
-            "quiet" | "error" | "none" => Some(LogLevel::Quiet),
-            "normal" => Some(LogLevel::Normal),
-            "info" | "verbose" | "detailed" => Some(LogLevel::Info),
-            "debug" | "trace" => Some(LogLevel::Debug),
-            _ => None,
-        }
+        s.parse().ok()
     }
 }
 
@@ -40,6 +59,7 @@ impl fmt::Display for LogLevel {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let s = match self {
             LogLevel::Quiet => "quiet",
+            LogLevel::Warn => "warn",
             LogLevel::Normal => "normal",
             LogLevel::Info => "info",
             LogLevel::Debug => "debug",
@@ -49,13 +69,801 @@ impl fmt::Display for LogLevel {
 }
 
 impl FromStr for LogLevel {
+    type Err = String;
+    /// The `Display` names plus common aliases (case-insensitive, surrounding spaces ignored).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "quiet" | "error" | "none" => Ok(LogLevel::Quiet),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "normal" => Ok(LogLevel::Normal),
+            "info" | "verbose" | "detailed" => Ok(LogLevel::Info),
+            "debug" | "trace" => Ok(LogLevel::Debug),
+            _ => Err(format!(
+                "invalid log level: '{s}' (expected quiet, warn, normal, info or debug)"
+            )),
+        }
+    }
+}
+
+/// Where human-readable console output (logs and status lines) goes (`console_stream`).
+/// Machine-readable output (`print_user`, JSON reports) always stays on stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConsoleStream {
+    /// Logs and info lines on stdout, warnings and errors on stderr (default)
+    #[default]
+    Stdout,
+    /// Everything human-readable on stderr (aria2 hooks, pipelines reading stdout)
+    Stderr,
+    /// No console logs or status lines; errors are still printed to stderr
+    None,
+}
+
+impl ConsoleStream {
+    /// Parse `stdout`, `stderr` or `none` (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "stdout" => Some(ConsoleStream::Stdout),
+            "stderr" => Some(ConsoleStream::Stderr),
+            "none" | "off" => Some(ConsoleStream::None),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ConsoleStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ConsoleStream::Stdout => "stdout",
+            ConsoleStream::Stderr => "stderr",
+            ConsoleStream::None => "none",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for ConsoleStream {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("invalid console stream: '{s}'"))
+    }
+}
+
+/// Timestamp format of log lines (`log_time_format`).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum LogTimeFormat {
+    /// RFC 3339 with milliseconds, e.g. `2024-05-01T14:03:07.123+02:00` (default)
+    #[default]
+    Rfc3339,
+    /// The historical `DD/MM/YY HH:MM:SS`
+    Legacy,
+    /// A chrono `strftime` format such as `%Y-%m-%d %H:%M:%S`
+    Custom(String),
+}
+
+impl LogTimeFormat {
+    /// `rfc3339` (or `iso8601`), `legacy`, or a valid `strftime` format containing `%`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let t = s.trim();
+        match t.to_ascii_lowercase().as_str() {
+            "rfc3339" | "rfc-3339" | "iso8601" | "iso-8601" => Some(LogTimeFormat::Rfc3339),
+            "legacy" => Some(LogTimeFormat::Legacy),
+            _ if t.contains('%')
+                && !chrono::format::StrftimeItems::new(t)
+                    .any(|item| item == chrono::format::Item::Error) =>
+            {
+                Some(LogTimeFormat::Custom(t.to_string()))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for LogTimeFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogTimeFormat::Rfc3339 => f.write_str("rfc3339"),
+            LogTimeFormat::Legacy => f.write_str("legacy"),
+            LogTimeFormat::Custom(format) => f.write_str(format),
+        }
+    }
+}
+
+impl FromStr for LogTimeFormat {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| {
+            format!(
+                "invalid log time format: '{s}' (expected rfc3339, legacy or a strftime format)"
+            )
+        })
+    }
+}
+
+/// Download client whose incomplete-file suffixes mark a file as partial
+/// (`partial_extensions_preset`). The default, aria2 plus chrome, is the historical list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartialPreset {
+    /// `.aria2`, `.part`, `.tmp`
+    Aria2,
+    /// `.!qB`
+    Qbittorrent,
+    /// `.part`
+    Deluge,
+    /// `.crdownload`
+    Chrome,
+    /// `.!ut`, `.ut`
+    Utorrent,
+    /// `.bc!`
+    Bitcomet,
+}
+
+impl PartialPreset {
+    /// Every preset, as selected by `all`.
+    pub const ALL: [PartialPreset; 6] = [
+        PartialPreset::Aria2,
+        PartialPreset::Qbittorrent,
+        PartialPreset::Deluge,
+        PartialPreset::Chrome,
+        PartialPreset::Utorrent,
+        PartialPreset::Bitcomet,
+    ];
+
+    /// The presets used when none are configured.
+    pub fn defaults() -> Vec<PartialPreset> {
+        vec![PartialPreset::Aria2, PartialPreset::Chrome]
+    }
+
+    /// Parse common string names into a PartialPreset (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "aria2" => Some(PartialPreset::Aria2),
+            "qbittorrent" | "qbit" => Some(PartialPreset::Qbittorrent),
+            "deluge" => Some(PartialPreset::Deluge),
+            "chrome" | "chromium" => Some(PartialPreset::Chrome),
+            "utorrent" => Some(PartialPreset::Utorrent),
+            "bitcomet" => Some(PartialPreset::Bitcomet),
+            _ => None,
+        }
+    }
+
+    /// Suffixes (without the dot, lowercase) this client gives incomplete files.
+    pub fn suffixes(self) -> &'static [&'static str] {
+        match self {
+            PartialPreset::Aria2 => &["aria2", "part", "tmp"],
+            PartialPreset::Qbittorrent => &["!qb"],
+            PartialPreset::Deluge => &["part"],
+            PartialPreset::Chrome => &["crdownload"],
+            PartialPreset::Utorrent => &["!ut", "ut"],
+            PartialPreset::Bitcomet => &["bc!"],
+        }
+    }
+}
+
+impl fmt::Display for PartialPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            PartialPreset::Aria2 => "aria2",
+            PartialPreset::Qbittorrent => "qbittorrent",
+            PartialPreset::Deluge => "deluge",
+            PartialPreset::Chrome => "chrome",
+            PartialPreset::Utorrent => "utorrent",
+            PartialPreset::Bitcomet => "bitcomet",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for PartialPreset {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("invalid partial extensions preset: '{s}'"))
+    }
+}
+
+/// Order in which files are scheduled during a directory copy (cross-filesystem fallback).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CopyOrder {
+    /// Directory walk order (default)
+    #[default]
+    Walk,
+    /// Biggest files first: fail fast when space is tight
+    LargestFirst,
+    /// Smallest files first: finish many small files quickly
+    SmallestFirst,
+}
+
+impl CopyOrder {
+    /// Parse common string names into a CopyOrder (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "walk" | "default" => Some(CopyOrder::Walk),
+            "largest-first" | "largest_first" | "largest" => Some(CopyOrder::LargestFirst),
+            "smallest-first" | "smallest_first" | "smallest" => Some(CopyOrder::SmallestFirst),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CopyOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            CopyOrder::Walk => "walk",
+            CopyOrder::LargestFirst => "largest-first",
+            CopyOrder::SmallestFirst => "smallest-first",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for CopyOrder {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("invalid copy order: '{s}'"))
+    }
+}
+
+/// How file data is copied when a rename is not possible (cross-filesystem).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CopyBackend {
+    /// In-process streaming copy with append-only resume (default)
+    #[default]
+    Builtin,
+    /// External `rsync` delta transfer: rewrites only changed blocks of an interrupted temp
+    Rsync,
+}
+
+impl CopyBackend {
+    /// Parse common string names into a CopyBackend (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "builtin" | "internal" | "default" => Some(CopyBackend::Builtin),
+            "rsync" | "delta" => Some(CopyBackend::Rsync),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CopyBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            CopyBackend::Builtin => "builtin",
+            CopyBackend::Rsync => "rsync",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for CopyBackend {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("invalid copy backend: '{s}'"))
+    }
+}
+
+/// How an interrupted temp file is checked against the source before the builtin copy resumes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResumeVerify {
+    /// Compare the last 4 MiB of the temp with the source (default)
+    #[default]
+    Tail,
+    /// Compare the whole temp with the source (reads the copied prefix twice)
+    Full,
+    /// Trust the temp and append to it
+    None,
+}
+
+impl ResumeVerify {
+    /// Parse common string names into a ResumeVerify (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "tail" | "default" => Some(ResumeVerify::Tail),
+            "full" => Some(ResumeVerify::Full),
+            "none" | "off" => Some(ResumeVerify::None),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ResumeVerify {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ResumeVerify::Tail => "tail",
+            ResumeVerify::Full => "full",
+            ResumeVerify::None => "none",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for ResumeVerify {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("invalid resume verification: '{s}'"))
+    }
+}
+
+/// Which fsyncs moves perform (shared by file and directory moves).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FsyncPolicy {
+    /// Sync copied file data and the directories holding renames and deletions (default).
+    /// Windows has no directory fsync; renamed files are flushed with FlushFileBuffers instead.
+    #[default]
+    Full,
+    /// Sync copied file data only; a rename may be lost on power failure, never half a file
+    Data,
+    /// No fsync at all; data reaches the disk whenever the OS writes it back
+    None,
+}
+
+impl FsyncPolicy {
+    /// Parse common string names into an FsyncPolicy (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "full" | "default" => Some(FsyncPolicy::Full),
+            "data" | "files" => Some(FsyncPolicy::Data),
+            "none" | "off" => Some(FsyncPolicy::None),
+            _ => None,
+        }
+    }
+
+    /// True if copied file data is synced before it is renamed into place.
+    pub fn syncs_files(self) -> bool {
+        self != FsyncPolicy::None
+    }
+
+    /// True if directories are synced after renames and deletions.
+    pub fn syncs_dirs(self) -> bool {
+        self == FsyncPolicy::Full
+    }
+}
+
+impl fmt::Display for FsyncPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            FsyncPolicy::Full => "full",
+            FsyncPolicy::Data => "data",
+            FsyncPolicy::None => "none",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for FsyncPolicy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("invalid fsync policy: '{s}'"))
+    }
+}
+
+/// What aria_move asks aria2 to do with the finished task before moving it (`rpc_action`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RpcAction {
+    /// Leave the task alone (default)
+    #[default]
+    None,
+    /// Pause it, then point it at the moved data and unpause it (seeding continues)
+    Pause,
+    /// Remove it from aria2; it is not added back
+    Remove,
+}
+
+impl RpcAction {
+    /// Parse common string names into an RpcAction (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" | "off" => Some(RpcAction::None),
+            "pause" => Some(RpcAction::Pause),
+            "remove" | "stop" => Some(RpcAction::Remove),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RpcAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            RpcAction::None => "none",
+            RpcAction::Pause => "pause",
+            RpcAction::Remove => "remove",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for RpcAction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("invalid rpc action: '{s}'"))
+    }
+}
+
+/// Advisory locking mechanism for source and destination directories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LockStrategy {
+    /// Lockfile on network filesystems (NFS/SMB), flock elsewhere (default)
+    #[default]
+    Auto,
+    /// flock(2) on the directory (Windows: exclusive sidecar file)
+    Flock,
+    /// O_EXCL lockfile with owner pid/hostname and TTL-based expiry
+    Lockfile,
+    /// No locking
+    None,
+}
+
+impl LockStrategy {
+    /// Parse common string names into a LockStrategy (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" | "default" => Some(LockStrategy::Auto),
+            "flock" => Some(LockStrategy::Flock),
+            "lockfile" | "lock-file" | "lock_file" => Some(LockStrategy::Lockfile),
+            "none" | "off" | "disabled" => Some(LockStrategy::None),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for LockStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            LockStrategy::Auto => "auto",
+            LockStrategy::Flock => "flock",
+            LockStrategy::Lockfile => "lockfile",
+            LockStrategy::None => "none",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for LockStrategy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("invalid lock strategy: '{s}'"))
+    }
+}
+
+/// How a source path that is itself a symlink is handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymlinkPolicy {
+    /// Refuse to move symlinks (default)
+    #[default]
+    Error,
+    /// Move the link itself; the target is left untouched
+    MoveLink,
+    /// Move the file or directory the link points to, then remove the link
+    MoveTarget,
+}
+
+impl SymlinkPolicy {
+    /// Parse common string names into a SymlinkPolicy (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" | "reject" => Some(SymlinkPolicy::Error),
+            "move-link" | "move_link" | "link" => Some(SymlinkPolicy::MoveLink),
+            "move-target" | "move_target" | "target" => Some(SymlinkPolicy::MoveTarget),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SymlinkPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            SymlinkPolicy::Error => "error",
+            SymlinkPolicy::MoveLink => "move-link",
+            SymlinkPolicy::MoveTarget => "move-target",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for SymlinkPolicy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("invalid symlink policy: '{s}'"))
+    }
+}
+
+/// What to do when `completed_base/<name>` is already a symlink (dangling or not).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DestSymlinkPolicy {
+    /// Refuse the move (default)
+    #[default]
+    Error,
+    /// Remove the link (never its target) and move into its place
+    Replace,
+}
+
+impl DestSymlinkPolicy {
+    /// Parse common string names into a DestSymlinkPolicy (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" | "reject" => Some(DestSymlinkPolicy::Error),
+            "replace" | "overwrite" => Some(DestSymlinkPolicy::Replace),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DestSymlinkPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            DestSymlinkPolicy::Error => "error",
+            DestSymlinkPolicy::Replace => "replace",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for DestSymlinkPolicy {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("invalid destination symlink policy: '{s}'"))
+    }
+}
+
+/// Default for `Config::recent_window` (seconds). A window of 0 is unbounded (no age check).
+pub const RECENT_WINDOW_SECS_DEFAULT: u64 = 300;
+
+/// Default for `Config::on_full_max_wait` (seconds).
+pub const ON_FULL_MAX_WAIT_SECS_DEFAULT: u64 = 3600;
+
+/// Default `created_dir_mode`: base directories aria_move creates are private to its user.
+pub const CREATED_DIR_MODE_DEFAULT: u32 = 0o700;
+
+/// Parse an octal directory mode such as `0755`, `755` or `0o2775` (at most `07777`).
+pub fn parse_dir_mode(s: &str) -> Result<u32, String> {
+    let t = s.trim();
+    let digits = t.strip_prefix("0o").unwrap_or(t);
+    match u32::from_str_radix(digits, 8) {
+        Ok(mode) if !digits.is_empty() && mode <= 0o7777 => Ok(mode),
+        _ => Err(format!(
+            "invalid directory mode: '{s}' (expected octal, e.g. 0755)"
+        )),
+    }
+}
+
+/// Default `space_cushion`: headroom required beyond an item's size before copying it.
+pub const SPACE_CUSHION_DEFAULT: ByteSize = ByteSize(4 * 1024 * 1024);
+
+/// aria2's default JSON-RPC endpoint (`--rpc-listen-port=6800`).
+pub const ARIA2_RPC_URL_DEFAULT: &str = "http://localhost:6800/jsonrpc";
+
+/// A size in bytes, written as plain bytes (`4096`) or with a decimal (`kB`, `MB`, `GB`,
+/// `TB`) or binary (`KiB`, `MiB`, `GiB`, `TiB`; `K`, `M`, `G`, `T` are binary too) unit.
+/// Fractions are allowed with a unit (`1.5GB`) and rounded down to whole bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+pub struct ByteSize(pub u64);
+
+impl ByteSize {
+    /// The size in bytes.
+    pub fn bytes(self) -> u64 {
+        self.0
+    }
+
+    /// Parse a size string (case-insensitive units, optional space before the unit).
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let invalid =
+            || format!("invalid size: '{s}' (expected bytes or a size such as 500MiB or 1.5GB)");
+        let t = s.trim();
+        let split = t
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(t.len());
+        let (number, unit) = t.split_at(split);
+        let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+            "" | "b" => 1,
+            "kb" => 1000,
+            "mb" => 1000_u64.pow(2),
+            "gb" => 1000_u64.pow(3),
+            "tb" => 1000_u64.pow(4),
+            "k" | "kib" => 1 << 10,
+            "m" | "mib" => 1 << 20,
+            "g" | "gib" => 1 << 30,
+            "t" | "tib" => 1 << 40,
+            _ => return Err(invalid()),
+        };
+        if number.is_empty() {
+            return Err(invalid());
+        }
+        if let Ok(whole) = number.parse::<u64>() {
+            return whole
+                .checked_mul(multiplier)
+                .map(ByteSize)
+                .ok_or_else(invalid);
+        }
+        // A fraction needs a unit; "1.5" or "1.5B" is not a whole number of bytes.
+        if multiplier == 1 {
+            return Err(invalid());
+        }
+        let value: f64 = number.parse().map_err(|_| invalid())?;
+        let bytes = value * multiplier as f64;
+        if !bytes.is_finite() || bytes >= u64::MAX as f64 {
+            return Err(invalid());
+        }
+        Ok(ByteSize(bytes as u64))
+    }
+}
+
+impl fmt::Display for ByteSize {
+    /// The largest binary unit that represents the size exactly (`4MiB`), else bytes.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (unit, shift) in [("TiB", 40), ("GiB", 30), ("MiB", 20), ("KiB", 10)] {
+            if self.0 != 0 && self.0.trailing_zeros() >= shift {
+                return write!(f, "{}{unit}", self.0 >> shift);
+            }
+        }
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for ByteSize {
     type Err = String;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Self::parse(s).ok_or_else(|| format!("invalid log level: '{s}'"))
+        Self::parse(s)
+    }
+}
+
+impl serde::Serialize for ByteSize {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ByteSize {
+    /// A size string, or a plain integer number of bytes.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = ByteSize;
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a size such as 4096, 500MiB or 1.5GB")
+            }
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<ByteSize, E> {
+                Ok(ByteSize(v))
+            }
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<ByteSize, E> {
+                ByteSize::parse(v).map_err(E::custom)
+            }
+        }
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+/// A duration written as plain seconds (`300`) or as unit terms such as `90s`, `5m`, `2h`,
+/// `1d` or `1h30m` (`ms`, `s`, `m`, `h`, `d`, also spelled out: `sec`, `min`, `hours`, ...).
+/// Each term may carry a fraction (`1.5h`); sub-millisecond remainders are dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+pub struct HumanDuration(pub Duration);
+
+impl HumanDuration {
+    /// Parse a duration string (case-insensitive units, optional spaces between terms).
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let invalid = || {
+            format!(
+                "invalid duration: '{s}' (expected seconds or a duration such as 90s, 5m or 1h30m)"
+            )
+        };
+        let t = s.trim();
+        if t.is_empty() {
+            return Err(invalid());
+        }
+        if let Ok(secs) = t.parse::<u64>() {
+            return Ok(HumanDuration(Duration::from_secs(secs)));
+        }
+        let mut total_ms: u128 = 0;
+        let mut rest = t;
+        while !rest.is_empty() {
+            let n = rest
+                .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                .unwrap_or(rest.len());
+            let (number, tail) = rest.split_at(n);
+            let tail = tail.trim_start();
+            let u = tail
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(tail.len());
+            let (unit, tail) = tail.split_at(u);
+            let unit_ms: u128 = match unit.to_ascii_lowercase().as_str() {
+                "ms" | "msec" | "millis" => 1,
+                "s" | "sec" | "secs" | "second" | "seconds" => 1000,
+                "m" | "min" | "mins" | "minute" | "minutes" => 60_000,
+                "h" | "hr" | "hrs" | "hour" | "hours" => 3_600_000,
+                "d" | "day" | "days" => 86_400_000,
+                _ => return Err(invalid()),
+            };
+            if number.is_empty() {
+                return Err(invalid());
+            }
+            let term_ms = match number.parse::<u128>() {
+                Ok(whole) => whole.checked_mul(unit_ms).ok_or_else(invalid)?,
+                Err(_) => {
+                    let value: f64 = number.parse().map_err(|_| invalid())?;
+                    let ms = value * unit_ms as f64;
+                    if !ms.is_finite() || ms >= u64::MAX as f64 {
+                        return Err(invalid());
+                    }
+                    ms as u128
+                }
+            };
+            total_ms = total_ms.checked_add(term_ms).ok_or_else(invalid)?;
+            rest = tail.trim_start();
+        }
+        let ms = u64::try_from(total_ms).map_err(|_| invalid())?;
+        Ok(HumanDuration(Duration::from_millis(ms)))
+    }
+
+    /// The duration.
+    pub fn get(self) -> Duration {
+        self.0
+    }
+}
+
+impl fmt::Display for HumanDuration {
+    /// Compound units, largest first (`1h30m`, `90ms`); zero is `0s`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut ms = self.0.as_millis();
+        if ms == 0 {
+            return f.write_str("0s");
+        }
+        for (unit, size) in [
+            ("d", 86_400_000),
+            ("h", 3_600_000),
+            ("m", 60_000),
+            ("s", 1000),
+            ("ms", 1),
+        ] {
+            if ms >= size {
+                write!(f, "{}{unit}", ms / size)?;
+                ms %= size;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for HumanDuration {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl From<HumanDuration> for Duration {
+    fn from(d: HumanDuration) -> Self {
+        d.0
+    }
+}
+
+impl serde::Serialize for HumanDuration {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for HumanDuration {
+    /// A duration string, or a plain integer number of seconds.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = HumanDuration;
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a duration such as 300, 90s, 5m or 1h30m")
+            }
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<HumanDuration, E> {
+                Ok(HumanDuration(Duration::from_secs(v)))
+            }
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<HumanDuration, E> {
+                HumanDuration::parse(v).map_err(E::custom)
+            }
+        }
+        deserializer.deserialize_any(Visitor)
     }
 }
 
 /// Runtime configuration used by the mover.
+///
+/// Prefer `Config::builder()`, which validates on `build()`. The fields stay public for
+/// backward compatibility but may become private in a future major release.
 #[derive(Debug, Clone)]
 pub struct Config {
     /// Where partial/new downloads appear
@@ -64,19 +872,137 @@ pub struct Config {
     pub completed_base: PathBuf,
     /// Console verbosity
     pub log_level: LogLevel,
+    /// Extra `EnvFilter` directives on top of `log_level`, e.g. `aria_move::fs_ops=debug`
+    pub log_filter: Option<String>,
+    /// If true, a non-empty `RUST_LOG` replaces `log_level` and `log_filter`
+    pub honor_rust_log: bool,
+    /// If true, each low-level filesystem operation (open, rename, fsync, unlink) is logged at
+    /// debug level with its path, duration and errno
+    pub trace_fs_ops: bool,
+    /// Timestamp format of log lines (console and file)
+    pub log_time_format: LogTimeFormat,
+    /// If true, log timestamps are in UTC instead of local time
+    pub log_utc: bool,
     /// Optional path to a log file
     pub log_file: Option<PathBuf>,
+    /// Stream for console logs and status lines (stdout, stderr or none)
+    pub console_stream: ConsoleStream,
     /// If true, print actions but do not modify the filesystem
     pub dry_run: bool,
     /// If true, preserve permissions and timestamps
     pub preserve_metadata: bool,
     /// If true, preserve only permissions (mode / readonly). Ignored if preserve_metadata is true.
     pub preserve_permissions: bool,
+    /// Unix: mode of directories created under completed_base, set explicitly so the umask
+    /// does not matter. None: the matching source directory's mode when preserving
+    /// metadata or permissions, else whatever the umask gives.
+    pub dest_dir_mode: Option<u32>,
+    /// Unix: mode of the base directories (download_base, completed_base, overflow bases)
+    /// that validation creates when they are missing. Existing directories are never changed.
+    pub created_dir_mode: u32,
+    /// Linux: copy POSIX ACLs onto moved items and let created directories keep the ACL
+    /// inherited from their parent's default ACL
+    pub preserve_acls: bool,
     /// If true, disable directory locking (for ZFS/NFS/network shares in containers)
     pub disable_locks: bool,
+    /// Locking mechanism: auto (detect network filesystems), flock, lockfile, none
+    pub lock_strategy: LockStrategy,
+    /// Scheduling order for files when a directory move falls back to copying
+    pub copy_order: CopyOrder,
+    /// How file data is copied when a rename is not possible (builtin or rsync)
+    pub copy_backend: CopyBackend,
+    /// Copy engine by registered name (builtin, reflink, kernel, streaming, rsync, or one a
+    /// library user registered); overrides `copy_backend` when set
+    pub copy_engine: Option<String>,
+    /// How an interrupted temp is checked before the builtin copy resumes it (tail, full, none)
+    pub resume_verify: ResumeVerify,
+    /// If true, directory copies move what they can and leave problem files in place
+    /// instead of aborting and rolling back the whole directory
+    pub partial_dir_moves: bool,
+    /// If true, copy into completed_base and keep the source (no rename, no removal)
+    pub copy_only: bool,
+    /// If true, a download aria2 still seeds (asked over `aria2_rpc_url` by GID) is handled
+    /// as with `copy_only`, since aria2 keeps its files open
+    pub bt_mode: bool,
+    /// aria2 JSON-RPC endpoint (`bt_mode`)
+    pub aria2_rpc_url: String,
+    /// aria2 `rpc-secret`, sent as `token:<secret>`
+    pub aria2_rpc_secret: Option<String>,
+    /// What to ask aria2 to do with the task (by GID) before moving it (none, pause, remove)
+    pub rpc_action: RpcAction,
+    /// If true, never rename: always copy, then remove the source (for FUSE/overlay
+    /// filesystems whose rename misbehaves)
+    pub force_copy: bool,
+    /// If true, refuse to move files whose tail is all zeros (a preallocated download that
+    /// is not fully written yet)
+    pub content_sanity_check: bool,
+    /// Linux: copy into an unnamed temp (O_TMPFILE) and link it in when complete, so no
+    /// `.aria_move.resume.*` name ever appears in completed_base (interrupted copies restart)
+    pub unnamed_temp: bool,
+    /// Which fsyncs moves perform: full (files and directories), data (files only), none
+    pub fsync_policy: FsyncPolicy,
+    /// What to do when the destination directory already exists (rename with suffix, or merge)
+    pub dir_on_duplicate: OnDuplicate,
+    /// Per-file collision policy applied inside a directory merge (file moves never use it)
+    pub merge_on_conflict: OnDuplicate,
+    /// What to do when the source path is a symlink (error, move-link, move-target)
+    pub symlink_source_policy: SymlinkPolicy,
+    /// How an existing symlink at `completed_base/<name>` is handled
+    pub dest_symlink_policy: DestSymlinkPolicy,
+    /// If true, a source must lie beneath download_base (and never inside completed_base)
+    pub require_source_in_base: bool,
+    /// What to do when a torrent (by infohash) that was moved before completes again
+    pub on_redownload: OnRedownload,
+    /// What to do when completed_base is full (fail, pause-and-retry, spill)
+    pub on_full: OnFull,
+    /// Longest total wait of `on_full = pause-and-retry`
+    pub on_full_max_wait: Duration,
+    /// Destinations tried in order by `on_full = spill`
+    pub overflow_bases: Vec<PathBuf>,
+    /// Items modified more recently than this are treated as possibly still in progress
+    /// (sweep, and directory auto-resolution with `resolve_directories`).
+    /// Zero means unbounded: items are never held back because of their age.
+    pub recent_window: Duration,
+    /// If true and no source path is given, auto-pick the most recent stable top-level directory
+    pub resolve_directories: bool,
+    /// If true, dot-files/dot-dirs are considered by resolve, sweep and reconcile
+    /// (internal `.aria_move.*` names are always skipped)
+    pub include_hidden: bool,
+    /// Download clients whose incomplete-file suffixes mark an item as partial (resolve,
+    /// sweep, mirror and directory copies skip or wait for it)
+    pub partial_extensions_preset: Vec<PartialPreset>,
+    /// Extra partial suffixes (without the dot, compared case-insensitively)
+    pub partial_extensions: Vec<String>,
+    /// rclone executable used for `rclone:remote:path` destinations
+    pub rclone_binary: PathBuf,
+    /// File rewritten after every successful run (checked by `aria_move healthcheck`)
+    pub heartbeat_file: Option<PathBuf>,
+    /// Hash-chained JSON-lines log of every move (checked by `aria_move audit verify`)
+    pub audit_log: Option<PathBuf>,
+    /// Directory that receives a JSON diagnostic bundle for every failed move (also logged)
+    pub diagnostics_dir: Option<PathBuf>,
+    /// Warn (log + `low-space` notification) when completed_base has less free space, in MiB
+    pub low_space_warn_mib: Option<u64>,
+    /// Free space required beyond an item's size before it is copied into completed_base
+    pub space_cushion: ByteSize,
+    /// When started as root, switch to this user (name or uid) after startup (Unix)
+    pub run_as_user: Option<String>,
+    /// When started as root, switch to this group (name or gid) after startup (Unix)
+    pub run_as_group: Option<String>,
+    /// How many items a sweep moves at once (1 = one after another; 0 is treated as 1)
+    pub max_concurrent_moves: usize,
+    /// Abort the whole run after this long (moves stop safely; None = no limit)
+    pub max_runtime: Option<Duration>,
+    /// Warn (log with phase timings + `slow-move` notification) when one move takes longer
+    pub slow_move_warning: Option<Duration>,
+    /// If true, write provenance xattrs (`user.aria_move.*`) on moved items (feature `xattrs`)
+    pub tag_destination: bool,
+    /// Per-extension handlers (verify-sha256, extract, delete, archive) applied around a move
+    pub handlers: Vec<Handler>,
+    /// Push notifications (ntfy, Gotify, Pushover) per event type
+    pub notifiers: Vec<Notifier>,
     // Single switch: when true, preserve all available metadata (times, perms, readonly, xattrs).
     // When false, preserve nothing.
-    // (auto-pick recency window removed; explicit source path required)
 }
 
 impl Default for Config {
@@ -86,12 +1012,63 @@ impl Default for Config {
             completed_base: PathBuf::from(COMPLETED_BASE_DEFAULT),
             log_level: LogLevel::Normal,
             // paths::default_log_path() returns Result<PathBuf>; store Some(path) on success.
+            log_filter: None,
+            honor_rust_log: false,
+            trace_fs_ops: false,
+            log_time_format: LogTimeFormat::Rfc3339,
+            log_utc: false,
             log_file: paths::default_log_path().ok(),
+            console_stream: ConsoleStream::Stdout,
             dry_run: false,
             preserve_metadata: false,
             preserve_permissions: false,
+            dest_dir_mode: None,
+            created_dir_mode: CREATED_DIR_MODE_DEFAULT,
+            preserve_acls: false,
             disable_locks: false,
-            // no auto-pick window
+            lock_strategy: LockStrategy::Auto,
+            copy_order: CopyOrder::Walk,
+            copy_backend: CopyBackend::Builtin,
+            copy_engine: None,
+            resume_verify: ResumeVerify::Tail,
+            partial_dir_moves: false,
+            copy_only: false,
+            bt_mode: false,
+            aria2_rpc_url: ARIA2_RPC_URL_DEFAULT.to_string(),
+            aria2_rpc_secret: None,
+            rpc_action: RpcAction::None,
+            force_copy: false,
+            content_sanity_check: false,
+            unnamed_temp: false,
+            fsync_policy: FsyncPolicy::Full,
+            dir_on_duplicate: OnDuplicate::RenameWithSuffix,
+            merge_on_conflict: OnDuplicate::RenameWithSuffix,
+            symlink_source_policy: SymlinkPolicy::Error,
+            dest_symlink_policy: DestSymlinkPolicy::Error,
+            require_source_in_base: true,
+            on_redownload: OnRedownload::KeepBoth,
+            on_full: OnFull::Fail,
+            on_full_max_wait: Duration::from_secs(ON_FULL_MAX_WAIT_SECS_DEFAULT),
+            overflow_bases: Vec::new(),
+            recent_window: Duration::from_secs(RECENT_WINDOW_SECS_DEFAULT),
+            resolve_directories: false,
+            include_hidden: false,
+            partial_extensions_preset: PartialPreset::defaults(),
+            partial_extensions: Vec::new(),
+            rclone_binary: PathBuf::from("rclone"),
+            heartbeat_file: None,
+            audit_log: None,
+            diagnostics_dir: None,
+            low_space_warn_mib: None,
+            space_cushion: SPACE_CUSHION_DEFAULT,
+            run_as_user: None,
+            run_as_group: None,
+            max_concurrent_moves: 1,
+            max_runtime: None,
+            slow_move_warning: None,
+            tag_destination: false,
+            handlers: Vec::new(),
+            notifiers: Vec::new(),
         }
     }
 }
@@ -105,4 +1082,17 @@ impl Config {
             ..Default::default()
         }
     }
+
+    /// True if `ext` (without the dot) is a partial suffix under the configured presets or
+    /// the custom `partial_extensions` (case-insensitive).
+    pub fn is_partial_extension(&self, ext: &str) -> bool {
+        let ext = ext.to_ascii_lowercase();
+        self.partial_extensions_preset
+            .iter()
+            .any(|p| p.suffixes().contains(&ext.as_str()))
+            || self
+                .partial_extensions
+                .iter()
+                .any(|e| e.trim_start_matches('.').eq_ignore_ascii_case(&ext))
+    }
 }
@@ -1,25 +1,35 @@
 //! XML configuration support.
 //! - Loads settings from config.xml (quick_xml).
-//! - Creates a secure template if missing (unless ARIA_MOVE_CONFIG is set).
-//! - Exposes helpers to ensure a default config exists.
+//! - Writes the one config template (`create_template_config`, used by `config::load_or_init`).
 //!
 //! Notes:
 //! - This module only reads/writes the config file; directory validation happens elsewhere.
 //! - Unknown XML fields cause a hard failure (panic) to surface misconfigurations early.
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow};
 use quick_xml::de::from_str as from_xml_str;
 use serde::Deserialize;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 // duration no longer parsed from XML; keep runtime default in Config
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use super::paths::{default_config_path, default_log_path, path_has_symlink_ancestor};
-use super::{COMPLETED_BASE_DEFAULT, DOWNLOAD_BASE_DEFAULT};
+use super::{
+    COMPLETED_BASE_DEFAULT, DOWNLOAD_BASE_DEFAULT, LoadResult, TEMPLATE_COMPLETED_BASE,
+    TEMPLATE_DOWNLOAD_BASE, auto_template_disabled, load_or_init,
+};
 
-use crate::config::types::{Config, LogLevel};
+use crate::config::types::{
+    ByteSize, Config, ConsoleStream, CopyBackend, CopyOrder, DestSymlinkPolicy, FsyncPolicy,
+    HumanDuration, LockStrategy, LogLevel, LogTimeFormat, PartialPreset, ResumeVerify, RpcAction,
+    SymlinkPolicy, parse_dir_mode,
+};
+use crate::fs_ops::{Handler, OnDuplicate, OnFull, OnRedownload};
+use crate::notify::{Notifier, NotifierSpec};
+use crate::output as out;
 use crate::platform::{set_dir_mode_0700, set_file_mode_0600, write_config_secure_new_0600};
 
 /// Struct mirroring the XML config for deserialization.
@@ -33,47 +43,268 @@ struct XmlConfig {
     completed_base: Option<String>,
     #[serde(rename = "log_level")]
     log_level: Option<String>,
+    #[serde(rename = "log_filter")]
+    log_filter: Option<String>,
+    #[serde(rename = "honor_rust_log")]
+    honor_rust_log: Option<bool>,
+    #[serde(rename = "trace_fs_ops")]
+    trace_fs_ops: Option<bool>,
+    #[serde(rename = "log_time_format")]
+    log_time_format: Option<String>,
+    #[serde(rename = "log_utc")]
+    log_utc: Option<bool>,
     #[serde(rename = "log_file")]
     log_file: Option<String>,
+    #[serde(rename = "console_stream")]
+    console_stream: Option<String>,
     #[serde(rename = "preserve_metadata")]
     preserve_metadata: Option<bool>,
     #[serde(rename = "preserve_permissions")]
     preserve_permissions: Option<bool>,
+    #[serde(rename = "dest_dir_mode")]
+    dest_dir_mode: Option<String>,
+    #[serde(rename = "created_dir_mode")]
+    created_dir_mode: Option<String>,
+    #[serde(rename = "preserve_acls")]
+    preserve_acls: Option<bool>,
     #[serde(rename = "disable_locks")]
     disable_locks: Option<bool>,
+    #[serde(rename = "lock_strategy")]
+    lock_strategy: Option<String>,
+    #[serde(rename = "copy_order")]
+    copy_order: Option<String>,
+    #[serde(rename = "copy_backend")]
+    copy_backend: Option<String>,
+    #[serde(rename = "copy_engine")]
+    copy_engine: Option<String>,
+    #[serde(rename = "resume_verify")]
+    resume_verify: Option<String>,
+    #[serde(rename = "partial_dir_moves")]
+    partial_dir_moves: Option<bool>,
+    #[serde(rename = "copy_only")]
+    copy_only: Option<bool>,
+    #[serde(rename = "bt_mode")]
+    bt_mode: Option<bool>,
+    #[serde(rename = "aria2_rpc_url")]
+    aria2_rpc_url: Option<String>,
+    #[serde(rename = "aria2_rpc_secret")]
+    aria2_rpc_secret: Option<String>,
+    #[serde(rename = "rpc_action")]
+    rpc_action: Option<String>,
+    #[serde(rename = "force_copy")]
+    force_copy: Option<bool>,
+    #[serde(rename = "content_sanity_check")]
+    content_sanity_check: Option<bool>,
+    #[serde(rename = "unnamed_temp")]
+    unnamed_temp: Option<bool>,
+    #[serde(rename = "fsync_policy")]
+    fsync_policy: Option<String>,
+    #[serde(rename = "dir_on_duplicate")]
+    dir_on_duplicate: Option<String>,
+    #[serde(rename = "merge_on_conflict")]
+    merge_on_conflict: Option<String>,
+    #[serde(rename = "symlink_source_policy")]
+    symlink_source_policy: Option<String>,
+    #[serde(rename = "dest_symlink_policy")]
+    dest_symlink_policy: Option<String>,
+    #[serde(rename = "require_source_in_base")]
+    require_source_in_base: Option<bool>,
+    #[serde(rename = "on_redownload")]
+    on_redownload: Option<String>,
+    #[serde(rename = "on_full")]
+    on_full: Option<String>,
+    #[serde(rename = "on_full_max_wait", alias = "on_full_max_wait_seconds")]
+    on_full_max_wait: Option<String>,
+    #[serde(rename = "overflow_bases")]
+    overflow_bases: Option<XmlOverflowBases>,
+    #[serde(rename = "recent_window", alias = "recent_window_seconds")]
+    recent_window: Option<String>,
+    #[serde(rename = "resolve_directories")]
+    resolve_directories: Option<bool>,
+    #[serde(rename = "include_hidden")]
+    include_hidden: Option<bool>,
+    #[serde(rename = "partial_extensions_preset")]
+    partial_extensions_preset: Option<String>,
+    #[serde(rename = "partial_extensions")]
+    partial_extensions: Option<String>,
+    #[serde(rename = "rclone_binary")]
+    rclone_binary: Option<String>,
+    #[serde(rename = "heartbeat_file")]
+    heartbeat_file: Option<String>,
+    #[serde(rename = "audit_log")]
+    audit_log: Option<String>,
+    #[serde(rename = "diagnostics_dir")]
+    diagnostics_dir: Option<String>,
+    #[serde(rename = "low_space_warn_mib")]
+    low_space_warn_mib: Option<u64>,
+    #[serde(rename = "space_cushion")]
+    space_cushion: Option<String>,
+    #[serde(rename = "run_as_user")]
+    run_as_user: Option<String>,
+    #[serde(rename = "run_as_group")]
+    run_as_group: Option<String>,
+    #[serde(rename = "max_concurrent_moves")]
+    max_concurrent_moves: Option<usize>,
+    #[serde(rename = "max_runtime", alias = "max_runtime_seconds")]
+    max_runtime: Option<String>,
+    #[serde(rename = "slow_move_warning", alias = "slow_move_warning_seconds")]
+    slow_move_warning: Option<String>,
+    #[serde(rename = "tag_destination")]
+    tag_destination: Option<bool>,
+    #[serde(rename = "handlers")]
+    handlers: Option<XmlHandlers>,
+    #[serde(rename = "notifiers")]
+    notifiers: Option<XmlNotifiers>,
 }
 
-// Reduce visual complexity of the return type used by load_config_from_xml().
-type LoadedConfig = (
-    PathBuf,          // download_base
-    PathBuf,          // completed_base
-    Option<LogLevel>, // log_level
-    Option<PathBuf>,  // log_file
-    bool,             // preserve_metadata
-    bool,             // preserve_permissions
-    bool,             // disable_locks
-);
+/// `<handlers>`: a list of `<handler ext="..." action="..." dir="..."/>`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct XmlHandlers {
+    #[serde(rename = "handler", default)]
+    handler: Vec<XmlHandler>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct XmlHandler {
+    #[serde(rename = "@ext")]
+    ext: String,
+    #[serde(rename = "@action")]
+    action: String,
+    #[serde(rename = "@dir")]
+    dir: Option<String>,
+}
+
+/// `<overflow_bases>`: a list of `<base>` paths for `on_full = spill`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct XmlOverflowBases {
+    #[serde(rename = "base", default)]
+    base: Vec<String>,
+}
+
+/// `<notifiers>`: a list of `<notifier kind="..." .../>`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct XmlNotifiers {
+    #[serde(rename = "notifier", default)]
+    notifier: Vec<XmlNotifier>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct XmlNotifier {
+    #[serde(rename = "@kind")]
+    kind: String,
+    #[serde(rename = "@url")]
+    url: Option<String>,
+    #[serde(rename = "@topic")]
+    topic: Option<String>,
+    #[serde(rename = "@user")]
+    user: Option<String>,
+    #[serde(rename = "@token")]
+    token: Option<String>,
+    #[serde(rename = "@events")]
+    events: Option<String>,
+}
+
+/// Settings read by load_config_from_xml(), before CLI overrides are merged.
+/// Optional fields are None when the XML omits them so callers keep Config defaults.
+#[derive(Debug, Clone)]
+pub struct LoadedConfig {
+    pub download_base: PathBuf,
+    pub completed_base: PathBuf,
+    pub log_level: Option<LogLevel>,
+    pub log_filter: Option<String>,
+    pub honor_rust_log: bool,
+    pub trace_fs_ops: bool,
+    pub log_time_format: Option<LogTimeFormat>,
+    pub log_utc: bool,
+    pub log_file: Option<PathBuf>,
+    pub console_stream: Option<ConsoleStream>,
+    pub preserve_metadata: bool,
+    pub preserve_permissions: bool,
+    pub dest_dir_mode: Option<u32>,
+    pub created_dir_mode: Option<u32>,
+    pub preserve_acls: bool,
+    pub disable_locks: bool,
+    pub lock_strategy: Option<LockStrategy>,
+    pub copy_order: Option<CopyOrder>,
+    pub copy_backend: Option<CopyBackend>,
+    pub copy_engine: Option<String>,
+    pub resume_verify: Option<ResumeVerify>,
+    pub partial_dir_moves: bool,
+    pub copy_only: bool,
+    pub bt_mode: bool,
+    pub aria2_rpc_url: Option<String>,
+    pub aria2_rpc_secret: Option<String>,
+    pub rpc_action: Option<RpcAction>,
+    pub force_copy: bool,
+    pub content_sanity_check: bool,
+    pub unnamed_temp: bool,
+    pub fsync_policy: Option<FsyncPolicy>,
+    pub dir_on_duplicate: Option<OnDuplicate>,
+    pub merge_on_conflict: Option<OnDuplicate>,
+    pub symlink_source_policy: Option<SymlinkPolicy>,
+    pub dest_symlink_policy: Option<DestSymlinkPolicy>,
+    pub require_source_in_base: Option<bool>,
+    pub on_redownload: Option<OnRedownload>,
+    pub on_full: Option<OnFull>,
+    pub on_full_max_wait: Option<Duration>,
+    pub overflow_bases: Vec<PathBuf>,
+    pub recent_window: Option<Duration>,
+    pub resolve_directories: bool,
+    pub include_hidden: bool,
+    pub partial_extensions_preset: Option<Vec<PartialPreset>>,
+    pub partial_extensions: Vec<String>,
+    pub rclone_binary: Option<PathBuf>,
+    pub heartbeat_file: Option<PathBuf>,
+    pub audit_log: Option<PathBuf>,
+    pub diagnostics_dir: Option<PathBuf>,
+    pub low_space_warn_mib: Option<u64>,
+    pub space_cushion: Option<ByteSize>,
+    pub run_as_user: Option<String>,
+    pub run_as_group: Option<String>,
+    pub max_concurrent_moves: Option<usize>,
+    pub max_runtime: Option<Duration>,
+    pub slow_move_warning: Option<Duration>,
+    pub tag_destination: bool,
+    pub handlers: Vec<Handler>,
+    pub notifiers: Vec<Notifier>,
+}
 
 /// Read config from XML. OS-aware default path used if ARIA_MOVE_CONFIG not set.
-/// Returns None if no meaningful settings are present or the file doesn’t exist.
-pub fn load_config_from_xml() -> Option<LoadedConfig> {
+/// Returns None if no meaningful settings are present or the file doesn’t exist, and an
+/// error naming the field when a value is invalid (a duration, mode, size, notifier, handler
+/// or time format).
+pub fn load_config_from_xml() -> Result<Option<LoadedConfig>> {
     // 1) Choose config path:
     //    - ARIA_MOVE_CONFIG (if set)
     //    - default per-platform path (best-effort)
     // Resolve env override via default_config_path() to keep logic (rel/dir) consistent
     let env_set = env::var_os("ARIA_MOVE_CONFIG").is_some();
-    let cfg_path = default_config_path().ok()?;
+    let Ok(cfg_path) = default_config_path() else {
+        return Ok(None);
+    };
+    if env_set {
+        debug!(path = %cfg_path.display(), "ARIA_MOVE_CONFIG is set");
+    } else {
+        debug!(path = %cfg_path.display(), "ARIA_MOVE_CONFIG not set, using the default config path");
+    }
 
-    // 2) If missing: create a template (only when using default path), then return None.
+    // 2) If missing: create a template (only when using default path and not disabled), then return None.
     if !cfg_path.exists() {
-        if !env_set {
+        if !env_set && !auto_template_disabled() {
             let _ = create_template_config(&cfg_path);
         }
-        return None;
+        return Ok(None);
     }
 
     // 3) Read and parse
-    let content = fs::read_to_string(&cfg_path).ok()?;
+    let Ok(content) = fs::read_to_string(&cfg_path) else {
+        return Ok(None);
+    };
     let parsed: XmlConfig = match from_xml_str(&content) {
         Ok(x) => x,
         Err(e) => {
@@ -91,9 +322,10 @@ pub fn load_config_from_xml() -> Option<LoadedConfig> {
                 cfg_path.display(),
                 msg
             );
-            return None;
+            return Ok(None);
         }
     };
+    let invalid = invalid_config(&cfg_path);
 
     // 4) Map fields
     let download_base = parsed
@@ -110,20 +342,99 @@ pub fn load_config_from_xml() -> Option<LoadedConfig> {
         .and_then(|s| s.trim().parse::<LogLevel>().ok());
     // Only override the default log file if user specified a non-empty value.
     // (If tag omitted or empty, we leave Config default in place during merge.)
-    let log_file = parsed
-        .log_file
+    let log_file = parsed.log_file.as_deref().and_then(|s| {
+        let trimmed = s.trim();
+        if trimmed.is_empty() {
+            None
+        } else {
+            Some(PathBuf::from(trimmed))
+        }
+    });
+    let console_stream = parsed
+        .console_stream
         .as_deref()
-        .and_then(|s| {
-            let trimmed = s.trim();
-            if trimmed.is_empty() {
-                None
-            } else {
-                Some(PathBuf::from(trimmed))
-            }
-        });
+        .and_then(|s| s.trim().parse::<ConsoleStream>().ok());
     let preserve_metadata = parsed.preserve_metadata.unwrap_or(false);
     let preserve_permissions = parsed.preserve_permissions.unwrap_or(false);
+    let dest_dir_mode =
+        dir_mode_from_xml("dest_dir_mode", parsed.dest_dir_mode.as_deref()).map_err(&invalid)?;
     let disable_locks = parsed.disable_locks.unwrap_or(false);
+    let lock_strategy = parsed
+        .lock_strategy
+        .as_deref()
+        .and_then(|s| s.trim().parse::<LockStrategy>().ok());
+    let copy_order = parsed
+        .copy_order
+        .as_deref()
+        .and_then(|s| s.trim().parse::<CopyOrder>().ok());
+    let copy_backend = parsed
+        .copy_backend
+        .as_deref()
+        .and_then(|s| s.trim().parse::<CopyBackend>().ok());
+    let copy_engine = non_empty(parsed.copy_engine.as_deref());
+    let resume_verify = parsed
+        .resume_verify
+        .as_deref()
+        .and_then(|s| s.trim().parse::<ResumeVerify>().ok());
+    let partial_dir_moves = parsed.partial_dir_moves.unwrap_or(false);
+    let copy_only = parsed.copy_only.unwrap_or(false);
+    let force_copy = parsed.force_copy.unwrap_or(false);
+    let content_sanity_check = parsed.content_sanity_check.unwrap_or(false);
+    let unnamed_temp = parsed.unnamed_temp.unwrap_or(false);
+    let fsync_policy = parsed
+        .fsync_policy
+        .as_deref()
+        .and_then(|s| s.trim().parse::<FsyncPolicy>().ok());
+    let dir_on_duplicate = parsed
+        .dir_on_duplicate
+        .as_deref()
+        .and_then(|s| s.trim().parse::<OnDuplicate>().ok());
+    let merge_on_conflict = parsed
+        .merge_on_conflict
+        .as_deref()
+        .and_then(|s| s.trim().parse::<OnDuplicate>().ok());
+    let symlink_source_policy = parsed
+        .symlink_source_policy
+        .as_deref()
+        .and_then(|s| s.trim().parse::<SymlinkPolicy>().ok());
+    let dest_symlink_policy = parsed
+        .dest_symlink_policy
+        .as_deref()
+        .and_then(|s| s.trim().parse::<DestSymlinkPolicy>().ok());
+    let on_redownload = parsed
+        .on_redownload
+        .as_deref()
+        .and_then(|s| s.trim().parse::<OnRedownload>().ok());
+    let on_full = parsed
+        .on_full
+        .as_deref()
+        .and_then(|s| s.trim().parse::<OnFull>().ok());
+    let on_full_max_wait =
+        duration_from_xml("on_full_max_wait", parsed.on_full_max_wait.as_deref())
+            .map_err(&invalid)?;
+    let recent_window =
+        duration_from_xml("recent_window", parsed.recent_window.as_deref()).map_err(&invalid)?;
+    let resolve_directories = parsed.resolve_directories.unwrap_or(false);
+    let include_hidden = parsed.include_hidden.unwrap_or(false);
+    let rclone_binary = match parsed.rclone_binary.as_deref().map(str::trim) {
+        Some(s) if !s.is_empty() => Some(PathBuf::from(s)),
+        _ => None,
+    };
+    let heartbeat_file = match parsed.heartbeat_file.as_deref().map(str::trim) {
+        Some(s) if !s.is_empty() => Some(PathBuf::from(s)),
+        _ => None,
+    };
+    let audit_log = match parsed.audit_log.as_deref().map(str::trim) {
+        Some(s) if !s.is_empty() => Some(PathBuf::from(s)),
+        _ => None,
+    };
+    let run_as_user = non_empty(parsed.run_as_user.as_deref());
+    let run_as_group = non_empty(parsed.run_as_group.as_deref());
+
+    let log_time_format =
+        log_time_format_from_xml(parsed.log_time_format.as_deref()).map_err(&invalid)?;
+    let handlers = handlers_from_xml(parsed.handlers).map_err(&invalid)?;
+    let notifiers = notifiers_from_xml(parsed.notifiers).map_err(&invalid)?;
 
     // If no meaningful settings were provided, treat as "no config" so callers can use defaults.
     if download_base.is_none()
@@ -131,19 +442,83 @@ pub fn load_config_from_xml() -> Option<LoadedConfig> {
         && log_level.is_none()
         && log_file.is_none()
     {
-        return None;
+        return Ok(None);
     }
 
-    Some((
-        download_base.unwrap_or_else(|| PathBuf::from(DOWNLOAD_BASE_DEFAULT)),
-        completed_base.unwrap_or_else(|| PathBuf::from(COMPLETED_BASE_DEFAULT)),
+    Ok(Some(LoadedConfig {
+        download_base: download_base.unwrap_or_else(|| PathBuf::from(DOWNLOAD_BASE_DEFAULT)),
+        completed_base: completed_base.unwrap_or_else(|| PathBuf::from(COMPLETED_BASE_DEFAULT)),
         log_level,
-    // Do NOT inject a default here; leave Config::default() value intact unless user provided one.
-    log_file,
+        log_filter: non_empty(parsed.log_filter.as_deref()),
+        honor_rust_log: parsed.honor_rust_log.unwrap_or(false),
+        trace_fs_ops: parsed.trace_fs_ops.unwrap_or(false),
+        log_time_format,
+        log_utc: parsed.log_utc.unwrap_or(false),
+        // Do NOT inject a default here; leave Config::default() value intact unless user provided one.
+        log_file,
+        console_stream,
         preserve_metadata,
         preserve_permissions,
+        dest_dir_mode,
+        created_dir_mode: dir_mode_from_xml("created_dir_mode", parsed.created_dir_mode.as_deref())
+            .map_err(&invalid)?,
+        preserve_acls: parsed.preserve_acls.unwrap_or(false),
         disable_locks,
-    ))
+        lock_strategy,
+        copy_order,
+        copy_backend,
+        copy_engine,
+        resume_verify,
+        partial_dir_moves,
+        copy_only,
+        bt_mode: parsed.bt_mode.unwrap_or(false),
+        aria2_rpc_url: non_empty(parsed.aria2_rpc_url.as_deref()),
+        aria2_rpc_secret: non_empty(parsed.aria2_rpc_secret.as_deref()),
+        rpc_action: parsed
+            .rpc_action
+            .as_deref()
+            .and_then(|s| s.trim().parse::<RpcAction>().ok()),
+        force_copy,
+        content_sanity_check,
+        unnamed_temp,
+        fsync_policy,
+        dir_on_duplicate,
+        merge_on_conflict,
+        symlink_source_policy,
+        dest_symlink_policy,
+        require_source_in_base: parsed.require_source_in_base,
+        on_redownload,
+        on_full,
+        on_full_max_wait,
+        overflow_bases: overflow_bases_from_xml(parsed.overflow_bases),
+        recent_window,
+        resolve_directories,
+        include_hidden,
+        partial_extensions_preset: partial_presets_from_xml(
+            parsed.partial_extensions_preset.as_deref(),
+        ),
+        partial_extensions: partial_extensions_from_xml(parsed.partial_extensions.as_deref()),
+        rclone_binary,
+        heartbeat_file,
+        audit_log,
+        diagnostics_dir: non_empty(parsed.diagnostics_dir.as_deref()).map(PathBuf::from),
+        low_space_warn_mib: parsed.low_space_warn_mib.filter(|&m| m > 0),
+        space_cushion: size_from_xml("space_cushion", parsed.space_cushion.as_deref())
+            .map_err(&invalid)?,
+        run_as_user,
+        run_as_group,
+        max_concurrent_moves: parsed.max_concurrent_moves,
+        max_runtime: max_runtime_from_xml(parsed.max_runtime.as_deref()).map_err(&invalid)?,
+        slow_move_warning: duration_from_xml(
+            "slow_move_warning",
+            parsed.slow_move_warning.as_deref(),
+        )
+        .map_err(&invalid)?
+        .filter(|d| !d.is_zero()),
+        tag_destination: parsed.tag_destination.unwrap_or(false),
+        handlers,
+        notifiers,
+    }))
 }
 
 /// Create default template config file and parent directory (best-effort permissions).
@@ -155,6 +530,12 @@ pub fn create_template_config(path: &Path) -> Result<()> {
             path.display()
         ));
     }
+    if fs::symlink_metadata(path).is_ok() {
+        return Err(anyhow::anyhow!(
+            "Refusing to create config: {} already exists",
+            path.display()
+        ));
+    }
 
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
@@ -172,30 +553,171 @@ pub fn create_template_config(path: &Path) -> Result<()> {
     Boolean flags (true/false):
         preserve_metadata      -> copy permissions + timestamps (+ xattrs when feature enabled)
         preserve_permissions   -> copy only permissions (mode on Unix, readonly on Windows)
+        preserve_acls          -> Linux: copy POSIX ACLs and keep default-ACL inheritance for created directories
         disable_locks          -> disable directory locking (for ZFS/NFS/network shares in containers)
+        partial_dir_moves      -> on directory copy errors, move what can be moved and leave the rest
+        copy_only              -> copy into completed_base and keep the source (read-only sources, keep seeding)
+        bt_mode                -> aria2 hook runs: ask aria2 over JSON-RPC whether the GID is still seeding and,
+                                  if so, copy as with copy_only (for on-bt-download-complete, which runs while seeding)
+        aria2_rpc_url          -> aria2 JSON-RPC endpoint for bt_mode (default http://localhost:6800/jsonrpc)
+        aria2_rpc_secret       -> aria2 rpc-secret, if set
+        rpc_action             -> none | pause | remove: on aria2 hook runs, stop the task over JSON-RPC before moving;
+                                  pause points it at the moved data and unpauses it, remove does not add it back
+        force_copy             -> never rename; always copy then remove the source (FUSE/overlay mounts with broken rename)
+        content_sanity_check   -> refuse files whose last 64 KiB are all zeros (preallocated, not yet written downloads)
+        unnamed_temp           -> Linux: copy into an unnamed temp file so scanners never see .aria_move temps (no resume)
+        fsync_policy           -> full | data | none (full syncs files and directories; data skips directory syncs)
+        resolve_directories    -> with no source path, auto-pick the most recent stable top-level directory
+        include_hidden         -> consider dot-files/dirs in resolve, sweep and reconcile (.aria_move.* never)
+        require_source_in_base -> refuse sources outside download_base (and any inside completed_base); default true
+        tag_destination        -> write user.aria_move.src / .gid / .moved_at xattrs on moved items (xattrs feature)
 
     Other fields:
         download_base          -> directory where new/partial downloads appear
         completed_base         -> directory where completed items are moved
-        log_level              -> quiet | normal | info | debug
+        log_level              -> quiet | warn | normal | info | debug
+        log_filter             -> extra per-module directives on top of log_level, e.g.
+                                  aria_move::fs_ops=debug,aria_move::config=warn (empty = none)
+        honor_rust_log         -> true: a non-empty RUST_LOG environment variable replaces log_level and log_filter
+        trace_fs_ops           -> true: log each open/rename/fsync/unlink at debug level with path, duration and
+                                  errno (needs log_level debug, or log_filter aria_move::fs_ops::fs_trace=debug)
+        log_time_format        -> rfc3339 (default, e.g. 2024-05-01T14:03:07.123+02:00) | legacy (DD/MM/YY HH:MM:SS)
+                                  | a strftime format such as %Y-%m-%d %H:%M:%S
+        log_utc                -> true: log timestamps in UTC instead of local time
         log_file               -> path to log file (optional; stdout/stderr still used)
+        console_stream         -> stdout | stderr | none (where console logs and status lines go; JSON
+                                  reports and scriptable output stay on stdout; errors always reach stderr)
+        dest_dir_mode          -> Unix: octal mode for directories created in completed_base, e.g. 0755
+                                  (empty = the source directory's mode when preserving metadata/permissions, else umask)
+        created_dir_mode       -> Unix: octal mode for missing base directories aria_move creates (default 0700;
+                                  e.g. 0755 so a media server running as another user can read completed_base)
+        lock_strategy          -> auto | flock | lockfile | none (auto uses a TTL lockfile on NFS/SMB)
+        copy_order             -> walk | largest-first | smallest-first (directory copy fallback)
+        copy_backend           -> builtin | rsync (cross-filesystem copies; rsync resumes with delta transfer)
+        copy_engine            -> optional: builtin | reflink | kernel | streaming | rsync (overrides copy_backend)
+        resume_verify          -> tail | full | none (check an interrupted temp against the source before resuming)
+        dir_on_duplicate       -> rename | merge (when completed_base/<dir> already exists)
+        merge_on_conflict      -> rename | skip | overwrite (per file, only inside a directory merge)
+        symlink_source_policy  -> error | move-link | move-target (when the source is a symlink)
+        dest_symlink_policy    -> error | replace (when completed_base/<name> is a symlink; replace removes the link only)
+        on_redownload          -> keep-both | skip | replace (a torrent moved before, by --infohash, completes again)
+        on_full                -> fail | pause-and-retry | spill (completed_base has no room for the item)
+        on_full_max_wait       -> longest total wait of pause-and-retry (default 1h)
+        overflow_bases         -> <base>...</base> destinations tried in order by on_full = spill
+        recent_window          -> items modified within this window count as in progress (--sweep, resolve_directories; default 5m; 0 = no age check)
+        partial_extensions_preset -> comma-separated clients whose incomplete-file suffixes mark an item as partial:
+                                  aria2 (.aria2 .part .tmp), qbittorrent (.!qB), deluge (.part), chrome (.crdownload),
+                                  utorrent (.!ut .ut), bitcomet (.bc!) or all; default aria2,chrome
+        partial_extensions     -> extra comma-separated partial suffixes, e.g. .partial,.dltemp
+        rclone_binary          -> rclone executable for completed_base = rclone:remote:path
+        heartbeat_file         -> file rewritten after each successful run (for `aria_move healthcheck`)
+        audit_log              -> hash-chained JSON-lines record of every move (check with `aria_move audit verify`)
+        diagnostics_dir        -> directory for a JSON diagnostic bundle per failed move (fs types, free space,
+                                  mount flags, lock states, recent audit records); the bundle is always logged
+        low_space_warn_mib     -> warn (log + low-space notification) once when completed_base has less free space (MiB; 0 = off)
+        space_cushion          -> free space required beyond an item's size before copying it (default 4MiB);
+                                  bytes or a size such as 64MiB or 1.5GB
+        run_as_user            -> when started as root: user (name or uid) to switch to after startup
+        run_as_group           -> when started as root: group (name or gid); default is the user's group
+        max_concurrent_moves   -> how many items --sweep-move moves at once (1 = one after another)
+        max_runtime            -> abort the whole run after this long (source kept, temps cleaned; 0 = no limit)
+        slow_move_warning      -> warn (log with phase timings + slow-move notification) when one move takes longer
+                                  than this (0 = off)
+                                  durations are seconds or a duration such as 90s, 5m, 2h or 1h30m; the older
+                                  *_seconds element names are still read
+        handlers               -> per-extension <handler ext="..." action="..."/> entries, first match wins:
+                                  verify-sha256 (check <name>.sha256 before moving), delete (instead of moving),
+                                  archive (move into dir="..." instead), extract (zip or tar archive, unpacked
+                                  into <name>/ after moving)
+        notifiers              -> <notifier kind="ntfy|gotify|pushover" .../> push targets (sent with curl):
+                                  ntfy needs topic (url defaults to https://ntfy.sh; token optional), gotify url and token,
+                                  pushover token and user; events="success,failure,low-space" (default: all)
 
     Notes:
         - CLI flags override XML values.
         - Setting preserve_metadata implies permissions; preserve_permissions is ignored if preserve_metadata=true.
         - Set disable_locks=true only if you encounter "Permission denied (os error 13)" on ZFS/NFS shares in containers.
+        - IMPORTANT: {} and {} are placeholders. Edit them before running.
+            When running as root, aria_move will refuse to create these placeholder paths.
 -->
 <config>
     <download_base>{}</download_base>
     <completed_base>{}</completed_base>
     <log_level>normal</log_level>
+    <log_filter></log_filter>
+    <honor_rust_log>false</honor_rust_log>
+    <trace_fs_ops>false</trace_fs_ops>
+    <log_time_format>rfc3339</log_time_format>
+    <log_utc>false</log_utc>
     <log_file>{}</log_file>
+    <console_stream>stdout</console_stream>
     <preserve_metadata>false</preserve_metadata>
     <preserve_permissions>false</preserve_permissions>
+    <preserve_acls>false</preserve_acls>
+    <dest_dir_mode></dest_dir_mode>
+    <created_dir_mode>0700</created_dir_mode>
     <disable_locks>false</disable_locks>
+    <lock_strategy>auto</lock_strategy>
+    <copy_order>walk</copy_order>
+    <copy_backend>builtin</copy_backend>
+    <copy_engine></copy_engine>
+    <resume_verify>tail</resume_verify>
+    <partial_dir_moves>false</partial_dir_moves>
+    <copy_only>false</copy_only>
+    <bt_mode>false</bt_mode>
+    <aria2_rpc_url>http://localhost:6800/jsonrpc</aria2_rpc_url>
+    <aria2_rpc_secret></aria2_rpc_secret>
+    <rpc_action>none</rpc_action>
+    <force_copy>false</force_copy>
+    <content_sanity_check>false</content_sanity_check>
+    <unnamed_temp>false</unnamed_temp>
+    <fsync_policy>full</fsync_policy>
+    <dir_on_duplicate>rename</dir_on_duplicate>
+    <merge_on_conflict>rename</merge_on_conflict>
+    <symlink_source_policy>error</symlink_source_policy>
+    <dest_symlink_policy>error</dest_symlink_policy>
+    <require_source_in_base>true</require_source_in_base>
+    <on_redownload>keep-both</on_redownload>
+    <on_full>fail</on_full>
+    <on_full_max_wait>1h</on_full_max_wait>
+    <overflow_bases>
+        <!-- <base>/path/to/overflow</base> -->
+    </overflow_bases>
+    <recent_window>5m</recent_window>
+    <resolve_directories>false</resolve_directories>
+    <include_hidden>false</include_hidden>
+    <partial_extensions_preset>aria2,chrome</partial_extensions_preset>
+    <partial_extensions></partial_extensions>
+    <rclone_binary>rclone</rclone_binary>
+    <heartbeat_file></heartbeat_file>
+    <audit_log></audit_log>
+    <diagnostics_dir></diagnostics_dir>
+    <low_space_warn_mib>0</low_space_warn_mib>
+    <space_cushion>4MiB</space_cushion>
+    <run_as_user></run_as_user>
+    <run_as_group></run_as_group>
+    <max_concurrent_moves>1</max_concurrent_moves>
+    <max_runtime>0</max_runtime>
+    <slow_move_warning>0</slow_move_warning>
+    <tag_destination>false</tag_destination>
+    <handlers>
+        <!-- <handler ext="iso" action="verify-sha256"/> -->
+        <!-- <handler ext="zip" action="extract"/> -->
+        <!-- <handler ext="nfo" action="delete"/> -->
+        <!-- <handler ext="torrent" action="archive" dir="/path/to/torrents"/> -->
+    </handlers>
+    <notifiers>
+        <!-- <notifier kind="ntfy" topic="my-downloads" events="success,failure"/> -->
+        <!-- <notifier kind="gotify" url="https://gotify.example.com" token="APP_TOKEN" events="failure,low-space"/> -->
+        <!-- <notifier kind="pushover" token="APP_TOKEN" user="USER_KEY" events="failure,low-space"/> -->
+    </notifiers>
 </config>
 "#,
-        DOWNLOAD_BASE_DEFAULT, COMPLETED_BASE_DEFAULT, suggested_log
+        TEMPLATE_DOWNLOAD_BASE,
+        TEMPLATE_COMPLETED_BASE,
+        TEMPLATE_DOWNLOAD_BASE,
+        TEMPLATE_COMPLETED_BASE,
+        suggested_log
     );
 
     // Atomic, secure write (O_NOFOLLOW + create_new on Unix), then tighten perms.
@@ -207,43 +729,26 @@ pub fn create_template_config(path: &Path) -> Result<()> {
 }
 
 /// Create default config if ARIA_MOVE_CONFIG not set; return created path so CLI can inform the user.
+#[deprecated(
+    since = "1.0.0",
+    note = "Use config::load_or_init (or config::ensure_default_config_exists) instead."
+)]
 pub fn ensure_default_config_exists() -> Option<PathBuf> {
     if env::var_os("ARIA_MOVE_CONFIG").is_some() {
         return None;
     }
-
-    let cfg_path = match default_config_path() {
-        Ok(p) => p,
-        Err(_) => return None,
-    };
-
-    if cfg_path.exists() {
-        return None;
-    }
-
-    if let Ok(true) = path_has_symlink_ancestor(&cfg_path) {
-        eprintln!(
-            "Refusing to create template config because an existing ancestor is a symlink: {}",
-            cfg_path.display()
-        );
-        return None;
-    }
-
-    match create_template_config(&cfg_path) {
-        Ok(()) => Some(cfg_path),
+    match load_or_init() {
+        Ok(LoadResult::CreatedTemplate(p)) => Some(p),
+        Ok(LoadResult::Loaded(..)) => None,
         Err(e) => {
-            eprintln!(
-                "Failed to create template config at {}: {}",
-                cfg_path.display(),
-                e
-            );
+            out::print_warn(&format!("Failed to create template config: {e:#}"));
             None
         }
     }
 }
 
-// Map XmlConfig -> Config (used by both loaders)
-fn xml_to_config(parsed: XmlConfig) -> Config {
+// Map XmlConfig -> Config (used by both loaders); an invalid value is an error naming its field.
+fn xml_to_config(parsed: XmlConfig) -> Result<Config> {
     let default_cfg = Config::default();
     let download_base = parsed
         .download_base
@@ -264,23 +769,315 @@ fn xml_to_config(parsed: XmlConfig) -> Config {
         .as_deref()
         .and_then(|s| s.trim().parse::<LogLevel>().ok())
         .unwrap_or(default_cfg.log_level);
+    let console_stream = parsed
+        .console_stream
+        .as_deref()
+        .and_then(|s| s.trim().parse::<ConsoleStream>().ok())
+        .unwrap_or(default_cfg.console_stream);
     let preserve_metadata = parsed.preserve_metadata.unwrap_or(false);
     let preserve_permissions = if preserve_metadata {
         false
     } else {
         parsed.preserve_permissions.unwrap_or(false)
     };
+    let dest_dir_mode = dir_mode_from_xml("dest_dir_mode", parsed.dest_dir_mode.as_deref())?;
     let disable_locks = parsed.disable_locks.unwrap_or(false);
-    Config {
+    let lock_strategy = parsed
+        .lock_strategy
+        .as_deref()
+        .and_then(|s| s.trim().parse::<LockStrategy>().ok())
+        .unwrap_or(default_cfg.lock_strategy);
+    let copy_order = parsed
+        .copy_order
+        .as_deref()
+        .and_then(|s| s.trim().parse::<CopyOrder>().ok())
+        .unwrap_or(default_cfg.copy_order);
+    let copy_backend = parsed
+        .copy_backend
+        .as_deref()
+        .and_then(|s| s.trim().parse::<CopyBackend>().ok())
+        .unwrap_or(default_cfg.copy_backend);
+    let copy_engine = non_empty(parsed.copy_engine.as_deref());
+    let resume_verify = parsed
+        .resume_verify
+        .as_deref()
+        .and_then(|s| s.trim().parse::<ResumeVerify>().ok())
+        .unwrap_or(default_cfg.resume_verify);
+    let partial_dir_moves = parsed.partial_dir_moves.unwrap_or(false);
+    let copy_only = parsed.copy_only.unwrap_or(false);
+    let force_copy = parsed.force_copy.unwrap_or(false);
+    let content_sanity_check = parsed.content_sanity_check.unwrap_or(false);
+    let unnamed_temp = parsed.unnamed_temp.unwrap_or(false);
+    let fsync_policy = parsed
+        .fsync_policy
+        .as_deref()
+        .and_then(|s| s.trim().parse::<FsyncPolicy>().ok())
+        .unwrap_or(default_cfg.fsync_policy);
+    let dir_on_duplicate = parsed
+        .dir_on_duplicate
+        .as_deref()
+        .and_then(|s| s.trim().parse::<OnDuplicate>().ok())
+        .unwrap_or(default_cfg.dir_on_duplicate);
+    let merge_on_conflict = parsed
+        .merge_on_conflict
+        .as_deref()
+        .and_then(|s| s.trim().parse::<OnDuplicate>().ok())
+        .unwrap_or(default_cfg.merge_on_conflict);
+    let symlink_source_policy = parsed
+        .symlink_source_policy
+        .as_deref()
+        .and_then(|s| s.trim().parse::<SymlinkPolicy>().ok())
+        .unwrap_or(default_cfg.symlink_source_policy);
+    let dest_symlink_policy = parsed
+        .dest_symlink_policy
+        .as_deref()
+        .and_then(|s| s.trim().parse::<DestSymlinkPolicy>().ok())
+        .unwrap_or(default_cfg.dest_symlink_policy);
+    let on_redownload = parsed
+        .on_redownload
+        .as_deref()
+        .and_then(|s| s.trim().parse::<OnRedownload>().ok())
+        .unwrap_or(default_cfg.on_redownload);
+    let on_full = parsed
+        .on_full
+        .as_deref()
+        .and_then(|s| s.trim().parse::<OnFull>().ok())
+        .unwrap_or(default_cfg.on_full);
+    let on_full_max_wait =
+        duration_from_xml("on_full_max_wait", parsed.on_full_max_wait.as_deref())?
+            .unwrap_or(default_cfg.on_full_max_wait);
+    let recent_window = duration_from_xml("recent_window", parsed.recent_window.as_deref())?
+        .unwrap_or(default_cfg.recent_window);
+    let resolve_directories = parsed.resolve_directories.unwrap_or(false);
+    let include_hidden = parsed.include_hidden.unwrap_or(false);
+    let rclone_binary = match parsed.rclone_binary.as_deref().map(str::trim) {
+        Some(s) if !s.is_empty() => PathBuf::from(s),
+        _ => default_cfg.rclone_binary.clone(),
+    };
+    let heartbeat_file = match parsed.heartbeat_file.as_deref().map(str::trim) {
+        Some(s) if !s.is_empty() => Some(PathBuf::from(s)),
+        _ => None,
+    };
+    let audit_log = match parsed.audit_log.as_deref().map(str::trim) {
+        Some(s) if !s.is_empty() => Some(PathBuf::from(s)),
+        _ => None,
+    };
+    Ok(Config {
         download_base,
         completed_base,
         log_level,
+        log_filter: non_empty(parsed.log_filter.as_deref()),
+        honor_rust_log: parsed.honor_rust_log.unwrap_or(false),
+        trace_fs_ops: parsed.trace_fs_ops.unwrap_or(false),
+        log_time_format: log_time_format_from_xml(parsed.log_time_format.as_deref())?
+            .unwrap_or_default(),
+        log_utc: parsed.log_utc.unwrap_or(false),
         log_file,
+        console_stream,
         dry_run: false,
         preserve_metadata,
         preserve_permissions,
+        dest_dir_mode,
+        created_dir_mode: dir_mode_from_xml(
+            "created_dir_mode",
+            parsed.created_dir_mode.as_deref(),
+        )?
+        .unwrap_or(default_cfg.created_dir_mode),
+        preserve_acls: parsed.preserve_acls.unwrap_or(false),
         disable_locks,
+        lock_strategy,
+        copy_order,
+        copy_backend,
+        copy_engine,
+        resume_verify,
+        partial_dir_moves,
+        copy_only,
+        bt_mode: parsed.bt_mode.unwrap_or(false),
+        aria2_rpc_url: non_empty(parsed.aria2_rpc_url.as_deref())
+            .unwrap_or_else(|| default_cfg.aria2_rpc_url.clone()),
+        aria2_rpc_secret: non_empty(parsed.aria2_rpc_secret.as_deref()),
+        rpc_action: parsed
+            .rpc_action
+            .as_deref()
+            .and_then(|s| s.trim().parse::<RpcAction>().ok())
+            .unwrap_or(default_cfg.rpc_action),
+        force_copy,
+        content_sanity_check,
+        unnamed_temp,
+        fsync_policy,
+        dir_on_duplicate,
+        merge_on_conflict,
+        symlink_source_policy,
+        dest_symlink_policy,
+        require_source_in_base: parsed
+            .require_source_in_base
+            .unwrap_or(default_cfg.require_source_in_base),
+        on_redownload,
+        on_full,
+        on_full_max_wait,
+        overflow_bases: overflow_bases_from_xml(parsed.overflow_bases),
+        recent_window,
+        resolve_directories,
+        include_hidden,
+        partial_extensions_preset: partial_presets_from_xml(
+            parsed.partial_extensions_preset.as_deref(),
+        )
+        .unwrap_or(default_cfg.partial_extensions_preset),
+        partial_extensions: partial_extensions_from_xml(parsed.partial_extensions.as_deref()),
+        rclone_binary,
+        heartbeat_file,
+        audit_log,
+        diagnostics_dir: non_empty(parsed.diagnostics_dir.as_deref()).map(PathBuf::from),
+        low_space_warn_mib: parsed.low_space_warn_mib.filter(|&m| m > 0),
+        space_cushion: size_from_xml("space_cushion", parsed.space_cushion.as_deref())?
+            .unwrap_or(default_cfg.space_cushion),
+        run_as_user: non_empty(parsed.run_as_user.as_deref()),
+        run_as_group: non_empty(parsed.run_as_group.as_deref()),
+        max_concurrent_moves: parsed
+            .max_concurrent_moves
+            .unwrap_or(default_cfg.max_concurrent_moves),
+        max_runtime: max_runtime_from_xml(parsed.max_runtime.as_deref())?,
+        slow_move_warning: duration_from_xml(
+            "slow_move_warning",
+            parsed.slow_move_warning.as_deref(),
+        )?
+        .filter(|d| !d.is_zero()),
+        tag_destination: parsed.tag_destination.unwrap_or(false),
+        handlers: handlers_from_xml(parsed.handlers)?,
+        notifiers: notifiers_from_xml(parsed.notifiers)?,
+    })
+}
+
+/// `<max_runtime>`; 0 means no limit.
+fn max_runtime_from_xml(parsed: Option<&str>) -> Result<Option<Duration>> {
+    Ok(duration_from_xml("max_runtime", parsed)?.filter(|d| !d.is_zero()))
+}
+
+/// Non-empty `<base>` entries of `<overflow_bases>`.
+fn overflow_bases_from_xml(parsed: Option<XmlOverflowBases>) -> Vec<PathBuf> {
+    parsed
+        .unwrap_or_default()
+        .base
+        .iter()
+        .map(|b| b.trim())
+        .filter(|b| !b.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// `<partial_extensions_preset>` as a comma-separated list of client names (`all` selects
+/// every preset); empty means unset, unknown names warn and are skipped.
+fn partial_presets_from_xml(parsed: Option<&str>) -> Option<Vec<PartialPreset>> {
+    let s = parsed.map(str::trim).filter(|s| !s.is_empty())?;
+    let mut presets = Vec::new();
+    for name in s.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+        if name.eq_ignore_ascii_case("all") {
+            return Some(PartialPreset::ALL.to_vec());
+        }
+        match name.parse::<PartialPreset>() {
+            Ok(p) if !presets.contains(&p) => presets.push(p),
+            Ok(_) => {}
+            Err(e) => warn!("ignoring config partial_extensions_preset entry: {e}"),
+        }
     }
+    Some(presets)
+}
+
+/// `<partial_extensions>` as lowercase suffixes without the leading dot.
+fn partial_extensions_from_xml(parsed: Option<&str>) -> Vec<String> {
+    parsed
+        .unwrap_or_default()
+        .split(',')
+        .map(|e| e.trim().trim_start_matches('.').to_ascii_lowercase())
+        .filter(|e| !e.is_empty())
+        .collect()
+}
+
+/// A size field (`field`, e.g. `<space_cushion>`); empty means unset, an invalid value is an
+/// error naming the field.
+fn size_from_xml(field: &str, parsed: Option<&str>) -> Result<Option<ByteSize>> {
+    let Some(s) = parsed.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    ByteSize::parse(s)
+        .map(Some)
+        .map_err(|e| anyhow!("{field}: {e}"))
+}
+
+/// `<log_time_format>`; empty means unset.
+fn log_time_format_from_xml(parsed: Option<&str>) -> Result<Option<LogTimeFormat>> {
+    let Some(s) = parsed.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    s.parse::<LogTimeFormat>()
+        .map(Some)
+        .map_err(|e| anyhow!("log_time_format: {e}"))
+}
+
+/// A duration field (`field`, e.g. `<recent_window>`; the older `<field_seconds>` name is
+/// read too); empty means unset, an invalid value is an error naming the field.
+fn duration_from_xml(field: &str, parsed: Option<&str>) -> Result<Option<Duration>> {
+    let Some(s) = parsed.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    HumanDuration::parse(s)
+        .map(|d| Some(d.get()))
+        .map_err(|e| anyhow!("{field}: {e}"))
+}
+
+/// `<dest_dir_mode>` / `<created_dir_mode>` (named `field`) as an octal mode; empty means
+/// unset, an invalid value is an error naming the field.
+fn dir_mode_from_xml(field: &str, parsed: Option<&str>) -> Result<Option<u32>> {
+    let Some(s) = parsed.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    parse_dir_mode(s)
+        .map(Some)
+        .map_err(|e| anyhow!("{field}: {e}"))
+}
+
+/// The `<notifier>` entries; the first invalid one is an error.
+fn notifiers_from_xml(parsed: Option<XmlNotifiers>) -> Result<Vec<Notifier>> {
+    parsed
+        .unwrap_or_default()
+        .notifier
+        .into_iter()
+        .map(|n| {
+            let spec = NotifierSpec {
+                kind: &n.kind,
+                url: n.url.as_deref(),
+                topic: n.topic.as_deref(),
+                user: n.user.as_deref(),
+                token: n.token.as_deref(),
+                events: n.events.as_deref(),
+            };
+            Notifier::parse(&spec).map_err(|e| anyhow!("notifier: {e}"))
+        })
+        .collect()
+}
+
+/// The `<handler>` entries; the first invalid one is an error.
+fn handlers_from_xml(parsed: Option<XmlHandlers>) -> Result<Vec<Handler>> {
+    parsed
+        .unwrap_or_default()
+        .handler
+        .into_iter()
+        .map(|h| {
+            Handler::parse(&h.ext, &h.action, h.dir.as_deref()).map_err(|e| anyhow!("handler: {e}"))
+        })
+        .collect()
+}
+
+/// Wrap a field error as one line naming the file (main prints only the top message).
+fn invalid_config(path: &Path) -> impl Fn(anyhow::Error) -> anyhow::Error + '_ {
+    move |e| anyhow!("invalid config xml '{}': {e}", path.display())
+}
+
+/// Trimmed value of an optional text element; None when absent or blank.
+fn non_empty(s: Option<&str>) -> Option<String> {
+    s.map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
 }
 
 /// Load a Config from a specific XML file path (quick_xml).
@@ -289,25 +1086,22 @@ pub fn load_config_from_xml_path(path: &Path) -> Result<Config> {
         .with_context(|| format!("read config xml '{}'", path.display()))?;
     let parsed: XmlConfig = from_xml_str(&contents)
         .with_context(|| format!("parse config xml '{}'", path.display()))?;
-    Ok(xml_to_config(parsed))
+    xml_to_config(parsed).map_err(invalid_config(path))
 }
 
 /// If ARIA_MOVE_CONFIG is set, load and return that Config; otherwise Ok(None).
 pub fn load_config_from_xml_env() -> Result<Option<Config>> {
     if let Some(p) = env::var_os("ARIA_MOVE_CONFIG") {
-        eprintln!("[DEBUG] ARIA_MOVE_CONFIG is set to: {:?}", p);
+        debug!(path = ?p, "ARIA_MOVE_CONFIG is set");
         let cfg = load_config_from_xml_path(Path::new(&p))?;
-        eprintln!(
-            "[DEBUG] Loaded config - download_base: {}",
-            cfg.download_base.display()
-        );
-        eprintln!(
-            "[DEBUG] Loaded config - completed_base: {}",
-            cfg.completed_base.display()
+        debug!(
+            download_base = %cfg.download_base.display(),
+            completed_base = %cfg.completed_base.display(),
+            "Loaded config from ARIA_MOVE_CONFIG"
         );
         return Ok(Some(cfg));
     }
-    eprintln!("[DEBUG] ARIA_MOVE_CONFIG not set, will use default config path");
+    debug!("ARIA_MOVE_CONFIG not set, will use default config path");
     Ok(None)
 }
 
@@ -318,7 +1112,7 @@ pub fn load_config_from_default_xml() -> Result<Option<Config>> {
     if !path.exists() {
         return Ok(None);
     }
-    eprintln!("[INFO] Using config from default XML: {}", path.display());
+    debug!(path = %path.display(), "Using config from default XML");
     let cfg = load_config_from_xml_path(&path)?;
     Ok(Some(cfg))
 }
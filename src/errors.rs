@@ -1,11 +1,18 @@
 //! Typed error definitions for aria_move.
 //! Small, focused set of well-known failure modes for better logs and tests.
+//!
+//! Notes:
+//! - `move_file`, `move_dir` and `safe_copy_and_rename` return `Result<_, AriaMoveError>`;
+//!   I/O details without a dedicated variant are kept in `Io`, anything else in `Other`.
+//! - `hint()` gives a short, actionable suggestion where one is known.
 
 use serde::{Deserialize, Serialize};
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
+use crate::messages::{Msg, text};
+
 /// Non-exhaustive to allow adding new variants without breaking downstream code.
 #[derive(Debug, Error, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[non_exhaustive]
@@ -32,6 +39,53 @@ pub enum AriaMoveError {
     #[error("Operation interrupted by user")]
     Interrupted,
 
+    // --- Locking / transfer errors ---
+    /// Another process holds the lock and the caller asked not to wait.
+    #[error("Lock is held by another process: {0}")]
+    LockContended(PathBuf),
+    /// Waiting for a lock exceeded the configured timeout (ARIA_MOVE_LOCK_TIMEOUT).
+    #[error("Timed out waiting for lock: {0}")]
+    LockTimeout(PathBuf),
+    /// Copying data failed; `phase` names the step (copy, resume, rename, rsync, upload, ...).
+    #[error("Copy failed during {phase} for {path}: {context}")]
+    CopyFailed {
+        path: PathBuf,
+        phase: String,
+        context: String,
+    },
+    /// The copied data does not match the source (size check after the copy).
+    #[error("Verification failed for {path}: expected {expected} bytes, got {actual} bytes")]
+    VerificationFailed {
+        path: PathBuf,
+        expected: u64,
+        actual: u64,
+    },
+    /// A file does not match its expected SHA-256: the one in its `.sha256` sidecar (handler
+    /// `verify-sha256`), or the local source's for an uploaded remote copy.
+    #[error("Checksum mismatch for {path}: expected {expected}, file hashes to {actual}")]
+    ChecksumMismatch {
+        path: PathBuf,
+        expected: String,
+        actual: String,
+    },
+    /// The destination cannot be reached or prepared (missing mount, remote host down, ...).
+    #[error("Destination unavailable {dest}: {context}")]
+    DestinationUnavailable { dest: PathBuf, context: String },
+
+    // --- Wrapped details ---
+    /// Other I/O failure. `kind` is the `io::ErrorKind` name; `message` includes op and path.
+    #[error("{message}")]
+    Io {
+        op: String,
+        path: Option<PathBuf>,
+        kind: String,
+        message: String,
+        hint: Option<String>,
+    },
+    /// Failure without a more specific class (message includes the full context chain).
+    #[error("{0}")]
+    Other(String),
+
     // --- Resolution-specific errors ---
     /// Provided path exists but is not a regular file (e.g., directory, symlink if disallowed).
     #[error("Provided path is not a regular file: {0}")]
@@ -45,6 +99,34 @@ pub enum AriaMoveError {
     /// Download base missing or not a directory.
     #[error("Download base invalid: {0}")]
     BaseInvalid(PathBuf),
+    /// Path is excluded by download_base/.ariamoveignore.
+    #[error("Path is excluded by .ariamoveignore: {0}")]
+    Ignored(PathBuf),
+    /// The destination already exists and the duplicate policy is `skip` (source left in place).
+    #[error("Destination already exists, skipped: {0}")]
+    DestinationExists(PathBuf),
+    /// `completed_base/<name>` is a symlink and `dest_symlink_policy` is `error`.
+    #[error("Destination is a symlink, refusing to move into it: {0}")]
+    DestinationIsSymlink(PathBuf),
+    /// The source is not beneath download_base (`require_source_in_base`).
+    #[error("Source {path} is outside the download base {base}")]
+    OutsideBase { path: PathBuf, base: PathBuf },
+    /// The source already lies inside completed_base; moving it would only rename it in place.
+    #[error("Source is already inside completed_base: {0}")]
+    SourceInCompletedBase(PathBuf),
+    /// No config file exists and automatic template creation is off (`--no-auto-config`).
+    #[error("Config file not found: {0}")]
+    ConfigMissing(PathBuf),
+    /// The source was written to while it was being copied; the copy was discarded.
+    #[error("Source changed while it was being copied: {0}")]
+    SourceChanged(PathBuf),
+    /// `content_sanity_check`: the file ends in an all-zero region, i.e. it looks preallocated
+    /// but not fully written.
+    #[error("File looks incomplete (all-zero tail): {0}")]
+    LooksIncomplete(PathBuf),
+    /// A bug: aria_move panicked. The backtrace goes to the log file only.
+    #[error("Internal error (panic): {0}")]
+    InternalPanic(String),
 }
 
 impl AriaMoveError {
@@ -55,10 +137,69 @@ impl AriaMoveError {
             AriaMoveError::PermissionDenied { .. } => "permission_denied",
             AriaMoveError::InsufficientSpace { .. } => "insufficient_space",
             AriaMoveError::Interrupted => "interrupted",
+            AriaMoveError::LockContended(_) => "lock_contended",
+            AriaMoveError::LockTimeout(_) => "lock_timeout",
+            AriaMoveError::CopyFailed { .. } => "copy_failed",
+            AriaMoveError::VerificationFailed { .. } => "verification_failed",
+            AriaMoveError::ChecksumMismatch { .. } => "checksum_mismatch",
+            AriaMoveError::DestinationUnavailable { .. } => "destination_unavailable",
+            AriaMoveError::Io { .. } => "io",
+            AriaMoveError::Other(_) => "other",
             AriaMoveError::ProvidedNotFile(_) => "provided_not_file",
             AriaMoveError::Disappeared(_) => "disappeared",
             AriaMoveError::NoneFound(_) => "none_found",
             AriaMoveError::BaseInvalid(_) => "base_invalid",
+            AriaMoveError::Ignored(_) => "ignored",
+            AriaMoveError::DestinationExists(_) => "destination_exists",
+            AriaMoveError::DestinationIsSymlink(_) => "destination_is_symlink",
+            AriaMoveError::OutsideBase { .. } => "outside_base",
+            AriaMoveError::SourceInCompletedBase(_) => "source_in_completed_base",
+            AriaMoveError::ConfigMissing(_) => "config_missing",
+            AriaMoveError::SourceChanged(_) => "source_changed",
+            AriaMoveError::LooksIncomplete(_) => "looks_incomplete",
+            AriaMoveError::InternalPanic(_) => "internal_panic",
+        }
+    }
+
+    /// Short, actionable suggestion for the failure, if one is known.
+    pub fn hint(&self) -> Option<&str> {
+        match self {
+            AriaMoveError::SourceNotFound(_) => Some(text(Msg::HintNotFound)),
+            AriaMoveError::PermissionDenied { .. } => Some(text(Msg::HintPermissionDenied)),
+            AriaMoveError::InsufficientSpace { .. } => Some(text(Msg::HintInsufficientSpace)),
+            AriaMoveError::Ignored(_) => Some(text(Msg::HintIgnored)),
+            AriaMoveError::LockContended(_) => Some(text(Msg::HintLockContended)),
+            AriaMoveError::LockTimeout(_) => Some(text(Msg::HintLockTimeout)),
+            AriaMoveError::VerificationFailed { .. } => Some(text(Msg::HintVerificationFailed)),
+            AriaMoveError::DestinationUnavailable { .. } => {
+                Some(text(Msg::HintDestinationUnavailable))
+            }
+            AriaMoveError::OutsideBase { .. } => Some(text(Msg::HintOutsideBase)),
+            AriaMoveError::ConfigMissing(_) => Some(text(Msg::HintConfigMissing)),
+            AriaMoveError::SourceChanged(_) => Some(text(Msg::HintSourceChanged)),
+            AriaMoveError::LooksIncomplete(_) => Some(text(Msg::HintLooksIncomplete)),
+            AriaMoveError::InternalPanic(_) => Some(text(Msg::HintInternalPanic)),
+            AriaMoveError::Io { hint, .. } => hint.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Wrap an io::Error from `op` on `path`; the message carries platform-aware hints.
+    /// Permission errors map to `PermissionDenied`, everything else to `Io`.
+    pub fn from_io_op(op: &str, path: &Path, err: &io::Error) -> AriaMoveError {
+        let message = io_message(op, path, err);
+        if err.kind() == io::ErrorKind::PermissionDenied {
+            return AriaMoveError::PermissionDenied {
+                path: path.to_path_buf(),
+                context: message,
+            };
+        }
+        AriaMoveError::Io {
+            op: op.to_string(),
+            path: Some(path.to_path_buf()),
+            kind: format!("{:?}", err.kind()),
+            message,
+            hint: io_hint(err).map(str::to_string),
         }
     }
 
@@ -79,6 +220,91 @@ impl AriaMoveError {
     }
 }
 
+/// Convert an internal anyhow error at the public API boundary: typed errors pass through,
+/// an io::Error anywhere in the chain becomes `Io`, everything else `Other`.
+impl From<anyhow::Error> for AriaMoveError {
+    fn from(e: anyhow::Error) -> Self {
+        if let Some(am) = e.downcast_ref::<AriaMoveError>() {
+            return am.clone();
+        }
+        let message = format!("{e:#}");
+        match e.chain().find_map(|c| c.downcast_ref::<io::Error>()) {
+            Some(ioe) => AriaMoveError::Io {
+                op: "io".to_string(),
+                path: None,
+                kind: format!("{:?}", ioe.kind()),
+                message,
+                hint: io_hint(ioe).map(str::to_string),
+            },
+            None => AriaMoveError::Other(message),
+        }
+    }
+}
+
+/// Human-friendly message: op and path, the io error, a hint and the OS code when known.
+pub(crate) fn io_message(op: &str, path: &Path, e: &io::Error) -> String {
+    let mut msg = format!("{} '{}': {}", op, path.display(), e);
+    if let Some(h) = io_hint(e) {
+        msg.push_str(" — ");
+        msg.push_str(h);
+    }
+    if let Some(code) = e.raw_os_error() {
+        // Include OS code for diagnostics
+        msg.push_str(&format!(" [os code: {}]", code));
+    }
+    msg
+}
+
+/// Platform-aware hint for an io::Error (raw OS code first, then ErrorKind).
+pub(crate) fn io_hint(e: &io::Error) -> Option<&'static str> {
+    if let Some(code) = e.raw_os_error() {
+        return os_code_hint(code);
+    }
+    // Fallback to Kind-based hints
+    match e.kind() {
+        io::ErrorKind::PermissionDenied => Some(text(Msg::HintPermissionDenied)),
+        io::ErrorKind::NotFound => Some(text(Msg::HintNotFound)),
+        io::ErrorKind::AlreadyExists => Some(text(Msg::HintAlreadyExistsRemove)),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut => Some(text(Msg::HintBusyTimedOut)),
+        _ => None,
+    }
+}
+
+#[cfg(unix)]
+fn os_code_hint(code: i32) -> Option<&'static str> {
+    match code {
+        libc::EACCES | libc::EPERM => Some(text(Msg::HintPermissionDenied)),
+        libc::EXDEV => Some(text(Msg::HintCrossDevice)),
+        libc::EBUSY => Some(text(Msg::HintBusy)),
+        libc::ENOENT => Some(text(Msg::HintNotFound)),
+        libc::EEXIST => Some(text(Msg::HintAlreadyExists)),
+        libc::ENOSPC => Some(text(Msg::HintNoSpace)),
+        libc::EROFS => Some(text(Msg::HintReadOnly)),
+        libc::ELOOP => Some(text(Msg::HintSymlinkLoop)),
+        libc::ENAMETOOLONG => Some(text(Msg::HintNameTooLong)),
+        libc::EMFILE => Some(text(Msg::HintProcessFdLimit)),
+        libc::ENFILE => Some(text(Msg::HintSystemFileTable)),
+        _ => None,
+    }
+}
+
+#[cfg(windows)]
+fn os_code_hint(code: i32) -> Option<&'static str> {
+    // Common Win32 errors
+    match code {
+        5 => Some(text(Msg::HintAccessDenied)), // ERROR_ACCESS_DENIED
+        17 => Some(text(Msg::HintNotSameDevice)), // ERROR_NOT_SAME_DEVICE
+        32 => Some(text(Msg::HintSharingViolation)), // ERROR_SHARING_VIOLATION
+        2 | 3 => Some(text(Msg::HintNotFound)), // FILE/ PATH NOT FOUND
+        80 => Some(text(Msg::HintFileExists)),  // ERROR_FILE_EXISTS
+        112 => Some(text(Msg::HintDiskFull)),   // ERROR_DISK_FULL
+        19 => Some(text(Msg::HintWriteProtected)), // ERROR_WRITE_PROTECT
+        206 => Some(text(Msg::HintPathTooLong)), // ERROR_FILENAME_EXCED_RANGE
+        4 => Some(text(Msg::HintTooManyOpenFiles)), // ERROR_TOO_MANY_OPEN_FILES
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,6 +335,49 @@ mod tests {
             "insufficient_space"
         );
         assert_eq!(AriaMoveError::Interrupted.code(), "interrupted");
+        assert_eq!(
+            AriaMoveError::LockContended(PathBuf::from("/x")).code(),
+            "lock_contended"
+        );
+        assert_eq!(
+            AriaMoveError::LockTimeout(PathBuf::from("/x")).code(),
+            "lock_timeout"
+        );
+        assert_eq!(
+            AriaMoveError::CopyFailed {
+                path: PathBuf::from("/x"),
+                phase: "rename".into(),
+                context: "busy".into()
+            }
+            .code(),
+            "copy_failed"
+        );
+        assert_eq!(
+            AriaMoveError::VerificationFailed {
+                path: PathBuf::from("/x"),
+                expected: 10,
+                actual: 4
+            }
+            .code(),
+            "verification_failed"
+        );
+        assert_eq!(
+            AriaMoveError::ChecksumMismatch {
+                path: PathBuf::from("/x.iso"),
+                expected: "ab".into(),
+                actual: "cd".into()
+            }
+            .code(),
+            "checksum_mismatch"
+        );
+        assert_eq!(
+            AriaMoveError::DestinationUnavailable {
+                dest: PathBuf::from("/mnt/nas"),
+                context: "not mounted".into()
+            }
+            .code(),
+            "destination_unavailable"
+        );
         assert_eq!(
             AriaMoveError::ProvidedNotFile(PathBuf::from("/x")).code(),
             "provided_not_file"
@@ -125,6 +394,46 @@ mod tests {
             AriaMoveError::BaseInvalid(PathBuf::from("/db")).code(),
             "base_invalid"
         );
+        assert_eq!(
+            AriaMoveError::Ignored(PathBuf::from("/db/manual")).code(),
+            "ignored"
+        );
+        assert_eq!(
+            AriaMoveError::DestinationExists(PathBuf::from("/done/a.mkv")).code(),
+            "destination_exists"
+        );
+        assert_eq!(
+            AriaMoveError::DestinationIsSymlink(PathBuf::from("/done/a.mkv")).code(),
+            "destination_is_symlink"
+        );
+        assert_eq!(
+            AriaMoveError::OutsideBase {
+                path: PathBuf::from("/etc/passwd"),
+                base: PathBuf::from("/db")
+            }
+            .code(),
+            "outside_base"
+        );
+        assert_eq!(
+            AriaMoveError::SourceInCompletedBase(PathBuf::from("/done/a.mkv")).code(),
+            "source_in_completed_base"
+        );
+        assert_eq!(
+            AriaMoveError::ConfigMissing(PathBuf::from("/etc/aria_move/config.xml")).code(),
+            "config_missing"
+        );
+        assert_eq!(
+            AriaMoveError::SourceChanged(PathBuf::from("/db/a.mkv")).code(),
+            "source_changed"
+        );
+        assert_eq!(
+            AriaMoveError::LooksIncomplete(PathBuf::from("/db/a.mkv")).code(),
+            "looks_incomplete"
+        );
+        assert_eq!(
+            AriaMoveError::InternalPanic("boom".into()).code(),
+            "internal_panic"
+        );
     }
 
     #[test]
@@ -139,6 +448,63 @@ mod tests {
         let inner = &v["PermissionDenied"];
         assert_eq!(inner["path"], json!(PathBuf::from("/x")));
         assert_eq!(inner["context"], json!("ro"));
+
+        let e = AriaMoveError::VerificationFailed {
+            path: PathBuf::from("/x"),
+            expected: 10,
+            actual: 4,
+        };
+        let v = to_value(&e).unwrap();
+        assert_eq!(v["VerificationFailed"]["expected"], json!(10));
+        assert_eq!(v["VerificationFailed"]["actual"], json!(4));
+    }
+
+    #[test]
+    fn converts_anyhow_at_api_boundary() {
+        use anyhow::Context;
+
+        let typed = anyhow::Error::from(AriaMoveError::Interrupted).context("while moving");
+        assert_eq!(AriaMoveError::from(typed), AriaMoveError::Interrupted);
+
+        let io = Err::<(), _>(io::Error::from(io::ErrorKind::NotFound))
+            .context("stat /x")
+            .unwrap_err();
+        match AriaMoveError::from(io) {
+            AriaMoveError::Io {
+                kind,
+                message,
+                hint,
+                ..
+            } => {
+                assert_eq!(kind, "NotFound");
+                assert!(message.starts_with("stat /x"));
+                assert!(hint.is_some());
+            }
+            other => panic!("expected Io, got {other:?}"),
+        }
+
+        let other = AriaMoveError::from(anyhow::anyhow!("boom"));
+        assert_eq!(other, AriaMoveError::Other("boom".into()));
+        assert_eq!(other.hint(), None);
+    }
+
+    #[test]
+    fn io_op_errors_carry_hints() {
+        let e = AriaMoveError::from_io_op(
+            "open",
+            Path::new("/x"),
+            &io::Error::from(io::ErrorKind::AlreadyExists),
+        );
+        assert_eq!(e.code(), "io");
+        assert_eq!(e.hint(), Some(text(Msg::HintAlreadyExistsRemove)));
+        assert!(e.to_string().starts_with("open '/x'"));
+
+        let e = AriaMoveError::from_io_op(
+            "create",
+            Path::new("/ro"),
+            &io::Error::from(io::ErrorKind::PermissionDenied),
+        );
+        assert_eq!(e.code(), "permission_denied");
     }
 
     #[test]
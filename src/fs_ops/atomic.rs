@@ -1,11 +1,18 @@
 //! Atomic rename helper.
 //! - Performs a rename with context-rich errors.
 //! - On Windows, removes an existing destination first (RenameFile doesn’t overwrite).
-//! - On Unix, best-effort fsync of the destination directory after rename.
+//! - On Unix, best-effort fsync of the destination directory after rename; on Windows the
+//!   renamed file is flushed (FlushFileBuffers) instead. `try_atomic_move_with` follows
+//!   `fsync_policy` (only `full` syncs after the rename).
+//! - `try_atomic_move_beneath` resolves the destination beneath a base directory (see
+//!   beneath.rs), so a symlink swapped into the destination path cannot redirect it.
 
 use anyhow::{Context, Result};
 use tracing::debug;
 
+use super::fs_trace::traced_to;
+use crate::config::types::FsyncPolicy;
+
 /// Outcome of an attempted atomic move.
 /// - Renamed: atomic rename completed on the same filesystem.
 /// - CrossDevice: pre-detected cross-filesystem move; caller should copy instead.
@@ -18,16 +25,41 @@ use std::fs;
 use std::path::Path;
 
 pub fn try_atomic_move(src: &Path, dst: &Path) -> Result<MoveOutcome> {
-    // Unix: pre-detect cross-device moves to avoid a failing rename with EXDEV.
-    #[cfg(unix)]
+    try_atomic_move_with(src, dst, FsyncPolicy::Full)
+}
+
+/// `try_atomic_move` with an explicit fsync policy for the directories (or, on Windows, the
+/// file) holding the rename.
+pub(super) fn try_atomic_move_with(
+    src: &Path,
+    dst: &Path,
+    policy: FsyncPolicy,
+) -> Result<MoveOutcome> {
+    atomic_move(None, src, dst, policy)
+}
+
+/// `try_atomic_move_with` with `dst` resolved beneath `base` (normally completed_base).
+pub(super) fn try_atomic_move_beneath(
+    base: &Path,
+    src: &Path,
+    dst: &Path,
+    policy: FsyncPolicy,
+) -> Result<MoveOutcome> {
+    atomic_move(Some(base), src, dst, policy)
+}
+
+fn atomic_move(
+    base: Option<&Path>,
+    src: &Path,
+    dst: &Path,
+    policy: FsyncPolicy,
+) -> Result<MoveOutcome> {
+    // Pre-detect cross-device moves to avoid a failing rename (EXDEV / NOT_SAME_DEVICE).
+    // Detection errors fall through to the rename, which reports the real problem.
+    if let (Some(src_parent), Some(dst_parent)) = (src.parent(), dst.parent())
+        && let Ok(false) = crate::fsys::same_filesystem(src_parent, dst_parent)
     {
-        use std::os::unix::fs::MetadataExt;
-        if let (Some(src_parent), Some(dst_parent)) = (src.parent(), dst.parent())
-            && let (Ok(s_meta), Ok(d_meta)) = (fs::metadata(src_parent), fs::metadata(dst_parent))
-            && s_meta.dev() != d_meta.dev()
-        {
-            return Ok(MoveOutcome::CrossDevice);
-        }
+        return Ok(MoveOutcome::CrossDevice);
     }
 
     // Windows: ensure destination path is free (rename doesn’t overwrite there).
@@ -50,8 +82,15 @@ pub fn try_atomic_move(src: &Path, dst: &Path) -> Result<MoveOutcome> {
     }
 
     // Perform the atomic rename.
-    fs::rename(src, dst)
-        .with_context(|| format!("atomic rename '{}' -> '{}'", src.display(), dst.display()))?;
+    traced_to("rename", src, Some(dst), || match base {
+        Some(base) => super::beneath::rename(base, src, dst),
+        None => fs::rename(src, dst),
+    })
+    .with_context(|| format!("atomic rename '{}' -> '{}'", src.display(), dst.display()))?;
+
+    if !policy.syncs_dirs() {
+        return Ok(MoveOutcome::Renamed);
+    }
 
     // Unix: fsync directories to persist the rename (best-effort).
     #[cfg(unix)]
@@ -70,5 +109,13 @@ pub fn try_atomic_move(src: &Path, dst: &Path) -> Result<MoveOutcome> {
         }
     }
 
+    // Windows: directories cannot be synced; flush the renamed file instead (best-effort).
+    #[cfg(windows)]
+    if fs::symlink_metadata(dst).is_ok_and(|m| m.is_file())
+        && let Err(e) = super::util::flush_file(dst)
+    {
+        debug!(error = %e, path = %dst.display(), "best-effort FlushFileBuffers(dst) failed");
+    }
+
     Ok(MoveOutcome::Renamed)
 }
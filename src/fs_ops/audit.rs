@@ -0,0 +1,371 @@
+//! Hash-chained audit log (`audit_log`), for users who must show that the move history
+//! was not edited after the fact.
+//! - Every real (non dry-run) `move_entry` appends one JSON line: sequence number, time,
+//!   outcome, source, destination or error, and `prev` = the hash of the previous record.
+//! - `hash` is the SHA-256 of the record's JSON without the `hash` field, so changing,
+//!   removing or reordering a record breaks the chain from that point on.
+//! - `<audit_log>.head` holds the sequence number and hash of the last record; a log that
+//!   ends before the head was cut off.
+//! - `aria_move audit verify` (`verify_audit_log`) checks all of this.
+//! - `aria_move history` (`audit_history`) lists the last records; `aria_move undo` appends
+//!   an `undone` record for the move it reverses.
+//!
+//! Notes:
+//! - The chain has no secret key: it exposes edits and truncation, not a writer who rebuilds
+//!   every hash. Ship the head (or the whole log) elsewhere for stronger guarantees.
+//! - Appends hold an exclusive lock on the log, so concurrent runs chain correctly.
+//! - Audit failures are logged; they never fail the move itself.
+//! - Ignored sources are not recorded (they were never candidates for a move).
+
+use anyhow::{Context, Result, bail};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::config::types::Config;
+use crate::errors::AriaMoveError;
+
+use super::digest::{Sha256, to_hex};
+
+/// `prev` of the first record.
+const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Hashed part of a record (field order is part of the format).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Body {
+    seq: u64,
+    time: String,
+    outcome: String,
+    src: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dest: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    prev: String,
+}
+
+impl Body {
+    fn hash(&self) -> Result<String> {
+        let mut h = Sha256::default();
+        h.update(&serde_json::to_vec(self)?);
+        Ok(to_hex(&h.finalize()))
+    }
+}
+
+/// One line of the log.
+#[derive(Debug, Serialize, Deserialize)]
+struct Record {
+    #[serde(flatten)]
+    body: Body,
+    hash: String,
+}
+
+/// One record as `audit_history` returns it (the chain fields are left out).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub seq: u64,
+    /// RFC 3339, local time
+    pub time: String,
+    /// `moved`, `failed` or `undone`
+    pub outcome: String,
+    pub src: PathBuf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dest: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Content of `<audit_log>.head`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Head {
+    seq: u64,
+    hash: String,
+}
+
+/// Result of `verify_audit_log`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditReport {
+    pub path: PathBuf,
+    /// Number of records checked
+    pub records: u64,
+    /// Hash of the last record (what the head file vouches for)
+    pub last_hash: Option<String>,
+}
+
+/// `<audit_log>.head`.
+fn head_path(log: &Path) -> PathBuf {
+    let mut name = log.as_os_str().to_os_string();
+    name.push(".head");
+    PathBuf::from(name)
+}
+
+/// Append the outcome of a move of `src` to `config.audit_log` (if set). Best-effort.
+pub(super) fn record(config: &Config, src: &Path, result: &Result<PathBuf>) {
+    let Some(log) = config.audit_log.as_deref() else {
+        return;
+    };
+    if config.dry_run {
+        return;
+    }
+    let (outcome, dest, error) = match result {
+        Ok(dest) => ("moved", Some(dest.to_string_lossy().into_owned()), None),
+        Err(e) => match e.downcast_ref::<AriaMoveError>() {
+            Some(AriaMoveError::Ignored(_)) => return,
+            _ => ("failed", None, Some(format!("{e:#}"))),
+        },
+    };
+    if let Err(e) = append(log, outcome, src, dest, error) {
+        warn!(path = %log.display(), error = %format!("{e:#}"), "could not write audit record");
+    }
+}
+
+pub(super) fn append(
+    log: &Path,
+    outcome: &str,
+    src: &Path,
+    dest: Option<String>,
+    error: Option<String>,
+) -> Result<()> {
+    if let Some(dir) = log.parent()
+        && !dir.as_os_str().is_empty()
+    {
+        fs::create_dir_all(dir).with_context(|| format!("create '{}'", dir.display()))?;
+    }
+    let mut file = OpenOptions::new()
+        .read(true)
+        .append(true)
+        .create(true)
+        .open(log)
+        .with_context(|| format!("open audit log '{}'", log.display()))?;
+    file.lock_exclusive()
+        .with_context(|| format!("lock audit log '{}'", log.display()))?;
+
+    let (seq, prev) = match last_line(&mut file)? {
+        Some(line) => {
+            let last: Record = serde_json::from_str(&line)
+                .context("last audit record is unreadable; run `aria_move audit verify`")?;
+            (last.body.seq + 1, last.hash)
+        }
+        None => (1, GENESIS.to_string()),
+    };
+    let body = Body {
+        seq,
+        time: chrono::Local::now().to_rfc3339(),
+        outcome: outcome.to_string(),
+        src: src.to_string_lossy().into_owned(),
+        dest,
+        error,
+        prev,
+    };
+    let record = Record {
+        hash: body.hash()?,
+        body,
+    };
+    let mut line = serde_json::to_vec(&record)?;
+    line.push(b'\n');
+    file.write_all(&line)?;
+    file.sync_data()?;
+
+    let head = head_path(log);
+    let tmp = head.with_extension("head.tmp");
+    let data = serde_json::to_vec(&Head {
+        seq: record.body.seq,
+        hash: record.hash,
+    })?;
+    fs::write(&tmp, data).with_context(|| format!("write '{}'", tmp.display()))?;
+    fs::rename(&tmp, &head).with_context(|| format!("rename to '{}'", head.display()))?;
+    Ok(())
+}
+
+/// Last non-empty line of `file`, read backwards in growing chunks.
+fn last_line(file: &mut File) -> io::Result<Option<String>> {
+    let len = file.seek(SeekFrom::End(0))?;
+    let mut window = 4096u64;
+    loop {
+        let start = len.saturating_sub(window);
+        file.seek(SeekFrom::Start(start))?;
+        let mut buf = Vec::new();
+        Read::by_ref(file).take(len - start).read_to_end(&mut buf)?;
+        let trimmed = buf.strip_suffix(b"\n").unwrap_or(&buf);
+        if let Some(pos) = trimmed.iter().rposition(|&b| b == b'\n') {
+            return Ok(Some(
+                String::from_utf8_lossy(&trimmed[pos + 1..]).into_owned(),
+            ));
+        }
+        if start == 0 {
+            return Ok((!trimmed.is_empty()).then(|| String::from_utf8_lossy(trimmed).into_owned()));
+        }
+        window *= 4;
+    }
+}
+
+/// The last `n` records of `log` (oldest first) as raw JSON; unreadable lines are skipped.
+/// Reads at most the final 256 KiB.
+pub(super) fn tail(log: &Path, n: usize) -> io::Result<Vec<serde_json::Value>> {
+    const WINDOW: u64 = 256 * 1024;
+    let mut file = File::open(log)?;
+    let len = file.seek(SeekFrom::End(0))?;
+    let start = len.saturating_sub(WINDOW);
+    file.seek(SeekFrom::Start(start))?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf)?;
+    let buf = String::from_utf8_lossy(&buf);
+    let mut lines: Vec<&str> = buf.lines().collect();
+    if start > 0 && !lines.is_empty() {
+        // The window most likely starts inside a record.
+        lines.remove(0);
+    }
+    let records: Vec<serde_json::Value> = lines
+        .iter()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect();
+    let skip = records.len().saturating_sub(n);
+    Ok(records.into_iter().skip(skip).collect())
+}
+
+/// The last `limit` records of `log`, oldest first. Unreadable lines are skipped, and only
+/// the final 256 KiB are read (see `tail`).
+pub fn audit_history(log: &Path, limit: usize) -> Result<Vec<AuditEntry>> {
+    let records =
+        tail(log, limit).with_context(|| format!("read audit log '{}'", log.display()))?;
+    Ok(records
+        .into_iter()
+        .filter_map(|v| serde_json::from_value(v).ok())
+        .collect())
+}
+
+/// Check the chain in `log` and its head file. Fails on the first edited, missing,
+/// reordered or unreadable record, and when the log ends before the head.
+pub fn verify_audit_log(log: &Path) -> Result<AuditReport> {
+    let file = File::open(log).with_context(|| format!("open audit log '{}'", log.display()))?;
+    let mut records = 0u64;
+    let mut prev = GENESIS.to_string();
+    for (idx, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.with_context(|| format!("read '{}'", log.display()))?;
+        let lineno = idx + 1;
+        if line.trim().is_empty() {
+            bail!("audit log broken at line {lineno}: empty line");
+        }
+        let record: Record = serde_json::from_str(&line)
+            .with_context(|| format!("audit log broken at line {lineno}: unreadable record"))?;
+        if record.body.seq != records + 1 {
+            bail!(
+                "audit log broken at line {lineno}: expected record {}, found {} (records removed or reordered)",
+                records + 1,
+                record.body.seq
+            );
+        }
+        if record.body.prev != prev {
+            bail!("audit log broken at line {lineno}: does not chain to the previous record");
+        }
+        if record.body.hash()? != record.hash {
+            bail!("audit log broken at line {lineno}: record was modified");
+        }
+        records += 1;
+        prev = record.hash;
+    }
+    let last_hash = (records > 0).then_some(prev);
+
+    let head_file = head_path(log);
+    match fs::read(&head_file) {
+        Ok(data) => {
+            let head: Head = serde_json::from_slice(&data)
+                .with_context(|| format!("unreadable audit head '{}'", head_file.display()))?;
+            if head.seq > records {
+                bail!(
+                    "audit log truncated: head records {} entries, log has {records}",
+                    head.seq
+                );
+            }
+            if head.seq < records {
+                bail!(
+                    "audit log has {} entries past its head (record {}); they were not written by a completed append",
+                    records - head.seq,
+                    head.seq
+                );
+            }
+            if Some(&head.hash) != last_hash.as_ref() {
+                bail!("audit log does not match its head: the last record was replaced");
+            }
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound && records == 0 => {}
+        Err(e) => {
+            return Err(e).with_context(|| format!("read audit head '{}'", head_file.display()));
+        }
+    }
+    Ok(AuditReport {
+        path: log.to_path_buf(),
+        records,
+        last_hash,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write(log: &Path, n: usize) {
+        for i in 0..n {
+            let src = PathBuf::from(format!("/in/item{i}"));
+            append(log, "moved", &src, Some(format!("/out/item{i}")), None).unwrap();
+        }
+    }
+
+    #[test]
+    fn appended_chain_verifies() {
+        let td = tempdir().unwrap();
+        let log = td.path().join("audit").join("moves.jsonl");
+        write(&log, 3);
+        let report = verify_audit_log(&log).unwrap();
+        assert_eq!(report.records, 3);
+        assert!(report.last_hash.is_some());
+    }
+
+    #[test]
+    fn history_lists_the_last_records_oldest_first() {
+        let td = tempdir().unwrap();
+        let log = td.path().join("moves.jsonl");
+        write(&log, 3);
+        let entries = audit_history(&log, 2).unwrap();
+        let seqs: Vec<u64> = entries.iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, [2, 3]);
+        assert_eq!(entries[1].outcome, "moved");
+        assert_eq!(entries[1].src, Path::new("/in/item2"));
+        assert_eq!(entries[1].dest.as_deref(), Some(Path::new("/out/item2")));
+    }
+
+    #[test]
+    fn last_line_handles_long_records() {
+        let td = tempdir().unwrap();
+        let log = td.path().join("moves.jsonl");
+        let long = "x".repeat(20_000);
+        append(&log, "failed", Path::new(&long), None, Some("boom".into())).unwrap();
+        append(&log, "moved", Path::new(&long), Some(long.clone()), None).unwrap();
+        assert_eq!(verify_audit_log(&log).unwrap().records, 2);
+    }
+
+    #[test]
+    fn edits_and_truncation_are_detected() {
+        let td = tempdir().unwrap();
+        let log = td.path().join("moves.jsonl");
+        write(&log, 3);
+        let original = fs::read_to_string(&log).unwrap();
+
+        fs::write(&log, original.replacen("/out/item1", "/out/other", 1)).unwrap();
+        let err = verify_audit_log(&log).unwrap_err().to_string();
+        assert!(err.contains("line 2") && err.contains("modified"), "{err}");
+
+        let lines: Vec<&str> = original.lines().collect();
+        fs::write(&log, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        let err = verify_audit_log(&log).unwrap_err().to_string();
+        assert!(err.contains("removed or reordered"), "{err}");
+
+        fs::write(&log, format!("{}\n{}\n", lines[0], lines[1])).unwrap();
+        let err = verify_audit_log(&log).unwrap_err().to_string();
+        assert!(err.contains("truncated"), "{err}");
+    }
+}
@@ -0,0 +1,271 @@
+//! Copy-method calibration (`aria_move bench-dest`).
+//! - Writes a sample file in download_base and times each way of getting it into
+//!   completed_base: rename, reflink (FICLONE / clonefile), in-kernel copy (copy_file_range)
+//!   and buffered streaming.
+//! - `save_bench` stores the report in the data dir (`paths::default_bench_path`); later
+//!   copies between the same bases start with the fastest method measured there
+//!   (`preferred_method`).
+//!
+//! Notes:
+//! - A rename that works always wins and is tried first by every move anyway; it is reported
+//!   so it is clear copies are the exception. Only the copy method is chosen from the results.
+//! - The sample was just written, so it is read from the page cache: the numbers compare the
+//!   write paths into completed_base, not the source disk.
+//! - Sample files are named like resume temps, so `clean` removes them after a crash.
+//! - Without a stored report for the configured bases, copies keep the built-in order
+//!   (`CopyMethod::Auto`: clonefile on macOS, copy_file_range on Linux, then streaming).
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Instant;
+use tracing::{debug, info};
+
+use crate::config::paths::default_bench_path;
+use crate::config::types::Config;
+
+use super::io_copy;
+use super::remote::is_remote;
+
+/// What the builtin copy engine tries first (unsupported methods fall back down this list).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CopyMethod {
+    /// Built-in order: clonefile (macOS), copy_file_range (Linux), streaming
+    #[default]
+    Auto,
+    /// Copy-on-write clone (FICLONE on Linux, clonefile on macOS)
+    Reflink,
+    /// In-kernel copy (copy_file_range, Linux)
+    Kernel,
+    /// Buffered read/write in user space
+    Streaming,
+}
+
+impl fmt::Display for CopyMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            CopyMethod::Auto => "auto",
+            CopyMethod::Reflink => "reflink",
+            CopyMethod::Kernel => "kernel",
+            CopyMethod::Streaming => "streaming",
+        };
+        f.write_str(s)
+    }
+}
+
+/// One measured copy method.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MethodResult {
+    pub method: CopyMethod,
+    /// Throughput in MiB/s, or None if the method is not available between the bases.
+    pub mib_per_sec: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of `bench_dest`, as stored in `bench.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub download_base: PathBuf,
+    pub completed_base: PathBuf,
+    pub sample_bytes: u64,
+    /// True if a rename between the bases works (same filesystem): moves never copy then.
+    pub rename: bool,
+    pub methods: Vec<MethodResult>,
+    /// Fastest available method; used by later copies between these bases.
+    pub best: CopyMethod,
+    /// RFC 3339 time of the measurement.
+    pub measured_at: String,
+}
+
+/// Time every copy method from download_base into completed_base with a `sample_bytes` file.
+pub fn bench_dest(config: &Config, sample_bytes: u64) -> Result<BenchReport> {
+    if is_remote(&config.completed_base) {
+        bail!("bench-dest needs a local completed_base");
+    }
+    let tag = std::process::id();
+    let sample = config
+        .download_base
+        .join(format!(".aria_move.resume.bench-{tag}.tmp"));
+    write_sample(&sample, sample_bytes)?;
+    let result = measure(config, &sample, sample_bytes, tag);
+    let _ = fs::remove_file(&sample);
+    result
+}
+
+fn measure(config: &Config, sample: &Path, sample_bytes: u64, tag: u32) -> Result<BenchReport> {
+    let rename = rename_works(config, tag);
+    let mut methods = Vec::new();
+    for method in [
+        CopyMethod::Reflink,
+        CopyMethod::Kernel,
+        CopyMethod::Streaming,
+    ] {
+        let dest = config
+            .completed_base
+            .join(format!(".aria_move.resume.bench-{tag}-{method}.tmp"));
+        let start = Instant::now();
+        let res = io_copy::copy_with_method_only(sample, &dest, method);
+        let secs = start.elapsed().as_secs_f64().max(1e-6);
+        let _ = fs::remove_file(&dest);
+        let result = match res {
+            Ok(bytes) if bytes == sample_bytes => MethodResult {
+                method,
+                mib_per_sec: Some(bytes as f64 / (1024.0 * 1024.0) / secs),
+                error: None,
+            },
+            Ok(bytes) => MethodResult {
+                method,
+                mib_per_sec: None,
+                error: Some(format!("short copy: {bytes} of {sample_bytes} bytes")),
+            },
+            Err(e) => MethodResult {
+                method,
+                mib_per_sec: None,
+                error: Some(e.to_string()),
+            },
+        };
+        debug!(method = %method, mib_per_sec = ?result.mib_per_sec, error = ?result.error, "bench");
+        methods.push(result);
+    }
+    let best = methods
+        .iter()
+        .filter_map(|r| r.mib_per_sec.map(|v| (r.method, v)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(method, _)| method)
+        .context("no copy method works between download_base and completed_base")?;
+    info!(best = %best, rename, "Measured copy methods");
+    Ok(BenchReport {
+        download_base: config.download_base.clone(),
+        completed_base: config.completed_base.clone(),
+        sample_bytes,
+        rename,
+        methods,
+        best,
+        measured_at: chrono::Local::now().to_rfc3339(),
+    })
+}
+
+/// Incompressible sample so neither compression nor sparse files flatter a method.
+fn write_sample(path: &Path, len: u64) -> Result<()> {
+    let mut f = File::create(path).with_context(|| format!("create {}", path.display()))?;
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut buf = vec![0u8; 1024 * 1024];
+    let mut left = len;
+    while left > 0 {
+        for chunk in buf.chunks_exact_mut(8) {
+            // xorshift64
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            chunk.copy_from_slice(&state.to_le_bytes());
+        }
+        let n = left.min(buf.len() as u64) as usize;
+        f.write_all(&buf[..n])
+            .with_context(|| format!("write {}", path.display()))?;
+        left -= n as u64;
+    }
+    f.sync_all()
+        .with_context(|| format!("fsync {}", path.display()))
+}
+
+fn rename_works(config: &Config, tag: u32) -> bool {
+    let probe = config
+        .download_base
+        .join(format!(".aria_move.resume.bench-{tag}-rename.tmp"));
+    let dest = config
+        .completed_base
+        .join(format!(".aria_move.resume.bench-{tag}-renamed.tmp"));
+    if fs::write(&probe, b"probe").is_err() {
+        return false;
+    }
+    let ok = fs::rename(&probe, &dest).is_ok();
+    let _ = fs::remove_file(if ok { &dest } else { &probe });
+    ok
+}
+
+/// Store `report` where `preferred_method` looks for it; returns the path written.
+pub fn save_bench(report: &BenchReport) -> Result<PathBuf> {
+    let path = default_bench_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("create {}", dir.display()))?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_vec_pretty(report)?)
+        .with_context(|| format!("write {}", tmp.display()))?;
+    fs::rename(&tmp, &path).with_context(|| format!("rename to {}", path.display()))?;
+    Ok(path)
+}
+
+/// The method `bench-dest` found fastest for the configured bases, else `Auto`.
+/// The stored report is read once per process.
+pub(super) fn preferred_method(config: &Config) -> CopyMethod {
+    static STORED: OnceLock<Option<BenchReport>> = OnceLock::new();
+    let stored = STORED.get_or_init(|| {
+        let path = default_bench_path().ok()?;
+        let data = fs::read(&path).ok()?;
+        serde_json::from_slice(&data)
+            .inspect_err(|e| debug!(path = %path.display(), error = %e, "ignoring bench file"))
+            .ok()
+    });
+    method_for(stored.as_ref(), config)
+}
+
+fn method_for(stored: Option<&BenchReport>, config: &Config) -> CopyMethod {
+    match stored {
+        Some(r)
+            if r.download_base == config.download_base
+                && r.completed_base == config.completed_base =>
+        {
+            r.best
+        }
+        _ => CopyMethod::Auto,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn bench_measures_streaming_and_cleans_up() {
+        let download = tempdir().unwrap();
+        let completed = tempdir().unwrap();
+        let cfg = Config::new(download.path(), completed.path());
+
+        let report = bench_dest(&cfg, 2 * 1024 * 1024).unwrap();
+        let streaming = report
+            .methods
+            .iter()
+            .find(|r| r.method == CopyMethod::Streaming)
+            .unwrap();
+        assert!(streaming.mib_per_sec.is_some());
+        assert_ne!(report.best, CopyMethod::Auto);
+        assert_eq!(fs::read_dir(download.path()).unwrap().count(), 0);
+        assert_eq!(fs::read_dir(completed.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn stored_method_applies_only_to_the_measured_bases() {
+        let cfg = Config::new("/in", "/out");
+        let report = BenchReport {
+            download_base: "/in".into(),
+            completed_base: "/out".into(),
+            sample_bytes: 1,
+            rename: false,
+            methods: Vec::new(),
+            best: CopyMethod::Streaming,
+            measured_at: String::new(),
+        };
+        assert_eq!(method_for(Some(&report), &cfg), CopyMethod::Streaming);
+        let other = Config::new("/in", "/elsewhere");
+        assert_eq!(method_for(Some(&report), &other), CopyMethod::Auto);
+        assert_eq!(method_for(None, &cfg), CopyMethod::Auto);
+    }
+}
@@ -0,0 +1,328 @@
+//! Destination operations resolved beneath a base directory (normally completed_base).
+//! - Linux: paths below the base are resolved with `openat2(RESOLVE_BENEATH |
+//!   RESOLVE_NO_MAGICLINKS)` relative to an O_PATH handle of the base, and files,
+//!   directories, links and renames are made relative to the resulting directory handles
+//!   (`mkdirat`, `openat`, `linkat`, `renameat`). A symlink swapped into the destination
+//!   tree between validation and the move cannot redirect the write outside the base.
+//! - A path that resolves outside the base fails with `PermissionDenied`.
+//!
+//! Notes:
+//! - The base itself is trusted: it was validated (no symlinked ancestors) at config load.
+//! - Paths that are not below the base, other platforms, and kernels without openat2
+//!   (before 5.6, or with a seccomp filter blocking it) use the plain path-based calls.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// `fs::create_dir_all(dir)`, creating each component below `base` beneath it.
+pub(super) fn create_dir_all(base: &Path, dir: &Path) -> io::Result<()> {
+    #[cfg(target_os = "linux")]
+    if let Some(rel) = linux::relative(base, dir) {
+        return linux::create_dir_all(base, rel, dir);
+    }
+    let _ = base;
+    fs::create_dir_all(dir)
+}
+
+/// `fs::rename(src, dst)` with `dst` (and `src`, if it is below `base` too) resolved
+/// beneath `base`.
+pub(super) fn rename(base: &Path, src: &Path, dst: &Path) -> io::Result<()> {
+    #[cfg(target_os = "linux")]
+    if let Some(rel) = linux::relative(base, dst) {
+        return linux::rename(base, rel, src, dst);
+    }
+    let _ = base;
+    fs::rename(src, dst)
+}
+
+#[cfg(target_os = "linux")]
+pub(super) use linux::{create_new, link_file, open_rw, open_tmpfile};
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::ffi::{CStr, CString, OsStr};
+    use std::fs::{self, File, OpenOptions};
+    use std::io;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::path::{Component, Path, PathBuf};
+    use std::sync::OnceLock;
+
+    /// `path` relative to `base` when openat2 is usable and `path` lies strictly below it.
+    pub(super) fn relative<'a>(base: &Path, path: &'a Path) -> Option<&'a Path> {
+        let rel = path.strip_prefix(base).ok()?;
+        let normal = rel.components().all(|c| matches!(c, Component::Normal(_)));
+        (normal && !rel.as_os_str().is_empty() && supported()).then_some(rel)
+    }
+
+    /// Whether openat2 works here (probed once with a harmless O_PATH open of ".").
+    fn supported() -> bool {
+        static SUPPORTED: OnceLock<bool> = OnceLock::new();
+        *SUPPORTED.get_or_init(|| {
+            openat2(libc::AT_FDCWD, c".", libc::O_PATH | libc::O_DIRECTORY, 0).is_ok()
+        })
+    }
+
+    fn cstring(s: &OsStr) -> io::Result<CString> {
+        CString::new(s.as_bytes())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path contains a NUL byte"))
+    }
+
+    fn owned(fd: libc::c_int) -> io::Result<OwnedFd> {
+        if fd < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+        }
+    }
+
+    fn check(rc: libc::c_int) -> io::Result<()> {
+        if rc == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+
+    fn openat2(dir: RawFd, path: &CStr, flags: libc::c_int, mode: u32) -> io::Result<OwnedFd> {
+        let mut how: libc::open_how = unsafe { std::mem::zeroed() };
+        how.flags = (flags | libc::O_CLOEXEC) as u64;
+        how.mode = mode as u64;
+        how.resolve = libc::RESOLVE_BENEATH | libc::RESOLVE_NO_MAGICLINKS;
+        let rc = unsafe {
+            libc::syscall(
+                libc::SYS_openat2,
+                dir,
+                path.as_ptr(),
+                &how as *const libc::open_how,
+                std::mem::size_of::<libc::open_how>(),
+            )
+        };
+        owned(rc as libc::c_int)
+    }
+
+    /// `rel` (below `base`) opened beneath `base`; escapes become `PermissionDenied`.
+    fn open_beneath(
+        base: &Path,
+        rel: &Path,
+        flags: libc::c_int,
+        mode: u32,
+        full: &Path,
+    ) -> io::Result<OwnedFd> {
+        let base_fd = open_base(base)?;
+        openat2(base_fd.as_raw_fd(), &cstring(rel.as_os_str())?, flags, mode)
+            .map_err(|e| escaped(e, base, full))
+    }
+
+    fn open_base(base: &Path) -> io::Result<OwnedFd> {
+        let c = cstring(base.as_os_str())?;
+        owned(unsafe {
+            libc::open(
+                c.as_ptr(),
+                libc::O_PATH | libc::O_DIRECTORY | libc::O_CLOEXEC,
+            )
+        })
+    }
+
+    fn escaped(e: io::Error, base: &Path, path: &Path) -> io::Error {
+        if e.raw_os_error() == Some(libc::EXDEV) {
+            io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!(
+                    "'{}' resolves outside '{}' (symlink in the destination path)",
+                    path.display(),
+                    base.display()
+                ),
+            )
+        } else {
+            e
+        }
+    }
+
+    /// Handle of the directory holding `path` (resolved beneath `base`) and its file name.
+    fn parent_beneath(base: &Path, rel: &Path, full: &Path) -> io::Result<(OwnedFd, CString)> {
+        let name = rel.file_name().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("'{}' has no file name", full.display()),
+            )
+        })?;
+        let parent = match rel.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p,
+            _ => Path::new("."),
+        };
+        let dir = open_beneath(base, parent, libc::O_PATH | libc::O_DIRECTORY, 0, full)?;
+        Ok((dir, cstring(name)?))
+    }
+
+    pub(super) fn create_dir_all(base: &Path, rel: &Path, full: &Path) -> io::Result<()> {
+        fs::create_dir_all(base)?;
+        let base_fd = open_base(base)?;
+        let mut cur: Option<OwnedFd> = None;
+        let mut prefix = PathBuf::new();
+        for name in rel.iter() {
+            let dir_fd = cur.as_ref().unwrap_or(&base_fd).as_raw_fd();
+            let c_name = cstring(name)?;
+            if let Err(e) = check(unsafe { libc::mkdirat(dir_fd, c_name.as_ptr(), 0o777) })
+                && e.kind() != io::ErrorKind::AlreadyExists
+            {
+                return Err(e);
+            }
+            prefix.push(name);
+            let fd = openat2(
+                base_fd.as_raw_fd(),
+                &cstring(prefix.as_os_str())?,
+                libc::O_PATH | libc::O_DIRECTORY,
+                0,
+            )
+            .map_err(|e| escaped(e, base, full))?;
+            cur = Some(fd);
+        }
+        Ok(())
+    }
+
+    pub(super) fn rename(base: &Path, rel: &Path, src: &Path, dst: &Path) -> io::Result<()> {
+        let (dst_dir, dst_name) = parent_beneath(base, rel, dst)?;
+        let (src_dir, src_name) = match relative(base, src) {
+            Some(src_rel) => {
+                let (dir, name) = parent_beneath(base, src_rel, src)?;
+                (Some(dir), name)
+            }
+            None => (None, cstring(src.as_os_str())?),
+        };
+        let src_fd = src_dir.as_ref().map_or(libc::AT_FDCWD, |d| d.as_raw_fd());
+        check(unsafe {
+            libc::renameat(
+                src_fd,
+                src_name.as_ptr(),
+                dst_dir.as_raw_fd(),
+                dst_name.as_ptr(),
+            )
+        })
+    }
+
+    /// New file `path` for writing (O_EXCL, never follows a final symlink).
+    pub(crate) fn create_new(base: &Path, path: &Path) -> io::Result<File> {
+        let Some(rel) = relative(base, path) else {
+            return OpenOptions::new().write(true).create_new(true).open(path);
+        };
+        let (dir, name) = parent_beneath(base, rel, path)?;
+        let fd = owned(unsafe {
+            libc::openat(
+                dir.as_raw_fd(),
+                name.as_ptr(),
+                libc::O_WRONLY | libc::O_CREAT | libc::O_EXCL | libc::O_NOFOLLOW | libc::O_CLOEXEC,
+                0o666 as libc::c_uint,
+            )
+        })?;
+        Ok(File::from(fd))
+    }
+
+    /// Existing file `path` for reading and writing (never follows a final symlink).
+    pub(crate) fn open_rw(base: &Path, path: &Path) -> io::Result<File> {
+        let Some(rel) = relative(base, path) else {
+            return OpenOptions::new().read(true).write(true).open(path);
+        };
+        let (dir, name) = parent_beneath(base, rel, path)?;
+        let fd = owned(unsafe {
+            libc::openat(
+                dir.as_raw_fd(),
+                name.as_ptr(),
+                libc::O_RDWR | libc::O_NOFOLLOW | libc::O_CLOEXEC,
+            )
+        })?;
+        Ok(File::from(fd))
+    }
+
+    /// Unnamed file (O_TMPFILE) in `dir`.
+    pub(crate) fn open_tmpfile(base: &Path, dir: &Path) -> io::Result<File> {
+        let Some(rel) = relative(base, dir) else {
+            return OpenOptions::new()
+                .read(true)
+                .write(true)
+                .mode(0o666)
+                .custom_flags(libc::O_TMPFILE)
+                .open(dir);
+        };
+        open_beneath(base, rel, libc::O_TMPFILE | libc::O_RDWR, 0o666, dir).map(File::from)
+    }
+
+    /// Give the unnamed `file` the name `dest` (fails if `dest` exists).
+    /// Tries linkat(AT_EMPTY_PATH) first; kernels that reserve it for CAP_DAC_READ_SEARCH
+    /// get the equivalent link through /proc/self/fd.
+    pub(crate) fn link_file(base: &Path, file: &File, dest: &Path) -> io::Result<()> {
+        let (dir, name) = match relative(base, dest) {
+            Some(rel) => {
+                let (dir, name) = parent_beneath(base, rel, dest)?;
+                (Some(dir), name)
+            }
+            None => (None, cstring(dest.as_os_str())?),
+        };
+        let dir_fd = dir.as_ref().map_or(libc::AT_FDCWD, |d| d.as_raw_fd());
+        let rc = unsafe {
+            libc::linkat(
+                file.as_raw_fd(),
+                c"".as_ptr(),
+                dir_fd,
+                name.as_ptr(),
+                libc::AT_EMPTY_PATH,
+            )
+        };
+        if rc == 0 {
+            return Ok(());
+        }
+        let err = io::Error::last_os_error();
+        if !matches!(err.raw_os_error(), Some(libc::ENOENT | libc::EPERM)) {
+            return Err(err);
+        }
+        let proc_path = CString::new(format!("/proc/self/fd/{}", file.as_raw_fd()))?;
+        check(unsafe {
+            libc::linkat(
+                libc::AT_FDCWD,
+                proc_path.as_ptr(),
+                dir_fd,
+                name.as_ptr(),
+                libc::AT_SYMLINK_FOLLOW,
+            )
+        })
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::os::unix::fs::symlink;
+    use tempfile::tempdir;
+
+    #[test]
+    fn operations_stay_beneath_the_base() {
+        let td = tempdir().unwrap();
+        let base = td.path().join("base");
+        let outside = td.path().join("outside");
+        fs::create_dir_all(&outside).unwrap();
+
+        create_dir_all(&base, &base.join("a/b")).unwrap();
+        let mut f = create_new(&base, &base.join("a/b/f.tmp")).unwrap();
+        f.write_all(b"data").unwrap();
+        rename(&base, &base.join("a/b/f.tmp"), &base.join("a/f")).unwrap();
+        assert_eq!(fs::read(base.join("a/f")).unwrap(), b"data");
+
+        // A directory swapped for a symlink pointing outside is refused.
+        symlink(&outside, base.join("evil")).unwrap();
+        let err = create_new(&base, &base.join("evil/x")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied, "{err}");
+        let err = create_dir_all(&base, &base.join("evil/sub")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied, "{err}");
+        let err = rename(&base, &base.join("a/f"), &base.join("evil/f")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied, "{err}");
+        assert!(fs::read_dir(&outside).unwrap().next().is_none());
+
+        // Paths outside the base use the plain calls.
+        let plain = outside.join("plain");
+        create_new(&base, &plain).unwrap();
+        assert!(plain.exists());
+    }
+}
@@ -1,7 +1,8 @@
 //! Claim a source file by atomically renaming it in-place to a unique hidden name.
 //! - Only one concurrent process can succeed (atomic rename in the same directory).
 //! - Losers will see NotFound later and can exit gracefully if the destination exists.
-//! - Name format: ".aria_move.moving.<pid>.<nanos>[.<attempt>]" (hidden dotfile)
+//! - Name format: ".aria_move.moving.<pid>.<nanos>[.<attempt>]--<original name>" (hidden
+//!   dotfile); the original name lets `clean` restore claims abandoned by a crash.
 
 use std::ffi::{OsStr, OsString};
 use std::fs;
@@ -11,6 +12,18 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::lock::acquire_dir_lock; // Better option: reuse existing directory advisory lock
 
+const CLAIM_PREFIX: &str = ".aria_move.moving.";
+const CLAIM_SEPARATOR: &str = "--";
+
+/// (owner pid, original name) of a claimed name, or None if `name` is not a claim.
+pub(super) fn parse_claimed(name: &str) -> Option<(u32, &str)> {
+    let (stamp, original) = name
+        .strip_prefix(CLAIM_PREFIX)?
+        .split_once(CLAIM_SEPARATOR)?;
+    let pid = stamp.split('.').next()?.parse().ok()?;
+    (!original.is_empty()).then_some((pid, original))
+}
+
 /// Atomically rename `src` to a unique hidden "claimed" name in the same directory.
 /// Returns the claimed path on success.
 /// Notes:
@@ -26,7 +39,13 @@ pub(super) fn claim_source(src: &Path) -> io::Result<PathBuf> {
         .unwrap_or(0);
 
     let parent = src.parent().unwrap_or_else(|| Path::new("."));
-    let _fname = src.file_name().unwrap_or_else(|| OsStr::new("file"));
+    let fname = src.file_name().unwrap_or_else(|| OsStr::new("file"));
+    let with_original = |stem: String| {
+        let mut name = OsString::from(stem);
+        name.push(CLAIM_SEPARATOR);
+        name.push(fname);
+        name
+    };
 
     // Acquire an advisory directory lock to ensure serialization of claims within this
     // directory. This is the "better option" replacing a bespoke sidecar lock file.
@@ -39,12 +58,9 @@ pub(super) fn claim_source(src: &Path) -> io::Result<PathBuf> {
     const MAX_TRIES: u32 = 5;
     for attempt in 0..=MAX_TRIES {
         let new_name = if attempt == 0 {
-            OsString::from(format!(".aria_move.moving.{}.{}", pid, base_nanos))
+            with_original(format!("{CLAIM_PREFIX}{}.{}", pid, base_nanos))
         } else {
-            OsString::from(format!(
-                ".aria_move.moving.{}.{}.{}",
-                pid, base_nanos, attempt
-            ))
+            with_original(format!("{CLAIM_PREFIX}{}.{}.{}", pid, base_nanos, attempt))
         };
         let claimed = parent.join(new_name);
 
@@ -68,15 +84,14 @@ pub(super) fn claim_source(src: &Path) -> io::Result<PathBuf> {
     }
 
     // If we exhausted retries, fall back to a final rename attempt to surface the real error.
-    let final_name: OsString =
-        OsString::from(format!(".aria_move.moving.{}.{}.final", pid, base_nanos));
+    let final_name = with_original(format!("{CLAIM_PREFIX}{}.{}.final", pid, base_nanos));
     let final_claimed = parent.join(final_name);
     fs::rename(src, &final_claimed).map(|_| final_claimed)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::claim_source;
+    use super::{claim_source, parse_claimed};
     use std::fs;
     use std::thread;
     use std::time::Duration;
@@ -98,6 +113,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn claimed_names_keep_the_original() {
+        let td = tempdir().unwrap();
+        let src = td.path().join("Show -- S01.mkv");
+        fs::write(&src, "data").unwrap();
+        let claimed = claim_source(&src).unwrap();
+        let name = claimed.file_name().unwrap().to_str().unwrap();
+        assert_eq!(
+            parse_claimed(name),
+            Some((std::process::id(), "Show -- S01.mkv"))
+        );
+        assert_eq!(parse_claimed(".aria_move.resume.00.tmp"), None);
+    }
+
     #[test]
     fn claim_handles_notfound() {
         let td = tempdir().unwrap();
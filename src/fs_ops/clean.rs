@@ -0,0 +1,361 @@
+//! Crash cleanup of both bases (`aria_move clean`).
+//! - `reconcile`: the startup pass. Removes orphaned resume temps and unfinished directory
+//!   copies at the top of completed_base, and renames abandoned claimed sources at the top
+//!   of download_base back to their original names.
+//! - `clean_bases`: `reconcile`, then a walk of both bases that also removes stale lock files
+//!   and lockfile asides, and restores abandoned claims at any depth.
+//! - `CleanReport` lists every action (serializable, for `clean`'s JSON output).
+//!
+//! Notes:
+//! - Only aria_move's own `.aria_move.*` names are touched, besides partial directory copies.
+//! - A directory copy is only deleted if it still carries the copy journal written by
+//!   `dir_move` and every file in it also exists, at least as large, in the recorded source.
+//!   Without a journal (finished copies, merges, anything not written by aria_move) nothing
+//!   is deleted. A copy whose destination lock is held by a running move is left alone.
+//! - A flock sidecar is stale when nobody holds it; a lockfile when `lockfile.rs` says so
+//!   (TTL or dead local owner). Locks of running moves are left alone.
+//! - Claimed names carry the original name (see `claim.rs`), so no journal is needed.
+//!   A claim is abandoned when its owner process no longer exists (Unix); Windows cannot
+//!   tell, so claims are left alone there. A claim whose original name has been reused
+//!   is reported as failed and left hidden.
+//! - With `dry_run` nothing is changed; the report lists what would be done.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+use walkdir::WalkDir;
+
+use crate::config::types::Config;
+use crate::utils::{is_internal_name, skip_by_hidden_policy};
+
+use super::claim::parse_claimed;
+use super::dir_move::COPY_JOURNAL_NAME;
+use super::lockfile::{self, LOCKFILE_NAME};
+use super::path_lock::{is_sidecar_name, remove_if_unheld, try_acquire_path_lock_with};
+
+/// What `clean` did (or would do) with one path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanKind {
+    /// Orphaned resume temp or stale-lock aside, removed.
+    TempFile,
+    /// Lock file no process holds, removed.
+    StaleLock,
+    /// Abandoned claimed source, renamed back (`restored_to`).
+    ClaimedSource,
+    /// Partial directory copy in completed_base, removed.
+    PartialDir,
+}
+
+/// One cleanup action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CleanAction {
+    pub kind: CleanKind,
+    pub path: PathBuf,
+    /// Original path of a restored claim.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restored_to: Option<PathBuf>,
+}
+
+/// A path that should have been cleaned but could not be.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CleanFailure {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+/// Result of `reconcile` / `clean_bases`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct CleanReport {
+    pub dry_run: bool,
+    pub actions: Vec<CleanAction>,
+    pub failed: Vec<CleanFailure>,
+}
+
+impl CleanReport {
+    fn record(&mut self, kind: CleanKind, path: &Path, restored_to: Option<PathBuf>) {
+        info!(event = "clean", kind = ?kind, path = %path.display(), dry_run = self.dry_run, "Cleaned up after an interrupted run");
+        self.actions.push(CleanAction {
+            kind,
+            path: path.to_path_buf(),
+            restored_to,
+        });
+    }
+
+    fn fail(&mut self, path: &Path, error: impl std::fmt::Display) {
+        warn!(event = "clean_failed", path = %path.display(), error = %error, "Cleanup failed");
+        self.failed.push(CleanFailure {
+            path: path.to_path_buf(),
+            error: error.to_string(),
+        });
+    }
+
+    /// Number of actions of `kind`.
+    pub fn count(&self, kind: CleanKind) -> usize {
+        self.actions.iter().filter(|a| a.kind == kind).count()
+    }
+}
+
+/// Startup pass: orphaned resume temps, partial directory copies and abandoned claims
+/// (top level of the bases only).
+pub fn reconcile(config: &Config, dry_run: bool) -> Result<CleanReport> {
+    let mut report = CleanReport {
+        dry_run,
+        ..Default::default()
+    };
+    if let Ok(rd) = fs::read_dir(&config.download_base) {
+        for ent in rd.flatten() {
+            let name = ent.file_name();
+            if let Some((pid, original)) = name.to_str().and_then(parse_claimed) {
+                restore_claim(&mut report, &ent.path(), pid, original);
+            }
+        }
+    }
+    let Ok(rd) = fs::read_dir(&config.completed_base) else {
+        return Ok(report);
+    };
+    for ent in rd.flatten() {
+        let path = ent.path();
+        let name = ent.file_name();
+        if path.is_file() && name.to_str().is_some_and(is_resume_temp) {
+            remove(&mut report, CleanKind::TempFile, &path);
+        } else if path.is_dir()
+            && !skip_by_hidden_policy(&name, config.include_hidden)
+            && is_partial_copy(&path)
+        {
+            remove_abandoned_copy(config, &mut report, &path);
+        }
+    }
+    Ok(report)
+}
+
+/// Full cleanup of both bases: `reconcile`, then stale locks, asides and abandoned claims.
+pub fn clean_bases(config: &Config, dry_run: bool) -> Result<CleanReport> {
+    let mut report = reconcile(config, dry_run)?;
+    // Paths reconcile handled or failed on (dry runs and failures leave them in place).
+    let done: HashSet<PathBuf> = report
+        .actions
+        .iter()
+        .map(|a| a.path.clone())
+        .chain(report.failed.iter().map(|f| f.path.clone()))
+        .collect();
+    for base in [&config.download_base, &config.completed_base] {
+        if !base.is_dir() {
+            continue;
+        }
+        let mut walk = WalkDir::new(base).min_depth(1).into_iter();
+        while let Some(entry) = walk.next() {
+            let entry = match entry {
+                Ok(e) => e,
+                Err(e) => {
+                    let path = e.path().unwrap_or(base).to_path_buf();
+                    report.fail(&path, e);
+                    continue;
+                }
+            };
+            let is_dir = entry.file_type().is_dir();
+            if done.contains(entry.path()) {
+                if is_dir {
+                    walk.skip_current_dir();
+                }
+                continue;
+            }
+            let Some(name) = entry.file_name().to_str() else {
+                continue;
+            };
+            if is_dir {
+                // A claimed directory is restored as a whole.
+                if let Some((pid, original)) = parse_claimed(name) {
+                    restore_claim(&mut report, entry.path(), pid, original);
+                    walk.skip_current_dir();
+                }
+            } else if entry.file_type().is_file() {
+                clean_entry(&mut report, entry.path(), name);
+            }
+        }
+    }
+    Ok(report)
+}
+
+fn clean_entry(report: &mut CleanReport, path: &Path, name: &str) {
+    if !name.starts_with(".aria_move.") {
+        return;
+    }
+    if is_resume_temp(name) || name.contains(".stale.") {
+        remove(report, CleanKind::TempFile, path);
+    } else if name == LOCKFILE_NAME || is_sidecar_name(name) {
+        let stale = if lockfile::is_lockfile(path) {
+            lockfile::remove_if_stale(path, report.dry_run)
+        } else {
+            remove_if_unheld(path, report.dry_run)
+        };
+        match stale {
+            Ok(true) => report.record(CleanKind::StaleLock, path, None),
+            Ok(false) => debug!(path = %path.display(), "lock is held; left alone"),
+            Err(e) => report.fail(path, e),
+        }
+    } else if let Some((pid, original)) = parse_claimed(name) {
+        restore_claim(report, path, pid, original);
+    }
+}
+
+fn restore_claim(report: &mut CleanReport, path: &Path, pid: u32, original: &str) {
+    if lockfile::process_alive(pid) {
+        debug!(path = %path.display(), pid, "claim owner may still be running; left alone");
+        return;
+    }
+    let target = path.with_file_name(original);
+    if target.exists() {
+        report.fail(path, format!("original name {} is taken", target.display()));
+        return;
+    }
+    if !report.dry_run
+        && let Err(e) = fs::rename(path, &target)
+    {
+        report.fail(path, e);
+        return;
+    }
+    report.record(CleanKind::ClaimedSource, path, Some(target));
+}
+
+fn remove(report: &mut CleanReport, kind: CleanKind, path: &Path) {
+    let res = if report.dry_run {
+        Ok(())
+    } else if kind == CleanKind::PartialDir {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    match res {
+        Ok(()) => report.record(kind, path, None),
+        Err(e) => report.fail(path, e),
+    }
+}
+
+/// Remove an unfinished copy unless a running move still holds its destination lock.
+/// Without locks a running copy cannot be told apart, so nothing is removed then.
+fn remove_abandoned_copy(config: &Config, report: &mut CleanReport, path: &Path) {
+    if config.disable_locks || std::env::var("ARIA_MOVE_DISABLE_LOCKS").ok().as_deref() == Some("1")
+    {
+        debug!(path = %path.display(), "locks disabled; keeping unfinished directory copy");
+        return;
+    }
+    match try_acquire_path_lock_with(path, config.lock_strategy) {
+        Ok(Some(_guard)) => remove(report, CleanKind::PartialDir, path),
+        Ok(None) => debug!(path = %path.display(), "directory copy in progress; left alone"),
+        Err(e) => report.fail(path, e),
+    }
+}
+
+fn is_resume_temp(name: &str) -> bool {
+    name.starts_with(".aria_move.resume.") && name.ends_with(".tmp")
+}
+
+/// True if `target` is an unfinished directory copy holding nothing its source lacks: it
+/// carries the copy journal, the recorded source still exists, and every file in it has a
+/// counterpart in the source at least as large (a copy cut short is smaller).
+fn is_partial_copy(target: &Path) -> bool {
+    let Ok(recorded) = fs::read_to_string(target.join(COPY_JOURNAL_NAME)) else {
+        return false;
+    };
+    let source = PathBuf::from(recorded);
+    if !source.is_dir() {
+        debug!(target = %target.display(), source = %source.display(), "copy journal names a missing source; keeping destination");
+        return false;
+    }
+    WalkDir::new(target).min_depth(1).into_iter().all(|e| {
+        let Ok(e) = e else {
+            return false;
+        };
+        if e.file_type().is_dir() || is_internal_name(e.file_name()) {
+            return true;
+        }
+        let Ok(rel) = e.path().strip_prefix(target) else {
+            return false;
+        };
+        match (e.metadata(), fs::metadata(source.join(rel))) {
+            (Ok(copy), Ok(orig)) => copy.is_file() && orig.is_file() && copy.len() <= orig.len(),
+            _ => false,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn config(root: &Path) -> Config {
+        let download_base = root.join("incoming");
+        let completed_base = root.join("completed");
+        fs::create_dir_all(&download_base).unwrap();
+        fs::create_dir_all(&completed_base).unwrap();
+        Config {
+            download_base,
+            completed_base,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn dry_run_reports_without_touching() {
+        let td = tempdir().unwrap();
+        let cfg = config(td.path());
+        let tmp = cfg
+            .completed_base
+            .join(".aria_move.resume.deadbeefdeadbeef.tmp");
+        fs::write(&tmp, b"partial").unwrap();
+        let sidecar = cfg.download_base.join(".aria_move.0123456789abcdef.lock");
+        fs::write(&sidecar, b"").unwrap();
+
+        let report = clean_bases(&cfg, true).unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.count(CleanKind::TempFile), 1);
+        assert_eq!(report.count(CleanKind::StaleLock), 1);
+        assert!(tmp.exists() && sidecar.exists());
+
+        let report = clean_bases(&cfg, false).unwrap();
+        assert_eq!(report.actions.len(), 2, "{report:?}");
+        assert!(!tmp.exists() && !sidecar.exists());
+    }
+
+    #[test]
+    fn held_locks_are_left_alone() {
+        let td = tempdir().unwrap();
+        let cfg = config(td.path());
+        let item = cfg.download_base.join("a.iso");
+        let _held = super::super::path_lock::acquire_path_lock_with(
+            &item,
+            crate::config::types::LockStrategy::Flock,
+        )
+        .unwrap();
+
+        let report = clean_bases(&cfg, false).unwrap();
+        assert!(report.actions.is_empty(), "{report:?}");
+        assert!(super::super::path_lock::sidecar_path(&item).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn abandoned_claims_get_their_name_back() {
+        let td = tempdir().unwrap();
+        let cfg = config(td.path());
+        // pid_max on Linux is at most 2^22, so this owner cannot be running.
+        let claimed = cfg
+            .download_base
+            .join(".aria_move.moving.99999999.1--movie.mkv");
+        fs::write(&claimed, b"data").unwrap();
+
+        let report = clean_bases(&cfg, false).unwrap();
+        assert_eq!(report.count(CleanKind::ClaimedSource), 1);
+        assert_eq!(
+            report.actions[0].restored_to.as_deref(),
+            Some(cfg.download_base.join("movie.mkv").as_path())
+        );
+        assert!(cfg.download_base.join("movie.mkv").exists());
+        assert!(!claimed.exists());
+    }
+}
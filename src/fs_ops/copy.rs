@@ -1,32 +1,118 @@
 //! Safe copy-and-rename helper:
 //! - Copies to a temp file in the destination directory
-//! - Ensures data durability (io_copy::copy_streaming fsyncs the temp file)
+//! - Ensures data durability (the temp file is fsynced unless `fsync_policy = none`)
 //! - Atomically renames temp -> dest (Windows overwrite-safe)
-//! - Fsyncs the destination directory (Unix; handled in atomic::try_atomic_move)
+//! - Fsyncs the destination directory per `fsync_policy` (handled in atomic::try_atomic_move_with)
+//! - `copy_with_backend` copies through the selected engine (see engine.rs); `BuiltinEngine`
+//!   is this module's copy, starting with the method `bench-dest` measured fastest
+//! - An interrupted temp is resumed only if it passes the `resume_verify` check
+//!   (the public `safe_copy_and_rename*` helpers use the default, tail)
+//! - On Linux the public `safe_copy_and_rename*` helpers (and moves with `unnamed_temp`) copy
+//!   into an O_TMPFILE that is linked in as `dest` once complete, so no temp name is ever
+//!   visible or orphaned; they fall back to the named temp where O_TMPFILE is unsupported,
+//!   and an existing named temp is still resumed
+//! - Everything in the destination (directory, temp, rename, link) is resolved beneath a base
+//!   directory: completed_base for moves, the destination's parent for the public helpers, or
+//!   the caller's choice with `safe_copy_and_rename_beneath` (see beneath.rs)
+//! - Failures are typed: `DestinationUnavailable`, `CopyFailed { phase }` (copy/resume/rename)
+//!   and `VerificationFailed` for short writes
 
 use anyhow::{Context, Result, anyhow};
 use std::fs;
+#[cfg(target_os = "linux")]
+use std::io;
 use std::path::Path;
+#[cfg(target_os = "linux")]
+use tracing::debug;
+use tracing::warn;
 
-use super::atomic::try_atomic_move;
-use super::io_error_with_help;
-use super::{io_copy, metadata, util};
+use crate::config::types::{Config, FsyncPolicy, ResumeVerify};
+use crate::errors::AriaMoveError;
+use crate::shutdown;
+
+use super::atomic::try_atomic_move_beneath;
+use super::bench::{self, CopyMethod};
+use super::engine::{self, CopyEngine, EngineCapabilities};
+use super::helpers::{copy_error, destination_error};
+use super::io_copy::DurabilityMode;
+use super::{beneath, io_copy, metadata, util};
+
+/// Settings for one `copy_and_rename`, taken from `Config` (see `From<&Config>`).
+#[derive(Debug, Clone, Copy)]
+struct CopyTuning {
+    verify: ResumeVerify,
+    /// Linux: copy into an O_TMPFILE instead of the named resume temp.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    unnamed: bool,
+    fsync: FsyncPolicy,
+    method: CopyMethod,
+}
+
+impl CopyTuning {
+    /// What the public `safe_copy_and_rename*` helpers use.
+    const HELPERS: CopyTuning = CopyTuning {
+        verify: ResumeVerify::Tail,
+        unnamed: true,
+        fsync: FsyncPolicy::Full,
+        method: CopyMethod::Auto,
+    };
+
+    fn durability(self) -> DurabilityMode {
+        if self.fsync.syncs_files() {
+            DurabilityMode::Full
+        } else {
+            DurabilityMode::Data
+        }
+    }
+}
+
+impl From<&Config> for CopyTuning {
+    fn from(config: &Config) -> Self {
+        CopyTuning {
+            verify: config.resume_verify,
+            unnamed: config.unnamed_temp,
+            fsync: config.fsync_policy,
+            method: bench::preferred_method(config),
+        }
+    }
+}
 
 /// Core: copy src -> temp in dest dir, then atomic rename temp -> dest.
 /// Notes:
-/// - io_copy::copy_streaming creates the temp file with O_EXCL and fsyncs it before returning.
-/// - try_atomic_move handles Windows "overwrite" and fsyncs the destination directory on Unix.
-pub fn safe_copy_and_rename(src: &Path, dest: &Path) -> Result<()> {
+/// - io_copy::copy_streaming_ex creates the temp file with O_EXCL and fsyncs it before returning.
+/// - try_atomic_move_with handles Windows "overwrite" and fsyncs the destination directory on Unix.
+/// - Linux: a fresh copy uses an unnamed temp (see `copy_unnamed`), so an interrupted call
+///   leaves nothing behind.
+pub fn safe_copy_and_rename(src: &Path, dest: &Path) -> Result<(), AriaMoveError> {
+    copy_and_rename(None, src, dest, CopyTuning::HELPERS).map_err(AriaMoveError::from)
+}
+
+/// `safe_copy_and_rename` with every destination path below `base` (directories, the
+/// temp file and the final rename) resolved beneath `base`: on Linux a symlink swapped
+/// into the destination path cannot redirect the copy outside it.
+pub fn safe_copy_and_rename_beneath(
+    base: &Path,
+    src: &Path,
+    dest: &Path,
+) -> Result<(), AriaMoveError> {
+    copy_and_rename(Some(base), src, dest, CopyTuning::HELPERS).map_err(AriaMoveError::from)
+}
+
+/// `base`: directory the destination is resolved beneath (default: `dest`'s parent).
+fn copy_and_rename(base: Option<&Path>, src: &Path, dest: &Path, tuning: CopyTuning) -> Result<()> {
     let dest_dir = dest
         .parent()
         .ok_or_else(|| anyhow!("destination has no parent: {}", dest.display()))?;
+    let base = base.unwrap_or(dest_dir);
 
     // Ensure destination directory exists.
-    fs::create_dir_all(dest_dir)
-        .map_err(io_error_with_help("create destination directory", dest_dir))?;
+    beneath::create_dir_all(base, dest_dir)
+        .map_err(destination_error("create destination directory", dest_dir))?;
 
-    // Choose deterministic resume temp path inside destination directory.
-    let tmp_path = util::resume_temp_path(dest);
+    // Choose deterministic resume temp path (per source) inside destination directory.
+    let tmp_path = util::resume_temp_path(src, dest);
+    // A forced exit (second signal) removes the temp rather than leaving it for resume.
+    let _tracked = shutdown::track_temp(&tmp_path);
 
     // Determine sizes
     let src_size = fs::metadata(src)
@@ -36,81 +122,259 @@ pub fn safe_copy_and_rename(src: &Path, dest: &Path) -> Result<()> {
 
     // If a previous partial exists, resume; else perform fresh copy.
     if let Some(existing) = tmp_len {
-        if existing > src_size {
-            // Corrupted temp (larger than source) — start fresh
+        if existing > src_size
+            || !util::resume_matches(src, &tmp_path, existing, tuning.verify).unwrap_or(false)
+        {
+            // Corrupted temp (larger than source, or not a prefix of it) — start fresh
+            warn!(temp = %tmp_path.display(), have = existing, total = src_size, "resume temp does not match the source; copying from scratch");
             let _ = fs::remove_file(&tmp_path);
         } else if existing == src_size {
             // Already fully copied; just finalize
-            if let Err(e) = try_atomic_move(&tmp_path, dest) {
+            if let Err(e) = try_atomic_move_beneath(base, &tmp_path, dest, tuning.fsync) {
                 // Best-effort cleanup on failure
                 let _ = fs::remove_file(&tmp_path);
-                return Err(e).with_context(|| {
-                    format!(
-                        "rename temporary file '{}' -> '{}'",
-                        tmp_path.display(),
-                        dest.display()
-                    )
-                });
+                return Err(rename_failed(&tmp_path, dest, e));
             }
             return Ok(());
         } else {
             // Resume from existing offset
-            let res = io_copy::copy_streaming_resume(src, &tmp_path, existing).map_err(
-                io_error_with_help("resume copy to temporary file", &tmp_path),
-            )?;
+            let res =
+                io_copy::copy_streaming_resume(base, src, &tmp_path, existing, tuning.durability())
+                    .map_err(|e| discard_if_aborted(e, &tmp_path))
+                    .map_err(copy_error(
+                        "resume",
+                        "resume copy to temporary file",
+                        &tmp_path,
+                    ))?;
             if res != src_size {
                 // Incomplete resume; treat as error and cleanup
                 let _ = fs::remove_file(&tmp_path);
-                return Err(anyhow!(
-                    "resume short write: wrote {} bytes but source is {} bytes",
-                    res,
-                    src_size
-                ));
+                return Err(AriaMoveError::VerificationFailed {
+                    path: dest.to_path_buf(),
+                    expected: src_size,
+                    actual: res,
+                }
+                .into());
             }
             // Finalize rename
-            if let Err(e) = try_atomic_move(&tmp_path, dest) {
+            if let Err(e) = try_atomic_move_beneath(base, &tmp_path, dest, tuning.fsync) {
                 let _ = fs::remove_file(&tmp_path);
-                return Err(e).with_context(|| {
-                    format!(
-                        "rename temporary file '{}' -> '{}'",
-                        tmp_path.display(),
-                        dest.display()
-                    )
-                });
+                return Err(rename_failed(&tmp_path, dest, e));
             }
             return Ok(());
         }
     }
 
+    #[cfg(target_os = "linux")]
+    if tuning.unnamed
+        && tmp_len.is_none()
+        && copy_unnamed(base, src, dest, dest_dir, src_size, tuning)?
+    {
+        return Ok(());
+    }
+
     // Fresh copy path
-    let written = io_copy::copy_streaming(src, &tmp_path)
-        .map_err(io_error_with_help("copy to temporary file", &tmp_path))?;
+    let written =
+        io_copy::copy_streaming_beneath(base, src, &tmp_path, tuning.durability(), tuning.method)
+            .map(|r| r.bytes)
+            .map_err(|e| discard_if_aborted(e, &tmp_path))
+            .map_err(copy_error("copy", "copy to temporary file", &tmp_path))?;
     if written != src_size {
         let _ = fs::remove_file(&tmp_path);
-        return Err(anyhow!(
-            "short write while copying: wrote {} bytes but source is {} bytes",
-            written,
-            src_size
-        ));
+        return Err(AriaMoveError::VerificationFailed {
+            path: dest.to_path_buf(),
+            expected: src_size,
+            actual: written,
+        }
+        .into());
     }
-    if let Err(e) = try_atomic_move(&tmp_path, dest) {
+    if let Err(e) = try_atomic_move_beneath(base, &tmp_path, dest, tuning.fsync) {
         let _ = fs::remove_file(&tmp_path);
-        return Err(e).with_context(|| {
-            format!(
-                "rename temporary file '{}' -> '{}'",
-                tmp_path.display(),
-                dest.display()
-            )
-        });
+        return Err(rename_failed(&tmp_path, dest, e));
     }
 
     Ok(())
 }
 
+/// Copy `src` into an unnamed temp in `dest_dir` and link it in as `dest`.
+/// Returns false (nothing written) if O_TMPFILE is unsupported there.
+#[cfg(target_os = "linux")]
+fn copy_unnamed(
+    base: &Path,
+    src: &Path,
+    dest: &Path,
+    dest_dir: &Path,
+    src_size: u64,
+    tuning: CopyTuning,
+) -> Result<bool> {
+    let (file, written) = match io_copy::copy_to_unnamed(
+        base,
+        src,
+        dest_dir,
+        tuning.durability(),
+        tuning.method,
+    ) {
+        Ok(r) => r,
+        Err(e) if io_copy::unnamed_unsupported(&e) => {
+            debug!(dir = %dest_dir.display(), error = %e, "O_TMPFILE unsupported; using a named temp");
+            return Ok(false);
+        }
+        Err(e) => {
+            return Err(copy_error(
+                "copy",
+                "copy to unnamed temporary file",
+                dest_dir,
+            )(e));
+        }
+    };
+    if written != src_size {
+        // Dropping the unnamed file discards it; nothing to clean up.
+        return Err(AriaMoveError::VerificationFailed {
+            path: dest.to_path_buf(),
+            expected: src_size,
+            actual: written,
+        }
+        .into());
+    }
+    match super::fs_trace::traced("link", dest, || beneath::link_file(base, &file, dest)) {
+        Ok(()) => {
+            util::sync_dir(tuning.fsync, dest_dir).ok();
+        }
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+            // linkat cannot replace: name it as the temp, then rename over `dest`.
+            let tmp_path = util::resume_temp_path(src, dest);
+            beneath::link_file(base, &file, &tmp_path).map_err(copy_error(
+                "rename",
+                "link unnamed temporary file",
+                &tmp_path,
+            ))?;
+            if let Err(e) = try_atomic_move_beneath(base, &tmp_path, dest, tuning.fsync) {
+                let _ = fs::remove_file(&tmp_path);
+                return Err(rename_failed(&tmp_path, dest, e));
+            }
+        }
+        Err(e) => return Err(copy_error("rename", "link unnamed temporary file", dest)(e)),
+    }
+    Ok(true)
+}
+
 /// Wrapper: perform safe copy-and-rename, then preserve metadata if requested.
 /// When `strict` is true and `preserve` is true, any failure to preserve metadata returns an error.
-pub fn safe_copy_and_rename_with_metadata(src: &Path, dest: &Path, preserve: bool) -> Result<()> {
-    safe_copy_and_rename(src, dest)?;
+pub fn safe_copy_and_rename_with_metadata(
+    src: &Path,
+    dest: &Path,
+    preserve: bool,
+) -> Result<(), AriaMoveError> {
+    copy_and_rename(None, src, dest, CopyTuning::HELPERS)
+        .and_then(|()| preserve_after_copy(src, dest, preserve))
+        .map_err(AriaMoveError::from)
+}
+
+/// Copy `src` -> `dest` with the configured engine, then preserve metadata if requested.
+pub(super) fn copy_with_backend(config: &Config, src: &Path, dest: &Path) -> Result<()> {
+    engine::select(config)?.copy_file(config, src, dest)?;
+    preserve_after_copy(src, dest, config.preserve_metadata)?;
+    if config.preserve_acls {
+        metadata::preserve_acls(src, dest);
+    }
+    Ok(())
+}
+
+/// The built-in engines: `builtin` starts with the method `bench-dest` measured fastest;
+/// `reflink`, `kernel` and `streaming` always start with theirs.
+pub(super) struct BuiltinEngine {
+    name: &'static str,
+    method: Option<CopyMethod>,
+}
+
+impl BuiltinEngine {
+    pub(super) fn auto() -> Self {
+        BuiltinEngine {
+            name: "builtin",
+            method: None,
+        }
+    }
+
+    pub(super) fn with_method(method: CopyMethod) -> Self {
+        let name = match method {
+            CopyMethod::Auto => "builtin",
+            CopyMethod::Reflink => "reflink",
+            CopyMethod::Kernel => "kernel",
+            CopyMethod::Streaming => "streaming",
+        };
+        BuiltinEngine {
+            name,
+            method: Some(method),
+        }
+    }
+}
+
+impl CopyEngine for BuiltinEngine {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            resumable: true,
+            verifies: false,
+            sparse_aware: false,
+        }
+    }
+
+    fn copy_file(&self, config: &Config, src: &Path, dest: &Path) -> Result<(), AriaMoveError> {
+        let mut tuning = CopyTuning::from(config);
+        if let Some(method) = self.method {
+            tuning.method = method;
+        }
+        copy_and_rename(Some(&config.completed_base), src, dest, tuning)
+            .map_err(AriaMoveError::from)
+    }
+
+    fn copy_tree_file(
+        &self,
+        _config: &Config,
+        src: &Path,
+        dest: &Path,
+    ) -> Result<u64, AriaMoveError> {
+        let copied = match self.method {
+            None => fs::copy(src, dest),
+            Some(method) => {
+                // The staged tree may hold a stale copy from an earlier attempt.
+                let _ = fs::remove_file(dest);
+                io_copy::copy_streaming_ex(src, dest, DurabilityMode::Data, method).map(|r| r.bytes)
+            }
+        };
+        copied
+            .map_err(|e| discard_if_aborted(e, dest))
+            .map_err(copy_error("copy", "copy file to destination", dest))
+            .map_err(AriaMoveError::from)
+    }
+}
+
+/// A copy stopped by a shutdown request is not resumed later: remove what it wrote to `partial`.
+fn discard_if_aborted(e: io::Error, partial: &Path) -> io::Error {
+    if io_copy::is_aborted(&e) {
+        let _ = fs::remove_file(partial);
+    }
+    e
+}
+
+/// Typed error for a failed temp -> dest rename (the last step of every copy).
+pub(super) fn rename_failed(tmp: &Path, dest: &Path, e: anyhow::Error) -> anyhow::Error {
+    AriaMoveError::CopyFailed {
+        path: dest.to_path_buf(),
+        phase: "rename".into(),
+        context: format!(
+            "rename temporary file '{}' -> '{}': {e:#}",
+            tmp.display(),
+            dest.display()
+        ),
+    }
+    .into()
+}
+
+fn preserve_after_copy(src: &Path, dest: &Path, preserve: bool) -> Result<()> {
     if preserve {
         let meta = fs::metadata(src).with_context(|| format!("stat {}", src.display()))?;
         metadata::preserve_metadata(dest, &meta)
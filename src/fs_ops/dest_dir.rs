@@ -0,0 +1,111 @@
+//! Directories created under completed_base get an explicit mode instead of whatever the
+//! process umask leaves (aria2 under systemd often runs with a very different umask from
+//! the user who owns the files).
+//! - `dest_dir_mode` wins when set; otherwise, with `preserve_metadata` or
+//!   `preserve_permissions`, a directory created for a source directory gets that
+//!   directory's mode. Missing parents without a source counterpart keep the umask result.
+//! - `preserve_acls` (Linux): a new directory whose parent has a default POSIX ACL keeps the
+//!   ACL it inherited (no chmod, which would rewrite its mask), and the source directory's
+//!   ACLs are copied onto it.
+//!
+//! Notes:
+//! - Only directories this call creates are touched; existing ones keep their mode.
+//! - Creation goes through `beneath::create_dir_all`, so it stays inside completed_base.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config::types::Config;
+
+use super::{beneath, metadata};
+
+/// Create `dir` (and missing parents) under `config.completed_base`. `like` is the source
+/// directory `dir` stands for, if any.
+pub(super) fn create_dest_dir(config: &Config, dir: &Path, like: Option<&Path>) -> io::Result<()> {
+    let base = &config.completed_base;
+    let created: Vec<PathBuf> = dir
+        .ancestors()
+        .take_while(|p| *p != base.as_path() && fs::symlink_metadata(p).is_err())
+        .map(Path::to_path_buf)
+        .collect();
+    beneath::create_dir_all(base, dir)?;
+    for new in created.iter().rev() {
+        let src = like.filter(|_| new == dir);
+        apply(config, new, src);
+    }
+    Ok(())
+}
+
+/// Mode and ACLs for the directory `dir` just created (best-effort; failures are logged).
+fn apply(config: &Config, dir: &Path, src: Option<&Path>) {
+    let inherits = config.preserve_acls && dir.parent().is_some_and(metadata::has_default_acl);
+    #[cfg(unix)]
+    if !inherits && let Some(mode) = mode_for(config, src) {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(e) = fs::set_permissions(dir, fs::Permissions::from_mode(mode)) {
+            tracing::warn!(path = %dir.display(), mode = format!("{mode:o}"), error = %e, "failed to set mode of created directory");
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = inherits;
+    if config.preserve_acls
+        && let Some(src) = src
+    {
+        metadata::preserve_acls(src, dir);
+    }
+}
+
+/// Mode for a created directory: `dest_dir_mode`, else the source directory's when
+/// preserving metadata or permissions.
+#[cfg(unix)]
+fn mode_for(config: &Config, src: Option<&Path>) -> Option<u32> {
+    use std::os::unix::fs::PermissionsExt;
+    if config.dest_dir_mode.is_some() {
+        return config.dest_dir_mode;
+    }
+    if !(config.preserve_metadata || config.preserve_permissions) {
+        return None;
+    }
+    let meta = fs::metadata(src?).ok()?;
+    Some(meta.permissions().mode() & 0o7777)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::tempdir;
+
+    fn mode(p: &Path) -> u32 {
+        fs::metadata(p).unwrap().permissions().mode() & 0o7777
+    }
+
+    #[test]
+    fn created_directories_get_the_configured_or_source_mode() {
+        let td = tempdir().unwrap();
+        let base = td.path().join("done");
+        fs::create_dir(&base).unwrap();
+        let src = td.path().join("show");
+        fs::create_dir(&src).unwrap();
+        fs::set_permissions(&src, fs::Permissions::from_mode(0o750)).unwrap();
+
+        let mut cfg = Config::new(td.path().join("in"), &base);
+        cfg.dest_dir_mode = Some(0o775);
+        let dir = base.join("a").join("b");
+        create_dest_dir(&cfg, &dir, Some(&src)).unwrap();
+        assert_eq!(mode(&base.join("a")), 0o775);
+        assert_eq!(mode(&dir), 0o775);
+
+        cfg.dest_dir_mode = None;
+        cfg.preserve_permissions = true;
+        let dir = base.join("show");
+        create_dest_dir(&cfg, &dir, Some(&src)).unwrap();
+        assert_eq!(mode(&dir), 0o750);
+
+        // Existing directories are left alone.
+        cfg.dest_dir_mode = Some(0o700);
+        create_dest_dir(&cfg, &dir, Some(&src)).unwrap();
+        assert_eq!(mode(&dir), 0o750);
+    }
+}
@@ -0,0 +1,172 @@
+//! Diagnostic bundle for failed moves.
+//! - `on_failure` collects what a bug report about a failed copy needs: filesystem type,
+//!   free space, mount flags and network/overlay detection for both ends, the effective
+//!   lock strategy and the lock files present, and the last audit records.
+//! - The bundle is logged as one JSON line (warn) and, with `diagnostics_dir`, written to
+//!   `<diagnostics_dir>/aria_move-diag-<time>-<pid>.json`.
+//!
+//! Notes:
+//! - Refusals by policy (ignored, outside the base, duplicates, ...) are not failures of the
+//!   move itself and get no bundle; neither do dry runs.
+//! - Everything is best-effort: a value that cannot be read is left empty, and a bundle that
+//!   cannot be written only logs a warning.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::warn;
+
+use crate::config::types::Config;
+use crate::errors::AriaMoveError;
+use crate::fsys;
+
+use super::audit;
+use super::lock::effective_lock_strategy;
+use super::lockfile::LOCKFILE_NAME;
+use super::path_lock::sidecar_path;
+
+/// Audit records included in a bundle.
+const AUDIT_RECORDS: usize = 20;
+
+/// Everything recorded about one failed move.
+#[derive(Debug, Serialize)]
+struct Diagnostics {
+    time: String,
+    src: PathBuf,
+    error: String,
+    code: Option<&'static str>,
+    source: EndInfo,
+    destination: EndInfo,
+    lock_strategy: String,
+    locks: Vec<LockState>,
+    recent_audit: Vec<serde_json::Value>,
+}
+
+/// Filesystem facts about one end of the move.
+#[derive(Debug, Serialize)]
+struct EndInfo {
+    path: PathBuf,
+    fs_type: Option<String>,
+    free_bytes: Option<u64>,
+    mount_flags: Vec<&'static str>,
+    network: bool,
+    rename_quirk: Option<&'static str>,
+}
+
+impl EndInfo {
+    fn of(path: &Path) -> Self {
+        let probe = fsys::existing_ancestor(path).unwrap_or(path);
+        EndInfo {
+            path: path.to_path_buf(),
+            fs_type: fsys::fs_type(probe),
+            free_bytes: fsys::free_space_bytes(probe).ok(),
+            mount_flags: fsys::mount_flags(probe).unwrap_or_default(),
+            network: crate::platform::is_network_fs(probe),
+            rename_quirk: fsys::rename_quirk(probe),
+        }
+    }
+}
+
+/// A lock file aria_move may have left: the per-path sidecar or a directory lockfile.
+#[derive(Debug, Serialize)]
+struct LockState {
+    guards: PathBuf,
+    lock_file: PathBuf,
+    present: bool,
+    /// Seconds since the lock file was last modified
+    age_secs: Option<u64>,
+}
+
+impl LockState {
+    fn of(guards: &Path, lock_file: PathBuf) -> Self {
+        let meta = fs::symlink_metadata(&lock_file).ok();
+        LockState {
+            guards: guards.to_path_buf(),
+            present: meta.is_some(),
+            age_secs: meta
+                .and_then(|m| m.modified().ok())
+                .and_then(|t| SystemTime::now().duration_since(t).ok())
+                .map(|d| d.as_secs()),
+            lock_file,
+        }
+    }
+}
+
+/// Log (and with `diagnostics_dir`, save) a bundle for the failed move of `src`.
+pub(super) fn on_failure(config: &Config, src: &Path, err: &anyhow::Error) {
+    if config.dry_run || is_refusal(err) {
+        return;
+    }
+    let bundle = collect(config, src, err);
+    let json = match serde_json::to_string(&bundle) {
+        Ok(json) => json,
+        Err(e) => {
+            warn!(error = %e, "could not serialize diagnostics");
+            return;
+        }
+    };
+    warn!(src = %src.display(), diagnostics = %json, "move failed; diagnostic bundle");
+    if let Some(dir) = config.diagnostics_dir.as_deref()
+        && let Err(e) = save(dir, &bundle)
+    {
+        warn!(dir = %dir.display(), error = %format!("{e:#}"), "could not write diagnostics");
+    }
+}
+
+fn collect(config: &Config, src: &Path, err: &anyhow::Error) -> Diagnostics {
+    let base = &config.completed_base;
+    let dest = src.file_name().map(|n| base.join(n));
+    let mut locks = vec![LockState::of(src, sidecar_path(src))];
+    locks.extend(dest.iter().map(|d| LockState::of(d, sidecar_path(d))));
+    for dir in [&config.download_base, base] {
+        locks.push(LockState::of(dir, dir.join(LOCKFILE_NAME)));
+    }
+    let recent_audit = config
+        .audit_log
+        .as_deref()
+        .and_then(|log| audit::tail(log, AUDIT_RECORDS).ok())
+        .unwrap_or_default();
+    Diagnostics {
+        time: chrono::Local::now().to_rfc3339(),
+        src: src.to_path_buf(),
+        error: format!("{err:#}"),
+        code: err.downcast_ref::<AriaMoveError>().map(|e| e.code()),
+        source: EndInfo::of(src),
+        destination: EndInfo::of(base),
+        lock_strategy: effective_lock_strategy(base, config.lock_strategy).to_string(),
+        locks,
+        recent_audit,
+    }
+}
+
+fn save(dir: &Path, bundle: &Diagnostics) -> Result<()> {
+    fs::create_dir_all(dir).with_context(|| format!("create '{}'", dir.display()))?;
+    let name = format!(
+        "aria_move-diag-{}-{}.json",
+        chrono::Local::now().format("%Y%m%dT%H%M%S%.3f"),
+        std::process::id()
+    );
+    let path = dir.join(name);
+    fs::write(&path, serde_json::to_vec_pretty(bundle)?)
+        .with_context(|| format!("write '{}'", path.display()))
+}
+
+/// Failures that are decisions, not broken copies.
+fn is_refusal(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<AriaMoveError>(),
+        Some(
+            AriaMoveError::Ignored(_)
+                | AriaMoveError::OutsideBase { .. }
+                | AriaMoveError::SourceInCompletedBase(_)
+                | AriaMoveError::DestinationExists(_)
+                | AriaMoveError::DestinationIsSymlink(_)
+                | AriaMoveError::LooksIncomplete(_)
+                | AriaMoveError::SourceNotFound(_)
+                | AriaMoveError::NoneFound(_)
+                | AriaMoveError::ProvidedNotFile(_)
+        )
+    )
+}
@@ -0,0 +1,80 @@
+//! SHA-256 for checksum sidecars, the audit chain and duplicate detection (via `sha2`).
+//! - `Sha256` hashes incrementally; `sha256_file` streams a file through it.
+//! - `to_hex` renders a digest the way `sha256sum` prints it (lowercase).
+
+use sha2::Digest;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// Incremental SHA-256 state (the `sha2` crate).
+#[derive(Clone, Default)]
+pub(crate) struct Sha256(sha2::Sha256);
+
+impl Sha256 {
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    pub(crate) fn finalize(self) -> [u8; 32] {
+        self.0.finalize().into()
+    }
+}
+
+/// SHA-256 of the file at `path`, read in 1 MiB chunks.
+pub(crate) fn sha256_file(path: &Path) -> io::Result<[u8; 32]> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::default();
+    let mut buf = vec![0u8; 1024 * 1024];
+    loop {
+        match file.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => hasher.update(&buf[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(hasher.finalize())
+}
+
+/// Lowercase hex rendering of `bytes`.
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex_of(data: &[u8]) -> String {
+        let mut h = Sha256::default();
+        h.update(data);
+        to_hex(&h.finalize())
+    }
+
+    #[test]
+    fn matches_known_vectors() {
+        assert_eq!(
+            hex_of(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex_of(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            hex_of(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn incremental_updates_match_one_shot() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i % 251) as u8).collect();
+        let mut h = Sha256::default();
+        for chunk in data.chunks(37) {
+            h.update(chunk);
+        }
+        assert_eq!(to_hex(&h.finalize()), hex_of(&data));
+    }
+}
@@ -0,0 +1,180 @@
+//! Merge a source directory into an existing destination directory (`OnDuplicate::Merge`).
+//! Strategy:
+//! - Recreate the source tree under the destination and move files one at a time.
+//! - Colliding files follow `Config::merge_on_conflict` (skip, overwrite, rename with suffix).
+//! - Each file is renamed when possible, else copied (per `copy_backend`) and removed.
+//! - Skipped files stay in the source; source directories left empty are removed.
+//!
+//! Notes:
+//! - Files already merged are not rolled back if a later file fails; with
+//!   `partial_dir_moves` failures are recorded and the merge continues instead.
+//! - Callers hold the completed_base lock while merging.
+
+use anyhow::{Context, Result, anyhow};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+use walkdir::WalkDir;
+
+use crate::config::types::Config;
+use crate::utils::file_is_mutable;
+
+use super::atomic::{MoveOutcome, try_atomic_move_beneath};
+use super::copy::copy_with_backend;
+use super::duplicate::{OnDuplicate, resolve_destination};
+use super::metadata;
+use super::progress::{DirMoveSummary, Observer, SkippedItem};
+
+/// Merge `src_dir` into the existing directory `target`. Returns `target`.
+pub(super) fn merge_dir(
+    config: &Config,
+    src_dir: &Path,
+    target: &Path,
+    observer: &dyn Observer,
+) -> Result<PathBuf> {
+    let policy = config.merge_on_conflict;
+    let mut moved: u64 = 0;
+    let mut skipped: Vec<SkippedItem> = Vec::new();
+
+    for entry in WalkDir::new(src_dir).sort_by_file_name() {
+        let entry = entry.with_context(|| format!("walk {}", src_dir.display()))?;
+        let path = entry.path();
+        let rel = path.strip_prefix(src_dir)?;
+        let dst = target.join(rel);
+
+        if entry.file_type().is_dir() {
+            if dst.exists() && !dst.is_dir() {
+                // A file occupies the directory's name; its children are reported individually below.
+                debug!(path = %dst.display(), "merge: destination has a file where a directory is expected");
+                continue;
+            }
+            super::dest_dir::create_dest_dir(config, &dst, Some(path))
+                .map_err(super::io_error_with_help("create directory", &dst))?;
+            continue;
+        }
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        match merge_file(config, path, &dst, policy) {
+            Ok(Some(final_dest)) => {
+                moved += 1;
+                debug!(src = %path.display(), dest = %final_dest.display(), "merge: moved file");
+            }
+            Ok(None) => skipped.push(SkippedItem {
+                path: path.to_path_buf(),
+                reason: format!("destination exists (merge_on_conflict={policy})"),
+            }),
+            Err(e) if config.partial_dir_moves => skipped.push(SkippedItem {
+                path: path.to_path_buf(),
+                reason: format!("{e:#}"),
+            }),
+            Err(e) => {
+                return Err(e.context(format!(
+                    "merge '{}' into '{}' stopped; {} file(s) already merged",
+                    src_dir.display(),
+                    target.display(),
+                    moved
+                )));
+            }
+        }
+    }
+
+    // Remove source directories that are now empty (deepest first).
+    if !config.copy_only {
+        for entry in WalkDir::new(src_dir)
+            .contents_first(true)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_dir())
+        {
+            let _ = fs::remove_dir(entry.path());
+        }
+    }
+
+    #[cfg(unix)]
+    if let Err(e) = super::util::sync_dir(config.fsync_policy, target) {
+        warn!(error = %e, dir = %target.display(), "best-effort fsync(target) failed");
+    }
+
+    let summary = DirMoveSummary {
+        src: src_dir.to_path_buf(),
+        dest: target.to_path_buf(),
+        files_moved: moved,
+        skipped,
+    };
+    observer.on_dir_summary(&summary);
+    info!(
+        src = %src_dir.display(),
+        dest = %target.display(),
+        files_moved = summary.files_moved,
+        skipped = summary.skipped.len(),
+        "Merged directory into existing destination"
+    );
+    Ok(target.to_path_buf())
+}
+
+/// Move one file into the merge target honoring the duplicate policy.
+/// Returns Ok(None) when the file was skipped because the destination exists.
+fn merge_file(
+    config: &Config,
+    src: &Path,
+    dst: &Path,
+    policy: OnDuplicate,
+) -> Result<Option<PathBuf>> {
+    if file_is_mutable(src, config)? {
+        return Err(anyhow!("File '{}' seems in-use", src.display()));
+    }
+
+    let dest = if dst.exists() {
+        match policy {
+            OnDuplicate::Skip => return Ok(None),
+            OnDuplicate::Overwrite => {
+                if dst.is_dir() {
+                    return Err(anyhow!(
+                        "cannot overwrite directory '{}' with a file",
+                        dst.display()
+                    ));
+                }
+                dst.to_path_buf()
+            }
+            OnDuplicate::RenameWithSuffix | OnDuplicate::Merge => {
+                let parent = dst
+                    .parent()
+                    .ok_or_else(|| anyhow!("destination has no parent: {}", dst.display()))?;
+                let name = dst
+                    .file_name()
+                    .ok_or_else(|| anyhow!("destination has no file name: {}", dst.display()))?;
+                resolve_destination(parent, name, OnDuplicate::RenameWithSuffix)
+            }
+        }
+    } else {
+        dst.to_path_buf()
+    };
+
+    let meta_before = if config.preserve_permissions && !config.preserve_metadata {
+        Some(fs::metadata(src).with_context(|| format!("stat {}", src.display()))?)
+    } else {
+        None
+    };
+
+    if !config.copy_only {
+        let base = &config.completed_base;
+        match try_atomic_move_beneath(base, src, &dest, config.fsync_policy) {
+            Ok(MoveOutcome::Renamed) => return Ok(Some(dest)),
+            Ok(MoveOutcome::CrossDevice) => {}
+            Err(e) => {
+                debug!(error = %e, src = %src.display(), dest = %dest.display(), "merge: rename failed; copying");
+            }
+        }
+    }
+
+    copy_with_backend(config, src, &dest)?;
+    if let Some(meta) = meta_before.as_ref() {
+        let _ = metadata::preserve_permissions_only(&dest, meta);
+    }
+    if !config.copy_only {
+        fs::remove_file(src).map_err(super::io_error_with_help("remove original file", src))?;
+    }
+    Ok(Some(dest))
+}
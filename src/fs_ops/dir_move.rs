@@ -2,48 +2,98 @@
 //! Strategy:
 //! - Try atomic rename of the whole directory first (fast path).
 //! - On failure (e.g., EXDEV), pre-check disk space, then copy the tree and remove the source.
+//!   Files are scheduled per `Config::copy_order` (walk, largest-first, smallest-first) and
+//!   copied with the selected copy engine (`fs::copy` for `builtin`; see engine.rs).
+//! - If the destination directory exists and `dir_on_duplicate` is `merge`, merge into it
+//!   file by file instead (see `dir_merge`). A symlink at that name is refused or removed
+//!   first (`dest_symlink_policy`), so a merge never follows it.
+//! - `copy_only` skips the rename and copies, leaving the source tree untouched.
+//! - `force_copy` skips the rename too, but removes the source after the copy. On
+//!   overlayfs/FUSE the rename is skipped automatically and each copied file is verified.
+//! - While a copy runs, `<target>/.aria_move.copying` records the source; it is removed once
+//!   every file is copied, before the source is deleted. `reconcile` only ever deletes a
+//!   destination that still carries it (see `clean.rs`).
 //!   Concurrency:
 //! - Per-source move lock to avoid concurrent claims on the same source.
-//! - Per-destination-base lock to serialize finalization into the completed_base.
+//! - Per-destination-name lock to serialize finalization into the same name in completed_base.
+//! - The final name is chosen under that lock; a suffixed name is locked too.
 
-use anyhow::{Context, Result, anyhow, bail};
+use anyhow::{Context, Result, anyhow};
 use rayon::prelude::*;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 use walkdir::WalkDir;
 
-use crate::config::types::Config;
+use crate::config::types::{Config, CopyOrder};
+use crate::errors::AriaMoveError;
 use crate::shutdown;
 use crate::utils::{ensure_not_base, file_is_mutable};
 
+use super::beneath;
+use super::dest_dir::create_dest_dir;
+use super::dir_merge;
+use super::duplicate::OnDuplicate;
+use super::file_move::read_only_source;
+use super::helpers::copy_error;
 use super::io_error_with_help;
-use super::lock::{acquire_dir_lock, acquire_move_lock};
+use super::lock::lock_error;
+use super::path_lock::{PathLock, acquire_path_lock_with, lock_final_destination};
+use super::progress::{DirMoveSummary, LogObserver, Observer, ProgressTracker, SkippedItem};
 use super::space;
+use super::timing::{self, Phase};
+
+/// Journal file inside a directory copy that has not finished yet; holds the source path.
+pub(super) const COPY_JOURNAL_NAME: &str = ".aria_move.copying";
 
 /// Move directory contents into completed_base/<src_dir_name>.
 /// - Returns the final destination directory path on success.
 /// - Dry-run prints intent and returns the target path.
-pub fn move_dir(config: &Config, src_dir: &Path) -> Result<PathBuf> {
+/// - Copy progress is logged via `LogObserver`; see `move_dir_with_observer` for custom sinks.
+pub fn move_dir(config: &Config, src_dir: &Path) -> Result<PathBuf, AriaMoveError> {
+    move_dir_with_observer(config, src_dir, &LogObserver::default())
+}
+
+/// Like `move_dir`, but reports per-file progress (files, bytes, ETA) to `observer`
+/// while the copy fallback runs.
+pub fn move_dir_with_observer(
+    config: &Config,
+    src_dir: &Path,
+    observer: &dyn Observer,
+) -> Result<PathBuf, AriaMoveError> {
+    move_dir_impl(config, src_dir, observer).map_err(AriaMoveError::from)
+}
+
+fn move_dir_impl(config: &Config, src_dir: &Path, observer: &dyn Observer) -> Result<PathBuf> {
     if shutdown::is_requested() {
-        bail!("shutdown requested");
+        return Err(AriaMoveError::Interrupted.into());
+    }
+    if super::remote::is_remote(&config.completed_base) {
+        return super::remote::move_to_remote(config, src_dir);
+    }
+    if crate::fsys::is_symlink(src_dir) {
+        return super::symlink::move_symlink_source(config, src_dir, observer);
     }
 
     // Optional: disable locks via env for environments where directory flock returns EACCES.
     let disable_locks = config.disable_locks
         || std::env::var("ARIA_MOVE_DISABLE_LOCKS").ok().as_deref() == Some("1");
-    let _src_lock: Option<super::lock::DirLock> = if disable_locks {
+    let _src_lock: Option<PathLock> = if disable_locks {
         debug!(src = %src_dir.display(), "locks disabled via config or ARIA_MOVE_DISABLE_LOCKS=1 (source dir)");
         None
     } else {
-        match acquire_move_lock(src_dir) {
+        match timing::timed(Phase::LockWait, || {
+            acquire_path_lock_with(src_dir, config.lock_strategy)
+        }) {
             Ok(l) => Some(l),
             Err(e) => {
-                if e.kind() == std::io::ErrorKind::PermissionDenied {
-                    debug!(error = %e, src = %src_dir.display(), "acquire_move_lock permission denied; proceeding without lock (diagnostic)");
+                if e.kind() == std::io::ErrorKind::PermissionDenied || read_only_source(config, &e)
+                {
+                    debug!(error = %e, src = %src_dir.display(), "source lock permission denied; proceeding without lock (diagnostic)");
                     None
                 } else {
-                    return Err(e.into());
+                    return Err(lock_error(src_dir, e));
                 }
             }
         }
@@ -54,79 +104,110 @@ pub fn move_dir(config: &Config, src_dir: &Path) -> Result<PathBuf> {
     let src_name = src_dir
         .file_name()
         .ok_or_else(|| anyhow!("Source directory missing name: {}", src_dir.display()))?;
-    let mut target = config.completed_base.join(src_name);
-    if target.exists() {
-        // Mirror file move behavior: choose a unique destination directory name.
-        target = crate::utils::unique_destination(&target);
-    }
+    let dest_key = config
+        .completed_base
+        .join(super::fit_name(&config.completed_base, src_name));
+    // Never merge into (or follow) a symlink at completed_base/<name>.
+    super::symlink::guard_dest_symlink(config, &dest_key)?;
+    let merge = dest_key.is_dir() && config.dir_on_duplicate == OnDuplicate::Merge;
+    // Mirror file move behavior: choose a unique destination directory name.
+    let resolve_target = || -> Result<PathBuf> {
+        Ok(if dest_key.exists() && !merge {
+            crate::utils::unique_destination(&dest_key)
+        } else {
+            dest_key.clone()
+        })
+    };
+    let target = resolve_target()?;
 
     if config.dry_run {
-        info!(src = %src_dir.display(), dest = %target.display(), "dry-run: would move directory");
+        if merge {
+            info!(src = %src_dir.display(), dest = %target.display(), "dry-run: would merge directory into existing destination");
+        } else {
+            info!(src = %src_dir.display(), dest = %target.display(), "dry-run: would move directory");
+        }
         return Ok(target);
     }
 
-    // Serialize moves that finalize into the same completed_base to avoid races.
-    let _dst_lock: Option<super::lock::DirLock> = if disable_locks {
-        debug!(dest = %config.completed_base.display(), "locks disabled via config or ARIA_MOVE_DISABLE_LOCKS=1 (dest dir)");
+    // Serialize moves that finalize into the same name to avoid races.
+    let _dst_lock: Option<PathLock> = if disable_locks {
+        debug!(dest = %dest_key.display(), "locks disabled via config or ARIA_MOVE_DISABLE_LOCKS=1 (dest dir)");
         None
     } else {
-        match acquire_dir_lock(&config.completed_base) {
+        match timing::timed(Phase::LockWait, || {
+            acquire_path_lock_with(&dest_key, config.lock_strategy)
+        }) {
             Ok(l) => Some(l),
             Err(e) => {
                 if e.kind() == std::io::ErrorKind::PermissionDenied {
-                    debug!(error = %e, dest = %config.completed_base.display(), "acquire_dir_lock permission denied; proceeding without lock (diagnostic)");
+                    debug!(error = %e, dest = %dest_key.display(), "destination lock permission denied; proceeding without lock (diagnostic)");
                     None
                 } else {
-                    return Err(anyhow!(
-                        "acquire lock for '{}': {}",
-                        config.completed_base.display(),
-                        e
-                    ));
+                    return Err(lock_error(&dest_key, e));
                 }
             }
         }
     };
 
+    // Decide the final name under the lock (another mover may have taken it meanwhile) and
+    // lock a suffixed name too.
+    let (target, _final_lock) = if _dst_lock.is_some() {
+        timing::timed(Phase::LockWait, || {
+            lock_final_destination(&dest_key, config.lock_strategy, resolve_target)
+        })?
+    } else {
+        (target, None)
+    };
+
+    if merge {
+        return dir_merge::merge_dir(config, src_dir, &target, observer);
+    }
+
     // Fast path: same-filesystem atomic directory rename.
     // Optional pre-detect of cross-device (Unix) to skip a failing rename.
     let mut did_rename = false;
 
-    // In tests, allow forcing the copy fallback to exercise that path.
-    #[cfg(test)]
-    let force_copy = std::env::var("ARIA_MOVE_FORCE_DIR_COPY").ok().as_deref() == Some("1");
-    #[cfg(not(test))]
-    let force_copy = false;
+    // `force_copy`, or ARIA_MOVE_FORCE_DIR_COPY=1 (the older, directory-only switch).
+    let force_copy =
+        config.force_copy || std::env::var("ARIA_MOVE_FORCE_DIR_COPY").ok().as_deref() == Some("1");
+    // overlayfs/FUSE: copy, and compare each file before the source goes.
+    let quirk = target
+        .parent()
+        .and_then(|dest_dir| super::util::rename_quirk(src_dir, dest_dir));
 
-    #[cfg(unix)]
-    let cross_device =
-        if let (Some(src_parent), Some(dst_parent)) = (src_dir.parent(), target.parent()) {
-            use std::os::unix::fs::MetadataExt;
-            if let (Ok(s_meta), Ok(d_meta)) = (fs::metadata(src_parent), fs::metadata(dst_parent)) {
-                s_meta.dev() != d_meta.dev()
-            } else {
-                false
-            }
-        } else {
-            false
-        };
-    #[cfg(not(unix))]
-    let cross_device = false;
+    let cross_device = match (src_dir.parent(), target.parent()) {
+        (Some(src_parent), Some(dst_parent)) => {
+            matches!(
+                crate::fsys::same_filesystem(src_parent, dst_parent),
+                Ok(false)
+            )
+        }
+        _ => false,
+    };
 
-    if !force_copy && !cross_device {
-        match fs::rename(src_dir, &target) {
+    if !force_copy && !cross_device && !config.copy_only && quirk.is_none() {
+        match timing::timed(Phase::Rename, || {
+            super::fs_trace::traced_to("rename", src_dir, Some(&target), || {
+                beneath::rename(&config.completed_base, src_dir, &target)
+            })
+        }) {
             Ok(()) => {
                 debug!(src = %src_dir.display(), dest = %target.display(), "Renamed directory atomically");
                 // Best-effort fsync of destination parent (and source parent if different) on Unix.
                 #[cfg(unix)]
                 {
                     if let Some(dst_parent) = target.parent()
-                        && let Err(e) = super::util::fsync_dir(dst_parent)
+                        && let Err(e) = timing::timed(Phase::Fsync, || {
+                            super::util::sync_dir(config.fsync_policy, dst_parent)
+                        })
                     {
                         warn!(error = %e, dir = %dst_parent.display(), "best-effort fsync(dst_parent) failed");
                     }
                     if let (Some(sp), Some(dp)) = (src_dir.parent(), target.parent())
                         && sp != dp
-                        && let Err(e) = super::util::fsync_dir(sp)
+                        && let Err(e) = timing::timed(Phase::Fsync, || {
+                            super::util::sync_dir(config.fsync_policy, sp)
+                        })
                     {
                         warn!(error = %e, dir = %sp.display(), "best-effort fsync(src_parent) failed");
                     }
@@ -168,16 +249,18 @@ pub fn move_dir(config: &Config, src_dir: &Path) -> Result<PathBuf> {
     // Cross-filesystem or other rename failures: fallback to copy.
     // Before copying, estimate total size and ensure destination has enough free space.
     let total_bytes = total_bytes_in_tree(src_dir);
-    // Best-effort check; if statting sizes failed we still proceed, but enforce if we have a number.
+    // Best-effort check; if statting sizes failed or took too long we still proceed, but
+    // enforce if we have a number.
     if let Some(required) = total_bytes {
-        space::ensure_space_for_copy(&config.completed_base, required).with_context(|| {
-            format!(
-                "insufficient free space to copy '{}' (~{}) into '{}'",
-                src_dir.display(),
-                space::format_bytes(required),
-                config.completed_base.display()
-            )
-        })?;
+        space::ensure_space_for_copy(&config.completed_base, required, config.space_cushion)
+            .with_context(|| {
+                format!(
+                    "insufficient free space to copy '{}' (~{}) into '{}'",
+                    src_dir.display(),
+                    space::format_bytes(required),
+                    config.completed_base.display()
+                )
+            })?;
     }
 
     // 1) Create directory structure under target.
@@ -188,26 +271,36 @@ pub fn move_dir(config: &Config, src_dir: &Path) -> Result<PathBuf> {
         .try_for_each(|d| -> Result<()> {
             if let Ok(rel) = d.path().strip_prefix(src_dir) {
                 let new_dir = target.join(rel);
-                fs::create_dir_all(&new_dir)
+                create_dest_dir(config, &new_dir, Some(d.path()))
                     .map_err(io_error_with_help("create directory", &new_dir))?;
             }
             Ok(())
         })?;
+    // Partial-mode targets are kept on purpose, so only full copies are journaled.
+    let journal = target.join(COPY_JOURNAL_NAME);
+    if !config.partial_dir_moves {
+        fs::write(&journal, src_dir.to_string_lossy().as_bytes())
+            .map_err(io_error_with_help("write copy journal", &journal))?;
+    }
 
-    // 2) Collect files and copy them in parallel.
-    let files: Vec<_> = WalkDir::new(src_dir)
-        .into_iter()
-        .filter_map(Result::ok)
-        .filter(|e| e.file_type().is_file())
-        .map(|e| e.into_path())
-        .collect();
+    // 2) Collect files and copy them in parallel (scheduled per config.copy_order).
+    let files = ordered_files(src_dir, config.copy_order);
+    debug!(count = files.len(), order = %config.copy_order, "directory copy scheduled");
+    let progress = ProgressTracker::new(files.len() as u64, total_bytes.unwrap_or(0), observer);
 
-    let copy_result: Result<()> = files.par_iter().try_for_each(|path| -> Result<()> {
+    let engine = super::engine::select(config)?;
+    let copy_one = |path: &PathBuf| -> Result<()> {
         // Skip files that appear to be in use to avoid partial copies.
-        if file_is_mutable(path)? {
+        if file_is_mutable(path, config)? {
+            let action = if config.partial_dir_moves {
+                "leaving it in place"
+            } else {
+                "aborting directory move"
+            };
             return Err(anyhow!(
-                "File '{}' seems in-use; aborting directory move",
-                path.display()
+                "File '{}' seems in-use; {}",
+                path.display(),
+                action
             ));
         }
 
@@ -215,11 +308,18 @@ pub fn move_dir(config: &Config, src_dir: &Path) -> Result<PathBuf> {
         let dst = target.join(rel);
 
         if let Some(parent) = dst.parent() {
-            fs::create_dir_all(parent).map_err(io_error_with_help("create directory", parent))?;
+            create_dest_dir(config, parent, None)
+                .map_err(io_error_with_help("create directory", parent))?;
         }
 
         // Copy file data
-        fs::copy(path, &dst).map_err(io_error_with_help("copy file to destination", &dst))?;
+        let bytes = super::source_guard::guard_copy(path, &dst, || {
+            Ok(engine.copy_tree_file(config, path, &dst)?)
+        })?;
+        if quirk.is_some() {
+            super::util::verify_copy(path, &dst)
+                .map_err(io_error_with_help("verify copy", &dst))?;
+        }
         // Metadata preservation; apply full or permissions-only per flags (best-effort)
         if (config.preserve_metadata || config.preserve_permissions)
             && let Ok(src_meta) = fs::metadata(path)
@@ -231,42 +331,367 @@ pub fn move_dir(config: &Config, src_dir: &Path) -> Result<PathBuf> {
                 let _ = super::metadata::preserve_permissions_only(&dst, &src_meta);
             }
         }
+        if config.preserve_acls {
+            super::metadata::preserve_acls(path, &dst);
+        }
+        progress.file_done(bytes);
         Ok(())
+    };
+    if config.partial_dir_moves {
+        // Partial moves copy and clean up in one pass; all of it counts as copy time.
+        return timing::timed(Phase::Copy, || {
+            finish_partial(src_dir, &target, &files, &copy_one, config, observer)
+        });
+    }
+
+    // Walk order keeps rayon's default splitting; explicit orders use par_bridge so workers
+    // pull files from the sorted list front-to-back instead of from split halves.
+    let copy_result: Result<()> = timing::timed(Phase::Copy, || match config.copy_order {
+        CopyOrder::Walk => files.par_iter().try_for_each(copy_one),
+        CopyOrder::LargestFirst | CopyOrder::SmallestFirst => {
+            files.iter().par_bridge().try_for_each(copy_one)
+        }
     });
     if let Err(e) = copy_result {
         // Partial failure cleanup: remove target subtree to avoid half-copied results.
         let _ = fs::remove_dir_all(&target);
         return Err(e);
     }
+    timing::add_bytes(total_bytes.unwrap_or(0));
+    // The copy is complete: from here on a crash must never cost the destination.
+    fs::remove_file(&journal).map_err(io_error_with_help("remove copy journal", &journal))?;
 
-    // 3) Remove the original tree after successful copy.
-    fs::remove_dir_all(src_dir).map_err(io_error_with_help("remove source directory", src_dir))?;
+    // 3) Remove the original tree after successful copy (unless copy-only).
+    if !config.copy_only {
+        super::fs_trace::traced("remove_dir_all", src_dir, || fs::remove_dir_all(src_dir))
+            .map_err(copy_error(
+                "remove_source",
+                "remove source directory",
+                src_dir,
+            ))?;
+    }
 
     // Best-effort fsync of the destination directory to persist entries.
     #[cfg(unix)]
-    if let Err(e) = super::util::fsync_dir(&target) {
+    if let Err(e) = timing::timed(Phase::Fsync, || {
+        super::util::sync_dir(config.fsync_policy, &target)
+    }) {
         warn!(error = %e, dir = %target.display(), "best-effort fsync(target) failed");
     }
 
     info!(
         src = %src_dir.display(),
         dest = %target.display(),
-        "Copied directory contents and removed source"
+        "{}",
+        outcome(config)
     );
     Ok(target)
 }
 
+/// Log message for a finished directory copy.
+fn outcome(config: &Config) -> &'static str {
+    if config.copy_only {
+        "Copied directory contents (source retained)"
+    } else {
+        "Copied directory contents and removed source"
+    }
+}
+
+/// Partial-failure mode (`partial_dir_moves`): copy every file that can be copied, remove only
+/// those sources (none with `copy_only`), leave problematic files in place and keep the
+/// destination tree. The skipped items are reported to the observer as a `DirMoveSummary`.
+/// If no file at all could be moved, the destination tree is removed and the move fails.
+fn finish_partial<F>(
+    src_dir: &Path,
+    target: &Path,
+    files: &[PathBuf],
+    copy_one: &F,
+    config: &Config,
+    observer: &dyn Observer,
+) -> Result<PathBuf>
+where
+    F: Fn(&PathBuf) -> Result<()> + Sync,
+{
+    let attempt = |path: &PathBuf| (path.clone(), copy_one(path));
+    let results: Vec<(PathBuf, Result<()>)> = match config.copy_order {
+        CopyOrder::Walk => files.par_iter().map(attempt).collect(),
+        CopyOrder::LargestFirst | CopyOrder::SmallestFirst => {
+            files.iter().par_bridge().map(attempt).collect()
+        }
+    };
+
+    let mut moved: u64 = 0;
+    let mut skipped: Vec<SkippedItem> = Vec::new();
+    for (path, res) in results {
+        match res {
+            Ok(()) if config.copy_only => moved += 1,
+            Ok(()) => match super::fs_trace::traced("unlink", &path, || fs::remove_file(&path)) {
+                Ok(()) => moved += 1,
+                Err(e) => skipped.push(SkippedItem {
+                    path,
+                    reason: format!("copied but could not remove source: {e}"),
+                }),
+            },
+            Err(e) => {
+                // Drop any half-written copy so the destination only holds complete files.
+                if let Ok(rel) = path.strip_prefix(src_dir) {
+                    let _ = fs::remove_file(target.join(rel));
+                }
+                skipped.push(SkippedItem {
+                    path,
+                    reason: format!("{e:#}"),
+                });
+            }
+        }
+    }
+    skipped.sort_by(|a, b| a.path.cmp(&b.path));
+
+    if moved == 0 && !skipped.is_empty() {
+        // Nothing made it across: drop the destination tree and leave the source as it was.
+        let _ = fs::remove_dir_all(target);
+        let err = AriaMoveError::CopyFailed {
+            path: src_dir.to_path_buf(),
+            phase: "copy".to_string(),
+            context: format!(
+                "no file could be moved ({} skipped; first: {}: {})",
+                skipped.len(),
+                skipped[0].path.display(),
+                skipped[0].reason
+            ),
+        };
+        observer.on_dir_summary(&DirMoveSummary {
+            src: src_dir.to_path_buf(),
+            dest: target.to_path_buf(),
+            files_moved: 0,
+            skipped,
+        });
+        return Err(err.into());
+    }
+
+    // Remove now-empty source directories (deepest first); non-empty ones stay behind.
+    if !config.copy_only {
+        for entry in WalkDir::new(src_dir)
+            .contents_first(true)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_dir())
+        {
+            let _ = fs::remove_dir(entry.path());
+        }
+    }
+
+    #[cfg(unix)]
+    if let Err(e) = super::util::sync_dir(config.fsync_policy, target) {
+        warn!(error = %e, dir = %target.display(), "best-effort fsync(target) failed");
+    }
+
+    let summary = DirMoveSummary {
+        src: src_dir.to_path_buf(),
+        dest: target.to_path_buf(),
+        files_moved: moved,
+        skipped,
+    };
+    observer.on_dir_summary(&summary);
+    if summary.skipped.is_empty() {
+        info!(src = %src_dir.display(), dest = %target.display(), "{}", outcome(config));
+    } else {
+        warn!(
+            src = %src_dir.display(),
+            dest = %target.display(),
+            files_moved = summary.files_moved,
+            skipped = summary.skipped.len(),
+            "Partial directory move: some files were left in place"
+        );
+    }
+    Ok(target.to_path_buf())
+}
+
+/// Collect regular files under `root`, sorted according to `order`.
+/// Files whose size cannot be read sort as zero bytes (they surface errors during copy).
+fn ordered_files(root: &Path, order: CopyOrder) -> Vec<PathBuf> {
+    let entries = WalkDir::new(root)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file());
+    if order == CopyOrder::Walk {
+        return entries.map(|e| e.into_path()).collect();
+    }
+
+    let mut sized: Vec<(u64, PathBuf)> = entries
+        .map(|e| (e.metadata().map(|m| m.len()).unwrap_or(0), e.into_path()))
+        .collect();
+    // Stable sort keeps walk order among equally sized files.
+    match order {
+        CopyOrder::LargestFirst => sized.sort_by_key(|e| std::cmp::Reverse(e.0)),
+        CopyOrder::SmallestFirst => sized.sort_by_key(|e| e.0),
+        CopyOrder::Walk => {}
+    }
+    sized.into_iter().map(|(_, p)| p).collect()
+}
+
+/// How long the pre-copy size estimate may take before the copy starts without it.
+const SIZE_ESTIMATE_BUDGET: Duration = Duration::from_secs(30);
+
 /// Estimate total bytes of regular files under `root`.
-/// Returns Some(bytes) on success, or None if any metadata read fails.
-fn total_bytes_in_tree(root: &Path) -> Option<u64> {
+/// Subdirectories are walked in parallel; returns None if any metadata read fails or the
+/// walk takes longer than `SIZE_ESTIMATE_BUDGET` (the estimate is best effort).
+pub(super) fn total_bytes_in_tree(root: &Path) -> Option<u64> {
+    let total = total_bytes_within(root, SIZE_ESTIMATE_BUDGET);
+    if total.is_none() {
+        debug!(path = %root.display(), "size estimate unavailable; continuing without it");
+    }
+    total
+}
+
+fn total_bytes_within(root: &Path, budget: Duration) -> Option<u64> {
+    tree_bytes(root, Instant::now() + budget)
+}
+
+fn tree_bytes(dir: &Path, deadline: Instant) -> Option<u64> {
+    if Instant::now() >= deadline {
+        return None;
+    }
+    // Unreadable directories are skipped, like the walk that copies the tree.
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Some(0);
+    };
     let mut total: u64 = 0;
-    for entry in WalkDir::new(root).into_iter().filter_map(Result::ok) {
-        if entry.file_type().is_file() {
-            match entry.metadata() {
-                Ok(m) => total = total.saturating_add(m.len()),
-                Err(_) => return None, // give up on precise check; we'll proceed without enforcing
-            }
+    let mut subdirs = Vec::new();
+    for entry in entries.filter_map(Result::ok) {
+        let Ok(ft) = entry.file_type() else {
+            continue;
+        };
+        if ft.is_dir() {
+            subdirs.push(entry.path());
+        } else if ft.is_file() {
+            // give up on precise check; the move proceeds without enforcing
+            total = total.saturating_add(entry.metadata().ok()?.len());
         }
     }
-    Some(total)
+    let nested = subdirs
+        .par_iter()
+        .map(|d| tree_bytes(d, deadline))
+        .try_reduce(|| 0, |a, b| Some(a.saturating_add(b)))?;
+    Some(total.saturating_add(nested))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::bail;
+    use tempfile::tempdir;
+
+    fn names(files: &[PathBuf]) -> Vec<String> {
+        files
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn ordered_files_sorts_by_size() {
+        let td = tempdir().unwrap();
+        fs::create_dir_all(td.path().join("sub")).unwrap();
+        fs::write(td.path().join("mid.bin"), vec![0u8; 100]).unwrap();
+        fs::write(td.path().join("sub/big.bin"), vec![0u8; 1000]).unwrap();
+        fs::write(td.path().join("small.bin"), vec![0u8; 10]).unwrap();
+
+        let largest = ordered_files(td.path(), CopyOrder::LargestFirst);
+        assert_eq!(names(&largest), ["big.bin", "mid.bin", "small.bin"]);
+
+        let smallest = ordered_files(td.path(), CopyOrder::SmallestFirst);
+        assert_eq!(names(&smallest), ["small.bin", "mid.bin", "big.bin"]);
+
+        let walk = ordered_files(td.path(), CopyOrder::Walk);
+        assert_eq!(walk.len(), 3);
+    }
+
+    #[test]
+    fn size_estimate_sums_nested_files_within_budget() {
+        let td = tempdir().unwrap();
+        fs::create_dir_all(td.path().join("a/b")).unwrap();
+        fs::create_dir_all(td.path().join("c")).unwrap();
+        fs::write(td.path().join("top.bin"), vec![0u8; 10]).unwrap();
+        fs::write(td.path().join("a/b/deep.bin"), vec![0u8; 100]).unwrap();
+        fs::write(td.path().join("c/side.bin"), vec![0u8; 1000]).unwrap();
+
+        assert_eq!(total_bytes_in_tree(td.path()), Some(1110));
+        assert_eq!(total_bytes_within(td.path(), Duration::ZERO), None);
+    }
+
+    #[test]
+    fn finish_partial_leaves_failed_files_in_place() {
+        use crate::fs_ops::progress::NoopObserver;
+
+        let td = tempdir().unwrap();
+        let src = td.path().join("src");
+        let dst = td.path().join("dst");
+        fs::create_dir_all(src.join("sub")).unwrap();
+        fs::create_dir_all(dst.join("sub")).unwrap();
+        fs::write(src.join("ok.bin"), b"ok").unwrap();
+        fs::write(src.join("sub/busy.part"), b"busy").unwrap();
+
+        let files = ordered_files(&src, CopyOrder::Walk);
+        let copy_one = |p: &PathBuf| -> Result<()> {
+            if p.extension().is_some_and(|e| e == "part") {
+                bail!("in use");
+            }
+            fs::copy(p, dst.join(p.strip_prefix(&src)?))?;
+            Ok(())
+        };
+        let out = finish_partial(
+            &src,
+            &dst,
+            &files,
+            &copy_one,
+            &Config::default(),
+            &NoopObserver,
+        )
+        .unwrap();
+
+        assert_eq!(out, dst);
+        assert_eq!(fs::read(dst.join("ok.bin")).unwrap(), b"ok");
+        assert!(!src.join("ok.bin").exists(), "moved source removed");
+        assert!(
+            src.join("sub/busy.part").exists(),
+            "busy file left in place"
+        );
+        assert!(!dst.join("sub/busy.part").exists());
+    }
+
+    #[test]
+    fn finish_partial_fails_when_no_file_moves() {
+        use crate::fs_ops::progress::NoopObserver;
+
+        let td = tempdir().unwrap();
+        let src = td.path().join("src");
+        let dst = td.path().join("dst");
+        fs::create_dir_all(src.join("sub")).unwrap();
+        fs::create_dir_all(dst.join("sub")).unwrap();
+        fs::write(src.join("a.part"), b"a").unwrap();
+        fs::write(src.join("sub/b.part"), b"b").unwrap();
+
+        let files = ordered_files(&src, CopyOrder::Walk);
+        let copy_one = |_: &PathBuf| -> Result<()> { bail!("in use") };
+        let err = finish_partial(
+            &src,
+            &dst,
+            &files,
+            &copy_one,
+            &Config::default(),
+            &NoopObserver,
+        )
+        .unwrap_err();
+
+        assert!(
+            matches!(
+                err.downcast_ref::<AriaMoveError>(),
+                Some(AriaMoveError::CopyFailed { .. })
+            ),
+            "{err:#}"
+        );
+        assert!(!dst.exists(), "empty destination tree removed");
+        assert!(src.join("a.part").exists());
+        assert!(src.join("sub/b.part").exists());
+    }
 }
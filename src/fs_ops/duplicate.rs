@@ -4,23 +4,67 @@
 //! - Skip: return the intended path; caller should check for existence and skip if it exists.
 //! - Overwrite: return the intended path; caller overwrites existing file if present.
 //! - RenameWithSuffix: generate a unique name by appending " (n)" before the extension.
+//! - Merge (directories only): merge into the existing directory, applying the per-file policy
+//!   to each colliding file. For files it behaves like RenameWithSuffix.
 //!
 //! Notes:
 //! - This only decides the path name based on current filesystem state. Callers should still
 //!   hold appropriate directory locks to avoid races with concurrent movers.
+//! - `fit_name` shortens names that would exceed the destination's name or path limit up
+//!   front (keeping the extension, adding a hash of the full name), so long torrent names
+//!   do not fail late with ENAMETOOLONG. Suffixed names are kept within the limit too.
 
 use std::ffi::{OsStr, OsString};
+use std::fmt;
 use std::path::{Path, PathBuf};
-use tracing::trace;
+use std::str::FromStr;
+use tracing::{debug, trace};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use crate::utils::path_taken;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum OnDuplicate {
     /// Use the requested name; caller should skip operation if the path already exists.
     Skip,
     /// Use the requested name and overwrite if it already exists.
     Overwrite,
     /// Pick a unique name by appending " (n)" before the extension.
+    #[default]
     RenameWithSuffix,
+    /// Directories: merge into the existing directory. Files: same as RenameWithSuffix.
+    Merge,
+}
+
+impl OnDuplicate {
+    /// Parse common string names into a policy (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "skip" => Some(OnDuplicate::Skip),
+            "overwrite" | "replace" => Some(OnDuplicate::Overwrite),
+            "rename" | "rename-with-suffix" | "suffix" => Some(OnDuplicate::RenameWithSuffix),
+            "merge" => Some(OnDuplicate::Merge),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for OnDuplicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            OnDuplicate::Skip => "skip",
+            OnDuplicate::Overwrite => "overwrite",
+            OnDuplicate::RenameWithSuffix => "rename",
+            OnDuplicate::Merge => "merge",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for OnDuplicate {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("invalid duplicate policy: '{s}'"))
+    }
 }
 
 /// Compute the destination filename according to the duplicate policy.
@@ -36,7 +80,7 @@ pub fn resolve_destination(dst_dir: &Path, name: &OsStr, policy: OnDuplicate) ->
 
     match policy {
         OnDuplicate::Skip | OnDuplicate::Overwrite => candidate,
-        OnDuplicate::RenameWithSuffix => {
+        OnDuplicate::RenameWithSuffix | OnDuplicate::Merge => {
             // Do not suffix our own internal transient names; keep them as-is.
             if let Some(s) = name.to_str()
                 && s.starts_with(".aria_move.")
@@ -52,7 +96,7 @@ pub fn resolve_destination(dst_dir: &Path, name: &OsStr, policy: OnDuplicate) ->
             let ext_os: Option<OsString> = base.extension().map(|e| e.to_os_string());
             let adjusted_base = build_name_with_suffix(&stem_os, ext_os.as_deref(), "");
             let adjusted_candidate = dst_dir.join(&adjusted_base);
-            if !adjusted_candidate.exists() {
+            if !path_taken(&adjusted_candidate) {
                 return adjusted_candidate;
             }
             unique_with_numeric_suffix(dst_dir, &adjusted_base)
@@ -78,7 +122,7 @@ fn unique_with_numeric_suffix(dst_dir: &Path, name: &OsStr) -> PathBuf {
 
     // First try the requested name; if free, use it.
     let mut candidate = dst_dir.join(name);
-    if !candidate.exists() {
+    if !path_taken(&candidate) {
         return candidate;
     }
 
@@ -91,7 +135,7 @@ fn unique_with_numeric_suffix(dst_dir: &Path, name: &OsStr) -> PathBuf {
         let new_name = build_name_with_suffix(&stem, ext.as_deref(), &suffix);
 
         candidate = dst_dir.join(&new_name);
-        if !candidate.exists() {
+        if !path_taken(&candidate) {
             return candidate;
         }
         collisions = collisions.saturating_add(1);
@@ -113,6 +157,51 @@ const MAX_FILENAME_LEN: usize = 240; // leave headroom for legacy MAX_PATH
 #[cfg(not(windows))]
 const MAX_FILENAME_LEN: usize = 255; // typical POSIX/EXT limits
 
+/// Longest destination path: legacy MAX_PATH on Windows (unless `\\?\` verbatim), PATH_MAX
+/// elsewhere.
+#[cfg(windows)]
+const MAX_PATH_LEN: usize = 259;
+#[cfg(not(windows))]
+const MAX_PATH_LEN: usize = 4095;
+
+/// Room a shortened name leaves for a later duplicate suffix (`-<epoch ms>-<pid>-n`, ` (n)`).
+const SUFFIX_RESERVE: usize = 32;
+
+/// Longer "extensions" (e.g. after a dot in a release name) are shortened with the stem.
+const MAX_KEPT_EXT: usize = 16;
+
+/// `name` as it may be created in `dst_dir`: unchanged if it fits the filesystem's name limit
+/// and the path limit, else shortened to leave `SUFFIX_RESERVE`, keeping the extension and
+/// ending the stem in `~<8 hex>` of the SHA-256 of the full name. Distinct long names that
+/// share a prefix therefore stay distinct, and the same name always maps to the same result.
+pub(crate) fn fit_name(dst_dir: &Path, name: &OsStr) -> OsString {
+    let name_limit = crate::fsys::name_max(dst_dir)
+        .unwrap_or(MAX_FILENAME_LEN)
+        .min(MAX_FILENAME_LEN);
+    let verbatim = cfg!(windows) && dst_dir.as_os_str().to_string_lossy().starts_with(r"\\?\");
+    let path_room = if verbatim {
+        usize::MAX
+    } else {
+        MAX_PATH_LEN.saturating_sub(name_len_units(dst_dir.as_os_str()) + 1)
+    };
+    let limit = name_limit.min(path_room);
+    if name_len_units(name) <= limit {
+        return name.to_os_string();
+    }
+
+    let path = Path::new(name);
+    let (stem, ext) = match (path.file_stem(), path.extension()) {
+        (Some(stem), Some(ext)) if name_len_units(ext) <= MAX_KEPT_EXT => (stem, Some(ext)),
+        _ => (name, None),
+    };
+    let mut hasher = super::digest::Sha256::default();
+    hasher.update(name.as_encoded_bytes());
+    let tag = format!("~{}", &super::digest::to_hex(&hasher.finalize())[..8]);
+    let fitted = build_name_within(stem, ext, &tag, limit.saturating_sub(SUFFIX_RESERVE));
+    debug!(name = ?name, fitted = ?fitted, limit, "destination name too long; shortened");
+    fitted
+}
+
 /// Measure the approximate length of an OsStr for filename budgeting.
 #[cfg(unix)]
 fn name_len_units(s: &OsStr) -> usize {
@@ -127,7 +216,12 @@ fn name_len_units(s: &OsStr) -> usize {
 }
 
 /// Truncate the stem if needed to ensure `stem + suffix + ["." + ext]` fits within MAX_FILENAME_LEN.
-fn build_name_with_suffix(stem: &OsStr, ext: Option<&OsStr>, suffix: &str) -> OsString {
+pub(crate) fn build_name_with_suffix(stem: &OsStr, ext: Option<&OsStr>, suffix: &str) -> OsString {
+    build_name_within(stem, ext, suffix, MAX_FILENAME_LEN)
+}
+
+/// `build_name_with_suffix` with an explicit length limit.
+fn build_name_within(stem: &OsStr, ext: Option<&OsStr>, suffix: &str, limit: usize) -> OsString {
     // Compute fixed overhead (suffix + optional "." + ext)
     let mut overhead = name_len_units(OsStr::new(suffix));
     let mut ext_part = OsString::new();
@@ -139,9 +233,9 @@ fn build_name_with_suffix(stem: &OsStr, ext: Option<&OsStr>, suffix: &str) -> Os
 
     let mut stem_os = stem.to_os_string();
     let name_len = name_len_units(&stem_os) + overhead;
-    if name_len > MAX_FILENAME_LEN {
+    if name_len > limit {
         // Need to shrink stem to fit
-        let budget = MAX_FILENAME_LEN.saturating_sub(overhead);
+        let budget = limit.saturating_sub(overhead);
         if budget == 0 {
             // Pathologically small budget; fall back to minimal marker
             stem_os = OsString::from("f");
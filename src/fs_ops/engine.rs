@@ -0,0 +1,160 @@
+//! Pluggable copy engines.
+//! - A `CopyEngine` gets file data into completed_base when a rename is not possible. Moves
+//!   go through `select`, so file, directory, merge and mirror code never names an engine.
+//! - Built in: `builtin` (clonefile / copy_file_range / streaming, starting with the method
+//!   `bench-dest` measured fastest), `reflink`, `kernel` and `streaming` (that method first,
+//!   then the usual fallback), and `rsync`.
+//! - `copy_engine` (config / `--copy-engine`) picks an engine by name; unset, `copy_backend`
+//!   picks `builtin` or `rsync`.
+//! - `EngineCapabilities` describe an engine: resumable, verifies, sparse-aware.
+//!
+//! Notes:
+//! - Library users add engines with `EngineRegistry::global().register(...)`; registering a
+//!   name again replaces the earlier engine.
+//! - rclone is not an engine: it serves `rclone:` destinations, which are not local paths.
+
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use crate::config::types::{Config, CopyBackend};
+use crate::errors::AriaMoveError;
+
+use super::bench::CopyMethod;
+use super::copy::BuiltinEngine;
+use super::rsync::RsyncEngine;
+
+/// What a copy engine offers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct EngineCapabilities {
+    /// An interrupted copy continues from its temp instead of starting over.
+    pub resumable: bool,
+    /// The copied data is checked against the source (beyond its length).
+    pub verifies: bool,
+    /// Holes in sparse files stay holes at the destination.
+    pub sparse_aware: bool,
+}
+
+/// A way of copying one file into completed_base.
+pub trait CopyEngine: Send + Sync {
+    /// Name used by `copy_engine` and the registry.
+    fn name(&self) -> &str;
+
+    fn capabilities(&self) -> EngineCapabilities;
+
+    /// Copy `src` to `dest` so that `dest` appears complete or not at all (temp + rename).
+    /// Metadata is preserved by the caller.
+    fn copy_file(&self, config: &Config, src: &Path, dest: &Path) -> Result<(), AriaMoveError>;
+
+    /// Copy one file of a directory move into the staged target tree and return the bytes
+    /// copied. The tree is journaled as a whole, so engines may skip the temp; the default
+    /// uses `copy_file`.
+    fn copy_tree_file(
+        &self,
+        config: &Config,
+        src: &Path,
+        dest: &Path,
+    ) -> Result<u64, AriaMoveError> {
+        self.copy_file(config, src, dest)?;
+        Ok(fs::metadata(dest).map(|m| m.len()).unwrap_or(0))
+    }
+}
+
+/// Process-wide set of copy engines, by name.
+pub struct EngineRegistry {
+    engines: RwLock<BTreeMap<String, Arc<dyn CopyEngine>>>,
+}
+
+impl EngineRegistry {
+    /// The registry used by every move in this process, with the built-in engines.
+    pub fn global() -> &'static EngineRegistry {
+        static GLOBAL: OnceLock<EngineRegistry> = OnceLock::new();
+        GLOBAL.get_or_init(|| {
+            let registry = EngineRegistry {
+                engines: RwLock::new(BTreeMap::new()),
+            };
+            registry.register(Arc::new(BuiltinEngine::auto()));
+            for method in [
+                CopyMethod::Reflink,
+                CopyMethod::Kernel,
+                CopyMethod::Streaming,
+            ] {
+                registry.register(Arc::new(BuiltinEngine::with_method(method)));
+            }
+            registry.register(Arc::new(RsyncEngine));
+            registry
+        })
+    }
+
+    /// Add `engine` under its name; returns the engine it replaced, if any.
+    pub fn register(&self, engine: Arc<dyn CopyEngine>) -> Option<Arc<dyn CopyEngine>> {
+        let mut engines = self.engines.write().unwrap_or_else(|e| e.into_inner());
+        engines.insert(engine.name().to_string(), engine)
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn CopyEngine>> {
+        let engines = self.engines.read().unwrap_or_else(|e| e.into_inner());
+        engines.get(name).cloned()
+    }
+
+    /// Registered engine names, sorted.
+    pub fn names(&self) -> Vec<String> {
+        let engines = self.engines.read().unwrap_or_else(|e| e.into_inner());
+        engines.keys().cloned().collect()
+    }
+}
+
+/// The engine `config` asks for: `copy_engine` by name, else the one for `copy_backend`.
+pub(super) fn select(config: &Config) -> Result<Arc<dyn CopyEngine>> {
+    let name = match config.copy_engine.as_deref() {
+        Some(name) => name,
+        None => match config.copy_backend {
+            CopyBackend::Builtin => "builtin",
+            CopyBackend::Rsync => "rsync",
+        },
+    };
+    let registry = EngineRegistry::global();
+    registry.get(name).ok_or_else(|| {
+        anyhow!(
+            "unknown copy engine '{name}' (available: {})",
+            registry.names().join(", ")
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fake;
+
+    impl CopyEngine for Fake {
+        fn name(&self) -> &str {
+            "fake-for-test"
+        }
+        fn capabilities(&self) -> EngineCapabilities {
+            EngineCapabilities::default()
+        }
+        fn copy_file(&self, _: &Config, _: &Path, _: &Path) -> Result<(), AriaMoveError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn select_follows_copy_engine_then_copy_backend() {
+        let mut cfg = Config::default();
+        assert_eq!(select(&cfg).unwrap().name(), "builtin");
+        cfg.copy_backend = CopyBackend::Rsync;
+        assert_eq!(select(&cfg).unwrap().name(), "rsync");
+        assert!(select(&cfg).unwrap().capabilities().verifies);
+
+        cfg.copy_engine = Some("fake-for-test".into());
+        let err = select(&cfg).err().unwrap().to_string();
+        assert!(err.contains("streaming"), "{err}");
+        EngineRegistry::global().register(Arc::new(Fake));
+        assert_eq!(select(&cfg).unwrap().name(), "fake-for-test");
+    }
+}
@@ -1,22 +1,320 @@
 use anyhow::{Result, anyhow, bail};
+use std::borrow::Cow;
 use std::fs;
 use std::path::{Path, PathBuf};
-use tracing::debug;
+use tracing::{debug, info, warn};
 
-use crate::config::types::Config;
+use crate::aria2_rpc;
+use crate::config::types::{Config, RpcAction, SymlinkPolicy};
+use crate::errors::AriaMoveError;
+use crate::notify::{self, NotifyEvent};
 use crate::utils::ensure_not_base;
 
+use super::audit;
+use super::diagnostics;
 use super::dir_move::move_dir;
-use super::file_move::move_file;
+use super::duplicate::OnDuplicate;
+use super::file_move::move_file_with;
+use super::handlers;
+use super::ignore::IgnoreRules;
+use super::progress::LogObserver;
+use super::provenance;
+use super::redownload::{self, OnRedownload, Replaced};
+use super::remote::{is_remote, move_to_remote};
+use super::sanity;
+use super::symlink::move_symlink_source;
+use super::timing::{self, MoveTimings};
+
+/// Per-call overrides for `move_entry_with`. `None` fields keep the `Config` behaviour.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MoveOptions {
+    /// Policy when `completed_base/<name>` already exists.
+    /// - Files: skip (`DestinationExists` error, source untouched), overwrite, or rename with
+    ///   a " (n)" suffix. `None` keeps the timestamp-suffixed unique name.
+    /// - Directories: rename or merge (`None` uses `Config::dir_on_duplicate`), or skip.
+    ///   Overwriting a directory is refused.
+    pub on_duplicate: Option<OnDuplicate>,
+    /// Per-file policy inside a directory merge (`Config::merge_on_conflict`).
+    pub merge_on_conflict: Option<OnDuplicate>,
+    /// How a symlink source is handled (`Config::symlink_source_policy`).
+    pub symlink_source_policy: Option<SymlinkPolicy>,
+    /// Only report the destination (`Config::dry_run`).
+    pub dry_run: Option<bool>,
+    /// aria2 GID written to the `user.aria_move.gid` tag (`Config::tag_destination`).
+    pub gid: Option<String>,
+    /// Torrent infohash (hex): recorded with the destination, and a repeat completion is
+    /// handled per `Config::on_redownload`.
+    pub infohash: Option<String>,
+}
+
+impl MoveOptions {
+    /// `config` with this call's overrides applied (borrowed when there are none).
+    fn apply<'a>(&self, config: &'a Config) -> Cow<'a, Config> {
+        let dir_policy = self
+            .on_duplicate
+            .filter(|p| matches!(p, OnDuplicate::Merge | OnDuplicate::RenameWithSuffix));
+        if dir_policy.is_none()
+            && self.merge_on_conflict.is_none()
+            && self.symlink_source_policy.is_none()
+            && self.dry_run.is_none()
+        {
+            return Cow::Borrowed(config);
+        }
+        let mut cfg = config.clone();
+        if let Some(p) = dir_policy {
+            cfg.dir_on_duplicate = p;
+        }
+        if let Some(p) = self.merge_on_conflict {
+            cfg.merge_on_conflict = p;
+        }
+        if let Some(p) = self.symlink_source_policy {
+            cfg.symlink_source_policy = p;
+        }
+        if let Some(d) = self.dry_run {
+            cfg.dry_run = d;
+        }
+        Cow::Owned(cfg)
+    }
+}
 
 /// Top-level dispatcher for moving a single path (file or directory).
 /// - Ensures `src` is not the configured download base.
+/// - With `require_source_in_base`, refuses sources outside download_base or inside completed_base.
+/// - Refuses paths excluded by `download_base/.ariamoveignore`.
+/// - With `content_sanity_check`, refuses files that end in an all-zero region.
+/// - Applies the per-extension `handlers`: verify before the move, delete or archive a file
+///   source once the move may go ahead, and work on the moved tree of a directory after it.
+/// - With an infohash (`MoveOptions::infohash`), applies `on_redownload` to a torrent moved before.
+/// - With `bt_mode` and a GID (`MoveOptions::gid`), copies instead of moving while aria2 seeds it.
+/// - With `rpc_action` and a GID, pauses or removes the aria2 task first (a paused task is
+///   resumed from the new location afterwards).
+/// - Transfers to a remote `completed_base` (`sftp://...`, `rclone:...`) instead of moving locally.
 /// - Stats once and branches based on the file type (avoids double syscalls).
 /// - Delegates to file or directory mover and returns the final destination path.
 pub fn move_entry(config: &Config, src: &Path) -> Result<PathBuf> {
+    move_entry_with(config, src, &MoveOptions::default())
+}
+
+/// `move_entry` with per-call overrides (duplicate policies, symlink policy, dry run).
+/// Remote destinations ignore `on_duplicate` (uploads always pick a free name).
+pub fn move_entry_with(config: &Config, src: &Path, opts: &MoveOptions) -> Result<PathBuf> {
+    move_entry_timed(config, src, opts).map(|(dest, _)| dest)
+}
+
+/// `move_entry_with` that also returns the phase timings. A real (non dry-run) move logs
+/// them at info level together with the copy throughput, after tagging the destination
+/// when `tag_destination` is on. Its outcome goes to `audit_log` when that is set; a failure
+/// also logs a diagnostic bundle (saved to `diagnostics_dir` when set).
+pub(crate) fn move_entry_timed(
+    config: &Config,
+    src: &Path,
+    opts: &MoveOptions,
+) -> Result<(PathBuf, MoveTimings)> {
+    let config = &*opts.apply(config);
+    let paused = stop_task(config, opts.gid.as_deref());
+    let config = &*bt_mode_config(config, opts.gid.as_deref());
+    if config.trace_fs_ops {
+        super::fs_trace::set_trace_fs_ops(true);
+    }
+    let (result, timings) = timing::collect(|| dispatch(config, src, opts));
+    if let Some(gid) = paused {
+        resume_task(config, gid, src, &result);
+    }
+    audit::record(config, src, &result);
+    if let Err(e) = &result {
+        diagnostics::on_failure(config, src, e);
+    }
+    let dest = result?;
+    if !config.dry_run
+        && config.tag_destination
+        && !is_remote(&config.completed_base)
+        && fs::symlink_metadata(&dest).is_ok()
+    {
+        provenance::tag_destination(src, &dest, opts.gid.as_deref());
+    }
+    if !config.dry_run {
+        info!(
+            src = %src.display(),
+            dest = %dest.display(),
+            bytes = timings.bytes_copied,
+            mb_per_s = timings.throughput_mb_s().map(|v| (v * 10.0).round() / 10.0),
+            total_ms = timings.total.as_millis() as u64,
+            probe_ms = timings.probe.as_millis() as u64,
+            lock_wait_ms = timings.lock_wait.as_millis() as u64,
+            copy_ms = timings.copy.as_millis() as u64,
+            rename_ms = timings.rename.as_millis() as u64,
+            fsync_ms = timings.fsync.as_millis() as u64,
+            "Move timings"
+        );
+        warn_if_slow(config, src, &dest, &timings);
+    }
+    Ok((dest, timings))
+}
+
+/// `rpc_action`: pause or remove the aria2 task `gid` before the move, so nothing in aria2
+/// holds its files. Returns the GID when it was paused (to be resumed afterwards). A task
+/// that cannot be stopped only logs a warning; the move goes ahead.
+fn stop_task<'a>(config: &Config, gid: Option<&'a str>) -> Option<&'a str> {
+    let gid = gid.filter(|_| config.rpc_action != RpcAction::None && !config.dry_run)?;
+    match aria2_rpc::stop(config, gid, config.rpc_action) {
+        Ok(()) => {
+            info!(gid, action = %config.rpc_action, "aria2 task stopped before the move");
+            (config.rpc_action == RpcAction::Pause).then_some(gid)
+        }
+        Err(e) => {
+            warn!(gid, action = %config.rpc_action, error = %e, "could not stop the aria2 task; moving anyway");
+            None
+        }
+    }
+}
+
+/// Unpause the task paused by `stop_task`. After a move it first points aria2 at the new
+/// location so it seeds from there; a destination aria2 cannot use (renamed on a duplicate,
+/// remote) leaves it paused, as resuming would download everything again.
+fn resume_task(config: &Config, gid: &str, src: &Path, result: &Result<PathBuf>) {
+    let moved = match result {
+        Ok(dest) if !config.copy_only && fs::symlink_metadata(src).is_err() => Some(dest),
+        _ => None,
+    };
+    let dir = match moved {
+        Some(dest) if dest.file_name() == src.file_name() && !is_remote(&config.completed_base) => {
+            dest.parent()
+        }
+        Some(dest) => {
+            warn!(gid, dest = %dest.display(), "aria2 cannot find the moved data under its new name; task left paused");
+            return;
+        }
+        None => None,
+    };
+    match aria2_rpc::resume_from(config, gid, dir) {
+        Ok(()) => info!(gid, dir = ?dir, "aria2 task resumed"),
+        Err(e) => warn!(gid, error = %e, "could not resume the aria2 task; it stays paused"),
+    }
+}
+
+/// `bt_mode`: while aria2 still seeds `gid` it holds the files open, so the download is
+/// copied as with `copy_only`. Without an answer from aria2 the copy is the safe choice.
+fn bt_mode_config<'a>(config: &'a Config, gid: Option<&str>) -> Cow<'a, Config> {
+    let Some(gid) = gid.filter(|_| config.bt_mode && !config.copy_only) else {
+        return Cow::Borrowed(config);
+    };
+    match aria2_rpc::tell_status(config, gid) {
+        Ok(status) if !status.is_seeding() => {
+            debug!(gid, status = %status.status, "bt_mode: aria2 is not seeding; moving");
+            return Cow::Borrowed(config);
+        }
+        Ok(_) => info!(
+            gid,
+            "bt_mode: aria2 is still seeding; copying and keeping the source"
+        ),
+        Err(e) => {
+            warn!(gid, error = %e, "bt_mode: could not ask aria2 about the download; copying and keeping the source")
+        }
+    }
+    let mut cfg = config.clone();
+    cfg.copy_only = true;
+    Cow::Owned(cfg)
+}
+
+/// Log a warning and send a `slow-move` notification if the move exceeded
+/// `slow_move_warning`.
+fn warn_if_slow(config: &Config, src: &Path, dest: &Path, timings: &MoveTimings) {
+    let Some(limit) = config.slow_move_warning.filter(|l| timings.total > *l) else {
+        return;
+    };
+    let ms = |d: std::time::Duration| d.as_millis() as u64;
+    warn!(
+        src = %src.display(),
+        dest = %dest.display(),
+        limit_ms = ms(limit),
+        total_ms = ms(timings.total),
+        probe_ms = ms(timings.probe),
+        lock_wait_ms = ms(timings.lock_wait),
+        copy_ms = ms(timings.copy),
+        rename_ms = ms(timings.rename),
+        fsync_ms = ms(timings.fsync),
+        bytes = timings.bytes_copied,
+        "Move exceeded slow_move_warning"
+    );
+    let message = format!(
+        "{} took {:.1}s (limit {:.1}s): probe {:.1}s, lock wait {:.1}s, copy {:.1}s, rename {:.1}s, fsync {:.1}s",
+        dest.display(),
+        timings.total.as_secs_f64(),
+        limit.as_secs_f64(),
+        timings.probe.as_secs_f64(),
+        timings.lock_wait.as_secs_f64(),
+        timings.copy.as_secs_f64(),
+        timings.rename.as_secs_f64(),
+        timings.fsync.as_secs_f64(),
+    );
+    notify::send(
+        config,
+        NotifyEvent::SlowMove,
+        "aria_move: slow move",
+        &message,
+    );
+}
+
+/// Body of `move_entry_timed`, run with the timing recorder installed.
+fn dispatch(config: &Config, src: &Path, opts: &MoveOptions) -> Result<PathBuf> {
     ensure_not_base(&config.download_base, src)?;
+    if config.require_source_in_base {
+        ensure_source_in_base(config, src)?;
+    }
+    if is_ignored_source(config, src) {
+        return Err(AriaMoveError::Ignored(src.to_path_buf()).into());
+    }
+    if config.content_sanity_check {
+        sanity::check_content(src)?;
+    }
+    handlers::verify(config, src)?;
+    let remote = is_remote(&config.completed_base);
+    let infohash = opts
+        .infohash
+        .as_deref()
+        .filter(|_| !remote)
+        .and_then(redownload::normalize);
+    let replaced = match &infohash {
+        Some(h) => check_redownload(config, h)?,
+        None => None,
+    };
+    if let Some(handled) = handlers::consume(config, src)? {
+        // Nothing reached completed_base: a stashed earlier copy is restored on drop.
+        return Ok(handled);
+    }
+    if remote {
+        return move_to_remote(config, src);
+    }
+    let dest = move_local(config, src, opts)?;
+    if let Some(previous) = replaced {
+        previous.discard();
+    }
+    if let Some(h) = &infohash
+        && !config.dry_run
+    {
+        redownload::record(config, h, &dest);
+    }
+    handlers::after_move(config, src, &dest);
+    Ok(dest)
+}
+
+/// `on_redownload` for a torrent whose earlier copy is still in place. For `replace` the
+/// old copy is moved aside; it is deleted once the new move succeeds, else restored.
+fn check_redownload(config: &Config, infohash: &str) -> Result<Option<Replaced>> {
+    let Some(previous) = redownload::previous(config, infohash) else {
+        return Ok(None);
+    };
+    info!(infohash, previous = %previous.display(), policy = %config.on_redownload, "torrent was moved before");
+    match config.on_redownload {
+        OnRedownload::Skip => Err(AriaMoveError::DestinationExists(previous).into()),
+        OnRedownload::Replace if !config.dry_run => Ok(Some(Replaced::stash(&previous)?)),
+        OnRedownload::Replace | OnRedownload::KeepBoth => Ok(None),
+    }
+}
 
-    // First use symlink_metadata to detect and reject symlinks explicitly.
+/// Local part of `dispatch`: branch on the source type and run the file or directory mover.
+fn move_local(config: &Config, src: &Path, opts: &MoveOptions) -> Result<PathBuf> {
+    // First use symlink_metadata to detect symlinks explicitly (handled per symlink_source_policy).
     let lmeta = fs::symlink_metadata(src).map_err(|e| {
         if e.kind() == std::io::ErrorKind::NotFound {
             anyhow!("Source does not exist: {}", src.display())
@@ -27,7 +325,7 @@ pub fn move_entry(config: &Config, src: &Path) -> Result<PathBuf> {
 
     let ftype = lmeta.file_type();
     if ftype.is_symlink() {
-        bail!("Refusing to move symlink: {}", src.display());
+        return move_symlink_source(config, src, &LogObserver::default());
     }
 
     // For regular files/dirs, a second metadata call isn't strictly necessary, but
@@ -35,9 +333,10 @@ pub fn move_entry(config: &Config, src: &Path) -> Result<PathBuf> {
     debug!(path = %src.display(), is_file = ftype.is_file(), is_dir = ftype.is_dir(), "dispatch move_entry");
 
     if ftype.is_file() {
-        move_file(config, src)
+        Ok(move_file_with(config, src, opts.on_duplicate)?)
     } else if ftype.is_dir() {
-        move_dir(config, src)
+        check_dir_duplicate(config, src, opts.on_duplicate)?;
+        Ok(move_dir(config, src)?)
     } else {
         bail!(
             "Source path is neither a regular file nor a directory: {}",
@@ -45,3 +344,72 @@ pub fn move_entry(config: &Config, src: &Path) -> Result<PathBuf> {
         )
     }
 }
+
+/// True if `src` lies inside download_base and matches its `.ariamoveignore`.
+pub(super) fn is_ignored_source(config: &Config, src: &Path) -> bool {
+    let rules = IgnoreRules::load(&config.download_base);
+    if rules.is_empty() {
+        return false;
+    }
+    let base =
+        dunce::canonicalize(&config.download_base).unwrap_or_else(|_| config.download_base.clone());
+    let abs = canonical_source(src);
+    let is_dir = fs::symlink_metadata(src)
+        .map(|m| m.is_dir())
+        .unwrap_or(false);
+    rules.is_ignored(&base, &abs, is_dir)
+}
+
+/// `src` with its parent canonicalized, so a symlink source is judged by its own name.
+fn canonical_source(src: &Path) -> PathBuf {
+    match (src.parent(), src.file_name()) {
+        (Some(parent), Some(name)) => dunce::canonicalize(parent)
+            .map(|p| p.join(name))
+            .unwrap_or_else(|_| src.to_path_buf()),
+        _ => src.to_path_buf(),
+    }
+}
+
+/// `require_source_in_base`: `src` must lie beneath download_base and not inside a local
+/// completed_base (checked first, since completed_base may itself sit under download_base).
+/// A move-target symlink is checked again on its resolved target (see symlink.rs).
+pub(super) fn ensure_source_in_base(config: &Config, src: &Path) -> Result<()> {
+    let abs = canonical_source(src);
+    if !is_remote(&config.completed_base)
+        && let Ok(completed) = dunce::canonicalize(&config.completed_base)
+        && abs.starts_with(&completed)
+    {
+        return Err(AriaMoveError::SourceInCompletedBase(src.to_path_buf()).into());
+    }
+    let base =
+        dunce::canonicalize(&config.download_base).unwrap_or_else(|_| config.download_base.clone());
+    if !abs.starts_with(&base) {
+        return Err(AriaMoveError::OutsideBase {
+            path: src.to_path_buf(),
+            base: config.download_base.clone(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Skip/Overwrite for a directory whose `completed_base/<name>` already exists.
+fn check_dir_duplicate(config: &Config, src: &Path, policy: Option<OnDuplicate>) -> Result<()> {
+    let Some(name) = src.file_name() else {
+        return Ok(());
+    };
+    let target = config
+        .completed_base
+        .join(super::fit_name(&config.completed_base, name));
+    if !target.exists() {
+        return Ok(());
+    }
+    match policy {
+        Some(OnDuplicate::Skip) => Err(AriaMoveError::DestinationExists(target).into()),
+        Some(OnDuplicate::Overwrite) => bail!(
+            "refusing to overwrite existing directory '{}' (use merge or rename)",
+            target.display()
+        ),
+        _ => Ok(()),
+    }
+}
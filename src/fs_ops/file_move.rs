@@ -1,10 +1,16 @@
 //! File move implementation:
 //! - Fast path: atomic rename into completed_base
 //! - Fallback: safe copy -> fsync -> atomic rename, then remove source
+//! - `copy_only`: always copy and keep the source (no rename, no removal)
+//! - `force_copy`: always copy, then remove the source (no rename)
+//! - On overlayfs/FUSE (`fsys::rename_quirk`) the copy path is taken automatically and the
+//!   copy is compared byte for byte before the source is removed
 //! - Optional: preserve src permissions/timestamps on destination
+//! - A symlink already at completed_base/<name> is handled per `dest_symlink_policy`
 //!   Concurrency:
 //! - Per-source lock to prevent double-processing of the same item
-//! - Per-destination-base lock to serialize finalization inside completed_base
+//! - Per-destination-name lock to serialize finalization into the same name in completed_base
+//! - The final (possibly suffixed) destination name is locked too
 
 use anyhow::{Context, Result, anyhow};
 use std::fs::{self};
@@ -15,46 +21,80 @@ use tracing::{debug, info, warn};
 
 use crate::config::types::Config;
 use crate::errors::AriaMoveError;
-use crate::platform::check_disk_space;
+use crate::fsys::free_space_bytes;
 use crate::shutdown;
 use crate::utils::{ensure_not_base, stable_file_probe, unique_destination};
 
-use super::atomic::{MoveOutcome, try_atomic_move};
-use super::copy::safe_copy_and_rename_with_metadata;
-use super::io_error_with_help;
-use super::lock::{acquire_dir_lock, acquire_move_lock};
+use super::atomic::{MoveOutcome, try_atomic_move_beneath};
+use super::copy::copy_with_backend;
+use super::duplicate::{OnDuplicate, resolve_destination};
+use super::helpers::{copy_error, destination_error};
+use super::lock::lock_error;
 use super::metadata;
+use super::path_lock::{PathLock, acquire_path_lock_with, lock_final_destination};
+use super::progress::LogObserver;
+use super::source_guard;
+use super::timing::{self, Phase};
 
 /// Move a single file into `completed_base`.
-/// Returns the final destination path.
-pub fn move_file(config: &Config, src: &Path) -> Result<PathBuf> {
+/// Returns the final destination path; failures are typed (see `AriaMoveError::hint`).
+pub fn move_file(config: &Config, src: &Path) -> Result<PathBuf, AriaMoveError> {
+    move_file_with(config, src, None)
+}
+
+/// Like `move_file`, with an explicit policy for an existing `completed_base/<name>`.
+/// `None` keeps the default timestamp-suffixed unique name.
+pub(super) fn move_file_with(
+    config: &Config,
+    src: &Path,
+    on_duplicate: Option<OnDuplicate>,
+) -> Result<PathBuf, AriaMoveError> {
+    move_file_impl(config, src, on_duplicate).map_err(AriaMoveError::from)
+}
+
+fn move_file_impl(
+    config: &Config,
+    src: &Path,
+    on_duplicate: Option<OnDuplicate>,
+) -> Result<PathBuf> {
     // Honor shutdown request early.
     if shutdown::is_requested() {
         return Err(AriaMoveError::Interrupted.into());
     }
+    if super::remote::is_remote(&config.completed_base) {
+        return super::remote::move_to_remote(config, src);
+    }
+    // A symlink source is handled per symlink_source_policy, never half-renamed/half-copied.
+    if crate::fsys::is_symlink(src) {
+        return super::symlink::move_symlink_source(config, src, &LogObserver::default());
+    }
 
     // Serialize on this source and ensure it's stable (size/mtime unchanged briefly).
     // Optional: allow disabling locks for environments where directory flock is denied.
     let disable_locks = config.disable_locks
         || std::env::var("ARIA_MOVE_DISABLE_LOCKS").ok().as_deref() == Some("1");
-    let _move_lock: Option<super::lock::DirLock> = if disable_locks {
+    let _move_lock: Option<PathLock> = if disable_locks {
         debug!(src = %src.display(), "locks disabled via config or ARIA_MOVE_DISABLE_LOCKS=1 (source)");
         None
     } else {
-        match acquire_move_lock(src) {
+        match timing::timed(Phase::LockWait, || {
+            acquire_path_lock_with(src, config.lock_strategy)
+        }) {
             Ok(l) => Some(l),
             Err(e) => {
-                if e.kind() == io::ErrorKind::PermissionDenied {
-                    debug!(error = %e, src = %src.display(), "acquire_move_lock permission denied; proceeding without lock (diagnostic)");
+                if e.kind() == io::ErrorKind::PermissionDenied || read_only_source(config, &e) {
+                    debug!(error = %e, src = %src.display(), "source lock permission denied; proceeding without lock (diagnostic)");
                     None
                 } else {
-                    return Err(e.into());
+                    return Err(lock_error(src, e));
                 }
             }
         }
     };
     ensure_not_base(&config.download_base, src)?;
-    stable_file_probe(src, Duration::from_millis(200), 3)?;
+    timing::timed(Phase::Probe, || {
+        stable_file_probe(src, Duration::from_millis(200), 3)
+    })?;
 
     // Compute final destination path (deduplicate name if needed).
     let dest_dir = &config.completed_base;
@@ -64,14 +104,22 @@ pub fn move_file(config: &Config, src: &Path) -> Result<PathBuf> {
             if e.kind() == io::ErrorKind::PermissionDenied {
                 debug!(error = %e, dest = %dest_dir.display(), "create_dir_all permission denied");
             }
-            return Err(io_error_with_help("create destination directory", dest_dir)(e));
+            return Err(destination_error("create destination directory", dest_dir)(
+                e,
+            ));
         }
     } else {
         // Dry-run: keep a light permission check to surface obvious issues without writing.
-        info!(action = "mkdir -p", path = %dest_dir.display(), "dry-run");
-        if let Some(parent) = dest_dir.parent()
-            && (!parent.exists() || parent.metadata()?.permissions().readonly())
-        {
+        // completed_base itself may not exist yet (read-only validation creates nothing):
+        // check the closest existing ancestor that `mkdir -p` would start from.
+        if !dest_dir.exists() {
+            info!(action = "mkdir -p", path = %dest_dir.display(), "dry-run");
+        }
+        let writable_from = match dest_dir.parent() {
+            Some(parent) if dest_dir.exists() => Some(parent),
+            _ => crate::fsys::existing_ancestor(dest_dir).ok(),
+        };
+        if writable_from.is_none_or(|p| p.metadata().is_ok_and(|m| m.permissions().readonly())) {
             return Err(AriaMoveError::PermissionDenied {
                 path: dest_dir.to_path_buf(),
                 context: "dry-run parent missing or readonly".into(),
@@ -80,45 +128,52 @@ pub fn move_file(config: &Config, src: &Path) -> Result<PathBuf> {
         }
     }
 
+    let dest_key = dest_dir.join(super::fit_name(
+        dest_dir,
+        src.file_name().unwrap_or_default(),
+    ));
     if config.dry_run {
         // Dry-run: compute and return intended destination without taking locks.
-        let file_name = src
-            .file_name()
-            .ok_or_else(|| anyhow!("Source file missing a file name: {}", src.display()))?;
-        let mut dest = dest_dir.join(file_name);
-        if dest.exists() {
-            dest = unique_destination(&dest);
-        }
+        super::symlink::guard_dest_symlink(config, &dest_key)?;
+        let dest = file_destination(dest_dir, src, on_duplicate)?;
         info!(src = %src.display(), dest = %dest.display(), "dry-run: would move file");
         return Ok(dest);
     }
 
-    // Serialize finalization into completed_base to avoid races on destination naming and final rename.
-    let _dir_lock: Option<super::lock::DirLock> = if disable_locks {
-        debug!(dest = %dest_dir.display(), "locks disabled via config or ARIA_MOVE_DISABLE_LOCKS=1 (dest)");
+    // Serialize moves into the same name to avoid races on destination naming and final rename.
+    let _dest_lock: Option<PathLock> = if disable_locks {
+        debug!(dest = %dest_key.display(), "locks disabled via config or ARIA_MOVE_DISABLE_LOCKS=1 (dest)");
         None
     } else {
-        match acquire_dir_lock(dest_dir) {
+        match timing::timed(Phase::LockWait, || {
+            acquire_path_lock_with(&dest_key, config.lock_strategy)
+        }) {
             Ok(l) => Some(l),
             Err(e) => {
                 if e.kind() == io::ErrorKind::PermissionDenied {
-                    debug!(error = %e, dest = %dest_dir.display(), "acquire_dir_lock permission denied; proceeding without lock (diagnostic)");
+                    debug!(error = %e, dest = %dest_key.display(), "destination lock permission denied; proceeding without lock (diagnostic)");
                     None
                 } else {
-                    return Err(anyhow!("acquire lock for '{}': {}", dest_dir.display(), e));
+                    return Err(lock_error(&dest_key, e));
                 }
             }
         }
     };
 
-    // Now decide final destination name while holding the directory lock.
-    let file_name = src
-        .file_name()
-        .ok_or_else(|| anyhow!("Source file missing a file name: {}", src.display()))?;
-    let mut dest = dest_dir.join(file_name);
-    if dest.exists() {
-        dest = unique_destination(&dest);
-    }
+    // A symlink at completed_base/<name> is refused or removed (dest_symlink_policy).
+    super::symlink::guard_dest_symlink(config, &dest_key)?;
+
+    // Now decide final destination name while holding the destination lock; a suffixed
+    // name is locked as well, since a move of a file with that name keys on it directly.
+    let (dest, _final_lock) = if _dest_lock.is_some() {
+        timing::timed(Phase::LockWait, || {
+            lock_final_destination(&dest_key, config.lock_strategy, || {
+                file_destination(dest_dir, src, on_duplicate)
+            })
+        })?
+    } else {
+        (file_destination(dest_dir, src, on_duplicate)?, None)
+    };
 
     // Capture source metadata BEFORE any rename (after rename, src path no longer exists).
     let meta_before = if config.preserve_metadata || config.preserve_permissions {
@@ -128,37 +183,44 @@ pub fn move_file(config: &Config, src: &Path) -> Result<PathBuf> {
     };
 
     // Fast path: atomic rename (same filesystem). May return CrossDevice prediction.
-    match try_atomic_move(src, &dest) {
-        Ok(MoveOutcome::Renamed) => {
-            debug!(src = %src.display(), dest = %dest.display(), "Renamed file atomically");
-            if let Some(meta) = meta_before.as_ref() {
-                if config.preserve_metadata {
-                    let _ = metadata::preserve_metadata(&dest, meta);
-                    let _ = metadata::preserve_xattrs(src, &dest);
-                } else if config.preserve_permissions {
-                    let _ = metadata::preserve_permissions_only(&dest, meta);
+    // Copy-only never renames: the source has to stay where it is. Force-copy never renames
+    // either, for filesystems whose rename cannot be trusted; overlayfs/FUSE are detected.
+    let quirk = super::util::rename_quirk(src, dest_dir);
+    if !config.copy_only && !config.force_copy && quirk.is_none() {
+        match timing::timed(Phase::Rename, || {
+            try_atomic_move_beneath(dest_dir, src, &dest, config.fsync_policy)
+        }) {
+            Ok(MoveOutcome::Renamed) => {
+                debug!(src = %src.display(), dest = %dest.display(), "Renamed file atomically");
+                if let Some(meta) = meta_before.as_ref() {
+                    if config.preserve_metadata {
+                        let _ = metadata::preserve_metadata(&dest, meta);
+                        let _ = metadata::preserve_xattrs(src, &dest);
+                    } else if config.preserve_permissions {
+                        let _ = metadata::preserve_permissions_only(&dest, meta);
+                    }
                 }
+                return Ok(dest);
             }
-            return Ok(dest);
-        }
-        Ok(MoveOutcome::CrossDevice) => {
-            info!(src = %src.display(), dest = %dest.display(), "Cross-device move detected; using copy fallback");
-        }
-        Err(e) => {
-            // Compute a short hint for logs; still proceed to copy fallback.
-            let hint: &str = if let Some(ioe) = e.downcast_ref::<io::Error>() {
-                if super::util::is_cross_device(ioe) {
-                    "cross-filesystem; will copy instead"
-                } else if ioe.kind() == io::ErrorKind::PermissionDenied {
-                    "permission denied; check destination perms"
+            Ok(MoveOutcome::CrossDevice) => {
+                info!(src = %src.display(), dest = %dest.display(), "Cross-device move detected; using copy fallback");
+            }
+            Err(e) => {
+                // Compute a short hint for logs; still proceed to copy fallback.
+                let hint: &str = if let Some(ioe) = e.downcast_ref::<io::Error>() {
+                    if super::util::is_cross_device(ioe) {
+                        "cross-filesystem; will copy instead"
+                    } else if ioe.kind() == io::ErrorKind::PermissionDenied {
+                        "permission denied; check destination perms"
+                    } else {
+                        "falling back to copy"
+                    }
                 } else {
                     "falling back to copy"
-                }
-            } else {
-                "falling back to copy"
-            };
+                };
 
-            warn!(error = %e, hint, src = %src.display(), dest = %dest.display(), "Atomic rename failed, using safe copy+rename");
+                warn!(error = %e, hint, src = %src.display(), dest = %dest.display(), "Atomic rename failed, using safe copy+rename");
+            }
         }
     }
 
@@ -172,37 +234,54 @@ pub fn move_file(config: &Config, src: &Path) -> Result<PathBuf> {
             return Err(anyhow!("stat source {}: {}", src.display(), e));
         }
     };
-    let available = match check_disk_space(dest_dir) {
+    let available = match free_space_bytes(dest_dir) {
         Ok(av) => av,
         Err(e) => {
             debug!(error = %e, dest = %dest_dir.display(), "disk space check failed");
-            return Err(anyhow!("check disk space at {}: {}", dest_dir.display(), e));
+            return Err(AriaMoveError::DestinationUnavailable {
+                dest: dest_dir.to_path_buf(),
+                context: format!("check disk space: {e}"),
+            }
+            .into());
         }
     };
-    if available < src_size {
+    if !super::space::has_space(available, src_size, config.space_cushion) {
         return Err(AriaMoveError::InsufficientSpace {
-            required: src_size as u128,
+            required: src_size as u128 + config.space_cushion.bytes() as u128,
             available: available as u128,
             dest: dest_dir.to_path_buf(),
         }
         .into());
     }
     // Copy with or without metadata; permissions-only handled after file is at dest.
-    safe_copy_and_rename_with_metadata(src, &dest, config.preserve_metadata)?;
-
-    // Remove original after successful copy into place.
-    match fs::remove_file(src) {
-        Ok(()) => {}
-        Err(e) if e.kind() == io::ErrorKind::NotFound => { /* already gone; ignore */ }
-        Err(e) => return Err(io_error_with_help("remove original file", src)(e)),
+    timing::timed(Phase::Copy, || {
+        source_guard::guard_copy(src, &dest, || copy_with_backend(config, src, &dest))
+    })?;
+    timing::add_bytes(src_size);
+    if quirk.is_some()
+        && let Err(e) = super::util::verify_copy(src, &dest)
+    {
+        let _ = fs::remove_file(&dest);
+        return Err(copy_error("verify", "verify copy", &dest)(e));
     }
 
-    // Best-effort fsync of the source parent to persist the deletion on Unix.
-    #[cfg(unix)]
-    if let Some(src_parent) = src.parent()
-        && let Err(e) = super::util::fsync_dir(src_parent)
-    {
-        warn!(error = %e, dir = %src_parent.display(), "best-effort fsync(src_parent after delete) failed");
+    // Remove original after successful copy into place (unless copy-only).
+    if !config.copy_only {
+        match super::fs_trace::traced("unlink", src, || fs::remove_file(src)) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => { /* already gone; ignore */ }
+            Err(e) => return Err(copy_error("remove_source", "remove original file", src)(e)),
+        }
+
+        // Best-effort fsync of the source parent to persist the deletion on Unix.
+        #[cfg(unix)]
+        if let Some(src_parent) = src.parent()
+            && let Err(e) = timing::timed(Phase::Fsync, || {
+                super::util::sync_dir(config.fsync_policy, src_parent)
+            })
+        {
+            warn!(error = %e, dir = %src_parent.display(), "best-effort fsync(src_parent after delete) failed");
+        }
     }
 
     // If only permissions (not full metadata) requested, apply now at dest
@@ -213,6 +292,44 @@ pub fn move_file(config: &Config, src: &Path) -> Result<PathBuf> {
         let _ = metadata::preserve_permissions_only(&dest, meta);
     }
 
-    info!(src = %src.display(), dest = %dest.display(), "Copied file and removed source");
+    if config.copy_only {
+        info!(src = %src.display(), dest = %dest.display(), "Copied file (source retained)");
+    } else {
+        info!(src = %src.display(), dest = %dest.display(), "Copied file and removed source");
+    }
     Ok(dest)
 }
+
+/// True for a source lock that cannot be created because the source is on a read-only
+/// filesystem while `copy_only` is set; the move then goes ahead without that lock.
+pub(super) fn read_only_source(config: &Config, e: &io::Error) -> bool {
+    config.copy_only && e.kind() == io::ErrorKind::ReadOnlyFilesystem
+}
+
+/// Destination for `src` inside `dest_dir` under the duplicate policy.
+/// - None: `unique_destination` (timestamp + pid suffix), the historical default.
+/// - Some(policy): `resolve_destination`; Skip reports `DestinationExists` and Overwrite
+///   refuses to replace a directory.
+fn file_destination(
+    dest_dir: &Path,
+    src: &Path,
+    on_duplicate: Option<OnDuplicate>,
+) -> Result<PathBuf> {
+    let file_name = super::fit_name(
+        dest_dir,
+        src.file_name()
+            .ok_or_else(|| anyhow!("Source file missing a file name: {}", src.display()))?,
+    );
+    let Some(on_duplicate) = on_duplicate else {
+        return Ok(unique_destination(&dest_dir.join(file_name)));
+    };
+    let dest = resolve_destination(dest_dir, &file_name, on_duplicate);
+    match on_duplicate {
+        OnDuplicate::Skip if dest.exists() => Err(AriaMoveError::DestinationExists(dest).into()),
+        OnDuplicate::Overwrite if dest.is_dir() => Err(anyhow!(
+            "cannot overwrite directory '{}' with a file",
+            dest.display()
+        )),
+        _ => Ok(dest),
+    }
+}
@@ -0,0 +1,88 @@
+//! Per-syscall debug events (`trace_fs_ops`).
+//! - `traced` runs one low-level filesystem operation (open, create, rename, fsync, unlink)
+//!   and, when tracing is on, logs a debug event with `op`, `path` (and `to` for renames),
+//!   `elapsed_us` and, on failure, `errno` and `error`.
+//! - Meant for sick NFS/SMB mounts: the slow or failing call shows up by name and path.
+//!
+//! Notes:
+//! - The switch is process-wide so rayon workers see it too. The binary sets it from the
+//!   config; `move_entry` turns it on for a config with `trace_fs_ops = true`.
+//! - Events use this module's target, `aria_move::fs_ops::fs_trace`, so `log_filter` can
+//!   enable them without the rest of the debug output.
+//! - Off (the default), `traced` only costs an atomic load.
+
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+use tracing::debug;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Turn per-operation debug events on or off (`trace_fs_ops`).
+pub fn set_trace_fs_ops(on: bool) {
+    ENABLED.store(on, Ordering::Relaxed);
+}
+
+/// Whether per-operation events are emitted.
+fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Run `f`, the operation `op` on `path`, and log it when tracing is on.
+pub(super) fn traced<T>(
+    op: &'static str,
+    path: &Path,
+    f: impl FnOnce() -> io::Result<T>,
+) -> io::Result<T> {
+    traced_to(op, path, None, f)
+}
+
+/// `traced` for operations with a second path (rename `path` -> `to`).
+pub(super) fn traced_to<T>(
+    op: &'static str,
+    path: &Path,
+    to: Option<&Path>,
+    f: impl FnOnce() -> io::Result<T>,
+) -> io::Result<T> {
+    if !enabled() {
+        return f();
+    }
+    let started = Instant::now();
+    let res = f();
+    let elapsed_us = started.elapsed().as_micros() as u64;
+    let to = to.map(|p| p.display().to_string());
+    match &res {
+        Ok(_) => debug!(op, path = %path.display(), to, elapsed_us, "fs op"),
+        Err(e) => debug!(
+            op,
+            path = %path.display(),
+            to,
+            elapsed_us,
+            errno = e.raw_os_error(),
+            error = %e,
+            "fs op failed"
+        ),
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[serial_test::serial]
+    fn traced_passes_results_through_either_way() {
+        let td = tempfile::tempdir().unwrap();
+        let missing = td.path().join("missing");
+        for on in [false, true] {
+            set_trace_fs_ops(on);
+            let err = traced("open", &missing, || std::fs::File::open(&missing)).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::NotFound);
+            let n = traced_to("rename", &missing, Some(td.path()), || Ok(7)).unwrap();
+            assert_eq!(n, 7);
+        }
+        set_trace_fs_ops(false);
+    }
+}
@@ -0,0 +1,458 @@
+//! Per-extension handlers (`<handlers>` in config.xml), run by `move_entry` once the source
+//! is known.
+//! - `verify-sha256`: before the move, check the file against its `<name>.sha256` sidecar;
+//!   a mismatch fails with `ChecksumMismatch` and leaves the source in place.
+//! - `delete`: remove the file instead of moving it.
+//! - `archive`: move the file into the handler's `dir` instead of completed_base.
+//! - `extract`: after the move, unpack the archive next to it into `<name>/`, the file name
+//!   without the handler's extension (the archive is kept). Only zip and tar archives
+//!   (`tar`, `tar.gz`/`tgz`, `tar.bz2`/`tbz2`, `tar.xz`/`txz`) can be extracted; another
+//!   extension is refused when the config is parsed.
+//!
+//! Notes:
+//! - An extension matches the end of the file name, case-insensitively, so `tar.gz` works;
+//!   the first matching handler wins.
+//! - Nothing that changes the source runs before the move is known to go ahead: the
+//!   `verify-sha256` checks come first (read-only, under the source lock), and a file
+//!   source is deleted or archived only after the `on_redownload` check.
+//! - For a directory source every regular file below it is verified before the move
+//!   (symlinks are skipped); `delete`, `archive` and `extract` then apply to the moved tree
+//!   at the destination, so a refused move (an existing destination, too little space)
+//!   leaves the source whole. They are skipped for a directory sent to a remote destination.
+//! - Dry runs only log what would happen. `delete` and `archive` of a file source are
+//!   skipped with `copy_only` (the source is kept and copied).
+//! - Work on the moved tree is best-effort: the move has already succeeded, so failures
+//!   are logged. `extract` runs `unzip` (or `ARIA_MOVE_UNZIP`) for zips and `tar` (or
+//!   `ARIA_MOVE_TAR`) for tarballs, from PATH, and removes a partial `<name>/`.
+
+use anyhow::{Result, anyhow};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::{debug, info, warn};
+use walkdir::WalkDir;
+
+use crate::config::types::Config;
+use crate::errors::AriaMoveError;
+
+use super::digest::{sha256_file, to_hex};
+use super::file_move::{move_file_with, read_only_source};
+use super::helpers::io_error_with_help;
+use super::lock::lock_error;
+use super::path_lock::{PathLock, acquire_path_lock_with};
+use super::remote::is_remote;
+
+/// What a handler does with a matching file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandlerAction {
+    /// Check the file against its `<name>.sha256` sidecar before moving it
+    VerifySha256,
+    /// Unpack the zip or tarball into `<name>/` next to its destination after the move
+    Extract,
+    /// Delete the file instead of moving it
+    Delete,
+    /// Move the file into this directory instead of completed_base
+    Archive(PathBuf),
+}
+
+impl fmt::Display for HandlerAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            HandlerAction::VerifySha256 => "verify-sha256",
+            HandlerAction::Extract => "extract",
+            HandlerAction::Delete => "delete",
+            HandlerAction::Archive(_) => "archive",
+        };
+        f.write_str(s)
+    }
+}
+
+/// One `<handler ext="..." action="..."/>` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Handler {
+    /// Lowercase extension without the leading dot (`iso`, `tar.gz`)
+    pub extension: String,
+    pub action: HandlerAction,
+}
+
+impl Handler {
+    /// Handler for `extension` (leading dot and case are ignored).
+    pub fn new(extension: &str, action: HandlerAction) -> Self {
+        Self {
+            extension: extension
+                .trim()
+                .trim_start_matches('.')
+                .to_ascii_lowercase(),
+            action,
+        }
+    }
+
+    /// Parse the XML attributes: `action` is verify-sha256 | extract | delete | archive
+    /// (case-insensitive); `archive` needs `dir`, `extract` a zip or tar extension.
+    pub fn parse(extension: &str, action: &str, dir: Option<&str>) -> Result<Self, String> {
+        let action = match action.trim().to_ascii_lowercase().as_str() {
+            "verify-sha256" | "verify_sha256" | "sha256" | "verify" => HandlerAction::VerifySha256,
+            "extract" | "unzip" => HandlerAction::Extract,
+            "delete" | "remove" => HandlerAction::Delete,
+            "archive" => match dir.map(str::trim).filter(|d| !d.is_empty()) {
+                Some(d) => HandlerAction::Archive(PathBuf::from(d)),
+                None => return Err(format!("handler '{extension}': archive needs a dir")),
+            },
+            other => return Err(format!("handler '{extension}': invalid action '{other}'")),
+        };
+        let handler = Self::new(extension, action);
+        if handler.extension.is_empty() {
+            return Err("handler without an extension".to_string());
+        }
+        if handler.action == HandlerAction::Extract
+            && Extractor::for_extension(&handler.extension).is_none()
+        {
+            return Err(format!(
+                "handler '{extension}': extract supports {} only",
+                Extractor::EXTENSIONS.join(", ")
+            ));
+        }
+        Ok(handler)
+    }
+
+    /// True if the file name of `path` ends in `.<extension>` (case-insensitive).
+    pub fn matches(&self, path: &Path) -> bool {
+        let Some(name) = path.file_name() else {
+            return false;
+        };
+        let name = name.to_string_lossy().to_ascii_lowercase();
+        name.len() > self.extension.len() + 1
+            && name.ends_with(&self.extension)
+            && name.as_bytes()[name.len() - self.extension.len() - 1] == b'.'
+    }
+}
+
+/// The tool that unpacks an archive, chosen by the handler's extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Extractor {
+    Unzip,
+    Tar,
+}
+
+impl Extractor {
+    /// Extensions `extract` accepts (tar detects the compression itself).
+    const EXTENSIONS: [&str; 8] = [
+        "zip", "tar", "tar.gz", "tgz", "tar.bz2", "tbz2", "tar.xz", "txz",
+    ];
+
+    fn for_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "zip" => Some(Extractor::Unzip),
+            e if Self::EXTENSIONS.contains(&e) => Some(Extractor::Tar),
+            _ => None,
+        }
+    }
+
+    /// The tool on PATH and the variable that overrides it.
+    fn tool(self) -> (&'static str, &'static str) {
+        match self {
+            Extractor::Unzip => ("unzip", "ARIA_MOVE_UNZIP"),
+            Extractor::Tar => ("tar", "ARIA_MOVE_TAR"),
+        }
+    }
+}
+
+/// First handler of `config` matching `path`.
+fn handler_for<'a>(config: &'a Config, path: &Path) -> Option<&'a Handler> {
+    config.handlers.iter().find(|h| h.matches(path))
+}
+
+/// True for the actions that take the file away (`delete`, `archive`).
+fn consumes(action: &HandlerAction) -> bool {
+    matches!(action, HandlerAction::Delete | HandlerAction::Archive(_))
+}
+
+/// `root` (a regular file) or the regular files below it (a directory), with the first
+/// handler of `config` that matches each and whose action passes `wanted`.
+fn matching_files<'a>(
+    config: &'a Config,
+    root: &Path,
+    wanted: impl Fn(&HandlerAction) -> bool,
+) -> Vec<(PathBuf, &'a Handler)> {
+    if config.handlers.is_empty() {
+        return Vec::new();
+    }
+    WalkDir::new(root)
+        .follow_links(false)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let handler = handler_for(config, e.path()).filter(|h| wanted(&h.action))?;
+            Some((e.path().to_path_buf(), handler))
+        })
+        .collect()
+}
+
+/// Pre-move check: verify `src` (or every file below a directory `src`) against its
+/// sidecar, under the source lock. Nothing is changed, so a mismatch leaves the source whole.
+pub(super) fn verify(config: &Config, src: &Path) -> Result<()> {
+    let files = matching_files(config, src, |a| *a == HandlerAction::VerifySha256);
+    if files.is_empty() {
+        return Ok(());
+    }
+    let _lock = lock_source(config, src)?;
+    for (path, handler) in files {
+        handle_file(config, &path, handler)?;
+    }
+    Ok(())
+}
+
+/// Pre-move stage for a file source: `delete` or `archive` replace its move. Call it once
+/// every refusal check has passed. Returns the final path when the file was consumed;
+/// `None` means the move goes ahead (a directory is handled by `after_move`).
+pub(super) fn consume(config: &Config, src: &Path) -> Result<Option<PathBuf>> {
+    let Ok(meta) = fs::symlink_metadata(src) else {
+        return Ok(None); // the mover reports a missing source
+    };
+    if meta.is_dir() {
+        if is_remote(&config.completed_base) && !matching_files(config, src, consumes).is_empty() {
+            warn!(src = %src.display(), "delete/archive handlers are not applied inside a directory sent to a remote destination");
+        }
+        return Ok(None);
+    }
+    let Some(handler) = handler_for(config, src).filter(|h| consumes(&h.action)) else {
+        return Ok(None);
+    };
+    if config.copy_only {
+        debug!(path = %src.display(), action = %handler.action, "copy_only: handler skipped");
+        return Ok(None);
+    }
+    let _lock = match handler.action {
+        HandlerAction::Archive(_) => None,
+        _ => lock_source(config, src)?,
+    };
+    handle_file(config, src, handler)
+}
+
+/// Lock `src` for the handler stage, with the movers' opt-outs (`disable_locks`, a denied lock).
+fn lock_source(config: &Config, src: &Path) -> Result<Option<PathLock>> {
+    if config.disable_locks || std::env::var("ARIA_MOVE_DISABLE_LOCKS").ok().as_deref() == Some("1")
+    {
+        return Ok(None);
+    }
+    match acquire_path_lock_with(src, config.lock_strategy) {
+        Ok(lock) => Ok(Some(lock)),
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied || read_only_source(config, &e) => {
+            debug!(error = %e, src = %src.display(), "source lock denied; handlers run without lock");
+            Ok(None)
+        }
+        Err(e) => Err(lock_error(src, e)),
+    }
+}
+
+/// Apply `handler` to one regular file.
+fn handle_file(config: &Config, path: &Path, handler: &Handler) -> Result<Option<PathBuf>> {
+    if config.dry_run {
+        info!(path = %path.display(), action = %handler.action, "dry-run: would apply handler");
+        return Ok(match &handler.action {
+            HandlerAction::Delete => Some(path.to_path_buf()),
+            HandlerAction::Archive(dir) => Some(dir.join(path.file_name().unwrap_or_default())),
+            _ => None,
+        });
+    }
+    match &handler.action {
+        HandlerAction::VerifySha256 => {
+            verify_sidecar(path)?;
+            Ok(None)
+        }
+        HandlerAction::Delete => {
+            fs::remove_file(path).map_err(io_error_with_help("delete file (handler)", path))?;
+            info!(path = %path.display(), "Deleted by handler");
+            Ok(Some(path.to_path_buf()))
+        }
+        HandlerAction::Archive(dir) => {
+            // The file may go (see `consume`), or it is part of the moved tree.
+            let mut cfg = config.clone();
+            cfg.completed_base = dir.clone();
+            cfg.copy_only = false;
+            let dest = move_file_with(&cfg, path, None)?;
+            info!(src = %path.display(), dest = %dest.display(), "Archived by handler");
+            Ok(Some(dest))
+        }
+        HandlerAction::Extract => {
+            extract(path, &handler.extension);
+            Ok(None)
+        }
+    }
+}
+
+/// `<path>.sha256`.
+fn sidecar_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".sha256");
+    PathBuf::from(name)
+}
+
+/// Compare `path` with the digest in its sidecar (`sha256sum` format: the first token).
+/// A missing sidecar only logs a warning.
+fn verify_sidecar(path: &Path) -> Result<()> {
+    let sidecar = sidecar_path(path);
+    let text = match fs::read_to_string(&sidecar) {
+        Ok(t) => t,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            warn!(path = %path.display(), "no .sha256 sidecar; moving without verification");
+            return Ok(());
+        }
+        Err(e) => return Err(io_error_with_help("read checksum sidecar", &sidecar)(e)),
+    };
+    let expected = text
+        .split_whitespace()
+        .next()
+        .map(str::to_ascii_lowercase)
+        .filter(|h| h.len() == 64 && h.bytes().all(|b| b.is_ascii_hexdigit()))
+        .ok_or_else(|| {
+            anyhow!(
+                "checksum sidecar '{}' does not start with a SHA-256 digest",
+                sidecar.display()
+            )
+        })?;
+    let actual = to_hex(&sha256_file(path).map_err(io_error_with_help("hash file", path))?);
+    if actual != expected {
+        return Err(AriaMoveError::ChecksumMismatch {
+            path: path.to_path_buf(),
+            expected,
+            actual,
+        }
+        .into());
+    }
+    info!(path = %path.display(), sha256 = %actual, "checksum verified");
+    Ok(())
+}
+
+/// Post-move stage on the moved tree at `dest`: below a directory, `delete` and `archive`
+/// apply to the matching files; matching archives are extracted. The move has already
+/// succeeded, so failures are logged. A dry run logs what would happen below `src`.
+pub(super) fn after_move(config: &Config, src: &Path, dest: &Path) {
+    let is_dir =
+        fs::symlink_metadata(if config.dry_run { src } else { dest }).is_ok_and(|m| m.is_dir());
+    let wanted = |a: &HandlerAction| *a == HandlerAction::Extract || (is_dir && consumes(a));
+    if config.dry_run {
+        for (path, handler) in matching_files(config, src, wanted) {
+            let _ = handle_file(config, &path, handler);
+        }
+        return;
+    }
+    // Collected first: archiving and extracting change the tree being walked.
+    for (path, handler) in matching_files(config, dest, wanted) {
+        if let Err(e) = handle_file(config, &path, handler) {
+            warn!(path = %path.display(), action = %handler.action, error = %format!("{e:#}"), "handler failed on the moved tree; file left in place");
+        }
+    }
+}
+
+/// Unpack `archive` (matched by handler `extension`) into `<name>/` beside it, where
+/// `<name>` is its file name without that extension; an existing `<name>/` is left alone.
+fn extract(archive: &Path, extension: &str) {
+    let Some(extractor) = Extractor::for_extension(extension) else {
+        warn!(archive = %archive.display(), extension, "extract: not a zip or tar archive; skipped");
+        return;
+    };
+    let Some(name) = archive.file_name().map(|n| n.to_string_lossy()) else {
+        return;
+    };
+    // `matches` compared the ASCII-lowercased name, which has the same byte length.
+    let target = archive.with_file_name(&name[..name.len() - extension.len() - 1]);
+    if fs::symlink_metadata(&target).is_ok() {
+        debug!(archive = %archive.display(), target = %target.display(), "extract target exists; skipped");
+        return;
+    }
+    let (tool, var) = extractor.tool();
+    let bin = std::env::var_os(var).unwrap_or_else(|| tool.into());
+    let mut cmd = Command::new(&bin);
+    match extractor {
+        Extractor::Unzip => {
+            cmd.arg("-q")
+                .arg(explicit(archive))
+                .arg("-d")
+                .arg(explicit(&target));
+        }
+        Extractor::Tar => {
+            // tar needs the directory to exist.
+            if let Err(e) = fs::create_dir(&target) {
+                warn!(archive = %archive.display(), error = %e, "extract failed; archive left as moved");
+                return;
+            }
+            cmd.arg("-x")
+                .arg("-f")
+                .arg(explicit(archive))
+                .arg("-C")
+                .arg(explicit(&target));
+        }
+    }
+    debug!(cmd = ?cmd, "running {tool}");
+    let failure = match cmd.output() {
+        Ok(out) if out.status.success() => {
+            info!(archive = %archive.display(), target = %target.display(), "Extracted by handler");
+            return;
+        }
+        Ok(out) => format!(
+            "{}: {}",
+            out.status,
+            String::from_utf8_lossy(&out.stderr).trim()
+        ),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => format!(
+            "{tool} not found ({}); install {tool} or set {var}",
+            PathBuf::from(&bin).display()
+        ),
+        Err(e) => e.to_string(),
+    };
+    warn!(archive = %archive.display(), error = %failure, "extract failed; archive left as moved");
+    let _ = fs::remove_dir_all(&target);
+}
+
+/// `path` with a `./` prefix when relative, so a leading `-` is never read as an option.
+fn explicit(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        Path::new(".").join(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_normalizes_extension_and_requires_archive_dir() {
+        let h = Handler::parse(".ISO", "Verify-SHA256", None).unwrap();
+        assert_eq!(h.extension, "iso");
+        assert_eq!(h.action, HandlerAction::VerifySha256);
+        assert_eq!(
+            Handler::parse("torrent", "archive", Some("/srv/torrents"))
+                .unwrap()
+                .action,
+            HandlerAction::Archive(PathBuf::from("/srv/torrents"))
+        );
+        assert!(Handler::parse("torrent", "archive", Some("  ")).is_err());
+        assert!(Handler::parse("nfo", "shred", None).is_err());
+        assert!(Handler::parse(".", "delete", None).is_err());
+        assert_eq!(
+            Handler::parse("TGZ", "extract", None).unwrap().action,
+            HandlerAction::Extract
+        );
+        let err = Handler::parse("rar", "extract", None).unwrap_err();
+        assert!(err.contains("extract supports zip, tar"), "{err}");
+    }
+
+    #[test]
+    fn matches_whole_extension_case_insensitively() {
+        let h = Handler::new("tar.gz", HandlerAction::Extract);
+        assert!(h.matches(Path::new("/d/Backup.TAR.GZ")));
+        assert!(!h.matches(Path::new("/d/backup.gz")));
+        assert!(!h.matches(Path::new("/d/.tar.gz")));
+        let nfo = Handler::new("nfo", HandlerAction::Delete);
+        assert!(nfo.matches(Path::new("movie.nfo")));
+        assert!(!nfo.matches(Path::new("movie.xnfo")));
+        assert!(!nfo.matches(Path::new("nfo")));
+    }
+}
@@ -0,0 +1,294 @@
+//! `.ariamoveignore`: gitignore-style exclusions inside download_base.
+//! Supported syntax (a practical gitignore subset):
+//! - Blank lines and lines starting with `#` are ignored.
+//! - `!pattern` re-includes a path excluded by an earlier pattern (last match wins).
+//! - A trailing `/` matches directories only.
+//! - A pattern containing `/` (other than trailing) is anchored at download_base;
+//!   otherwise it matches the name at any depth.
+//! - Globs: `*` and `?` (not crossing `/`), `**` (any depth), `[abc]`, `[a-z]`, `[!x]`.
+//!
+//! Notes:
+//! - As in git, anything inside an excluded directory is excluded too.
+//! - Paths outside download_base are never ignored.
+
+use std::fs;
+use std::path::{Component, Path};
+use tracing::{debug, warn};
+
+/// File name looked up in download_base.
+pub const IGNORE_FILE_NAME: &str = ".ariamoveignore";
+
+#[derive(Debug, Clone)]
+struct Rule {
+    pattern: String,
+    negate: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+/// Parsed ignore rules for one download_base.
+#[derive(Debug, Clone, Default)]
+pub struct IgnoreRules {
+    rules: Vec<Rule>,
+}
+
+impl IgnoreRules {
+    /// Load `<base>/.ariamoveignore`. A missing or unreadable file yields no rules.
+    pub fn load(base: &Path) -> Self {
+        let path = base.join(IGNORE_FILE_NAME);
+        match fs::read_to_string(&path) {
+            Ok(s) => {
+                let rules = Self::parse(&s);
+                debug!(path = %path.display(), rules = rules.rules.len(), "loaded ignore file");
+                rules
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                warn!(path = %path.display(), error = %e, "could not read ignore file; ignoring it");
+                Self::default()
+            }
+        }
+    }
+
+    /// Parse ignore-file contents.
+    pub fn parse(contents: &str) -> Self {
+        let rules = contents
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim_end();
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+                let (negate, rest) = match line.strip_prefix('!') {
+                    Some(r) => (true, r),
+                    None => (false, line.strip_prefix('\\').unwrap_or(line)),
+                };
+                let (dir_only, rest) = match rest.strip_suffix('/') {
+                    Some(r) => (true, r),
+                    None => (false, rest),
+                };
+                let anchored = rest.contains('/');
+                let pattern = rest.trim_start_matches('/').to_string();
+                if pattern.is_empty() {
+                    return None;
+                }
+                Some(Rule {
+                    pattern,
+                    negate,
+                    dir_only,
+                    anchored,
+                })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// True if `path` (absolute, or relative to `base`) is excluded.
+    pub fn is_ignored(&self, base: &Path, path: &Path, is_dir: bool) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+        let rel = match path.strip_prefix(base) {
+            Ok(r) => r,
+            Err(_) if path.is_relative() => path,
+            Err(_) => return false,
+        };
+        let parts: Vec<String> = rel
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(s) => Some(s.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+
+        // Check each ancestor first: an excluded directory excludes everything below it.
+        for i in 1..=parts.len() {
+            let last = i == parts.len();
+            let prefix = parts[..i].join("/");
+            if self.matches(&prefix, &parts[i - 1], !last || is_dir) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Last matching rule wins.
+    fn matches(&self, rel: &str, name: &str, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for r in &self.rules {
+            if r.dir_only && !is_dir {
+                continue;
+            }
+            let subject = if r.anchored { rel } else { name };
+            if glob_match(r.pattern.as_bytes(), subject.as_bytes()) {
+                ignored = !r.negate;
+            }
+        }
+        ignored
+    }
+}
+
+/// Minimal glob matcher: `*`, `?`, `**`, and bracket classes.
+fn glob_match(pat: &[u8], text: &[u8]) -> bool {
+    Glob {
+        pat,
+        text,
+        failed: vec![false; (pat.len() + 1) * (text.len() + 1)],
+    }
+    .at(0, 0)
+}
+
+/// Matcher state. `failed` remembers (pattern, text) positions that cannot match, so
+/// `*` and `**` backtracking stays polynomial instead of exponential.
+struct Glob<'a> {
+    pat: &'a [u8],
+    text: &'a [u8],
+    failed: Vec<bool>,
+}
+
+impl Glob<'_> {
+    /// Does `pat[p..]` match `text[t..]`?
+    fn at(&mut self, p: usize, t: usize) -> bool {
+        let slot = p * (self.text.len() + 1) + t;
+        if self.failed[slot] {
+            return false;
+        }
+        let ok = self.step(p, t);
+        if !ok {
+            self.failed[slot] = true;
+        }
+        ok
+    }
+
+    fn step(&mut self, p: usize, t: usize) -> bool {
+        let (pat, text) = (self.pat, self.text);
+        match pat.get(p) {
+            None => t == text.len(),
+            Some(b'*') if pat.get(p + 1) == Some(&b'*') => {
+                // `**/` may match zero directories.
+                let rest = p + 2;
+                let rest_after_slash = if pat.get(rest) == Some(&b'/') {
+                    rest + 1
+                } else {
+                    rest
+                };
+                self.at(rest_after_slash, t) || (t..text.len()).any(|i| self.at(rest, i + 1))
+            }
+            Some(b'*') => {
+                for i in t..=text.len() {
+                    if self.at(p + 1, i) {
+                        return true;
+                    }
+                    if i < text.len() && text[i] == b'/' {
+                        break;
+                    }
+                }
+                false
+            }
+            Some(b'?') => match text.get(t) {
+                Some(&c) if c != b'/' => self.at(p + 1, t + 1),
+                _ => false,
+            },
+            Some(b'[') => {
+                let Some(&c) = text.get(t) else {
+                    return false;
+                };
+                match class_match(&pat[p + 1..], c) {
+                    Some((ok, consumed)) => ok && self.at(p + 1 + consumed, t + 1),
+                    // Unterminated class: treat '[' literally.
+                    None => c == b'[' && self.at(p + 1, t + 1),
+                }
+            }
+            Some(&c) => text.get(t) == Some(&c) && self.at(p + 1, t + 1),
+        }
+    }
+}
+
+/// Match `c` against a bracket class body; returns (matched, bytes consumed incl. `]`).
+fn class_match(body: &[u8], c: u8) -> Option<(bool, usize)> {
+    let (negate, mut i) = match body.first() {
+        Some(b'!') | Some(b'^') => (true, 1),
+        _ => (false, 0),
+    };
+    let mut matched = false;
+    let mut first = true;
+    while i < body.len() {
+        let b = body[i];
+        if b == b']' && !first {
+            return Some((matched != negate, i + 1));
+        }
+        first = false;
+        if i + 2 < body.len() && body[i + 1] == b'-' && body[i + 2] != b']' {
+            if body[i] <= c && c <= body[i + 2] {
+                matched = true;
+            }
+            i += 3;
+        } else {
+            if b == c {
+                matched = true;
+            }
+            i += 1;
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn ignored(rules: &str, path: &str, is_dir: bool) -> bool {
+        IgnoreRules::parse(rules).is_ignored(Path::new("/base"), &PathBuf::from(path), is_dir)
+    }
+
+    #[test]
+    fn glob_basics() {
+        assert!(glob_match(b"*.iso", b"x.iso"));
+        assert!(!glob_match(b"*.iso", b"a/x.iso"));
+        assert!(glob_match(b"**/x.iso", b"a/b/x.iso"));
+        assert!(glob_match(b"**/x.iso", b"x.iso"));
+        assert!(glob_match(b"a/**", b"a/b/c"));
+        assert!(glob_match(b"file?.txt", b"file1.txt"));
+        assert!(glob_match(b"[a-c]x", b"bx"));
+        assert!(!glob_match(b"[!a-c]x", b"bx"));
+    }
+
+    #[test]
+    fn double_star_backtracking_is_bounded() {
+        let name = "a".repeat(200);
+        let started = std::time::Instant::now();
+        assert!(!glob_match(b"**a**a**a**a**a**a**a**b", name.as_bytes()));
+        assert!(glob_match(b"**a**a**a**a**a**a**a", name.as_bytes()));
+        assert!(!ignored(
+            "**a**a**a**a**b\n",
+            &format!("/base/{name}"),
+            false
+        ));
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+        // `**/` only stands for whole directories.
+        assert!(!glob_match(b"**/b", b"xb"));
+    }
+
+    #[test]
+    fn directory_rules_cover_children() {
+        let rules = "manual/\n";
+        assert!(ignored(rules, "/base/manual", true));
+        assert!(ignored(rules, "/base/manual/keep.mkv", false));
+        assert!(!ignored(rules, "/base/manual", false), "dir-only rule");
+        assert!(!ignored(rules, "/elsewhere/manual", true));
+    }
+
+    #[test]
+    fn anchoring_and_negation() {
+        let rules = "# comment\n/top.bin\n*.nfo\n!keep.nfo\n";
+        assert!(ignored(rules, "/base/top.bin", false));
+        assert!(!ignored(rules, "/base/sub/top.bin", false));
+        assert!(ignored(rules, "/base/sub/x.nfo", false));
+        assert!(!ignored(rules, "/base/sub/keep.nfo", false));
+    }
+}
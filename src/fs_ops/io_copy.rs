@@ -5,19 +5,58 @@
 //! - Buffered I/O with large (1 MiB) buffers to reduce syscall count.
 //! - Optional write-through / full fsync for strong durability guarantees.
 //! - Returns a `CopyResult` struct for richer instrumentation.
+//! - `CopyMethod` picks the first thing tried: reflink (FICLONE / clonefile), in-kernel
+//!   copy (copy_file_range) or streaming; unsupported methods fall back down that list.
+//! - Linux: `copy_to_unnamed` copies into an O_TMPFILE that the caller links in with
+//!   `beneath::link_file`, so the file never exists under a temporary name.
+//! - `copy_streaming_beneath`, `copy_to_unnamed` and `copy_streaming_resume` open the
+//!   destination beneath a base directory on Linux (see beneath.rs).
+//!
+//! Cancellation: streaming and in-kernel copies check for a shutdown request (Ctrl-C,
+//! SIGTERM, `max_runtime`) before every buffer / chunk and stop with an error for which
+//! `is_aborted` is true; callers remove the partial destination.
 //!
 //! Snapshot semantics: the source file is read once from start to EOF; if it grows
 //! concurrently, the additional bytes are not included. Shrinks/truncation during
 //! copy will surface as read errors or early EOF; caller can compare `bytes` to the
 //! original metadata length if stricter validation is required.
 
+use std::fmt;
 use std::fs::{File, OpenOptions};
-use std::io::{self, BufReader, BufWriter, Seek, SeekFrom, Write};
+use std::io::{self, BufReader, Read, Seek, SeekFrom, Write};
 use std::path::Path;
 
+use super::fs_trace::traced;
+use crate::shutdown;
+
+use super::bench::CopyMethod;
+
+const BUF_SIZE: usize = 1024 * 1024; // 1 MiB buffers
+
+/// Payload of the io::Error a copy returns when it stops for a shutdown request.
+#[derive(Debug)]
+struct CopyAborted;
+
+impl fmt::Display for CopyAborted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("copy aborted: shutdown requested")
+    }
+}
+
+impl std::error::Error for CopyAborted {}
+
+fn aborted() -> io::Error {
+    io::Error::new(io::ErrorKind::Interrupted, CopyAborted)
+}
+
+/// True if `e` means the copy stopped because a shutdown was requested.
+pub(super) fn is_aborted(e: &io::Error) -> bool {
+    e.get_ref().is_some_and(|inner| inner.is::<CopyAborted>())
+}
+
 /// Durability mode controlling post-write flush behavior.
+/// Selected from `fsync_policy` (`none` -> Data, otherwise Full).
 #[derive(Clone, Copy, Debug)]
-#[allow(dead_code)] // Data mode unused today (future lighter durability); keep for API clarity.
 pub enum DurabilityMode {
     /// Ensure written data reaches the OS page cache (`flush`), but do not force
     /// a disk barrier. Fastest; may lose data on sudden power loss.
@@ -44,54 +83,68 @@ pub struct CopyResult {
 /// Notes:
 /// - `dst` is created with `create_new(true)` so we never clobber an existing file.
 /// - Callers are responsible for syncing the parent directory after the final rename.
+#[cfg(test)]
 pub(super) fn copy_streaming(src: &Path, dst: &Path) -> io::Result<u64> {
     // Backwards compatibility shim returning just bytes with Full semantics.
-    let res = copy_streaming_ex(src, dst, DurabilityMode::Full)?;
+    let res = copy_streaming_ex(src, dst, DurabilityMode::Full, CopyMethod::Auto)?;
     Ok(res.bytes)
 }
 
-/// Extended streaming copy with selectable durability.
+/// Extended streaming copy with selectable durability and copy method.
 pub(super) fn copy_streaming_ex(
     src: &Path,
     dst: &Path,
     mode: DurabilityMode,
+    method: CopyMethod,
 ) -> io::Result<CopyResult> {
-    const BUF_SIZE: usize = 1024 * 1024; // 1 MiB buffers
-
     // Fast-path: on macOS, try APFS clonefile to CoW-clone the file.
     // This creates the destination path atomically and is O(1) for metadata.
     #[cfg(target_os = "macos")]
-    {
-        use std::ffi::CString;
-        use std::os::unix::ffi::OsStrExt;
-        unsafe {
-            let src_c = CString::new(src.as_os_str().as_bytes()).unwrap();
-            let dst_c = CString::new(dst.as_os_str().as_bytes()).unwrap();
-            // clonefile returns 0 on success, -1 on error with errno set.
-            let rc = libc::clonefile(src_c.as_ptr(), dst_c.as_ptr(), 0);
-            if rc == 0 {
-                let bytes = File::open(src)?.metadata()?.len();
-                // Apply durability if requested
-                if matches!(mode, DurabilityMode::Full) {
-                    let f = File::options().read(true).write(false).open(dst)?;
-                    f.sync_all()?;
-                }
-                return Ok(CopyResult {
-                    bytes,
-                    buf_size: BUF_SIZE,
-                    mode,
-                });
-            } else {
-                // On errors like EXDEV/ENOTSUP/EPERM fall through to streaming; EEXIST should be
-                // impossible here since we always choose a unique temp name in higher layers.
-            }
+    if matches!(method, CopyMethod::Auto | CopyMethod::Reflink) && clonefile(src, dst).is_ok() {
+        let bytes = File::open(src)?.metadata()?.len();
+        // Apply durability if requested
+        if matches!(mode, DurabilityMode::Full) {
+            let f = File::options().read(true).write(false).open(dst)?;
+            f.sync_all()?;
         }
+        return Ok(CopyResult {
+            bytes,
+            buf_size: BUF_SIZE,
+            mode,
+        });
     }
+    // On errors like EXDEV/ENOTSUP/EPERM fall through to streaming; EEXIST should be
+    // impossible here since we always choose a unique temp name in higher layers.
 
     // Open source file for streaming or Linux fast-path.
-    let src_f = File::open(src)?;
+    let src_f = traced("open", src, || File::open(src))?;
+    let dst_f = traced("create", dst, || create_dest(dst, mode))?;
+    copy_into(src_f, dst_f, dst, mode, method)
+}
+
+/// `copy_streaming_ex` with `dst` created beneath `base` (Linux; elsewhere by path).
+pub(super) fn copy_streaming_beneath(
+    base: &Path,
+    src: &Path,
+    dst: &Path,
+    mode: DurabilityMode,
+    method: CopyMethod,
+) -> io::Result<CopyResult> {
+    #[cfg(target_os = "linux")]
+    {
+        let src_f = traced("open", src, || File::open(src))?;
+        let dst_f = traced("create", dst, || super::beneath::create_new(base, dst))?;
+        copy_into(src_f, dst_f, dst, mode, method)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = base;
+        copy_streaming_ex(src, dst, mode, method)
+    }
+}
 
-    // Destination options
+/// Create `dst` for writing; never clobbers an existing file.
+fn create_dest(dst: &Path, mode: DurabilityMode) -> io::Result<File> {
     let mut opts = OpenOptions::new();
     opts.write(true).create_new(true);
 
@@ -104,77 +157,32 @@ pub(super) fn copy_streaming_ex(
             opts.custom_flags(FILE_FLAG_WRITE_THROUGH);
         }
     }
+    #[cfg(not(windows))]
+    let _ = mode;
 
-    let dst_f = opts.open(dst)?;
-
-    // Fast-path: on Linux, try copy_file_range for in-kernel copy when supported.
-    #[cfg(target_os = "linux")]
-    {
-        use std::os::unix::io::AsRawFd;
-        // Try once with a large chunk size to detect support; if unsupported and no bytes copied,
-        // we'll fall back to streaming.
-        let mut total: u64 = 0;
-        let chunk: usize = 16 * 1024 * 1024; // 16 MiB per call
-        loop {
-            let rc = unsafe {
-                libc::copy_file_range(
-                    src_f.as_raw_fd(),
-                    std::ptr::null_mut(),
-                    dst_f.as_raw_fd(),
-                    std::ptr::null_mut(),
-                    chunk,
-                    0,
-                )
-            };
-            if rc > 0 {
-                total += rc as u64;
-                continue;
-            } else if rc == 0 {
-                // EOF reached
-                if matches!(mode, DurabilityMode::Full) {
-                    dst_f.sync_all()?;
-                }
-                return Ok(CopyResult {
-                    bytes: total,
-                    buf_size: BUF_SIZE,
-                    mode,
-                });
-            } else {
-                // Error; if no bytes copied and error indicates unsupported, fall back.
-                let err = io::Error::last_os_error();
-                if total == 0 {
-                    if let Some(code) = err.raw_os_error() {
-                        if code == libc::EXDEV
-                            || code == libc::ENOSYS
-                            || code == libc::EINVAL
-                            || code == libc::EPERM
-                        {
-                            // Unsupported; break to streaming fallback
-                        } else {
-                            return Err(err);
-                        }
-                    } else {
-                        return Err(err);
-                    }
-                } else {
-                    // Partial copy then error: return error; higher level will cleanup temp.
-                    return Err(err);
-                }
-                break; // fallback
-            }
-        }
-    }
-
-    // Streaming fallback (or non-Linux/non-macOS default): buffered io::copy
-    let mut reader = BufReader::with_capacity(BUF_SIZE, src_f);
-    let mut writer = BufWriter::with_capacity(BUF_SIZE, dst_f);
-    let bytes = io::copy(&mut reader, &mut writer)?;
-    writer.flush()?;
+    opts.open(dst)
+}
 
+/// Copy the open `src_f` into the empty, open `dst_f` with `method` (falling back along
+/// reflink -> kernel copy -> streaming), then apply `mode`. `dst` names `dst_f` in traces.
+fn copy_into(
+    src_f: File,
+    dst_f: File,
+    dst: &Path,
+    mode: DurabilityMode,
+    method: CopyMethod,
+) -> io::Result<CopyResult> {
+    let bytes = match method {
+        CopyMethod::Streaming => stream_copy(&src_f, &dst_f)?,
+        CopyMethod::Reflink => match reflink(&src_f, &dst_f) {
+            Ok(n) => n,
+            Err(_) => kernel_or_stream(&src_f, &dst_f)?,
+        },
+        CopyMethod::Auto | CopyMethod::Kernel => kernel_or_stream(&src_f, &dst_f)?,
+    };
     if matches!(mode, DurabilityMode::Full) {
-        writer.get_ref().sync_all()?;
+        traced("fsync", dst, || dst_f.sync_all())?;
     }
-
     Ok(CopyResult {
         bytes,
         buf_size: BUF_SIZE,
@@ -182,18 +190,203 @@ pub(super) fn copy_streaming_ex(
     })
 }
 
+/// Copy `src` to the new file `dst` with exactly `method` (no fallback) and fsync it.
+/// Used by `bench-dest`; `Unsupported` means the method is not available here.
+pub(super) fn copy_with_method_only(src: &Path, dst: &Path, method: CopyMethod) -> io::Result<u64> {
+    #[cfg(target_os = "macos")]
+    if method == CopyMethod::Reflink {
+        clonefile(src, dst)?;
+        File::open(dst)?.sync_all()?;
+        return Ok(std::fs::metadata(dst)?.len());
+    }
+    let src_f = traced("open", src, || File::open(src))?;
+    let dst_f = create_dest(dst, DurabilityMode::Full)?;
+    let bytes = match method {
+        CopyMethod::Reflink => reflink(&src_f, &dst_f)?,
+        CopyMethod::Kernel => kernel_copy(&src_f, &dst_f).unwrap_or_else(|| {
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "no in-kernel copy on this platform or filesystem",
+            ))
+        })?,
+        CopyMethod::Streaming | CopyMethod::Auto => stream_copy(&src_f, &dst_f)?,
+    };
+    dst_f.sync_all()?;
+    Ok(bytes)
+}
+
+fn kernel_or_stream(src_f: &File, dst_f: &File) -> io::Result<u64> {
+    match kernel_copy(src_f, dst_f) {
+        Some(res) => res,
+        None => stream_copy(src_f, dst_f),
+    }
+}
+
+/// Copy in BUF_SIZE chunks (the portable default).
+fn stream_copy(mut src_f: &File, mut dst_f: &File) -> io::Result<u64> {
+    copy_cancellable(&mut src_f, &mut dst_f)
+}
+
+/// io::copy with a shutdown check before every buffer.
+fn copy_cancellable(reader: &mut impl Read, writer: &mut impl Write) -> io::Result<u64> {
+    let mut buf = vec![0u8; BUF_SIZE];
+    let mut total: u64 = 0;
+    loop {
+        if shutdown::is_requested() {
+            return Err(aborted());
+        }
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        writer.write_all(&buf[..n])?;
+        total += n as u64;
+    }
+    writer.flush()?;
+    Ok(total)
+}
+
+/// In-kernel copy with copy_file_range (Linux). None if unsupported before any byte was
+/// copied, so the caller can stream instead.
+#[cfg(target_os = "linux")]
+fn kernel_copy(src_f: &File, dst_f: &File) -> Option<io::Result<u64>> {
+    use std::os::unix::io::AsRawFd;
+    let mut total: u64 = 0;
+    let chunk: usize = 16 * 1024 * 1024; // 16 MiB per call
+    loop {
+        if shutdown::is_requested() {
+            return Some(Err(aborted()));
+        }
+        let rc = unsafe {
+            libc::copy_file_range(
+                src_f.as_raw_fd(),
+                std::ptr::null_mut(),
+                dst_f.as_raw_fd(),
+                std::ptr::null_mut(),
+                chunk,
+                0,
+            )
+        };
+        if rc > 0 {
+            total += rc as u64;
+        } else if rc == 0 {
+            // EOF reached
+            return Some(Ok(total));
+        } else {
+            let err = io::Error::last_os_error();
+            let unsupported = matches!(
+                err.raw_os_error(),
+                Some(libc::EXDEV | libc::ENOSYS | libc::EINVAL | libc::EPERM)
+            );
+            // Partial copy then error: return error; higher level will cleanup temp.
+            return if total == 0 && unsupported {
+                None
+            } else {
+                Some(Err(err))
+            };
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn kernel_copy(_src_f: &File, _dst_f: &File) -> Option<io::Result<u64>> {
+    None
+}
+
+/// Share `src_f`'s extents with `dst_f` (FICLONE on Btrfs/XFS/bcachefs).
+#[cfg(target_os = "linux")]
+fn reflink(src_f: &File, dst_f: &File) -> io::Result<u64> {
+    use std::os::unix::io::AsRawFd;
+    const FICLONE: u32 = 0x4004_9409; // _IOW(0x94, 9, int)
+    let rc = unsafe { libc::ioctl(dst_f.as_raw_fd(), FICLONE as _, src_f.as_raw_fd()) };
+    if rc == 0 {
+        src_f.metadata().map(|m| m.len())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Reflinks of open files exist only on Linux here; macOS clones by path (`clonefile`).
+#[cfg(not(target_os = "linux"))]
+fn reflink(_src_f: &File, _dst_f: &File) -> io::Result<u64> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "reflink of an open file is not supported on this platform",
+    ))
+}
+
+/// APFS clonefile(2): creates `dst` as a copy-on-write clone of `src`.
+#[cfg(target_os = "macos")]
+fn clonefile(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    let src_c = CString::new(src.as_os_str().as_bytes())?;
+    let dst_c = CString::new(dst.as_os_str().as_bytes())?;
+    // clonefile returns 0 on success, -1 on error with errno set.
+    if unsafe { libc::clonefile(src_c.as_ptr(), dst_c.as_ptr(), 0) } == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Copy `src` into a new unnamed file (O_TMPFILE) in `dir` (beneath `base`) and fsync it.
+/// The file has no name until `beneath::link_file` gives it one; dropping it discards the data.
+#[cfg(target_os = "linux")]
+pub(super) fn copy_to_unnamed(
+    base: &Path,
+    src: &Path,
+    dir: &Path,
+    mode: DurabilityMode,
+    method: CopyMethod,
+) -> io::Result<(File, u64)> {
+    let src_f = traced("open", src, || File::open(src))?;
+    let dst_f = traced("open_tmpfile", dir, || {
+        super::beneath::open_tmpfile(base, dir)
+    })?;
+    let keep = dst_f.try_clone()?;
+    let res = copy_into(src_f, dst_f, dir, mode, method)?;
+    Ok((keep, res.bytes))
+}
+
+/// True if `e` from `copy_to_unnamed` means the kernel or filesystem lacks O_TMPFILE.
+#[cfg(target_os = "linux")]
+pub(super) fn unnamed_unsupported(e: &io::Error) -> bool {
+    matches!(
+        e.raw_os_error(),
+        Some(libc::EOPNOTSUPP | libc::EISDIR | libc::EINVAL)
+    )
+}
+
 /// Resume variant: append remaining bytes to an existing temp file that is smaller than the source.
 /// Preconditions: `dst` exists, its length == `offset`, and `offset < source_size`.
 /// Returns the final total bytes written (should equal source size on success).
-pub(super) fn copy_streaming_resume(src: &Path, dst: &Path, offset: u64) -> io::Result<u64> {
-    let src_f = File::open(src)?;
+/// `dst` is reopened beneath `base` on Linux.
+pub(super) fn copy_streaming_resume(
+    base: &Path,
+    src: &Path,
+    dst: &Path,
+    offset: u64,
+    mode: DurabilityMode,
+) -> io::Result<u64> {
+    let src_f = traced("open", src, || File::open(src))?;
     let src_meta = src_f.metadata()?;
     let total = src_meta.len();
     if offset >= total {
         return Ok(offset);
     }
 
-    let mut dst_f = OpenOptions::new().write(true).read(true).open(dst)?;
+    #[cfg(target_os = "linux")]
+    let mut dst_f = traced("open", dst, || super::beneath::open_rw(base, dst))?;
+    #[cfg(not(target_os = "linux"))]
+    let mut dst_f = {
+        let _ = base;
+        traced("open", dst, || {
+            OpenOptions::new().write(true).read(true).open(dst)
+        })?
+    };
     let cur_len = dst_f.metadata()?.len();
     if cur_len != offset {
         return Err(io::Error::new(
@@ -206,11 +399,10 @@ pub(super) fn copy_streaming_resume(src: &Path, dst: &Path, offset: u64) -> io::
     let mut reader = BufReader::new(src_f);
     reader.seek(SeekFrom::Start(offset))?;
     dst_f.seek(SeekFrom::Start(offset))?; // should already be at end, but enforce
-    let mut writer = BufWriter::new(dst_f);
-
-    let copied = io::copy(&mut reader, &mut writer)?;
-    writer.flush()?;
-    writer.get_ref().sync_all()?; // durability same as full mode
+    let copied = copy_cancellable(&mut reader, &mut dst_f)?;
+    if matches!(mode, DurabilityMode::Full) {
+        traced("fsync", dst, || dst_f.sync_all())?;
+    }
 
     Ok(offset + copied)
 }
@@ -238,6 +430,32 @@ mod tests {
         assert_eq!(&got, data);
     }
 
+    /// Reader that requests shutdown once the first buffer has been handed out.
+    struct ShutdownAfterFirstRead(bool);
+
+    impl Read for ShutdownAfterFirstRead {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0 {
+                shutdown::request();
+            }
+            self.0 = true;
+            buf.fill(7);
+            Ok(buf.len())
+        }
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn shutdown_aborts_between_buffers() {
+        shutdown::reset();
+        let mut out = Vec::new();
+        let err = copy_cancellable(&mut ShutdownAfterFirstRead(false), &mut out).unwrap_err();
+        shutdown::reset();
+        assert!(is_aborted(&err));
+        assert_eq!(out.len(), 2 * BUF_SIZE);
+        assert!(!is_aborted(&io::Error::from(io::ErrorKind::Interrupted)));
+    }
+
     #[test]
     fn copy_zero_length_ok() {
         let dir = tempdir().unwrap();
@@ -281,7 +499,7 @@ mod tests {
         }
         fs::write(&src, &data).unwrap();
 
-        let res = copy_streaming_ex(&src, &dst, DurabilityMode::Data).unwrap();
+        let res = copy_streaming_ex(&src, &dst, DurabilityMode::Data, CopyMethod::Auto).unwrap();
         assert_eq!(res.bytes as usize, size);
         assert_eq!(res.buf_size, BUF_SIZE);
         assert!(matches!(res.mode, DurabilityMode::Data));
@@ -296,7 +514,7 @@ mod tests {
         let src = dir.path().join("d.txt");
         let dst = dir.path().join("d.out");
         fs::write(&src, b"abcdef").unwrap();
-        let res = copy_streaming_ex(&src, &dst, DurabilityMode::Full).unwrap();
+        let res = copy_streaming_ex(&src, &dst, DurabilityMode::Full, CopyMethod::Auto).unwrap();
         assert_eq!(res.bytes, 6);
         assert!(matches!(res.mode, DurabilityMode::Full));
         let got = fs::read(&dst).unwrap();
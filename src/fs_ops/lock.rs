@@ -6,22 +6,36 @@
 //! - Unix: use flock(LOCK_EX) on the file descriptor (blocks until acquired).
 //! - Windows: open the file without sharing (exclusive); retry on sharing violations.
 //!
+//! - `lock_strategy` picks the mechanism: `flock` (above), `lockfile` (O_EXCL file with TTL,
+//!   see `lockfile.rs`) or `none`. `auto` uses the lockfile on network filesystems (NFS/SMB),
+//!   where flock is unreliable, and flock everywhere else. The plain functions use `auto`.
+//!
 //! Notes:
 //! - The lock is released when the DirLock guard is dropped.
-//! - This module returns io::Result to keep low-level errors precise.
+//! - This module returns io::Result to keep low-level errors precise; `lock_error` maps
+//!   contention/timeouts to typed errors for callers.
+//! - `ARIA_MOVE_LOCK_TIMEOUT=<secs>` bounds blocking acquires (`0` = fail at once if held).
 //!
 //! Callers typically use:
 //!   - acquire_move_lock(src_path)       // serialize per-source (parent dir)
 //!   - acquire_dir_lock(destination_dir) // serialize finalization into destination
+//!
+//! The movers themselves lock single paths (see `path_lock.rs`) so unrelated moves don't
+//! serialize on download_base or completed_base.
 
 #[cfg(unix)]
 use std::fs::{File, OpenOptions};
 use std::io;
 use std::path::{Path, PathBuf};
-use std::time::Instant;
-use tracing::trace;
+use std::time::{Duration, Instant};
 #[cfg(windows)]
 use tracing::warn;
+use tracing::{debug, trace};
+
+use crate::config::types::LockStrategy;
+use crate::errors::AriaMoveError;
+
+use super::lockfile::{self, LockFileGuard};
 
 #[cfg(unix)]
 use std::os::fd::AsRawFd;
@@ -45,18 +59,39 @@ const GENERIC_WRITE: u32 = 0x4000_0000;
 /// Public for integration tests / advanced callers; stability not guaranteed.
 pub struct DirLock {
     #[cfg(unix)]
-    file: File, // on Unix we lock the directory fd directly
+    file: Option<File>, // on Unix we flock the directory fd directly (None for other strategies)
     #[cfg(windows)]
-    handle: isize, // HANDLE for the hidden lock file
+    handle: isize, // HANDLE for the hidden lock file (0 for other strategies)
+    lockfile: Option<LockFileGuard>, // lock_strategy = lockfile
     _path: PathBuf, // for logs; on Windows this is the lock file path, on Unix the directory path
 }
 
+impl DirLock {
+    /// Guard that holds nothing (lock_strategy = none).
+    fn unlocked(dir: &Path) -> Self {
+        Self {
+            #[cfg(unix)]
+            file: None,
+            #[cfg(windows)]
+            handle: 0,
+            lockfile: None,
+            _path: dir.to_path_buf(),
+        }
+    }
+
+    fn from_lockfile(dir: &Path, guard: LockFileGuard) -> Self {
+        let mut lock = Self::unlocked(dir);
+        lock.lockfile = Some(guard);
+        lock
+    }
+}
+
 impl Drop for DirLock {
     fn drop(&mut self) {
         #[cfg(unix)]
-        {
+        if let Some(file) = self.file.as_ref() {
             // Unlock by closing; flock releases on fd close. Best-effort: ignore errors.
-            let _ = unsafe { libc::flock(self.file.as_raw_fd(), libc::LOCK_UN) };
+            let _ = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_UN) };
         }
         #[cfg(windows)]
         unsafe {
@@ -67,12 +102,114 @@ impl Drop for DirLock {
         // On Windows, we use a hidden on-disk lock file — try to remove it on drop.
         // On Unix, we flock the directory fd directly and there is no lock file.
         #[cfg(windows)]
-        {
+        if self.handle != 0 {
             let _ = std::fs::remove_file(&self._path);
         }
+        // A held lockfile is released by LockFileGuard's own Drop.
+        self.lockfile.take();
+    }
+}
+
+/// Resolve `auto` for `dir`: lockfile on network filesystems, flock otherwise.
+pub fn effective_lock_strategy(dir: &Path, strategy: LockStrategy) -> LockStrategy {
+    match strategy {
+        LockStrategy::Auto if crate::platform::is_network_fs(dir) => LockStrategy::Lockfile,
+        LockStrategy::Auto => LockStrategy::Flock,
+        other => other,
+    }
+}
+
+/// Blocking acquire of a directory lock using `strategy`.
+/// With `ARIA_MOVE_LOCK_TIMEOUT` set, fails with `WouldBlock`/`TimedOut` instead of waiting forever.
+pub fn acquire_dir_lock_with(dir: &Path, strategy: LockStrategy) -> io::Result<DirLock> {
+    if let Some(limit) = lock_timeout() {
+        return acquire_with_deadline(dir, strategy, limit);
+    }
+    match effective_lock_strategy(dir, strategy) {
+        LockStrategy::Lockfile => {
+            debug!(path = %dir.display(), "using lockfile strategy");
+            lockfile::acquire(dir).map(|g| DirLock::from_lockfile(dir, g))
+        }
+        LockStrategy::None => Ok(DirLock::unlocked(dir)),
+        LockStrategy::Flock | LockStrategy::Auto => flock_dir_lock(dir),
+    }
+}
+
+/// Non-blocking acquire of a directory lock using `strategy`.
+pub fn try_acquire_dir_lock_with(
+    dir: &Path,
+    strategy: LockStrategy,
+) -> io::Result<Option<DirLock>> {
+    match effective_lock_strategy(dir, strategy) {
+        LockStrategy::Lockfile => {
+            Ok(lockfile::try_acquire(dir)?.map(|g| DirLock::from_lockfile(dir, g)))
+        }
+        LockStrategy::None => Ok(Some(DirLock::unlocked(dir))),
+        LockStrategy::Flock | LockStrategy::Auto => try_flock_dir_lock(dir),
     }
 }
 
+/// Acquire a move lock for `src` (locks its parent directory) using `strategy`.
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))] // movers use per-path locks now
+pub fn acquire_move_lock_with(src: &Path, strategy: LockStrategy) -> io::Result<DirLock> {
+    let parent = src.parent().unwrap_or_else(|| Path::new("."));
+    acquire_dir_lock_with(parent, strategy)
+}
+
+/// Poll the non-blocking acquire until `limit` elapses.
+fn acquire_with_deadline(
+    dir: &Path,
+    strategy: LockStrategy,
+    limit: Duration,
+) -> io::Result<DirLock> {
+    let start = Instant::now();
+    loop {
+        if let Some(l) = try_acquire_dir_lock_with(dir, strategy)? {
+            return Ok(l);
+        }
+        if limit.is_zero() {
+            return Err(io::Error::new(
+                io::ErrorKind::WouldBlock,
+                format!("lock on {} is held by another process", dir.display()),
+            ));
+        }
+        if crate::shutdown::is_requested() {
+            return Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "shutdown requested while waiting for lock",
+            ));
+        }
+        if start.elapsed() >= limit {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!(
+                    "lock on {} not acquired within {}s",
+                    dir.display(),
+                    limit.as_secs()
+                ),
+            ));
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// `ARIA_MOVE_LOCK_TIMEOUT` in seconds; unset or invalid = wait indefinitely.
+pub(super) fn lock_timeout() -> Option<Duration> {
+    let raw = std::env::var("ARIA_MOVE_LOCK_TIMEOUT").ok()?;
+    raw.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
+
+/// Convert a lock acquisition error for `dir` into a typed error where one applies.
+pub(super) fn lock_error(dir: &Path, e: io::Error) -> anyhow::Error {
+    match e.kind() {
+        io::ErrorKind::WouldBlock => AriaMoveError::LockContended(dir.to_path_buf()).into(),
+        io::ErrorKind::TimedOut => AriaMoveError::LockTimeout(dir.to_path_buf()).into(),
+        io::ErrorKind::Interrupted => AriaMoveError::Interrupted.into(),
+        _ => anyhow::anyhow!("acquire lock for '{}': {}", dir.display(), e),
+    }
+}
+
+#[cfg(windows)]
 fn lock_file_path(dir: &Path) -> PathBuf {
     dir.join(".aria_move.dir.lock")
 }
@@ -81,6 +218,10 @@ fn lock_file_path(dir: &Path) -> PathBuf {
 /// Blocks until acquired. Returns a guard that releases on drop.
 /// Blocking acquire of a directory lock. Waits until the lock is available.
 pub fn acquire_dir_lock(dir: &Path) -> io::Result<DirLock> {
+    acquire_dir_lock_with(dir, LockStrategy::Auto)
+}
+
+fn flock_dir_lock(dir: &Path) -> io::Result<DirLock> {
     let start = Instant::now();
 
     #[cfg(unix)]
@@ -104,7 +245,8 @@ pub fn acquire_dir_lock(dir: &Path) -> io::Result<DirLock> {
             trace!(path = %dir.display(), waited_ms = waited.as_millis() as u64, "lock acquired after wait");
         }
         Ok(DirLock {
-            file: f,
+            file: Some(f),
+            lockfile: None,
             _path: dir.to_path_buf(),
         })
     }
@@ -143,6 +285,7 @@ pub fn acquire_dir_lock(dir: &Path) -> io::Result<DirLock> {
                 trace!(path = %lock_path.display(), attempts = attempts, waited_ms = waited.as_millis() as u64, "lock acquired");
                 return Ok(DirLock {
                     handle: handle as isize,
+                    lockfile: None,
                     _path: lock_path.clone(),
                 });
             }
@@ -170,7 +313,12 @@ pub fn acquire_dir_lock(dir: &Path) -> io::Result<DirLock> {
 /// or Err on unexpected errors.
 /// Non-blocking attempt to acquire a directory lock.
 /// Returns Ok(None) if lock is currently held elsewhere.
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))] // only reachable via the unstable API
 pub fn try_acquire_dir_lock(dir: &Path) -> io::Result<Option<DirLock>> {
+    try_acquire_dir_lock_with(dir, LockStrategy::Auto)
+}
+
+fn try_flock_dir_lock(dir: &Path) -> io::Result<Option<DirLock>> {
     let start = Instant::now();
 
     #[cfg(unix)]
@@ -184,7 +332,8 @@ pub fn try_acquire_dir_lock(dir: &Path) -> io::Result<Option<DirLock>> {
         if rc == 0 {
             trace!(path = %dir.display(), waited_ms = start.elapsed().as_millis() as u64, "try-lock success");
             return Ok(Some(DirLock {
-                file: f,
+                file: Some(f),
+                lockfile: None,
                 _path: dir.to_path_buf(),
             }));
         }
@@ -225,6 +374,7 @@ pub fn try_acquire_dir_lock(dir: &Path) -> io::Result<Option<DirLock>> {
             trace!(path = %lock_path.display(), waited_ms = start.elapsed().as_millis() as u64, "try-lock success");
             return Ok(Some(DirLock {
                 handle: handle as isize,
+                lockfile: None,
                 _path: lock_path,
             }));
         }
@@ -243,6 +393,7 @@ pub fn try_acquire_dir_lock(dir: &Path) -> io::Result<Option<DirLock>> {
 /// Acquire a move lock for `src` by locking its parent directory.
 /// Serializes operations on the same source path.
 /// Acquire a move lock for a source path (locks its parent directory).
+#[cfg_attr(not(feature = "unstable"), allow(dead_code))] // only reachable via the unstable API
 pub fn acquire_move_lock(src: &Path) -> io::Result<DirLock> {
     let parent = src.parent().unwrap_or_else(|| Path::new("."));
     acquire_dir_lock(parent)
@@ -0,0 +1,169 @@
+//! In-process registry of held path locks (`LockManager`).
+//! - Every per-path lock (`path_lock`) first claims its sidecar in the process-wide
+//!   `LockManager`, then takes the on-disk lock.
+//! - Threads of one process that want the same path wait on a condition variable instead
+//!   of polling the sidecar; only the claim holder ever opens or locks it.
+//!
+//! Notes:
+//! - Used automatically; library consumers running many moves in one process need no setup.
+//! - The on-disk lock is still taken, so other processes and hosts are excluded as before.
+//! - Claims are keyed by sidecar path, so hash collisions serialize exactly like on disk.
+//! - Guards are `Send`: a lock may be released on another thread (e.g. after an await in
+//!   an async service that runs moves on blocking threads).
+
+use std::collections::HashSet;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Process-wide registry of path locks held by this process.
+pub struct LockManager {
+    held: Mutex<HashSet<PathBuf>>,
+    released: Condvar,
+}
+
+/// A claim on one key in the registry; released on drop.
+pub(super) struct Claim {
+    manager: &'static LockManager,
+    key: PathBuf,
+}
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        self.manager.held().remove(&self.key);
+        self.manager.released.notify_all();
+    }
+}
+
+impl LockManager {
+    /// The registry shared by all moves in this process.
+    pub fn global() -> &'static LockManager {
+        static GLOBAL: OnceLock<LockManager> = OnceLock::new();
+        GLOBAL.get_or_init(|| LockManager {
+            held: Mutex::new(HashSet::new()),
+            released: Condvar::new(),
+        })
+    }
+
+    /// Number of path locks currently held by this process.
+    #[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+    pub fn held_count(&self) -> usize {
+        self.held().len()
+    }
+
+    /// True if this process holds the lock for `sidecar`.
+    #[cfg_attr(not(feature = "unstable"), allow(dead_code))]
+    pub fn is_held(&self, sidecar: &Path) -> bool {
+        self.held().contains(sidecar)
+    }
+
+    /// Claim `key` if no other thread of this process holds it.
+    pub(super) fn try_claim(&'static self, key: &Path) -> Option<Claim> {
+        self.held()
+            .insert(key.to_path_buf())
+            .then(|| self.claim(key))
+    }
+
+    /// Wait until `key` is free in this process and claim it.
+    /// `limit` bounds the wait like `ARIA_MOVE_LOCK_TIMEOUT` (zero = do not wait).
+    pub(super) fn claim_within(
+        &'static self,
+        key: &Path,
+        limit: Option<Duration>,
+    ) -> io::Result<Claim> {
+        let start = Instant::now();
+        let mut next_warn = Duration::from_secs(10);
+        let mut held = self.held();
+        loop {
+            if held.insert(key.to_path_buf()) {
+                return Ok(self.claim(key));
+            }
+            if limit.is_some_and(|l| l.is_zero()) {
+                return Err(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    format!("lock on {} is held by this process", key.display()),
+                ));
+            }
+            if crate::shutdown::is_requested() {
+                return Err(io::Error::new(
+                    io::ErrorKind::Interrupted,
+                    "shutdown requested while waiting for lock",
+                ));
+            }
+            if let Some(limit) = limit
+                && start.elapsed() >= limit
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!(
+                        "lock on {} not acquired within {}s",
+                        key.display(),
+                        limit.as_secs()
+                    ),
+                ));
+            }
+            if start.elapsed() >= next_warn {
+                warn!(path = %key.display(), waited_s = start.elapsed().as_secs(), "still waiting for path lock held by this process");
+                next_warn += Duration::from_secs(10);
+            }
+            // Wake periodically to notice shutdown and the time limit.
+            held = self
+                .released
+                .wait_timeout(held, Duration::from_millis(250))
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
+    fn claim(&'static self, key: &Path) -> Claim {
+        Claim {
+            manager: self,
+            key: key.to_path_buf(),
+        }
+    }
+
+    fn held(&self) -> MutexGuard<'_, HashSet<PathBuf>> {
+        // A panic while holding the guard cannot leave the set inconsistent.
+        self.held.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::thread;
+
+    #[test]
+    fn claims_are_exclusive_and_released_on_drop() {
+        let m = LockManager::global();
+        let key = Path::new("/registry-test/.aria_move.0000000000000001.lock");
+        let claim = m.try_claim(key).expect("free key");
+        assert!(m.is_held(key));
+        assert!(m.try_claim(key).is_none());
+        let err = m.claim_within(key, Some(Duration::ZERO)).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::WouldBlock);
+        drop(claim);
+        assert!(!m.is_held(key));
+    }
+
+    #[test]
+    fn waiter_is_woken_when_the_holder_releases() {
+        let m = LockManager::global();
+        let key = Path::new("/registry-test/.aria_move.0000000000000002.lock");
+        let claim = m.try_claim(key).unwrap();
+        let (tx, rx) = mpsc::channel();
+        let waiter = thread::spawn(move || {
+            let c = m.claim_within(key, None).unwrap();
+            tx.send(()).unwrap();
+            drop(c);
+        });
+        assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+        drop(claim);
+        rx.recv_timeout(Duration::from_secs(5))
+            .expect("waiter claimed");
+        waiter.join().unwrap();
+    }
+}
@@ -0,0 +1,319 @@
+//! O_EXCL lockfile protocol (`lock_strategy = lockfile`), for NFS and other network
+//! filesystems where flock is unreliable.
+//!
+//! Design:
+//! - Locking = creating `<dir>/.aria_move.lockfile` with `create_new` (O_EXCL). The file holds
+//!   a token line: `pid=<pid> host=<hostname> nonce=<nanos>`.
+//! - While the guard lives, a heartbeat thread refreshes the file's mtime every TTL/4.
+//! - A lockfile is stale when its mtime is older than `LOCKFILE_TTL`, or when its owner is a
+//!   process on this host that no longer exists. Stale files are renamed aside (atomic) and
+//!   removed, then acquisition retries.
+//! - On drop the lockfile is removed, but only if it still holds our token.
+//!
+//! Notes:
+//! - Staleness compares mtimes written by different clients: keep clocks in sync (NTP).
+//! - If a stale file is replaced by a fresh lock between our check and our rename, the fresh
+//!   lock is put back (hard link, create-if-absent semantics) before retrying.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{debug, trace, warn};
+
+use crate::shutdown;
+
+/// Name of the lockfile created inside the locked directory.
+pub const LOCKFILE_NAME: &str = ".aria_move.lockfile";
+
+/// A lockfile not refreshed for this long is considered abandoned.
+pub const LOCKFILE_TTL: Duration = Duration::from_secs(120);
+
+/// Held lockfile; removes the file (if still ours) and stops the heartbeat on drop.
+pub(super) struct LockFileGuard {
+    path: PathBuf,
+    token: String,
+    stop: Arc<AtomicBool>,
+    heartbeat: Option<JoinHandle<()>>,
+}
+
+impl Drop for LockFileGuard {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(h) = self.heartbeat.take() {
+            h.thread().unpark();
+            let _ = h.join();
+        }
+        if read_token(&self.path).as_deref() == Some(self.token.as_str()) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Blocking acquire: retries until the lock is free or stale. Fails on shutdown request.
+pub(super) fn acquire(dir: &Path) -> io::Result<LockFileGuard> {
+    let start = Instant::now();
+    let mut next_warn = Duration::from_secs(10);
+    loop {
+        if let Some(g) = try_acquire(dir)? {
+            trace!(path = %g.path.display(), waited_ms = start.elapsed().as_millis() as u64, "lockfile acquired");
+            return Ok(g);
+        }
+        if shutdown::is_requested() {
+            return Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "shutdown requested while waiting for lockfile",
+            ));
+        }
+        if start.elapsed() >= next_warn {
+            warn!(path = %dir.join(LOCKFILE_NAME).display(), waited_s = start.elapsed().as_secs(), "still waiting for lockfile");
+            next_warn += Duration::from_secs(10);
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// Non-blocking attempt. Ok(None) if another live owner holds the lock.
+pub(super) fn try_acquire(dir: &Path) -> io::Result<Option<LockFileGuard>> {
+    try_acquire_at(dir.join(LOCKFILE_NAME))
+}
+
+/// `try_acquire` with an explicit lockfile path (per-path locks use a sidecar name).
+pub(super) fn try_acquire_at(path: PathBuf) -> io::Result<Option<LockFileGuard>> {
+    let token = new_token();
+    // Two rounds: the second runs after a stale lockfile was cleared.
+    for _ in 0..2 {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut f) => {
+                f.write_all(token.as_bytes())?;
+                f.sync_all()?;
+                return Ok(Some(guard(path, token)));
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                if !clear_if_stale(&path)? {
+                    return Ok(None);
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(None)
+}
+
+fn guard(path: PathBuf, token: String) -> LockFileGuard {
+    let stop = Arc::new(AtomicBool::new(false));
+    let heartbeat = {
+        let (path, token, stop) = (path.clone(), token.clone(), stop.clone());
+        thread::Builder::new()
+            .name("aria_move-lockfile".into())
+            .spawn(move || {
+                while !stop.load(Ordering::SeqCst) {
+                    thread::park_timeout(LOCKFILE_TTL / 4);
+                    if stop.load(Ordering::SeqCst) {
+                        break;
+                    }
+                    if read_token(&path).as_deref() != Some(token.as_str()) {
+                        warn!(path = %path.display(), "lockfile was taken over by another process");
+                        break;
+                    }
+                    let _ = filetime::set_file_mtime(&path, filetime::FileTime::now());
+                }
+            })
+            .ok()
+    };
+    LockFileGuard {
+        path,
+        token,
+        stop,
+        heartbeat,
+    }
+}
+
+/// Remove `path` if it is stale. Returns true if the caller should retry creating it.
+fn clear_if_stale(path: &Path) -> io::Result<bool> {
+    let meta = match fs::metadata(path) {
+        Ok(m) => m,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(true),
+        Err(e) => return Err(e),
+    };
+    let token = read_token(path).unwrap_or_default();
+    let (expired, dead_owner) = staleness(&meta, &token);
+    if !expired && !dead_owner {
+        return Ok(false);
+    }
+
+    // Rename aside atomically so only one contender clears it.
+    let name = path
+        .file_name()
+        .map_or_else(|| LOCKFILE_NAME.into(), |n| n.to_string_lossy());
+    let aside = path.with_file_name(format!("{name}.stale.{}.{}", std::process::id(), nanos()));
+    match fs::rename(path, &aside) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(true),
+        Err(e) => return Err(e),
+    }
+    if read_token(&aside).unwrap_or_default() != token {
+        // We grabbed a fresh lock that replaced the stale one: put it back.
+        let _ = fs::hard_link(&aside, path);
+        let _ = fs::remove_file(&aside);
+        return Ok(false);
+    }
+    let _ = fs::remove_file(&aside);
+    debug!(path = %path.display(), expired, dead_owner, owner = %token.trim(), "removed stale lockfile");
+    Ok(true)
+}
+
+/// (older than the TTL, owner is a dead process on this host) for a lockfile.
+fn staleness(meta: &fs::Metadata, token: &str) -> (bool, bool) {
+    let age = meta
+        .modified()
+        .ok()
+        .and_then(|m| SystemTime::now().duration_since(m).ok())
+        .unwrap_or_default();
+    (age > LOCKFILE_TTL, owner_is_dead_local_process(token))
+}
+
+/// True if `path` holds a lockfile token (as opposed to an empty flock sidecar).
+pub(super) fn is_lockfile(path: &Path) -> bool {
+    read_token(path).is_some_and(|t| t.starts_with("pid="))
+}
+
+/// Remove the lockfile at `path` if it is stale (`dry_run`: only report).
+/// Returns true if it was (or would be) removed.
+pub(super) fn remove_if_stale(path: &Path, dry_run: bool) -> io::Result<bool> {
+    let meta = match fs::metadata(path) {
+        Ok(m) => m,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    let (expired, dead_owner) = staleness(&meta, &read_token(path).unwrap_or_default());
+    if dry_run || !(expired || dead_owner) {
+        return Ok(expired || dead_owner);
+    }
+    // Another contender may clear it first; either way it is gone when this returns true.
+    Ok(clear_if_stale(path)? && !path.exists())
+}
+
+fn read_token(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok()
+}
+
+fn new_token() -> String {
+    format!(
+        "pid={} host={} nonce={}\n",
+        std::process::id(),
+        hostname(),
+        nanos()
+    )
+}
+
+fn nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
+}
+
+/// True only when the token names a process on this host that no longer exists.
+fn owner_is_dead_local_process(token: &str) -> bool {
+    let field = |key: &str| {
+        token
+            .split_whitespace()
+            .find_map(|kv| kv.strip_prefix(key)?.strip_prefix('='))
+    };
+    let (Some(pid), Some(host)) = (field("pid"), field("host")) else {
+        return false;
+    };
+    if host != hostname() {
+        return false;
+    }
+    let Ok(pid) = pid.parse::<u32>() else {
+        return false;
+    };
+    !process_alive(pid)
+}
+
+#[cfg(unix)]
+pub(super) fn process_alive(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return true;
+    };
+    let rc = unsafe { libc::kill(pid, 0) };
+    rc == 0 || io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+}
+
+#[cfg(windows)]
+pub(super) fn process_alive(_pid: u32) -> bool {
+    // No cheap liveness probe without extra APIs; rely on the TTL.
+    true
+}
+
+#[cfg(unix)]
+fn hostname() -> String {
+    let mut buf = [0u8; 256];
+    let rc = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) };
+    if rc != 0 {
+        return "unknown".into();
+    }
+    let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8_lossy(&buf[..end]).into_owned()
+}
+
+#[cfg(windows)]
+fn hostname() -> String {
+    std::env::var("COMPUTERNAME").unwrap_or_else(|_| "unknown".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use filetime::{FileTime, set_file_mtime};
+    use tempfile::tempdir;
+
+    #[test]
+    fn exclusive_and_released_on_drop() {
+        let dir = tempdir().unwrap();
+        let first = try_acquire(dir.path()).unwrap().expect("first");
+        assert!(try_acquire(dir.path()).unwrap().is_none());
+        drop(first);
+        assert!(!dir.path().join(LOCKFILE_NAME).exists());
+        assert!(try_acquire(dir.path()).unwrap().is_some());
+    }
+
+    #[test]
+    fn expired_lockfile_is_taken_over() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(LOCKFILE_NAME);
+        fs::write(&path, "pid=1 host=elsewhere nonce=0\n").unwrap();
+        let old = SystemTime::now() - LOCKFILE_TTL - Duration::from_secs(5);
+        set_file_mtime(&path, FileTime::from_system_time(old)).unwrap();
+        let g = try_acquire(dir.path())
+            .unwrap()
+            .expect("stale lock cleared");
+        assert_eq!(read_token(&path).as_deref(), Some(g.token.as_str()));
+    }
+
+    #[test]
+    fn fresh_lockfile_of_other_host_is_respected() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join(LOCKFILE_NAME),
+            "pid=1 host=elsewhere nonce=0\n",
+        )
+        .unwrap();
+        assert!(try_acquire(dir.path()).unwrap().is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn dead_local_owner_is_taken_over() {
+        let dir = tempdir().unwrap();
+        // PIDs are capped well below i32::MAX on real systems, so this one cannot exist.
+        let token = format!("pid={} host={} nonce=0\n", i32::MAX, hostname());
+        fs::write(dir.path().join(LOCKFILE_NAME), token).unwrap();
+        assert!(try_acquire(dir.path()).unwrap().is_some());
+    }
+}
@@ -2,6 +2,8 @@
 //! - Copies timestamps (atime, mtime) and, on Unix, permissions (mode) from source->dest.
 //! - Best-effort: failures to set times/perms are ignored (function returns Ok(())).
 //! - Callers decide whether to treat failures as fatal; this helper itself does not.
+//! - `preserve_acls` copies POSIX ACLs (Linux `system.posix_acl_*` xattrs, without the
+//!   xattrs feature); `has_default_acl` tells whether new entries in a directory inherit one.
 
 use anyhow::Result;
 use filetime::{FileTime, set_file_times};
@@ -174,3 +176,107 @@ pub fn preserve_xattrs(src: &Path, dest: &Path) -> Result<()> {
         Ok(())
     }
 }
+
+/// POSIX ACL xattrs copied by `preserve_acls` (the default ACL only exists on directories).
+#[cfg(target_os = "linux")]
+const ACL_XATTRS: [&std::ffi::CStr; 2] = [c"system.posix_acl_access", c"system.posix_acl_default"];
+
+/// Copy the POSIX ACLs of `src` onto `dest` (Linux; a no-op elsewhere). Best-effort: failures
+/// are logged. Sources without ACLs leave `dest` unchanged.
+pub fn preserve_acls(src: &Path, dest: &Path) {
+    #[cfg(target_os = "linux")]
+    for name in ACL_XATTRS {
+        match linux_acl::get(src, name) {
+            Ok(Some(value)) => {
+                if let Err(e) = linux_acl::set(dest, name, &value) {
+                    warn!(src = %src.display(), dest = %dest.display(), acl = ?name, error = %e, "failed to set ACL on destination");
+                } else {
+                    trace!(dest = %dest.display(), acl = ?name, "preserved ACL");
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                warn!(src = %src.display(), acl = ?name, error = %e, "failed to read ACL from source");
+            }
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = (src, dest);
+}
+
+/// True if `dir` has a default POSIX ACL, i.e. new entries inherit their permissions from it.
+pub fn has_default_acl(dir: &Path) -> bool {
+    #[cfg(target_os = "linux")]
+    return matches!(linux_acl::get(dir, ACL_XATTRS[1]), Ok(Some(_)));
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = dir;
+        false
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux_acl {
+    use std::ffi::{CStr, CString};
+    use std::io;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::Path;
+
+    fn cpath(p: &Path) -> io::Result<CString> {
+        CString::new(p.as_os_str().as_bytes())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path contains a NUL byte"))
+    }
+
+    /// Value of xattr `name` on `path` (not following a final symlink); None if unset or
+    /// unsupported by the filesystem.
+    pub(super) fn get(path: &Path, name: &CStr) -> io::Result<Option<Vec<u8>>> {
+        let p = cpath(path)?;
+        loop {
+            let len =
+                unsafe { libc::lgetxattr(p.as_ptr(), name.as_ptr(), std::ptr::null_mut(), 0) };
+            if len < 0 {
+                let err = io::Error::last_os_error();
+                return match err.raw_os_error() {
+                    Some(libc::ENODATA | libc::EOPNOTSUPP) => Ok(None),
+                    _ => Err(err),
+                };
+            }
+            let mut buf = vec![0u8; len as usize];
+            let got = unsafe {
+                libc::lgetxattr(
+                    p.as_ptr(),
+                    name.as_ptr(),
+                    buf.as_mut_ptr().cast(),
+                    buf.len(),
+                )
+            };
+            if got >= 0 {
+                buf.truncate(got as usize);
+                return Ok(Some(buf));
+            }
+            let err = io::Error::last_os_error();
+            if err.raw_os_error() != Some(libc::ERANGE) {
+                return Err(err);
+            }
+            // Grew between the two calls; ask again.
+        }
+    }
+
+    pub(super) fn set(path: &Path, name: &CStr, value: &[u8]) -> io::Result<()> {
+        let p = cpath(path)?;
+        let rc = unsafe {
+            libc::lsetxattr(
+                p.as_ptr(),
+                name.as_ptr(),
+                value.as_ptr().cast(),
+                value.len(),
+                0,
+            )
+        };
+        if rc == 0 {
+            Ok(())
+        } else {
+            Err(io::Error::last_os_error())
+        }
+    }
+}
@@ -0,0 +1,226 @@
+//! One-way mirror of download_base into completed_base (`aria_move mirror`).
+//! - Every stable file under download_base is copied to the same relative path under
+//!   completed_base unless the copy there is already up to date (same size, not older).
+//! - Files are copied with the regular safe copy (temp file, fsync, atomic rename, per
+//!   `copy_backend`), so a reader of completed_base never sees a half-written file.
+//! - With `prune`, items in completed_base that no longer exist in download_base are removed.
+//! - `MirrorReport` lists what was done (serializable, for `mirror`'s JSON output).
+//!
+//! Notes:
+//! - Nothing in download_base is ever changed; mirror is a copy-only sync.
+//! - "Stable" means what `--sweep` means: not partial (.part/.aria2/...), no aria2 control
+//!   file, and not modified within `recent_window`. Unstable files wait for a later pass.
+//! - Hidden, internal (`.aria_move.*`) and `.ariamoveignore`d paths are neither copied nor
+//!   pruned, and symlinks are skipped.
+//! - Prune refuses to run when download_base holds nothing at all (an unmounted share must
+//!   not empty completed_base).
+//! - With `dry_run` nothing is changed; the report lists what would be done.
+
+use anyhow::{Result, bail};
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+use walkdir::WalkDir;
+
+use crate::config::types::Config;
+use crate::shutdown;
+use crate::utils::skip_by_hidden_policy;
+
+use super::copy::copy_with_backend;
+use super::ignore::IgnoreRules;
+use super::lock::lock_error;
+use super::path_lock::acquire_path_lock_with;
+use super::remote::is_remote;
+use super::sweep::{is_leftover, recent_cutoff};
+
+/// A path that could not be mirrored or pruned.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MirrorFailure {
+    pub path: PathBuf,
+    pub error: String,
+}
+
+/// Result of one `mirror_download_base` pass. Paths are relative to the bases.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct MirrorReport {
+    pub dry_run: bool,
+    /// Files copied (or that would be copied) into completed_base.
+    pub copied: Vec<PathBuf>,
+    /// Number of files already up to date in completed_base.
+    pub unchanged: usize,
+    /// Files not copied yet because they are partial or recently modified.
+    pub pending: Vec<PathBuf>,
+    /// Items removed (or that would be removed) from completed_base.
+    pub pruned: Vec<PathBuf>,
+    pub failed: Vec<MirrorFailure>,
+}
+
+impl MirrorReport {
+    fn fail(&mut self, path: &Path, error: impl std::fmt::Display) {
+        warn!(event = "mirror_failed", path = %path.display(), error = %error, "Mirror failed");
+        self.failed.push(MirrorFailure {
+            path: path.to_path_buf(),
+            error: error.to_string(),
+        });
+    }
+}
+
+/// Copy new and changed stable files from download_base into completed_base; with `prune`,
+/// also remove what no longer exists in download_base.
+pub fn mirror_download_base(config: &Config, prune: bool) -> Result<MirrorReport> {
+    if is_remote(&config.completed_base) {
+        bail!("mirror needs a local completed_base");
+    }
+    let src_base = &config.download_base;
+    let dst_base = &config.completed_base;
+    let cutoff = recent_cutoff(config);
+    let ignore = IgnoreRules::load(src_base);
+    let mut report = MirrorReport {
+        dry_run: config.dry_run,
+        ..Default::default()
+    };
+
+    let mut seen_any = false;
+    let walker = WalkDir::new(src_base)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            !skip_by_hidden_policy(e.file_name(), config.include_hidden)
+                && !ignore.is_ignored(src_base, e.path(), e.file_type().is_dir())
+        });
+    for entry in walker {
+        if shutdown::is_requested() {
+            return Ok(report);
+        }
+        let entry = match entry {
+            Ok(e) => e,
+            Err(e) => {
+                let path = e.path().unwrap_or(src_base).to_path_buf();
+                report.fail(&path, e);
+                continue;
+            }
+        };
+        seen_any = true;
+        let Ok(rel) = entry.path().strip_prefix(src_base) else {
+            continue;
+        };
+        let dest = dst_base.join(rel);
+        if entry.file_type().is_dir() {
+            if !config.dry_run
+                && let Err(e) = super::dest_dir::create_dest_dir(config, &dest, Some(entry.path()))
+            {
+                report.fail(&dest, e);
+            }
+            continue;
+        }
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        if !is_leftover(config, entry.path(), cutoff) {
+            debug!(path = %entry.path().display(), "mirror: not stable yet");
+            report.pending.push(rel.to_path_buf());
+            continue;
+        }
+        if up_to_date(entry.path(), &dest) {
+            report.unchanged += 1;
+            continue;
+        }
+        if !config.dry_run
+            && let Err(e) = mirror_file(config, entry.path(), &dest)
+        {
+            report.fail(&dest, format!("{e:#}"));
+            continue;
+        }
+        info!(event = "mirror_copy", src = %entry.path().display(), dest = %dest.display(), dry_run = config.dry_run, "Mirrored file");
+        report.copied.push(rel.to_path_buf());
+    }
+
+    if prune {
+        if seen_any {
+            prune_removed(config, &ignore, &mut report);
+        } else {
+            warn!(base = %src_base.display(), "download_base is empty; not pruning (unmounted?)");
+        }
+    }
+    Ok(report)
+}
+
+/// True if `dest` is a file of the same size as `src` and not older than it.
+fn up_to_date(src: &Path, dest: &Path) -> bool {
+    let (Ok(s), Ok(d)) = (fs::metadata(src), fs::metadata(dest)) else {
+        return false;
+    };
+    d.is_file()
+        && d.len() == s.len()
+        && matches!((s.modified(), d.modified()), (Ok(sm), Ok(dm)) if dm >= sm)
+}
+
+/// Copy one file into place under its destination lock.
+fn mirror_file(config: &Config, src: &Path, dest: &Path) -> Result<()> {
+    let disable_locks = config.disable_locks
+        || std::env::var("ARIA_MOVE_DISABLE_LOCKS").ok().as_deref() == Some("1");
+    let _lock = if disable_locks {
+        None
+    } else {
+        match acquire_path_lock_with(dest, config.lock_strategy) {
+            Ok(l) => Some(l),
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => None,
+            Err(e) => return Err(lock_error(dest, e)),
+        }
+    };
+    copy_with_backend(config, src, dest)
+}
+
+/// Remove items under completed_base whose counterpart in download_base is gone.
+fn prune_removed(config: &Config, ignore: &IgnoreRules, report: &mut MirrorReport) {
+    let src_base = &config.download_base;
+    let dst_base = &config.completed_base;
+    let mut walker = WalkDir::new(dst_base)
+        .min_depth(1)
+        .sort_by_file_name()
+        .into_iter();
+    while let Some(entry) = walker.next() {
+        if shutdown::is_requested() {
+            return;
+        }
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let is_dir = entry.file_type().is_dir();
+        let Ok(rel) = entry.path().strip_prefix(dst_base) else {
+            continue;
+        };
+        let src = src_base.join(rel);
+        if skip_by_hidden_policy(entry.file_name(), config.include_hidden)
+            || ignore.is_ignored(src_base, &src, is_dir)
+        {
+            if is_dir {
+                walker.skip_current_dir();
+            }
+            continue;
+        }
+        if fs::symlink_metadata(&src).is_ok() {
+            continue;
+        }
+        if is_dir {
+            walker.skip_current_dir();
+        }
+        let removed = if config.dry_run {
+            Ok(())
+        } else if is_dir {
+            fs::remove_dir_all(entry.path())
+        } else {
+            fs::remove_file(entry.path())
+        };
+        match removed {
+            Ok(()) => {
+                info!(event = "mirror_prune", path = %entry.path().display(), dry_run = config.dry_run, "Pruned item no longer in download_base");
+                report.pruned.push(rel.to_path_buf());
+            }
+            Err(e) => report.fail(entry.path(), e),
+        }
+    }
+}
@@ -6,38 +6,113 @@
 //!
 //! Keep this file minimal to avoid name clashes and duplication. Add new public
 //! operations by re-exporting them here, not by implementing them inline.
+//! The stable surface for library users is `crate::api`; low-level items are re-exported
+//! only with the `unstable` feature.
 
 //
 // Internal implementation modules (crate-private)
 //
 mod atomic;
+mod audit;
+mod bench;
+mod beneath;
 mod claim;
+mod clean;
 mod copy;
+mod dest_dir;
+mod diagnostics;
+mod digest;
+mod dir_merge;
 mod dir_move;
 mod duplicate;
+mod engine;
 mod entry;
 mod file_move;
+mod fs_trace;
+mod handlers;
 mod helpers;
+mod ignore;
 mod io_copy;
 mod lock;
+mod lock_registry;
+mod lockfile;
 mod metadata;
+mod mirror;
+mod on_full;
+mod path_lock;
+mod plan;
+mod progress;
+mod provenance;
+mod rclone;
+mod redownload;
+mod remote;
 mod resolve;
+mod rsync;
+mod sanity;
+mod source_guard;
 mod space;
+mod sweep;
+mod symlink;
+mod timing;
+mod undo;
 mod util;
 
 //
 // Public API (re-exported)
 //
-pub use atomic::{MoveOutcome, try_atomic_move}; // exposed for targeted tests & outcome usage
-pub use copy::{safe_copy_and_rename, safe_copy_and_rename_with_metadata};
-pub use dir_move::move_dir;
+pub use crate::fsys::same_filesystem; // plan instant rename vs. copy up front
+pub use audit::{AuditEntry, AuditReport, audit_history, verify_audit_log};
+pub use bench::{BenchReport, CopyMethod, MethodResult, bench_dest, save_bench};
+pub use clean::{CleanAction, CleanFailure, CleanKind, CleanReport, clean_bases, reconcile};
+pub use copy::{
+    safe_copy_and_rename, safe_copy_and_rename_beneath, safe_copy_and_rename_with_metadata,
+};
+pub use dir_move::{move_dir, move_dir_with_observer};
 pub use duplicate::{OnDuplicate, resolve_destination};
-pub use entry::move_entry;
+pub use engine::{CopyEngine, EngineCapabilities, EngineRegistry};
+pub use entry::{MoveOptions, move_entry, move_entry_with};
 pub use file_move::move_file;
+pub use fs_trace::set_trace_fs_ops;
+pub use handlers::{Handler, HandlerAction};
 pub use helpers::{io_error_with_help, io_error_with_help_io};
+pub use ignore::{IGNORE_FILE_NAME, IgnoreRules};
 pub use metadata::{preserve_metadata, preserve_xattrs};
-pub use resolve::resolve_source_path;
-pub use util::resume_temp_path; // expose for tests (deterministic resume temp naming)
+pub use mirror::{MirrorFailure, MirrorReport, mirror_download_base};
+pub use on_full::{OnFull, move_entry_on_full};
+pub use plan::{EntryKind, MovePlan, PlannedLock, TransferStrategy, plan_move};
+pub use progress::{DirMoveSummary, DirProgress, LogObserver, NoopObserver, Observer, SkippedItem};
+pub use provenance::{GID_ATTR, MOVED_AT_ATTR, SHA256_ATTR, SRC_ATTR, TagReport, verify_tags};
+pub use rclone::RcloneTarget;
+pub use redownload::{INFOHASH_REGISTRY_NAME, OnRedownload};
+pub use remote::{RemoteTarget, is_remote};
+pub use resolve::{Candidate, Stability, list_candidates, resolve_source_path};
+pub use space::{
+    LOW_SPACE_HYSTERESIS_PERCENT, LOW_SPACE_MARKER_NAME, SpaceAlert, watch_free_space,
+};
+pub use sweep::{SweepReport, sweep_download_base};
+pub use timing::MoveTimings;
+pub use undo::{UndoReport, undo_last_move};
 
-// Locking API (currently considered advanced; subject to change)
-pub use lock::{DirLock, acquire_dir_lock, acquire_move_lock, try_acquire_dir_lock};
+// Crate-internal (the stable facade reports timings through it).
+pub(crate) use on_full::move_entry_timed_on_full;
+// Crate-internal: name-length budgeting shared with `utils::unique_destination`.
+pub(crate) use duplicate::{build_name_with_suffix, fit_name};
+
+//
+// Unstable API (feature "unstable"): no semver guarantees, may change in any release.
+//
+#[cfg(feature = "unstable")]
+pub use atomic::{MoveOutcome, try_atomic_move};
+#[cfg(feature = "unstable")]
+pub use lock::{
+    DirLock, acquire_dir_lock, acquire_dir_lock_with, acquire_move_lock, acquire_move_lock_with,
+    effective_lock_strategy, try_acquire_dir_lock, try_acquire_dir_lock_with,
+};
+#[cfg(feature = "unstable")]
+pub use lock_registry::LockManager;
+#[cfg(feature = "unstable")]
+pub use lockfile::{LOCKFILE_NAME, LOCKFILE_TTL};
+#[cfg(feature = "unstable")]
+pub use path_lock::{PathLock, acquire_path_lock_with, try_acquire_path_lock_with};
+#[cfg(feature = "unstable")]
+pub use util::resume_temp_path; // deterministic resume temp naming
@@ -0,0 +1,272 @@
+//! What to do when completed_base is full (`on_full`), applied around `move_entry` by the
+//! callers that move whole items (CLI, `api::Mover`, sweeps).
+//! - `fail` (default): return `InsufficientSpace` as before.
+//! - `pause-and-retry`: wait and retry with exponential backoff until the move fits, for at
+//!   most `on_full_max_wait`.
+//! - `spill`: retry with each of `overflow_bases` in order as the destination.
+//!
+//! Notes:
+//! - "Full" means `InsufficientSpace` from the pre-flight check, or an I/O error of kind
+//!   `StorageFull` during the copy (the partial copy is cleaned up by the mover).
+//! - Waiting stops early on shutdown (Ctrl-C / SIGTERM) with `Interrupted`.
+//! - Remote destinations are passed through unchanged (the uploader reports its own errors).
+
+use anyhow::Result;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::config::types::Config;
+use crate::errors::AriaMoveError;
+use crate::shutdown;
+
+use super::entry::{MoveOptions, move_entry_timed};
+use super::remote::is_remote;
+use super::timing::MoveTimings;
+
+/// First wait of `pause-and-retry`; doubled after every attempt.
+const FIRST_BACKOFF: Duration = Duration::from_secs(5);
+/// Longest single wait of `pause-and-retry`.
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// Policy when the destination runs out of space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnFull {
+    /// Fail with `InsufficientSpace`
+    #[default]
+    Fail,
+    /// Wait with backoff and retry until space frees (bounded by `on_full_max_wait`)
+    PauseAndRetry,
+    /// Move into the next `overflow_bases` entry instead
+    Spill,
+}
+
+impl OnFull {
+    /// Parse common string names into a policy (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "fail" | "error" => Some(OnFull::Fail),
+            "pause-and-retry" | "pause_and_retry" | "pause" | "retry" | "wait" => {
+                Some(OnFull::PauseAndRetry)
+            }
+            "spill" | "overflow" => Some(OnFull::Spill),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for OnFull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            OnFull::Fail => "fail",
+            OnFull::PauseAndRetry => "pause-and-retry",
+            OnFull::Spill => "spill",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for OnFull {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("invalid on_full policy: '{s}'"))
+    }
+}
+
+/// True if `err` means the destination filesystem is out of space.
+pub(super) fn is_full(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<AriaMoveError>(),
+            Some(AriaMoveError::InsufficientSpace { .. })
+        ) || cause
+            .downcast_ref::<io::Error>()
+            .is_some_and(|e| e.kind() == io::ErrorKind::StorageFull)
+    })
+}
+
+/// `move_entry_with` under `Config::on_full`.
+pub fn move_entry_on_full(config: &Config, src: &Path, opts: &MoveOptions) -> Result<PathBuf> {
+    move_entry_timed_on_full(config, src, opts).map(|(dest, _)| dest)
+}
+
+/// `move_entry_timed` under `Config::on_full`.
+pub(crate) fn move_entry_timed_on_full(
+    config: &Config,
+    src: &Path,
+    opts: &MoveOptions,
+) -> Result<(PathBuf, MoveTimings)> {
+    with_policy(config, FIRST_BACKOFF, |cfg| {
+        move_entry_timed(cfg, src, opts)
+    })
+}
+
+/// Run `attempt` (a move into `cfg.completed_base`) under `config.on_full`.
+fn with_policy<T>(
+    config: &Config,
+    first_backoff: Duration,
+    mut attempt: impl FnMut(&Config) -> Result<T>,
+) -> Result<T> {
+    let first = attempt(config);
+    let err = match first {
+        Err(e) if is_full(&e) && !config.dry_run && !is_remote(&config.completed_base) => e,
+        other => return other,
+    };
+    match config.on_full {
+        OnFull::Fail => Err(err),
+        OnFull::Spill => spill(config, err, attempt),
+        OnFull::PauseAndRetry => pause_and_retry(config, err, first_backoff, attempt),
+    }
+}
+
+/// Try each overflow base in order until one has room.
+fn spill<T>(
+    config: &Config,
+    mut err: anyhow::Error,
+    mut attempt: impl FnMut(&Config) -> Result<T>,
+) -> Result<T> {
+    for base in &config.overflow_bases {
+        warn!(full = %config.completed_base.display(), spill_to = %base.display(), "destination full; spilling");
+        let mut cfg = config.clone();
+        cfg.completed_base = base.clone();
+        match attempt(&cfg) {
+            Err(e) if is_full(&e) => err = e,
+            other => return other,
+        }
+    }
+    if config.overflow_bases.is_empty() {
+        warn!("on_full = spill but no overflow_bases are configured");
+    }
+    Err(err)
+}
+
+/// Retry with exponential backoff until the attempt stops failing for space, the total
+/// wait reaches `on_full_max_wait`, or shutdown is requested.
+fn pause_and_retry<T>(
+    config: &Config,
+    mut err: anyhow::Error,
+    first_backoff: Duration,
+    mut attempt: impl FnMut(&Config) -> Result<T>,
+) -> Result<T> {
+    let deadline = Instant::now() + config.on_full_max_wait;
+    let mut backoff = first_backoff;
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(err);
+        }
+        let wait = backoff.min(deadline - now);
+        warn!(dest = %config.completed_base.display(), wait_secs = wait.as_secs_f64(), "destination full; waiting for space");
+        let until = now + wait;
+        while Instant::now() < until {
+            if shutdown::is_requested() {
+                return Err(AriaMoveError::Interrupted.into());
+            }
+            std::thread::sleep((until - Instant::now()).min(Duration::from_millis(250)));
+        }
+        match attempt(config) {
+            Err(e) if is_full(&e) => err = e,
+            other => {
+                if other.is_ok() {
+                    info!(dest = %config.completed_base.display(), "space freed; move completed");
+                }
+                return other;
+            }
+        }
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use std::cell::Cell;
+
+    fn full(dest: &Path) -> anyhow::Error {
+        AriaMoveError::InsufficientSpace {
+            required: 10,
+            available: 1,
+            dest: dest.to_path_buf(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn parse_round_trips_display() {
+        for p in [OnFull::Fail, OnFull::PauseAndRetry, OnFull::Spill] {
+            assert_eq!(OnFull::parse(&p.to_string()), Some(p));
+        }
+        assert!("later".parse::<OnFull>().is_err());
+    }
+
+    #[test]
+    fn storage_full_io_errors_count_as_full() {
+        let e = anyhow::Error::from(io::Error::from(io::ErrorKind::StorageFull)).context("copy");
+        assert!(is_full(&e));
+        assert!(!is_full(&anyhow!("other")));
+    }
+
+    #[test]
+    fn spill_uses_the_first_overflow_base_with_room() {
+        let cfg = Config {
+            completed_base: PathBuf::from("/a"),
+            on_full: OnFull::Spill,
+            overflow_bases: vec![PathBuf::from("/b"), PathBuf::from("/c")],
+            ..Config::default()
+        };
+        let got = with_policy(&cfg, Duration::ZERO, |c| {
+            if c.completed_base == Path::new("/c") {
+                Ok(c.completed_base.clone())
+            } else {
+                Err(full(&c.completed_base))
+            }
+        });
+        assert_eq!(got.unwrap(), PathBuf::from("/c"));
+
+        let fail = Config {
+            on_full: OnFull::Fail,
+            ..cfg
+        };
+        let calls = Cell::new(0);
+        let got: Result<()> = with_policy(&fail, Duration::ZERO, |c| {
+            calls.set(calls.get() + 1);
+            Err(full(&c.completed_base))
+        });
+        assert!(is_full(&got.unwrap_err()));
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn pause_and_retry_waits_until_space_frees_or_time_runs_out() {
+        let cfg = Config {
+            completed_base: PathBuf::from("/a"),
+            on_full: OnFull::PauseAndRetry,
+            on_full_max_wait: Duration::from_secs(5),
+            ..Config::default()
+        };
+        let calls = Cell::new(0);
+        let got = with_policy(&cfg, Duration::from_millis(1), |c| {
+            calls.set(calls.get() + 1);
+            if calls.get() < 3 {
+                Err(full(&c.completed_base))
+            } else {
+                Ok(())
+            }
+        });
+        assert!(got.is_ok());
+        assert_eq!(calls.get(), 3);
+
+        let short = Config {
+            on_full_max_wait: Duration::from_millis(20),
+            ..cfg
+        };
+        let got: Result<()> = with_policy(&short, Duration::from_millis(1), |c| {
+            Err(full(&c.completed_base))
+        });
+        assert!(is_full(&got.unwrap_err()));
+    }
+}
@@ -0,0 +1,348 @@
+//! Per-path advisory locks, so moves of unrelated items run in parallel.
+//! - `acquire_path_lock_with(path, strategy)`: exclusive lock on one path (which need not exist),
+//!   held through a hidden sidecar `.aria_move.<hash>.lock` in its parent directory.
+//! - The movers lock the source path and `completed_base/<name>` (the destination before
+//!   duplicate renaming), so only moves of the same item or into the same name serialize.
+//! - `lock_final_destination`: when the duplicate policy picks another name, that final
+//!   path is locked too, so a move whose own name equals it cannot race for it.
+//!
+//! Notes:
+//! - flock (Unix): the sidecar is created, flocked, and checked to still be the file on disk
+//!   (the previous holder unlinks it on release); Windows opens it without sharing.
+//! - `lockfile` uses the same sidecar name with the O_EXCL protocol from `lockfile.rs`.
+//! - The hash (FNV-1a of the file name) keeps sidecar names short; a collision only
+//!   serializes two unrelated moves.
+//! - `ARIA_MOVE_LOCK_TIMEOUT` bounds the wait exactly like the directory locks.
+//! - Waits between threads of one process go through `LockManager` (see `lock_registry`).
+
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tracing::{trace, warn};
+
+use crate::config::types::LockStrategy;
+use crate::errors::AriaMoveError;
+
+use super::lock::{effective_lock_strategy, lock_error, lock_timeout};
+use super::lock_registry::{Claim, LockManager};
+use super::lockfile::{self, LockFileGuard};
+
+/// RAII guard for a per-path lock; releases (and removes the sidecar) on drop.
+pub struct PathLock {
+    held: Held,
+    // Dropped after `Drop::drop` has released the on-disk lock.
+    _claim: Option<Claim>,
+}
+
+enum Held {
+    Unlocked,
+    File { file: File, sidecar: PathBuf },
+    Lockfile { _guard: LockFileGuard },
+}
+
+impl Drop for PathLock {
+    fn drop(&mut self) {
+        if let Held::File { file, sidecar } = std::mem::replace(&mut self.held, Held::Unlocked) {
+            // Unix: unlink while still holding the flock, so waiters on the old inode retry.
+            #[cfg(unix)]
+            let _ = fs::remove_file(&sidecar);
+            drop(file);
+            // Windows: the exclusive handle must be closed before the file can be deleted.
+            #[cfg(windows)]
+            let _ = fs::remove_file(&sidecar);
+        }
+    }
+}
+
+/// Sidecar lock file guarding `path`.
+pub(super) fn sidecar_path(path: &Path) -> PathBuf {
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let name = path.file_name().unwrap_or_default().as_encoded_bytes();
+    let hash = name.iter().fold(0xcbf2_9ce4_8422_2325u64, |h, &b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    parent.join(format!(".aria_move.{hash:016x}.lock"))
+}
+
+/// True for sidecar names produced by `sidecar_path`.
+pub(super) fn is_sidecar_name(name: &str) -> bool {
+    name.strip_prefix(".aria_move.")
+        .and_then(|n| n.strip_suffix(".lock"))
+        .is_some_and(|h| h.len() == 16 && h.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// Remove a flock sidecar that no process holds (left behind by a crash).
+/// `dry_run` only checks. Returns true if it was (or would be) removed.
+pub(super) fn remove_if_unheld(sidecar: &Path, dry_run: bool) -> io::Result<bool> {
+    let Some(claim) = LockManager::global().try_claim(sidecar) else {
+        return Ok(false);
+    };
+    let file = match try_lock_sidecar(sidecar) {
+        Ok(Some(file)) => file,
+        Ok(None) => return Ok(false),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    if dry_run {
+        return Ok(true);
+    }
+    // Released like any other holder: unlinked under the lock, so waiters retry.
+    drop(PathLock {
+        held: Held::File {
+            file,
+            sidecar: sidecar.to_path_buf(),
+        },
+        _claim: Some(claim),
+    });
+    Ok(true)
+}
+
+/// Blocking acquire of the lock on `path` using `strategy`.
+/// With `ARIA_MOVE_LOCK_TIMEOUT` set, fails with `WouldBlock`/`TimedOut` instead of waiting forever.
+pub fn acquire_path_lock_with(path: &Path, strategy: LockStrategy) -> io::Result<PathLock> {
+    let limit = lock_timeout();
+    let start = Instant::now();
+    let sidecar = sidecar_path(path);
+    let dir = sidecar.parent().unwrap_or_else(|| Path::new("."));
+    let strategy = effective_lock_strategy(dir, strategy);
+    if strategy == LockStrategy::None {
+        return Ok(PathLock {
+            held: Held::Unlocked,
+            _claim: None,
+        });
+    }
+    // Other threads of this process are waited for in-process; only then is the disk polled.
+    let claim = LockManager::global().claim_within(&sidecar, limit)?;
+    let mut next_warn = Duration::from_secs(10);
+    loop {
+        if let Some(held) = try_lock_on_disk(&sidecar, strategy)? {
+            let lock = PathLock {
+                held,
+                _claim: Some(claim),
+            };
+            trace!(path = %path.display(), waited_ms = start.elapsed().as_millis() as u64, "path lock acquired");
+            return Ok(lock);
+        }
+        if limit.is_some_and(|l| l.is_zero()) {
+            return Err(io::Error::new(
+                io::ErrorKind::WouldBlock,
+                format!("lock on {} is held by another process", path.display()),
+            ));
+        }
+        if crate::shutdown::is_requested() {
+            return Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "shutdown requested while waiting for lock",
+            ));
+        }
+        if let Some(limit) = limit
+            && start.elapsed() >= limit
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!(
+                    "lock on {} not acquired within {}s",
+                    path.display(),
+                    limit.as_secs()
+                ),
+            ));
+        }
+        if start.elapsed() >= next_warn {
+            warn!(path = %path.display(), waited_s = start.elapsed().as_secs(), "still waiting for path lock");
+            next_warn += Duration::from_secs(10);
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Resolve the final destination while holding the lock on `key` (`completed_base/<name>`)
+/// and lock it as well if it differs from `key`. `resolve` is re-run until the path it
+/// returns is locked and unchanged, so a name taken meanwhile is never reused.
+pub(super) fn lock_final_destination(
+    key: &Path,
+    strategy: LockStrategy,
+    mut resolve: impl FnMut() -> anyhow::Result<PathBuf>,
+) -> anyhow::Result<(PathBuf, Option<PathLock>)> {
+    loop {
+        let dest = resolve()?;
+        if dest == key {
+            return Ok((dest, None));
+        }
+        let held = try_acquire_path_lock_with(&dest, strategy).map_err(|e| lock_error(&dest, e))?;
+        if let Some(lock) = held {
+            if resolve()? == dest {
+                trace!(key = %key.display(), dest = %dest.display(), "final destination locked");
+                return Ok((dest, Some(lock)));
+            }
+            continue;
+        }
+        if crate::shutdown::is_requested() {
+            return Err(AriaMoveError::Interrupted.into());
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Non-blocking acquire. Ok(None) if another holder (in this or another process) has the lock.
+pub fn try_acquire_path_lock_with(
+    path: &Path,
+    strategy: LockStrategy,
+) -> io::Result<Option<PathLock>> {
+    let sidecar = sidecar_path(path);
+    let dir = sidecar.parent().unwrap_or_else(|| Path::new("."));
+    let strategy = effective_lock_strategy(dir, strategy);
+    if strategy == LockStrategy::None {
+        return Ok(Some(PathLock {
+            held: Held::Unlocked,
+            _claim: None,
+        }));
+    }
+    let Some(claim) = LockManager::global().try_claim(&sidecar) else {
+        return Ok(None);
+    };
+    Ok(try_lock_on_disk(&sidecar, strategy)?.map(|held| PathLock {
+        held,
+        _claim: Some(claim),
+    }))
+}
+
+/// One attempt at the on-disk lock; `strategy` is already resolved (not None).
+fn try_lock_on_disk(sidecar: &Path, strategy: LockStrategy) -> io::Result<Option<Held>> {
+    Ok(match strategy {
+        LockStrategy::None => Some(Held::Unlocked),
+        LockStrategy::Lockfile => {
+            lockfile::try_acquire_at(sidecar.to_path_buf())?.map(|g| Held::Lockfile { _guard: g })
+        }
+        LockStrategy::Flock | LockStrategy::Auto => {
+            try_lock_sidecar(sidecar)?.map(|file| Held::File {
+                file,
+                sidecar: sidecar.to_path_buf(),
+            })
+        }
+    })
+}
+
+#[cfg(unix)]
+fn try_lock_sidecar(sidecar: &Path) -> io::Result<Option<File>> {
+    use std::os::fd::AsRawFd;
+    use std::os::unix::fs::{MetadataExt, OpenOptionsExt};
+
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .mode(0o600)
+        .custom_flags(libc::O_CLOEXEC)
+        .open(sidecar)?;
+    let rc = unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) };
+    if rc != 0 {
+        let err = io::Error::last_os_error();
+        return if err.raw_os_error() == Some(libc::EWOULDBLOCK) {
+            Ok(None)
+        } else {
+            Err(err)
+        };
+    }
+    // The previous holder may have unlinked the sidecar between our open and flock.
+    let ours = file.metadata()?;
+    match fs::metadata(sidecar) {
+        Ok(m) if m.dev() == ours.dev() && m.ino() == ours.ino() => Ok(Some(file)),
+        Ok(_) => Ok(None),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(windows)]
+fn try_lock_sidecar(sidecar: &Path) -> io::Result<Option<File>> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use windows_sys::Win32::Storage::FileSystem::FILE_ATTRIBUTE_HIDDEN;
+
+    const ERROR_SHARING_VIOLATION: i32 = 32;
+    match OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .share_mode(0)
+        .attributes(FILE_ATTRIBUTE_HIDDEN)
+        .open(sidecar)
+    {
+        Ok(file) => Ok(Some(file)),
+        Err(e) if e.raw_os_error() == Some(ERROR_SHARING_VIOLATION) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn same_path_is_exclusive_other_paths_are_not() {
+        let dir = tempdir().unwrap();
+        let a = dir.path().join("a.iso");
+        let b = dir.path().join("b.iso");
+
+        let held = try_acquire_path_lock_with(&a, LockStrategy::Flock)
+            .unwrap()
+            .expect("first lock");
+        assert!(
+            try_acquire_path_lock_with(&a, LockStrategy::Flock)
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            try_acquire_path_lock_with(&b, LockStrategy::Flock)
+                .unwrap()
+                .is_some()
+        );
+        assert!(sidecar_path(&a).exists());
+        assert!(LockManager::global().is_held(&sidecar_path(&a)));
+
+        drop(held);
+        assert!(!sidecar_path(&a).exists());
+        assert!(!LockManager::global().is_held(&sidecar_path(&a)));
+        assert!(
+            try_acquire_path_lock_with(&a, LockStrategy::Lockfile)
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn final_destination_is_locked_when_renamed() {
+        let dir = tempdir().unwrap();
+        let key = dir.path().join("a.iso");
+        let renamed = dir.path().join("a (2).iso");
+
+        let (dest, lock) =
+            lock_final_destination(&key, LockStrategy::Flock, || Ok(key.clone())).unwrap();
+        assert_eq!(dest, key);
+        assert!(lock.is_none());
+
+        let (dest, lock) =
+            lock_final_destination(&key, LockStrategy::Flock, || Ok(renamed.clone())).unwrap();
+        assert_eq!(dest, renamed);
+        assert!(lock.is_some());
+        assert!(
+            try_acquire_path_lock_with(&renamed, LockStrategy::Flock)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn sidecar_names_are_hidden_and_stable() {
+        let p = Path::new("/data/completed/Some Movie (2024)");
+        let s = sidecar_path(p);
+        assert_eq!(s, sidecar_path(p));
+        assert_eq!(s.parent(), p.parent());
+        let name = s.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with(".aria_move.") && name.ends_with(".lock"));
+        assert_ne!(s, sidecar_path(Path::new("/data/completed/other")));
+    }
+}
@@ -0,0 +1,361 @@
+//! Pre-flight plan of a move (`aria_move plan`).
+//! - `plan_move`: what `move_entry` would do for a path, computed without side effects:
+//!   destination after the duplicate policy, rename vs copy, bytes, free space and locks.
+//! - `MovePlan` implements Display as the human-readable report.
+//!
+//! Notes:
+//! - Read-only: no directory is created, no lock is taken, nothing is renamed.
+//! - A plan is a snapshot; a concurrent download or move can change the real outcome.
+//! - Remote destinations are reported as an upload; space and locks are not checked for them.
+
+use anyhow::{Context, Result, anyhow, bail};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config::types::{Config, CopyBackend, LockStrategy, SymlinkPolicy};
+use crate::errors::AriaMoveError;
+use crate::fsys::{existing_ancestor, free_space_bytes, is_symlink, same_filesystem};
+use crate::utils::{ensure_not_base, unique_destination};
+
+use super::dir_move::total_bytes_in_tree;
+use super::duplicate::OnDuplicate;
+use super::entry::{ensure_source_in_base, is_ignored_source};
+use super::lock::effective_lock_strategy;
+use super::remote::is_remote;
+use super::space::{format_bytes, has_space};
+
+/// What kind of entry is moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    File,
+    Directory,
+    /// The link itself (`symlink_source_policy = move-link`).
+    Symlink,
+}
+
+impl fmt::Display for EntryKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            EntryKind::File => "file",
+            EntryKind::Directory => "directory",
+            EntryKind::Symlink => "symlink",
+        };
+        f.write_str(s)
+    }
+}
+
+/// How the data reaches the destination.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferStrategy {
+    /// Same filesystem: an atomic rename, no data is copied.
+    Rename,
+    /// Cross-filesystem: copy with the given backend, then remove the source.
+    Copy(CopyBackend),
+    /// `copy_only`: copy with the given backend and keep the source.
+    CopyOnly(CopyBackend),
+    /// Remote completed_base (sftp/rclone).
+    Upload,
+}
+
+impl fmt::Display for TransferStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransferStrategy::Rename => f.write_str("rename (same filesystem)"),
+            TransferStrategy::Copy(backend) => write!(f, "copy ({backend}) then remove source"),
+            TransferStrategy::CopyOnly(backend) => write!(f, "copy ({backend}), source retained"),
+            TransferStrategy::Upload => f.write_str("upload to remote destination"),
+        }
+    }
+}
+
+/// A per-path lock the move would take.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedLock {
+    /// The locked path (the source, `completed_base/<name>` or the suffixed destination).
+    pub path: PathBuf,
+    /// Strategy after resolving `auto` for the path's directory.
+    pub strategy: LockStrategy,
+}
+
+/// Everything a move would do, computed by `plan_move`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MovePlan {
+    /// The path that would be moved (the link target for `move-target`).
+    pub source: PathBuf,
+    pub kind: EntryKind,
+    /// Final path under completed_base (unique name if the plain one is taken).
+    pub destination: PathBuf,
+    /// True if a directory would be merged into an existing destination directory.
+    pub merge: bool,
+    pub strategy: TransferStrategy,
+    /// Bytes of regular files in the source.
+    pub bytes: u64,
+    /// Free bytes on the destination filesystem (None if unknown or remote).
+    pub free_bytes: Option<u64>,
+    /// Whether the space check would pass (None if it is not performed).
+    pub space_ok: Option<bool>,
+    /// Locks in acquisition order; empty if locking is disabled.
+    pub locks: Vec<PlannedLock>,
+}
+
+impl fmt::Display for MovePlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "source:      {} ({})", self.source.display(), self.kind)?;
+        write!(f, "destination: {}", self.destination.display())?;
+        if self.merge {
+            write!(f, " (merge into existing directory)")?;
+        }
+        writeln!(f)?;
+        writeln!(f, "strategy:    {}", self.strategy)?;
+        writeln!(
+            f,
+            "size:        {} ({} bytes)",
+            format_bytes(self.bytes),
+            self.bytes
+        )?;
+        let free = self
+            .free_bytes
+            .map_or_else(|| "unknown".to_string(), format_bytes);
+        match self.space_ok {
+            Some(true) => writeln!(f, "space:       ok ({free} free)")?,
+            Some(false) => writeln!(f, "space:       INSUFFICIENT ({free} free)")?,
+            None => writeln!(f, "space:       not checked ({free} free)")?,
+        }
+        if self.locks.is_empty() {
+            write!(f, "locks:       none")?;
+        }
+        for (i, lock) in self.locks.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            let label = if i == 0 { "locks:" } else { "" };
+            write!(f, "{label:<13}{} ({})", lock.path.display(), lock.strategy)?;
+        }
+        Ok(())
+    }
+}
+
+/// Plan the move of `src` (file, directory or symlink) without touching anything.
+/// Fails where `move_entry` would fail before doing any work (base, outside base, ignored,
+/// missing, refused symlink).
+pub fn plan_move(config: &Config, src: &Path) -> Result<MovePlan> {
+    ensure_not_base(&config.download_base, src)?;
+    if config.require_source_in_base {
+        ensure_source_in_base(config, src)?;
+    }
+    if is_ignored_source(config, src) {
+        return Err(AriaMoveError::Ignored(src.to_path_buf()).into());
+    }
+    let lmeta = fs::symlink_metadata(src).map_err(|e| {
+        if e.kind() == io::ErrorKind::NotFound {
+            anyhow::Error::from(AriaMoveError::SourceNotFound(src.to_path_buf()))
+        } else {
+            anyhow::Error::from(e)
+        }
+    })?;
+    if !lmeta.file_type().is_symlink() {
+        return plan_for(config, src, &lmeta);
+    }
+    match config.symlink_source_policy {
+        SymlinkPolicy::Error => bail!("Refusing to move symlink: {}", src.display()),
+        SymlinkPolicy::MoveLink => plan_entry(config, src, EntryKind::Symlink, 0),
+        SymlinkPolicy::MoveTarget => {
+            let target = fs::canonicalize(src)
+                .with_context(|| format!("resolve symlink target of {}", src.display()))?;
+            if config.require_source_in_base {
+                ensure_source_in_base(config, &target)?;
+            }
+            let meta =
+                fs::metadata(&target).with_context(|| format!("stat {}", target.display()))?;
+            plan_for(config, &target, &meta)
+        }
+    }
+}
+
+fn plan_for(config: &Config, src: &Path, meta: &fs::Metadata) -> Result<MovePlan> {
+    if meta.is_file() {
+        plan_entry(config, src, EntryKind::File, meta.len())
+    } else if meta.is_dir() {
+        let bytes = total_bytes_in_tree(src).unwrap_or(0);
+        plan_entry(config, src, EntryKind::Directory, bytes)
+    } else {
+        bail!(
+            "Source path is neither a regular file nor a directory: {}",
+            src.display()
+        )
+    }
+}
+
+fn plan_entry(config: &Config, src: &Path, kind: EntryKind, bytes: u64) -> Result<MovePlan> {
+    let name = src
+        .file_name()
+        .ok_or_else(|| anyhow!("Source path missing a file name: {}", src.display()))?;
+    let dest_dir = &config.completed_base;
+    let mut plan = MovePlan {
+        source: src.to_path_buf(),
+        kind,
+        destination: dest_dir.join(super::fit_name(dest_dir, name)),
+        merge: false,
+        strategy: TransferStrategy::Upload,
+        bytes,
+        free_bytes: None,
+        space_ok: None,
+        locks: Vec::new(),
+    };
+    if is_remote(dest_dir) {
+        return Ok(plan);
+    }
+
+    plan.merge = kind == EntryKind::Directory
+        && plan.destination.is_dir()
+        && config.dir_on_duplicate == OnDuplicate::Merge;
+    if !plan.merge && (plan.destination.exists() || is_symlink(&plan.destination)) {
+        plan.destination = unique_destination(&plan.destination);
+    }
+
+    let same_fs = src
+        .parent()
+        .is_some_and(|p| matches!(same_filesystem(p, dest_dir), Ok(true)));
+    // overlayfs/FUSE: the movers copy (and verify) instead of renaming.
+    let rename_quirk = src
+        .parent()
+        .and_then(crate::fsys::rename_quirk)
+        .or_else(|| crate::fsys::rename_quirk(dest_dir))
+        .is_some();
+    plan.strategy = if config.copy_only {
+        TransferStrategy::CopyOnly(config.copy_backend)
+    } else if same_fs && !config.force_copy && !rename_quirk {
+        TransferStrategy::Rename
+    } else {
+        TransferStrategy::Copy(config.copy_backend)
+    };
+    plan.free_bytes = existing_ancestor(dest_dir).and_then(free_space_bytes).ok();
+    // A rename needs no space; a copy needs the bytes plus the usual cushion.
+    plan.space_ok = if plan.strategy == TransferStrategy::Rename {
+        Some(true)
+    } else {
+        plan.free_bytes
+            .map(|free| has_space(free, bytes, config.space_cushion))
+    };
+    plan.locks = planned_locks(config, src, kind, &plan.destination);
+    Ok(plan)
+}
+
+/// The source (not for move-link), `completed_base/<name>`, then the final destination if it
+/// differs, as the movers acquire them.
+fn planned_locks(config: &Config, src: &Path, kind: EntryKind, dest: &Path) -> Vec<PlannedLock> {
+    let disable_locks = config.disable_locks
+        || std::env::var("ARIA_MOVE_DISABLE_LOCKS").ok().as_deref() == Some("1");
+    if disable_locks {
+        return Vec::new();
+    }
+    let name = src.file_name().unwrap_or_default();
+    let mut paths = Vec::new();
+    if kind != EntryKind::Symlink {
+        paths.push(src.to_path_buf());
+    }
+    let key = config
+        .completed_base
+        .join(super::fit_name(&config.completed_base, name));
+    let suffixed = kind != EntryKind::Symlink && dest != key;
+    paths.push(key);
+    if suffixed {
+        paths.push(dest.to_path_buf());
+    }
+    paths
+        .into_iter()
+        .map(|path| {
+            let dir = path.parent().unwrap_or_else(|| Path::new("."));
+            let strategy = effective_lock_strategy(dir, config.lock_strategy);
+            PlannedLock { path, strategy }
+        })
+        .filter(|lock| lock.strategy != LockStrategy::None)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn config(root: &Path) -> Config {
+        let download_base = root.join("incoming");
+        let completed_base = root.join("completed");
+        fs::create_dir_all(&download_base).unwrap();
+        fs::create_dir_all(&completed_base).unwrap();
+        Config {
+            download_base,
+            completed_base,
+            lock_strategy: LockStrategy::Flock,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn file_plan_is_a_rename_and_touches_nothing() {
+        let td = tempdir().unwrap();
+        let cfg = config(td.path());
+        let src = cfg.download_base.join("a.bin");
+        fs::write(&src, b"12345").unwrap();
+
+        let plan = plan_move(&cfg, &src).unwrap();
+        assert_eq!(plan.kind, EntryKind::File);
+        assert_eq!(plan.destination, cfg.completed_base.join("a.bin"));
+        assert_eq!(plan.strategy, TransferStrategy::Rename);
+        assert_eq!(plan.bytes, 5);
+        assert_eq!(plan.space_ok, Some(true));
+        let locked: Vec<_> = plan.locks.iter().map(|l| l.path.clone()).collect();
+        assert_eq!(locked, vec![src.clone(), cfg.completed_base.join("a.bin")]);
+
+        assert!(src.exists());
+        assert!(!plan.destination.exists());
+        assert!(plan.to_string().contains("rename"));
+    }
+
+    #[test]
+    fn taken_names_get_a_unique_destination_unless_merging() {
+        let td = tempdir().unwrap();
+        let mut cfg = config(td.path());
+        let src = cfg.download_base.join("show");
+        fs::create_dir_all(&src).unwrap();
+        fs::write(src.join("e1.mkv"), b"xx").unwrap();
+        fs::create_dir_all(cfg.completed_base.join("show")).unwrap();
+
+        let plan = plan_move(&cfg, &src).unwrap();
+        assert!(!plan.merge);
+        assert_ne!(plan.destination, cfg.completed_base.join("show"));
+        assert_eq!(plan.bytes, 2);
+        let locked: Vec<_> = plan.locks.iter().map(|l| l.path.clone()).collect();
+        assert_eq!(
+            locked,
+            vec![
+                src.clone(),
+                cfg.completed_base.join("show"),
+                plan.destination.clone()
+            ]
+        );
+
+        cfg.dir_on_duplicate = OnDuplicate::Merge;
+        let plan = plan_move(&cfg, &src).unwrap();
+        assert!(plan.merge);
+        assert_eq!(plan.destination, cfg.completed_base.join("show"));
+    }
+
+    #[test]
+    fn disabled_locks_and_missing_sources() {
+        let td = tempdir().unwrap();
+        let mut cfg = config(td.path());
+        cfg.disable_locks = true;
+        let src = cfg.download_base.join("a.bin");
+        fs::write(&src, b"x").unwrap();
+        assert!(plan_move(&cfg, &src).unwrap().locks.is_empty());
+
+        let err = plan_move(&cfg, &cfg.download_base.join("missing")).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AriaMoveError>(),
+            Some(AriaMoveError::SourceNotFound(_))
+        ));
+    }
+}
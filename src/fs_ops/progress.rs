@@ -0,0 +1,229 @@
+//! Progress reporting for long-running directory moves.
+//!
+//! - `Observer`: callback trait for library consumers (UIs, wrappers) that want progress events.
+//! - `LogObserver`: default observer; emits throttled tracing events (visible in `--json` output).
+//! - `ProgressTracker`: internal, thread-safe aggregator shared by the parallel copy workers.
+//! - `DirMoveSummary`: end-of-move report for partial directory moves (`partial_dir_moves`).
+//!
+//! Notes:
+//! - Progress is only reported for the copy fallback; an atomic rename completes in one step.
+//! - ETA is a simple linear estimate from aggregate throughput so far (bytes/elapsed).
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Snapshot of directory copy progress.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirProgress {
+    /// Files fully copied so far.
+    pub files_done: u64,
+    /// Total regular files scheduled for copy.
+    pub files_total: u64,
+    /// Bytes copied so far (sum of completed files).
+    pub bytes_done: u64,
+    /// Total bytes scheduled for copy (best-effort estimate).
+    pub bytes_total: u64,
+    /// Time since the copy started.
+    pub elapsed: Duration,
+    /// Estimated time remaining; None until some bytes have been copied.
+    pub eta: Option<Duration>,
+}
+
+impl DirProgress {
+    /// True once every scheduled file has been copied.
+    pub fn is_complete(&self) -> bool {
+        self.files_done >= self.files_total
+    }
+}
+
+/// A file left behind by a partial directory move, with the reason it was skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedItem {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// Outcome of a directory move run with `partial_dir_moves` enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirMoveSummary {
+    /// Source directory (still present if anything was skipped).
+    pub src: PathBuf,
+    /// Destination directory (kept even when some files were skipped).
+    pub dest: PathBuf,
+    /// Files copied and removed from the source.
+    pub files_moved: u64,
+    /// Files left in place, sorted by path.
+    pub skipped: Vec<SkippedItem>,
+}
+
+/// Receives progress events from move operations. Implementations must be cheap and
+/// thread-safe: events are delivered from the parallel copy workers.
+pub trait Observer: Send + Sync {
+    /// Called after each file of a directory copy completes.
+    fn on_dir_progress(&self, _progress: &DirProgress) {}
+
+    /// Called once when a partial directory move finishes (see `Config::partial_dir_moves`).
+    fn on_dir_summary(&self, _summary: &DirMoveSummary) {}
+}
+
+/// No-op observer for callers that do not care about progress.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopObserver;
+
+impl Observer for NoopObserver {}
+
+/// Default observer: logs progress through tracing at most once per `interval`
+/// (plus a final event on completion). With `--json`, these become JSON events.
+#[derive(Debug)]
+pub struct LogObserver {
+    interval: Duration,
+    last: Mutex<Option<Instant>>,
+}
+
+impl LogObserver {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last: Mutex::new(None),
+        }
+    }
+}
+
+impl Default for LogObserver {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(1))
+    }
+}
+
+impl Observer for LogObserver {
+    fn on_dir_progress(&self, p: &DirProgress) {
+        let now = Instant::now();
+        if let Ok(mut last) = self.last.lock() {
+            let due = last.is_none_or(|t| now.duration_since(t) >= self.interval);
+            if !due && !p.is_complete() {
+                return;
+            }
+            *last = Some(now);
+        }
+        info!(
+            event = "dir_progress",
+            files_done = p.files_done,
+            files_total = p.files_total,
+            bytes_done = p.bytes_done,
+            bytes_total = p.bytes_total,
+            elapsed_ms = p.elapsed.as_millis() as u64,
+            eta_secs = p.eta.map(|d| d.as_secs()),
+            "Directory copy progress"
+        );
+    }
+
+    fn on_dir_summary(&self, s: &DirMoveSummary) {
+        for item in &s.skipped {
+            warn!(
+                event = "dir_skipped",
+                path = %item.path.display(),
+                reason = %item.reason,
+                "Left file in place"
+            );
+        }
+        info!(
+            event = "dir_summary",
+            src = %s.src.display(),
+            dest = %s.dest.display(),
+            files_moved = s.files_moved,
+            files_skipped = s.skipped.len() as u64,
+            "Directory move summary"
+        );
+    }
+}
+
+/// Shared counters for a single directory copy.
+pub(super) struct ProgressTracker<'a> {
+    files_total: u64,
+    bytes_total: u64,
+    files_done: AtomicU64,
+    bytes_done: AtomicU64,
+    start: Instant,
+    observer: &'a dyn Observer,
+}
+
+impl<'a> ProgressTracker<'a> {
+    pub(super) fn new(files_total: u64, bytes_total: u64, observer: &'a dyn Observer) -> Self {
+        Self {
+            files_total,
+            bytes_total,
+            files_done: AtomicU64::new(0),
+            bytes_done: AtomicU64::new(0),
+            start: Instant::now(),
+            observer,
+        }
+    }
+
+    /// Record one completed file of `bytes` and notify the observer.
+    pub(super) fn file_done(&self, bytes: u64) {
+        let files_done = self.files_done.fetch_add(1, Ordering::Relaxed) + 1;
+        let bytes_done = self.bytes_done.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        let elapsed = self.start.elapsed();
+        let p = DirProgress {
+            files_done,
+            files_total: self.files_total,
+            bytes_done,
+            bytes_total: self.bytes_total,
+            elapsed,
+            eta: estimate_eta(bytes_done, self.bytes_total, elapsed),
+        };
+        self.observer.on_dir_progress(&p);
+    }
+}
+
+/// Linear ETA from throughput so far. None until at least one byte was copied.
+fn estimate_eta(done: u64, total: u64, elapsed: Duration) -> Option<Duration> {
+    if done == 0 {
+        return None;
+    }
+    let remaining = total.saturating_sub(done);
+    let secs = elapsed.as_secs_f64() * (remaining as f64) / (done as f64);
+    Some(Duration::from_secs_f64(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<DirProgress>>);
+    impl Observer for Recorder {
+        fn on_dir_progress(&self, p: &DirProgress) {
+            self.0.lock().unwrap().push(*p);
+        }
+    }
+
+    #[test]
+    fn eta_is_linear() {
+        assert_eq!(estimate_eta(0, 100, Duration::from_secs(5)), None);
+        assert_eq!(
+            estimate_eta(50, 100, Duration::from_secs(10)),
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(
+            estimate_eta(100, 100, Duration::from_secs(10)),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn tracker_counts_files_and_bytes() {
+        let rec = Recorder::default();
+        let t = ProgressTracker::new(2, 30, &rec);
+        t.file_done(10);
+        t.file_done(20);
+        let events = rec.0.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].files_done, 2);
+        assert_eq!(events[1].bytes_done, 30);
+        assert!(events[1].is_complete());
+    }
+}
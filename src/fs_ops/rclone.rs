@@ -0,0 +1,333 @@
+//! rclone destination backend: `completed_base = rclone:remote:path`.
+//! - aria_move still resolves the source, probes stability, takes the source lock and removes
+//!   the source; only the data transfer is delegated to an `rclone` subprocess.
+//! - Files use `rclone copyto`, directories `rclone copy`, into `remote:path/<name>`.
+//! - rclone runs with `--use-json-log`; stats lines are logged as progress and error lines are
+//!   collected into the returned error.
+//! - The local source is removed only after rclone exits successfully (rclone verifies sizes
+//!   and, where the remote supports it, checksums of every transferred file).
+//!
+//! Notes:
+//! - The binary is `Config::rclone_binary` (default `rclone` on PATH).
+//! - Name collisions on the remote get a `-<millis>-<pid>` suffix, like local moves.
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Deserialize;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+
+use crate::config::types::Config;
+use crate::errors::AriaMoveError;
+use crate::shutdown;
+use crate::utils::{ensure_not_base, stable_file_probe};
+
+use super::file_move::read_only_source;
+use super::lock::lock_error;
+use super::path_lock::acquire_path_lock_with;
+
+/// Parsed `rclone:remote:path` destination.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RcloneTarget {
+    /// Configured rclone remote name (as in `rclone config`).
+    pub remote: String,
+    /// Directory on the remote (may be empty for the remote's root).
+    pub path: String,
+}
+
+impl RcloneTarget {
+    /// Parse `completed_base`; returns None unless it has the form `rclone:remote:path`.
+    pub fn parse(base: &Path) -> Option<Self> {
+        let rest = base.to_str()?.strip_prefix("rclone:")?;
+        let (remote, path) = rest.split_once(':')?;
+        if remote.is_empty() || remote.starts_with('-') {
+            return None;
+        }
+        Some(Self {
+            remote: remote.to_string(),
+            path: path.trim_end_matches('/').to_string(),
+        })
+    }
+
+    /// rclone spec (`remote:path/name`) for an entry below the target directory.
+    fn spec(&self, name: &str) -> String {
+        if self.path.is_empty() {
+            format!("{}:{name}", self.remote)
+        } else {
+            format!("{}:{}/{name}", self.remote, self.path)
+        }
+    }
+}
+
+/// One line of `rclone --use-json-log` output (only the fields we use).
+#[derive(Debug, Deserialize)]
+struct LogLine {
+    #[serde(default)]
+    level: String,
+    #[serde(default)]
+    msg: String,
+    #[serde(default)]
+    object: Option<String>,
+    #[serde(default)]
+    stats: Option<Stats>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Stats {
+    #[serde(default)]
+    bytes: u64,
+    #[serde(default, rename = "totalBytes")]
+    total_bytes: u64,
+    #[serde(default)]
+    errors: u64,
+}
+
+/// Transfer `src` to the rclone target, then remove the local source.
+pub(super) fn transfer(config: &Config, target: &RcloneTarget, src: &Path) -> Result<PathBuf> {
+    if shutdown::is_requested() {
+        return Err(AriaMoveError::Interrupted.into());
+    }
+    ensure_not_base(&config.download_base, src)?;
+    let meta =
+        fs::symlink_metadata(src).map_err(|e| AriaMoveError::from_io(src.to_path_buf(), &e))?;
+    if meta.file_type().is_symlink() {
+        bail!(
+            "Refusing to transfer symlink to rclone destination: {}",
+            src.display()
+        );
+    }
+    if !(meta.is_file() || meta.is_dir()) {
+        bail!(
+            "Source path is neither a regular file nor a directory: {}",
+            src.display()
+        );
+    }
+    let name = src
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| anyhow!("source has no valid UTF-8 file name: {}", src.display()))?;
+
+    let disable_locks = config.disable_locks
+        || std::env::var("ARIA_MOVE_DISABLE_LOCKS").ok().as_deref() == Some("1");
+    let _move_lock = if disable_locks {
+        None
+    } else {
+        match acquire_path_lock_with(src, config.lock_strategy) {
+            Ok(l) => Some(l),
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => None,
+            Err(e) if read_only_source(config, &e) => None,
+            Err(e) => return Err(lock_error(src, e)),
+        }
+    };
+    if meta.is_file() {
+        stable_file_probe(src, Duration::from_millis(200), 3)?;
+    }
+
+    let dest = unique_spec(config, target, name)?;
+    if config.dry_run {
+        info!(src = %src.display(), dest = %dest, "[DRY-RUN] would transfer with rclone");
+        return Ok(PathBuf::from(format!("rclone:{dest}")));
+    }
+
+    let verb = if meta.is_dir() { "copy" } else { "copyto" };
+    let mut cmd = Command::new(&config.rclone_binary);
+    cmd.args([
+        verb,
+        "--use-json-log",
+        "--log-level",
+        "INFO",
+        "--stats",
+        "5s",
+        "--stats-log-level",
+        "NOTICE",
+        "--",
+    ])
+    .arg(explicit(src))
+    .arg(&dest)
+    .stdin(Stdio::null())
+    .stdout(Stdio::null())
+    .stderr(Stdio::piped());
+    debug!(cmd = ?cmd, "running rclone");
+
+    let mut child = cmd.spawn().map_err(|e| spawn_error(config, e))?;
+    let mut errors: Vec<String> = Vec::new();
+    if let Some(stderr) = child.stderr.take() {
+        for line in BufReader::new(stderr).lines() {
+            let line = line.context("read rclone output")?;
+            handle_log_line(&line, src, &mut errors);
+        }
+    }
+    let status = child.wait().context("wait for rclone")?;
+    if !status.success() {
+        let detail = if errors.is_empty() {
+            "no error details in rclone log".to_string()
+        } else {
+            errors.join("; ")
+        };
+        let context = match status.code() {
+            Some(code) => format!(
+                "rclone {verb} to '{dest}' failed with exit code {code} ({}): {detail}",
+                exit_hint(code)
+            ),
+            None => format!("rclone was terminated by a signal: {detail}"),
+        };
+        return Err(AriaMoveError::CopyFailed {
+            path: src.to_path_buf(),
+            phase: "rclone".into(),
+            context,
+        }
+        .into());
+    }
+
+    // rclone succeeded (and verified the transfer): only now remove the local source
+    // (unless copy-only).
+    let removed = if config.copy_only {
+        Ok(())
+    } else if meta.is_dir() {
+        fs::remove_dir_all(src)
+    } else {
+        fs::remove_file(src)
+    };
+    if let Err(e) = removed {
+        warn!(error = %e, src = %src.display(), "transferred, but failed to remove local source");
+    }
+    info!(src = %src.display(), dest = %dest, "Transferred to rclone destination");
+    Ok(PathBuf::from(format!("rclone:{dest}")))
+}
+
+/// Log progress from stats lines and collect error messages.
+fn handle_log_line(line: &str, src: &Path, errors: &mut Vec<String>) {
+    let Ok(entry) = serde_json::from_str::<LogLine>(line) else {
+        if !line.trim().is_empty() {
+            debug!(line, "rclone (non-JSON output)");
+        }
+        return;
+    };
+    if let Some(stats) = entry.stats.as_ref() {
+        info!(
+            src = %src.display(),
+            bytes = stats.bytes,
+            total = stats.total_bytes,
+            errors = stats.errors,
+            "rclone progress"
+        );
+    }
+    match entry.level.as_str() {
+        "error" | "critical" | "fatal" => {
+            let msg = match entry.object.as_deref() {
+                Some(obj) => format!("{obj}: {}", entry.msg.trim()),
+                None => entry.msg.trim().to_string(),
+            };
+            warn!(src = %src.display(), error = %msg, "rclone reported an error");
+            errors.push(msg);
+        }
+        _ => debug!(msg = %entry.msg.trim(), "rclone"),
+    }
+}
+
+/// Pick `remote:path/<name>`, or a suffixed variant if it already exists.
+fn unique_spec(config: &Config, target: &RcloneTarget, name: &str) -> Result<String> {
+    let candidate = target.spec(name);
+    let out = Command::new(&config.rclone_binary)
+        .args(["lsjson", "--stat", "--"])
+        .arg(&candidate)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| spawn_error(config, e))?;
+    match out.status.code() {
+        // 3 = directory not found, 4 = file not found: the name is free.
+        Some(3) | Some(4) => Ok(candidate),
+        Some(0) => {
+            let millis = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or(0);
+            Ok(target.spec(&format!("{name}-{millis}-{}", std::process::id())))
+        }
+        code => Err(anyhow!(
+            "rclone lsjson failed for {candidate} (exit {code:?}): {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        )),
+    }
+}
+
+fn spawn_error(config: &Config, e: io::Error) -> anyhow::Error {
+    if e.kind() == io::ErrorKind::NotFound {
+        anyhow!(
+            "rclone not found ({}); install rclone or set rclone_binary",
+            config.rclone_binary.display()
+        )
+    } else {
+        anyhow!("failed to run rclone: {e}")
+    }
+}
+
+/// Local paths must not look like `remote:path` to rclone: relative paths get a `./` prefix.
+fn explicit(p: &Path) -> OsString {
+    if p.is_absolute() {
+        p.as_os_str().to_owned()
+    } else {
+        Path::new(".").join(p).into_os_string()
+    }
+}
+
+/// Short explanation for rclone exit codes.
+fn exit_hint(code: i32) -> &'static str {
+    match code {
+        1 => "syntax or usage error",
+        2 => "uncategorised error",
+        3 => "directory not found",
+        4 => "file not found",
+        5 => "temporary error; retrying may succeed",
+        6 => "less serious errors (NoRetry)",
+        7 => "fatal error",
+        8 => "transfer limit exceeded",
+        9 => "no files transferred",
+        _ => "see rclone docs for exit codes",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_rclone_targets() {
+        let t = RcloneTarget::parse(Path::new("rclone:gdrive:media/done/")).unwrap();
+        assert_eq!(t.remote, "gdrive");
+        assert_eq!(t.path, "media/done");
+        assert_eq!(t.spec("x.mkv"), "gdrive:media/done/x.mkv");
+
+        let t = RcloneTarget::parse(Path::new("rclone:b2:")).unwrap();
+        assert_eq!(t.spec("x"), "b2:x");
+
+        assert!(RcloneTarget::parse(Path::new("rclone:noremote")).is_none());
+        assert!(RcloneTarget::parse(Path::new("rclone::path")).is_none());
+        assert!(RcloneTarget::parse(Path::new("/local/completed")).is_none());
+    }
+
+    #[test]
+    fn collects_errors_from_json_log() {
+        let mut errors = Vec::new();
+        let src = Path::new("/dl/x");
+        handle_log_line(
+            r#"{"level":"notice","msg":"stats","stats":{"bytes":10,"totalBytes":20,"errors":0}}"#,
+            src,
+            &mut errors,
+        );
+        handle_log_line(
+            r#"{"level":"error","msg":"Failed to copy: quota exceeded","object":"x"}"#,
+            src,
+            &mut errors,
+        );
+        handle_log_line("plain text", src, &mut errors);
+        assert_eq!(
+            errors,
+            vec!["x: Failed to copy: quota exceeded".to_string()]
+        );
+    }
+}
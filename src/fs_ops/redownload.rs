@@ -0,0 +1,222 @@
+//! Re-download detection by torrent infohash.
+//! - A move that knows its infohash (`MoveOptions::infohash`, `--infohash`) records
+//!   infohash -> destination in `completed_base/.aria_move.infohashes.json`.
+//! - When the same infohash completes again and its recorded destination still exists,
+//!   `on_redownload` decides: skip (`DestinationExists`, source left in place), replace (the
+//!   new copy takes the old one's place), or keep-both (default: the usual duplicate naming).
+//!
+//! Notes:
+//! - Infohashes are hex (40 digits for v1, 64 for v2), compared case-insensitively; anything
+//!   else is ignored with a warning.
+//! - `replace` renames the old item to a hidden `.aria_move.replaced.*` name first and only
+//!   deletes it after the new move succeeded; on failure it is renamed back.
+//! - Remote destinations keep no registry. Writers within one process are serialized; the
+//!   registry is rewritten atomically (temp + rename).
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+use tracing::{debug, info, warn};
+
+use crate::config::types::Config;
+
+/// Registry file in completed_base.
+pub const INFOHASH_REGISTRY_NAME: &str = ".aria_move.infohashes.json";
+
+/// What to do when a torrent that was already moved completes again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnRedownload {
+    /// Leave the new download in place and report `DestinationExists`
+    Skip,
+    /// Move the new download and delete the previous copy
+    Replace,
+    /// Keep both (the new one gets a unique name if the old name is taken)
+    #[default]
+    KeepBoth,
+}
+
+impl OnRedownload {
+    /// Parse common string names into a policy (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "skip" => Some(OnRedownload::Skip),
+            "replace" | "overwrite" => Some(OnRedownload::Replace),
+            "keep-both" | "keep_both" | "keep" | "default" => Some(OnRedownload::KeepBoth),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for OnRedownload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            OnRedownload::Skip => "skip",
+            OnRedownload::Replace => "replace",
+            OnRedownload::KeepBoth => "keep-both",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for OnRedownload {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("invalid re-download policy: '{s}'"))
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Registry {
+    entries: BTreeMap<String, Record>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Record {
+    dest: PathBuf,
+    moved_at: String,
+}
+
+/// Serializes read-modify-write of registries within this process.
+static REGISTRY_LOCK: Mutex<()> = Mutex::new(());
+
+/// Lowercase `infohash` if it is a v1 (40) or v2 (64) hex digest; warns otherwise.
+pub(super) fn normalize(infohash: &str) -> Option<String> {
+    let h = infohash.trim().to_ascii_lowercase();
+    if matches!(h.len(), 40 | 64) && h.bytes().all(|b| b.is_ascii_hexdigit()) {
+        Some(h)
+    } else {
+        warn!(infohash, "ignoring infohash: expected 40 or 64 hex digits");
+        None
+    }
+}
+
+fn registry_path(config: &Config) -> PathBuf {
+    config.completed_base.join(INFOHASH_REGISTRY_NAME)
+}
+
+fn load(path: &Path) -> Registry {
+    match fs::read(path) {
+        Ok(data) => serde_json::from_slice(&data)
+            .inspect_err(|e| warn!(path = %path.display(), error = %e, "ignoring unreadable infohash registry"))
+            .unwrap_or_default(),
+        Err(_) => Registry::default(),
+    }
+}
+
+/// Destination recorded for `infohash`, if it still exists.
+pub(super) fn previous(config: &Config, infohash: &str) -> Option<PathBuf> {
+    let _guard = REGISTRY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let dest = load(&registry_path(config)).entries.remove(infohash)?.dest;
+    fs::symlink_metadata(&dest).is_ok().then_some(dest)
+}
+
+/// Record `infohash` -> `dest`. Best-effort: failures are logged, the move stands.
+pub(super) fn record(config: &Config, infohash: &str, dest: &Path) {
+    let path = registry_path(config);
+    let _guard = REGISTRY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut registry = load(&path);
+    registry.entries.insert(
+        infohash.to_string(),
+        Record {
+            dest: dest.to_path_buf(),
+            moved_at: chrono::Local::now().to_rfc3339(),
+        },
+    );
+    let res = serde_json::to_vec_pretty(&registry)
+        .map_err(anyhow::Error::from)
+        .and_then(|data| {
+            let tmp = path.with_extension("json.tmp");
+            fs::write(&tmp, data).with_context(|| format!("write {}", tmp.display()))?;
+            fs::rename(&tmp, &path).with_context(|| format!("rename to {}", path.display()))
+        });
+    match res {
+        Ok(()) => debug!(infohash, dest = %dest.display(), "recorded infohash"),
+        Err(e) => warn!(infohash, error = %format!("{e:#}"), "could not record infohash"),
+    }
+}
+
+/// A previous copy moved aside by `replace`; restored unless `discard` is called.
+pub(super) struct Replaced {
+    original: PathBuf,
+    stash: Option<PathBuf>,
+}
+
+impl Replaced {
+    /// Rename `previous` to a hidden name beside it so the new move can take its name.
+    pub(super) fn stash(previous: &Path) -> Result<Self> {
+        let name = previous.file_name().unwrap_or_default().to_string_lossy();
+        let stash = previous.with_file_name(format!(
+            ".aria_move.replaced.{}--{name}",
+            std::process::id()
+        ));
+        fs::rename(previous, &stash).with_context(|| {
+            format!(
+                "move previous copy {} aside for replacement",
+                previous.display()
+            )
+        })?;
+        Ok(Self {
+            original: previous.to_path_buf(),
+            stash: Some(stash),
+        })
+    }
+
+    /// The new move succeeded: delete the previous copy.
+    pub(super) fn discard(mut self) {
+        let Some(stash) = self.stash.take() else {
+            return;
+        };
+        let res = if stash.is_dir() {
+            fs::remove_dir_all(&stash)
+        } else {
+            fs::remove_file(&stash)
+        };
+        match res {
+            Ok(()) => info!(previous = %self.original.display(), "replaced previous download"),
+            Err(e) => {
+                warn!(path = %stash.display(), error = %e, "could not delete replaced copy")
+            }
+        }
+    }
+}
+
+impl Drop for Replaced {
+    fn drop(&mut self) {
+        if let Some(stash) = self.stash.take()
+            && let Err(e) = fs::rename(&stash, &self.original)
+        {
+            warn!(path = %stash.display(), error = %e, "could not restore previous copy");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_accepts_v1_and_v2_hex_only() {
+        let v1 = "C12FE1C06BBA254A9DC9F519B335AA7C1367A88A";
+        assert_eq!(normalize(v1), Some(v1.to_ascii_lowercase()));
+        assert!(normalize(&"ab".repeat(32)).is_some());
+        assert!(normalize("not-a-hash").is_none());
+        assert!(normalize(&"zz".repeat(20)).is_none());
+    }
+
+    #[test]
+    fn parse_round_trips_display() {
+        for p in [
+            OnRedownload::Skip,
+            OnRedownload::Replace,
+            OnRedownload::KeepBoth,
+        ] {
+            assert_eq!(OnRedownload::parse(&p.to_string()), Some(p));
+        }
+        assert!("bogus".parse::<OnRedownload>().is_err());
+    }
+}
@@ -0,0 +1,459 @@
+//! Remote destinations: `completed_base = sftp://[user@]host[:port]/path`, or
+//! `completed_base = rclone:remote:path` (delegated to rclone, see `rclone.rs`).
+//! - Parsing is always available so validation can explain a missing feature.
+//! - Uploading requires the `sftp` cargo feature and an OpenSSH `ssh` client on PATH
+//!   (override the binary with `ARIA_MOVE_SSH`).
+//! - Every file of the source must keep its size for one probe interval before anything
+//!   is sent, so a download that is still growing is not uploaded.
+//! - Each file is streamed over SSH into a hidden `.aria_move.upload.*` staging path,
+//!   its remote size and SHA-256 (`sha256sum` on the remote) are compared with the local
+//!   file, then the staging path is renamed into place.
+//! - The local source is removed only after every file has been verified and renamed.
+//!
+//! Notes:
+//! - Runs with `BatchMode=yes`: key-based auth only, never prompts.
+//! - Name collisions on the remote get a `-<millis>-<pid>` suffix, like local moves. The
+//!   final rename is `mv -n -T` (GNU coreutils or BusyBox on the remote), so a name taken
+//!   while uploading fails the move instead of being replaced or moved into.
+//! - Symlink sources are refused; pass the target path instead.
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use crate::config::types::Config;
+
+use super::rclone::RcloneTarget;
+
+/// Parsed `sftp://` destination.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteTarget {
+    pub user: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+    /// Absolute directory on the remote host.
+    pub path: String,
+}
+
+impl RemoteTarget {
+    /// Parse `completed_base`; returns None for ordinary local paths.
+    pub fn parse(base: &Path) -> Option<Self> {
+        let s = base.to_str()?;
+        let rest = s.strip_prefix("sftp://")?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let (user, hostport) = match authority.rsplit_once('@') {
+            Some((u, h)) if !u.is_empty() => (Some(u.to_string()), h),
+            _ => (None, authority),
+        };
+        let (host, port) = match hostport.rsplit_once(':') {
+            Some((h, p)) => (h, Some(p.parse().ok()?)),
+            None => (hostport, None),
+        };
+        if host.is_empty() || host.starts_with('-') {
+            return None;
+        }
+        let path = path.trim_end_matches('/');
+        Some(Self {
+            user,
+            host: host.to_string(),
+            port,
+            path: if path.is_empty() {
+                "/".into()
+            } else {
+                path.into()
+            },
+        })
+    }
+
+    /// `user@host` as passed to ssh.
+    #[cfg_attr(not(feature = "sftp"), allow(dead_code))]
+    fn destination(&self) -> String {
+        match &self.user {
+            Some(u) => format!("{u}@{}", self.host),
+            None => self.host.clone(),
+        }
+    }
+
+    /// URL of `remote_path` on this host (used as the returned destination).
+    #[cfg_attr(not(feature = "sftp"), allow(dead_code))]
+    fn url(&self, remote_path: &str) -> PathBuf {
+        let auth = self.destination();
+        match self.port {
+            Some(p) => PathBuf::from(format!("sftp://{auth}:{p}{remote_path}")),
+            None => PathBuf::from(format!("sftp://{auth}{remote_path}")),
+        }
+    }
+}
+
+/// True if `completed_base` names a remote destination (`sftp://` or `rclone:`).
+pub fn is_remote(base: &Path) -> bool {
+    base.to_str()
+        .is_some_and(|s| s.starts_with("sftp://") || s.starts_with("rclone:"))
+}
+
+/// Move `src` (file or directory) to the remote destination.
+pub(super) fn move_to_remote(config: &Config, src: &Path) -> Result<PathBuf> {
+    if let Some(target) = RcloneTarget::parse(&config.completed_base) {
+        return super::rclone::transfer(config, &target, src);
+    }
+    move_to_sftp(config, src)
+}
+
+/// Quote `s` for a POSIX shell on the remote side.
+#[cfg_attr(not(feature = "sftp"), allow(dead_code))]
+fn sh_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(not(feature = "sftp"))]
+fn move_to_sftp(config: &Config, _src: &Path) -> Result<PathBuf> {
+    anyhow::bail!(
+        "completed_base '{}' is a remote destination, but aria_move was built without the `sftp` feature",
+        config.completed_base.display()
+    )
+}
+
+#[cfg(feature = "sftp")]
+fn move_to_sftp(config: &Config, src: &Path) -> Result<PathBuf> {
+    sftp::upload(config, src)
+}
+
+#[cfg(feature = "sftp")]
+mod sftp {
+    use anyhow::{Context, Result, anyhow, bail};
+    use std::fs::{self, File};
+    use std::io;
+    use std::path::{Path, PathBuf};
+    use std::process::{Command, Stdio};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    use tracing::{debug, info, warn};
+    use walkdir::WalkDir;
+
+    use super::{RemoteTarget, sh_quote};
+    use crate::config::types::Config;
+    use crate::errors::AriaMoveError;
+    use crate::fs_ops::digest::{sha256_file, to_hex};
+    use crate::fs_ops::file_move::read_only_source;
+    use crate::fs_ops::lock::lock_error;
+    use crate::fs_ops::path_lock::acquire_path_lock_with;
+    use crate::shutdown;
+    use crate::utils::ensure_not_base;
+
+    /// Quiet time every file must show before an upload starts.
+    const PROBE_INTERVAL: Duration = Duration::from_millis(200);
+    const PROBE_ATTEMPTS: usize = 3;
+
+    pub(super) fn upload(config: &Config, src: &Path) -> Result<PathBuf> {
+        if shutdown::is_requested() {
+            return Err(AriaMoveError::Interrupted.into());
+        }
+        let Some(target) = RemoteTarget::parse(&config.completed_base) else {
+            bail!(
+                "invalid remote completed_base: {}",
+                config.completed_base.display()
+            );
+        };
+        ensure_not_base(&config.download_base, src)?;
+        let meta =
+            fs::symlink_metadata(src).map_err(|e| AriaMoveError::from_io(src.to_path_buf(), &e))?;
+        if meta.file_type().is_symlink() {
+            bail!(
+                "Refusing to upload symlink to remote destination: {}",
+                src.display()
+            );
+        }
+        let name = src
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| anyhow!("source has no valid UTF-8 file name: {}", src.display()))?;
+
+        let disable_locks = config.disable_locks
+            || std::env::var("ARIA_MOVE_DISABLE_LOCKS").ok().as_deref() == Some("1");
+        let _move_lock = if disable_locks {
+            None
+        } else {
+            match acquire_path_lock_with(src, config.lock_strategy) {
+                Ok(l) => Some(l),
+                Err(e) if e.kind() == io::ErrorKind::PermissionDenied => None,
+                Err(e) if read_only_source(config, &e) => None,
+                Err(e) => return Err(lock_error(src, e)),
+            }
+        };
+
+        // (relative path, local path, size) for every file; relative dirs to create.
+        let mut files: Vec<(String, PathBuf, u64)> = Vec::new();
+        let mut dirs: Vec<String> = Vec::new();
+        if meta.is_dir() {
+            for entry in WalkDir::new(src).min_depth(1).sort_by_file_name() {
+                let entry = entry.with_context(|| format!("walk {}", src.display()))?;
+                let rel = relative_unix(src, entry.path())?;
+                let ft = entry.file_type();
+                if ft.is_dir() {
+                    dirs.push(rel);
+                } else if ft.is_file() {
+                    let len = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                    files.push((rel, entry.into_path(), len));
+                } else {
+                    bail!(
+                        "Refusing to upload non-regular entry: {}",
+                        entry.path().display()
+                    );
+                }
+            }
+        } else if meta.is_file() {
+            files.push((String::new(), src.to_path_buf(), meta.len()));
+        } else {
+            bail!(
+                "Source path is neither a regular file nor a directory: {}",
+                src.display()
+            );
+        }
+
+        probe_stable(&mut files)?;
+
+        let final_path = unique_remote_path(&target, name)?;
+        if config.dry_run {
+            info!(src = %src.display(), dest = %target.url(&final_path).display(), files = files.len(), "[DRY-RUN] would upload");
+            return Ok(target.url(&final_path));
+        }
+
+        let staging = format!(
+            "{}/.aria_move.upload.{}.{}",
+            target.path.trim_end_matches('/'),
+            std::process::id(),
+            name
+        );
+        let result = upload_tree(&target, &staging, meta.is_dir(), &dirs, &files)
+            .and_then(|()| rename_into_place(&target, &staging, &final_path));
+        if let Err(e) = result {
+            if let Err(ce) = run(&target, &format!("rm -rf -- {}", sh_quote(&staging)), None) {
+                warn!(error = %ce, staging = %staging, "failed to remove remote staging path");
+            }
+            return Err(e);
+        }
+
+        // Verified and in place remotely: only now remove the local source (unless copy-only).
+        let removed = if config.copy_only {
+            Ok(())
+        } else if meta.is_dir() {
+            fs::remove_dir_all(src)
+        } else {
+            fs::remove_file(src)
+        };
+        if let Err(e) = removed {
+            warn!(error = %e, src = %src.display(), "uploaded, but failed to remove local source");
+        }
+        let dest = target.url(&final_path);
+        info!(src = %src.display(), dest = %dest.display(), files = files.len(), "Uploaded to remote destination");
+        Ok(dest)
+    }
+
+    fn upload_tree(
+        target: &RemoteTarget,
+        staging: &str,
+        is_dir: bool,
+        dirs: &[String],
+        files: &[(String, PathBuf, u64)],
+    ) -> Result<()> {
+        let parent = target.path.as_str();
+        let mut mkdir = format!("mkdir -p -- {}", sh_quote(parent));
+        if is_dir {
+            mkdir.push(' ');
+            mkdir.push_str(&sh_quote(staging));
+            for d in dirs {
+                mkdir.push(' ');
+                mkdir.push_str(&sh_quote(&format!("{staging}/{d}")));
+            }
+        }
+        run(target, &mkdir, None)?;
+
+        for (rel, local, len) in files {
+            if shutdown::is_requested() {
+                return Err(AriaMoveError::Interrupted.into());
+            }
+            let remote = if rel.is_empty() {
+                staging.to_string()
+            } else {
+                format!("{staging}/{rel}")
+            };
+            let local_sum =
+                to_hex(&sha256_file(local).with_context(|| format!("hash {}", local.display()))?);
+            let file = File::open(local).with_context(|| format!("open {}", local.display()))?;
+            debug!(local = %local.display(), remote = %remote, "uploading");
+            run(target, &format!("cat > {}", sh_quote(&remote)), Some(file))?;
+
+            let out = run(target, &format!("wc -c < {}", sh_quote(&remote)), None)?;
+            let remote_len: u64 = out
+                .trim()
+                .parse()
+                .map_err(|_| anyhow!("could not read remote size of {remote}: {:?}", out.trim()))?;
+            if remote_len != *len {
+                return Err(AriaMoveError::VerificationFailed {
+                    path: target.url(&remote),
+                    expected: *len,
+                    actual: remote_len,
+                }
+                .into());
+            }
+
+            let out = run(target, &format!("sha256sum < {}", sh_quote(&remote)), None)?;
+            let remote_sum = out.split_whitespace().next().unwrap_or_default();
+            if remote_sum != local_sum {
+                return Err(AriaMoveError::ChecksumMismatch {
+                    path: target.url(&remote),
+                    expected: local_sum,
+                    actual: remote_sum.to_string(),
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Wait until no file in `files` changes size for one `PROBE_INTERVAL` (the recorded
+    /// sizes are updated), or fail after `PROBE_ATTEMPTS` intervals of growth.
+    fn probe_stable(files: &mut [(String, PathBuf, u64)]) -> Result<()> {
+        let mut growing = None;
+        for _ in 0..PROBE_ATTEMPTS {
+            if shutdown::is_requested() {
+                return Err(AriaMoveError::Interrupted.into());
+            }
+            std::thread::sleep(PROBE_INTERVAL);
+            growing = None;
+            for (_, local, len) in files.iter_mut() {
+                let now = fs::metadata(&*local)
+                    .with_context(|| format!("stat {}", local.display()))?
+                    .len();
+                if now != *len {
+                    growing = Some(local.display().to_string());
+                    *len = now;
+                }
+            }
+            if growing.is_none() {
+                return Ok(());
+            }
+        }
+        bail!(
+            "File {} did not stabilize in size",
+            growing.unwrap_or_default()
+        )
+    }
+
+    /// Rename the staging path to `final_path` unless something appeared there meanwhile:
+    /// `-n` never replaces an existing entry and `-T` never moves into a directory.
+    fn rename_into_place(target: &RemoteTarget, staging: &str, final_path: &str) -> Result<()> {
+        let staging = sh_quote(staging);
+        let cmd = format!(
+            "mv -n -T -- {staging} {} && if [ -e {staging} ]; then echo taken; fi",
+            sh_quote(final_path)
+        );
+        if run(target, &cmd, None)?.trim() == "taken" {
+            bail!(
+                "remote destination {} was created by someone else during the upload",
+                target.url(final_path).display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Pick `<path>/<name>`, or a suffixed variant if it already exists remotely.
+    fn unique_remote_path(target: &RemoteTarget, name: &str) -> Result<String> {
+        let base = target.path.trim_end_matches('/');
+        let candidate = format!("{base}/{name}");
+        let probe = format!(
+            "if [ -e {0} ]; then echo exists; else echo free; fi",
+            sh_quote(&candidate)
+        );
+        if run(target, &probe, None)?.trim() == "free" {
+            return Ok(candidate);
+        }
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0);
+        Ok(format!("{base}/{name}-{millis}-{}", std::process::id()))
+    }
+
+    /// Run `remote_cmd` on the target via ssh, optionally feeding `stdin`; returns stdout.
+    fn run(target: &RemoteTarget, remote_cmd: &str, stdin: Option<File>) -> Result<String> {
+        let bin = std::env::var_os("ARIA_MOVE_SSH").unwrap_or_else(|| "ssh".into());
+        let mut cmd = Command::new(&bin);
+        cmd.arg("-o").arg("BatchMode=yes");
+        if let Some(p) = target.port {
+            cmd.arg("-p").arg(p.to_string());
+        }
+        cmd.arg("--").arg(target.destination()).arg(remote_cmd);
+        cmd.stdin(match stdin {
+            Some(f) => Stdio::from(f),
+            None => Stdio::null(),
+        });
+        let out = cmd
+            .output()
+            .map_err(|e| AriaMoveError::DestinationUnavailable {
+                dest: target.url(&target.path),
+                context: format!("failed to run {:?}: {e}", bin),
+            })?;
+        // ssh reserves exit status 255 for its own (connection/auth) failures.
+        if out.status.code() == Some(255) {
+            return Err(AriaMoveError::DestinationUnavailable {
+                dest: target.url(&target.path),
+                context: format!(
+                    "ssh to {} failed: {}",
+                    target.host,
+                    String::from_utf8_lossy(&out.stderr).trim()
+                ),
+            }
+            .into());
+        }
+        if !out.status.success() {
+            bail!(
+                "remote command failed on {} ({}): {}",
+                target.host,
+                out.status,
+                String::from_utf8_lossy(&out.stderr).trim()
+            );
+        }
+        Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+    }
+
+    fn relative_unix(root: &Path, path: &Path) -> Result<String> {
+        let rel = path.strip_prefix(root)?;
+        let parts: Option<Vec<&str>> = rel.components().map(|c| c.as_os_str().to_str()).collect();
+        parts
+            .map(|p| p.join("/"))
+            .ok_or_else(|| anyhow!("non UTF-8 path: {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sftp_urls() {
+        let t = RemoteTarget::parse(Path::new("sftp://alice@nas:2222/srv/done/")).unwrap();
+        assert_eq!(t.user.as_deref(), Some("alice"));
+        assert_eq!(t.host, "nas");
+        assert_eq!(t.port, Some(2222));
+        assert_eq!(t.path, "/srv/done");
+        assert_eq!(
+            t.url("/srv/done/x"),
+            PathBuf::from("sftp://alice@nas:2222/srv/done/x")
+        );
+
+        let t = RemoteTarget::parse(Path::new("sftp://nas")).unwrap();
+        assert_eq!((t.user, t.port, t.path.as_str()), (None, None, "/"));
+
+        assert!(RemoteTarget::parse(Path::new("/local/completed")).is_none());
+        assert!(RemoteTarget::parse(Path::new("sftp://-oProxy=x/p")).is_none());
+        assert!(RemoteTarget::parse(Path::new("sftp://nas:port/p")).is_none());
+    }
+
+    #[test]
+    fn quoting_survives_single_quotes() {
+        assert_eq!(sh_quote("a b"), "'a b'");
+        assert_eq!(sh_quote("it's"), r"'it'\''s'");
+    }
+}
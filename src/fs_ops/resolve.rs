@@ -2,21 +2,35 @@
 //! - If the caller provides a concrete path, use it if it exists and is a regular file OR directory
 //!   (or a symlink that resolves to one of those types).
 //! - For a bare filename, try resolving it under `download_base` with the same rules.
-//! - Otherwise, do not auto-pick; return an error. Auto-selection is out of scope for this program.
+//! - Otherwise, do not auto-pick files; return an error.
+//! - Exception: with `resolve_directories = true`, pick the most-recently-modified top-level
+//!   directory of `download_base` whose contents are stable (multi-file downloads whose hook
+//!   argument went missing) and untouched for `recent_window`, as sweep requires.
+//! - Entries matching `download_base/.ariamoveignore` are never candidates; dot-entries only
+//!   with `include_hidden` (internal `.aria_move.*` names never).
 //!
 //! Notes:
 //! - Single-pass walk (no intermediate Vec) for efficiency.
 //! - Re-validates the chosen path before returning to avoid TOCTOU surprises.
 
 use anyhow::Result;
+use std::fmt;
+use std::fs;
 use std::path::{Path, PathBuf};
-use tracing::{instrument, warn};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, info, instrument, warn};
+use walkdir::WalkDir;
 
 use crate::config::types::Config;
 use crate::errors::AriaMoveError;
+use crate::shutdown;
+use crate::utils::{has_aria2_control_file, has_partial_extension, skip_by_hidden_policy};
+
+use super::ignore::{IGNORE_FILE_NAME, IgnoreRules};
+use super::sweep::recent_cutoff;
 
 /// Resolve the source path. If `maybe_path` is Some and exists, that wins.
-/// Otherwise returns an error (auto-pick is out of scope).
+/// Otherwise returns an error, unless `resolve_directories` allows auto-picking a directory.
 #[instrument(level = "debug", skip(config), fields(base=%config.download_base.display()))]
 pub fn resolve_source_path(config: &Config, maybe_path: Option<&Path>) -> Result<PathBuf> {
     // 1) Prefer explicitly provided path when it exists.
@@ -75,10 +89,193 @@ pub fn resolve_source_path(config: &Config, maybe_path: Option<&Path>) -> Result
         }
     }
 
-    // No explicit path provided -> out of scope. Do not auto-pick.
+    // No explicit path provided: only directories may be auto-picked, and only when enabled.
+    if config.resolve_directories {
+        return pick_recent_directory(config);
+    }
     Err(AriaMoveError::NoneFound(config.download_base.clone()).into())
 }
 
+/// How settled a candidate looks right now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stability {
+    /// No partial/control files and size unchanged over the probe interval.
+    Stable,
+    /// Has an incomplete-download extension or an aria2 control file (in or next to it).
+    Partial,
+    /// Size changed during the probe: still being written.
+    Changing,
+    /// Modified within `recent_window`: possibly still in progress.
+    Recent,
+}
+
+impl fmt::Display for Stability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Stability::Stable => "stable",
+            Stability::Partial => "partial",
+            Stability::Changing => "changing",
+            Stability::Recent => "recent",
+        };
+        f.write_str(s)
+    }
+}
+
+/// A top-level entry of download_base as seen by the resolver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub path: PathBuf,
+    pub is_dir: bool,
+    /// Newest modification time of the entry (for directories: of anything inside it).
+    pub mtime: SystemTime,
+    /// Size in bytes (for directories: total of regular files).
+    pub size: u64,
+    pub stability: Stability,
+    /// True if auto-resolution may pick this entry (directories with `resolve_directories`).
+    pub eligible: bool,
+}
+
+impl Candidate {
+    /// Short human-readable explanation of why the resolver would or would not pick this entry.
+    pub fn reason(&self) -> &'static str {
+        match (self.is_dir, self.eligible, self.stability) {
+            (false, _, _) => "files are never auto-picked; pass the path explicitly",
+            (true, false, _) => "directory auto-pick disabled (resolve_directories=false)",
+            (true, true, Stability::Partial) => "contains partial or aria2 control files",
+            (true, true, Stability::Changing) => "contents still changing",
+            (true, true, Stability::Recent) => "modified within recent_window",
+            (true, true, Stability::Stable) => "eligible",
+        }
+    }
+}
+
+/// List top-level entries of download_base in resolver order.
+/// Order: newest mtime first; ties are broken by larger size, then by path (ascending),
+/// so the result is deterministic. The resolver picks the first eligible, stable entry.
+/// Returns an empty list if download_base cannot be read.
+pub fn list_candidates(config: &Config) -> Vec<Candidate> {
+    let base = &config.download_base;
+    let Ok(rd) = fs::read_dir(base) else {
+        return Vec::new();
+    };
+    let ignore = IgnoreRules::load(base);
+    let cutoff = recent_cutoff(config);
+    let mut entries: Vec<(PathBuf, bool, SystemTime, Option<u64>)> = rd
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let path = e.path();
+            let name = path.file_name()?;
+            if skip_by_hidden_policy(name, config.include_hidden) || name == IGNORE_FILE_NAME {
+                return None;
+            }
+            let ft = e.file_type().ok()?;
+            if !(ft.is_dir() || ft.is_file()) || ignore.is_ignored(base, &path, ft.is_dir()) {
+                return None;
+            }
+            let mtime = newest_mtime(&path)?;
+            let size = settled_size(config, &path);
+            Some((path, ft.is_dir(), mtime, size))
+        })
+        .collect();
+
+    // One short probe for the whole listing, not one per entry.
+    if entries.iter().any(|e| e.3.is_some()) && !shutdown::is_requested() {
+        std::thread::sleep(Duration::from_millis(200));
+    }
+
+    let mut out: Vec<Candidate> = entries
+        .drain(..)
+        .map(|(path, is_dir, mtime, before)| {
+            let after = settled_size(config, &path);
+            let stability = match (before, after) {
+                (None, _) | (_, None) => Stability::Partial,
+                (Some(a), Some(b)) if a != b => Stability::Changing,
+                _ if cutoff.is_some_and(|c| mtime > c) => Stability::Recent,
+                _ => Stability::Stable,
+            };
+            let size = after.or(before).unwrap_or_else(|| tree_size(&path));
+            Candidate {
+                eligible: is_dir && config.resolve_directories,
+                path,
+                is_dir,
+                mtime,
+                size,
+                stability,
+            }
+        })
+        .collect();
+    out.sort_by(|a, b| {
+        b.mtime
+            .cmp(&a.mtime)
+            .then_with(|| b.size.cmp(&a.size))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    out
+}
+
+/// Pick the most-recently-modified top-level directory under download_base whose contents
+/// are stable (no partial/control files, total size unchanged over a short probe, nothing
+/// modified within `recent_window`).
+fn pick_recent_directory(config: &Config) -> Result<PathBuf> {
+    let base = &config.download_base;
+    if !base.is_dir() {
+        return Err(AriaMoveError::BaseInvalid(base.clone()).into());
+    }
+    let candidates = list_candidates(config);
+    if shutdown::is_requested() {
+        return Err(AriaMoveError::Interrupted.into());
+    }
+    for c in candidates {
+        if c.eligible && c.stability == Stability::Stable {
+            if !c.path.is_dir() {
+                return Err(AriaMoveError::Disappeared(c.path).into());
+            }
+            info!(path = %c.path.display(), "auto-resolved most recent stable directory");
+            return Ok(c.path);
+        }
+        debug!(path = %c.path.display(), stability = %c.stability, reason = c.reason(), "skipping candidate");
+    }
+    Err(AriaMoveError::NoneFound(base.clone()).into())
+}
+
+/// Newest modification time of the entry or anything inside it.
+fn newest_mtime(path: &Path) -> Option<SystemTime> {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok()?.modified().ok())
+        .max()
+}
+
+/// Total size of regular files, or None if anything looks partial / still downloading.
+fn settled_size(config: &Config, path: &Path) -> Option<u64> {
+    if has_partial_extension(path, config) || has_aria2_control_file(path) {
+        return None;
+    }
+    let mut total = 0u64;
+    for entry in WalkDir::new(path) {
+        let entry = entry.ok()?;
+        if entry.file_type().is_file() {
+            if has_partial_extension(entry.path(), config) || has_aria2_control_file(entry.path()) {
+                return None;
+            }
+            total = total.saturating_add(entry.metadata().ok()?.len());
+        }
+    }
+    Some(total)
+}
+
+/// Total size of regular files, ignoring partial markers (for display only).
+fn tree_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
 #[inline]
 fn is_bare_filename(p: &Path) -> bool {
     // A single path component (no separators) and not absolute.
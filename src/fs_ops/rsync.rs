@@ -0,0 +1,181 @@
+//! rsync copy backend (`copy_backend = rsync`, engine `rsync`).
+//! - Copies into the same deterministic resume temp as the builtin copier, so a partial temp
+//!   left by an interrupted run (of either backend) becomes rsync's basis file.
+//! - Runs `rsync --inplace --partial --no-whole-file`: the rolling-checksum delta rewrites only
+//!   blocks that differ, instead of restarting a large transfer from byte zero.
+//! - On success the temp is size-checked and atomically renamed into place.
+//!
+//! Notes:
+//! - The binary is `rsync` on PATH, or `ARIA_MOVE_RSYNC` if set.
+//! - On failure the temp is kept on purpose: the next run resumes from it.
+//! - Paths are passed after `--` and made explicit (`./` prefix for relative paths), so names
+//!   starting with `-` or containing `:` are never taken as options or remote hosts.
+
+use anyhow::{Context, Result, anyhow};
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::{debug, info};
+
+use crate::config::types::Config;
+use crate::errors::AriaMoveError;
+
+use super::atomic::try_atomic_move;
+use super::copy::rename_failed;
+use super::engine::{CopyEngine, EngineCapabilities};
+use super::helpers::destination_error;
+use super::util;
+
+/// The `rsync` copy engine. rsync checks each transferred file against a whole-file checksum.
+pub(super) struct RsyncEngine;
+
+impl CopyEngine for RsyncEngine {
+    fn name(&self) -> &str {
+        "rsync"
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities {
+            resumable: true,
+            verifies: true,
+            sparse_aware: false,
+        }
+    }
+
+    fn copy_file(&self, _config: &Config, src: &Path, dest: &Path) -> Result<(), AriaMoveError> {
+        rsync_copy_and_rename(src, dest).map_err(AriaMoveError::from)
+    }
+}
+
+/// Copy `src` -> resume temp via rsync, then rename the temp to `dest`.
+pub(super) fn rsync_copy_and_rename(src: &Path, dest: &Path) -> Result<()> {
+    let dest_dir = dest
+        .parent()
+        .ok_or_else(|| anyhow!("destination has no parent: {}", dest.display()))?;
+    fs::create_dir_all(dest_dir)
+        .map_err(destination_error("create destination directory", dest_dir))?;
+
+    let tmp_path = util::resume_temp_path(src, dest);
+    let src_size = fs::metadata(src)
+        .with_context(|| format!("stat {}", src.display()))?
+        .len();
+    if let Ok(m) = fs::metadata(&tmp_path) {
+        info!(temp = %tmp_path.display(), have = m.len(), total = src_size, "resuming interrupted copy with rsync delta transfer");
+    }
+
+    let bin = std::env::var_os("ARIA_MOVE_RSYNC").unwrap_or_else(|| "rsync".into());
+    let mut cmd = Command::new(&bin);
+    cmd.args(["--inplace", "--partial", "--no-whole-file", "--"])
+        .arg(explicit(src))
+        .arg(explicit(&tmp_path));
+    debug!(cmd = ?cmd, "running rsync");
+    let out = cmd.output().map_err(|e| {
+        if e.kind() == io::ErrorKind::NotFound {
+            anyhow!(
+                "rsync not found ({}); install rsync, set ARIA_MOVE_RSYNC, or use copy_backend=builtin",
+                PathBuf::from(&bin).display()
+            )
+        } else {
+            anyhow!("failed to run rsync: {e}")
+        }
+    })?;
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        let context = match out.status.code() {
+            Some(code) => format!(
+                "rsync failed with exit code {code} ({}): {}",
+                exit_hint(code),
+                stderr.trim()
+            ),
+            None => format!("rsync was terminated by a signal: {}", stderr.trim()),
+        };
+        return Err(AriaMoveError::CopyFailed {
+            path: src.to_path_buf(),
+            phase: "rsync".into(),
+            context,
+        }
+        .into());
+    }
+
+    let written = fs::metadata(&tmp_path)
+        .with_context(|| format!("stat {}", tmp_path.display()))?
+        .len();
+    if written != src_size {
+        return Err(AriaMoveError::VerificationFailed {
+            path: dest.to_path_buf(),
+            expected: src_size,
+            actual: written,
+        }
+        .into());
+    }
+    try_atomic_move(&tmp_path, dest).map_err(|e| rename_failed(&tmp_path, dest, e))?;
+    Ok(())
+}
+
+/// Make a path unambiguous for rsync: relative paths get a `./` prefix.
+fn explicit(p: &Path) -> OsString {
+    if p.is_absolute() {
+        p.as_os_str().to_owned()
+    } else {
+        Path::new(".").join(p).into_os_string()
+    }
+}
+
+/// Short explanation for common rsync exit codes.
+fn exit_hint(code: i32) -> &'static str {
+    match code {
+        1 => "syntax or usage error",
+        2 => "protocol incompatibility",
+        3 => "errors selecting input/output files",
+        10 | 12 => "I/O error on the transfer stream",
+        11 => "file I/O error (destination full or unreachable?)",
+        20 => "interrupted",
+        23 => "partial transfer due to error",
+        24 => "source vanished during transfer",
+        30 | 35 => "timeout waiting for data",
+        _ => "see rsync(1) EXIT VALUES",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_paths_are_made_explicit() {
+        assert_eq!(explicit(Path::new("/a/b:c")), OsString::from("/a/b:c"));
+        assert_eq!(explicit(Path::new("-x")), OsString::from("./-x"));
+    }
+
+    #[test]
+    fn resumes_from_stale_partial_temp() {
+        if Command::new("rsync").arg("--version").output().is_err() {
+            eprintln!("rsync not installed; skipping");
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src.bin");
+        let dest = dir.path().join("out/dest.bin");
+        let data: Vec<u8> = (0..300_000u32).map(|i| (i % 251) as u8).collect();
+        fs::write(&src, &data).unwrap();
+
+        // Simulate an interrupted copy whose temp also has a damaged block.
+        fs::create_dir_all(dest.parent().unwrap()).unwrap();
+        let mut partial = data[..200_000].to_vec();
+        partial[1000..2000].fill(0xff);
+        let tmp = util::resume_temp_path(&src, &dest);
+        fs::write(&tmp, &partial).unwrap();
+
+        rsync_copy_and_rename(&src, &dest).unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), data);
+        assert!(!tmp.exists());
+    }
+
+    #[test]
+    fn exit_codes_have_hints() {
+        assert_eq!(exit_hint(23), "partial transfer due to error");
+        assert_eq!(exit_hint(99), "see rsync(1) EXIT VALUES");
+    }
+}
@@ -0,0 +1,111 @@
+//! Content sanity check (`content_sanity_check`).
+//! - aria2 (`file-allocation=prealloc|falloc`) and many torrent clients create a download at
+//!   its full size up front and fill it in place, so name, size and a quiet mtime can all
+//!   look final while the end of the file is still zeros.
+//! - `check_content` reads the last `TAIL_BYTES` of each regular file (every file of a
+//!   directory) and refuses the move with `LooksIncomplete` if they are all zero.
+//!
+//! Notes:
+//! - Opt-in: some complete files legitimately end in zeros (disk images, ISO padding).
+//! - Files smaller than `MIN_SIZE` are not sniffed; symlinks are not followed.
+
+use anyhow::Result;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use tracing::{debug, warn};
+use walkdir::WalkDir;
+
+use crate::errors::AriaMoveError;
+
+/// Bytes read from the end of each file.
+const TAIL_BYTES: u64 = 64 * 1024;
+
+/// Smaller files are not checked.
+const MIN_SIZE: u64 = 1024 * 1024;
+
+/// Fail with `LooksIncomplete` if `src` (or any file below it) ends in an all-zero region.
+pub(super) fn check_content(src: &Path) -> Result<()> {
+    let Ok(meta) = fs::symlink_metadata(src) else {
+        return Ok(());
+    };
+    if meta.is_file() {
+        return check_file(src, meta.len());
+    }
+    if meta.is_dir() {
+        for entry in WalkDir::new(src).follow_links(false).into_iter().flatten() {
+            if entry.file_type().is_file() {
+                let len = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                check_file(entry.path(), len)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_file(path: &Path, len: u64) -> Result<()> {
+    if len < MIN_SIZE {
+        return Ok(());
+    }
+    match zero_tail(path, len) {
+        Ok(true) => {
+            warn!(path = %path.display(), "file ends in {TAIL_BYTES} zero bytes; looks preallocated but not fully written");
+            Err(AriaMoveError::LooksIncomplete(path.to_path_buf()).into())
+        }
+        Ok(false) => Ok(()),
+        Err(e) => {
+            // Unreadable files fail later with a proper error; the check only sniffs.
+            debug!(error = %e, path = %path.display(), "content sanity check skipped");
+            Ok(())
+        }
+    }
+}
+
+/// True if the last `TAIL_BYTES` of the file are all zero.
+fn zero_tail(path: &Path, len: u64) -> io::Result<bool> {
+    let mut file = File::open(path)?;
+    let n = TAIL_BYTES.min(len);
+    file.seek(SeekFrom::Start(len - n))?;
+    let mut buf = vec![0u8; n as usize];
+    file.read_exact(&mut buf)?;
+    Ok(buf.iter().all(|&b| b == 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_tail_is_refused_and_data_tail_passes() {
+        let td = tempfile::tempdir().unwrap();
+        let prealloc = td.path().join("prealloc.bin");
+        let mut data = vec![0xabu8; MIN_SIZE as usize];
+        data.extend(vec![0u8; TAIL_BYTES as usize]);
+        fs::write(&prealloc, &data).unwrap();
+        let err = check_content(&prealloc).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AriaMoveError>(),
+            Some(AriaMoveError::LooksIncomplete(p)) if *p == prealloc
+        ));
+
+        let complete = td.path().join("complete.bin");
+        data.push(1);
+        fs::write(&complete, &data).unwrap();
+        check_content(&complete).unwrap();
+
+        // Small files are never sniffed.
+        let small = td.path().join("small.bin");
+        fs::write(&small, vec![0u8; 4096]).unwrap();
+        check_content(&small).unwrap();
+    }
+
+    #[test]
+    fn directories_are_checked_file_by_file() {
+        let td = tempfile::tempdir().unwrap();
+        let dir = td.path().join("pack");
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("a.bin"), vec![1u8; 16]).unwrap();
+        fs::write(dir.join("b.bin"), vec![0u8; MIN_SIZE as usize]).unwrap();
+        assert!(check_content(&dir).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+//! Guard against the source changing while it is copied.
+//! - `guard_copy` records the source's size and mtime, runs the copy, and stats the source
+//!   again. Any difference means a writer (e.g. aria2 resuming) touched it mid-copy: the
+//!   copy at `dest` is removed and `SourceChanged` returned; the source stays in place.
+//! - Unix: a shared flock is held on the source for the duration of the copy, so tools that
+//!   lock before writing wait for it. A source that is exclusively locked counts as changing.
+//!
+//! Notes:
+//! - aria2 does not flock its downloads; the before/after comparison is what catches it.
+//! - Read leases (F_SETLEASE) are not used: a lease break is delivered as SIGIO, whose
+//!   default action would terminate the process.
+//! - Filesystems without flock support (some NFS/FUSE mounts) only get the comparison.
+
+use anyhow::Result;
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+use std::time::SystemTime;
+use tracing::warn;
+
+use crate::errors::AriaMoveError;
+
+/// Size and modification time of a source, as compared by `guard_copy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Snapshot {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl Snapshot {
+    fn of(file: &File) -> io::Result<Self> {
+        let meta = file.metadata()?;
+        Ok(Snapshot {
+            len: meta.len(),
+            modified: meta.modified().ok(),
+        })
+    }
+}
+
+/// Run `copy` (which writes `src` to `dest`) and fail with `SourceChanged` if `src` was
+/// modified meanwhile. `dest` is removed on that failure; other errors pass through as is.
+pub(super) fn guard_copy<T>(
+    src: &Path,
+    dest: &Path,
+    copy: impl FnOnce() -> Result<T>,
+) -> Result<T> {
+    let file = File::open(src).map_err(|e| AriaMoveError::from_io_op("open source", src, &e))?;
+    #[cfg(unix)]
+    let _lock = SharedLock::try_acquire(&file, src)?;
+    let before =
+        Snapshot::of(&file).map_err(|e| AriaMoveError::from_io_op("stat source", src, &e))?;
+
+    let out = copy()?;
+
+    // Stat by path as well: a writer may have replaced the file rather than appended to it.
+    let after_fd = Snapshot::of(&file).ok();
+    let after_path = File::open(src).and_then(|f| Snapshot::of(&f)).ok();
+    if after_fd != Some(before) || after_path != Some(before) {
+        warn!(src = %src.display(), dest = %dest.display(), "source changed during the copy; discarding the copy");
+        remove_copy(dest);
+        return Err(AriaMoveError::SourceChanged(src.to_path_buf()).into());
+    }
+    Ok(out)
+}
+
+fn remove_copy(dest: &Path) {
+    let res = match fs::symlink_metadata(dest) {
+        Ok(m) if m.is_dir() => fs::remove_dir_all(dest),
+        Ok(_) => fs::remove_file(dest),
+        Err(_) => return,
+    };
+    if let Err(e) = res {
+        warn!(error = %e, dest = %dest.display(), "could not remove the discarded copy");
+    }
+}
+
+/// Shared flock on the source, released on drop.
+#[cfg(unix)]
+struct SharedLock<'a>(&'a File);
+
+#[cfg(unix)]
+impl<'a> SharedLock<'a> {
+    /// None where flock is unsupported; `SourceChanged` if a writer holds it exclusively.
+    fn try_acquire(file: &'a File, src: &Path) -> Result<Option<Self>> {
+        match fs2::FileExt::try_lock_shared(file) {
+            Ok(()) => Ok(Some(SharedLock(file))),
+            Err(e) if e.kind() == fs2::lock_contended_error().kind() => {
+                Err(AriaMoveError::SourceChanged(src.to_path_buf()).into())
+            }
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for SharedLock<'_> {
+    fn drop(&mut self) {
+        let _ = fs2::FileExt::unlock(self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn unchanged_source_keeps_the_copy() {
+        let td = tempfile::tempdir().unwrap();
+        let src = td.path().join("a.bin");
+        let dest = td.path().join("b.bin");
+        fs::write(&src, b"data").unwrap();
+
+        guard_copy(&src, &dest, || Ok(fs::copy(&src, &dest)?)).unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), b"data");
+    }
+
+    #[test]
+    fn growing_source_discards_the_copy() {
+        let td = tempfile::tempdir().unwrap();
+        let src = td.path().join("a.bin");
+        let dest = td.path().join("b.bin");
+        fs::write(&src, b"data").unwrap();
+
+        let err = guard_copy(&src, &dest, || {
+            fs::copy(&src, &dest)?;
+            // aria2 resumes writing after the copy started.
+            fs::OpenOptions::new()
+                .append(true)
+                .open(&src)?
+                .write_all(b"more")?;
+            Ok(())
+        })
+        .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<AriaMoveError>(),
+            Some(AriaMoveError::SourceChanged(p)) if *p == src
+        ));
+        assert!(!dest.exists());
+        assert_eq!(fs::read(&src).unwrap(), b"datamore");
+    }
+}
@@ -1,31 +1,43 @@
 //! Free-space helpers and human-readable byte formatting.
 //!
 //! Responsibilities:
-//! - `free_space_bytes`: platform-specific free space query on the filesystem of a path.
+//! - Free space itself comes from `fsys::free_space_bytes` (statvfs / GetDiskFreeSpaceExW).
 //! - `ensure_space_for_copy`: guard enforcing a small cushion beyond required bytes (to cover metadata, journal, temp files).
 //! - `format_bytes`: compact, human-friendly formatting for diagnostics.
 //! - `has_space`: pure helper for deterministic unit testing of space logic.
+//! - `watch_free_space`: early warning (log + `low-space` notification) when completed_base
+//!   drops below `low_space_warn_mib`, before moves fail with `InsufficientSpace`.
 //!
 //! Design notes:
-//! - A fixed cushion (`SPACE_CUSHION_BYTES`) avoids borderline failures when post-copy metadata updates or temp files consume additional blocks.
+//! - A cushion (`space_cushion`, default 4 MiB) avoids borderline failures when post-copy metadata updates or temp files consume additional blocks.
 //! - `ensure_space_for_copy` treats a non-existent destination path as a prospective file and falls back to its parent directory for the space check.
 //! - Space checks are inherently racy; the functions provide a best-effort pre-flight validation only.
 //! - We use `f_bavail` (user-available blocks) rather than `f_bfree` on Unix for conservative estimation.
 //! - Formatting trims trailing `.0` for cleaner output (e.g. `1 GiB` instead of `1.0 GiB`).
+//! - Low-space warnings have hysteresis: a marker file (`LOW_SPACE_MARKER_NAME`) in
+//!   completed_base records an active warning across runs, so each episode warns once; it
+//!   clears only when free space is `LOW_SPACE_HYSTERESIS_PERCENT` above the threshold.
 //!
 //! Potential future enhancements:
-//! - Make cushion configurable from a higher-level config.
 //! - Add an error variant instead of generic anyhow.
 //! - Expose raw bytes in error metadata (already embedded via formatting).
 
+use crate::config::types::{ByteSize, Config};
 use crate::errors::AriaMoveError;
-use std::io;
+use crate::fsys::free_space_bytes;
+use crate::notify::{self, NotifyEvent};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
 use std::path::Path;
+use tracing::{debug, info, warn};
 
-#[cfg(unix)]
-use std::os::unix::ffi::OsStrExt;
-#[cfg(windows)]
-use std::os::windows::ffi::OsStrExt;
+use super::remote::is_remote;
+
+/// Marker in completed_base while a low-space warning is active.
+pub const LOW_SPACE_MARKER_NAME: &str = ".aria_move.lowspace";
+
+/// A warning clears once free space is this many percent above the threshold.
+pub const LOW_SPACE_HYSTERESIS_PERCENT: u64 = 10;
 
 /// Binary-unit formatting (KiB/MiB/GiB) rounded to one decimal; trims trailing `.0`.
 pub(super) fn format_bytes(n: u64) -> String {
@@ -52,17 +64,18 @@ pub(super) fn format_bytes(n: u64) -> String {
     format!("{} {}", trimmed, unit)
 }
 
-/// Fixed cushion in bytes added to required amount when validating free space.
-pub(super) const SPACE_CUSHION_BYTES: u64 = 4 * 1024 * 1024; // 4 MiB
-
 /// Pure function: returns true if `free` bytes is >= required + cushion.
-pub(super) fn has_space(free: u64, required: u64) -> bool {
-    free >= required.saturating_add(SPACE_CUSHION_BYTES)
+pub(super) fn has_space(free: u64, required: u64, cushion: ByteSize) -> bool {
+    free >= required.saturating_add(cushion.bytes())
 }
 
-/// Ensure the destination filesystem has at least `required` bytes plus a small cushion.
+/// Ensure the destination filesystem has at least `required` bytes plus `cushion`.
 /// The cushion helps avoid borderline failures from metadata, journal, and temp usage.
-pub(super) fn ensure_space_for_copy(dst_dir: &Path, required: u64) -> Result<(), AriaMoveError> {
+pub(super) fn ensure_space_for_copy(
+    dst_dir: &Path,
+    required: u64,
+    cushion: ByteSize,
+) -> Result<(), AriaMoveError> {
     // Resolve actual directory for the free space query:
     // - If `dst_dir` exists and is a directory: use it directly.
     // - If it does not exist: attempt its parent (prospective file case).
@@ -75,14 +88,15 @@ pub(super) fn ensure_space_for_copy(dst_dir: &Path, required: u64) -> Result<(),
         dst_dir // Exists but not directory (caller may have passed file path); use its parent if present.
     };
 
+    let needed = (required as u128).saturating_add(cushion.bytes() as u128);
     let free = free_space_bytes(query_path).map_err(|_| AriaMoveError::InsufficientSpace {
-        required: (required as u128).saturating_add(SPACE_CUSHION_BYTES as u128),
+        required: needed,
         available: 0u128,
         dest: query_path.to_path_buf(),
     })?;
-    if !has_space(free, required) {
+    if !has_space(free, required, cushion) {
         return Err(AriaMoveError::InsufficientSpace {
-            required: (required as u128).saturating_add(SPACE_CUSHION_BYTES as u128),
+            required: needed,
             available: free as u128,
             dest: query_path.to_path_buf(),
         });
@@ -90,70 +104,104 @@ pub(super) fn ensure_space_for_copy(dst_dir: &Path, required: u64) -> Result<(),
     Ok(())
 }
 
-/// Return available free space (in bytes) on the filesystem hosting `path`.
-#[cfg(unix)]
-pub(super) fn free_space_bytes(path: &Path) -> io::Result<u64> {
-    use libc::statvfs;
-    use std::mem::MaybeUninit;
-
-    let mut stat: MaybeUninit<statvfs> = MaybeUninit::uninit();
-    let cpath = std::ffi::CString::new(path.as_os_str().as_bytes())
-        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path contains NUL"))?;
-
-    let rc = unsafe { libc::statvfs(cpath.as_ptr(), stat.as_mut_ptr()) };
-    if rc != 0 {
-        return Err(io::Error::last_os_error());
-    }
-
-    let s = unsafe { stat.assume_init() };
+/// Outcome of `watch_free_space`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpaceAlert {
+    /// No threshold set, remote destination, or free space unknown
+    Off,
+    /// Free space is fine and no warning is active
+    Ok,
+    /// Free space dropped below the threshold: warned now
+    Warned { free: u64 },
+    /// Still low; already warned for this episode
+    StillLow { free: u64 },
+    /// Free space recovered past the hysteresis band: warning cleared
+    Recovered { free: u64 },
+}
 
-    // On some platforms (e.g., older macOS), f_frsize may be 0; fall back to f_bsize.
-    // Convert underlying C types into u64 for arithmetic.
-    fn block_size_u64(s: &statvfs) -> u64 {
-        if s.f_frsize != 0 {
-            s.f_frsize as u64
-        } else {
-            s.f_bsize as u64
-        }
+/// Pure transition: `active` is whether a warning is already in effect.
+fn space_alert(active: bool, free: u64, threshold: u64) -> SpaceAlert {
+    let clear_at = threshold.saturating_add(threshold / 100 * LOW_SPACE_HYSTERESIS_PERCENT);
+    match (active, free < threshold, free >= clear_at) {
+        (false, true, _) => SpaceAlert::Warned { free },
+        (false, false, _) => SpaceAlert::Ok,
+        (true, _, true) => SpaceAlert::Recovered { free },
+        (true, _, false) => SpaceAlert::StillLow { free },
     }
-
-    let block_size = block_size_u64(&s);
-    Ok((s.f_bavail as u64).saturating_mul(block_size))
 }
 
-/// Return available free space (in bytes) on the filesystem hosting `path`.
-#[cfg(windows)]
-pub(super) fn free_space_bytes(path: &Path) -> io::Result<u64> {
-    use std::iter::once;
-    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
-
-    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(once(0)).collect();
-
-    let mut free_avail: u64 = 0;
-    let mut _total: u64 = 0;
-    let mut _total_free: u64 = 0;
-
-    let ok = unsafe {
-        GetDiskFreeSpaceExW(
-            wide.as_ptr(),
-            &mut free_avail as *mut u64,
-            &mut _total as *mut u64,
-            &mut _total_free as *mut u64,
-        )
+/// Compare completed_base's free space with `low_space_warn_mib`; log and notify (`low-space`)
+/// when it drops below, and again when it has recovered. Callers skip dry runs.
+pub fn watch_free_space(config: &Config) -> SpaceAlert {
+    let Some(mib) = config.low_space_warn_mib.filter(|&m| m > 0) else {
+        return SpaceAlert::Off;
     };
-    if ok == 0 {
-        return Err(io::Error::last_os_error());
+    if is_remote(&config.completed_base) {
+        return SpaceAlert::Off;
     }
-    Ok(free_avail)
-}
-
-/// Fallback for unsupported targets: report “unsupported”.
-#[cfg(not(any(unix, windows)))]
-pub(super) fn free_space_bytes(_path: &Path) -> io::Result<u64> {
-    Err(io::Error::new(
-        io::ErrorKind::Unsupported,
-        "free space query not supported on this platform",
-    ))
+    let base = &config.completed_base;
+    let free = match free_space_bytes(base) {
+        Ok(free) => free,
+        Err(e) => {
+            debug!(path = %base.display(), error = %e, "free space unknown; low-space check skipped");
+            return SpaceAlert::Off;
+        }
+    };
+    let threshold = mib.saturating_mul(1024 * 1024);
+    let marker = base.join(LOW_SPACE_MARKER_NAME);
+    let alert = space_alert(marker.exists(), free, threshold);
+    match alert {
+        SpaceAlert::Warned { .. } => {
+            // create_new: of several concurrent runs, only one reports the episode.
+            match OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&marker)
+            {
+                Ok(mut f) => {
+                    let _ = writeln!(f, "{}", chrono::Local::now().to_rfc3339());
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    return SpaceAlert::StillLow { free };
+                }
+                Err(e) => {
+                    warn!(path = %marker.display(), error = %e, "could not record low-space warning")
+                }
+            }
+            let message = format!(
+                "{} has {} free (warning below {})",
+                base.display(),
+                format_bytes(free),
+                format_bytes(threshold)
+            );
+            warn!(free, threshold, path = %base.display(), "completed_base is low on space");
+            notify::send(
+                config,
+                NotifyEvent::LowSpace,
+                "aria_move: completed_base is low on space",
+                &message,
+            );
+        }
+        SpaceAlert::Recovered { .. } => {
+            if fs::remove_file(&marker).is_ok() {
+                info!(free, threshold, path = %base.display(), "completed_base free space recovered");
+                notify::send(
+                    config,
+                    NotifyEvent::LowSpace,
+                    "aria_move: completed_base space recovered",
+                    &format!("{} has {} free again", base.display(), format_bytes(free)),
+                );
+            }
+        }
+        SpaceAlert::StillLow { .. } => {
+            debug!(
+                free,
+                threshold, "completed_base still low on space; already warned"
+            )
+        }
+        SpaceAlert::Ok | SpaceAlert::Off => {}
+    }
+    alert
 }
 
 // ---------- Tests ----------
@@ -170,15 +218,39 @@ mod tests {
         assert_eq!(format_bytes(5 * 1024 * 1024), "5 MiB");
     }
 
+    const CUSHION: ByteSize = crate::config::types::SPACE_CUSHION_DEFAULT;
+
     #[test]
     fn has_space_logic() {
-        let cushion = SPACE_CUSHION_BYTES;
-        assert!(has_space(cushion, 0)); // Free == cushion, required 0
-        assert!(has_space(cushion + 1, 1));
-        assert!(!has_space(cushion - 1, 0));
+        let cushion = CUSHION.bytes();
+        assert!(has_space(cushion, 0, CUSHION)); // Free == cushion, required 0
+        assert!(has_space(cushion + 1, 1, CUSHION));
+        assert!(!has_space(cushion - 1, 0, CUSHION));
         // Near saturation
         let max = u64::MAX;
-        assert!(has_space(max, max - cushion));
+        assert!(has_space(max, max - cushion, CUSHION));
+        // A zero cushion only needs the bytes themselves.
+        assert!(has_space(10, 10, ByteSize(0)));
+    }
+
+    #[test]
+    fn space_alert_has_hysteresis() {
+        let t = 1000;
+        assert_eq!(space_alert(false, 999, t), SpaceAlert::Warned { free: 999 });
+        assert_eq!(space_alert(false, 1000, t), SpaceAlert::Ok);
+        assert_eq!(
+            space_alert(true, 500, t),
+            SpaceAlert::StillLow { free: 500 }
+        );
+        // Back above the threshold but inside the band: still active.
+        assert_eq!(
+            space_alert(true, 1050, t),
+            SpaceAlert::StillLow { free: 1050 }
+        );
+        assert_eq!(
+            space_alert(true, 1100, t),
+            SpaceAlert::Recovered { free: 1100 }
+        );
     }
 
     #[test]
@@ -187,7 +259,7 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let prospective = dir.path().join("future_file.bin");
         // Required 1 byte should pass (unless disk is almost full).
-        ensure_space_for_copy(&prospective, 1).unwrap();
+        ensure_space_for_copy(&prospective, 1, CUSHION).unwrap();
     }
 
     // Helper to exercise the error path deterministically without relying on actual disk space.
@@ -197,9 +269,9 @@ mod tests {
         free: u64,
         required: u64,
     ) -> Result<(), AriaMoveError> {
-        if !has_space(free, required) {
+        if !has_space(free, required, CUSHION) {
             return Err(AriaMoveError::InsufficientSpace {
-                required: (required as u128).saturating_add(SPACE_CUSHION_BYTES as u128),
+                required: (required as u128).saturating_add(CUSHION.bytes() as u128),
                 available: free as u128,
                 dest: query_path.to_path_buf(),
             });
@@ -212,7 +284,7 @@ mod tests {
         let dir = tempfile::tempdir().unwrap();
         let prospective = dir.path().join("file.bin");
         // Choose free smaller than cushion to force error for any required > 0
-        let free = SPACE_CUSHION_BYTES - 1;
+        let free = CUSHION.bytes() - 1;
         let required = 1u64;
         let err = simulate_insufficient(&prospective, free, required).unwrap_err();
         match err {
@@ -221,7 +293,7 @@ mod tests {
                 available,
                 dest,
             } => {
-                assert_eq!(need, (required as u128) + (SPACE_CUSHION_BYTES as u128));
+                assert_eq!(need, (required as u128) + (CUSHION.bytes() as u128));
                 assert_eq!(available, free as u128);
                 assert_eq!(dest, prospective);
             }
@@ -0,0 +1,167 @@
+//! Follow-up sweep of download_base for items left behind by earlier runs.
+//! - Looks only at top-level entries of download_base (files and directories).
+//! - Skips partial downloads (.part/.aria2/.tmp/.crdownload, or a sibling `<name>.aria2`
+//!   control file), and anything modified within `recent_window` (0 = no recency check).
+//! - Dot-entries are skipped unless `include_hidden`; internal `.aria_move.*` names always are.
+//! - Skips entries matching `download_base/.ariamoveignore` (and the ignore file itself).
+//! - Directories qualify only if nothing inside is partial or recently modified.
+//! - Report-only by default; `move_items = true` moves each leftover via `move_entry` (under `on_full`),
+//!   up to `max_concurrent_moves` at once.
+//!
+//! Notes:
+//! - Concurrent moves only contend on per-path locks (source and `completed_base/<name>`),
+//!   so unrelated leftovers do not wait for each other. Report order is unaffected.
+//! - Once shutdown is requested, items not yet started are left in place.
+//! - This is a safety net for hook invocations that failed silently; it never deletes anything.
+
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::{info, warn};
+use walkdir::WalkDir;
+
+use crate::config::types::Config;
+use crate::shutdown;
+use crate::utils::{has_aria2_control_file, has_partial_extension, skip_by_hidden_policy};
+
+use super::entry::MoveOptions;
+use super::ignore::{IGNORE_FILE_NAME, IgnoreRules};
+use super::on_full::move_entry_on_full;
+
+/// Result of a sweep over download_base.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SweepReport {
+    /// Stable, non-partial leftovers found (sorted by path).
+    pub found: Vec<PathBuf>,
+    /// Leftovers moved (source, destination); only filled when moving.
+    pub moved: Vec<(PathBuf, PathBuf)>,
+    /// Leftovers that could not be moved, with the error message.
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+/// Scan download_base for leftovers, excluding `exclude` (the item just handled).
+/// When `move_items` is true, each leftover is moved into completed_base.
+pub fn sweep_download_base(
+    config: &Config,
+    exclude: Option<&Path>,
+    move_items: bool,
+) -> Result<SweepReport> {
+    let base = &config.download_base;
+    let exclude = exclude.map(|p| dunce::canonicalize(p).unwrap_or_else(|_| p.to_path_buf()));
+    let cutoff = recent_cutoff(config);
+
+    let ignore = IgnoreRules::load(base);
+    let mut report = SweepReport::default();
+    for entry in fs::read_dir(base).with_context(|| format!("read_dir {}", base.display()))? {
+        let entry = entry.with_context(|| format!("read_dir {}", base.display()))?;
+        let path = entry.path();
+        if let Some(ex) = exclude.as_ref()
+            && (dunce::canonicalize(&path).unwrap_or_else(|_| path.clone()) == *ex)
+        {
+            continue;
+        }
+        let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+        if ignore.is_ignored(base, &path, is_dir) {
+            continue;
+        }
+        if skip_by_hidden_policy(&entry.file_name(), config.include_hidden) {
+            continue;
+        }
+        if is_leftover(config, &path, cutoff) {
+            report.found.push(path);
+        }
+    }
+    report.found.sort();
+
+    for path in &report.found {
+        info!(event = "sweep_found", path = %path.display(), "Found item left behind in download_base");
+    }
+
+    if move_items {
+        let outcomes = move_all(config, &report.found)?;
+        for (path, outcome) in report.found.iter().zip(outcomes) {
+            match outcome {
+                None => {}
+                Some(Ok(dest)) => report.moved.push((path.clone(), dest)),
+                Some(Err(e)) => {
+                    warn!(event = "sweep_failed", path = %path.display(), error = %e, "Sweep could not move item");
+                    report.failed.push((path.clone(), e));
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// Move each path, up to `max_concurrent_moves` at a time; results keep the input order.
+/// None marks an item skipped because shutdown was requested.
+fn move_all(config: &Config, paths: &[PathBuf]) -> Result<Vec<Option<Result<PathBuf, String>>>> {
+    let move_one = |path: &PathBuf| {
+        if shutdown::is_requested() {
+            return None;
+        }
+        Some(
+            move_entry_on_full(config, path, &MoveOptions::default()).map_err(|e| format!("{e:#}")),
+        )
+    };
+    let threads = config.max_concurrent_moves.max(1);
+    if threads == 1 || paths.len() < 2 {
+        return Ok(paths.iter().map(move_one).collect());
+    }
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|i| format!("aria_move-sweep-{i}"))
+        .build()
+        .context("build sweep thread pool")?;
+    Ok(pool.install(|| paths.par_iter().map(move_one).collect()))
+}
+
+/// Items modified after this are still in progress (None: `recent_window` is zero).
+pub(super) fn recent_cutoff(config: &Config) -> Option<SystemTime> {
+    // A zero window is unbounded: age is not considered at all (future mtimes included).
+    (!config.recent_window.is_zero()).then(|| {
+        SystemTime::now()
+            .checked_sub(config.recent_window)
+            .unwrap_or(SystemTime::UNIX_EPOCH)
+    })
+}
+
+/// True if an entry looks complete: not partial and quiet since `cutoff`.
+pub(super) fn is_leftover(config: &Config, path: &Path, cutoff: Option<SystemTime>) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    if name == IGNORE_FILE_NAME
+        || has_partial_extension(path, config)
+        || has_aria2_control_file(path)
+    {
+        return false;
+    }
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return false;
+    };
+    if meta.is_file() {
+        return modified_before(&meta, cutoff);
+    }
+    if !meta.is_dir() {
+        return false;
+    }
+    WalkDir::new(path).into_iter().all(|e| match e {
+        Ok(e) => {
+            !has_partial_extension(e.path(), config)
+                && e.metadata()
+                    .map(|m| modified_before(&m, cutoff))
+                    .unwrap_or(false)
+        }
+        Err(_) => false,
+    })
+}
+
+fn modified_before(meta: &fs::Metadata, cutoff: Option<SystemTime>) -> bool {
+    let Some(cutoff) = cutoff else {
+        return true;
+    };
+    meta.modified().map(|m| m <= cutoff).unwrap_or(false)
+}
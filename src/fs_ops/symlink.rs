@@ -0,0 +1,208 @@
+//! Symlink sources, handled per `Config::symlink_source_policy`.
+//! - error: refuse to move (default).
+//! - move-link: move the link itself. Fast path renames it; the cross-device fallback
+//!   recreates a link with the same (unmodified) target and removes the original, so both
+//!   paths produce the same result.
+//! - move-target: move whatever the link points to (file or directory) through the normal
+//!   movers, then remove the now-dangling link.
+//!
+//! Notes:
+//! - Relative link targets are kept verbatim by move-link, exactly as rename(2) would.
+//! - With `copy_only`, move-link always recreates the link and move-target copies the
+//!   target; the original link (and target) stay in place.
+//! - move-target refuses targets that already live inside completed_base, and with
+//!   `require_source_in_base` targets outside download_base (`OutsideBase`).
+//!
+//! `guard_dest_symlink` covers the other side: a symlink already sitting at
+//! `completed_base/<name>` is refused or removed per `Config::dest_symlink_policy`, so a
+//! move never follows it out of completed_base.
+
+use anyhow::{Context, Result, anyhow, bail};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+use crate::config::types::{Config, DestSymlinkPolicy, SymlinkPolicy};
+use crate::errors::AriaMoveError;
+use crate::fsys::is_symlink;
+use crate::utils::{ensure_not_base, unique_destination};
+
+use super::atomic::{MoveOutcome, try_atomic_move_beneath};
+use super::dir_move::move_dir_with_observer;
+use super::entry::ensure_source_in_base;
+use super::file_move::move_file;
+use super::io_error_with_help;
+use super::lock::lock_error;
+use super::path_lock::acquire_path_lock_with;
+use super::progress::Observer;
+
+/// Move a symlink source according to the configured policy. Returns the final destination.
+pub(super) fn move_symlink_source(
+    config: &Config,
+    src: &Path,
+    observer: &dyn Observer,
+) -> Result<PathBuf> {
+    match config.symlink_source_policy {
+        SymlinkPolicy::Error => bail!("Refusing to move symlink: {}", src.display()),
+        SymlinkPolicy::MoveLink => move_link(config, src),
+        SymlinkPolicy::MoveTarget => move_target(config, src, observer),
+    }
+}
+
+/// Apply `dest_symlink_policy` to `dest` (normally `completed_base/<name>`).
+/// - Not a symlink: nothing to do.
+/// - error: `DestinationIsSymlink`.
+/// - replace: remove the link itself (its target is left alone); dry-run only logs it.
+pub(super) fn guard_dest_symlink(config: &Config, dest: &Path) -> Result<()> {
+    if !is_symlink(dest) {
+        return Ok(());
+    }
+    match config.dest_symlink_policy {
+        DestSymlinkPolicy::Error => {
+            Err(AriaMoveError::DestinationIsSymlink(dest.to_path_buf()).into())
+        }
+        DestSymlinkPolicy::Replace if config.dry_run => {
+            info!(dest = %dest.display(), "dry-run: would remove symlink at destination");
+            Ok(())
+        }
+        DestSymlinkPolicy::Replace => {
+            match remove_link(dest) {
+                Ok(()) => {}
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(io_error_with_help("remove destination symlink", dest)(e)),
+            }
+            info!(dest = %dest.display(), "Removed symlink at destination");
+            Ok(())
+        }
+    }
+}
+
+fn move_link(config: &Config, src: &Path) -> Result<PathBuf> {
+    ensure_not_base(&config.download_base, src)?;
+    let dest_dir = &config.completed_base;
+    let name = &super::fit_name(
+        dest_dir,
+        src.file_name()
+            .ok_or_else(|| anyhow!("Source symlink missing a file name: {}", src.display()))?,
+    );
+
+    if config.dry_run {
+        let mut dest = dest_dir.join(name);
+        if dest.exists() || is_symlink(&dest) {
+            dest = unique_destination(&dest);
+        }
+        info!(src = %src.display(), dest = %dest.display(), "dry-run: would move symlink");
+        return Ok(dest);
+    }
+
+    fs::create_dir_all(dest_dir)
+        .map_err(io_error_with_help("create destination directory", dest_dir))?;
+    let disable_locks = config.disable_locks
+        || std::env::var("ARIA_MOVE_DISABLE_LOCKS").ok().as_deref() == Some("1");
+    let _dest_lock = if disable_locks {
+        None
+    } else {
+        let dest_key = dest_dir.join(name);
+        match acquire_path_lock_with(&dest_key, config.lock_strategy) {
+            Ok(l) => Some(l),
+            Err(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                debug!(error = %e, dest = %dest_key.display(), "destination lock permission denied; proceeding without lock (diagnostic)");
+                None
+            }
+            Err(e) => return Err(lock_error(&dest_key, e)),
+        }
+    };
+
+    let mut dest = dest_dir.join(name);
+    if dest.exists() || is_symlink(&dest) {
+        dest = unique_destination(&dest);
+    }
+
+    if !config.copy_only {
+        let base = &config.completed_base;
+        match try_atomic_move_beneath(base, src, &dest, config.fsync_policy) {
+            Ok(MoveOutcome::Renamed) => {
+                info!(src = %src.display(), dest = %dest.display(), "Moved symlink");
+                return Ok(dest);
+            }
+            Ok(MoveOutcome::CrossDevice) => {}
+            Err(e) => {
+                debug!(error = %e, src = %src.display(), dest = %dest.display(), "symlink rename failed; recreating link");
+            }
+        }
+    }
+
+    let target = fs::read_link(src).with_context(|| format!("read link {}", src.display()))?;
+    create_link(&target, src, &dest)
+        .map_err(io_error_with_help("create symlink at destination", &dest))?;
+    if config.copy_only {
+        info!(src = %src.display(), dest = %dest.display(), target = %target.display(), "Recreated symlink at destination (source retained)");
+        return Ok(dest);
+    }
+    remove_link(src).map_err(io_error_with_help("remove original symlink", src))?;
+    info!(src = %src.display(), dest = %dest.display(), target = %target.display(), "Recreated symlink at destination and removed source");
+    Ok(dest)
+}
+
+fn move_target(config: &Config, src: &Path, observer: &dyn Observer) -> Result<PathBuf> {
+    let target = fs::canonicalize(src)
+        .with_context(|| format!("resolve symlink target of {}", src.display()))?;
+    if let Ok(completed) = fs::canonicalize(&config.completed_base)
+        && target.starts_with(&completed)
+    {
+        bail!(
+            "Symlink '{}' points inside completed_base ('{}'); refusing to move it",
+            src.display(),
+            target.display()
+        );
+    }
+    if config.require_source_in_base {
+        ensure_source_in_base(config, &target)?;
+    }
+
+    let meta = fs::metadata(&target).with_context(|| format!("stat {}", target.display()))?;
+    debug!(link = %src.display(), target = %target.display(), "moving symlink target");
+    let dest = if meta.is_file() {
+        move_file(config, &target)?
+    } else if meta.is_dir() {
+        move_dir_with_observer(config, &target, observer)?
+    } else {
+        bail!(
+            "Symlink target is neither a regular file nor a directory: {}",
+            target.display()
+        );
+    };
+
+    if !config.dry_run && !config.copy_only {
+        match remove_link(src) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(io_error_with_help("remove symlink", src)(e)),
+        }
+    }
+    Ok(dest)
+}
+
+#[cfg(unix)]
+fn create_link(target: &Path, _src: &Path, dest: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, dest)
+}
+
+#[cfg(windows)]
+fn create_link(target: &Path, src: &Path, dest: &Path) -> io::Result<()> {
+    if fs::metadata(src).map(|m| m.is_dir()).unwrap_or(false) {
+        std::os::windows::fs::symlink_dir(target, dest)
+    } else {
+        std::os::windows::fs::symlink_file(target, dest)
+    }
+}
+
+/// Remove a symlink without touching its target (directory links need remove_dir on Windows).
+fn remove_link(path: &Path) -> io::Result<()> {
+    #[cfg(windows)]
+    if fs::metadata(path).map(|m| m.is_dir()).unwrap_or(false) {
+        return fs::remove_dir(path);
+    }
+    fs::remove_file(path)
+}
@@ -0,0 +1,128 @@
+//! Per-move phase timings and copy throughput.
+//! - `MoveTimings`: probe, lock wait, copy, rename and fsync durations plus bytes copied.
+//! - `collect`: run a move with a recorder installed and return what it recorded.
+//! - `timed` / `add_bytes`: used by the movers to record into the active recorder.
+//!
+//! Notes:
+//! - The recorder is thread-local, so the movers keep their signatures; phases measured
+//!   outside a `collect` call (or on rayon workers) are not recorded.
+//! - Phases that did not happen stay zero: a same-filesystem rename has no copy time.
+//! - Throughput is bytes copied / copy time, so renames report none.
+
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+/// Timings of one move. `total` covers the whole call, including phases not listed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MoveTimings {
+    /// Waiting for the source to stop changing (`stable_file_probe`).
+    pub probe: Duration,
+    /// Waiting for source and destination directory locks.
+    pub lock_wait: Duration,
+    /// Copying data across filesystems (zero for a rename).
+    pub copy: Duration,
+    /// The atomic rename into place (includes its directory fsync on Unix).
+    pub rename: Duration,
+    /// Explicit directory fsyncs after the move.
+    pub fsync: Duration,
+    pub total: Duration,
+    /// Bytes copied (zero for a rename).
+    pub bytes_copied: u64,
+}
+
+impl MoveTimings {
+    /// Copy throughput in MB/s (10^6 bytes), or None if nothing was copied.
+    pub fn throughput_mb_s(&self) -> Option<f64> {
+        let secs = self.copy.as_secs_f64();
+        (self.bytes_copied > 0 && secs > 0.0).then(|| self.bytes_copied as f64 / 1e6 / secs)
+    }
+}
+
+/// A phase of `MoveTimings`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Phase {
+    Probe,
+    LockWait,
+    Copy,
+    Rename,
+    Fsync,
+}
+
+thread_local! {
+    static RECORDER: RefCell<Option<MoveTimings>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with a fresh recorder; returns its result and the recorded timings.
+/// Nested calls record into their own recorder and restore the outer one afterwards.
+pub(super) fn collect<R>(f: impl FnOnce() -> R) -> (R, MoveTimings) {
+    let outer = RECORDER.with(|r| r.borrow_mut().replace(MoveTimings::default()));
+    let start = Instant::now();
+    let out = f();
+    let mut timings = RECORDER
+        .with(|r| std::mem::replace(&mut *r.borrow_mut(), outer))
+        .unwrap_or_default();
+    timings.total = start.elapsed();
+    (out, timings)
+}
+
+/// Run `f` and add its duration to `phase` of the active recorder (if any).
+pub(super) fn timed<R>(phase: Phase, f: impl FnOnce() -> R) -> R {
+    let start = Instant::now();
+    let out = f();
+    let elapsed = start.elapsed();
+    RECORDER.with(|r| {
+        if let Some(t) = r.borrow_mut().as_mut() {
+            let slot = match phase {
+                Phase::Probe => &mut t.probe,
+                Phase::LockWait => &mut t.lock_wait,
+                Phase::Copy => &mut t.copy,
+                Phase::Rename => &mut t.rename,
+                Phase::Fsync => &mut t.fsync,
+            };
+            *slot += elapsed;
+        }
+    });
+    out
+}
+
+/// Count `n` bytes as copied in the active recorder (if any).
+pub(super) fn add_bytes(n: u64) {
+    RECORDER.with(|r| {
+        if let Some(t) = r.borrow_mut().as_mut() {
+            t.bytes_copied = t.bytes_copied.saturating_add(n);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_only_inside_collect() {
+        timed(Phase::Copy, || std::thread::sleep(Duration::from_millis(2)));
+        add_bytes(10);
+
+        let ((), t) = collect(|| {
+            timed(Phase::Copy, || std::thread::sleep(Duration::from_millis(5)));
+            add_bytes(5_000_000);
+        });
+        assert!(t.copy >= Duration::from_millis(5));
+        assert!(t.total >= t.copy);
+        assert_eq!(t.bytes_copied, 5_000_000);
+        assert_eq!(t.rename, Duration::ZERO);
+        assert!(t.throughput_mb_s().unwrap() > 0.0);
+    }
+
+    #[test]
+    fn nested_collect_restores_the_outer_recorder() {
+        let ((), outer) = collect(|| {
+            add_bytes(1);
+            let ((), inner) = collect(|| add_bytes(2));
+            assert_eq!(inner.bytes_copied, 2);
+            add_bytes(3);
+        });
+        assert_eq!(outer.bytes_copied, 4);
+        assert_eq!(MoveTimings::default().throughput_mb_s(), None);
+    }
+}
@@ -0,0 +1,159 @@
+//! `aria_move undo`: move the most recent move back to where it came from.
+//! - The move to reverse is the newest `moved` record of `audit_log` that no `undone`
+//!   record answers yet, so repeated undos walk back through the history.
+//! - The item goes back under its original name via `move_entry`, with the destination's
+//!   directory as download_base and the source's as completed_base; afterwards an `undone`
+//!   record (`src` = where it was, `dest` = where it is now) is appended to the log.
+//!
+//! Notes:
+//! - Refused when the moved item is gone, when something else now sits at the original
+//!   path, and for remote destinations.
+//! - Handlers, provenance tags, notifications and the audit record of the inner move are
+//!   left out: the undo is its own record.
+//! - Only the final 256 KiB of the log are read (see `audit::tail`).
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::config::types::Config;
+
+use super::audit::{self, AuditEntry, audit_history};
+use super::entry::move_entry;
+use super::remote::is_remote;
+
+/// Outcome of `undo_last_move`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UndoReport {
+    /// Sequence number of the reversed `moved` record
+    pub seq: u64,
+    /// Where the item was (the move's destination)
+    pub from: PathBuf,
+    /// Where it is now (the move's source)
+    pub to: PathBuf,
+    /// Only reported (`dry_run`); nothing was moved
+    pub dry_run: bool,
+}
+
+/// Reverse the newest move in `config.audit_log` that has not been undone.
+pub fn undo_last_move(config: &Config) -> Result<UndoReport> {
+    let Some(log) = config.audit_log.as_deref() else {
+        bail!("undo needs the audit log: set audit_log in the config or pass --audit-log");
+    };
+    if is_remote(&config.completed_base) {
+        bail!("undo cannot fetch items back from a remote completed_base");
+    }
+    let entries = audit_history(log, usize::MAX)?;
+    let Some(last) = last_undoable(&entries) else {
+        bail!("nothing to undo in '{}'", log.display());
+    };
+    let (from, to) = (last.dest.clone().unwrap_or_default(), last.src.clone());
+    if fs::symlink_metadata(&from).is_err() {
+        bail!(
+            "cannot undo record {}: '{}' no longer exists",
+            last.seq,
+            from.display()
+        );
+    }
+    if fs::symlink_metadata(&to).is_ok() {
+        bail!(
+            "cannot undo record {}: '{}' already exists",
+            last.seq,
+            to.display()
+        );
+    }
+    let report = UndoReport {
+        seq: last.seq,
+        from,
+        to,
+        dry_run: config.dry_run,
+    };
+    if config.dry_run {
+        info!(from = %report.from.display(), to = %report.to.display(), "dry-run: would undo move");
+        return Ok(report);
+    }
+
+    let back = move_back_config(config, &report.from, &report.to)?;
+    let moved = move_entry(&back, &report.from)?;
+    let report = UndoReport {
+        to: moved,
+        ..report
+    };
+    audit::append(
+        log,
+        "undone",
+        &report.from,
+        Some(report.to.to_string_lossy().into_owned()),
+        None,
+    )?;
+    info!(seq = report.seq, from = %report.from.display(), to = %report.to.display(), "Move undone");
+    Ok(report)
+}
+
+/// Newest `moved` record not answered by a later `undone` record for the same paths.
+fn last_undoable(entries: &[AuditEntry]) -> Option<&AuditEntry> {
+    let mut undone: HashSet<(&Path, &Path)> = HashSet::new();
+    for entry in entries.iter().rev() {
+        let Some(dest) = entry.dest.as_deref() else {
+            continue;
+        };
+        match entry.outcome.as_str() {
+            "undone" => {
+                undone.insert((dest, &entry.src));
+            }
+            "moved" if !undone.remove(&(entry.src.as_path(), dest)) => return Some(entry),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// `config` for moving `from` back to `to`: plain move under `to`'s name, nothing else.
+fn move_back_config(config: &Config, from: &Path, to: &Path) -> Result<Config> {
+    let (Some(from_dir), Some(to_dir)) = (from.parent(), to.parent()) else {
+        bail!("cannot undo a move of a filesystem root");
+    };
+    fs::create_dir_all(to_dir).with_context(|| format!("create '{}'", to_dir.display()))?;
+    let mut cfg = config.clone();
+    cfg.download_base = from_dir.to_path_buf();
+    cfg.completed_base = to_dir.to_path_buf();
+    cfg.require_source_in_base = false;
+    cfg.content_sanity_check = false;
+    cfg.copy_only = false;
+    cfg.handlers.clear();
+    cfg.notifiers.clear();
+    cfg.audit_log = None;
+    cfg.tag_destination = false;
+    Ok(cfg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(seq: u64, outcome: &str, src: &str, dest: &str) -> AuditEntry {
+        AuditEntry {
+            seq,
+            time: String::new(),
+            outcome: outcome.into(),
+            src: src.into(),
+            dest: Some(dest.into()),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn undone_moves_are_skipped_newest_first() {
+        let log = vec![
+            entry(1, "moved", "/in/a", "/out/a"),
+            entry(2, "moved", "/in/b", "/out/b"),
+            entry(3, "undone", "/out/b", "/in/b"),
+        ];
+        assert_eq!(last_undoable(&log).map(|e| e.seq), Some(1));
+        let log = [log, vec![entry(4, "undone", "/out/a", "/in/a")]].concat();
+        assert_eq!(last_undoable(&log), None);
+    }
+}
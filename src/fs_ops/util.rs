@@ -2,13 +2,20 @@
 //!
 //! - unique_temp_path: generate a unique temporary path inside a destination directory
 //! - is_cross_device: detect cross-filesystem rename errors (EXDEV/ERROR_NOT_SAME_DEVICE)
-//! - fsync_dir: best-effort directory fsync after a rename (Unix only)
+//! - fsync_dir: best-effort directory fsync after a rename (Unix only); `sync_dir` applies
+//!   the configured `fsync_policy`, and `flush_file` is the Windows stand-in
+//! - resume_temp_path / resume_matches: resume temps namespaced per source, and the check
+//!   (per `resume_verify`) that an existing temp really is a prefix of the source
+//! - rename_quirk / verify_copy: overlayfs/FUSE detection for a move, and the byte-for-byte
+//!   check that replaces trusting the rename there
 
 // remove unused File import
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::io;
 use std::path::{Path, PathBuf};
+
+use crate::config::types::{FsyncPolicy, ResumeVerify};
 // no longer need timestamp imports; deterministic resume temp uses hashing
 
 // unique_temp_path removed in favor of deterministic resume_temp_path.
@@ -40,8 +47,7 @@ pub(super) fn is_cross_device(e: &io::Error) -> bool {
 #[cfg(unix)]
 pub(super) fn fsync_dir(dir: &Path) -> io::Result<()> {
     use std::fs::File;
-    let f = File::open(dir)?;
-    f.sync_all()
+    super::fs_trace::traced("fsync_dir", dir, || File::open(dir)?.sync_all())
 }
 
 #[cfg(windows)]
@@ -49,14 +55,37 @@ pub(super) fn fsync_dir(_dir: &Path) -> io::Result<()> {
     Ok(())
 }
 
-/// Deterministic resume temp path for a given final destination.
-/// Format: ".aria_move.resume.<hexhash>.tmp" where hash is of the absolute dest path.
+/// `fsync_dir` if `policy` syncs directories; otherwise a no-op.
+pub(super) fn sync_dir(policy: FsyncPolicy, dir: &Path) -> io::Result<()> {
+    if policy.syncs_dirs() {
+        fsync_dir(dir)
+    } else {
+        Ok(())
+    }
+}
+
+/// Flush a renamed file with FlushFileBuffers — Windows only, where the directory holding
+/// the rename cannot be synced; flushing the file commits its metadata (and name) instead.
+#[cfg(windows)]
+pub(super) fn flush_file(path: &Path) -> io::Result<()> {
+    super::fs_trace::traced("fsync", path, || {
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(path)?
+            .sync_all()
+    })
+}
+
+/// Deterministic resume temp path for copying `src` to `dest`.
+/// Format: ".aria_move.resume.<hexhash>.tmp" where the hash covers the dest path and the
+/// source's identity (`source_id`), so different sources aimed at the same destination name
+/// never share (and corrupt) a temp, while a retry of the same source finds its own.
 /// Public for use in integration tests to simulate partial copies.
-pub fn resume_temp_path(dest: &Path) -> PathBuf {
+pub fn resume_temp_path(src: &Path, dest: &Path) -> PathBuf {
     let mut hasher = DefaultHasher::new();
-    // Hash the full, lossy-display path for stability across runs.
-    // Canonicalization is optional; use as-provided to match caller's computed dest.
+    // Use the dest as provided to match the caller's computed destination.
     dest.to_string_lossy().hash(&mut hasher);
+    source_id(src).hash(&mut hasher);
     let h = hasher.finish();
     let name = format!(".aria_move.resume.{:016x}.tmp", h);
     match dest.parent() {
@@ -64,3 +93,143 @@ pub fn resume_temp_path(dest: &Path) -> PathBuf {
         None => PathBuf::from(name),
     }
 }
+
+/// Identity of a source file that survives renames and restarts: device and inode on Unix,
+/// creation time and size on Windows; the path if the file cannot be stat'ed.
+fn source_id(src: &Path) -> String {
+    let Ok(meta) = std::fs::metadata(src) else {
+        return src.to_string_lossy().into_owned();
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        format!("{}:{}", meta.dev(), meta.ino())
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        format!("{}:{}", meta.creation_time(), meta.file_size())
+    }
+}
+
+/// The overlayfs/FUSE filesystem under `src` or `dest_dir`, if any. Moves touching one copy
+/// (then `verify_copy`) instead of renaming; the decision is logged here.
+pub(super) fn rename_quirk(src: &Path, dest_dir: &Path) -> Option<&'static str> {
+    let fs = src
+        .parent()
+        .and_then(crate::fsys::rename_quirk)
+        .or_else(|| crate::fsys::rename_quirk(dest_dir))?;
+    tracing::info!(fs, src = %src.display(), dest = %dest_dir.display(), "rename is unreliable on this filesystem; copying with verification");
+    Some(fs)
+}
+
+/// Err unless `dest` has the same length and bytes as `src`.
+pub(super) fn verify_copy(src: &Path, dest: &Path) -> io::Result<()> {
+    let len = std::fs::metadata(src)?.len();
+    if std::fs::metadata(dest)?.len() != len || !resume_matches(src, dest, len, ResumeVerify::Full)?
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "copy of '{}' at '{}' does not match the source",
+                src.display(),
+                dest.display()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Bytes compared by `resume_matches` in `ResumeVerify::Tail` mode (the end of the temp).
+const RESUME_TAIL_BYTES: u64 = 4 * 1024 * 1024;
+
+/// True if the first `len` bytes of `tmp` match `src` as far as `verify` checks,
+/// i.e. the temp plausibly holds a prefix of this source and is safe to resume.
+/// - Tail: the last 4 MiB of the prefix; Full: the whole prefix; None: no check.
+pub(super) fn resume_matches(
+    src: &Path,
+    tmp: &Path,
+    len: u64,
+    verify: ResumeVerify,
+) -> io::Result<bool> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let start = match verify {
+        ResumeVerify::None => return Ok(true),
+        ResumeVerify::Tail => len.saturating_sub(RESUME_TAIL_BYTES),
+        ResumeVerify::Full => 0,
+    };
+    let open_at = |path: &Path| -> io::Result<_> {
+        let mut f = std::fs::File::open(path)?;
+        f.seek(SeekFrom::Start(start))?;
+        Ok(f.take(len - start))
+    };
+    let (mut ours, mut theirs) = (open_at(src)?, open_at(tmp)?);
+    let (mut a, mut b) = (vec![0u8; 64 * 1024], vec![0u8; 64 * 1024]);
+    let mut compared = 0;
+    loop {
+        let n = theirs.read(&mut b)?;
+        if n == 0 {
+            // A temp that shrank since it was stat'ed is not the prefix we were told about.
+            return Ok(compared == len - start);
+        }
+        // The source is at least `len` bytes (checked by the caller); EOF here means it shrank.
+        ours.read_exact(&mut a[..n])?;
+        if a[..n] != b[..n] {
+            return Ok(false);
+        }
+        compared += n as u64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn resume_check_depends_on_mode() {
+        let td = tempfile::tempdir().unwrap();
+        let src = td.path().join("src.bin");
+        let tmp = td.path().join("tmp.bin");
+        let len = RESUME_TAIL_BYTES + 1024;
+        let content: Vec<u8> = (0..len + 4096).map(|i| (i % 251) as u8).collect();
+        fs::write(&src, &content).unwrap();
+
+        // Matching prefix passes every mode.
+        fs::write(&tmp, &content[..len as usize]).unwrap();
+        for verify in [ResumeVerify::Tail, ResumeVerify::Full, ResumeVerify::None] {
+            assert!(resume_matches(&src, &tmp, len, verify).unwrap(), "{verify}");
+        }
+
+        // A difference before the tail window is only caught by a full check.
+        let mut head_differs = content[..len as usize].to_vec();
+        head_differs[0] ^= 0xff;
+        fs::write(&tmp, &head_differs).unwrap();
+        assert!(resume_matches(&src, &tmp, len, ResumeVerify::Tail).unwrap());
+        assert!(!resume_matches(&src, &tmp, len, ResumeVerify::Full).unwrap());
+
+        // A difference in the tail is caught by tail and full, never by none.
+        let mut tail_differs = content[..len as usize].to_vec();
+        tail_differs[len as usize - 1] ^= 0xff;
+        fs::write(&tmp, &tail_differs).unwrap();
+        assert!(!resume_matches(&src, &tmp, len, ResumeVerify::Tail).unwrap());
+        assert!(!resume_matches(&src, &tmp, len, ResumeVerify::Full).unwrap());
+        assert!(resume_matches(&src, &tmp, len, ResumeVerify::None).unwrap());
+    }
+
+    #[test]
+    fn verify_copy_requires_identical_bytes() {
+        let td = tempfile::tempdir().unwrap();
+        let src = td.path().join("src.bin");
+        let dest = td.path().join("dest.bin");
+        fs::write(&src, b"payload").unwrap();
+
+        fs::write(&dest, b"payload").unwrap();
+        assert!(verify_copy(&src, &dest).is_ok());
+        fs::write(&dest, b"paylod").unwrap();
+        assert!(verify_copy(&src, &dest).is_err());
+        fs::write(&dest, b"PAYLOAD").unwrap();
+        assert!(verify_copy(&src, &dest).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+//! Filesystem queries shared by the config, platform and fs_ops layers.
+//! - `free_space_bytes`: bytes available to the current user on the filesystem holding a path.
+//! - `same_filesystem`: whether two paths live on one filesystem (a rename between them works).
+//! - `path_has_symlink_ancestor`: whether any existing ancestor of a path is a link.
+//! - `is_symlink`: whether a path itself is a link (not followed).
+//! - `name_max`: the longest file name the filesystem holding a directory accepts.
+//! - `rename_quirk`: whether a path lives on overlayfs or FUSE, where a rename can report
+//!   success while copying, or fail in odd ways.
+//! - `fs_type` / `mount_flags`: filesystem name and notable mount flags, for diagnostics.
+//!
+//! Notes:
+//! - The OS-specific syscalls stay in `platform` (statvfs / GetDiskFreeSpaceExW, st_dev /
+//!   volume serial); everything else should call them through here.
+//! - "Link" means a symlink, or on Windows any symlink or junction (name-surrogate reparse
+//!   point, see `platform::is_link_reparse_point`).
+//! - Ancestor checks behave the same on every OS; callers decide where to enforce them.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Bytes available to unprivileged users on the filesystem holding `path` (must exist).
+pub fn free_space_bytes(path: &Path) -> io::Result<u64> {
+    crate::platform::check_disk_space(path)
+}
+
+/// True if `a` and `b` are on the same filesystem (st_dev on Unix, volume serial on Windows).
+/// Paths that don't exist yet are judged by their nearest existing ancestor, so a planned
+/// destination can be checked before it is created.
+pub fn same_filesystem(a: &Path, b: &Path) -> io::Result<bool> {
+    let id = |p: &Path| crate::platform::volume_id(existing_ancestor(p)?);
+    Ok(id(a)? == id(b)?)
+}
+
+/// Longest file name (bytes) accepted in the directory `dir`, judged by its nearest existing
+/// ancestor. None if the platform does not report one.
+pub fn name_max(dir: &Path) -> Option<usize> {
+    crate::platform::name_max(existing_ancestor(dir).ok()?)
+}
+
+/// "overlayfs" or "fuse" if the filesystem holding `path` (or its nearest existing ancestor)
+/// is one whose renames cannot be trusted; None otherwise, and on errors.
+pub fn rename_quirk(path: &Path) -> Option<&'static str> {
+    crate::platform::rename_quirk_fs(existing_ancestor(path).ok()?)
+}
+
+/// Name of the filesystem holding `path` (or its nearest existing ancestor), if known.
+pub fn fs_type(path: &Path) -> Option<String> {
+    crate::platform::fstype_name(existing_ancestor(path).ok()?)
+}
+
+/// Notable mount flags ("ro", "nosuid", ...) of the filesystem holding `path` (or its
+/// nearest existing ancestor).
+pub fn mount_flags(path: &Path) -> io::Result<Vec<&'static str>> {
+    crate::platform::mount_flags(existing_ancestor(path)?)
+}
+
+/// `path` itself if it exists, else its closest existing ancestor ("." for relative paths).
+pub(crate) fn existing_ancestor(path: &Path) -> io::Result<&Path> {
+    path.ancestors()
+        .map(|p| {
+            if p.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                p
+            }
+        })
+        .find(|p| fs::symlink_metadata(p).is_ok())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no existing ancestor of '{}'", path.display()),
+            )
+        })
+}
+
+/// True if `path` itself is a symlink or junction (does not follow it). Errors report false.
+pub fn is_symlink(path: &Path) -> bool {
+    is_link(path).unwrap_or(false)
+}
+
+fn is_link(path: &Path) -> io::Result<bool> {
+    let meta = fs::symlink_metadata(path)?;
+    #[cfg(windows)]
+    if !meta.file_type().is_symlink() {
+        return crate::platform::is_link_reparse_point(path);
+    }
+    Ok(meta.file_type().is_symlink())
+}
+
+/// Return true if any existing ancestor of `path` is a symlink or junction. `path` itself
+/// is not checked.
+/// Non-existent ancestors are skipped safely; relative paths are checked as given.
+pub fn path_has_symlink_ancestor(path: &Path) -> io::Result<bool> {
+    let mut cur = path.parent();
+    while let Some(dir) = cur {
+        if !dir.as_os_str().is_empty() && dir.exists() && is_link(dir)? {
+            return Ok(true);
+        }
+        cur = dir.parent();
+    }
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn free_space_is_reported_for_existing_dirs_only() {
+        let dir = tempdir().unwrap();
+        assert!(free_space_bytes(dir.path()).unwrap() > 0);
+        assert!(free_space_bytes(&dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn same_filesystem_within_one_dir_and_for_planned_paths() {
+        let dir = tempdir().unwrap();
+        let a = dir.path().join("a");
+        fs::create_dir(&a).unwrap();
+        assert!(same_filesystem(&a, dir.path()).unwrap());
+        // Not created yet: judged by the existing parent.
+        assert!(same_filesystem(&a, &dir.path().join("later").join("file.bin")).unwrap());
+    }
+
+    #[test]
+    fn plain_and_missing_paths_have_no_symlink_ancestor() {
+        let dir = tempdir().unwrap();
+        let base = dunce::canonicalize(dir.path()).unwrap();
+        let nested = base.join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        assert!(!path_has_symlink_ancestor(&nested.join("file.log")).unwrap());
+        assert!(!path_has_symlink_ancestor(&base.join("x").join("y").join("z")).unwrap());
+        assert!(!path_has_symlink_ancestor(Path::new("relative.log")).unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_ancestor_is_detected_but_the_path_itself_is_not() {
+        use std::os::unix::fs::symlink;
+        let dir = tempdir().unwrap();
+        let base = dunce::canonicalize(dir.path()).unwrap();
+        let real = base.join("real");
+        fs::create_dir_all(real.join("sub")).unwrap();
+        let link = base.join("link");
+        symlink(&real, &link).unwrap();
+
+        assert!(path_has_symlink_ancestor(&link.join("sub").join("file")).unwrap());
+        assert!(!path_has_symlink_ancestor(&link).unwrap());
+        assert!(is_symlink(&link));
+        assert!(!is_symlink(&real));
+        assert!(!is_symlink(&base.join("missing")));
+    }
+}
@@ -0,0 +1,71 @@
+//! Heartbeat file for container health checks.
+//! - Every successful run rewrites `heartbeat_file` with the current time (RFC 3339).
+//! - `aria_move healthcheck` succeeds only if that file was modified within `--max-age`.
+//!
+//! Notes:
+//! - The file's mtime is authoritative; the content is informational for humans.
+//! - Dry runs do not touch the heartbeat.
+
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// Record a successful run in `path` (parent directories are created).
+pub fn touch(path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent()
+        && !dir.as_os_str().is_empty()
+    {
+        fs::create_dir_all(dir).with_context(|| format!("create '{}'", dir.display()))?;
+    }
+    let stamp = chrono::Local::now().to_rfc3339();
+    fs::write(path, format!("{stamp}\n"))
+        .with_context(|| format!("write heartbeat '{}'", path.display()))
+}
+
+/// Age of the heartbeat in `path`; an error if it is missing.
+pub fn age(path: &Path) -> Result<Duration> {
+    let modified = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .with_context(|| format!("unhealthy: no heartbeat at '{}'", path.display()))?;
+    // A timestamp in the future (clock skew) counts as fresh.
+    Ok(SystemTime::now()
+        .duration_since(modified)
+        .unwrap_or_default())
+}
+
+/// Age of the heartbeat in `path`; an error if it is missing or older than `max_age`.
+pub fn check(path: &Path, max_age: Duration) -> Result<Duration> {
+    let age = age(path)?;
+    if age > max_age {
+        bail!(
+            "unhealthy: last successful run {}s ago (limit {}s), heartbeat '{}'",
+            age.as_secs(),
+            max_age.as_secs(),
+            path.display()
+        );
+    }
+    Ok(age)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use filetime::{FileTime, set_file_mtime};
+    use tempfile::tempdir;
+
+    #[test]
+    fn fresh_heartbeat_is_healthy_and_stale_one_is_not() {
+        let dir = tempdir().unwrap();
+        let hb = dir.path().join("state").join("heartbeat");
+        assert!(check(&hb, Duration::from_secs(60)).is_err());
+
+        touch(&hb).unwrap();
+        assert!(check(&hb, Duration::from_secs(60)).is_ok());
+
+        let old = SystemTime::now() - Duration::from_secs(600);
+        set_file_mtime(&hb, FileTime::from_system_time(old)).unwrap();
+        let err = check(&hb, Duration::from_secs(60)).unwrap_err();
+        assert!(err.to_string().contains("limit 60s"), "{err}");
+    }
+}
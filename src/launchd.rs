@@ -0,0 +1,130 @@
+//! launchd agent support (macOS).
+//! Generates a per-user LaunchAgent that runs `aria_move --quiet --sweep-move` whenever
+//! download_base changes (WatchPaths), plus a periodic StartInterval as a safety net.
+//!
+//! Notes:
+//! - aria_move never daemonizes; launchd starts one short run per trigger and SIGTERM from
+//!   `launchctl bootout` is handled like Ctrl-C (cooperative shutdown).
+//! - The plist points at the current executable and the resolved config file, so re-run
+//!   `aria_move launchd install` after moving the binary or the config.
+
+use anyhow::{Context, Result, bail};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// launchd job label (also the plist file stem).
+pub const LABEL: &str = "io.github.macg4dave.aria_move";
+
+/// Everything the generated plist refers to.
+pub struct AgentSpec {
+    pub program: PathBuf,
+    pub config: PathBuf,
+    pub watch: PathBuf,
+    pub interval_secs: u64,
+}
+
+/// Render the LaunchAgent plist for `spec`.
+pub fn render_plist(spec: &AgentSpec) -> String {
+    let args = [
+        spec.program.to_string_lossy().into_owned(),
+        "--config".to_string(),
+        spec.config.to_string_lossy().into_owned(),
+        "--quiet".to_string(),
+        "--sweep-move".to_string(),
+    ];
+    let mut out = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" ",
+        "\"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n",
+        "<plist version=\"1.0\">\n<dict>\n",
+    ));
+    out.push_str(&format!(
+        "  <key>Label</key>\n  <string>{}</string>\n",
+        escape(LABEL)
+    ));
+    out.push_str("  <key>ProgramArguments</key>\n  <array>\n");
+    for a in &args {
+        out.push_str(&format!("    <string>{}</string>\n", escape(a)));
+    }
+    out.push_str("  </array>\n");
+    out.push_str(&format!(
+        "  <key>WatchPaths</key>\n  <array>\n    <string>{}</string>\n  </array>\n",
+        escape(&spec.watch.to_string_lossy())
+    ));
+    out.push_str(&format!(
+        "  <key>StartInterval</key>\n  <integer>{}</integer>\n",
+        spec.interval_secs
+    ));
+    // Coalesce bursts of directory changes (aria2 writes often) into one run.
+    out.push_str("  <key>ThrottleInterval</key>\n  <integer>30</integer>\n");
+    out.push_str("  <key>ProcessType</key>\n  <string>Background</string>\n");
+    out.push_str("</dict>\n</plist>\n");
+    out
+}
+
+/// `~/Library/LaunchAgents/<LABEL>.plist`.
+pub fn agent_path() -> Result<PathBuf> {
+    let home = dirs::home_dir().context("resolve home directory for LaunchAgents")?;
+    Ok(home
+        .join("Library")
+        .join("LaunchAgents")
+        .join(format!("{LABEL}.plist")))
+}
+
+/// Write the plist for `spec` to the LaunchAgents directory; returns its path.
+pub fn install(spec: &AgentSpec) -> Result<PathBuf> {
+    if !cfg!(target_os = "macos") {
+        bail!(
+            "launchd agents are only supported on macOS; use `aria_move launchd print` to inspect the plist"
+        );
+    }
+    let path = agent_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("create '{}'", dir.display()))?;
+    }
+    fs::write(&path, render_plist(spec)).with_context(|| format!("write '{}'", path.display()))?;
+    Ok(path)
+}
+
+/// Remove the installed plist. Returns false if there was none.
+pub fn uninstall() -> Result<bool> {
+    let path = agent_path()?;
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(e).with_context(|| format!("remove '{}'", path.display())),
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Absolute form of `p` without requiring it to exist.
+pub fn absolute(p: &Path) -> PathBuf {
+    std::path::absolute(p).unwrap_or_else(|_| p.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plist_runs_sweep_move_on_download_base_changes() {
+        let spec = AgentSpec {
+            program: PathBuf::from("/usr/local/bin/aria_move"),
+            config: PathBuf::from("/Users/me/Library/Application Support/aria_move/config.xml"),
+            watch: PathBuf::from("/Volumes/Data/R&D <incoming>"),
+            interval_secs: 300,
+        };
+        let plist = render_plist(&spec);
+        assert!(plist.contains(&format!("<string>{LABEL}</string>")));
+        assert!(plist.contains("<string>/usr/local/bin/aria_move</string>"));
+        assert!(plist.contains("<string>--sweep-move</string>"));
+        assert!(plist.contains("<string>/Volumes/Data/R&amp;D &lt;incoming&gt;</string>"));
+        assert!(plist.contains("<integer>300</integer>"));
+        assert!(plist.trim_end().ends_with("</plist>"));
+    }
+}
@@ -3,7 +3,9 @@
 //!
 //! Notes:
 //! - Re-exports come from concrete submodules to avoid accidental breakage if mod.rs changes.
+//! - `api` is the semver-stable facade (`Mover`, `MoveOptions`, `MoveReport`, `Error`).
 //! - Prefer the `prelude` for downstream crates/tests to keep imports tidy.
+//! - Low-level fs_ops items (locks, atomic rename) require the `unstable` feature.
 //!
 //! Example
 //! -------
@@ -12,11 +14,12 @@
 //! use aria_move::prelude::*;
 //!
 //! fn run() -> AMResult<()> {
-//!     // Build a default config and wire minimal fields
-//!     let mut cfg = Config::default();
-//!     // cfg.download_base = "/incoming".into();
-//!     // cfg.completed_base = "/completed".into();
-//!     
+//!     // Build a validated config (creates missing bases, rejects overlapping ones)
+//!     let cfg = Config::builder()
+//!         .download_base("/incoming")
+//!         .completed_base("/completed")
+//!         .build()?;
+//!
 //!     // Discover default config/log paths if needed
 //!     let _default_cfg = default_config_path()?;
 //!
@@ -28,26 +31,42 @@
 //! # let _ = AriaMoveError::Interrupted;
 //! ```
 
+pub mod api;
+pub mod aria2_rpc;
 pub mod cli;
 pub mod config;
 pub mod errors;
 pub mod fs_ops;
+pub mod fsys;
+pub mod messages;
+pub mod notify;
 pub mod output;
 pub mod platform;
 pub mod shutdown;
 pub mod utils;
 
 // Re-exports for tests and binaries
-pub use config::types::{Config, LogLevel};
+pub use config::builder::ConfigBuilder;
+pub use config::types::{
+    ByteSize, Config, ConsoleStream, CopyBackend, CopyOrder, DestSymlinkPolicy, FsyncPolicy,
+    HumanDuration, LockStrategy, LogLevel, LogTimeFormat, PartialPreset, ResumeVerify, RpcAction,
+    SymlinkPolicy,
+};
 
 // Public API
-pub use config::paths::{default_config_path, default_log_path, path_has_symlink_ancestor};
+pub use config::paths::{default_config_path, default_log_path};
 pub use config::xml::{
     load_config_from_default_xml, load_config_from_xml_env, load_config_from_xml_path,
 };
+pub use fsys::path_has_symlink_ancestor;
 
 // Operations
-pub use fs_ops::{move_dir, move_entry, move_file, resolve_source_path, safe_copy_and_rename};
+pub use fs_ops::{
+    Candidate, CleanReport, MirrorReport, MoveOptions, MovePlan, MoveTimings, clean_bases,
+    list_candidates, mirror_download_base, move_dir, move_dir_with_observer, move_entry,
+    move_entry_with, move_file, plan_move, resolve_source_path, safe_copy_and_rename,
+    sweep_download_base,
+};
 
 // Errors
 pub use errors::AriaMoveError;
@@ -58,14 +77,22 @@ pub type AMResult<T> = anyhow::Result<T>;
 /// Common imports for applications/tests using aria_move.
 pub mod prelude {
     pub use crate::AMResult;
+    pub use crate::config::builder::ConfigBuilder;
     pub use crate::config::paths::default_config_path;
-    pub use crate::config::types::{Config, LogLevel};
+    pub use crate::config::types::{
+        Config, ConsoleStream, CopyBackend, CopyOrder, DestSymlinkPolicy, FsyncPolicy,
+        LockStrategy, LogLevel, PartialPreset, ResumeVerify, SymlinkPolicy,
+    };
     pub use crate::errors::AriaMoveError;
     pub use crate::errors::AriaMoveError as Error;
-    pub use crate::errors::AriaMoveError as E;
-    pub use crate::errors::AriaMoveError as AMError;
-    pub use crate::errors::AriaMoveError as AriaError;
-    pub use crate::errors::AriaMoveError as ErrorKind;
+    #[deprecated(since = "1.0.0", note = "use `Error` (or `aria_move::api::Error`)")]
+    pub type E = crate::errors::AriaMoveError;
+    #[deprecated(since = "1.0.0", note = "use `Error` (or `aria_move::api::Error`)")]
+    pub type AMError = crate::errors::AriaMoveError;
+    #[deprecated(since = "1.0.0", note = "use `Error` (or `aria_move::api::Error`)")]
+    pub type AriaError = crate::errors::AriaMoveError;
+    #[deprecated(since = "1.0.0", note = "use `Error` (or `aria_move::api::Error`)")]
+    pub type ErrorKind = crate::errors::AriaMoveError;
     pub use crate::fs_ops::{
         move_dir, move_entry, move_file, resolve_source_path, safe_copy_and_rename,
     };
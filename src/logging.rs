@@ -2,9 +2,16 @@
 //! Builds a subscriber with EnvFilter, supports compact or JSON formats, and optional file logging.
 //!
 //! Behavior:
-//! - Log level is driven by LogLevel (no RUST_LOG override here).
-//! - JSON/non-JSON stdout formatting is selected via the `json` flag.
+//! - Log level is driven by LogLevel, refined per module by `log_filter` (EnvFilter directives).
+//! - RUST_LOG is ignored unless `honor_rust_log` is set; then, when non-empty, it replaces both.
+//! - Invalid directives only warn: logging falls back to the plain level.
+//! - Timestamps follow `log_time_format` (RFC 3339 by default) in local time, or UTC with `log_utc`.
+//! - JSON/non-JSON console formatting is selected via the `json` flag.
+//! - The console layer writes to the `console_stream` (stdout, stderr or nowhere).
 //! - If `log_file` is provided and passes safety checks, a non-blocking file layer is added.
+//! - Panic reports (`panic_guard::PANIC_TARGET`) go to the file layer only.
+//! - Until `init_tracing` runs, `bootstrap_dispatch` shows the events logged while the config
+//!   loads, at the level given on the command line.
 //!
 //! Implementation notes:
 //! - File logging uses tracing_appender::non_blocking to avoid blocking on I/O.
@@ -12,26 +19,62 @@
 
 use anyhow::Result;
 use aria_move::output as out;
-use aria_move::{LogLevel, default_log_path, path_has_symlink_ancestor};
-use chrono::Local;
+use aria_move::{
+    Config, ConsoleStream, LogLevel, LogTimeFormat, default_log_path, path_has_symlink_ancestor,
+};
+use chrono::{DateTime, Local, SecondsFormat, TimeZone, Utc};
 use std::fmt as stdfmt;
 use std::path::Path;
+use tracing::{Dispatch, Metadata};
 use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
-use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+use tracing_subscriber::Layer;
+use tracing_subscriber::filter::{EnvFilter, FilterFn, LevelFilter, filter_fn};
 use tracing_subscriber::fmt as tsfmt;
 use tracing_subscriber::fmt::time::FormatTime;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::registry;
 use tracing_subscriber::util::SubscriberInitExt;
 
 use aria_move::platform::open_log_file_secure_append;
 
-/// Human-friendly timestamp formatter (DD/MM/YY HH:MM:SS)
-struct LocalHumanTime;
-impl FormatTime for LocalHumanTime {
+use crate::panic_guard::PANIC_TARGET;
+
+/// Log timestamp formatter (`log_time_format`, in local time or UTC with `log_utc`).
+#[derive(Debug, Clone)]
+struct LogTimer {
+    format: LogTimeFormat,
+    utc: bool,
+}
+
+impl LogTimer {
+    fn from_config(cfg: &Config) -> Self {
+        LogTimer {
+            format: cfg.log_time_format.clone(),
+            utc: cfg.log_utc,
+        }
+    }
+
+    fn render<Tz: TimeZone>(&self, now: DateTime<Tz>) -> String
+    where
+        Tz::Offset: stdfmt::Display,
+    {
+        match &self.format {
+            LogTimeFormat::Rfc3339 => now.to_rfc3339_opts(SecondsFormat::Millis, true),
+            LogTimeFormat::Legacy => now.format("%d/%m/%y %H:%M:%S").to_string(),
+            LogTimeFormat::Custom(format) => now.format(format).to_string(),
+        }
+    }
+}
+
+impl FormatTime for LogTimer {
     fn format_time(&self, w: &mut tracing_subscriber::fmt::format::Writer<'_>) -> stdfmt::Result {
-        let now = Local::now();
-        write!(w, "{}", now.format("%d/%m/%y %H:%M:%S"))
+        let stamp = if self.utc {
+            self.render(Utc::now())
+        } else {
+            self.render(Local::now())
+        };
+        w.write_str(&stamp)
     }
 }
 
@@ -39,6 +82,7 @@ impl FormatTime for LocalHumanTime {
 fn to_level_filter(lvl: &LogLevel) -> LevelFilter {
     match lvl {
         LogLevel::Quiet => LevelFilter::ERROR,
+        LogLevel::Warn => LevelFilter::WARN,
         LogLevel::Normal => LevelFilter::INFO,
         LogLevel::Info => LevelFilter::DEBUG,
         LogLevel::Debug => LevelFilter::TRACE,
@@ -46,16 +90,48 @@ fn to_level_filter(lvl: &LogLevel) -> LevelFilter {
 }
 
 #[inline]
-fn env_filter_from_level(level_filter: LevelFilter) -> EnvFilter {
-    let level_str = match level_filter {
+fn level_directive(level_filter: LevelFilter) -> &'static str {
+    match level_filter {
         LevelFilter::ERROR => "error",
         LevelFilter::WARN => "warn",
         LevelFilter::INFO => "info",
         LevelFilter::DEBUG => "debug",
         LevelFilter::TRACE => "trace",
         _ => "info",
-    };
-    EnvFilter::new(level_str)
+    }
+}
+
+/// Filter for `lvl` plus the `log_filter` directives, or `rust_log` instead when given
+/// (non-empty RUST_LOG with `honor_rust_log`). Invalid directives warn and are dropped.
+fn build_env_filter(lvl: &LogLevel, log_filter: Option<&str>, rust_log: Option<&str>) -> EnvFilter {
+    if let Some(rust_log) = rust_log.map(str::trim).filter(|s| !s.is_empty()) {
+        match EnvFilter::try_new(rust_log) {
+            Ok(filter) => return filter,
+            Err(e) => out::print_warn(&format!("Ignoring RUST_LOG '{rust_log}': {e}")),
+        }
+    }
+    let level = level_directive(to_level_filter(lvl));
+    if let Some(directives) = log_filter.map(str::trim).filter(|s| !s.is_empty()) {
+        match EnvFilter::try_new(format!("{level},{directives}")) {
+            Ok(filter) => return filter,
+            Err(e) => out::print_warn(&format!("Ignoring log_filter '{directives}': {e}")),
+        }
+    }
+    EnvFilter::new(level)
+}
+
+/// Per-layer filter for the console: everything except panic reports (log file only).
+fn console_filter() -> FilterFn<impl Fn(&Metadata<'_>) -> bool> {
+    filter_fn(|meta| meta.target() != PANIC_TARGET)
+}
+
+/// Writer for the console layer.
+fn console_writer() -> BoxMakeWriter {
+    match out::console_stream() {
+        ConsoleStream::Stdout => BoxMakeWriter::new(std::io::stdout),
+        ConsoleStream::Stderr => BoxMakeWriter::new(std::io::stderr),
+        ConsoleStream::None => BoxMakeWriter::new(std::io::sink),
+    }
 }
 
 /// Try to open a non-blocking file writer for logging:
@@ -65,18 +141,18 @@ fn env_filter_from_level(level_filter: LevelFilter) -> EnvFilter {
 fn maybe_open_non_blocking_writer(path: &Path) -> Option<(NonBlocking, WorkerGuard)> {
     match path_has_symlink_ancestor(path) {
         Ok(true) => {
-            eprintln!(
+            out::print_warn(&format!(
                 "Refusing to enable file logging: ancestor of {} is a symlink; proceeding without file logging.",
                 path.display()
-            );
+            ));
             return None;
         }
         Err(e) => {
-            eprintln!(
+            out::print_warn(&format!(
                 "Error checking log path {} for symlinks: {}; proceeding without file logging.",
                 path.display(),
                 e
-            );
+            ));
             return None;
         }
         Ok(false) => {}
@@ -92,37 +168,79 @@ fn maybe_open_non_blocking_writer(path: &Path) -> Option<(NonBlocking, WorkerGua
             Some((writer, guard))
         }
         Err(e) => {
-            eprintln!("Failed to open log file {}: {}", path.display(), e);
+            out::print_warn(&format!(
+                "Failed to open log file {}: {}",
+                path.display(),
+                e
+            ));
             None
         }
     }
 }
 
+/// Console-only subscriber for the events logged before `init_tracing` can run (config
+/// lookup and parsing). Only the command line is known then: `level` and `log_filter` come
+/// from the flags, and without a level just warnings are shown.
+pub fn bootstrap_dispatch(
+    level: Option<LogLevel>,
+    log_filter: Option<&str>,
+    json: bool,
+) -> Dispatch {
+    let env_filter = build_env_filter(&level.unwrap_or(LogLevel::Warn), log_filter, None);
+    let timer = LogTimer::from_config(&Config::default());
+    if json {
+        let console_layer = tsfmt::layer()
+            .event_format(tsfmt::format().json())
+            .with_timer(timer)
+            .with_level(true)
+            .with_target(false)
+            .with_thread_ids(false)
+            .with_writer(console_writer());
+        Dispatch::new(registry().with(env_filter).with(console_layer))
+    } else {
+        let console_layer = tsfmt::layer()
+            .with_timer(timer)
+            .with_level(true)
+            .with_target(false)
+            .with_thread_ids(false)
+            .with_ansi(out::console_color()) // honors --no-color / NO_COLOR
+            .compact()
+            .with_writer(console_writer());
+        Dispatch::new(registry().with(env_filter).with(console_layer))
+    }
+}
+
 /// Initialize tracing based on LogLevel and format. Returns an optional WorkerGuard
 /// if a file appender is created (must be held until shutdown to flush logs).
-pub fn init_tracing(
-    lvl: &LogLevel,
-    log_file: Option<&Path>,
-    json: bool,
-) -> Result<Option<WorkerGuard>> {
-    let level_filter = to_level_filter(lvl);
-    let env_filter = env_filter_from_level(level_filter);
+pub fn init_tracing(cfg: &Config, json: bool) -> Result<Option<WorkerGuard>> {
+    let rust_log = cfg
+        .honor_rust_log
+        .then(|| std::env::var("RUST_LOG").ok())
+        .flatten();
+    let env_filter = build_env_filter(
+        &cfg.log_level,
+        cfg.log_filter.as_deref(),
+        rust_log.as_deref(),
+    );
+    let log_file = cfg.log_file.as_deref();
+    let timer = LogTimer::from_config(cfg);
 
-    // Build stdout layer per format and initialize later to avoid type mismatch across branches
+    // Build console layer per format and initialize later to avoid type mismatch across branches
 
     // Optional file layer
     if let Some(path) = log_file {
         if let Some((writer, guard)) = maybe_open_non_blocking_writer(path) {
             if json {
-                let stdout_layer = tsfmt::layer()
+                let console_layer = tsfmt::layer()
                     .event_format(tsfmt::format().json())
-                    .with_timer(LocalHumanTime)
+                    .with_timer(timer.clone())
                     .with_level(true)
                     .with_target(false)
-                    .with_thread_ids(false);
+                    .with_thread_ids(false)
+                    .with_writer(console_writer());
                 let file_layer = tsfmt::layer()
                     .event_format(tsfmt::format().json())
-                    .with_timer(LocalHumanTime)
+                    .with_timer(timer.clone())
                     .with_level(true)
                     .with_target(false)
                     .with_thread_ids(false)
@@ -130,18 +248,20 @@ pub fn init_tracing(
                     .with_writer(writer);
                 registry()
                     .with(env_filter)
-                    .with(stdout_layer)
+                    .with(console_layer.with_filter(console_filter()))
                     .with(file_layer)
                     .init();
             } else {
-                let stdout_layer = tsfmt::layer()
-                    .with_timer(LocalHumanTime)
+                let console_layer = tsfmt::layer()
+                    .with_timer(timer.clone())
                     .with_level(true)
                     .with_target(false)
                     .with_thread_ids(false)
-                    .compact();
+                    .with_ansi(out::console_color()) // honors --no-color / NO_COLOR
+                    .compact()
+                    .with_writer(console_writer());
                 let file_layer = tsfmt::layer()
-                    .with_timer(LocalHumanTime)
+                    .with_timer(timer.clone())
                     .with_level(true)
                     .with_target(false)
                     .with_thread_ids(false)
@@ -150,7 +270,7 @@ pub fn init_tracing(
                     .with_writer(writer);
                 registry()
                     .with(env_filter)
-                    .with(stdout_layer)
+                    .with(console_layer.with_filter(console_filter()))
                     .with(file_layer)
                     .init();
             }
@@ -160,7 +280,7 @@ pub fn init_tracing(
         // Provide a clearer, actionable message to users running the binary so
         // they can diagnose why file logging was not enabled.
         out::print_warn(&format!(
-            "Requested file logging to '{}' was not enabled. Check that the parent directory exists, is writable by this process, and that no ancestor is a symlink. Logs will continue to stdout.",
+            "Requested file logging to '{}' was not enabled. Check that the parent directory exists, is writable by this process, and that no ancestor is a symlink. Logs will continue on the console.",
             path.display()
         ));
         if let Ok(def) = default_log_path() {
@@ -173,21 +293,69 @@ pub fn init_tracing(
 
     // No file layer (either not requested or refused/failed)
     if json {
-        let stdout_layer = tsfmt::layer()
+        let console_layer = tsfmt::layer()
             .event_format(tsfmt::format().json())
-            .with_timer(LocalHumanTime)
+            .with_timer(timer.clone())
             .with_level(true)
             .with_target(false)
-            .with_thread_ids(false);
-        registry().with(env_filter).with(stdout_layer).init();
+            .with_thread_ids(false)
+            .with_writer(console_writer());
+        registry()
+            .with(env_filter)
+            .with(console_layer.with_filter(console_filter()))
+            .init();
     } else {
-        let stdout_layer = tsfmt::layer()
-            .with_timer(LocalHumanTime)
+        let console_layer = tsfmt::layer()
+            .with_timer(timer.clone())
             .with_level(true)
             .with_target(false)
             .with_thread_ids(false)
-            .compact();
-        registry().with(env_filter).with(stdout_layer).init();
+            .with_ansi(out::console_color()) // honors --no-color / NO_COLOR
+            .compact()
+            .with_writer(console_writer());
+        registry()
+            .with(env_filter)
+            .with(console_layer.with_filter(console_filter()))
+            .init();
     }
     Ok(None)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_filter_refines_the_level() {
+        let filter = build_env_filter(&LogLevel::Warn, Some("aria_move::fs_ops=debug"), None);
+        let shown = filter.to_string();
+        assert!(shown.contains("aria_move::fs_ops=debug"), "{shown}");
+        assert!(shown.contains("warn"), "{shown}");
+        assert_eq!(filter.max_level_hint(), Some(LevelFilter::DEBUG));
+    }
+
+    #[test]
+    fn rust_log_replaces_level_and_filter() {
+        let filter = build_env_filter(&LogLevel::Debug, Some("aria_move=trace"), Some("error"));
+        assert_eq!(filter.to_string(), "error");
+    }
+
+    #[test]
+    fn timer_renders_presets_and_custom_formats() {
+        let at = Utc.with_ymd_and_hms(2024, 5, 1, 14, 3, 7).unwrap();
+        let timer = |format| LogTimer { format, utc: true };
+        assert_eq!(
+            timer(LogTimeFormat::Rfc3339).render(at),
+            "2024-05-01T14:03:07.000Z"
+        );
+        assert_eq!(timer(LogTimeFormat::Legacy).render(at), "01/05/24 14:03:07");
+        let custom = LogTimeFormat::Custom("%Y-%m-%d %H:%M".into());
+        assert_eq!(timer(custom).render(at), "2024-05-01 14:03");
+    }
+
+    #[test]
+    fn invalid_directives_fall_back_to_the_level() {
+        let filter = build_env_filter(&LogLevel::Normal, Some("aria_move=loud"), Some(" "));
+        assert_eq!(filter.to_string(), "info");
+    }
+}
@@ -1,16 +1,30 @@
 //! Binary entry for aria_move.
 //! Delegates orchestration to `app::run` and prints concise errors without verbose cause chains.
+//! Panics are caught (see `panic_guard`) and exit with their own status code.
 
 mod app;
+mod aria2_conf;
+mod health;
+mod launchd;
 mod logging;
+mod panic_guard;
 mod resume;
+mod selftest;
 
 fn main() {
+    panic_guard::install_hook();
     let args = aria_move::cli::parse();
-    if let Err(e) = app::run(args) {
+    if let Err(e) = panic_guard::catch(|| app::run(args)) {
         // Print a single-line, user-friendly error without the default "Caused by" chain.
         // The detailed chain is still available in logs when --debug or JSON logging is enabled.
         aria_move::output::print_error(&format!("{}", e));
-        std::process::exit(1);
+        let code = if panic_guard::is_panic(&e) {
+            panic_guard::PANIC_EXIT_CODE
+        } else if aria_move::shutdown::timed_out() {
+            app::TIMEOUT_EXIT_CODE
+        } else {
+            1
+        };
+        std::process::exit(code);
     }
 }
@@ -0,0 +1,339 @@
+//! Message catalog for user-facing hints and output prefixes.
+//! - English is always built in; the `i18n` feature adds locale tables (de, fr, es).
+//! - Locale: `ARIA_MOVE_LANG`, then `LC_ALL`, `LC_MESSAGES`, `LANG` (`de_DE.UTF-8` -> `de`).
+//!
+//! Notes:
+//! - Lookups fall back to English, so a locale table may be partial.
+//! - Only human-facing text is translated; error codes and log field names never are.
+
+use std::sync::OnceLock;
+
+/// Catalog keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Msg {
+    // --- I/O hints (OS error codes / io::ErrorKind) ---
+    HintPermissionDenied,
+    HintCrossDevice,
+    HintBusy,
+    HintNotFound,
+    HintAlreadyExists,
+    HintAlreadyExistsRemove,
+    HintNoSpace,
+    HintReadOnly,
+    HintSymlinkLoop,
+    HintNameTooLong,
+    HintProcessFdLimit,
+    HintSystemFileTable,
+    HintBusyTimedOut,
+    // --- Win32-specific I/O hints ---
+    HintAccessDenied,
+    HintNotSameDevice,
+    HintSharingViolation,
+    HintFileExists,
+    HintDiskFull,
+    HintWriteProtected,
+    HintPathTooLong,
+    HintTooManyOpenFiles,
+    // --- AriaMoveError::hint() ---
+    HintInsufficientSpace,
+    HintIgnored,
+    HintLockContended,
+    HintLockTimeout,
+    HintVerificationFailed,
+    HintDestinationUnavailable,
+    HintOutsideBase,
+    HintConfigMissing,
+    HintSourceChanged,
+    HintLooksIncomplete,
+    HintInternalPanic,
+    // --- output.rs line prefixes ---
+    PrefixDebug,
+    PrefixInfo,
+    PrefixWarn,
+    PrefixError,
+    PrefixOk,
+}
+
+/// Text for `msg` in the process locale.
+pub fn text(msg: Msg) -> &'static str {
+    text_in(locale(), msg)
+}
+
+/// Text for `msg` in `lang` (two-letter code); English if there is no translation.
+pub fn text_in(lang: &str, msg: Msg) -> &'static str {
+    translated(lang, msg).unwrap_or_else(|| english(msg))
+}
+
+/// Active two-letter language code (resolved once per process; `en` by default).
+pub fn locale() -> &'static str {
+    static LOCALE: OnceLock<String> = OnceLock::new();
+    LOCALE.get_or_init(|| {
+        ["ARIA_MOVE_LANG", "LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .find_map(|k| std::env::var(k).ok().and_then(|v| language_code(&v)))
+            .unwrap_or_else(|| "en".to_string())
+    })
+}
+
+/// `de_DE.UTF-8` / `fr-CA` / `es@euro` -> `de` / `fr` / `es`; None for empty, `C` and `POSIX`.
+fn language_code(raw: &str) -> Option<String> {
+    let lang = raw
+        .split(['_', '-', '.', '@'])
+        .next()
+        .unwrap_or("")
+        .trim()
+        .to_ascii_lowercase();
+    match lang.as_str() {
+        "" | "c" | "posix" => None,
+        _ => Some(lang),
+    }
+}
+
+fn english(msg: Msg) -> &'static str {
+    match msg {
+        Msg::HintPermissionDenied => "permission denied; check ownership and write permissions.",
+        Msg::HintCrossDevice => "cross-filesystem; atomic rename not possible.",
+        Msg::HintBusy => "resource busy; ensure no other process is writing.",
+        Msg::HintNotFound => "path not found; verify it exists.",
+        Msg::HintAlreadyExists => "already exists; pick a unique name or remove the target.",
+        Msg::HintAlreadyExistsRemove => "already exists; remove or choose a unique name.",
+        Msg::HintNoSpace => "insufficient space on device.",
+        Msg::HintReadOnly => "read-only filesystem; cannot write here.",
+        Msg::HintSymlinkLoop => "too many symbolic link levels (ELOOP); possible symlink cycle.",
+        Msg::HintNameTooLong => "filename or path too long; shorten path segments.",
+        Msg::HintProcessFdLimit => {
+            "process file descriptor limit reached; close files or raise limits."
+        }
+        Msg::HintSystemFileTable => "system-wide file table overflow; reduce open files.",
+        Msg::HintBusyTimedOut => "busy/timed out; retry after the current write finishes.",
+        Msg::HintAccessDenied => "access denied; check permissions.",
+        Msg::HintNotSameDevice => "not same device; cross-filesystem move.",
+        Msg::HintSharingViolation => "sharing violation; file is in use.",
+        Msg::HintFileExists => "already exists; pick a unique name.",
+        Msg::HintDiskFull => "insufficient disk space.",
+        Msg::HintWriteProtected => "write protected / read-only media.",
+        Msg::HintPathTooLong => "filename or path too long (MAX_PATH exceeded).",
+        Msg::HintTooManyOpenFiles => "too many open files; close handles or increase limit.",
+        Msg::HintInsufficientSpace => {
+            "free space at the destination or choose another completed_base."
+        }
+        Msg::HintIgnored => "remove the matching pattern from .ariamoveignore to move it.",
+        Msg::HintLockContended => {
+            "another aria_move holds the lock; retry later or raise ARIA_MOVE_LOCK_TIMEOUT."
+        }
+        Msg::HintLockTimeout => {
+            "raise ARIA_MOVE_LOCK_TIMEOUT or look for a stuck aria_move process holding the lock."
+        }
+        Msg::HintVerificationFailed => {
+            "the source may still be changing; retry once the download is complete."
+        }
+        Msg::HintDestinationUnavailable => {
+            "check that the destination is mounted or the remote host is reachable."
+        }
+        Msg::HintOutsideBase => {
+            "pass a path under download_base, or set require_source_in_base=false."
+        }
+        Msg::HintConfigMissing => {
+            "mount or create the config (aria_move config init), or point --config at it."
+        }
+        Msg::HintSourceChanged => {
+            "the download was still being written; it was left in place, run again once it is complete."
+        }
+        Msg::HintInternalPanic => {
+            "this is a bug in aria_move; please report it with the backtrace from the log file."
+        }
+        Msg::HintLooksIncomplete => {
+            "aria2 may still be filling a preallocated file; run again once it is complete, or turn off content_sanity_check if the file really ends in zeros."
+        }
+        Msg::PrefixDebug => "debug:",
+        Msg::PrefixInfo => "info:",
+        Msg::PrefixWarn => "warn:",
+        Msg::PrefixError => "error:",
+        Msg::PrefixOk => "ok:",
+    }
+}
+
+#[cfg(not(feature = "i18n"))]
+fn translated(_lang: &str, _msg: Msg) -> Option<&'static str> {
+    None
+}
+
+#[cfg(feature = "i18n")]
+fn translated(lang: &str, msg: Msg) -> Option<&'static str> {
+    match lang {
+        "de" => german(msg),
+        "fr" => french(msg),
+        "es" => spanish(msg),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "i18n")]
+fn german(msg: Msg) -> Option<&'static str> {
+    Some(match msg {
+        Msg::HintPermissionDenied => "Zugriff verweigert; Eigentümer und Schreibrechte prüfen.",
+        Msg::HintCrossDevice => "anderes Dateisystem; atomares Umbenennen nicht möglich.",
+        Msg::HintBusy => "Ressource belegt; sicherstellen, dass kein anderer Prozess schreibt.",
+        Msg::HintNotFound => "Pfad nicht gefunden; prüfen, ob er existiert.",
+        Msg::HintAlreadyExists => {
+            "existiert bereits; eindeutigen Namen wählen oder das Ziel entfernen."
+        }
+        Msg::HintAlreadyExistsRemove => {
+            "existiert bereits; entfernen oder eindeutigen Namen wählen."
+        }
+        Msg::HintNoSpace => "nicht genügend Speicherplatz auf dem Gerät.",
+        Msg::HintReadOnly => "schreibgeschütztes Dateisystem; hier kann nicht geschrieben werden.",
+        Msg::HintSymlinkLoop => {
+            "zu viele Ebenen symbolischer Links (ELOOP); möglicherweise ein Link-Zyklus."
+        }
+        Msg::HintNameTooLong => "Dateiname oder Pfad zu lang; Pfadteile kürzen.",
+        Msg::HintProcessFdLimit => {
+            "Limit für Dateideskriptoren des Prozesses erreicht; Dateien schließen oder Limit erhöhen."
+        }
+        Msg::HintSystemFileTable => "systemweite Dateitabelle voll; weniger Dateien öffnen.",
+        Msg::HintBusyTimedOut => {
+            "belegt/Zeitüberschreitung; nach Abschluss des laufenden Schreibvorgangs erneut versuchen."
+        }
+        Msg::HintInsufficientSpace => {
+            "Speicherplatz am Ziel freigeben oder eine andere completed_base wählen."
+        }
+        Msg::HintIgnored => {
+            "passendes Muster aus .ariamoveignore entfernen, um den Eintrag zu verschieben."
+        }
+        Msg::HintLockContended => {
+            "ein anderer aria_move-Prozess hält die Sperre; später erneut versuchen oder ARIA_MOVE_LOCK_TIMEOUT erhöhen."
+        }
+        Msg::HintLockTimeout => {
+            "ARIA_MOVE_LOCK_TIMEOUT erhöhen oder nach einem hängenden aria_move-Prozess mit der Sperre suchen."
+        }
+        Msg::HintVerificationFailed => {
+            "die Quelle ändert sich eventuell noch; nach abgeschlossenem Download erneut versuchen."
+        }
+        Msg::HintDestinationUnavailable => {
+            "prüfen, ob das Ziel eingehängt bzw. der entfernte Host erreichbar ist."
+        }
+        Msg::PrefixDebug => "Debug:",
+        Msg::PrefixInfo => "Info:",
+        Msg::PrefixWarn => "Warnung:",
+        Msg::PrefixError => "Fehler:",
+        Msg::PrefixOk => "OK:",
+        _ => return None,
+    })
+}
+
+#[cfg(feature = "i18n")]
+fn french(msg: Msg) -> Option<&'static str> {
+    Some(match msg {
+        Msg::HintPermissionDenied => {
+            "permission refusée ; vérifiez le propriétaire et les droits d'écriture."
+        }
+        Msg::HintCrossDevice => "autre système de fichiers ; renommage atomique impossible.",
+        Msg::HintBusy => "ressource occupée ; vérifiez qu'aucun autre processus n'écrit.",
+        Msg::HintNotFound => "chemin introuvable ; vérifiez qu'il existe.",
+        Msg::HintAlreadyExists => "existe déjà ; choisissez un nom unique ou supprimez la cible.",
+        Msg::HintAlreadyExistsRemove => "existe déjà ; supprimez-le ou choisissez un nom unique.",
+        Msg::HintNoSpace => "espace insuffisant sur le périphérique.",
+        Msg::HintReadOnly => "système de fichiers en lecture seule ; écriture impossible ici.",
+        Msg::HintSymlinkLoop => {
+            "trop de niveaux de liens symboliques (ELOOP) ; cycle de liens possible."
+        }
+        Msg::HintNameTooLong => "nom de fichier ou chemin trop long ; raccourcissez le chemin.",
+        Msg::HintInsufficientSpace => {
+            "libérez de l'espace à la destination ou choisissez un autre completed_base."
+        }
+        Msg::HintIgnored => "retirez le motif correspondant de .ariamoveignore pour le déplacer.",
+        Msg::HintLockContended => {
+            "un autre aria_move détient le verrou ; réessayez plus tard ou augmentez ARIA_MOVE_LOCK_TIMEOUT."
+        }
+        Msg::HintLockTimeout => {
+            "augmentez ARIA_MOVE_LOCK_TIMEOUT ou cherchez un processus aria_move bloqué qui détient le verrou."
+        }
+        Msg::HintVerificationFailed => {
+            "la source change peut-être encore ; réessayez une fois le téléchargement terminé."
+        }
+        Msg::HintDestinationUnavailable => {
+            "vérifiez que la destination est montée ou que l'hôte distant est joignable."
+        }
+        Msg::PrefixDebug => "débogage :",
+        Msg::PrefixInfo => "info :",
+        Msg::PrefixWarn => "attention :",
+        Msg::PrefixError => "erreur :",
+        Msg::PrefixOk => "ok :",
+        _ => return None,
+    })
+}
+
+#[cfg(feature = "i18n")]
+fn spanish(msg: Msg) -> Option<&'static str> {
+    Some(match msg {
+        Msg::HintPermissionDenied => {
+            "permiso denegado; compruebe el propietario y los permisos de escritura."
+        }
+        Msg::HintCrossDevice => {
+            "otro sistema de archivos; no es posible renombrar de forma atómica."
+        }
+        Msg::HintBusy => "recurso ocupado; asegúrese de que ningún otro proceso esté escribiendo.",
+        Msg::HintNotFound => "ruta no encontrada; compruebe que existe.",
+        Msg::HintAlreadyExists => "ya existe; elija un nombre único o elimine el destino.",
+        Msg::HintAlreadyExistsRemove => "ya existe; elimínelo o elija un nombre único.",
+        Msg::HintNoSpace => "espacio insuficiente en el dispositivo.",
+        Msg::HintReadOnly => "sistema de archivos de solo lectura; no se puede escribir aquí.",
+        Msg::HintInsufficientSpace => "libere espacio en el destino o elija otro completed_base.",
+        Msg::HintIgnored => "quite el patrón correspondiente de .ariamoveignore para moverlo.",
+        Msg::HintLockContended => {
+            "otro aria_move tiene el bloqueo; reintente más tarde o aumente ARIA_MOVE_LOCK_TIMEOUT."
+        }
+        Msg::HintLockTimeout => {
+            "aumente ARIA_MOVE_LOCK_TIMEOUT o busque un proceso aria_move bloqueado que tenga el bloqueo."
+        }
+        Msg::HintVerificationFailed => {
+            "es posible que el origen siga cambiando; reintente cuando termine la descarga."
+        }
+        Msg::HintDestinationUnavailable => {
+            "compruebe que el destino esté montado o que el host remoto sea accesible."
+        }
+        Msg::PrefixDebug => "depuración:",
+        Msg::PrefixInfo => "info:",
+        Msg::PrefixWarn => "aviso:",
+        Msg::PrefixError => "error:",
+        Msg::PrefixOk => "ok:",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_language_codes() {
+        assert_eq!(language_code("de_DE.UTF-8").as_deref(), Some("de"));
+        assert_eq!(language_code("fr-CA").as_deref(), Some("fr"));
+        assert_eq!(language_code("ES@euro").as_deref(), Some("es"));
+        assert_eq!(language_code("C.UTF-8"), None);
+        assert_eq!(language_code("POSIX"), None);
+        assert_eq!(language_code(""), None);
+    }
+
+    #[test]
+    fn unknown_locale_falls_back_to_english() {
+        assert_eq!(
+            text_in("xx", Msg::HintNotFound),
+            "path not found; verify it exists."
+        );
+        assert_eq!(text_in("en", Msg::PrefixError), "error:");
+    }
+
+    #[cfg(feature = "i18n")]
+    #[test]
+    fn translated_tables_are_used_with_english_fallback() {
+        assert_eq!(text_in("de", Msg::PrefixError), "Fehler:");
+        assert_eq!(text_in("fr", Msg::PrefixError), "erreur :");
+        // Partial table: Win32-only hints are not translated.
+        assert_eq!(
+            text_in("fr", Msg::HintSharingViolation),
+            "sharing violation; file is in use."
+        );
+    }
+}
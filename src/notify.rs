@@ -0,0 +1,417 @@
+//! Push notifications through ntfy, Gotify and Pushover (`<notifiers>` in config.xml).
+//! - Each notifier subscribes to event types: `success` (an item was moved), `failure` (a
+//!   move failed), `low-space` (completed_base fell below `low_space_warn_mib` or
+//!   recovered, or a move failed for lack of space) and `slow-move` (a move took longer
+//!   than `slow_move_warning`).
+//! - `send` delivers one event to every notifier subscribed to it.
+//!
+//! Notes:
+//! - Delivery runs `curl` on PATH, or `ARIA_MOVE_CURL` if set. Options are passed as a curl
+//!   config on stdin (`-K -`), so tokens never appear in the process list.
+//! - Delivery is best-effort: failures are logged and never fail the run. Each request is
+//!   limited to `SEND_TIMEOUT_SECS`.
+//! - Dry runs send nothing (callers check `Config::dry_run`).
+
+use std::fmt;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use tracing::{debug, warn};
+
+use crate::config::types::Config;
+
+/// Upper bound for one notification request.
+pub const SEND_TIMEOUT_SECS: u64 = 15;
+
+/// Default ntfy server.
+pub const NTFY_DEFAULT_URL: &str = "https://ntfy.sh";
+
+/// Pushover message endpoint.
+pub const PUSHOVER_URL: &str = "https://api.pushover.net/1/messages.json";
+
+/// What happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyEvent {
+    /// An item was moved (or copied with `copy_only`)
+    Success,
+    /// A move failed
+    Failure,
+    /// completed_base is (or was) short of space
+    LowSpace,
+    /// A move took longer than `slow_move_warning`
+    SlowMove,
+}
+
+impl NotifyEvent {
+    /// All event types (the default subscription).
+    pub const ALL: [NotifyEvent; 4] = [
+        NotifyEvent::Success,
+        NotifyEvent::Failure,
+        NotifyEvent::LowSpace,
+        NotifyEvent::SlowMove,
+    ];
+
+    /// Parse common string names into an event type (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "success" | "moved" | "ok" => Some(NotifyEvent::Success),
+            "failure" | "failed" | "error" => Some(NotifyEvent::Failure),
+            "low-space" | "low_space" | "lowspace" | "space" => Some(NotifyEvent::LowSpace),
+            "slow-move" | "slow_move" | "slowmove" | "slow" => Some(NotifyEvent::SlowMove),
+            _ => None,
+        }
+    }
+
+    /// Pushover / Gotify-style priority: problems are raised above normal.
+    fn urgent(self) -> bool {
+        !matches!(self, NotifyEvent::Success)
+    }
+}
+
+impl fmt::Display for NotifyEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            NotifyEvent::Success => "success",
+            NotifyEvent::Failure => "failure",
+            NotifyEvent::LowSpace => "low-space",
+            NotifyEvent::SlowMove => "slow-move",
+        };
+        f.write_str(s)
+    }
+}
+
+impl FromStr for NotifyEvent {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s).ok_or_else(|| format!("invalid notification event: '{s}'"))
+    }
+}
+
+/// Push service of a notifier, with its service-specific settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotifierKind {
+    /// ntfy: publish to `topic` on the server (default `NTFY_DEFAULT_URL`)
+    Ntfy { topic: String },
+    /// Gotify: post to the server's `/message` endpoint with an application token
+    Gotify,
+    /// Pushover: send to a user (or group) key with an application token
+    Pushover { user: String },
+}
+
+/// One `<notifier kind="..." .../>` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notifier {
+    pub kind: NotifierKind,
+    /// Server URL (ntfy, Gotify); Pushover uses `PUSHOVER_URL` unless this is set
+    pub url: Option<String>,
+    /// Access token (optional for ntfy, required for Gotify and Pushover)
+    pub token: Option<String>,
+    /// Event types this notifier receives
+    pub events: Vec<NotifyEvent>,
+}
+
+/// Raw `<notifier>` attributes, as read from config.xml.
+#[derive(Debug, Clone, Default)]
+pub struct NotifierSpec<'a> {
+    pub kind: &'a str,
+    pub url: Option<&'a str>,
+    pub topic: Option<&'a str>,
+    pub user: Option<&'a str>,
+    pub token: Option<&'a str>,
+    /// Comma-separated event types; empty or missing means all
+    pub events: Option<&'a str>,
+}
+
+impl Notifier {
+    /// Validate a `<notifier>` entry: `kind` is ntfy | gotify | pushover (case-insensitive);
+    /// ntfy needs `topic`, Gotify `url` and `token`, Pushover `token` and `user`.
+    pub fn parse(spec: &NotifierSpec<'_>) -> Result<Self, String> {
+        let given = |v: Option<&str>| {
+            v.map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        let kind_name = spec.kind.trim().to_ascii_lowercase();
+        let url = given(spec.url);
+        let token = given(spec.token);
+        let kind = match kind_name.as_str() {
+            "ntfy" => NotifierKind::Ntfy {
+                topic: given(spec.topic).ok_or("ntfy notifier needs a topic")?,
+            },
+            "gotify" => {
+                if url.is_none() || token.is_none() {
+                    return Err("gotify notifier needs a url and a token".to_string());
+                }
+                NotifierKind::Gotify
+            }
+            "pushover" => {
+                if token.is_none() {
+                    return Err("pushover notifier needs a token".to_string());
+                }
+                NotifierKind::Pushover {
+                    user: given(spec.user).ok_or("pushover notifier needs a user key")?,
+                }
+            }
+            other => return Err(format!("invalid notifier kind: '{other}'")),
+        };
+        let mut events = Vec::new();
+        for name in spec
+            .events
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            let ev = name.parse::<NotifyEvent>()?;
+            if !events.contains(&ev) {
+                events.push(ev);
+            }
+        }
+        if events.is_empty() {
+            events = NotifyEvent::ALL.to_vec();
+        }
+        Ok(Self {
+            kind,
+            url,
+            token,
+            events,
+        })
+    }
+
+    /// True if this notifier receives `event`.
+    pub fn wants(&self, event: NotifyEvent) -> bool {
+        self.events.contains(&event)
+    }
+
+    /// Service name for logs.
+    fn service(&self) -> &'static str {
+        match self.kind {
+            NotifierKind::Ntfy { .. } => "ntfy",
+            NotifierKind::Gotify => "gotify",
+            NotifierKind::Pushover { .. } => "pushover",
+        }
+    }
+
+    /// curl config (`-K` syntax) that delivers `title` / `message` for `event`.
+    fn curl_config(&self, event: NotifyEvent, title: &str, message: &str) -> String {
+        let mut lines = vec![
+            "fail".to_string(),
+            "silent".to_string(),
+            "show-error".to_string(),
+            format!("max-time = {SEND_TIMEOUT_SECS}"),
+        ];
+        let mut opt = |name: &str, value: &str| lines.push(format!("{name} = {}", quote(value)));
+        let base = |default: &str| {
+            self.url
+                .as_deref()
+                .unwrap_or(default)
+                .trim_end_matches('/')
+                .to_string()
+        };
+        match &self.kind {
+            NotifierKind::Ntfy { topic } => {
+                opt("url", &format!("{}/{topic}", base(NTFY_DEFAULT_URL)));
+                opt("header", &format!("Title: {title}"));
+                let priority = if event.urgent() { "high" } else { "default" };
+                opt("header", &format!("Priority: {priority}"));
+                opt("header", &format!("Tags: {event}"));
+                if let Some(token) = &self.token {
+                    opt("header", &format!("Authorization: Bearer {token}"));
+                }
+                opt("data-raw", message);
+            }
+            NotifierKind::Gotify => {
+                opt("url", &format!("{}/message", base("")));
+                opt(
+                    "header",
+                    &format!(
+                        "X-Gotify-Key: {}",
+                        self.token.as_deref().unwrap_or_default()
+                    ),
+                );
+                opt("form-string", &format!("title={title}"));
+                opt("form-string", &format!("message={message}"));
+                let priority = if event.urgent() { 8 } else { 5 };
+                opt("form-string", &format!("priority={priority}"));
+            }
+            NotifierKind::Pushover { user } => {
+                opt("url", &base(PUSHOVER_URL));
+                opt(
+                    "form-string",
+                    &format!("token={}", self.token.as_deref().unwrap_or_default()),
+                );
+                opt("form-string", &format!("user={user}"));
+                opt("form-string", &format!("title={title}"));
+                opt("form-string", &format!("message={message}"));
+                let priority = if event.urgent() { 1 } else { 0 };
+                opt("form-string", &format!("priority={priority}"));
+            }
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+}
+
+/// Double-quoted curl config value.
+pub(crate) fn quote(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Deliver `event` to every notifier of `config` subscribed to it. Best-effort.
+pub fn send(config: &Config, event: NotifyEvent, title: &str, message: &str) {
+    for notifier in config.notifiers.iter().filter(|n| n.wants(event)) {
+        match run_curl(&notifier.curl_config(event, title, message)) {
+            Ok(_) => debug!(service = notifier.service(), %event, "notification sent"),
+            Err(e) => {
+                warn!(service = notifier.service(), %event, error = %e, "notification failed")
+            }
+        }
+    }
+}
+
+/// Run curl with `config` on stdin and return what it wrote to stdout (also used for aria2
+/// JSON-RPC calls).
+pub(crate) fn run_curl(config: &str) -> io::Result<Vec<u8>> {
+    let bin = std::env::var_os("ARIA_MOVE_CURL").unwrap_or_else(|| "curl".into());
+    let mut child = Command::new(&bin)
+        .args(["-K", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            if e.kind() == io::ErrorKind::NotFound {
+                io::Error::new(
+                    e.kind(),
+                    "curl not found; install curl or set ARIA_MOVE_CURL",
+                )
+            } else {
+                e
+            }
+        })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(config.as_bytes())?;
+    }
+    let out = child.wait_with_output()?;
+    if out.status.success() {
+        Ok(out.stdout)
+    } else {
+        Err(io::Error::other(format!(
+            "{}: {}",
+            out.status,
+            String::from_utf8_lossy(&out.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_validates_per_service_settings() {
+        let ntfy = Notifier::parse(&NotifierSpec {
+            kind: "NTFY",
+            topic: Some("downloads"),
+            events: Some("failure, low-space,failure"),
+            ..NotifierSpec::default()
+        })
+        .unwrap();
+        assert_eq!(
+            ntfy.kind,
+            NotifierKind::Ntfy {
+                topic: "downloads".into()
+            }
+        );
+        assert_eq!(
+            ntfy.events,
+            vec![NotifyEvent::Failure, NotifyEvent::LowSpace]
+        );
+        assert!(!ntfy.wants(NotifyEvent::Success));
+
+        let gotify = NotifierSpec {
+            kind: "gotify",
+            url: Some("https://gotify.example"),
+            ..NotifierSpec::default()
+        };
+        assert!(Notifier::parse(&gotify).is_err());
+        let gotify = Notifier::parse(&NotifierSpec {
+            token: Some("app"),
+            ..gotify
+        })
+        .unwrap();
+        assert_eq!(gotify.events, NotifyEvent::ALL.to_vec());
+
+        let pushover = NotifierSpec {
+            kind: "pushover",
+            token: Some("app"),
+            ..NotifierSpec::default()
+        };
+        assert!(Notifier::parse(&pushover).is_err());
+        assert!(
+            Notifier::parse(&NotifierSpec {
+                user: Some("u"),
+                ..pushover
+            })
+            .is_ok()
+        );
+        assert!(
+            Notifier::parse(&NotifierSpec {
+                kind: "slack",
+                ..NotifierSpec::default()
+            })
+            .is_err()
+        );
+        assert!(
+            Notifier::parse(&NotifierSpec {
+                kind: "ntfy",
+                topic: Some("t"),
+                events: Some("sometimes"),
+                ..NotifierSpec::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn curl_config_quotes_values_and_keeps_token_in_headers() {
+        let n = Notifier::parse(&NotifierSpec {
+            kind: "ntfy",
+            url: Some("https://ntfy.example/"),
+            topic: Some("dl"),
+            token: Some("tk_secret"),
+            ..NotifierSpec::default()
+        })
+        .unwrap();
+        let cfg = n.curl_config(NotifyEvent::Failure, "aria_move", "a \"b\"\nc\\d");
+        assert!(cfg.contains("url = \"https://ntfy.example/dl\""), "{cfg}");
+        assert!(cfg.contains("header = \"Authorization: Bearer tk_secret\""));
+        assert!(cfg.contains("header = \"Priority: high\""));
+        assert!(cfg.contains(r#"data-raw = "a \"b\"\nc\\d""#), "{cfg}");
+
+        let p = Notifier::parse(&NotifierSpec {
+            kind: "pushover",
+            token: Some("app"),
+            user: Some("uk"),
+            ..NotifierSpec::default()
+        })
+        .unwrap();
+        let cfg = p.curl_config(NotifyEvent::Success, "t", "m");
+        assert!(cfg.contains(&format!("url = \"{PUSHOVER_URL}\"")));
+        assert!(cfg.contains("form-string = \"user=uk\""));
+        assert!(cfg.contains("form-string = \"priority=0\""));
+    }
+}
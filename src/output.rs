@@ -1,14 +1,114 @@
+//! Console reporter for user-facing messages (not tracing logs).
+//! - Levels: debug < info/success < warn < error; debug lines are hidden unless enabled.
+//! - Color: `--no-color`, `NO_COLOR`, `CLICOLOR=0` disable it; `CLICOLOR_FORCE=1` forces it;
+//!   otherwise colors are used only when the target stream is a TTY.
+//! - Wrapping: on a TTY, long messages wrap to the terminal width with continuation lines
+//!   indented under the message text.
+//!
+//! Notes:
+//! - `print_user` is never colored or wrapped: it is the scriptable output.
+//! - Quiet mode (`--quiet`) drops everything except errors and `print_user` output;
+//!   warn-only mode (`--log-level warn`) also keeps warnings.
+//! - `console_stream` moves info/success lines to stderr (`stderr`) or drops everything but
+//!   errors (`none`); `print_user` stays on stdout either way.
+//! - Settings are process-wide (`set_color_choice`, `set_debug`, `set_quiet`,
+//!   `set_warn_only`, `set_console_stream`) and set early by the binary.
+
 use owo_colors::OwoColorize;
 use std::env;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use crate::config::types::ConsoleStream;
+use crate::messages::{Msg, text};
+
+/// When to color console output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// Color only when the stream is a TTY and the environment allows it.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+static COLOR_CHOICE: AtomicU8 = AtomicU8::new(0);
+static DEBUG_ENABLED: AtomicBool = AtomicBool::new(false);
+static QUIET: AtomicBool = AtomicBool::new(false);
+static WARN_ONLY: AtomicBool = AtomicBool::new(false);
+static CONSOLE_STREAM: AtomicU8 = AtomicU8::new(0);
+
+/// Set the process-wide color policy (e.g. `Never` for `--no-color`).
+pub fn set_color_choice(choice: ColorChoice) {
+    let v = match choice {
+        ColorChoice::Auto => 0,
+        ColorChoice::Always => 1,
+        ColorChoice::Never => 2,
+    };
+    COLOR_CHOICE.store(v, Ordering::Relaxed);
+}
+
+fn color_choice() -> ColorChoice {
+    match COLOR_CHOICE.load(Ordering::Relaxed) {
+        1 => ColorChoice::Always,
+        2 => ColorChoice::Never,
+        _ => ColorChoice::Auto,
+    }
+}
 
-/// Small wrapper around stdout/stderr printing to provide consistent, colored
-/// user-facing messages. Colors are enabled only when output is a TTY.
-fn is_tty() -> bool {
-    atty::is(atty::Stream::Stdout)
+/// Show or hide `print_debug` lines (enabled by `--debug` / `--log-level debug`).
+pub fn set_debug(enabled: bool) {
+    DEBUG_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Suppress all non-error reporter output (enabled by `--quiet`).
+pub fn set_quiet(enabled: bool) {
+    QUIET.store(enabled, Ordering::Relaxed);
+}
+
+/// Suppress debug, info and success lines but keep warnings (log level `warn`).
+pub fn set_warn_only(enabled: bool) {
+    WARN_ONLY.store(enabled, Ordering::Relaxed);
+}
+
+/// Route human-readable console output (`console_stream`).
+pub fn set_console_stream(stream: ConsoleStream) {
+    let v = match stream {
+        ConsoleStream::Stdout => 0,
+        ConsoleStream::Stderr => 1,
+        ConsoleStream::None => 2,
+    };
+    CONSOLE_STREAM.store(v, Ordering::Relaxed);
+}
+
+/// Current `console_stream` setting.
+pub fn console_stream() -> ConsoleStream {
+    match CONSOLE_STREAM.load(Ordering::Relaxed) {
+        1 => ConsoleStream::Stderr,
+        2 => ConsoleStream::None,
+        _ => ConsoleStream::Stdout,
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum Stream {
+    Stdout,
+    Stderr,
+}
+
+fn is_tty(stream: Stream) -> bool {
+    match stream {
+        Stream::Stdout => atty::is(atty::Stream::Stdout),
+        Stream::Stderr => atty::is(atty::Stream::Stderr),
+    }
 }
 
 #[inline]
-fn color_enabled() -> bool {
+fn color_enabled(stream: Stream) -> bool {
+    match color_choice() {
+        ColorChoice::Always => return true,
+        ColorChoice::Never => return false,
+        ColorChoice::Auto => {}
+    }
     // Respect common env conventions first
     if env::var_os("NO_COLOR").is_some() {
         return false;
@@ -23,11 +123,26 @@ fn color_enabled() -> bool {
     {
         return false;
     }
-    is_tty()
+    is_tty(stream)
+}
+
+/// Whether stdout may carry ANSI colors.
+pub fn stdout_color() -> bool {
+    color_enabled(Stream::Stdout)
+}
+
+/// Whether the `console_stream` target may carry ANSI colors (tracing console layer).
+pub fn console_color() -> bool {
+    match console_stream() {
+        ConsoleStream::Stdout => color_enabled(Stream::Stdout),
+        ConsoleStream::Stderr => color_enabled(Stream::Stderr),
+        ConsoleStream::None => false,
+    }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Eq)]
 enum Kind {
+    Debug,
     Info,
     Warn,
     Error,
@@ -36,32 +151,144 @@ enum Kind {
 
 #[inline]
 fn format_line(kind: Kind, msg: &str, color: bool) -> String {
+    let prefix = match kind {
+        Kind::Debug => text(Msg::PrefixDebug),
+        Kind::Info => text(Msg::PrefixInfo),
+        Kind::Warn => text(Msg::PrefixWarn),
+        Kind::Error => text(Msg::PrefixError),
+        Kind::Ok => text(Msg::PrefixOk),
+    };
     match (kind, color) {
-        (Kind::Info, true) => format!("{} {}", "info:".cyan().bold(), msg),
-        (Kind::Warn, true) => format!("{} {}", "warn:".yellow().bold(), msg),
-        (Kind::Error, true) => format!("{} {}", "error:".red().bold(), msg),
-        (Kind::Ok, true) => format!("{} {}", "ok:".green().bold(), msg),
-        (Kind::Info, false) => format!("info: {}", msg),
-        (Kind::Warn, false) => format!("warn: {}", msg),
-        (Kind::Error, false) => format!("error: {}", msg),
-        (Kind::Ok, false) => format!("ok: {}", msg),
+        (Kind::Debug, true) => format!("{} {}", prefix.dimmed(), msg.dimmed()),
+        (Kind::Info, true) => format!("{} {}", prefix.cyan().bold(), msg),
+        (Kind::Warn, true) => format!("{} {}", prefix.yellow().bold(), msg.yellow()),
+        (Kind::Error, true) => format!("{} {}", prefix.red().bold(), msg.red()),
+        (Kind::Ok, true) => format!("{} {}", prefix.green().bold(), msg),
+        (_, false) => format!("{} {}", prefix, msg),
+    }
+}
+
+/// Greedy word wrap of `msg` to `width` columns; continuation lines get `indent` spaces.
+/// Words longer than a line (e.g. paths) are kept whole. Existing newlines are preserved,
+/// and lines that already fit are left untouched.
+fn wrap(msg: &str, width: usize, indent: usize) -> String {
+    let avail = width.saturating_sub(indent).max(20);
+    let pad = " ".repeat(indent);
+    let mut out = String::with_capacity(msg.len() + 16);
+    for (i, para) in msg.split('\n').enumerate() {
+        if i > 0 {
+            out.push('\n');
+            out.push_str(&pad);
+        }
+        // Lines that already fit are kept verbatim (keeps indentation of examples/paths).
+        if para.chars().count() <= avail {
+            out.push_str(para);
+            continue;
+        }
+        let mut col = 0;
+        for word in para.split_whitespace() {
+            let len = word.chars().count();
+            if col > 0 && col + 1 + len > avail {
+                out.push('\n');
+                out.push_str(&pad);
+                col = 0;
+            } else if col > 0 {
+                out.push(' ');
+                col += 1;
+            }
+            out.push_str(word);
+            col += len;
+        }
     }
+    out
+}
+
+/// Terminal width for `stream` when it is a TTY (`COLUMNS` wins, then the OS query).
+fn terminal_width(stream: Stream) -> Option<usize> {
+    if !is_tty(stream) {
+        return None;
+    }
+    if let Some(cols) = env::var("COLUMNS").ok().and_then(|v| v.trim().parse().ok())
+        && cols > 0
+    {
+        return Some(cols);
+    }
+    os_terminal_width(stream)
+}
+
+#[cfg(unix)]
+fn os_terminal_width(stream: Stream) -> Option<usize> {
+    let fd = match stream {
+        Stream::Stdout => libc::STDOUT_FILENO,
+        Stream::Stderr => libc::STDERR_FILENO,
+    };
+    let mut ws: libc::winsize = unsafe { std::mem::zeroed() };
+    let rc = unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, &mut ws) };
+    (rc == 0 && ws.ws_col > 0).then_some(ws.ws_col as usize)
+}
+
+#[cfg(windows)]
+fn os_terminal_width(_stream: Stream) -> Option<usize> {
+    // Console width needs extra windows-sys features; rely on COLUMNS or don't wrap.
+    None
+}
+
+/// Whether lines of `kind` are currently shown (quiet keeps errors only, warn-only also
+/// warnings).
+fn shown(kind: Kind) -> bool {
+    match kind {
+        Kind::Error => true,
+        _ if QUIET.load(Ordering::Relaxed) => false,
+        _ if console_stream() == ConsoleStream::None => false,
+        Kind::Warn => true,
+        _ if WARN_ONLY.load(Ordering::Relaxed) => false,
+        Kind::Debug => DEBUG_ENABLED.load(Ordering::Relaxed),
+        _ => true,
+    }
+}
+
+fn emit(kind: Kind, stream: Stream, msg: &str) {
+    if !shown(kind) {
+        return;
+    }
+    let stream = match console_stream() {
+        ConsoleStream::Stdout => stream,
+        _ => Stream::Stderr,
+    };
+    let body = match terminal_width(stream) {
+        // Indent continuation lines under the message text (prefix + one space).
+        Some(width) => {
+            let indent = format_line(kind, "", false).chars().count();
+            wrap(msg, width, indent)
+        }
+        None => msg.to_string(),
+    };
+    let line = format_line(kind, &body, color_enabled(stream));
+    match stream {
+        Stream::Stdout => println!("{}", line),
+        Stream::Stderr => eprintln!("{}", line),
+    }
+}
+
+/// Diagnostic line on stderr; shown only after `set_debug(true)`.
+pub fn print_debug(msg: &str) {
+    emit(Kind::Debug, Stream::Stderr, msg);
 }
 
 pub fn print_info(msg: &str) {
-    println!("{}", format_line(Kind::Info, msg, color_enabled()));
+    emit(Kind::Info, Stream::Stdout, msg);
 }
 
 pub fn print_warn(msg: &str) {
-    eprintln!("{}", format_line(Kind::Warn, msg, color_enabled()));
+    emit(Kind::Warn, Stream::Stderr, msg);
 }
 
 pub fn print_error(msg: &str) {
-    eprintln!("{}", format_line(Kind::Error, msg, color_enabled()));
+    emit(Kind::Error, Stream::Stderr, msg);
 }
 
 pub fn print_success(msg: &str) {
-    println!("{}", format_line(Kind::Ok, msg, color_enabled()));
+    emit(Kind::Ok, Stream::Stdout, msg);
 }
 
 /// Print a plain user-facing line (no prefix). Use this for primary outputs
@@ -83,6 +310,7 @@ mod tests {
         );
         assert_eq!(format_line(Kind::Error, "boom", false), "error: boom");
         assert_eq!(format_line(Kind::Ok, "done", false), "ok: done");
+        assert_eq!(format_line(Kind::Debug, "trace", false), "debug: trace");
     }
 
     #[test]
@@ -94,4 +322,68 @@ mod tests {
         assert!(colored.contains("hello"));
         assert!(colored.contains("info:"));
     }
+
+    #[test]
+    fn wraps_on_word_boundaries_with_indent() {
+        let msg = "alpha beta gamma delta epsilon zeta eta theta iota kappa";
+        let wrapped = wrap(msg, 30, 6);
+        for line in wrapped.lines() {
+            assert!(line.chars().count() <= 30, "{line:?}");
+        }
+        assert!(wrapped.lines().skip(1).all(|l| l.starts_with("      ")));
+        assert_eq!(
+            wrapped.split_whitespace().collect::<Vec<_>>().join(" "),
+            msg
+        );
+    }
+
+    #[test]
+    fn short_lines_keep_their_indentation() {
+        let msg = "Example:\n  <config>\n    <log_level>normal</log_level>";
+        assert_eq!(
+            wrap(msg, 80, 6),
+            "Example:\n        <config>\n          <log_level>normal</log_level>"
+        );
+    }
+
+    #[test]
+    fn long_words_are_not_split() {
+        let path = "/very/long/path/that/does/not/fit/in/the/available/width.mkv";
+        let wrapped = wrap(&format!("moving {path} now"), 30, 0);
+        assert!(wrapped.lines().any(|l| l == path));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn quiet_keeps_only_errors() {
+        set_quiet(true);
+        let visible = [Kind::Debug, Kind::Info, Kind::Warn, Kind::Ok, Kind::Error]
+            .into_iter()
+            .filter(|k| shown(*k))
+            .collect::<Vec<_>>();
+        set_quiet(false);
+        assert!(visible == [Kind::Error]);
+        assert!(shown(Kind::Info));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn warn_only_keeps_warnings_and_errors() {
+        set_warn_only(true);
+        let visible = [Kind::Debug, Kind::Info, Kind::Warn, Kind::Ok, Kind::Error]
+            .into_iter()
+            .filter(|k| shown(*k))
+            .collect::<Vec<_>>();
+        set_warn_only(false);
+        assert!(visible == [Kind::Warn, Kind::Error]);
+    }
+
+    #[test]
+    fn color_choice_overrides_environment() {
+        set_color_choice(ColorChoice::Never);
+        assert!(!color_enabled(Stream::Stdout));
+        set_color_choice(ColorChoice::Always);
+        assert!(color_enabled(Stream::Stderr));
+        set_color_choice(ColorChoice::Auto);
+    }
 }
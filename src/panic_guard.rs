@@ -0,0 +1,88 @@
+//! Panic containment for hook mode.
+//! - aria2 runs aria_move as `on-download-complete`; a panic must neither print a long
+//!   backtrace into aria2's output nor leave a half-finished process behind.
+//! - `install_hook` replaces the default panic printer: the message, location and a
+//!   backtrace are logged under `PANIC_TARGET`, which only the log file receives.
+//! - `catch` runs the app and turns a panic into `AriaMoveError::InternalPanic`; `main` exits
+//!   with `PANIC_EXIT_CODE` for it.
+//!
+//! Notes:
+//! - Without a log file (or before logging is set up) the backtrace is dropped; the one-line
+//!   error on stderr still names the panic.
+//! - Panics on rayon workers are re-raised on the calling thread, so they are caught too.
+
+use anyhow::Result;
+use aria_move::AriaMoveError;
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::panic::{self, AssertUnwindSafe};
+use tracing::error;
+
+/// tracing target of panic reports; the console layers filter it out.
+pub const PANIC_TARGET: &str = "aria_move::panic";
+
+/// Exit status after a panic (EX_SOFTWARE from sysexits.h), distinct from ordinary failures (1).
+pub const PANIC_EXIT_CODE: i32 = 70;
+
+/// Route panic reports to the log file instead of stderr.
+pub fn install_hook() {
+    panic::set_hook(Box::new(|info| {
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}", l.file(), l.line()))
+            .unwrap_or_default();
+        let backtrace = Backtrace::force_capture();
+        error!(
+            target: PANIC_TARGET,
+            code = "internal_panic",
+            %location,
+            %backtrace,
+            "panic: {}",
+            payload_message(info.payload())
+        );
+    }));
+}
+
+/// Run `f`, converting a panic into `AriaMoveError::InternalPanic`.
+pub fn catch<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        Err(AriaMoveError::InternalPanic(payload_message(payload.as_ref())).into())
+    })
+}
+
+/// True if `err` is the result of a caught panic.
+pub fn is_panic(err: &anyhow::Error) -> bool {
+    matches!(
+        err.downcast_ref::<AriaMoveError>(),
+        Some(AriaMoveError::InternalPanic(_))
+    )
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panics_become_internal_panic_errors() {
+        let err = catch(|| -> Result<()> { panic!("index {} out of range", 3) }).unwrap_err();
+        assert!(is_panic(&err));
+        assert_eq!(
+            err.to_string(),
+            "Internal error (panic): index 3 out of range"
+        );
+
+        let ok = catch(|| Ok(5)).unwrap();
+        assert_eq!(ok, 5);
+        assert!(!is_panic(&anyhow::anyhow!("plain failure")));
+    }
+}
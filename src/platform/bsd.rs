@@ -0,0 +1,113 @@
+//! BSD (OpenBSD, FreeBSD, NetBSD, DragonFly) implementations of platform helpers.
+//! - Network and FUSE filesystems via statfs(2) `f_fstypename` (disk space is shared in
+//!   `common_unix.rs`).
+//! - OpenBSD only: `sandbox` restricts the process with pledge(2) and unveil(2).
+//!
+//! Notes:
+//! - File/dir creation helpers are shared with Linux (`unix.rs`).
+//! - unveil is skipped when external tools (rsync, rclone, ssh) are run: they need their own
+//!   binaries and libraries, which we can't enumerate reliably.
+
+use std::ffi::{CStr, CString};
+use std::io;
+use std::mem::MaybeUninit;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+fn c_path(path: &Path) -> io::Result<CString> {
+    CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path contains null byte"))
+}
+
+/// True if `path` lives on NFS or SMB, judged by the filesystem type name. Errors report false.
+pub fn is_network_fs(path: &Path) -> bool {
+    fstype_name(path).is_some_and(|name| is_network_fstype(&name))
+}
+
+/// "fuse" if `path` lives on a FUSE mount (fusefs, puffs), whose renames may succeed while
+/// copying, or fail oddly. Errors report None.
+pub fn rename_quirk_fs(path: &Path) -> Option<&'static str> {
+    fstype_name(path)
+        .is_some_and(|name| is_fuse_fstype(&name))
+        .then_some("fuse")
+}
+
+/// statfs(2) `f_fstypename` of the filesystem holding `path` (e.g. "ufs", "zfs", "nfs").
+pub fn fstype_name(path: &Path) -> Option<String> {
+    let c_path = c_path(path).ok()?;
+    unsafe {
+        let mut stat: MaybeUninit<libc::statfs> = MaybeUninit::uninit();
+        if libc::statfs(c_path.as_ptr(), stat.as_mut_ptr()) != 0 {
+            return None;
+        }
+        let stat = stat.assume_init();
+        Some(
+            CStr::from_ptr(stat.f_fstypename.as_ptr())
+                .to_string_lossy()
+                .into_owned(),
+        )
+    }
+}
+
+fn is_network_fstype(name: &str) -> bool {
+    matches!(name, "nfs" | "nfs4" | "smbfs" | "cifs")
+}
+
+fn is_fuse_fstype(name: &str) -> bool {
+    name.starts_with("fuse") || name == "puffs"
+}
+
+/// Restrict the process (OpenBSD): read/write/create only below `rw_paths` and limit syscalls
+/// to file I/O. With `allow_exec`, child processes are permitted and paths are not unveiled.
+#[cfg(target_os = "openbsd")]
+pub fn sandbox(rw_paths: &[&Path], allow_exec: bool) -> io::Result<()> {
+    if !allow_exec {
+        for p in rw_paths {
+            let c = c_path(p)?;
+            if unsafe { libc::unveil(c.as_ptr(), c"rwc".as_ptr()) } != 0 {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        // Lock further unveil calls.
+        if unsafe { libc::unveil(std::ptr::null(), std::ptr::null()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    // `proc`: the lockfile protocol probes owner liveness with kill(pid, 0).
+    let promises = if allow_exec {
+        c"stdio rpath wpath cpath fattr flock chown proc exec"
+    } else {
+        c"stdio rpath wpath cpath fattr flock chown proc"
+    };
+    if unsafe { libc::pledge(promises.as_ptr(), std::ptr::null()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn local_dirs_are_not_network_fs() {
+        let dir = tempdir().unwrap();
+        assert!(!is_network_fs(dir.path()));
+    }
+
+    #[test]
+    fn recognizes_network_fstype_names() {
+        assert!(is_network_fstype("nfs"));
+        assert!(is_network_fstype("smbfs"));
+        assert!(!is_network_fstype("ffs"));
+        assert!(!is_network_fstype("zfs"));
+    }
+
+    #[test]
+    fn recognizes_fuse_fstype_names() {
+        assert!(is_fuse_fstype("fusefs"));
+        assert!(is_fuse_fstype("fusefs.sshfs"));
+        assert!(!is_fuse_fstype("ffs"));
+    }
+}
@@ -1,9 +1,14 @@
 //! Common Unix helpers shared by macOS and other Unix targets.
-//! Includes atomic write with 0600 mode and parent directory fsync.
+//! Includes atomic write with 0600 mode and parent directory fsync, the statvfs-based
+//! free-space query used on every Unix (Linux, macOS, BSDs, illumos/Solaris), and the
+//! pathconf-based file name limit.
 
 use anyhow::{Context, Result};
+use std::ffi::CString;
 use std::fs::{self, File, OpenOptions};
 use std::io::{self, Write};
+use std::mem::MaybeUninit;
+use std::os::unix::ffi::OsStrExt;
 use std::os::unix::fs::OpenOptionsExt;
 use std::path::Path;
 
@@ -46,3 +51,113 @@ pub fn atomic_write_0600(path: &Path, contents: &[u8]) -> Result<()> {
     dir_file.sync_all().context("fsync parent dir")?;
     Ok(())
 }
+
+/// Bytes available to unprivileged users on the filesystem holding `path` (statvfs `f_bavail`).
+/// Uses the fragment size `f_frsize`, falling back to `f_bsize` where it is reported as 0.
+pub fn check_disk_space(path: &Path) -> io::Result<u64> {
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path contains null byte"))?;
+    let mut stat: MaybeUninit<libc::statvfs> = MaybeUninit::uninit();
+    if unsafe { libc::statvfs(c_path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let stat = unsafe { stat.assume_init() };
+    // Field widths differ between targets (u32/u64/c_ulong).
+    #[allow(clippy::unnecessary_cast)]
+    let block = if stat.f_frsize != 0 {
+        stat.f_frsize as u64
+    } else {
+        stat.f_bsize as u64
+    };
+    #[allow(clippy::unnecessary_cast)]
+    Ok((stat.f_bavail as u64).saturating_mul(block))
+}
+
+/// Identifier of the filesystem holding `path` (`st_dev`); equal ids mean rename(2) works.
+pub fn volume_id(path: &Path) -> io::Result<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Ok(fs::metadata(path)?.dev())
+}
+
+/// Longest file name (bytes) the filesystem holding the directory `path` accepts
+/// (pathconf `_PC_NAME_MAX`). None if unknown or unlimited.
+pub fn name_max(path: &Path) -> Option<usize> {
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let max = unsafe { libc::pathconf(c_path.as_ptr(), libc::_PC_NAME_MAX) };
+    usize::try_from(max).ok().filter(|&n| n > 0)
+}
+
+/// Notable mount flags of the filesystem holding `path` (statvfs `f_flag`), e.g. "ro",
+/// "nosuid"; Linux also reports "nodev", "noexec", "sync", "noatime" and "relatime".
+// Flag and field widths differ between targets (u32/u64/c_ulong).
+#[allow(clippy::unnecessary_cast)]
+pub fn mount_flags(path: &Path) -> io::Result<Vec<&'static str>> {
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path contains null byte"))?;
+    let mut stat: MaybeUninit<libc::statvfs> = MaybeUninit::uninit();
+    if unsafe { libc::statvfs(c_path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let flags = unsafe { stat.assume_init() }.f_flag as u64;
+    let mut known: Vec<(u64, &'static str)> = vec![
+        (libc::ST_RDONLY as u64, "ro"),
+        (libc::ST_NOSUID as u64, "nosuid"),
+    ];
+    #[cfg(target_os = "linux")]
+    known.extend([
+        (libc::ST_NODEV as u64, "nodev"),
+        (libc::ST_NOEXEC as u64, "noexec"),
+        (libc::ST_SYNCHRONOUS as u64, "sync"),
+        (libc::ST_NOATIME as u64, "noatime"),
+        (libc::ST_RELATIME as u64, "relatime"),
+    ]);
+    Ok(known
+        .into_iter()
+        .filter(|&(bit, _)| flags & bit != 0)
+        .map(|(_, name)| name)
+        .collect())
+}
+
+/// Treat SIGQUIT like SIGTERM: the handler re-raises it as SIGTERM, which the ctrlc handler
+/// turns into a graceful shutdown (instead of the default core dump).
+pub fn forward_sigquit_to_sigterm() -> io::Result<()> {
+    extern "C" fn on_quit(_: libc::c_int) {
+        // kill(2) is async-signal-safe.
+        unsafe {
+            libc::kill(libc::getpid(), libc::SIGTERM);
+        }
+    }
+    let mut action: libc::sigaction = unsafe { std::mem::zeroed() };
+    action.sa_sigaction = on_quit as *const () as libc::sighandler_t;
+    action.sa_flags = libc::SA_RESTART;
+    unsafe { libc::sigemptyset(&mut action.sa_mask) };
+    if unsafe { libc::sigaction(libc::SIGQUIT, &action, std::ptr::null_mut()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn name_max_is_known_for_local_dirs() {
+        let dir = tempdir().unwrap();
+        assert!(name_max(dir.path()).is_some_and(|n| n >= 14));
+    }
+
+    #[test]
+    fn disk_space_smoke() {
+        let dir = tempdir().unwrap();
+        assert!(check_disk_space(dir.path()).unwrap() > 0);
+    }
+
+    #[test]
+    fn disk_space_nonexistent_path_errors() {
+        let p = Path::new("/this/definitely/does/not/exist/aria_move_test");
+        let err = check_disk_space(p).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
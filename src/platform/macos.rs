@@ -48,20 +48,38 @@ fn tmp_sibling_name(target: &Path) -> std::path::PathBuf {
     super::temp::tmp_config_sibling_name(target)
 }
 
-/// Check available disk space at the given path (returns bytes available) using statvfs.
-pub fn check_disk_space(path: &Path) -> io::Result<u64> {
-    use std::ffi::CString;
+/// True if `path` lives on a network filesystem (nfs, smbfs, afpfs, webdav) where flock is
+/// unreliable. Uses statfs(2) `f_fstypename`; errors report false.
+pub fn is_network_fs(path: &Path) -> bool {
+    fstype_name(path)
+        .is_some_and(|name| matches!(name.as_str(), "nfs" | "smbfs" | "afpfs" | "webdav"))
+}
+
+/// "fuse" if `path` lives on a macFUSE/osxfuse mount, whose renames may succeed while
+/// copying, or fail oddly. Errors report None.
+pub fn rename_quirk_fs(path: &Path) -> Option<&'static str> {
+    let name = fstype_name(path)?;
+    (name.starts_with("macfuse") || name.starts_with("osxfuse") || name.starts_with("fuse"))
+        .then_some("fuse")
+}
+
+/// statfs(2) `f_fstypename` of the filesystem holding `path` (e.g. "apfs", "smbfs").
+pub fn fstype_name(path: &Path) -> Option<String> {
+    use std::ffi::{CStr, CString};
     use std::mem::MaybeUninit;
     use std::os::unix::ffi::OsStrExt;
-    let c_path = CString::new(path.as_os_str().as_bytes())
-        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path contains null byte"))?;
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
     unsafe {
-        let mut stat: MaybeUninit<libc::statvfs> = MaybeUninit::uninit();
-        if libc::statvfs(c_path.as_ptr(), stat.as_mut_ptr()) != 0 {
-            return Err(io::Error::last_os_error());
+        let mut stat: MaybeUninit<libc::statfs> = MaybeUninit::uninit();
+        if libc::statfs(c_path.as_ptr(), stat.as_mut_ptr()) != 0 {
+            return None;
         }
         let stat = stat.assume_init();
-        Ok((stat.f_bavail as u64).saturating_mul(stat.f_bsize))
+        Some(
+            CStr::from_ptr(stat.f_fstypename.as_ptr())
+                .to_string_lossy()
+                .into_owned(),
+        )
     }
 }
 
@@ -142,21 +160,6 @@ mod tests {
         assert_eq!(sorted.len(), v.len(), "duplicate temp names found");
     }
 
-    #[test]
-    fn disk_space_nonexistent_path_errors() {
-        let p = Path::new("/this/definitely/does/not/exist/aria_move_test");
-        let err = check_disk_space(p).unwrap_err();
-        let s = format!("{}", err);
-        assert!(s.contains("No such file") || s.contains("no such file"));
-    }
-
-    #[test]
-    fn disk_space_smoke() {
-        let dir = tempdir().unwrap();
-        let bytes = check_disk_space(dir.path()).unwrap();
-        assert!(bytes > 0);
-    }
-
     #[test]
     fn config_write_second_call_conflict() {
         let dir = tempdir().unwrap();
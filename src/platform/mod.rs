@@ -1,4 +1,4 @@
-//! Platform-specific helpers (macOS/Linux/Windows).
+//! Platform-specific helpers (macOS/Linux/BSD/Windows).
 
 #[cfg(windows)]
 mod windows;
@@ -12,24 +12,72 @@ pub(crate) mod temp;
 #[cfg(any(unix, target_os = "macos"))]
 mod common_unix;
 
+#[cfg(unix)]
+mod privileges;
+
+// OpenBSD, FreeBSD, NetBSD, DragonFly: statvfs/statfs helpers and OpenBSD pledge/unveil.
+#[cfg(any(
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+))]
+mod bsd;
+
 // Unix but not macOS (e.g., Linux)
 #[cfg(all(unix, not(target_os = "macos")))]
 mod unix;
 
 #[cfg(windows)]
 pub use windows::{
-    check_disk_space, ensure_secure_directory, open_log_file_secure_append, set_dir_mode_0700,
-    set_file_mode_0600, write_config_secure_new_0600,
+    check_disk_space, ensure_secure_directory, forward_sigquit_to_sigterm, fstype_name,
+    is_link_reparse_point, is_network_fs, mount_flags, name_max, open_log_file_secure_append,
+    rename_quirk_fs, set_dir_mode_0700, set_file_mode_0600, volume_id,
+    write_config_secure_new_0600,
 };
 
 #[cfg(target_os = "macos")]
 pub use macos::{
-    check_disk_space, open_log_file_secure_append, set_dir_mode_0700, set_file_mode_0600,
-    write_config_secure_new_0600,
+    fstype_name, is_network_fs, open_log_file_secure_append, rename_quirk_fs, set_dir_mode_0700,
+    set_file_mode_0600, write_config_secure_new_0600,
 };
 
 #[cfg(all(unix, not(target_os = "macos")))]
 pub use unix::{
-    check_disk_space, open_log_file_secure_append, set_dir_mode_0700, set_file_mode_0600,
+    open_log_file_secure_append, set_dir_mode_0700, set_file_mode_0600,
     write_config_secure_new_0600,
 };
+
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "macos",
+        target_os = "openbsd",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "dragonfly"
+    ))
+))]
+pub use unix::{fstype_name, is_network_fs, rename_quirk_fs};
+
+#[cfg(any(
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+))]
+pub use bsd::{fstype_name, is_network_fs, rename_quirk_fs};
+
+#[cfg(target_os = "openbsd")]
+pub use bsd::sandbox;
+
+#[cfg(unix)]
+pub use common_unix::{
+    check_disk_space, forward_sigquit_to_sigterm, mount_flags, name_max, volume_id,
+};
+
+#[cfg(unix)]
+pub use privileges::drop_privileges;
+
+#[cfg(windows)]
+pub use windows::drop_privileges;
@@ -0,0 +1,178 @@
+//! Privilege dropping for processes started as root (Unix).
+//! - Users and groups are given by name or numeric id.
+//! - Order: supplementary groups, then gid, then uid (uid last, or the rest is no longer allowed).
+//! - Afterwards regaining root must fail; this is verified.
+//!
+//! Notes:
+//! - Without a group, the user's primary group is used.
+//! - A group without a user is refused for root: it would keep uid 0, which is no drop.
+//! - When not running as root, asking for the current identity is a no-op; anything else fails.
+
+use anyhow::{Result, anyhow, bail};
+use std::ffi::{CStr, CString};
+use std::io;
+
+/// Switch the process to `user` / `group` (either may be None; both None is a no-op).
+pub fn drop_privileges(user: Option<&str>, group: Option<&str>) -> Result<()> {
+    let user = user.map(lookup_user).transpose()?;
+    let gid = match (group, user.as_ref()) {
+        (Some(g), _) => Some(lookup_group(g)?),
+        (None, Some(u)) => Some(u.gid.ok_or_else(|| {
+            anyhow!(
+                "user id {} has no passwd entry; set run_as_group too",
+                u.uid
+            )
+        })?),
+        (None, None) => None,
+    };
+    let uid = user.as_ref().map(|u| u.uid);
+
+    let (cur_uid, cur_gid) = unsafe { (libc::geteuid(), libc::getegid()) };
+    if cur_uid != 0 {
+        if uid.is_none_or(|u| u == cur_uid) && gid.is_none_or(|g| g == cur_gid) {
+            return Ok(());
+        }
+        bail!("run_as_user/run_as_group require starting as root (current uid {cur_uid})");
+    }
+    if uid.is_none() && gid.is_some() {
+        bail!("run_as_group without run_as_user would keep running as root; set run_as_user too");
+    }
+
+    if let Some(gid) = gid {
+        let groups_set = match user.as_ref().and_then(|u| u.name.as_ref()) {
+            // Supplementary groups of the target user (keeps shared-group access working).
+            Some(name) => unsafe { libc::initgroups(name.as_ptr(), gid as _) },
+            None => unsafe { libc::setgroups(1, &gid) },
+        };
+        if groups_set != 0 {
+            return Err(os_error("set supplementary groups"));
+        }
+        if unsafe { libc::setgid(gid) } != 0 {
+            return Err(os_error(&format!("setgid({gid})")));
+        }
+    }
+    if let Some(uid) = uid {
+        if unsafe { libc::setuid(uid) } != 0 {
+            return Err(os_error(&format!("setuid({uid})")));
+        }
+        if uid != 0 && unsafe { libc::setuid(0) } == 0 {
+            bail!("privilege drop failed: process could regain root");
+        }
+    }
+    Ok(())
+}
+
+struct User {
+    name: Option<CString>,
+    uid: libc::uid_t,
+    /// Primary group (None for a numeric id without a passwd entry).
+    gid: Option<libc::gid_t>,
+}
+
+fn lookup_user(spec: &str) -> Result<User> {
+    let spec = spec.trim();
+    let mut buf = vec![0 as libc::c_char; 16 * 1024];
+    let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+    let mut result: *mut libc::passwd = std::ptr::null_mut();
+    let numeric = spec.parse::<libc::uid_t>().ok();
+    let cname = CString::new(spec).map_err(|_| anyhow!("invalid user name '{spec}'"))?;
+    let rc = unsafe {
+        match numeric {
+            Some(uid) => libc::getpwuid_r(uid, &mut pwd, buf.as_mut_ptr(), buf.len(), &mut result),
+            None => libc::getpwnam_r(
+                cname.as_ptr(),
+                &mut pwd,
+                buf.as_mut_ptr(),
+                buf.len(),
+                &mut result,
+            ),
+        }
+    };
+    if rc != 0 {
+        return Err(
+            anyhow!(io::Error::from_raw_os_error(rc)).context(format!("look up user '{spec}'"))
+        );
+    }
+    if result.is_null() {
+        // Numeric ids without a passwd entry are allowed (common in containers).
+        return match numeric {
+            Some(uid) => Ok(User {
+                name: None,
+                uid,
+                gid: None,
+            }),
+            None => bail!("unknown user '{spec}'"),
+        };
+    }
+    let name = unsafe { CStr::from_ptr(pwd.pw_name) }.to_owned();
+    Ok(User {
+        name: Some(name),
+        uid: pwd.pw_uid,
+        gid: Some(pwd.pw_gid),
+    })
+}
+
+fn lookup_group(spec: &str) -> Result<libc::gid_t> {
+    let spec = spec.trim();
+    if let Ok(gid) = spec.parse::<libc::gid_t>() {
+        return Ok(gid);
+    }
+    let cname = CString::new(spec).map_err(|_| anyhow!("invalid group name '{spec}'"))?;
+    let mut buf = vec![0 as libc::c_char; 16 * 1024];
+    let mut grp: libc::group = unsafe { std::mem::zeroed() };
+    let mut result: *mut libc::group = std::ptr::null_mut();
+    let rc = unsafe {
+        libc::getgrnam_r(
+            cname.as_ptr(),
+            &mut grp,
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut result,
+        )
+    };
+    if rc != 0 {
+        return Err(
+            anyhow!(io::Error::from_raw_os_error(rc)).context(format!("look up group '{spec}'"))
+        );
+    }
+    if result.is_null() {
+        bail!("unknown group '{spec}'");
+    }
+    Ok(grp.gr_gid)
+}
+
+fn os_error(what: &str) -> anyhow::Error {
+    anyhow!(io::Error::last_os_error()).context(format!("drop privileges: {what}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_identity_is_a_noop_for_unprivileged_users() {
+        let (uid, gid) = unsafe { (libc::geteuid(), libc::getegid()) };
+        if uid == 0 {
+            return; // would really switch identity when run as root
+        }
+        drop_privileges(Some(&uid.to_string()), Some(&gid.to_string())).unwrap();
+        assert!(drop_privileges(Some("0"), None).is_err());
+    }
+
+    #[test]
+    fn group_without_user_is_refused_for_root() {
+        if unsafe { libc::geteuid() } != 0 {
+            return; // only root can switch groups
+        }
+        let err = drop_privileges(None, Some("0")).unwrap_err();
+        assert!(err.to_string().contains("run_as_user"), "{err:#}");
+    }
+
+    #[test]
+    fn resolves_root_by_name_and_rejects_unknown_names() {
+        let root = lookup_user("root").unwrap();
+        assert_eq!(root.uid, 0);
+        assert!(lookup_user("no-such-user-aria-move").is_err());
+        assert!(lookup_group("no-such-group-aria-move").is_err());
+    }
+}
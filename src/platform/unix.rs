@@ -1,4 +1,6 @@
 //! Unix (non-macOS) implementations of platform helpers.
+//! On the BSDs, `is_network_fs` comes from `bsd.rs` instead; `check_disk_space` is shared
+//! by all Unix targets (`common_unix.rs`).
 
 use super::common_unix::atomic_write_0600;
 use anyhow::Result;
@@ -46,28 +48,103 @@ pub fn set_file_mode_0600(path: &Path) -> io::Result<()> {
 
 // (No local tmp_sibling_name wrapper needed; macOS/windows modules keep theirs if required.)
 
-/// Check available disk space at the given path (returns bytes available).
-/// Uses statvfs on Unix. Returns Ok(available_bytes) or an IO error.
-pub fn check_disk_space(path: &Path) -> io::Result<u64> {
+/// True if `path` lives on a network filesystem (NFS, CIFS/SMB) where flock is unreliable.
+/// Uses statfs(2) on Linux; other Unix targets report false. Errors also report false.
+#[cfg(not(any(
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+)))]
+pub fn is_network_fs(path: &Path) -> bool {
+    const NFS_SUPER_MAGIC: u32 = 0x6969;
+    const SMB_SUPER_MAGIC: u32 = 0x517B;
+    const CIFS_MAGIC_NUMBER: u32 = 0xFF53_4D42;
+    const SMB2_MAGIC_NUMBER: u32 = 0xFE53_4D42;
+    matches!(
+        statfs_magic(path),
+        Some(NFS_SUPER_MAGIC | SMB_SUPER_MAGIC | CIFS_MAGIC_NUMBER | SMB2_MAGIC_NUMBER)
+    )
+}
+
+/// "overlayfs" or "fuse" if `path` lives on one of those, whose renames may succeed while
+/// copying, or fail oddly. Uses statfs(2) on Linux; other Unix targets and errors report None.
+#[cfg(not(any(
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+)))]
+pub fn rename_quirk_fs(path: &Path) -> Option<&'static str> {
+    const OVERLAYFS_SUPER_MAGIC: u32 = 0x794C_7630;
+    const FUSE_SUPER_MAGIC: u32 = 0x6573_5546;
+    match statfs_magic(path)? {
+        OVERLAYFS_SUPER_MAGIC => Some("overlayfs"),
+        FUSE_SUPER_MAGIC => Some("fuse"),
+        _ => None,
+    }
+}
+
+/// Name of the filesystem holding `path` from its statfs(2) magic ("ext4", "zfs", "nfs", ...;
+/// unknown magics as hex). Linux only; other Unix targets and errors report None.
+#[cfg(not(any(
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+)))]
+pub fn fstype_name(path: &Path) -> Option<String> {
+    let magic = statfs_magic(path)?;
+    let name = match magic {
+        0xEF53 => "ext4",
+        0x5846_5342 => "xfs",
+        0x9123_683E => "btrfs",
+        0x2FC1_2FC1 => "zfs",
+        0x0102_1994 => "tmpfs",
+        0x6969 => "nfs",
+        0x517B => "smb",
+        0xFF53_4D42 => "cifs",
+        0xFE53_4D42 => "smb2",
+        0x794C_7630 => "overlayfs",
+        0x6573_5546 => "fuse",
+        0x4D44 => "vfat",
+        0x2011_BAB0 => "exfat",
+        0x5346_544E => "ntfs",
+        0xF2F5_2010 => "f2fs",
+        0x0102_1997 => "9p",
+        _ => return Some(format!("0x{magic:x}")),
+    };
+    Some(name.to_string())
+}
+
+/// statfs(2) `f_type` of the filesystem holding `path` (Linux only).
+#[cfg(not(any(
+    target_os = "openbsd",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly"
+)))]
+fn statfs_magic(path: &Path) -> Option<u32> {
     #[cfg(target_os = "linux")]
     {
         use std::ffi::CString;
         use std::mem::MaybeUninit;
         use std::os::unix::ffi::OsStrExt;
-        let c_path = CString::new(path.as_os_str().as_bytes())
-            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "path contains null byte"))?;
+        let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
         unsafe {
-            let mut stat: MaybeUninit<libc::statvfs> = MaybeUninit::uninit();
-            if libc::statvfs(c_path.as_ptr(), stat.as_mut_ptr()) != 0 {
-                return Err(io::Error::last_os_error());
+            let mut stat: MaybeUninit<libc::statfs> = MaybeUninit::uninit();
+            if libc::statfs(c_path.as_ptr(), stat.as_mut_ptr()) != 0 {
+                return None;
             }
-            let stat = stat.assume_init();
-            Ok((stat.f_bavail).saturating_mul(stat.f_bsize))
+            // f_type's width differs between targets; compare the low 32 bits.
+            #[allow(clippy::unnecessary_cast)]
+            Some(stat.assume_init().f_type as u32)
         }
     }
     #[cfg(not(target_os = "linux"))]
     {
-        Ok(u64::MAX)
+        let _ = path;
+        None
     }
 }
 
@@ -119,13 +196,4 @@ mod tests {
             );
         }
     }
-
-    // tmp_sibling_name uniqueness test not needed here after removal.
-
-    #[test]
-    fn disk_space_smoke() {
-        let dir = tempdir().unwrap();
-        let bytes = check_disk_space(dir.path()).unwrap();
-        assert!(bytes > 0);
-    }
 }
@@ -4,17 +4,24 @@
 //! - Windows lacks POSIX mode semantics; we do not attempt ACL management here.
 //! - We avoid following symlinks only where std allows (limited on Windows).
 //! - Config writes are done via temp + rename to be atomic.
-//! - Disk space query uses GetDiskFreeSpaceExW.
+//! - Disk space query uses GetDiskFreeSpaceExW; volume identity uses the volume serial number.
+//! - Symlinks and junctions are both reparse points; `is_link_reparse_point` finds them via
+//!   GetFileAttributesW and the reparse tag from FindFirstFileW.
 
 use anyhow::{Context, Result, bail};
 use std::fs::{self, OpenOptions};
 use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use windows_sys::Win32::Storage::FileSystem::{
-    DeleteFileW, FILE_ATTRIBUTE_READONLY, FILE_ATTRIBUTE_TEMPORARY, GetDiskFreeSpaceExW,
-    GetFileAttributesW, SetFileAttributesW,
+    DeleteFileW, FILE_ATTRIBUTE_READONLY, FILE_ATTRIBUTE_REPARSE_POINT, FILE_ATTRIBUTE_TEMPORARY,
+    FindClose, FindFirstFileW, GetDiskFreeSpaceExW, GetFileAttributesW, GetVolumeInformationW,
+    GetVolumePathNameW, INVALID_FILE_ATTRIBUTES, SetFileAttributesW, WIN32_FIND_DATAW,
 };
 
+/// Reparse tag bit set for tags that redirect to another path (symlinks, junctions / mount
+/// points); clear for placeholders such as OneDrive files or deduplicated data.
+const REPARSE_TAG_NAME_SURROGATE: u32 = 0x2000_0000;
+
 /// Open a log file for appending (best-effort; no ACL changes). Ensures the file exists.
 pub fn open_log_file_secure_append(path: &Path) -> io::Result<std::fs::File> {
     if let Some(parent) = path.parent() {
@@ -66,6 +73,14 @@ pub fn set_file_mode_0600(_path: &Path) -> io::Result<()> {
     Ok(())
 }
 
+/// run_as_user/run_as_group are Unix-only; on Windows run the service as the desired account.
+pub fn drop_privileges(user: Option<&str>, group: Option<&str>) -> Result<()> {
+    if user.is_some() || group.is_some() {
+        bail!("run_as_user/run_as_group are not supported on Windows");
+    }
+    Ok(())
+}
+
 /// Minimal security check: path must be an existing, non-readonly directory.
 /// Full ACL verification is out of scope.
 pub fn ensure_secure_directory(path: &Path, label: &str) -> Result<()> {
@@ -107,6 +122,39 @@ pub fn check_disk_space(path: &std::path::Path) -> std::io::Result<u64> {
     Ok(free_avail)
 }
 
+/// Serial number of the volume holding `path` (GetVolumePathNameW + GetVolumeInformationW).
+pub fn volume_id(path: &Path) -> io::Result<u64> {
+    use std::os::windows::ffi::OsStrExt;
+
+    let wide: Vec<u16> = path
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    // The volume root is never longer than the path itself (plus a trailing separator).
+    let mut root = vec![0u16; wide.len() + 2];
+    if unsafe { GetVolumePathNameW(wide.as_ptr(), root.as_mut_ptr(), root.len() as u32) } == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let mut serial: u32 = 0;
+    let ok = unsafe {
+        GetVolumeInformationW(
+            root.as_ptr(),
+            std::ptr::null_mut(),
+            0,
+            &mut serial,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(u64::from(serial))
+}
+
 /// Create a sibling temporary filename for atomic write/rename.
 fn tmp_sibling_name(target: &Path) -> PathBuf {
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -163,11 +211,85 @@ fn to_wide(p: &Path) -> Option<Vec<u16>> {
     Some(v)
 }
 
+/// True if `path` itself is a symlink or a junction (a name-surrogate reparse point).
+/// The link is not followed. Missing paths are an error (`NotFound`).
+pub fn is_link_reparse_point(path: &Path) -> io::Result<bool> {
+    let wide =
+        to_wide(path).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid path"))?;
+    let attrs = unsafe { GetFileAttributesW(wide.as_ptr()) };
+    if attrs == INVALID_FILE_ATTRIBUTES {
+        return Err(io::Error::last_os_error());
+    }
+    if attrs & FILE_ATTRIBUTE_REPARSE_POINT == 0 {
+        return Ok(false);
+    }
+    // For reparse points, dwReserved0 of the find data holds the reparse tag.
+    let mut data: WIN32_FIND_DATAW = unsafe { std::mem::zeroed() };
+    let handle = unsafe { FindFirstFileW(wide.as_ptr(), &mut data) };
+    if handle as isize == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    unsafe {
+        FindClose(handle);
+    }
+    Ok(data.dwReserved0 & REPARSE_TAG_NAME_SURROGATE != 0)
+}
+
+/// Network filesystem detection is not used on Windows: the exclusive sidecar lock file
+/// already works over SMB, so `lock_strategy = auto` keeps it.
+pub fn is_network_fs(_path: &Path) -> bool {
+    false
+}
+
+/// File name limits are applied from the fixed budget in `duplicate.rs` on Windows.
+pub fn name_max(_path: &Path) -> Option<usize> {
+    None
+}
+
+/// Filesystem names are not collected on Windows.
+pub fn fstype_name(_path: &Path) -> Option<String> {
+    None
+}
+
+/// Mount flags are a Unix notion; Windows reports none.
+pub fn mount_flags(_path: &Path) -> io::Result<Vec<&'static str>> {
+    Ok(Vec::new())
+}
+
+/// No SIGQUIT on Windows; console close/logoff/shutdown events already reach the ctrlc handler.
+pub fn forward_sigquit_to_sigterm() -> io::Result<()> {
+    Ok(())
+}
+
+/// No overlay or FUSE filesystems to detect on Windows.
+pub fn rename_quirk_fs(_path: &Path) -> Option<&'static str> {
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use tempfile::tempdir;
 
+    #[test]
+    fn junctions_are_link_reparse_points() {
+        let dir = tempdir().unwrap();
+        let real = dir.path().join("real");
+        fs::create_dir(&real).unwrap();
+        let link = dir.path().join("junction");
+        let status = std::process::Command::new("cmd")
+            .args(["/C", "mklink", "/J"])
+            .arg(&link)
+            .arg(&real)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success());
+        assert!(is_link_reparse_point(&link).unwrap());
+        assert!(!is_link_reparse_point(&real).unwrap());
+        assert!(is_link_reparse_point(&dir.path().join("missing")).is_err());
+    }
+
     #[test]
     fn log_file_append_preserves_content() {
         let dir = tempdir().unwrap();
@@ -1,97 +1,23 @@
 //! Resume / reconciliation pass.
-//! Cleans up orphaned resume temp files and removes partial directory copies safely.
+//! Cleans up orphaned resume temp files, removes partial directory copies safely and gives
+//! abandoned claimed sources their names back, so the resolver sees them again.
 //! This runs automatically at startup so headless deployments self-heal after crashes.
+//! `aria_move clean` runs the same pass plus stale locks and abandoned claims.
 
-use anyhow::{Context, Result};
-use std::fs;
-use std::path::Path;
-use tracing::{debug, warn};
+use anyhow::Result;
 
 use aria_move::Config; // use public re-export from library crate
 
-// Regex-like simple prefix matching for resume temp files.
-fn is_resume_temp(entry: &Path) -> bool {
-    if let Some(name) = entry.file_name().and_then(|s| s.to_str()) {
-        name.starts_with(".aria_move.resume.") && name.ends_with(".tmp")
-    } else {
-        false
-    }
-}
-
+/// Remove orphan resume temps and partial directory copies, restore abandoned claims
+/// (see `fs_ops::reconcile`).
 pub fn reconcile(cfg: &Config) -> Result<()> {
-    cleanup_resume_temps(&cfg.completed_base)?;
-    cleanup_partial_dirs(&cfg.download_base, &cfg.completed_base)?;
-    Ok(())
-}
-
-fn cleanup_resume_temps(completed_base: &Path) -> Result<()> {
-    let rd = match fs::read_dir(completed_base) {
-        Ok(r) => r,
-        Err(_) => return Ok(()),
-    };
-    for ent in rd.flatten() {
-        let p = ent.path();
-        if p.is_file() && is_resume_temp(&p) {
-            match fs::remove_file(&p) {
-                Ok(()) => debug!(path = %p.display(), "Removed orphan resume temp"),
-                Err(e) => {
-                    warn!(error = %e, path = %p.display(), "Failed to remove orphan resume temp")
-                }
-            }
-        }
-    }
-    Ok(())
-}
-
-fn cleanup_partial_dirs(download_base: &Path, completed_base: &Path) -> Result<()> {
-    let rd = match fs::read_dir(completed_base) {
-        Ok(r) => r,
-        Err(_) => return Ok(()),
-    };
-    for ent in rd.flatten() {
-        let target = ent.path();
-        if !target.is_dir() {
-            continue;
-        }
-        // Skip hidden internal dirs.
-        if let Some(name) = target.file_name().and_then(|s| s.to_str())
-            && name.starts_with('.')
-        {
-            continue;
-        }
-        let source = download_base.join(ent.file_name());
-        if source.is_dir() {
-            // Heuristic: if dest has fewer entries than source, consider it partial and remove.
-            let src_count = count_entries(&source).unwrap_or(0);
-            let dst_count = count_entries(&target).unwrap_or(u64::MAX); // if error reading, skip
-            if src_count > 0 && dst_count < src_count {
-                match fs::remove_dir_all(&target) {
-                    Ok(()) => {
-                        debug!(partial = %target.display(), "Removed partial destination directory for clean restart")
-                    }
-                    Err(e) => {
-                        warn!(error = %e, partial = %target.display(), "Failed to remove partial destination directory")
-                    }
-                }
-            }
-        }
-    }
-    Ok(())
-}
-
-fn count_entries(dir: &Path) -> Result<u64> {
-    let mut c = 0u64;
-    for e in fs::read_dir(dir).with_context(|| format!("read_dir {}", dir.display()))? {
-        if e.is_ok() {
-            c += 1;
-        }
-    }
-    Ok(c)
+    aria_move::fs_ops::reconcile(cfg, false).map(|_| ())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
     use tempfile::tempdir;
 
     #[test]
@@ -122,6 +48,12 @@ mod tests {
         let dst_dir = completed.path().join("movie");
         fs::create_dir_all(&dst_dir).unwrap();
         fs::write(dst_dir.join("a.bin"), b"a").unwrap();
+        // Copy journal left by an interrupted directory copy.
+        fs::write(
+            dst_dir.join(".aria_move.copying"),
+            src_dir.to_string_lossy().as_bytes(),
+        )
+        .unwrap();
         let cfg = Config {
             download_base: download.path().into(),
             completed_base: completed.path().into(),
@@ -131,4 +63,58 @@ mod tests {
         // Partial dest should be gone so move can restart cleanly later.
         assert!(!dst_dir.exists());
     }
+
+    #[test]
+    fn keeps_unjournaled_or_diverging_dirs() {
+        let completed = tempdir().unwrap();
+        let download = tempdir().unwrap();
+        let src_dir = download.path().join("show");
+        fs::create_dir_all(&src_dir).unwrap();
+        fs::write(src_dir.join("e1.mkv"), b"1").unwrap();
+        fs::write(src_dir.join("e2.mkv"), b"2").unwrap();
+        // Fewer entries than the source, but no journal: e.g. a merged directory.
+        let merged = completed.path().join("show");
+        fs::create_dir_all(&merged).unwrap();
+        fs::write(merged.join("e1.mkv"), b"1").unwrap();
+        // Journaled, but holds a file the source does not have.
+        let src_other = download.path().join("album");
+        fs::create_dir_all(&src_other).unwrap();
+        fs::write(src_other.join("01.flac"), b"x").unwrap();
+        fs::write(src_other.join("02.flac"), b"y").unwrap();
+        let diverged = completed.path().join("album");
+        fs::create_dir_all(&diverged).unwrap();
+        fs::write(diverged.join("cover.jpg"), b"img").unwrap();
+        fs::write(
+            diverged.join(".aria_move.copying"),
+            src_other.to_string_lossy().as_bytes(),
+        )
+        .unwrap();
+        let cfg = Config {
+            download_base: download.path().into(),
+            completed_base: completed.path().into(),
+            ..Config::default()
+        };
+        reconcile(&cfg).unwrap();
+        assert!(merged.join("e1.mkv").exists());
+        assert!(diverged.join("cover.jpg").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn restores_abandoned_claim() {
+        let completed = tempdir().unwrap();
+        let download = tempdir().unwrap();
+        // Owner pid beyond any pid_max: the claiming process is gone.
+        let claimed = download.path().join(".aria_move.moving.99999999.42--album");
+        fs::create_dir_all(&claimed).unwrap();
+        fs::write(claimed.join("01.flac"), b"x").unwrap();
+        let cfg = Config {
+            download_base: download.path().into(),
+            completed_base: completed.path().into(),
+            ..Config::default()
+        };
+        reconcile(&cfg).unwrap();
+        assert!(download.path().join("album/01.flac").exists());
+        assert!(!claimed.exists());
+    }
 }
@@ -0,0 +1,152 @@
+//! Deployment self-test (`aria_move selftest`).
+//! - Writes a small sample into download_base and moves it with the real config, twice:
+//!   a file through the normal path (rename when the bases share a filesystem), then a
+//!   directory with the copy fallback forced (`force_copy`).
+//! - Each destination must hold exactly the sample bytes and the source must be gone; both
+//!   destinations are removed again afterwards, whatever the outcome.
+//!
+//! Notes:
+//! - Samples are named `aria_move-selftest-<pid>...`, so concurrent runs do not collide.
+//! - Moves go through `move_entry`, so locks, fsync, metadata, handlers and the audit log
+//!   all take part, exactly as for a real download.
+
+use anyhow::{Context, Result, bail};
+use aria_move::Config;
+use aria_move::fs_ops::{is_remote, move_entry};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Size of the sample file.
+const SAMPLE_BYTES: usize = 256 * 1024;
+
+/// One checked move of the self-test.
+#[derive(Debug, Serialize)]
+pub struct SelfTestStep {
+    /// `move` (normal path) or `copy-fallback` (rename skipped).
+    pub step: &'static str,
+    pub source: PathBuf,
+    pub destination: PathBuf,
+    pub elapsed_ms: u64,
+}
+
+/// Result of a successful `run`.
+#[derive(Debug, Serialize)]
+pub struct SelfTestReport {
+    pub download_base: PathBuf,
+    pub completed_base: PathBuf,
+    pub steps: Vec<SelfTestStep>,
+}
+
+/// Move a sample file and a sample directory into completed_base and verify both.
+pub fn run(config: &Config) -> Result<SelfTestReport> {
+    if config.dry_run {
+        bail!("selftest moves real sample files; it cannot run with --dry-run");
+    }
+    if is_remote(&config.completed_base) {
+        bail!("selftest needs a local completed_base");
+    }
+    let payload = sample_payload();
+    let tag = format!("aria_move-selftest-{}", std::process::id());
+    let mut steps = Vec::new();
+
+    let file = config.download_base.join(format!("{tag}.bin"));
+    let step = write_file(&file, &payload)
+        .and_then(|()| check_move(config, "move", &file, |dest| verify_file(dest, &payload)));
+    cleanup(&file);
+    steps.push(step?);
+
+    let dir = config.download_base.join(&tag);
+    let step = fs::create_dir(&dir)
+        .with_context(|| format!("create '{}'", dir.display()))
+        .and_then(|()| write_file(&dir.join("sample.bin"), &payload))
+        .and_then(|()| {
+            let forced = Config {
+                force_copy: true,
+                ..config.clone()
+            };
+            check_move(&forced, "copy-fallback", &dir, |dest| {
+                verify_file(&dest.join("sample.bin"), &payload)
+            })
+        });
+    cleanup(&dir);
+    steps.push(step?);
+
+    Ok(SelfTestReport {
+        download_base: config.download_base.clone(),
+        completed_base: config.completed_base.clone(),
+        steps,
+    })
+}
+
+/// Move `src`, check the result with `verify`, and remove the destination again.
+fn check_move(
+    config: &Config,
+    step: &'static str,
+    src: &Path,
+    verify: impl Fn(&Path) -> Result<()>,
+) -> Result<SelfTestStep> {
+    let started = Instant::now();
+    let dest = move_entry(config, src).with_context(|| format!("selftest {step}"))?;
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    let checked = if fs::symlink_metadata(src).is_ok() {
+        Err(anyhow::anyhow!(
+            "selftest {step}: source '{}' still exists after the move",
+            src.display()
+        ))
+    } else {
+        verify(&dest).with_context(|| format!("selftest {step}"))
+    };
+    cleanup(&dest);
+    checked?;
+    Ok(SelfTestStep {
+        step,
+        source: src.to_path_buf(),
+        destination: dest,
+        elapsed_ms,
+    })
+}
+
+fn verify_file(path: &Path, expected: &[u8]) -> Result<()> {
+    let got = fs::read(path).with_context(|| format!("read '{}'", path.display()))?;
+    if got != expected {
+        bail!(
+            "'{}' does not match the sample ({} of {} bytes)",
+            path.display(),
+            got.len(),
+            expected.len()
+        );
+    }
+    Ok(())
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Result<()> {
+    fs::write(path, data).with_context(|| format!("write '{}'", path.display()))
+}
+
+/// Remove a sample file or directory if it is (still) there.
+fn cleanup(path: &Path) {
+    match fs::symlink_metadata(path) {
+        Ok(m) if m.is_dir() => {
+            let _ = fs::remove_dir_all(path);
+        }
+        Ok(_) => {
+            let _ = fs::remove_file(path);
+        }
+        Err(_) => {}
+    }
+}
+
+/// Non-repeating bytes, so a truncated or shifted copy cannot pass.
+fn sample_payload() -> Vec<u8> {
+    let mut state: u32 = 0x9e37_79b9;
+    (0..SAMPLE_BYTES)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as u8
+        })
+        .collect()
+}
@@ -1,4 +1,4 @@
-//! Global shutdown flag set by ctrlc handler for SIGINT/SIGTERM.
+//! Global shutdown flag set by the ctrlc handler (SIGINT/SIGTERM/SIGHUP/SIGQUIT, Windows console events).
 //! Other modules can call is_requested() to abort promptly.
 //! Process-wide shutdown coordination.
 //! Provides a flag set by signal handlers so long-running operations can exit early.
@@ -6,13 +6,21 @@
 //! Notes:
 //! - Relaxed atomics are sufficient for a one-way "stop" flag.
 //! - `request()` is safe to call from signal handlers.
+//! - Copies register their temp file with `track_temp` while they write it, so a forced exit
+//!   (second signal) can remove them with `remove_tracked_temps` before the process ends.
 //!
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 
 static SHUTDOWN: AtomicBool = AtomicBool::new(false);
-// 0 = None, 1 = Signal, 2 = User, 3 = Space, 4 = Error (generic)
+// 0 = None, 1 = Signal, 2 = User, 3 = Space, 4 = Error (generic), 5 = Timeout (max_runtime)
 static REASON: AtomicU8 = AtomicU8::new(0);
 
+/// Reason code for a run that exceeded `max_runtime`.
+pub const REASON_TIMEOUT: u8 = 5;
+
 /// Request a cooperative shutdown (idempotent).
 #[inline]
 pub fn request() {
@@ -40,6 +48,44 @@ pub fn reason_code() -> u8 {
     REASON.load(Ordering::Relaxed)
 }
 
+/// True if the shutdown was requested because the run exceeded its time budget.
+pub fn timed_out() -> bool {
+    reason_code() == REASON_TIMEOUT
+}
+
+/// Temp files being written right now.
+static TEMPS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Registration of an in-flight temp file; unregisters (without deleting) on drop.
+#[derive(Debug)]
+pub struct TrackedTemp(PathBuf);
+
+impl Drop for TrackedTemp {
+    fn drop(&mut self) {
+        if let Ok(mut temps) = TEMPS.lock()
+            && let Some(i) = temps.iter().position(|p| *p == self.0)
+        {
+            temps.swap_remove(i);
+        }
+    }
+}
+
+/// Register `path` for removal by `remove_tracked_temps` until the returned guard is dropped.
+pub fn track_temp(path: &Path) -> TrackedTemp {
+    if let Ok(mut temps) = TEMPS.lock() {
+        temps.push(path.to_path_buf());
+    }
+    TrackedTemp(path.to_path_buf())
+}
+
+/// Remove every registered temp file (best-effort); returns how many were removed.
+pub fn remove_tracked_temps() -> usize {
+    let Ok(temps) = TEMPS.lock() else {
+        return 0;
+    };
+    temps.iter().filter(|p| fs::remove_file(p).is_ok()).count()
+}
+
 /// Test/utility-only: clear the shutdown flag.
 #[cfg(any(test, feature = "test-helpers"))]
 #[inline]
@@ -75,6 +121,21 @@ mod tests {
         assert_eq!(reason_code(), 2); // original preserved
     }
 
+    #[test]
+    fn tracked_temps_are_removed_until_released() {
+        let td = tempfile::tempdir().unwrap();
+        let kept = td.path().join("kept.tmp");
+        let dropped = td.path().join("dropped.tmp");
+        fs::write(&kept, b"x").unwrap();
+        fs::write(&dropped, b"x").unwrap();
+
+        let _guard = track_temp(&kept);
+        drop(track_temp(&dropped));
+        assert_eq!(remove_tracked_temps(), 1);
+        assert!(!kept.exists());
+        assert!(dropped.exists());
+    }
+
     #[test]
     fn reset_clears_both() {
         reset();
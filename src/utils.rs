@@ -1,16 +1,23 @@
+use crate::config::types::Config;
 use crate::shutdown;
 use anyhow::Context;
+use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 use tracing::debug;
 
+/// True if anything exists at `path`, including a dangling symlink (`exists()` follows links).
+pub(crate) fn path_taken(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok()
+}
+
 /// Return a unique destination by appending timestamp+pid when candidate exists.
 /// - Preserves non-UTF8 names (uses OsString).
 /// - Format: "<stem>-<millis>-<pid>[ -<n>].<ext?>"
 /// - Adds a tiny retry loop if a collision still occurs (extremely unlikely).
 pub(crate) fn unique_destination(candidate: &Path) -> PathBuf {
-    if !candidate.exists() {
+    if !path_taken(candidate) {
         return candidate.to_path_buf();
     }
 
@@ -26,44 +33,26 @@ pub(crate) fn unique_destination(candidate: &Path) -> PathBuf {
         .map(|s| s.to_owned())
         .unwrap_or_else(|| std::ffi::OsStr::new("file").to_owned());
     let ext = candidate.extension().map(|e| e.to_owned());
+    // The stem is shortened if the suffix would push the name past the filename limit.
+    let with_suffix =
+        |suffix: &str| crate::fs_ops::build_name_with_suffix(&stem, ext.as_deref(), suffix);
 
     // Try base "<stem>-<epoch>-<pid>[.ext]".
-    let mut name = std::ffi::OsString::new();
-    name.push(&stem);
-    name.push(format!("-{epoch_ms}-{pid}"));
-    if let Some(ref e) = ext {
-        name.push(".");
-        name.push(e);
-    }
-    let mut dest = candidate.with_file_name(&name);
-    if !dest.exists() {
+    let mut dest = candidate.with_file_name(with_suffix(&format!("-{epoch_ms}-{pid}")));
+    if !path_taken(&dest) {
         return dest;
     }
 
     // Fallback attempts: append "-<n>" before the extension.
     for n in 2u32..=5 {
-        let mut alt = std::ffi::OsString::new();
-        alt.push(&stem);
-        alt.push(format!("-{epoch_ms}-{pid}-{n}"));
-        if let Some(ref e) = ext {
-            alt.push(".");
-            alt.push(e);
-        }
-        dest = candidate.with_file_name(&alt);
-        if !dest.exists() {
+        dest = candidate.with_file_name(with_suffix(&format!("-{epoch_ms}-{pid}-{n}")));
+        if !path_taken(&dest) {
             return dest;
         }
     }
 
     // Final fallback with "-final".
-    let mut final_name = std::ffi::OsString::new();
-    final_name.push(&stem);
-    final_name.push(format!("-{epoch_ms}-{pid}-final"));
-    if let Some(ref e) = ext {
-        final_name.push(".");
-        final_name.push(e);
-    }
-    candidate.with_file_name(final_name)
+    candidate.with_file_name(with_suffix(&format!("-{epoch_ms}-{pid}-final")))
 }
 
 /// Prevent moving the download base itself (exact path equality).
@@ -101,20 +90,46 @@ pub(crate) fn is_writable_probe(dir: &Path) -> std::io::Result<()> {
     }
 }
 
+/// True for aria_move's own transient names (`.aria_move.*`: locks, claims, resume temps).
+pub fn is_internal_name(name: &OsStr) -> bool {
+    name.to_str().is_some_and(|n| n.starts_with(".aria_move."))
+}
+
+/// Hidden-file policy shared by resolve, sweep and reconcile.
+/// Internal `.aria_move.*` names are always skipped; other dot-names only when
+/// `include_hidden` is false.
+pub fn skip_by_hidden_policy(name: &OsStr, include_hidden: bool) -> bool {
+    if is_internal_name(name) {
+        return true;
+    }
+    !include_hidden && name.to_str().is_some_and(|n| n.starts_with('.'))
+}
+
+/// True for incomplete-download suffixes of the configured client presets and
+/// `partial_extensions` (by default .part, .aria2, .tmp, .crdownload).
+pub(crate) fn has_partial_extension(path: &Path, config: &Config) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| config.is_partial_extension(ext))
+}
+
+/// aria2 keeps `<name>.aria2` next to an in-progress download.
+pub(crate) fn has_aria2_control_file(path: &Path) -> bool {
+    let mut ctl = path.as_os_str().to_owned();
+    ctl.push(".aria2");
+    Path::new(&ctl).exists()
+}
+
 /// Heuristic to detect if a file is still being written / in-use.
-/// - Common incomplete suffixes (.part, .aria2, .tmp, .crdownload) -> mutable
+/// - Incomplete suffixes (see `has_partial_extension`) -> mutable
 /// - If size changes over a short interval -> mutable
-pub(crate) fn file_is_mutable(path: &Path) -> anyhow::Result<bool> {
-    if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
-        let ext = ext.to_ascii_lowercase();
-        if matches!(ext.as_str(), "part" | "aria2" | "tmp" | "crdownload") {
-            debug!(
-                "File {} has extension {} -> considered mutable",
-                path.display(),
-                ext
-            );
-            return Ok(true);
-        }
+pub(crate) fn file_is_mutable(path: &Path, config: &Config) -> anyhow::Result<bool> {
+    if has_partial_extension(path, config) {
+        debug!(
+            "File {} has an incomplete-download extension -> considered mutable",
+            path.display()
+        );
+        return Ok(true);
     }
 
     // Basic stable-size probe
@@ -168,6 +183,18 @@ mod tests {
     use std::thread;
     use tempfile::tempdir;
 
+    #[test]
+    fn hidden_policy_always_skips_internal_names() {
+        let internal = OsStr::new(".aria_move.moving.1.2");
+        let hidden = OsStr::new(".stash");
+        let plain = OsStr::new("movie.mkv");
+        assert!(skip_by_hidden_policy(internal, false));
+        assert!(skip_by_hidden_policy(internal, true));
+        assert!(skip_by_hidden_policy(hidden, false));
+        assert!(!skip_by_hidden_policy(hidden, true));
+        assert!(!skip_by_hidden_policy(plain, false));
+    }
+
     #[test]
     fn unique_destination_same_when_absent() {
         let td = tempdir().unwrap();
@@ -224,7 +251,7 @@ mod tests {
                 let _ = writeln!(file, "more");
             }
         });
-        let mut_flag = file_is_mutable(&f).unwrap();
+        let mut_flag = file_is_mutable(&f, &Config::default()).unwrap();
         assert!(mut_flag, "should detect mutability while writing");
     }
 
@@ -0,0 +1,67 @@
+use aria_move::api::{ConfigBuilder, Error, MoveOptions, Mover, OnDuplicate};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn mover_moves_a_file_and_reports_it() {
+    let td = tempdir().unwrap();
+    let root = dunce::canonicalize(td.path()).unwrap();
+    let mover = Mover::new(
+        ConfigBuilder::default()
+            .download_base(root.join("incoming"))
+            .completed_base(root.join("completed")),
+    )
+    .unwrap();
+    let src = mover.config().download_base.join("movie.mkv");
+    fs::write(&src, b"data").unwrap();
+
+    let plan = mover.plan(&src).unwrap();
+    assert_eq!(plan.destination, root.join("completed").join("movie.mkv"));
+    assert_eq!(plan.bytes, 4);
+    assert!(src.exists());
+
+    let report = mover.move_path(&src).unwrap();
+    assert_eq!(report.source, src);
+    assert_eq!(report.destination, root.join("completed").join("movie.mkv"));
+    assert!(!report.dry_run);
+    // Same filesystem: a rename, so nothing was copied.
+    assert_eq!(report.timings.bytes_copied, 0);
+    assert_eq!(report.timings.throughput_mb_s(), None);
+    assert!(report.timings.total >= report.timings.rename);
+    assert_eq!(fs::read(&report.destination).unwrap(), b"data");
+    assert!(mover.sweep(false).unwrap().found.is_empty());
+
+    // Same name again: skip leaves the source alone, rename picks "movie (2).mkv".
+    fs::write(&src, b"again").unwrap();
+    let skip = MoveOptions {
+        on_duplicate: Some(OnDuplicate::Skip),
+        ..Default::default()
+    };
+    let err = mover.move_path_with(&src, &skip).unwrap_err();
+    assert_eq!(err.code(), "destination_exists");
+    assert!(src.exists());
+
+    let rename = MoveOptions {
+        on_duplicate: Some(OnDuplicate::RenameWithSuffix),
+        ..Default::default()
+    };
+    let report = mover.move_path_with(&src, &rename).unwrap();
+    assert_eq!(
+        report.destination,
+        root.join("completed").join("movie (2).mkv")
+    );
+}
+
+#[test]
+fn mover_rejects_invalid_config_with_typed_error() {
+    let td = tempdir().unwrap();
+    let base = td.path().join("same");
+    let err: Error = Mover::new(
+        ConfigBuilder::default()
+            .download_base(&base)
+            .completed_base(&base),
+    )
+    .unwrap_err();
+    assert_eq!(err.code(), "other");
+    assert!(err.to_string().contains("same path"), "{err}");
+}
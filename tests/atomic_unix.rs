@@ -1,4 +1,4 @@
-#[cfg(unix)]
+#[cfg(all(unix, feature = "unstable"))]
 mod tests {
     use aria_move::fs_ops::{MoveOutcome, try_atomic_move};
     use std::fs;
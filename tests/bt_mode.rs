@@ -0,0 +1,72 @@
+#![cfg(unix)]
+
+use aria_move::{Config, MoveOptions, move_entry_with};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use tempfile::tempdir;
+
+#[test]
+fn seeding_gid_is_copied_and_finished_gid_is_moved() {
+    let td = tempdir().unwrap();
+    let base = fs::canonicalize(td.path()).unwrap();
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+
+    // Stand-in for curl that records the request and answers as aria2 would.
+    let sent = base.join("sent.log");
+    let curl = base.join("fake-curl");
+    let answer = |reply: &str| {
+        fs::write(
+            &curl,
+            format!(
+                "#!/bin/sh\ncat >> '{}'\nprintf '%s' '{reply}'\n",
+                sent.display()
+            ),
+        )
+        .unwrap();
+        fs::set_permissions(&curl, fs::Permissions::from_mode(0o755)).unwrap();
+    };
+    unsafe { std::env::set_var("ARIA_MOVE_CURL", &curl) };
+
+    let mut cfg = Config::new(&download, &completed);
+    cfg.bt_mode = true;
+    cfg.aria2_rpc_secret = Some("s3cret".into());
+    let opts = MoveOptions {
+        gid: Some("2089b05ecca3d829".into()),
+        ..MoveOptions::default()
+    };
+
+    answer(r#"{"id":"aria_move","jsonrpc":"2.0","result":{"status":"active","seeder":"true"}}"#);
+    let src = download.join("seeding.bin");
+    fs::write(&src, b"data").unwrap();
+    let dest = move_entry_with(&cfg, &src, &opts).unwrap();
+    assert!(src.exists(), "a seeding download keeps its source");
+    assert_eq!(fs::read(&dest).unwrap(), b"data");
+    let log = fs::read_to_string(&sent).unwrap();
+    assert!(log.contains("aria2.tellStatus"), "{log}");
+    assert!(log.contains("token:s3cret"), "{log}");
+    assert!(log.contains("2089b05ecca3d829"), "{log}");
+
+    answer(r#"{"id":"aria_move","jsonrpc":"2.0","result":{"status":"complete"}}"#);
+    let src = download.join("done.bin");
+    fs::write(&src, b"data").unwrap();
+    move_entry_with(&cfg, &src, &opts).unwrap();
+    assert!(!src.exists(), "a finished download is moved");
+
+    // No answer from aria2: copy rather than pull files out from under it.
+    answer(r#"{"id":"aria_move","jsonrpc":"2.0","error":{"code":1,"message":"GID not found"}}"#);
+    let src = download.join("unknown.bin");
+    fs::write(&src, b"data").unwrap();
+    move_entry_with(&cfg, &src, &opts).unwrap();
+    assert!(src.exists());
+
+    // Without a GID (not a hook run) aria2 is not asked.
+    fs::remove_file(&sent).unwrap();
+    let src = download.join("manual.bin");
+    fs::write(&src, b"data").unwrap();
+    move_entry_with(&cfg, &src, &MoveOptions::default()).unwrap();
+    assert!(!src.exists());
+    assert!(!sent.exists());
+}
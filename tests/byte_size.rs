@@ -0,0 +1,53 @@
+use aria_move::config::types::ByteSize;
+use std::str::FromStr;
+
+#[test]
+fn parse_plain_decimal_and_binary_sizes() {
+    assert_eq!(ByteSize::parse("4096"), Ok(ByteSize(4096)));
+    assert_eq!(ByteSize::parse("4096B"), Ok(ByteSize(4096)));
+    assert_eq!(ByteSize::parse("500MiB"), Ok(ByteSize(500 * 1024 * 1024)));
+    assert_eq!(ByteSize::parse("500M"), Ok(ByteSize(500 * 1024 * 1024)));
+    assert_eq!(ByteSize::parse("1.5GB"), Ok(ByteSize(1_500_000_000)));
+    assert_eq!(ByteSize::parse("2kb"), Ok(ByteSize(2000)));
+    assert_eq!(ByteSize::parse(" 1 TiB "), Ok(ByteSize(1 << 40)));
+}
+
+#[test]
+fn fractional_bytes_are_rejected() {
+    for bad in ["1.5", "1.5B", "0.5 b"] {
+        let err = ByteSize::parse(bad).unwrap_err();
+        assert!(err.contains("invalid size"), "{bad}: {err}");
+    }
+    assert_eq!(ByteSize::parse("0.5KiB"), Ok(ByteSize(512)));
+}
+
+#[test]
+fn parse_invalid_sizes_is_err() {
+    for bad in ["", "MiB", "-1", "5 parsecs", "1.2.3GB", "99999999999TiB"] {
+        let err = ByteSize::from_str(bad).unwrap_err();
+        assert!(err.contains("invalid size"), "{bad}: {err}");
+    }
+}
+
+#[test]
+fn display_roundtrips_with_fromstr() {
+    for size in [
+        ByteSize(0),
+        ByteSize(1000),
+        ByteSize(4 << 20),
+        ByteSize(3 << 30),
+    ] {
+        let s = size.to_string();
+        assert_eq!(ByteSize::from_str(&s), Ok(size), "roundtrip failed for {s}");
+    }
+    assert_eq!(ByteSize(4 << 20).to_string(), "4MiB");
+}
+
+#[test]
+fn deserializes_from_strings_and_integers() {
+    let s: ByteSize = serde_json::from_str("\"1.5GB\"").unwrap();
+    assert_eq!(s, ByteSize(1_500_000_000));
+    let n: ByteSize = serde_json::from_str("4096").unwrap();
+    assert_eq!(n, ByteSize(4096));
+    assert!(serde_json::from_str::<ByteSize>("\"lots\"").is_err());
+}
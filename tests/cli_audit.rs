@@ -0,0 +1,82 @@
+use assert_cmd::cargo;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write_cfg(path: &Path, download: &Path, completed: &Path, audit: &Path) {
+    let xml = format!(
+        r#"<config>
+  <download_base>{}</download_base>
+  <completed_base>{}</completed_base>
+  <log_level>quiet</log_level>
+  <audit_log>{}</audit_log>
+</config>"#,
+        download.display(),
+        completed.display(),
+        audit.display()
+    );
+    fs::write(path, xml).unwrap();
+}
+
+#[test]
+fn moves_are_audited_and_tampering_is_detected() {
+    let td = tempdir().unwrap();
+    let base = fs::canonicalize(td.path()).unwrap();
+    let cfg_path = base.join("config.xml");
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    let audit = base.join("state").join("audit.jsonl");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    write_cfg(&cfg_path, &download, &completed, &audit);
+
+    let me = cargo::cargo_bin!("aria_move");
+    for name in ["a.mkv", "b.mkv"] {
+        let src = download.join(name);
+        fs::write(&src, name).unwrap();
+        let out = Command::new(me)
+            .env("ARIA_MOVE_CONFIG", &cfg_path)
+            .arg(&src)
+            .output()
+            .expect("spawn binary");
+        assert!(
+            out.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+    }
+    // A dry run leaves no record.
+    let src = download.join("c.mkv");
+    fs::write(&src, "c").unwrap();
+    let out = Command::new(me)
+        .env("ARIA_MOVE_CONFIG", &cfg_path)
+        .arg("--dry-run")
+        .arg(&src)
+        .output()
+        .expect("spawn binary");
+    assert!(out.status.success());
+
+    let verify = || {
+        Command::new(me)
+            .env("ARIA_MOVE_CONFIG", &cfg_path)
+            .args(["audit", "verify"])
+            .output()
+            .expect("spawn binary")
+    };
+    let out = verify();
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let report: serde_json::Value = serde_json::from_slice(&out.stdout).expect("JSON report");
+    assert_eq!(report["records"], 2);
+
+    let log = fs::read_to_string(&audit).unwrap();
+    assert!(log.contains(&*completed.join("a.mkv").to_string_lossy()));
+    fs::write(&audit, log.replacen("b.mkv", "x.mkv", 1)).unwrap();
+    let out = verify();
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("line 2"));
+}
@@ -0,0 +1,60 @@
+use assert_cmd::cargo;
+use std::fs;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write_cfg(path: &std::path::Path, download: &std::path::Path, completed: &std::path::Path) {
+    let xml = format!(
+        r#"<config>
+  <download_base>{}</download_base>
+  <completed_base>{}</completed_base>
+  <log_level>quiet</log_level>
+</config>"#,
+        download.display(),
+        completed.display()
+    );
+    fs::write(path, xml).unwrap();
+}
+
+#[test]
+fn clean_reports_json_and_honors_dry_run() {
+    let td = tempdir().unwrap();
+    let base = fs::canonicalize(td.path()).unwrap();
+    let cfg_path = base.join("config.xml");
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    write_cfg(&cfg_path, &download, &completed);
+
+    let temp = completed.join(".aria_move.resume.0123456789abcdef.tmp");
+    fs::write(&temp, b"partial").unwrap();
+    let kept = download.join("movie.mkv");
+    fs::write(&kept, b"data").unwrap();
+
+    let me = cargo::cargo_bin!("aria_move");
+    let run = |dry_run: bool| {
+        let mut cmd = Command::new(me);
+        cmd.env("ARIA_MOVE_CONFIG", &cfg_path).arg("clean");
+        if dry_run {
+            cmd.arg("--dry-run");
+        }
+        let out = cmd.output().expect("spawn binary");
+        assert!(
+            out.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        serde_json::from_slice::<serde_json::Value>(&out.stdout).expect("JSON report")
+    };
+
+    let report = run(true);
+    assert_eq!(report["dry_run"], true);
+    assert_eq!(report["actions"][0]["kind"], "temp_file");
+    assert!(temp.exists(), "dry run must not remove anything");
+
+    let report = run(false);
+    assert_eq!(report["actions"].as_array().unwrap().len(), 1);
+    assert!(!temp.exists());
+    assert!(kept.exists(), "regular files are never touched");
+}
@@ -0,0 +1,63 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::tempdir;
+
+/// Move one file with `<console_stream>` set to `stream` and return the process output.
+fn run_move(base: &Path, stream: &str) -> Output {
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    let cfg_path = base.join("config.xml");
+    fs::write(
+        &cfg_path,
+        format!(
+            "<config><download_base>{}</download_base><completed_base>{}</completed_base><log_level>info</log_level><log_file></log_file><console_stream>{stream}</console_stream></config>",
+            download.display(),
+            completed.display()
+        ),
+    )
+    .unwrap();
+    let src = download.join("a.bin");
+    fs::write(&src, b"data").unwrap();
+
+    let out = Command::new(assert_cmd::cargo::cargo_bin!("aria_move"))
+        .env("ARIA_MOVE_CONFIG", &cfg_path)
+        .arg(&src)
+        .output()
+        .expect("spawn binary");
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(completed.join("a.bin").exists());
+    out
+}
+
+#[test]
+fn stderr_stream_keeps_stdout_clean() {
+    let td = tempdir().unwrap();
+    let base = dunce::canonicalize(td.path()).unwrap();
+    let out = run_move(&base, "stderr");
+    assert!(
+        out.stdout.is_empty(),
+        "stdout: {}",
+        String::from_utf8_lossy(&out.stdout)
+    );
+    assert!(!out.stderr.is_empty());
+}
+
+#[test]
+fn none_stream_prints_nothing_on_success() {
+    let td = tempdir().unwrap();
+    let base = dunce::canonicalize(td.path()).unwrap();
+    let out = run_move(&base, "none");
+    assert!(out.stdout.is_empty());
+    assert!(
+        out.stderr.is_empty(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
@@ -0,0 +1,133 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::tempdir;
+
+/// Stand-in for aria2's RPC: tellStatus knows two GIDs, any other GID is an error.
+fn fake_aria2(base: &Path, download: &Path) -> std::path::PathBuf {
+    let curl = base.join("fake-curl");
+    let reply = |files: &str| {
+        format!(
+            r#"printf '%s' '{{"result":{{"dir":"{}","files":[{files}]}}}}'"#,
+            download.display()
+        )
+    };
+    fs::write(
+        &curl,
+        format!(
+            "#!/bin/sh\nreq=$(cat)\ncase \"$req\" in\n  *aaaa000000000001*) {} ;;\n  *bbbb000000000002*) {} ;;\n  *) printf '%s' '{{\"error\":{{\"code\":1,\"message\":\"GID is not found\"}}}}' ;;\nesac\n",
+            reply(&format!(r#"{{"path":"{}/a.bin"}}"#, download.display())),
+            reply(&format!(
+                r#"{{"path":"{0}/pack/x.bin"}},{{"path":"{0}/pack/y.bin"}}"#,
+                download.display()
+            )),
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&curl, fs::Permissions::from_mode(0o755)).unwrap();
+    curl
+}
+
+fn run(base: &Path, args: &[&str]) -> (Output, String) {
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    let cfg_path = base.join("config.xml");
+    fs::write(
+        &cfg_path,
+        format!(
+            "<config><download_base>{}</download_base><completed_base>{}</completed_base><log_file></log_file></config>",
+            download.display(),
+            completed.display()
+        ),
+    )
+    .unwrap();
+    let out = Command::new(assert_cmd::cargo::cargo_bin!("aria_move"))
+        .env("ARIA_MOVE_CONFIG", &cfg_path)
+        .env("ARIA_MOVE_CURL", fake_aria2(base, &download))
+        .args(args)
+        .output()
+        .expect("spawn binary");
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&out.stdout),
+        String::from_utf8_lossy(&out.stderr)
+    );
+    (out, text)
+}
+
+fn setup(base: &Path) {
+    let download = base.join("incoming");
+    fs::create_dir_all(download.join("pack")).unwrap();
+    fs::create_dir_all(base.join("completed")).unwrap();
+    fs::write(download.join("a.bin"), b"a").unwrap();
+    fs::write(download.join("pack/x.bin"), b"x").unwrap();
+    fs::write(download.join("pack/y.bin"), b"y").unwrap();
+}
+
+#[test]
+fn repeated_and_comma_separated_gids_are_moved_one_by_one() {
+    let td = tempdir().unwrap();
+    let base = dunce::canonicalize(td.path()).unwrap();
+    setup(&base);
+
+    let (out, text) = run(
+        &base,
+        &[
+            "--gid",
+            "aaaa000000000001,cccc000000000003",
+            "--gid",
+            "bbbb000000000002",
+        ],
+    );
+    assert!(!out.status.success(), "{text}");
+    assert!(text.contains("GID aaaa000000000001: moved"), "{text}");
+    assert!(text.contains("GID bbbb000000000002: moved"), "{text}");
+    assert!(
+        text.contains("GID cccc000000000003: could not move"),
+        "{text}"
+    );
+    assert!(text.contains("GID is not found"), "{text}");
+    let completed = base.join("completed");
+    assert!(completed.join("a.bin").exists());
+    assert!(completed.join("pack/x.bin").exists() && completed.join("pack/y.bin").exists());
+}
+
+#[test]
+fn hook_form_with_a_gid_list_is_a_batch() {
+    let td = tempdir().unwrap();
+    let base = dunce::canonicalize(td.path()).unwrap();
+    setup(&base);
+
+    let path = base.join("incoming/a.bin");
+    let (out, text) = run(
+        &base,
+        &[
+            "aaaa000000000001,bbbb000000000002",
+            "3",
+            path.to_str().unwrap(),
+        ],
+    );
+    assert!(out.status.success(), "{text}");
+    assert!(text.contains("GID aaaa000000000001: moved"), "{text}");
+    assert!(text.contains("GID bbbb000000000002: moved"), "{text}");
+    assert!(base.join("completed/pack").is_dir());
+}
+
+#[test]
+fn gid_with_a_source_path_is_refused() {
+    let td = tempdir().unwrap();
+    let base = dunce::canonicalize(td.path()).unwrap();
+    setup(&base);
+
+    let path = base.join("incoming/a.bin");
+    let (out, text) = run(
+        &base,
+        &["--gid", "aaaa000000000001", path.to_str().unwrap()],
+    );
+    assert!(!out.status.success(), "{text}");
+    assert!(text.contains("--gid takes the paths from aria2"), "{text}");
+    assert!(path.exists());
+}
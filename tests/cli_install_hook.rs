@@ -0,0 +1,74 @@
+#![cfg(unix)]
+
+use assert_cmd::cargo;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::process::Command;
+use tempfile::tempdir;
+
+#[test]
+fn install_hook_points_aria2_conf_at_this_binary() {
+    let td = tempdir().unwrap();
+    let conf = td.path().join("aria2.conf");
+    fs::write(
+        &conf,
+        "dir=/data\non-download-complete=\"/old/aria_move\"\n",
+    )
+    .unwrap();
+
+    let me = cargo::cargo_bin!("aria_move");
+    let out = Command::new(me)
+        .args(["install-hook", "--aria2-conf"])
+        .arg(&conf)
+        .output()
+        .expect("spawn binary");
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    let exe = dunce::canonicalize(me).unwrap();
+    let text = fs::read_to_string(&conf).unwrap();
+    assert_eq!(
+        text,
+        format!("dir=/data\non-download-complete={}\n", exe.display())
+    );
+    assert!(
+        fs::read_to_string(td.path().join("aria2.conf.bak"))
+            .unwrap()
+            .contains("/old/aria_move")
+    );
+
+    let check = Command::new(me)
+        .args(["install-hook", "--check", "--aria2-conf"])
+        .arg(&conf)
+        .output()
+        .expect("spawn binary");
+    assert!(check.status.success());
+}
+
+#[test]
+fn check_reports_missing_exec_bit() {
+    let td = tempdir().unwrap();
+    let hook = td.path().join("hook.sh");
+    fs::write(&hook, "#!/bin/sh\n").unwrap();
+    fs::set_permissions(&hook, fs::Permissions::from_mode(0o644)).unwrap();
+    let conf = td.path().join("aria2.conf");
+    let original = format!("on-download-complete={}\n", hook.display());
+    fs::write(&conf, &original).unwrap();
+
+    let out = Command::new(cargo::cargo_bin!("aria_move"))
+        .args(["install-hook", "--check", "--aria2-conf"])
+        .arg(&conf)
+        .output()
+        .expect("spawn binary");
+
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("not executable"),
+        "unexpected stderr: {stderr}"
+    );
+    assert_eq!(fs::read_to_string(&conf).unwrap(), original);
+}
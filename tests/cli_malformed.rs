@@ -74,3 +74,34 @@ fn too_many_args_are_rejected_by_cli() {
         "stderr did not indicate too many args: {stderr}"
     );
 }
+
+#[test]
+fn invalid_size_in_config_fails_the_run_naming_the_field() {
+    let td = tempdir().unwrap();
+    let base = fs::canonicalize(td.path()).unwrap();
+    let cfg_path = base.join("config.xml");
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    write_cfg(&cfg_path, &download, &completed);
+    let xml = fs::read_to_string(&cfg_path).unwrap().replace(
+        "</config>",
+        "  <space_cushion>lots</space_cushion>\n</config>",
+    );
+    fs::write(&cfg_path, xml).unwrap();
+    let src = download.join("file.bin");
+    fs::write(&src, b"data").unwrap();
+
+    let me = cargo::cargo_bin!("aria_move");
+    let out = Command::new(me)
+        .env("ARIA_MOVE_CONFIG", &cfg_path)
+        .arg(&src)
+        .output()
+        .expect("spawn binary");
+
+    assert!(!out.status.success(), "expected config failure");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("space_cushion"), "stderr: {stderr}");
+    assert!(src.exists(), "source must be kept");
+}
@@ -0,0 +1,64 @@
+#![cfg(unix)]
+
+use assert_cmd::cargo;
+use fs2::FileExt;
+use std::fs::{self, File};
+use std::path::Path;
+use std::process::Command;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+/// Sidecar lock guarding `path` (same naming as the per-path locks).
+fn sidecar(path: &Path) -> std::path::PathBuf {
+    let name = path.file_name().unwrap().as_encoded_bytes();
+    let hash = name.iter().fold(0xcbf2_9ce4_8422_2325u64, |h, &b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    path.parent()
+        .unwrap()
+        .join(format!(".aria_move.{hash:016x}.lock"))
+}
+
+#[test]
+fn run_stops_after_max_runtime_and_keeps_the_source() {
+    let td = tempdir().unwrap();
+    let base = dunce::canonicalize(td.path()).unwrap();
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    let cfg_path = base.join("config.xml");
+    fs::write(
+        &cfg_path,
+        format!(
+            "<config><download_base>{}</download_base><completed_base>{}</completed_base><lock_strategy>flock</lock_strategy></config>",
+            download.display(),
+            completed.display()
+        ),
+    )
+    .unwrap();
+    let src = download.join("a.bin");
+    fs::write(&src, b"data").unwrap();
+
+    // Another process holds the source lock, so the move waits until the budget runs out.
+    let held = File::create(sidecar(&src)).unwrap();
+    held.lock_exclusive().unwrap();
+
+    let started = Instant::now();
+    let out = Command::new(cargo::cargo_bin!("aria_move"))
+        .env("ARIA_MOVE_CONFIG", &cfg_path)
+        .args(["--max-runtime-seconds", "1"])
+        .arg(&src)
+        .output()
+        .expect("spawn binary");
+
+    assert_eq!(
+        out.status.code(),
+        Some(124),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(started.elapsed() < Duration::from_secs(20));
+    assert_eq!(fs::read(&src).unwrap(), b"data");
+    assert!(!completed.join("a.bin").exists());
+}
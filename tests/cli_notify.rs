@@ -0,0 +1,146 @@
+#![cfg(unix)]
+
+use assert_cmd::cargo;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+/// Stand-in for curl: appends the config it reads from stdin (`-K -`) to `log`.
+fn fake_curl(dir: &Path, log: &Path) -> std::path::PathBuf {
+    let fake = dir.join("fake-curl");
+    fs::write(
+        &fake,
+        format!(
+            "#!/bin/sh\n[ \"$1\" = -K ] && [ \"$2\" = - ] || exit 2\ncat >> '{}'\necho --- >> '{}'\n",
+            log.display(),
+            log.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&fake, fs::Permissions::from_mode(0o755)).unwrap();
+    fake
+}
+
+#[test]
+fn notifiers_receive_only_their_events() {
+    let td = tempdir().unwrap();
+    let base = fs::canonicalize(td.path()).unwrap();
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    let cfg_path = base.join("config.xml");
+    fs::write(
+        &cfg_path,
+        format!(
+            r#"<config>
+  <download_base>{}</download_base>
+  <completed_base>{}</completed_base>
+  <log_level>quiet</log_level>
+  <notifiers>
+    <notifier kind="ntfy" topic="dl" token="tk_secret" events="success"/>
+    <notifier kind="pushover" token="app" user="uk" events="failure"/>
+  </notifiers>
+</config>"#,
+            download.display(),
+            completed.display()
+        ),
+    )
+    .unwrap();
+    let sent = base.join("sent.log");
+    let curl = fake_curl(&base, &sent);
+    let me = cargo::cargo_bin!("aria_move");
+    let run = |src: &Path| {
+        Command::new(me)
+            .env("ARIA_MOVE_CONFIG", &cfg_path)
+            .env("ARIA_MOVE_CURL", &curl)
+            .arg(src)
+            .output()
+            .expect("spawn binary")
+    };
+
+    let src = download.join("movie.mkv");
+    fs::write(&src, b"data").unwrap();
+    let out = run(&src);
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let log = fs::read_to_string(&sent).unwrap();
+    assert_eq!(log.matches("---").count(), 1, "{log}");
+    assert!(log.contains("url = \"https://ntfy.sh/dl\""), "{log}");
+    assert!(log.contains("Authorization: Bearer tk_secret"));
+    assert!(log.contains(&*completed.join("movie.mkv").to_string_lossy()));
+
+    fs::remove_file(&sent).unwrap();
+    let out = run(&download.join("missing.mkv"));
+    assert!(!out.status.success());
+    let log = fs::read_to_string(&sent).unwrap_or_default();
+    assert!(
+        log.is_empty(),
+        "resolve failures are not move failures: {log}"
+    );
+
+    // A move that fails: the same torrent completes again under `--on-redownload skip`.
+    let hash = "c12fe1c06bba254a9dc9f519b335aa7c1367a88a";
+    let run_torrent = |src: &Path| {
+        Command::new(me)
+            .env("ARIA_MOVE_CONFIG", &cfg_path)
+            .env("ARIA_MOVE_CURL", &curl)
+            .args(["--infohash", hash, "--on-redownload", "skip"])
+            .arg(src)
+            .output()
+            .expect("spawn binary")
+    };
+    let src = download.join("show.mkv");
+    fs::write(&src, b"v1").unwrap();
+    assert!(run_torrent(&src).status.success());
+    fs::remove_file(&sent).unwrap();
+    fs::write(&src, b"v2").unwrap();
+    assert!(!run_torrent(&src).status.success());
+    let log = fs::read_to_string(&sent).unwrap();
+    assert_eq!(log.matches("---").count(), 1, "{log}");
+    assert!(log.contains("api.pushover.net"), "{log}");
+    assert!(log.contains("form-string = \"priority=1\""), "{log}");
+}
+
+#[test]
+fn invalid_notifier_fails_the_run() {
+    let td = tempdir().unwrap();
+    let base = fs::canonicalize(td.path()).unwrap();
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    let cfg_path = base.join("config.xml");
+    fs::write(
+        &cfg_path,
+        format!(
+            r#"<config>
+  <download_base>{}</download_base>
+  <completed_base>{}</completed_base>
+  <notifiers>
+    <notifier kind="gotify" events="success"/>
+  </notifiers>
+</config>"#,
+            download.display(),
+            completed.display()
+        ),
+    )
+    .unwrap();
+    let src = download.join("movie.mkv");
+    fs::write(&src, b"data").unwrap();
+
+    let out = Command::new(cargo::cargo_bin!("aria_move"))
+        .env("ARIA_MOVE_CONFIG", &cfg_path)
+        .arg(&src)
+        .output()
+        .expect("spawn binary");
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("notifier"), "stderr: {stderr}");
+    assert!(src.exists(), "nothing moved with a broken config");
+}
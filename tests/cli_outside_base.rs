@@ -0,0 +1,76 @@
+use assert_cmd::cargo;
+use std::fs;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write_cfg(path: &std::path::Path, download: &std::path::Path, completed: &std::path::Path) {
+    let xml = format!(
+        r#"<config>
+  <download_base>{}</download_base>
+  <completed_base>{}</completed_base>
+  <log_level>quiet</log_level>
+</config>"#,
+        download.display(),
+        completed.display()
+    );
+    fs::write(path, xml).unwrap();
+}
+
+#[test]
+fn explicit_path_outside_download_base_is_refused() {
+    let td = tempdir().unwrap();
+    let base = dunce::canonicalize(td.path()).unwrap();
+    let cfg_path = base.join("config.xml");
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    write_cfg(&cfg_path, &download, &completed);
+
+    let outside = base.join("secret.txt");
+    fs::write(&outside, b"x").unwrap();
+
+    let out = Command::new(cargo::cargo_bin!("aria_move"))
+        .env("ARIA_MOVE_CONFIG", &cfg_path)
+        .arg(&outside)
+        .output()
+        .expect("spawn binary");
+
+    assert!(!out.status.success(), "expected the source to be refused");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("outside the download base"),
+        "unexpected stderr: {stderr}"
+    );
+    assert!(outside.exists());
+}
+
+#[test]
+fn allow_outside_base_moves_it() {
+    let td = tempdir().unwrap();
+    let base = dunce::canonicalize(td.path()).unwrap();
+    let cfg_path = base.join("config.xml");
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    write_cfg(&cfg_path, &download, &completed);
+
+    let outside = base.join("elsewhere.txt");
+    fs::write(&outside, b"x").unwrap();
+
+    let out = Command::new(cargo::cargo_bin!("aria_move"))
+        .env("ARIA_MOVE_CONFIG", &cfg_path)
+        .arg("--allow-outside-base")
+        .arg(&outside)
+        .output()
+        .expect("spawn binary");
+
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(!outside.exists());
+    assert!(completed.join("elsewhere.txt").exists());
+}
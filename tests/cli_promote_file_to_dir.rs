@@ -47,7 +47,11 @@ fn passing_file_under_base_moves_entire_top_folder() {
         .output()
         .expect("spawn binary");
 
-    assert!(out.status.success(), "expected success; stderr: {}", String::from_utf8_lossy(&out.stderr));
+    assert!(
+        out.status.success(),
+        "expected success; stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
 
     let dest_dir = completed.join("rootname");
     assert!(dest_dir.exists());
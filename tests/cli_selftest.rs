@@ -0,0 +1,75 @@
+use assert_cmd::cargo;
+use std::fs;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn write_cfg(path: &std::path::Path, download: &std::path::Path, completed: &std::path::Path) {
+    let xml = format!(
+        r#"<config>
+  <download_base>{}</download_base>
+  <completed_base>{}</completed_base>
+  <log_level>quiet</log_level>
+</config>"#,
+        download.display(),
+        completed.display()
+    );
+    fs::write(path, xml).unwrap();
+}
+
+#[test]
+fn selftest_moves_and_cleans_up() {
+    let td = tempdir().unwrap();
+    let base = dunce::canonicalize(td.path()).unwrap();
+    let cfg_path = base.join("config.xml");
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    write_cfg(&cfg_path, &download, &completed);
+
+    let out = Command::new(cargo::cargo_bin!("aria_move"))
+        .env("ARIA_MOVE_CONFIG", &cfg_path)
+        .args(["--quiet", "selftest"])
+        .output()
+        .expect("spawn binary");
+
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let report: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let steps = report["steps"].as_array().unwrap();
+    assert_eq!(steps.len(), 2);
+    assert_eq!(steps[1]["step"], "copy-fallback");
+
+    let leftovers = |dir: &std::path::Path| {
+        fs::read_dir(dir)
+            .unwrap()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_name().to_string_lossy().contains("selftest"))
+            .count()
+    };
+    assert_eq!(leftovers(&download), 0);
+    assert_eq!(leftovers(&completed), 0);
+}
+
+#[test]
+fn selftest_refuses_dry_run() {
+    let td = tempdir().unwrap();
+    let base = dunce::canonicalize(td.path()).unwrap();
+    let cfg_path = base.join("config.xml");
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    write_cfg(&cfg_path, &download, &completed);
+
+    let out = Command::new(cargo::cargo_bin!("aria_move"))
+        .env("ARIA_MOVE_CONFIG", &cfg_path)
+        .args(["selftest", "--dry-run"])
+        .output()
+        .expect("spawn binary");
+
+    assert!(!out.status.success());
+}
@@ -0,0 +1,72 @@
+#![cfg(unix)]
+
+use assert_cmd::cargo;
+use fs2::FileExt;
+use std::fs::{self, File};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+/// Sidecar lock guarding `path` (same naming as the per-path locks).
+fn sidecar(path: &Path) -> std::path::PathBuf {
+    let name = path.file_name().unwrap().as_encoded_bytes();
+    let hash = name.iter().fold(0xcbf2_9ce4_8422_2325u64, |h, &b| {
+        (h ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    });
+    path.parent()
+        .unwrap()
+        .join(format!(".aria_move.{hash:016x}.lock"))
+}
+
+#[test]
+fn sigquit_shuts_down_gracefully_and_keeps_the_source() {
+    let td = tempdir().unwrap();
+    let base = dunce::canonicalize(td.path()).unwrap();
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    let cfg_path = base.join("config.xml");
+    fs::write(
+        &cfg_path,
+        format!(
+            "<config><download_base>{}</download_base><completed_base>{}</completed_base><lock_strategy>flock</lock_strategy></config>",
+            download.display(),
+            completed.display()
+        ),
+    )
+    .unwrap();
+    let src = download.join("a.bin");
+    fs::write(&src, b"data").unwrap();
+
+    // Another process holds the source lock, so the move is still waiting when the signal arrives.
+    let held = File::create(sidecar(&src)).unwrap();
+    held.lock_exclusive().unwrap();
+
+    let child = Command::new(cargo::cargo_bin!("aria_move"))
+        .env("ARIA_MOVE_CONFIG", &cfg_path)
+        .arg(&src)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn binary");
+    thread::sleep(Duration::from_millis(1500));
+    unsafe {
+        libc::kill(child.id() as libc::pid_t, libc::SIGQUIT);
+    }
+
+    let started = Instant::now();
+    let out = child.wait_with_output().unwrap();
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    // Exited on its own (no core dump from the default SIGQUIT action).
+    assert!(out.status.code().is_some(), "status: {:?}", out.status);
+    assert!(
+        stderr.contains("shutting down gracefully"),
+        "stderr: {stderr}"
+    );
+    assert!(started.elapsed() < Duration::from_secs(20));
+    assert_eq!(fs::read(&src).unwrap(), b"data");
+    assert!(!completed.join("a.bin").exists());
+}
@@ -1,7 +1,7 @@
 use aria_move::cli::Args;
 use aria_move::config::types::{Config, LogLevel};
-use clap::Parser;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[test]
 fn resolved_source_precedence_flag_over_positional() {
@@ -39,6 +39,25 @@ fn effective_log_level_precedence() {
     let args = Args::parse_from(["aria_move", "--log-level", "info"]);
     let lvl = args.effective_log_level().unwrap();
     assert_eq!(lvl, LogLevel::Info);
+
+    let args = Args::parse_from(["aria_move", "--log-level", "warn"]);
+    assert_eq!(args.effective_log_level(), Some(LogLevel::Warn));
+
+    // Unknown levels are rejected instead of silently falling back to the config.
+    assert!(Args::try_parse_from(["aria_move", "--log-level", "loud"]).is_err());
+}
+
+#[test]
+fn quiet_flag_overrides_log_level_and_conflicts_with_debug() {
+    let args = Args::parse_from(["aria_move", "--quiet", "--log-level", "debug"]);
+    assert_eq!(args.effective_log_level(), Some(LogLevel::Quiet));
+
+    let args = Args::parse_from(["aria_move", "-q"]);
+    let mut cfg = Config::default();
+    args.apply_overrides(&mut cfg);
+    assert_eq!(cfg.log_level, LogLevel::Quiet);
+
+    assert!(Args::try_parse_from(["aria_move", "--quiet", "--debug"]).is_err());
 }
 
 #[test]
@@ -62,3 +81,261 @@ fn apply_overrides_sets_flags() {
     assert!(cfg.dry_run);
     assert!(cfg.preserve_metadata);
 }
+
+#[test]
+fn completions_subcommand_generates_scripts() {
+    use aria_move::cli::{Command, write_completions};
+    use clap_complete::Shell;
+
+    let args = Args::parse_from(["aria_move", "completions", "zsh"]);
+    assert!(matches!(
+        args.command,
+        Some(Command::Completions { shell: Shell::Zsh })
+    ));
+    // aria2 hook invocations are unaffected.
+    let args = Args::parse_from(["aria_move", "7f3a", "1", "/tmp/file.iso"]);
+    assert!(args.command.is_none());
+
+    for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+        let mut buf = Vec::new();
+        write_completions(shell, &mut buf);
+        let script = String::from_utf8(buf).unwrap();
+        assert!(script.contains("lock-strategy"), "{shell:?}");
+    }
+}
+
+#[test]
+fn move_subcommand_matches_implicit_hook_form() {
+    use aria_move::cli::Command;
+
+    let hook = Args::parse_from(["aria_move", "7f3a", "1", "/tmp/file.iso", "--dry-run"]);
+    let explicit = Args::parse_from([
+        "aria_move",
+        "move",
+        "7f3a",
+        "1",
+        "/tmp/file.iso",
+        "--dry-run",
+    ]);
+    assert!(hook.command.is_none());
+    assert!(matches!(explicit.command, Some(Command::Move(_))));
+    for args in [&hook, &explicit] {
+        assert_eq!(args.resolved_source(), Some(PathBuf::from("/tmp/file.iso")));
+        assert!(args.move_args().dry_run);
+    }
+
+    // Global options are accepted after the subcommand too.
+    let args = Args::parse_from(["aria_move", "move", "--quiet", "/tmp/x"]);
+    assert_eq!(args.effective_log_level(), Some(LogLevel::Quiet));
+
+    // Move arguments cannot be mixed into another subcommand.
+    assert!(Args::try_parse_from(["aria_move", "--dry-run", "config", "path"]).is_err());
+}
+
+#[test]
+fn plan_subcommand_takes_move_arguments() {
+    use aria_move::cli::Command;
+    use aria_move::fs_ops::OnDuplicate;
+
+    let args = Args::parse_from([
+        "aria_move",
+        "plan",
+        "--merge-on-conflict",
+        "skip",
+        "/tmp/x.iso",
+    ]);
+    assert!(matches!(args.command, Some(Command::Plan(_))));
+    assert_eq!(args.resolved_source(), Some(PathBuf::from("/tmp/x.iso")));
+    assert_eq!(args.move_args().merge_on_conflict, Some(OnDuplicate::Skip));
+}
+
+#[test]
+fn clean_subcommand_takes_dry_run_and_bases() {
+    use aria_move::cli::Command;
+
+    let args = Args::parse_from(["aria_move", "clean", "--dry-run", "--download-base", "/dl"]);
+    assert!(matches!(args.command, Some(Command::Clean(_))));
+    assert!(args.move_args().dry_run);
+    assert_eq!(args.move_args().download_base, Some(PathBuf::from("/dl")));
+    assert_eq!(args.resolved_source(), None);
+}
+
+#[test]
+fn mirror_subcommand_takes_prune_and_interval() {
+    use aria_move::cli::Command;
+
+    let args = Args::parse_from([
+        "aria_move",
+        "mirror",
+        "--prune",
+        "--interval",
+        "90s",
+        "--completed-base",
+        "/done",
+    ]);
+    let Some(Command::Mirror(m)) = &args.command else {
+        panic!("expected mirror");
+    };
+    assert!(m.prune);
+    assert_eq!(m.interval.get(), Duration::from_secs(90));
+    assert_eq!(
+        args.move_args().completed_base,
+        Some(PathBuf::from("/done"))
+    );
+}
+
+#[test]
+fn watch_subcommand_takes_interval_and_defaults_to_a_minute() {
+    use aria_move::cli::Command;
+
+    let args = Args::parse_from(["aria_move", "watch", "--interval", "30s", "--dry-run"]);
+    let Some(Command::Watch(w)) = &args.command else {
+        panic!("expected watch");
+    };
+    assert_eq!(w.interval.get(), Duration::from_secs(30));
+    assert!(args.move_args().dry_run);
+    let default = Args::parse_from(["aria_move", "watch"]);
+    let Some(Command::Watch(w)) = &default.command else {
+        panic!("expected watch");
+    };
+    assert_eq!(w.interval.get(), Duration::from_secs(60));
+}
+
+#[test]
+fn status_undo_history_and_verify_subcommands_parse() {
+    use aria_move::cli::Command;
+
+    let args = Args::parse_from(["aria_move", "status", "--download-base", "/dl"]);
+    assert!(matches!(args.command, Some(Command::Status(_))));
+    assert_eq!(args.move_args().download_base, Some(PathBuf::from("/dl")));
+
+    let args = Args::parse_from(["aria_move", "undo", "--dry-run"]);
+    assert!(matches!(args.command, Some(Command::Undo(_))));
+    assert!(args.move_args().dry_run);
+
+    let args = Args::parse_from(["aria_move", "history", "-n", "5", "--file", "/a.jsonl"]);
+    let Some(Command::History { file, limit }) = &args.command else {
+        panic!("expected history");
+    };
+    assert_eq!(
+        (file.as_deref(), *limit),
+        (Some(std::path::Path::new("/a.jsonl")), 5)
+    );
+    let args = Args::parse_from(["aria_move", "history"]);
+    assert!(matches!(
+        args.command,
+        Some(Command::History {
+            file: None,
+            limit: 20
+        })
+    ));
+
+    let args = Args::parse_from(["aria_move", "verify", "/done/album"]);
+    let Some(Command::Verify { path }) = &args.command else {
+        panic!("expected verify");
+    };
+    assert_eq!(path.as_deref(), Some(std::path::Path::new("/done/album")));
+}
+
+#[test]
+fn bench_dest_subcommand_takes_sample_size() {
+    use aria_move::cli::Command;
+
+    let args = Args::parse_from(["aria_move", "bench-dest", "--size-mib", "8"]);
+    let Some(Command::BenchDest(b)) = &args.command else {
+        panic!("expected bench-dest");
+    };
+    assert_eq!(b.size_mib, 8);
+    let default = Args::parse_from(["aria_move", "bench-dest"]);
+    let Some(Command::BenchDest(b)) = &default.command else {
+        panic!("expected bench-dest");
+    };
+    assert_eq!(b.size_mib, 64);
+}
+
+#[test]
+fn aria2_gid_only_in_hook_form() {
+    let hook = Args::parse_from(["aria_move", "2089b05ecca3d829", "1", "/dl/file.iso"]);
+    assert_eq!(hook.move_args().aria2_gid(), Some("2089b05ecca3d829"));
+
+    let single = Args::parse_from(["aria_move", "/dl/file.iso"]);
+    assert_eq!(single.move_args().aria2_gid(), None);
+}
+
+#[test]
+fn config_subcommand_parses_actions() {
+    use aria_move::cli::{Command, ConfigCommand};
+
+    let args = Args::parse_from(["aria_move", "--config", "/etc/am.xml", "config", "init"]);
+    assert!(matches!(
+        args.command,
+        Some(Command::Config {
+            action: ConfigCommand::Init
+        })
+    ));
+    assert_eq!(args.global.config_path, Some(PathBuf::from("/etc/am.xml")));
+}
+
+#[test]
+fn global_options_before_a_subcommand_are_accepted() {
+    use aria_move::cli::{Command, ConfigCommand};
+
+    let args = Args::try_parse_from(["aria_move", "--config", "/tmp/am.xml", "config", "path"])
+        .expect("--config before a subcommand");
+    assert!(matches!(
+        args.command,
+        Some(Command::Config {
+            action: ConfigCommand::Path
+        })
+    ));
+    assert_eq!(args.global.config_path, Some(PathBuf::from("/tmp/am.xml")));
+
+    let args = Args::try_parse_from([
+        "aria_move",
+        "--quiet",
+        "--config",
+        "/tmp/am.xml",
+        "config",
+        "path",
+    ])
+    .expect("several global options before a subcommand");
+    assert_eq!(args.effective_log_level(), Some(LogLevel::Quiet));
+
+    // A move argument in the same position is still refused.
+    let err = Args::try_parse_from(["aria_move", "--dry-run", "config", "path"]).unwrap_err();
+    assert!(err.to_string().contains("--dry-run"), "{err}");
+}
+
+#[test]
+fn launchd_subcommand_parses_interval() {
+    use aria_move::cli::{Command, LaunchdCommand};
+
+    let args = Args::parse_from(["aria_move", "launchd", "install", "--interval", "60"]);
+    assert!(matches!(
+        args.command,
+        Some(Command::Launchd {
+            action: LaunchdCommand::Install,
+            interval
+        }) if interval.get() == Duration::from_secs(60)
+    ));
+}
+
+#[test]
+fn duration_flags_accept_units_and_plain_seconds() {
+    let args = Args::parse_from([
+        "aria_move",
+        "--recent-window",
+        "5m",
+        "--on-full-max-wait",
+        "2h",
+        "--max-runtime-seconds",
+        "90",
+    ]);
+    let mut cfg = Config::default();
+    args.move_args().apply_overrides(&mut cfg);
+    assert_eq!(cfg.recent_window, Duration::from_secs(300));
+    assert_eq!(cfg.on_full_max_wait, Duration::from_secs(7200));
+    assert_eq!(cfg.max_runtime, Some(Duration::from_secs(90)));
+
+    assert!(Args::try_parse_from(["aria_move", "--recent-window", "soon"]).is_err());
+}
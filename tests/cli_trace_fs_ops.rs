@@ -0,0 +1,59 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+/// Move one file with only the fs_trace target at debug level; return the console output.
+fn run_move(base: &Path, trace: bool) -> String {
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    let cfg_path = base.join("config.xml");
+    fs::write(
+        &cfg_path,
+        format!(
+            "<config><download_base>{}</download_base><completed_base>{}</completed_base><log_level>warn</log_level><log_filter>aria_move::fs_ops::fs_trace=debug</log_filter><trace_fs_ops>{trace}</trace_fs_ops><log_file></log_file></config>",
+            download.display(),
+            completed.display()
+        ),
+    )
+    .unwrap();
+    let src = download.join("a.bin");
+    fs::write(&src, b"data").unwrap();
+
+    let out = Command::new(assert_cmd::cargo::cargo_bin!("aria_move"))
+        .env("ARIA_MOVE_CONFIG", &cfg_path)
+        .arg(&src)
+        .output()
+        .expect("spawn binary");
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&out.stdout),
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(out.status.success(), "{text}");
+    assert!(completed.join("a.bin").exists());
+    text
+}
+
+#[test]
+fn trace_fs_ops_logs_the_rename_with_its_duration() {
+    let td = tempdir().unwrap();
+    let base = dunce::canonicalize(td.path()).unwrap();
+    let text = run_move(&base, true);
+    let line = text
+        .lines()
+        .find(|l| l.contains("fs op") && l.contains("rename"))
+        .unwrap_or_else(|| panic!("no rename event in: {text}"));
+    assert!(line.contains("elapsed_us"), "{line}");
+    assert!(line.contains("a.bin"), "{line}");
+}
+
+#[test]
+fn without_trace_fs_ops_nothing_is_logged() {
+    let td = tempdir().unwrap();
+    let base = dunce::canonicalize(td.path()).unwrap();
+    let text = run_move(&base, false);
+    assert!(!text.contains("fs op"), "{text}");
+}
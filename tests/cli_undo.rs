@@ -0,0 +1,111 @@
+use assert_cmd::cargo;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::tempdir;
+
+fn write_cfg(path: &Path, download: &Path, completed: &Path, audit: &Path) {
+    let xml = format!(
+        r#"<config>
+  <download_base>{}</download_base>
+  <completed_base>{}</completed_base>
+  <log_level>quiet</log_level>
+  <audit_log>{}</audit_log>
+</config>"#,
+        download.display(),
+        completed.display(),
+        audit.display()
+    );
+    fs::write(path, xml).unwrap();
+}
+
+fn run(cfg_path: &Path, args: &[&str]) -> Output {
+    Command::new(cargo::cargo_bin!("aria_move"))
+        .env("ARIA_MOVE_CONFIG", cfg_path)
+        .args(args)
+        .output()
+        .expect("spawn binary")
+}
+
+fn assert_ok(out: &Output) {
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}
+
+#[test]
+fn undo_walks_back_through_the_audit_log() {
+    let td = tempdir().unwrap();
+    let base = fs::canonicalize(td.path()).unwrap();
+    let cfg_path = base.join("config.xml");
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    let audit = base.join("state").join("audit.jsonl");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    write_cfg(&cfg_path, &download, &completed, &audit);
+
+    for name in ["a.mkv", "b.mkv"] {
+        let src = download.join(name);
+        fs::write(&src, name).unwrap();
+        assert_ok(&run(&cfg_path, &[&src.to_string_lossy()]));
+    }
+
+    let out = run(&cfg_path, &["history", "-n", "1"]);
+    assert_ok(&out);
+    let last: serde_json::Value = serde_json::from_slice(&out.stdout).expect("one JSON record");
+    assert_eq!(last["outcome"], "moved");
+    assert_eq!(last["seq"], 2);
+
+    let out = run(&cfg_path, &["status"]);
+    assert_ok(&out);
+    let status: serde_json::Value = serde_json::from_slice(&out.stdout).expect("JSON status");
+    assert_eq!(status["last_move"]["seq"], 2);
+    assert_eq!(status["candidates"], serde_json::json!([]));
+
+    // A dry run only reports.
+    let out = run(&cfg_path, &["undo", "--dry-run"]);
+    assert_ok(&out);
+    assert!(completed.join("b.mkv").exists());
+
+    let out = run(&cfg_path, &["undo"]);
+    assert_ok(&out);
+    let report: serde_json::Value = serde_json::from_slice(&out.stdout).expect("JSON report");
+    assert_eq!(report["seq"], 2);
+    assert_eq!(fs::read_to_string(download.join("b.mkv")).unwrap(), "b.mkv");
+    assert!(!completed.join("b.mkv").exists());
+
+    assert_ok(&run(&cfg_path, &["undo"]));
+    assert!(download.join("a.mkv").exists());
+    let out = run(&cfg_path, &["undo"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("nothing to undo"));
+
+    // The undo records keep the chain valid.
+    assert_ok(&run(&cfg_path, &["audit", "verify"]));
+    let out = run(&cfg_path, &["history"]);
+    assert_eq!(String::from_utf8_lossy(&out.stdout).lines().count(), 4);
+}
+
+#[test]
+fn watch_with_zero_interval_moves_leftovers_once() {
+    let td = tempdir().unwrap();
+    let base = fs::canonicalize(td.path()).unwrap();
+    let cfg_path = base.join("config.xml");
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    let audit = base.join("audit.jsonl");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    write_cfg(&cfg_path, &download, &completed, &audit);
+    let src = download.join("left.iso");
+    fs::write(&src, "iso").unwrap();
+    let old = filetime::FileTime::from_unix_time(1_600_000_000, 0);
+    filetime::set_file_mtime(&src, old).unwrap();
+
+    assert_ok(&run(&cfg_path, &["watch", "--interval", "0"]));
+    assert!(completed.join("left.iso").exists());
+    assert!(!src.exists());
+}
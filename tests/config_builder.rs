@@ -0,0 +1,39 @@
+use aria_move::{Config, LogLevel};
+use std::fs;
+use std::time::Duration;
+use tempfile::tempdir;
+
+#[test]
+fn build_validates_and_canonicalizes_bases() {
+    let td = tempdir().unwrap();
+    let root = dunce::canonicalize(td.path()).unwrap();
+    let cfg = Config::builder()
+        .download_base(root.join("incoming"))
+        .completed_base(root.join("x").join("..").join("completed"))
+        .log_level(LogLevel::Debug)
+        .recent_window(Duration::ZERO)
+        .dry_run(true)
+        .build()
+        .unwrap();
+
+    // Missing bases are created; paths come back canonical.
+    assert!(root.join("incoming").is_dir());
+    assert_eq!(cfg.download_base, root.join("incoming"));
+    assert_eq!(cfg.completed_base, root.join("completed"));
+    assert_eq!(cfg.log_level, LogLevel::Debug);
+    assert_eq!(cfg.recent_window, Duration::ZERO);
+    assert!(cfg.dry_run);
+}
+
+#[test]
+fn build_rejects_nested_bases() {
+    let td = tempdir().unwrap();
+    let download = td.path().join("incoming");
+    fs::create_dir_all(download.join("done")).unwrap();
+    let err = Config::builder()
+        .download_base(&download)
+        .completed_base(download.join("done"))
+        .build()
+        .unwrap_err();
+    assert!(format!("{err}").contains("must not be inside download_base"));
+}
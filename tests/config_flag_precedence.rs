@@ -2,21 +2,25 @@
 //! Creates two temp config files with different completed_base values and ensures
 //! the CLI picks the path passed via --config even when ARIA_MOVE_CONFIG points elsewhere.
 
-use assert_cmd::cargo;
 use assert_cmd::assert::OutputAssertExt; // bring .assert() into scope
+use assert_cmd::cargo;
 use std::fs;
 use tempfile::tempdir;
 
 // Minimal XML template with differing completed_base so we can detect which was used.
 fn write_cfg(path: &std::path::Path, download: &std::path::Path, completed: &std::path::Path) {
-    let xml = format!(r#"<config>
+    let xml = format!(
+        r#"<config>
   <download_base>{}</download_base>
   <completed_base>{}</completed_base>
   <log_level>quiet</log_level>
   <log_file></log_file>
   <preserve_metadata>false</preserve_metadata>
   <preserve_permissions>false</preserve_permissions>
-</config>"#, download.display(), completed.display());
+</config>"#,
+        download.display(),
+        completed.display()
+    );
     fs::write(path, xml).unwrap();
 }
 
@@ -45,7 +49,7 @@ fn config_flag_overrides_env() {
     fs::write(&source, b"data").unwrap();
 
     let bin = cargo::cargo_bin!("aria_move");
-    let mut cmd = std::process::Command::new(&bin);
+    let mut cmd = std::process::Command::new(bin);
     // Set ARIA_MOVE_CONFIG to env_cfg, but pass --config flag pointing to flag_cfg.
     cmd.env("ARIA_MOVE_CONFIG", &env_cfg)
         .arg("--config")
@@ -56,6 +60,46 @@ fn config_flag_overrides_env() {
     // Expect output to mention /tmp/completedB (from flag_cfg) not completedA.
     let output = cmd.assert().success().get_output().stdout.clone();
     let text = String::from_utf8_lossy(&output);
-    assert!(text.contains(&com_b.display().to_string()), "stdout should reference flag config completed_base");
-    assert!(!text.contains(&com_a.display().to_string()), "stdout should not reference env config completed_base");
+    assert!(
+        text.contains(&com_b.display().to_string()),
+        "stdout should reference flag config completed_base"
+    );
+    assert!(
+        !text.contains(&com_a.display().to_string()),
+        "stdout should not reference env config completed_base"
+    );
+}
+
+#[test]
+fn log_level_flag_shows_where_the_config_came_from() {
+    let td = tempdir().unwrap();
+    let base = fs::canonicalize(td.path()).unwrap();
+    let cfg = base.join("config.xml");
+    let (incoming, completed) = (base.join("incoming"), base.join("completed"));
+    fs::create_dir_all(&incoming).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    write_cfg(&cfg, &incoming, &completed);
+    let source = incoming.join("dummy.bin");
+    fs::write(&source, b"data").unwrap();
+
+    // The config is read before logging is set up from it.
+    let run = |flags: &[&str]| {
+        let out = std::process::Command::new(cargo::cargo_bin!("aria_move"))
+            .env("ARIA_MOVE_CONFIG", &cfg)
+            .args(flags)
+            .arg("--dry-run")
+            .arg(&source)
+            .output()
+            .expect("spawn binary");
+        assert!(out.status.success());
+        format!(
+            "{}{}",
+            String::from_utf8_lossy(&out.stdout),
+            String::from_utf8_lossy(&out.stderr)
+        )
+    };
+    let text = run(&["--log-level", "debug"]);
+    assert!(text.contains("ARIA_MOVE_CONFIG is set"), "{text}");
+    let text = run(&[]);
+    assert!(!text.contains("ARIA_MOVE_CONFIG is set"), "{text}");
 }
@@ -0,0 +1,114 @@
+//! Integration test: with --no-auto-config / ARIA_MOVE_NO_TEMPLATE=1 / --machine (and for
+//! aria2 hook calls without a TTY) a missing config is a clean error and no template is written.
+
+use assert_cmd::cargo;
+use std::fs;
+use std::process::Command;
+use tempfile::tempdir;
+
+#[test]
+fn no_auto_config_flag_reports_missing_config() {
+    let td = tempdir().unwrap();
+    let base = fs::canonicalize(td.path()).unwrap();
+    let cfg_path = base.join("etc").join("config.xml");
+
+    let out = Command::new(cargo::cargo_bin!("aria_move"))
+        .arg("--config")
+        .arg(&cfg_path)
+        .arg("--no-auto-config")
+        .arg("--dry-run")
+        .arg("file.bin")
+        .output()
+        .expect("spawn binary");
+
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("Config file not found"),
+        "unexpected stderr: {stderr}"
+    );
+    assert!(!cfg_path.exists());
+    assert!(!base.join("etc").exists());
+}
+
+#[test]
+fn no_template_env_reports_missing_config() {
+    let td = tempdir().unwrap();
+    let base = fs::canonicalize(td.path()).unwrap();
+    let cfg_path = base.join("config.xml");
+
+    let out = Command::new(cargo::cargo_bin!("aria_move"))
+        .env("ARIA_MOVE_CONFIG", &cfg_path)
+        .env("ARIA_MOVE_NO_TEMPLATE", "1")
+        .arg("file.bin")
+        .output()
+        .expect("spawn binary");
+
+    assert!(!out.status.success());
+    assert!(!cfg_path.exists());
+}
+
+#[test]
+fn aria2_hook_call_without_tty_reports_missing_config_in_one_line() {
+    let td = tempdir().unwrap();
+    let base = fs::canonicalize(td.path()).unwrap();
+    let cfg_path = base.join("config.xml");
+
+    // stdout is a pipe here, as it is when aria2 runs the hook.
+    let out = Command::new(cargo::cargo_bin!("aria_move"))
+        .env("ARIA_MOVE_CONFIG", &cfg_path)
+        .env_remove("ARIA_MOVE_NO_TEMPLATE")
+        .args(["2089b05ecca3d829", "1"])
+        .arg(base.join("file.bin"))
+        .output()
+        .expect("spawn binary");
+
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("Config file not found"),
+        "unexpected stderr: {stderr}"
+    );
+    assert_eq!(stderr.trim_end().lines().count(), 1, "stderr: {stderr}");
+    assert!(out.stdout.is_empty());
+    assert!(!cfg_path.exists());
+}
+
+#[test]
+fn machine_flag_reports_missing_config() {
+    let td = tempdir().unwrap();
+    let base = fs::canonicalize(td.path()).unwrap();
+    let cfg_path = base.join("config.xml");
+
+    let out = Command::new(cargo::cargo_bin!("aria_move"))
+        .env("ARIA_MOVE_CONFIG", &cfg_path)
+        .env_remove("ARIA_MOVE_NO_TEMPLATE")
+        .arg("--machine")
+        .arg("file.bin")
+        .output()
+        .expect("spawn binary");
+
+    assert!(!out.status.success());
+    assert!(!cfg_path.exists());
+}
+
+#[test]
+fn config_init_still_writes_the_template() {
+    let td = tempdir().unwrap();
+    let base = fs::canonicalize(td.path()).unwrap();
+    let cfg_path = base.join("config.xml");
+
+    let out = Command::new(cargo::cargo_bin!("aria_move"))
+        .env("ARIA_MOVE_CONFIG", &cfg_path)
+        .env("ARIA_MOVE_NO_TEMPLATE", "1")
+        .args(["config", "init"])
+        .output()
+        .expect("spawn binary");
+
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(cfg_path.exists());
+}
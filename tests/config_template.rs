@@ -0,0 +1,20 @@
+use aria_move::config::xml::create_template_config;
+use aria_move::config::{TEMPLATE_COMPLETED_BASE, TEMPLATE_DOWNLOAD_BASE};
+use aria_move::load_config_from_xml_path;
+use std::path::PathBuf;
+use tempfile::tempdir;
+
+#[test]
+fn template_parses_and_uses_placeholder_bases() {
+    let td = tempdir().unwrap();
+    let cfg_path = td.path().join("aria_move").join("config.xml");
+    create_template_config(&cfg_path).unwrap();
+
+    // Every element in the template must be known to the loader (deny_unknown_fields).
+    let cfg = load_config_from_xml_path(&cfg_path).unwrap();
+    assert_eq!(cfg.download_base, PathBuf::from(TEMPLATE_DOWNLOAD_BASE));
+    assert_eq!(cfg.completed_base, PathBuf::from(TEMPLATE_COMPLETED_BASE));
+
+    // Never overwrite an existing config.
+    assert!(create_template_config(&cfg_path).is_err());
+}
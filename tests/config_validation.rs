@@ -1,4 +1,4 @@
-use aria_move::config::{Config, validate_and_normalize};
+use aria_move::config::{Config, validate_and_normalize, validate_read_only};
 use assert_fs::TempDir;
 use std::fs;
 
@@ -28,6 +28,26 @@ fn download_and_completed_created_when_missing() {
     assert!(completed.exists(), "completed_base should be created");
 }
 
+#[test]
+fn read_only_validation_creates_nothing() {
+    let td = TempDir::new().unwrap();
+    let root = dunce::canonicalize(td.path()).unwrap();
+    let download = root.join("incoming");
+    fs::create_dir_all(&download).unwrap();
+    let completed = root.join("completed_missing").join("sub");
+
+    let mut cfg = Config::new(&download, &completed);
+    validate_read_only(&mut cfg).expect("read-only validation accepts a missing completed_base");
+    assert!(!root.join("completed_missing").exists());
+    assert_eq!(cfg.completed_base, completed);
+
+    // The disjointness checks still apply.
+    let mut nested = Config::new(&download, download.join("done"));
+    let err = validate_read_only(&mut nested).unwrap_err();
+    assert!(format!("{err}").contains("must not be inside download_base"));
+    assert!(!download.join("done").exists());
+}
+
 #[test]
 fn disallow_equal_paths() {
     let td = TempDir::new().unwrap();
@@ -88,3 +108,33 @@ fn reject_symlink_ancestor() {
         "expected rejection when a symlink is in an ancestor path"
     );
 }
+
+#[cfg(unix)]
+#[test]
+fn created_dir_mode_applies_to_created_directories_only() {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = |p: &std::path::Path| fs::metadata(p).unwrap().permissions().mode() & 0o7777;
+
+    let td = TempDir::new().unwrap();
+    let root = dunce::canonicalize(td.path()).unwrap();
+    let download = root.join("incoming");
+    fs::create_dir_all(&download).unwrap();
+    fs::set_permissions(&download, fs::Permissions::from_mode(0o750)).unwrap();
+    let completed = root.join("media").join("completed");
+
+    let mut cfg = Config::new(&download, &completed);
+    cfg.created_dir_mode = 0o755;
+    validate_and_normalize(&mut cfg).unwrap();
+    assert_eq!(mode(&completed), 0o755);
+    assert_eq!(mode(&root.join("media")), 0o755);
+    assert_eq!(
+        mode(&download),
+        0o750,
+        "existing directories are left alone"
+    );
+
+    // Default: private to the user.
+    let mut cfg = Config::new(&download, root.join("private"));
+    validate_and_normalize(&mut cfg).unwrap();
+    assert_eq!(mode(&root.join("private")), 0o700);
+}
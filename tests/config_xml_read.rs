@@ -13,6 +13,7 @@ fn reads_config_xml_and_applies_values() {
     let download_base = td.path().join("downloads");
     let completed_base = td.path().join("completed");
     let log_file = td.path().join("aria_move.log");
+    let heartbeat = td.path().join("heartbeat");
 
     let xml = format!(
         r#"
@@ -22,11 +23,13 @@ fn reads_config_xml_and_applies_values() {
   <log_level>normal</log_level>
   <log_file>{}</log_file>
   <preserve_metadata>true</preserve_metadata>
+  <heartbeat_file>{}</heartbeat_file>
 </config>
 "#,
         download_base.display(),
         completed_base.display(),
-        log_file.display()
+        log_file.display(),
+        heartbeat.display()
     );
     fs::write(&cfg_path, xml).expect("write config.xml");
 
@@ -45,5 +48,31 @@ fn reads_config_xml_and_applies_values() {
     );
     assert_eq!(cfg.log_level, LogLevel::Normal, "log_level mismatch");
     assert!(cfg.preserve_metadata, "preserve_metadata should be true");
+    assert_eq!(cfg.heartbeat_file.as_deref(), Some(heartbeat.as_path()));
     // auto-pick window removed; no assertion for recency.
 }
+
+#[test]
+fn reads_log_filter_and_rust_log_switch() {
+    let td = tempdir().expect("create tempdir");
+    let cfg_path = td.path().join("config.xml");
+    fs::write(
+        &cfg_path,
+        r#"<config>
+  <download_base>/in</download_base>
+  <completed_base>/out</completed_base>
+  <log_level>warn</log_level>
+  <log_filter> aria_move::fs_ops=debug,aria_move::config=warn </log_filter>
+  <honor_rust_log>true</honor_rust_log>
+</config>"#,
+    )
+    .expect("write config.xml");
+
+    let cfg = load_config_from_xml_path(&cfg_path).expect("load_config_from_xml_path");
+    assert_eq!(cfg.log_level, LogLevel::Warn);
+    assert_eq!(
+        cfg.log_filter.as_deref(),
+        Some("aria_move::fs_ops=debug,aria_move::config=warn")
+    );
+    assert!(cfg.honor_rust_log);
+}
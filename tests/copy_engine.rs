@@ -0,0 +1,120 @@
+use aria_move::api::{CopyEngine, EngineCapabilities, EngineRegistry, Error};
+use aria_move::{Config, fs_ops, load_config_from_xml_path};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tempfile::tempdir;
+
+/// Writes an upper-cased copy, so the test can tell it ran.
+struct Shouting {
+    calls: AtomicUsize,
+}
+
+impl CopyEngine for Shouting {
+    fn name(&self) -> &str {
+        "shouting"
+    }
+
+    fn capabilities(&self) -> EngineCapabilities {
+        EngineCapabilities::default()
+    }
+
+    fn copy_file(&self, _config: &Config, src: &Path, dest: &Path) -> Result<(), Error> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        shout(src, dest).map_err(|e| Error::from(anyhow::Error::from(e)))
+    }
+}
+
+fn shout(src: &Path, dest: &Path) -> std::io::Result<()> {
+    let tmp = dest.with_extension("shouting.tmp");
+    fs::write(&tmp, fs::read(src)?.to_ascii_uppercase())?;
+    fs::rename(&tmp, dest)
+}
+
+fn copy_only(download: &Path, completed: &Path, engine: &str) -> Config {
+    // copy_only forces the copy path even on one filesystem.
+    Config {
+        download_base: download.to_path_buf(),
+        completed_base: completed.to_path_buf(),
+        copy_only: true,
+        copy_engine: Some(engine.to_string()),
+        ..Config::default()
+    }
+}
+
+#[test]
+fn registered_engine_copies_files_and_directories() {
+    let engine = Arc::new(Shouting {
+        calls: AtomicUsize::new(0),
+    });
+    EngineRegistry::global().register(engine.clone());
+    assert!(
+        EngineRegistry::global()
+            .names()
+            .contains(&"shouting".to_string())
+    );
+
+    let download = tempdir().unwrap();
+    let completed = tempdir().unwrap();
+    let cfg = copy_only(download.path(), completed.path(), "shouting");
+
+    let src = download.path().join("notes.txt");
+    fs::write(&src, b"quiet").unwrap();
+    let dest = fs_ops::move_entry(&cfg, &src).unwrap();
+    assert_eq!(fs::read(&dest).unwrap(), b"QUIET");
+
+    let dir = download.path().join("album");
+    fs::create_dir_all(dir.join("cd1")).unwrap();
+    fs::write(dir.join("cd1/track.txt"), b"la").unwrap();
+    let dest = fs_ops::move_entry(&cfg, &dir).unwrap();
+    assert_eq!(fs::read(dest.join("cd1/track.txt")).unwrap(), b"LA");
+    assert_eq!(engine.calls.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn builtin_method_engines_copy_byte_for_byte() {
+    for name in ["builtin", "reflink", "kernel", "streaming"] {
+        let download = tempdir().unwrap();
+        let completed = tempdir().unwrap();
+        let cfg = copy_only(download.path(), completed.path(), name);
+        let src = download.path().join("data.bin");
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        fs::write(&src, &data).unwrap();
+
+        let dest = fs_ops::move_entry(&cfg, &src).unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), data, "engine {name}");
+        assert!(src.exists());
+    }
+}
+
+#[test]
+fn unknown_engine_is_an_error() {
+    let download = tempdir().unwrap();
+    let completed = tempdir().unwrap();
+    let cfg = copy_only(download.path(), completed.path(), "no-such-engine");
+    let src = download.path().join("a.txt");
+    fs::write(&src, b"a").unwrap();
+
+    let err = fs_ops::move_entry(&cfg, &src).unwrap_err().to_string();
+    assert!(err.contains("no-such-engine"), "{err}");
+    assert!(!completed.path().join("a.txt").exists());
+}
+
+#[test]
+fn copy_engine_is_read_from_xml() {
+    assert_eq!(Config::default().copy_engine, None);
+    let td = tempdir().unwrap();
+    let cfg_path = td.path().join("config.xml");
+    fs::write(
+        &cfg_path,
+        r#"<config>
+  <download_base>/tmp/in</download_base>
+  <completed_base>/tmp/out</completed_base>
+  <copy_engine> streaming </copy_engine>
+</config>"#,
+    )
+    .unwrap();
+    let cfg = load_config_from_xml_path(&cfg_path).unwrap();
+    assert_eq!(cfg.copy_engine.as_deref(), Some("streaming"));
+}
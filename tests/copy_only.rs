@@ -0,0 +1,73 @@
+use aria_move::api::{ConfigBuilder, Mover};
+use aria_move::fs_ops::{self, TransferStrategy};
+use aria_move::{Config, CopyBackend};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn mk_cfg(download: &Path, completed: &Path) -> Config {
+    Config {
+        download_base: download.to_path_buf(),
+        completed_base: completed.to_path_buf(),
+        copy_only: true,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn copy_only_keeps_the_source_file() {
+    let download = tempdir().unwrap();
+    let completed = tempdir().unwrap();
+    let cfg = mk_cfg(download.path(), completed.path());
+    let src = download.path().join("seed.iso");
+    fs::write(&src, b"payload").unwrap();
+
+    let plan = fs_ops::plan_move(&cfg, &src).unwrap();
+    assert_eq!(
+        plan.strategy,
+        TransferStrategy::CopyOnly(CopyBackend::Builtin)
+    );
+    assert!(plan.to_string().contains("source retained"));
+
+    let dest = fs_ops::move_entry(&cfg, &src).unwrap();
+    assert_eq!(fs::read(&dest).unwrap(), b"payload");
+    assert_eq!(fs::read(&src).unwrap(), b"payload");
+}
+
+#[test]
+fn copy_only_keeps_the_source_tree() {
+    let download = tempdir().unwrap();
+    let completed = tempdir().unwrap();
+    let cfg = mk_cfg(download.path(), completed.path());
+    let src = download.path().join("album");
+    fs::create_dir_all(src.join("cd1")).unwrap();
+    fs::write(src.join("cd1/01.flac"), b"one").unwrap();
+    fs::write(src.join("cover.jpg"), b"img").unwrap();
+
+    let dest = fs_ops::move_entry(&cfg, &src).unwrap();
+    assert_eq!(fs::read(dest.join("cd1/01.flac")).unwrap(), b"one");
+    assert_eq!(fs::read(dest.join("cover.jpg")).unwrap(), b"img");
+    assert!(!dest.join(".aria_move.copying").exists());
+    assert_eq!(fs::read(src.join("cd1/01.flac")).unwrap(), b"one");
+    assert_eq!(fs::read(src.join("cover.jpg")).unwrap(), b"img");
+}
+
+#[test]
+fn report_marks_the_source_as_retained() {
+    let td = tempdir().unwrap();
+    let root = dunce::canonicalize(td.path()).unwrap();
+    let mover = Mover::new(
+        ConfigBuilder::default()
+            .download_base(root.join("incoming"))
+            .completed_base(root.join("completed"))
+            .copy_only(true),
+    )
+    .unwrap();
+    let src = mover.config().download_base.join("movie.mkv");
+    fs::write(&src, b"data").unwrap();
+
+    let report = mover.move_path(&src).unwrap();
+    assert!(report.source_retained);
+    assert!(src.exists());
+    assert_eq!(report.timings.bytes_copied, 4);
+}
@@ -0,0 +1,92 @@
+use aria_move::{Config, CopyOrder, fs_ops, load_config_from_xml_path};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use tempfile::tempdir;
+
+#[test]
+fn parse_copy_order_names() {
+    assert_eq!(CopyOrder::parse("walk"), Some(CopyOrder::Walk));
+    assert_eq!(
+        CopyOrder::parse("Largest-First"),
+        Some(CopyOrder::LargestFirst)
+    );
+    assert_eq!(
+        CopyOrder::parse("largest_first"),
+        Some(CopyOrder::LargestFirst)
+    );
+    assert_eq!(
+        CopyOrder::parse("smallest-first"),
+        Some(CopyOrder::SmallestFirst)
+    );
+    assert_eq!(CopyOrder::parse("random"), None);
+    assert_eq!(CopyOrder::default(), CopyOrder::Walk);
+}
+
+#[test]
+fn display_roundtrips_with_fromstr() {
+    for order in [
+        CopyOrder::Walk,
+        CopyOrder::LargestFirst,
+        CopyOrder::SmallestFirst,
+    ] {
+        let s = order.to_string();
+        assert_eq!(
+            CopyOrder::from_str(&s).unwrap(),
+            order,
+            "roundtrip failed for {s}"
+        );
+    }
+}
+
+#[test]
+fn xml_copy_order_is_applied() {
+    let td = tempdir().unwrap();
+    let cfg_path = td.path().join("config.xml");
+    fs::write(
+        &cfg_path,
+        r#"<config>
+  <download_base>/tmp/in</download_base>
+  <completed_base>/tmp/out</completed_base>
+  <copy_order>smallest-first</copy_order>
+</config>"#,
+    )
+    .unwrap();
+    let cfg = load_config_from_xml_path(&cfg_path).unwrap();
+    assert_eq!(cfg.copy_order, CopyOrder::SmallestFirst);
+}
+
+fn mk_cfg(download: &Path, completed: &Path, order: CopyOrder) -> Config {
+    Config {
+        download_base: download.to_path_buf(),
+        completed_base: completed.to_path_buf(),
+        copy_order: order,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn move_dir_moves_everything_for_each_order() -> Result<(), Box<dyn std::error::Error>> {
+    for order in [
+        CopyOrder::Walk,
+        CopyOrder::LargestFirst,
+        CopyOrder::SmallestFirst,
+    ] {
+        let download = tempdir()?;
+        let completed = tempdir()?;
+        let cfg = mk_cfg(download.path(), completed.path(), order);
+
+        let src_dir = download.path().join("season");
+        fs::create_dir_all(src_dir.join("extras"))?;
+        fs::write(src_dir.join("e01.mkv"), vec![1u8; 4096])?;
+        fs::write(src_dir.join("e02.mkv"), vec![2u8; 64])?;
+        fs::write(src_dir.join("extras/notes.txt"), b"n")?;
+
+        let dest = fs_ops::move_dir(&cfg, &src_dir)?;
+        assert!(!src_dir.exists(), "source should be gone ({order})");
+        assert_eq!(fs::metadata(dest.join("e01.mkv"))?.len(), 4096);
+        assert_eq!(fs::metadata(dest.join("e02.mkv"))?.len(), 64);
+        assert_eq!(fs::read(dest.join("extras/notes.txt"))?, b"n");
+    }
+    Ok(())
+}
@@ -0,0 +1,66 @@
+#![cfg(unix)]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use tempfile::tempdir;
+
+use aria_move::load_config_from_xml_path;
+
+fn mode(p: &std::path::Path) -> u32 {
+    fs::metadata(p).unwrap().permissions().mode() & 0o7777
+}
+
+#[test]
+fn xml_dest_dir_mode_and_preserve_acls_are_parsed() {
+    let td = tempdir().unwrap();
+    let cfg_path = td.path().join("config.xml");
+    let xml = |mode: &str| {
+        format!(
+            "<config><download_base>{}</download_base><completed_base>{}</completed_base>\
+             <dest_dir_mode>{mode}</dest_dir_mode><preserve_acls>true</preserve_acls></config>",
+            td.path().join("in").display(),
+            td.path().join("out").display()
+        )
+    };
+    fs::write(&cfg_path, xml("2775")).unwrap();
+    let cfg = load_config_from_xml_path(&cfg_path).unwrap();
+    assert_eq!(cfg.dest_dir_mode, Some(0o2775));
+    assert!(cfg.preserve_acls);
+
+    fs::write(&cfg_path, xml("rwx")).unwrap();
+    let err = load_config_from_xml_path(&cfg_path)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("dest_dir_mode"), "{err}");
+}
+
+#[test]
+fn copied_directory_tree_uses_dest_dir_mode() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let src = download.path().join("season");
+    fs::create_dir_all(src.join("extras"))?;
+    fs::write(src.join("extras").join("a.txt"), b"a")?;
+    fs::set_permissions(src.join("extras"), fs::Permissions::from_mode(0o700))?;
+
+    let cfg = aria_move::Config {
+        download_base: download.path().to_path_buf(),
+        completed_base: completed.path().to_path_buf(),
+        copy_only: true,
+        dest_dir_mode: Some(0o770),
+        ..aria_move::Config::default()
+    };
+    let dest = aria_move::fs_ops::move_dir(&cfg, &src)?;
+    assert_eq!(mode(&dest), 0o770);
+    assert_eq!(mode(&dest.join("extras")), 0o770);
+
+    // Without dest_dir_mode, preserving permissions mirrors the source directories.
+    let cfg = aria_move::Config {
+        dest_dir_mode: None,
+        preserve_permissions: true,
+        ..cfg
+    };
+    let dest = aria_move::fs_ops::move_dir(&cfg, &src)?;
+    assert_eq!(mode(&dest.join("extras")), 0o700);
+    Ok(())
+}
@@ -0,0 +1,95 @@
+#![cfg(unix)]
+
+use aria_move::{AriaMoveError, Config, DestSymlinkPolicy, fs_ops};
+use std::fs;
+use std::os::unix::fs as unix_fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn mk_cfg(download: &Path, completed: &Path, policy: DestSymlinkPolicy) -> Config {
+    Config {
+        download_base: download.to_path_buf(),
+        completed_base: completed.to_path_buf(),
+        dest_symlink_policy: policy,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn parse_dest_symlink_policy_names() {
+    assert_eq!(
+        DestSymlinkPolicy::parse("Error"),
+        Some(DestSymlinkPolicy::Error)
+    );
+    assert_eq!(
+        DestSymlinkPolicy::parse("replace"),
+        Some(DestSymlinkPolicy::Replace)
+    );
+    assert_eq!(DestSymlinkPolicy::parse("follow"), None);
+    assert_eq!(DestSymlinkPolicy::default(), DestSymlinkPolicy::Error);
+}
+
+#[test]
+fn error_policy_refuses_symlinked_destination() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let elsewhere = tempdir()?;
+    let cfg = mk_cfg(download.path(), completed.path(), DestSymlinkPolicy::Error);
+
+    let src = download.path().join("show");
+    fs::create_dir(&src)?;
+    fs::write(src.join("ep1.mkv"), b"data")?;
+    let link = completed.path().join("show");
+    unix_fs::symlink(elsewhere.path(), &link)?;
+
+    let err = fs_ops::move_entry(&cfg, &src).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<AriaMoveError>(),
+        Some(AriaMoveError::DestinationIsSymlink(p)) if *p == link
+    ));
+    assert!(src.join("ep1.mkv").exists());
+    assert!(fs::read_dir(elsewhere.path())?.next().is_none());
+    Ok(())
+}
+
+#[test]
+fn replace_policy_removes_only_the_link() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let elsewhere = tempdir()?;
+    let cfg = mk_cfg(
+        download.path(),
+        completed.path(),
+        DestSymlinkPolicy::Replace,
+    );
+
+    let target = elsewhere.path().join("a.bin");
+    fs::write(&target, b"keep")?;
+    let src = download.path().join("a.bin");
+    fs::write(&src, b"new")?;
+    unix_fs::symlink(&target, completed.path().join("a.bin"))?;
+
+    let dest = fs_ops::move_entry(&cfg, &src)?;
+    assert_eq!(dest, completed.path().join("a.bin"));
+    assert!(!fs::symlink_metadata(&dest)?.file_type().is_symlink());
+    assert_eq!(fs::read(&dest)?, b"new");
+    assert_eq!(fs::read(&target)?, b"keep");
+    Ok(())
+}
+
+#[test]
+fn dangling_link_is_treated_as_taken() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let cfg = mk_cfg(download.path(), completed.path(), DestSymlinkPolicy::Error);
+
+    let src = download.path().join("b.bin");
+    fs::write(&src, b"data")?;
+    let link = completed.path().join("b.bin");
+    unix_fs::symlink(completed.path().join("missing"), &link)?;
+
+    assert!(fs_ops::move_entry(&cfg, &src).is_err());
+    assert!(src.exists());
+    assert!(fs::symlink_metadata(&link)?.file_type().is_symlink());
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use aria_move::{Config, fs_ops};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn failed_copy_writes_a_diagnostic_bundle() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let state = tempdir()?;
+    // completed_base below a regular file: the move cannot create its destination.
+    let blocker = state.path().join("not-a-dir");
+    fs::write(&blocker, b"x")?;
+    let diag = state.path().join("diag");
+    let cfg = Config {
+        download_base: download.path().to_path_buf(),
+        completed_base: blocker.join("completed"),
+        diagnostics_dir: Some(diag.clone()),
+        audit_log: Some(state.path().join("audit.jsonl")),
+        ..Config::default()
+    };
+    let src = download.path().join("a.bin");
+    fs::write(&src, b"data")?;
+
+    assert!(fs_ops::move_entry(&cfg, &src).is_err());
+    assert!(src.exists());
+
+    let bundles: Vec<_> = fs::read_dir(&diag)?.collect::<Result<_, _>>()?;
+    assert_eq!(bundles.len(), 1);
+    let json: serde_json::Value = serde_json::from_slice(&fs::read(bundles[0].path())?)?;
+    assert_eq!(json["src"], src.to_string_lossy().as_ref());
+    assert!(json["source"]["free_bytes"].is_u64());
+    assert!(json["destination"]["mount_flags"].is_array());
+    assert!(json["locks"].as_array().is_some_and(|l| l.len() == 4));
+    // The failure itself was audited before the bundle was taken.
+    assert_eq!(json["recent_audit"][0]["outcome"], "failed");
+    Ok(())
+}
+
+#[test]
+fn refusals_get_no_bundle() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let elsewhere = tempdir()?;
+    let diag = elsewhere.path().join("diag");
+    let cfg = Config {
+        download_base: download.path().to_path_buf(),
+        completed_base: completed.path().to_path_buf(),
+        diagnostics_dir: Some(diag.clone()),
+        ..Config::default()
+    };
+    let src = elsewhere.path().join("a.bin");
+    fs::write(&src, b"data")?;
+
+    assert!(fs_ops::move_entry(&cfg, &src).is_err());
+    assert!(!diag.exists());
+    Ok(())
+}
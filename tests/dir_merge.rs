@@ -0,0 +1,137 @@
+use aria_move::fs_ops::OnDuplicate;
+use aria_move::{Config, fs_ops, load_config_from_xml_path};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn mk_cfg(download: &Path, completed: &Path, merge_on_conflict: OnDuplicate) -> Config {
+    Config {
+        download_base: download.to_path_buf(),
+        completed_base: completed.to_path_buf(),
+        dir_on_duplicate: OnDuplicate::Merge,
+        merge_on_conflict,
+        ..Config::default()
+    }
+}
+
+/// download/Show.S01 with e01 (new bytes), e02 (new file), sub/nfo.txt; completed/Show.S01 has e01.
+fn setup(download: &Path, completed: &Path) -> std::io::Result<()> {
+    let src = download.join("Show.S01");
+    fs::create_dir_all(src.join("sub"))?;
+    fs::write(src.join("e01.mkv"), b"new-e01")?;
+    fs::write(src.join("e02.mkv"), b"e02")?;
+    fs::write(src.join("sub/nfo.txt"), b"nfo")?;
+
+    let existing = completed.join("Show.S01");
+    fs::create_dir_all(&existing)?;
+    fs::write(existing.join("e01.mkv"), b"old-e01")?;
+    Ok(())
+}
+
+#[test]
+fn parse_duplicate_policies() {
+    assert_eq!(OnDuplicate::parse("merge"), Some(OnDuplicate::Merge));
+    assert_eq!(OnDuplicate::parse("Skip"), Some(OnDuplicate::Skip));
+    assert_eq!(OnDuplicate::parse("replace"), Some(OnDuplicate::Overwrite));
+    assert_eq!(
+        OnDuplicate::parse("rename"),
+        Some(OnDuplicate::RenameWithSuffix)
+    );
+    assert_eq!(OnDuplicate::parse("keep"), None);
+    assert_eq!(OnDuplicate::default(), OnDuplicate::RenameWithSuffix);
+}
+
+#[test]
+fn existing_dir_is_suffixed_by_default() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    setup(download.path(), completed.path())?;
+    let cfg = Config {
+        download_base: download.path().to_path_buf(),
+        completed_base: completed.path().to_path_buf(),
+        ..Config::default()
+    };
+
+    let dest = fs_ops::move_dir(&cfg, &download.path().join("Show.S01"))?;
+    assert_ne!(dest, completed.path().join("Show.S01"));
+    assert_eq!(
+        fs::read(completed.path().join("Show.S01/e01.mkv"))?,
+        b"old-e01"
+    );
+    Ok(())
+}
+
+#[test]
+fn merge_renames_colliding_files() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    setup(download.path(), completed.path())?;
+    let cfg = mk_cfg(
+        download.path(),
+        completed.path(),
+        OnDuplicate::RenameWithSuffix,
+    );
+
+    let src = download.path().join("Show.S01");
+    let dest = fs_ops::move_dir(&cfg, &src)?;
+    assert_eq!(dest, completed.path().join("Show.S01"));
+    assert!(!src.exists(), "source should be removed after a full merge");
+    assert_eq!(fs::read(dest.join("e01.mkv"))?, b"old-e01");
+    assert_eq!(fs::read(dest.join("e01 (2).mkv"))?, b"new-e01");
+    assert_eq!(fs::read(dest.join("e02.mkv"))?, b"e02");
+    assert_eq!(fs::read(dest.join("sub/nfo.txt"))?, b"nfo");
+    Ok(())
+}
+
+#[test]
+fn merge_skip_leaves_colliding_files_in_source() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    setup(download.path(), completed.path())?;
+    let cfg = mk_cfg(download.path(), completed.path(), OnDuplicate::Skip);
+
+    let src = download.path().join("Show.S01");
+    let dest = fs_ops::move_dir(&cfg, &src)?;
+    assert_eq!(fs::read(dest.join("e01.mkv"))?, b"old-e01");
+    assert_eq!(fs::read(dest.join("e02.mkv"))?, b"e02");
+    assert_eq!(fs::read(src.join("e01.mkv"))?, b"new-e01");
+    assert!(
+        !src.join("sub").exists(),
+        "emptied subdirectories are removed"
+    );
+    Ok(())
+}
+
+#[test]
+fn merge_overwrite_replaces_colliding_files() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    setup(download.path(), completed.path())?;
+    let cfg = mk_cfg(download.path(), completed.path(), OnDuplicate::Overwrite);
+
+    let src = download.path().join("Show.S01");
+    let dest = fs_ops::move_dir(&cfg, &src)?;
+    assert!(!src.exists());
+    assert_eq!(fs::read(dest.join("e01.mkv"))?, b"new-e01");
+    assert!(!dest.join("e01 (2).mkv").exists());
+    Ok(())
+}
+
+#[test]
+fn xml_duplicate_policies_are_applied() {
+    let td = tempdir().unwrap();
+    let cfg_path = td.path().join("config.xml");
+    fs::write(
+        &cfg_path,
+        r#"<config>
+  <download_base>/tmp/in</download_base>
+  <completed_base>/tmp/out</completed_base>
+  <dir_on_duplicate>merge</dir_on_duplicate>
+  <merge_on_conflict>skip</merge_on_conflict>
+</config>"#,
+    )
+    .unwrap();
+    let cfg = load_config_from_xml_path(&cfg_path).unwrap();
+    assert_eq!(cfg.dir_on_duplicate, OnDuplicate::Merge);
+    assert_eq!(cfg.merge_on_conflict, OnDuplicate::Skip);
+}
@@ -0,0 +1,47 @@
+use aria_move::fs_ops::{DirProgress, Observer};
+use aria_move::{Config, move_dir_with_observer};
+use std::fs;
+use std::sync::Mutex;
+use tempfile::tempdir;
+
+#[derive(Default)]
+struct Recorder(Mutex<Vec<DirProgress>>);
+
+impl Observer for Recorder {
+    fn on_dir_progress(&self, p: &DirProgress) {
+        self.0.lock().unwrap().push(*p);
+    }
+}
+
+#[test]
+fn observer_sees_consistent_progress() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let cfg = Config {
+        download_base: download.path().to_path_buf(),
+        completed_base: completed.path().to_path_buf(),
+        // A same-filesystem rename emits no per-file events; copy so there are some.
+        force_copy: true,
+        ..Config::default()
+    };
+    let src_dir = download.path().join("pack");
+    fs::create_dir_all(&src_dir)?;
+    for i in 0..5 {
+        fs::write(src_dir.join(format!("f{i}.bin")), vec![0u8; 128])?;
+    }
+
+    let rec = Recorder::default();
+    let dest = move_dir_with_observer(&cfg, &src_dir, &rec)?;
+    assert!(dest.join("f0.bin").exists());
+
+    assert!(!src_dir.exists());
+
+    // One event per file, ending in a complete snapshot.
+    let events = rec.0.lock().unwrap();
+    assert_eq!(events.len(), 5);
+    let last = events.last().unwrap();
+    assert!(last.is_complete());
+    assert_eq!(last.bytes_done, 5 * 128);
+    assert_eq!(last.bytes_total, 5 * 128);
+    Ok(())
+}
@@ -1,8 +1,20 @@
 use std::ffi::OsString;
 use std::fs;
+use std::path::{Path, PathBuf};
 use tempfile::tempdir;
 
-use aria_move::fs_ops::{OnDuplicate, resolve_destination};
+use aria_move::fs_ops::{
+    MoveOptions, OnDuplicate, move_entry, move_entry_with, plan_move, resolve_destination,
+};
+use aria_move::{AriaMoveError, Config};
+
+fn mk_cfg(download: &Path, completed: &Path) -> Config {
+    Config {
+        download_base: download.to_path_buf(),
+        completed_base: completed.to_path_buf(),
+        ..Config::default()
+    }
+}
 
 fn long_name(base: &str, total_len: usize) -> OsString {
     // Build a name "<base_repeated>.txt" with at least total_len chars
@@ -55,3 +67,93 @@ fn trims_and_suffixes_on_collision() {
         f1
     );
 }
+
+#[test]
+fn long_name_collision_still_fits_the_name_limit() {
+    let download = tempdir().unwrap();
+    let completed = tempdir().unwrap();
+    let cfg = mk_cfg(download.path(), completed.path());
+    let name = long_name("c", 250);
+    fs::write(completed.path().join(&name), b"old").unwrap();
+    let src = download.path().join(&name);
+    fs::write(&src, b"new").unwrap();
+
+    let dest = move_entry(&cfg, &src).unwrap();
+    let fname = dest.file_name().unwrap().to_string_lossy().into_owned();
+    assert!(fname.len() <= 255, "got {} bytes", fname.len());
+    assert!(fname.ends_with(".txt"));
+    assert_eq!(fs::read(&dest).unwrap(), b"new");
+}
+
+/// A `completed_base` close to PATH_MAX, so a 250-byte name no longer fits beneath it.
+#[cfg(target_os = "linux")]
+fn deep_completed(root: &Path) -> PathBuf {
+    let mut completed = root.to_path_buf();
+    while completed.as_os_str().len() < 3900 {
+        completed.push("d".repeat(200));
+    }
+    fs::create_dir_all(&completed).unwrap();
+    completed
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn name_is_shortened_to_fit_the_path_limit() {
+    let download = tempdir().unwrap();
+    let root = tempdir().unwrap();
+    let completed = deep_completed(root.path());
+    let cfg = mk_cfg(download.path(), &completed);
+
+    let a = download.path().join(long_name("e", 250));
+    let mut b_name = long_name("e", 250).into_string().unwrap();
+    b_name.replace_range(240..241, "f");
+    let b = download.path().join(&b_name);
+    fs::write(&a, b"a").unwrap();
+    fs::write(&b, b"b").unwrap();
+
+    let plan = plan_move(&cfg, &a).unwrap();
+    let dest_a = move_entry(&cfg, &a).unwrap();
+    assert_eq!(plan.destination, dest_a);
+    assert!(dest_a.as_os_str().len() < 4096);
+    assert!(dest_a.to_string_lossy().ends_with(".txt"));
+    assert_eq!(fs::read(&dest_a).unwrap(), b"a");
+
+    // Same shortened prefix, different full name: a different destination.
+    let dest_b = move_entry(&cfg, &b).unwrap();
+    assert_ne!(dest_a, dest_b);
+    assert!(!dest_b.file_name().unwrap().to_string_lossy().contains(" ("));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn skip_finds_an_existing_directory_under_its_shortened_name() {
+    let download = tempdir().unwrap();
+    let root = tempdir().unwrap();
+    let completed = deep_completed(root.path());
+    let cfg = mk_cfg(download.path(), &completed);
+
+    let src = download.path().join("g".repeat(250));
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("a.txt"), b"a").unwrap();
+    let existing = plan_move(&cfg, &src).unwrap().destination;
+    assert_ne!(
+        existing.file_name(),
+        src.file_name(),
+        "name must be shortened"
+    );
+    fs::create_dir(&existing).unwrap();
+
+    let opts = MoveOptions {
+        on_duplicate: Some(OnDuplicate::Skip),
+        ..MoveOptions::default()
+    };
+    let err = move_entry_with(&cfg, &src, &opts).unwrap_err();
+    assert!(
+        matches!(
+            err.downcast_ref::<AriaMoveError>(),
+            Some(AriaMoveError::DestinationExists(p)) if *p == existing
+        ),
+        "{err:#}"
+    );
+    assert!(src.join("a.txt").exists(), "source must be kept");
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "unstable")]
+
 use std::fs;
 use std::io::Write;
 use std::path::Path;
@@ -12,8 +14,8 @@ fn mk_cfg(download: &Path, completed: &Path) -> aria_move::Config {
 }
 
 // Use the public util::resume_temp_path instead of duplicating hashing logic.
-fn test_resume_temp_path(dest: &Path) -> std::path::PathBuf {
-    aria_move::fs_ops::resume_temp_path(dest)
+fn test_resume_temp_path(src: &Path, dest: &Path) -> std::path::PathBuf {
+    aria_move::fs_ops::resume_temp_path(src, dest)
 }
 
 #[test]
@@ -28,7 +30,7 @@ fn resumes_partial_file_copy_and_finalizes() -> Result<(), Box<dyn std::error::E
 
     // Compute intended destination and temp path
     let dest = completed.path().join("big.dat");
-    let tmp = test_resume_temp_path(&dest);
+    let tmp = test_resume_temp_path(&src, &dest);
 
     // Pre-create partial temp file with first half of content
     if let Some(parent) = tmp.parent() {
@@ -50,3 +52,30 @@ fn resumes_partial_file_copy_and_finalizes() -> Result<(), Box<dyn std::error::E
 
     Ok(())
 }
+
+#[test]
+fn resume_temps_are_per_source_and_validated() -> Result<(), Box<dyn std::error::Error>> {
+    let td = tempdir()?;
+    let a = td.path().join("a/show.mkv");
+    let b = td.path().join("b/show.mkv");
+    fs::create_dir_all(a.parent().unwrap())?;
+    fs::create_dir_all(b.parent().unwrap())?;
+    fs::write(&a, vec![1u8; 100_000])?;
+    fs::write(&b, vec![2u8; 100_000])?;
+    let dest = td.path().join("out/show.mkv");
+    fs::create_dir_all(dest.parent().unwrap())?;
+
+    // Two sources aimed at the same destination name never share a temp.
+    assert_ne!(
+        test_resume_temp_path(&a, &dest),
+        test_resume_temp_path(&b, &dest)
+    );
+
+    // A temp that is not a prefix of the source is discarded, not resumed.
+    let tmp = test_resume_temp_path(&a, &dest);
+    fs::write(&tmp, vec![9u8; 40_000])?;
+    aria_move::fs_ops::safe_copy_and_rename(&a, &dest)?;
+    assert_eq!(fs::read(&dest)?, vec![1u8; 100_000]);
+    assert!(!tmp.exists());
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use aria_move::api::{ConfigBuilder, Mover};
+use aria_move::fs_ops::{self, TransferStrategy};
+use aria_move::{Config, CopyBackend};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn mover(root: &Path) -> Mover {
+    Mover::new(
+        ConfigBuilder::default()
+            .download_base(root.join("incoming"))
+            .completed_base(root.join("completed"))
+            .force_copy(true),
+    )
+    .unwrap()
+}
+
+#[test]
+fn force_copy_plans_a_copy_on_one_filesystem() {
+    let td = tempdir().unwrap();
+    let cfg = Config {
+        download_base: td.path().join("incoming"),
+        completed_base: td.path().join("completed"),
+        force_copy: true,
+        ..Config::default()
+    };
+    fs::create_dir_all(&cfg.download_base).unwrap();
+    fs::create_dir_all(&cfg.completed_base).unwrap();
+    let src = cfg.download_base.join("a.bin");
+    fs::write(&src, b"data").unwrap();
+
+    let plan = fs_ops::plan_move(&cfg, &src).unwrap();
+    assert_eq!(plan.strategy, TransferStrategy::Copy(CopyBackend::Builtin));
+}
+
+#[test]
+fn force_copy_copies_and_removes_the_source_file() {
+    let td = tempdir().unwrap();
+    let root = dunce::canonicalize(td.path()).unwrap();
+    let mover = mover(&root);
+    let src = mover.config().download_base.join("movie.mkv");
+    fs::write(&src, b"payload").unwrap();
+
+    let report = mover.move_path(&src).unwrap();
+    assert_eq!(report.timings.bytes_copied, 7);
+    assert!(!report.source_retained);
+    assert!(!src.exists());
+    assert_eq!(fs::read(&report.destination).unwrap(), b"payload");
+}
+
+#[test]
+fn force_copy_copies_and_removes_the_source_tree() {
+    let td = tempdir().unwrap();
+    let root = dunce::canonicalize(td.path()).unwrap();
+    let mover = mover(&root);
+    let src = mover.config().download_base.join("album");
+    fs::create_dir_all(src.join("cd1")).unwrap();
+    fs::write(src.join("cd1/01.flac"), b"one").unwrap();
+
+    let report = mover.move_path(&src).unwrap();
+    assert_eq!(report.timings.bytes_copied, 3);
+    assert!(!src.exists());
+    assert_eq!(
+        fs::read(report.destination.join("cd1/01.flac")).unwrap(),
+        b"one"
+    );
+}
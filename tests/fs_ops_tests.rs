@@ -1,4 +1,6 @@
+use aria_move::AriaMoveError;
 use aria_move::fs_ops::safe_copy_and_rename;
+use aria_move::messages::{Msg, text};
 use std::fs;
 use std::io::Write;
 use tempfile::tempdir;
@@ -64,3 +66,43 @@ fn safe_copy_and_rename_handles_existing_destination_by_replacing() {
     let content = fs::read_to_string(&dest).expect("read destination");
     assert_eq!(content, "new content");
 }
+
+#[test]
+fn safe_copy_and_rename_returns_typed_io_error_with_hint() {
+    let td = tempdir().unwrap();
+    let missing = td.path().join("missing.txt");
+    let dest = td.path().join("out/dest.txt");
+
+    let err = safe_copy_and_rename(&missing, &dest).unwrap_err();
+    match &err {
+        AriaMoveError::Io { kind, .. } => assert_eq!(kind, "NotFound"),
+        other => panic!("expected Io, got {other:?}"),
+    }
+    assert_eq!(err.code(), "io");
+    assert_eq!(err.hint(), Some(text(Msg::HintNotFound)));
+    assert!(err.to_string().contains("missing.txt"), "{err}");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn safe_copy_and_rename_beneath_refuses_symlink_escapes() {
+    use aria_move::fs_ops::safe_copy_and_rename_beneath;
+    let td = tempdir().unwrap();
+    let src = td.path().join("src.txt");
+    create_file_with_content(&src, "payload");
+    let base = td.path().join("base");
+    let outside = td.path().join("outside");
+    fs::create_dir_all(&base).unwrap();
+    fs::create_dir_all(&outside).unwrap();
+
+    safe_copy_and_rename_beneath(&base, &src, &base.join("a").join("ok.txt")).unwrap();
+    assert_eq!(
+        fs::read_to_string(base.join("a/ok.txt")).unwrap(),
+        "payload"
+    );
+
+    // A destination directory swapped for a symlink that leaves the base.
+    std::os::unix::fs::symlink(&outside, base.join("shows")).unwrap();
+    assert!(safe_copy_and_rename_beneath(&base, &src, &base.join("shows").join("x.txt")).is_err());
+    assert!(fs::read_dir(&outside).unwrap().next().is_none());
+}
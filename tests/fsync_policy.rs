@@ -0,0 +1,55 @@
+use aria_move::{Config, FsyncPolicy, fs_ops, load_config_from_xml_path};
+use std::fs;
+use std::str::FromStr;
+use tempfile::tempdir;
+
+#[test]
+fn parse_and_display_fsync_policies() {
+    assert_eq!(FsyncPolicy::parse("FULL"), Some(FsyncPolicy::Full));
+    assert_eq!(FsyncPolicy::parse("data"), Some(FsyncPolicy::Data));
+    assert_eq!(FsyncPolicy::parse("off"), Some(FsyncPolicy::None));
+    assert_eq!(FsyncPolicy::parse("sometimes"), None);
+    assert_eq!(Config::default().fsync_policy, FsyncPolicy::Full);
+    for policy in [FsyncPolicy::Full, FsyncPolicy::Data, FsyncPolicy::None] {
+        assert_eq!(FsyncPolicy::from_str(&policy.to_string()).unwrap(), policy);
+    }
+    assert!(FsyncPolicy::Data.syncs_files() && !FsyncPolicy::Data.syncs_dirs());
+    assert!(!FsyncPolicy::None.syncs_files());
+}
+
+#[test]
+fn xml_fsync_policy_is_applied() {
+    let td = tempdir().unwrap();
+    let cfg_path = td.path().join("config.xml");
+    fs::write(
+        &cfg_path,
+        r#"<config>
+  <download_base>/tmp/in</download_base>
+  <completed_base>/tmp/out</completed_base>
+  <fsync_policy>data</fsync_policy>
+</config>"#,
+    )
+    .unwrap();
+    let cfg = load_config_from_xml_path(&cfg_path).unwrap();
+    assert_eq!(cfg.fsync_policy, FsyncPolicy::Data);
+}
+
+#[test]
+fn moves_work_without_fsync() {
+    let download = tempdir().unwrap();
+    let completed = tempdir().unwrap();
+    for copy_only in [false, true] {
+        let cfg = Config {
+            download_base: download.path().to_path_buf(),
+            completed_base: completed.path().to_path_buf(),
+            fsync_policy: FsyncPolicy::None,
+            copy_only,
+            ..Config::default()
+        };
+        let src = download.path().join(format!("item-{copy_only}.bin"));
+        fs::write(&src, b"bytes").unwrap();
+        let dest = fs_ops::move_entry(&cfg, &src).unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), b"bytes");
+        assert_eq!(src.exists(), copy_only);
+    }
+}
@@ -0,0 +1,402 @@
+use aria_move::config::xml::create_template_config;
+use aria_move::fs_ops::{
+    Handler, HandlerAction, MoveOptions, OnDuplicate, OnRedownload, move_entry_with,
+};
+use aria_move::{AriaMoveError, Config, load_config_from_xml_path, move_entry};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+// A well-formed digest that matches none of the test files.
+const OTHER_SHA256: &str = "b46c3dc7d7e3b8f2b5ba1aa78c4b6b3b5a6dd0e4c9b1c5a4b8a4a5f9b1d0a3c2";
+
+fn mk_cfg(download: &Path, completed: &Path, handlers: Vec<Handler>) -> Config {
+    Config {
+        download_base: download.to_path_buf(),
+        completed_base: completed.to_path_buf(),
+        handlers,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn xml_handlers_block_is_parsed() {
+    let td = tempdir().unwrap();
+    let cfg_path = td.path().join("config.xml");
+    fs::write(
+        &cfg_path,
+        r#"<config>
+    <download_base>/in</download_base>
+    <completed_base>/out</completed_base>
+    <handlers>
+        <handler ext=".ISO" action="verify-sha256"/>
+        <handler ext="zip" action="extract"/>
+        <handler ext="nfo" action="delete"/>
+        <handler ext="torrent" action="archive" dir="/srv/torrents"/>
+    </handlers>
+</config>"#,
+    )
+    .unwrap();
+    let cfg = load_config_from_xml_path(&cfg_path).unwrap();
+    assert_eq!(
+        cfg.handlers,
+        vec![
+            Handler::new("iso", HandlerAction::VerifySha256),
+            Handler::new("zip", HandlerAction::Extract),
+            Handler::new("nfo", HandlerAction::Delete),
+            Handler::new(
+                "torrent",
+                HandlerAction::Archive(PathBuf::from("/srv/torrents"))
+            ),
+        ]
+    );
+
+    // An invalid entry fails the load, naming the handler.
+    let bad = td.path().join("bad.xml");
+    fs::write(
+        &bad,
+        r#"<config><handlers><handler ext="txt" action="shred"/></handlers></config>"#,
+    )
+    .unwrap();
+    let err = load_config_from_xml_path(&bad).unwrap_err();
+    assert!(format!("{err:#}").contains("handler 'txt'"), "{err:#}");
+
+    // The template's commented-out examples leave the list empty.
+    let tpl = td.path().join("tpl").join("config.xml");
+    create_template_config(&tpl).unwrap();
+    assert!(load_config_from_xml_path(&tpl).unwrap().handlers.is_empty());
+}
+
+#[test]
+fn delete_and_archive_consume_the_source() {
+    let td = tempdir().unwrap();
+    let (download, completed, torrents) = (
+        td.path().join("in"),
+        td.path().join("out"),
+        td.path().join("torrents"),
+    );
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    let cfg = mk_cfg(
+        &download,
+        &completed,
+        vec![
+            Handler::new("nfo", HandlerAction::Delete),
+            Handler::new("torrent", HandlerAction::Archive(torrents.clone())),
+        ],
+    );
+
+    let nfo = download.join("movie.nfo");
+    fs::write(&nfo, b"info").unwrap();
+    move_entry(&cfg, &nfo).unwrap();
+    assert!(!nfo.exists());
+    assert!(!completed.join("movie.nfo").exists());
+
+    let torrent = download.join("movie.torrent");
+    fs::write(&torrent, b"d8:announce").unwrap();
+    let dest = move_entry(&cfg, &torrent).unwrap();
+    assert_eq!(dest, torrents.join("movie.torrent"));
+    assert_eq!(fs::read(&dest).unwrap(), b"d8:announce");
+    assert!(!torrent.exists());
+}
+
+#[test]
+fn directory_sources_are_handled_file_by_file() {
+    let td = tempdir().unwrap();
+    let (download, completed) = (td.path().join("in"), td.path().join("out"));
+    let src = download.join("Show");
+    fs::create_dir_all(src.join("extras")).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    fs::write(src.join("e01.mkv"), b"video").unwrap();
+    fs::write(src.join("Show.NFO"), b"info").unwrap();
+    fs::write(src.join("extras/release.nfo"), b"info").unwrap();
+    let cfg = mk_cfg(
+        &download,
+        &completed,
+        vec![Handler::new("nfo", HandlerAction::Delete)],
+    );
+
+    let dest = move_entry(&cfg, &src).unwrap();
+    assert!(dest.join("e01.mkv").exists());
+    assert!(!dest.join("Show.NFO").exists());
+    assert!(!dest.join("extras/release.nfo").exists());
+    assert!(dest.join("extras").is_dir());
+}
+
+#[test]
+fn directory_checksums_are_verified_before_anything_is_deleted() {
+    let td = tempdir().unwrap();
+    let (download, completed) = (td.path().join("in"), td.path().join("out"));
+    let src = download.join("Release");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    // Handlers walk in name order, so a file-by-file pass would delete the .nfo first.
+    fs::write(src.join("a-release.nfo"), b"info").unwrap();
+    fs::write(src.join("z-disc.iso"), b"corrupt").unwrap();
+    fs::write(src.join("z-disc.iso.sha256"), OTHER_SHA256).unwrap();
+    let cfg = mk_cfg(
+        &download,
+        &completed,
+        vec![
+            Handler::new("nfo", HandlerAction::Delete),
+            Handler::new("iso", HandlerAction::VerifySha256),
+        ],
+    );
+
+    let err = move_entry(&cfg, &src).unwrap_err();
+    assert!(
+        matches!(
+            err.downcast_ref::<AriaMoveError>(),
+            Some(AriaMoveError::ChecksumMismatch { .. })
+        ),
+        "{err:#}"
+    );
+    assert!(
+        src.join("a-release.nfo").exists(),
+        "nothing deleted on a mismatch"
+    );
+    assert!(src.join("z-disc.iso").exists());
+    assert!(!completed.join("Release").exists());
+}
+
+#[test]
+fn a_refused_directory_move_leaves_its_files_in_place() {
+    let td = tempdir().unwrap();
+    let (download, completed) = (td.path().join("in"), td.path().join("out"));
+    let src = download.join("Show");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(completed.join("Show")).unwrap();
+    fs::write(src.join("e01.mkv"), b"video").unwrap();
+    fs::write(src.join("Show.nfo"), b"info").unwrap();
+    let cfg = mk_cfg(
+        &download,
+        &completed,
+        vec![Handler::new("nfo", HandlerAction::Delete)],
+    );
+
+    let skip = MoveOptions {
+        on_duplicate: Some(OnDuplicate::Skip),
+        ..MoveOptions::default()
+    };
+    let err = move_entry_with(&cfg, &src, &skip).unwrap_err();
+    assert!(
+        matches!(
+            err.downcast_ref::<AriaMoveError>(),
+            Some(AriaMoveError::DestinationExists(_))
+        ),
+        "{err:#}"
+    );
+    assert!(
+        src.join("Show.nfo").exists(),
+        "nothing deleted on a refusal"
+    );
+    assert!(src.join("e01.mkv").exists());
+}
+
+#[test]
+fn a_skipped_redownload_is_not_deleted_by_a_handler() {
+    let td = tempdir().unwrap();
+    let (download, completed) = (td.path().join("in"), td.path().join("out"));
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    let mut cfg = mk_cfg(&download, &completed, Vec::new());
+    cfg.on_redownload = OnRedownload::Skip;
+    let opts = MoveOptions {
+        infohash: Some("c12fe1c06bba254a9dc9f519b335aa7c1367a88a".to_string()),
+        ..MoveOptions::default()
+    };
+    let nfo = download.join("movie.nfo");
+    fs::write(&nfo, b"v1").unwrap();
+    move_entry_with(&cfg, &nfo, &opts).unwrap();
+
+    cfg.handlers = vec![Handler::new("nfo", HandlerAction::Delete)];
+    fs::write(&nfo, b"v2").unwrap();
+    let err = move_entry_with(&cfg, &nfo, &opts).unwrap_err();
+    assert!(
+        matches!(
+            err.downcast_ref::<AriaMoveError>(),
+            Some(AriaMoveError::DestinationExists(_))
+        ),
+        "{err:#}"
+    );
+    assert_eq!(fs::read(&nfo).unwrap(), b"v2", "the source is kept");
+}
+
+#[test]
+fn copy_only_directories_are_copied_whole_and_trimmed_at_the_destination() {
+    let td = tempdir().unwrap();
+    let (download, completed) = (td.path().join("in"), td.path().join("out"));
+    let src = download.join("Show");
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    fs::write(src.join("e01.mkv"), b"video").unwrap();
+    fs::write(src.join("Show.nfo"), b"info").unwrap();
+    let mut cfg = mk_cfg(
+        &download,
+        &completed,
+        vec![Handler::new("nfo", HandlerAction::Delete)],
+    );
+    cfg.copy_only = true;
+
+    let dest = move_entry(&cfg, &src).unwrap();
+    assert!(dest.join("e01.mkv").exists());
+    assert!(!dest.join("Show.nfo").exists());
+    assert!(
+        src.join("Show.nfo").exists(),
+        "copy_only keeps the source whole"
+    );
+}
+
+#[test]
+fn verify_sha256_moves_matching_files_and_keeps_mismatches() {
+    let td = tempdir().unwrap();
+    let (download, completed) = (td.path().join("in"), td.path().join("out"));
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    let cfg = mk_cfg(
+        &download,
+        &completed,
+        vec![Handler::new("iso", HandlerAction::VerifySha256)],
+    );
+
+    // sha256("abc"), in sha256sum's "<digest>  <name>" format
+    let good = download.join("good.iso");
+    fs::write(&good, b"abc").unwrap();
+    fs::write(
+        download.join("good.iso.sha256"),
+        "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD  good.iso\n",
+    )
+    .unwrap();
+    let dest = move_entry(&cfg, &good).unwrap();
+    assert_eq!(fs::read(&dest).unwrap(), b"abc");
+
+    let bad = download.join("bad.iso");
+    fs::write(&bad, b"corrupt").unwrap();
+    fs::write(download.join("bad.iso.sha256"), OTHER_SHA256).unwrap();
+    let err = move_entry(&cfg, &bad).unwrap_err();
+    match err.downcast_ref::<AriaMoveError>() {
+        Some(AriaMoveError::ChecksumMismatch { path, expected, .. }) => {
+            assert_eq!(path, &bad);
+            assert_eq!(expected, OTHER_SHA256);
+        }
+        other => panic!("expected ChecksumMismatch, got {other:?} ({err:#})"),
+    }
+    assert!(bad.exists(), "a mismatching source stays in place");
+    assert!(!completed.join("bad.iso").exists());
+
+    // No sidecar: moved without verification.
+    let plain = download.join("plain.iso");
+    fs::write(&plain, b"x").unwrap();
+    assert!(move_entry(&cfg, &plain).unwrap().exists());
+}
+
+#[test]
+fn dry_run_leaves_handled_files_alone() {
+    let td = tempdir().unwrap();
+    let (download, completed) = (td.path().join("in"), td.path().join("out"));
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    let mut cfg = mk_cfg(
+        &download,
+        &completed,
+        vec![Handler::new("nfo", HandlerAction::Delete)],
+    );
+    cfg.dry_run = true;
+
+    let nfo = download.join("movie.nfo");
+    fs::write(&nfo, b"info").unwrap();
+    assert_eq!(move_entry(&cfg, &nfo).unwrap(), nfo);
+    assert!(nfo.exists());
+}
+
+#[cfg(unix)]
+#[test]
+#[serial_test::serial]
+fn extract_unpacks_beside_the_moved_archive() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let td = tempdir().unwrap();
+    let (download, completed) = (td.path().join("in"), td.path().join("out"));
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    // Stand-in for unzip: `-q <archive> -d <target>` creates <target>/inside.txt.
+    let fake = td.path().join("fake-unzip");
+    fs::write(
+        &fake,
+        "#!/bin/sh\nmkdir -p \"$4\" && cp \"$2\" \"$4/inside.txt\"\n",
+    )
+    .unwrap();
+    fs::set_permissions(&fake, fs::Permissions::from_mode(0o755)).unwrap();
+    let cfg = mk_cfg(
+        &download,
+        &completed,
+        vec![Handler::new("zip", HandlerAction::Extract)],
+    );
+
+    let zip = download.join("bundle.zip");
+    fs::write(&zip, b"PK").unwrap();
+    unsafe { std::env::set_var("ARIA_MOVE_UNZIP", &fake) };
+    let dest = move_entry(&cfg, &zip);
+    unsafe { std::env::remove_var("ARIA_MOVE_UNZIP") };
+
+    let dest = dest.unwrap();
+    assert!(dest.exists(), "the archive itself is kept");
+    assert_eq!(
+        fs::read(completed.join("bundle/inside.txt")).unwrap(),
+        b"PK"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+#[serial_test::serial]
+fn extract_unpacks_a_tarball_without_its_full_extension() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let td = tempdir().unwrap();
+    let (download, completed) = (td.path().join("in"), td.path().join("out"));
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+    // Stand-in for tar: `-x -f <archive> -C <target>` into an existing <target>.
+    let fake = td.path().join("fake-tar");
+    fs::write(
+        &fake,
+        "#!/bin/sh\n[ -d \"$5\" ] && cp \"$3\" \"$5/inside.txt\"\n",
+    )
+    .unwrap();
+    fs::set_permissions(&fake, fs::Permissions::from_mode(0o755)).unwrap();
+    let cfg = mk_cfg(
+        &download,
+        &completed,
+        vec![Handler::new("tar.gz", HandlerAction::Extract)],
+    );
+
+    let tarball = download.join("bundle.TAR.GZ");
+    fs::write(&tarball, b"gz").unwrap();
+    unsafe { std::env::set_var("ARIA_MOVE_TAR", &fake) };
+    let dest = move_entry(&cfg, &tarball);
+    unsafe { std::env::remove_var("ARIA_MOVE_TAR") };
+
+    assert!(dest.unwrap().exists(), "the archive itself is kept");
+    assert_eq!(
+        fs::read(completed.join("bundle/inside.txt")).unwrap(),
+        b"gz"
+    );
+    assert!(!completed.join("bundle.TAR").exists());
+}
+
+#[test]
+fn extract_of_an_unsupported_archive_type_is_a_config_error() {
+    let td = tempdir().unwrap();
+    let cfg_path = td.path().join("config.xml");
+    fs::write(
+        &cfg_path,
+        r#"<config><handlers><handler ext="rar" action="extract"/></handlers></config>"#,
+    )
+    .unwrap();
+    let err = load_config_from_xml_path(&cfg_path)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("handler 'rar'"), "{err}");
+    assert!(err.contains("tar.gz"), "{err}");
+}
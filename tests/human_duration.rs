@@ -0,0 +1,84 @@
+use aria_move::config::types::HumanDuration;
+use aria_move::load_config_from_xml_path;
+use std::fs;
+use std::str::FromStr;
+use std::time::Duration;
+
+fn secs(s: &str) -> Duration {
+    HumanDuration::parse(s).unwrap().get()
+}
+
+#[test]
+fn parse_plain_seconds_and_unit_terms() {
+    assert_eq!(secs("300"), Duration::from_secs(300));
+    assert_eq!(secs("90s"), Duration::from_secs(90));
+    assert_eq!(secs("5m"), Duration::from_secs(300));
+    assert_eq!(secs("2h"), Duration::from_secs(7200));
+    assert_eq!(secs("1d"), Duration::from_secs(86_400));
+    assert_eq!(secs("1h30m"), Duration::from_secs(5400));
+    assert_eq!(secs(" 1 hour 30 min "), Duration::from_secs(5400));
+    assert_eq!(secs("1.5h"), Duration::from_secs(5400));
+    assert_eq!(secs("250ms"), Duration::from_millis(250));
+    assert_eq!(secs("0"), Duration::ZERO);
+}
+
+#[test]
+fn parse_invalid_durations_is_err() {
+    for bad in ["", "m", "-5s", "5 fortnights", "1.2.3h", "5m soon"] {
+        let err = HumanDuration::from_str(bad).unwrap_err();
+        assert!(err.contains("invalid duration"), "{bad}: {err}");
+    }
+}
+
+#[test]
+fn display_roundtrips_with_fromstr() {
+    for d in ["0s", "90ms", "1m30s", "5m", "1h30m", "2d3h"] {
+        let parsed = HumanDuration::parse(d).unwrap();
+        assert_eq!(parsed.to_string(), d);
+    }
+}
+
+#[test]
+fn deserializes_from_strings_and_integers() {
+    let d: HumanDuration = serde_json::from_str("\"5m\"").unwrap();
+    assert_eq!(d.get(), Duration::from_secs(300));
+    let n: HumanDuration = serde_json::from_str("45").unwrap();
+    assert_eq!(n.get(), Duration::from_secs(45));
+    assert!(serde_json::from_str::<HumanDuration>("\"later\"").is_err());
+}
+
+#[test]
+fn xml_duration_fields_accept_units() {
+    let td = tempfile::tempdir().unwrap();
+    let cfg_path = td.path().join("config.xml");
+    fs::write(
+        &cfg_path,
+        r#"<config>
+    <download_base>/in</download_base>
+    <completed_base>/out</completed_base>
+    <recent_window>10m</recent_window>
+    <on_full_max_wait>2h</on_full_max_wait>
+    <max_runtime>90s</max_runtime>
+</config>"#,
+    )
+    .unwrap();
+    let cfg = load_config_from_xml_path(&cfg_path).unwrap();
+    assert_eq!(cfg.recent_window, Duration::from_secs(600));
+    assert_eq!(cfg.on_full_max_wait, Duration::from_secs(7200));
+    assert_eq!(cfg.max_runtime, Some(Duration::from_secs(90)));
+
+    // An invalid value fails the load, naming the field.
+    fs::write(
+        &cfg_path,
+        r#"<config>
+    <download_base>/in</download_base>
+    <completed_base>/out</completed_base>
+    <recent_window>soon</recent_window>
+</config>"#,
+    )
+    .unwrap();
+    let err = load_config_from_xml_path(&cfg_path)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("recent_window"), "{err}");
+}
@@ -0,0 +1,83 @@
+use aria_move::{AriaMoveError, Config, list_candidates, move_entry, sweep_download_base};
+use filetime::{FileTime, set_file_mtime};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tempfile::tempdir;
+
+fn mk_cfg(download: &Path, completed: &Path) -> Config {
+    Config {
+        download_base: download.to_path_buf(),
+        completed_base: completed.to_path_buf(),
+        recent_window: Duration::from_secs(60),
+        resolve_directories: true,
+        ..Config::default()
+    }
+}
+
+fn age(path: &Path) {
+    let t = SystemTime::now() - Duration::from_secs(600);
+    set_file_mtime(path, FileTime::from_system_time(t)).unwrap();
+}
+
+/// download/{.ariamoveignore, manual/keep.mkv, show/e01.mkv}, all old enough to sweep.
+fn setup(base: &Path) {
+    fs::write(base.join(".ariamoveignore"), "# hands off\nmanual/\n").unwrap();
+    fs::create_dir_all(base.join("manual")).unwrap();
+    fs::write(base.join("manual/keep.mkv"), b"k").unwrap();
+    fs::create_dir_all(base.join("show")).unwrap();
+    fs::write(base.join("show/e01.mkv"), b"e").unwrap();
+    for p in [
+        base.join(".ariamoveignore"),
+        base.join("manual/keep.mkv"),
+        base.join("manual"),
+        base.join("show/e01.mkv"),
+        base.join("show"),
+    ] {
+        age(&p);
+    }
+}
+
+#[test]
+fn ignored_paths_are_not_candidates_or_swept() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let base = download.path();
+    setup(base);
+    let cfg = mk_cfg(base, completed.path());
+
+    let names: Vec<_> = list_candidates(&cfg)
+        .into_iter()
+        .map(|c| c.path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, vec!["show"]);
+
+    let report = sweep_download_base(&cfg, None, true)?;
+    assert_eq!(report.found, vec![base.join("show")]);
+    assert!(base.join("manual/keep.mkv").exists());
+    assert!(base.join(".ariamoveignore").exists());
+    Ok(())
+}
+
+#[test]
+fn move_entry_refuses_ignored_paths() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let base = download.path();
+    setup(base);
+    let cfg = mk_cfg(base, completed.path());
+
+    for p in [base.join("manual"), base.join("manual/keep.mkv")] {
+        let err = move_entry(&cfg, &p).unwrap_err();
+        assert!(
+            matches!(
+                err.downcast_ref::<AriaMoveError>(),
+                Some(AriaMoveError::Ignored(_))
+            ),
+            "expected Ignored for {}: {err:#}",
+            p.display()
+        );
+    }
+    assert!(base.join("manual/keep.mkv").exists());
+    Ok(())
+}
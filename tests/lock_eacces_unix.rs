@@ -1,6 +1,6 @@
 #![cfg(unix)]
 
-use aria_move::{move_entry, Config};
+use aria_move::{Config, move_entry};
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
 use std::path::PathBuf;
@@ -30,9 +30,11 @@ fn move_file_fallbacks_when_lock_eacces() {
     }
 
     // Build config
-    let mut cfg = Config::default();
-    cfg.download_base = PathBuf::from(&download_base);
-    cfg.completed_base = PathBuf::from(&completed_base);
+    let cfg = Config {
+        download_base: PathBuf::from(&download_base),
+        completed_base: PathBuf::from(&completed_base),
+        ..Default::default()
+    };
 
     // Perform the move: should succeed because fallback skips locks on EACCES
     let dest = move_entry(&cfg, &src_path).expect("move should succeed with lock EACCES fallback");
@@ -51,6 +53,14 @@ fn move_file_fallbacks_when_lock_eacces() {
 /// Negative check: if destination directory also lacks write permission (0o111), the move fails.
 #[test]
 fn move_file_fails_without_write_permission() {
+    // Skip on root: root ignores directory permission bits, which defeats this test's premise.
+    unsafe {
+        if libc::geteuid() == 0 {
+            eprintln!("skipping: running as root");
+            return;
+        }
+    }
+
     let td = tempdir().unwrap();
     let download_base = td.path().join("incoming2");
     let completed_base = td.path().join("completed2");
@@ -69,13 +79,20 @@ fn move_file_fails_without_write_permission() {
     d_perms.set_mode(0o111);
     fs::set_permissions(&completed_base, d_perms).unwrap();
 
-    let mut cfg = Config::default();
-    cfg.download_base = PathBuf::from(&download_base);
-    cfg.completed_base = PathBuf::from(&completed_base);
+    let cfg = Config {
+        download_base: PathBuf::from(&download_base),
+        completed_base: PathBuf::from(&completed_base),
+        ..Default::default()
+    };
 
-    let err = move_entry(&cfg, &src_path).expect_err("expected failure without write permission on destination");
+    let err = move_entry(&cfg, &src_path)
+        .expect_err("expected failure without write permission on destination");
     let msg = format!("{err}");
-    assert!(msg.to_ascii_lowercase().contains("permission") || msg.to_ascii_lowercase().contains("access"), "unexpected error message: {msg}");
+    assert!(
+        msg.to_ascii_lowercase().contains("permission")
+            || msg.to_ascii_lowercase().contains("access"),
+        "unexpected error message: {msg}"
+    );
 
     // Restore perms for cleanup
     for dir in [&download_base, &completed_base] {
@@ -1,6 +1,14 @@
+#![cfg(feature = "unstable")]
+
+use serial_test::serial;
 use std::fs;
+use std::io;
 
-use aria_move::fs_ops::{acquire_dir_lock, acquire_move_lock, try_acquire_dir_lock};
+use aria_move::LockStrategy;
+use aria_move::fs_ops::{
+    LOCKFILE_NAME, acquire_dir_lock, acquire_dir_lock_with, acquire_move_lock,
+    try_acquire_dir_lock, try_acquire_dir_lock_with,
+};
 
 #[test]
 fn try_lock_uncontended() {
@@ -33,3 +41,51 @@ fn move_lock_locks_parent_dir() {
     let none = try_acquire_dir_lock(dir.path()).unwrap();
     assert!(none.is_none());
 }
+
+#[test]
+fn lockfile_strategy_is_exclusive_and_cleans_up() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = acquire_dir_lock_with(dir.path(), LockStrategy::Lockfile).unwrap();
+    assert!(dir.path().join(LOCKFILE_NAME).exists());
+    let second = try_acquire_dir_lock_with(dir.path(), LockStrategy::Lockfile).unwrap();
+    assert!(second.is_none());
+    drop(first);
+    assert!(!dir.path().join(LOCKFILE_NAME).exists());
+    let third = try_acquire_dir_lock_with(dir.path(), LockStrategy::Lockfile).unwrap();
+    assert!(third.is_some());
+}
+
+#[test]
+fn none_strategy_never_blocks() {
+    let dir = tempfile::tempdir().unwrap();
+    let _held = acquire_dir_lock(dir.path()).unwrap();
+    let got = try_acquire_dir_lock_with(dir.path(), LockStrategy::None).unwrap();
+    assert!(got.is_some());
+}
+
+#[test]
+#[serial]
+fn lock_timeout_env_bounds_blocking_acquire() {
+    let dir = tempfile::tempdir().unwrap();
+    let _held = acquire_dir_lock_with(dir.path(), LockStrategy::Lockfile).unwrap();
+
+    unsafe {
+        std::env::set_var("ARIA_MOVE_LOCK_TIMEOUT", "0");
+    }
+    let contended = acquire_dir_lock_with(dir.path(), LockStrategy::Lockfile)
+        .err()
+        .map(|e| e.kind());
+
+    unsafe {
+        std::env::set_var("ARIA_MOVE_LOCK_TIMEOUT", "1");
+    }
+    let timed_out = acquire_dir_lock_with(dir.path(), LockStrategy::Lockfile)
+        .err()
+        .map(|e| e.kind());
+
+    unsafe {
+        std::env::remove_var("ARIA_MOVE_LOCK_TIMEOUT");
+    }
+    assert_eq!(contended, Some(io::ErrorKind::WouldBlock));
+    assert_eq!(timed_out, Some(io::ErrorKind::TimedOut));
+}
@@ -1,6 +1,6 @@
 #![cfg(unix)]
 
-use aria_move::{move_entry, Config};
+use aria_move::{Config, move_entry};
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
 use std::path::PathBuf;
@@ -33,13 +33,19 @@ fn move_dir_succeeds_when_dest_no_read_with_locks_disabled() {
         std::env::set_var("ARIA_MOVE_DISABLE_LOCKS", "1");
     }
 
-    let mut cfg = Config::default();
-    cfg.download_base = PathBuf::from(&download_base);
-    cfg.completed_base = PathBuf::from(&completed_base);
+    let cfg = Config {
+        download_base: PathBuf::from(&download_base),
+        completed_base: PathBuf::from(&completed_base),
+        ..Default::default()
+    };
 
-    let dest = move_entry(&cfg, &src_dir).expect("dir move should succeed without directory read perms when locks disabled");
+    let dest = move_entry(&cfg, &src_dir)
+        .expect("dir move should succeed without directory read perms when locks disabled");
     assert!(dest.exists(), "destination directory should exist");
-    assert!(dest.join("a.txt").exists(), "file should be present in destination");
+    assert!(
+        dest.join("a.txt").exists(),
+        "file should be present in destination"
+    );
 
     // Restore perms to allow cleanup
     let mut restore = fs::metadata(&completed_base).unwrap().permissions();
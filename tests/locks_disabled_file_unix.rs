@@ -1,6 +1,6 @@
 #![cfg(unix)]
 
-use aria_move::{move_entry, Config};
+use aria_move::{Config, move_entry};
 use std::fs;
 use std::io::Write;
 use std::os::unix::fs::PermissionsExt;
@@ -36,11 +36,14 @@ fn move_file_succeeds_when_dest_no_read_with_locks_disabled() {
         std::env::set_var("ARIA_MOVE_DISABLE_LOCKS", "1");
     }
 
-    let mut cfg = Config::default();
-    cfg.download_base = PathBuf::from(&download_base);
-    cfg.completed_base = PathBuf::from(&completed_base);
+    let cfg = Config {
+        download_base: PathBuf::from(&download_base),
+        completed_base: PathBuf::from(&completed_base),
+        ..Default::default()
+    };
 
-    let dest = move_entry(&cfg, &src).expect("move should succeed without directory read perms when locks disabled");
+    let dest = move_entry(&cfg, &src)
+        .expect("move should succeed without directory read perms when locks disabled");
     assert!(dest.exists(), "destination should exist");
 
     // Cleanup: restore perms so tempdir can cleanly delete on all systems
@@ -0,0 +1,60 @@
+use aria_move::config::types::LogTimeFormat;
+use std::str::FromStr;
+
+#[test]
+fn presets_and_custom_formats_parse() {
+    assert_eq!(
+        LogTimeFormat::parse("RFC3339"),
+        Some(LogTimeFormat::Rfc3339)
+    );
+    assert_eq!(
+        LogTimeFormat::parse("iso8601"),
+        Some(LogTimeFormat::Rfc3339)
+    );
+    assert_eq!(
+        LogTimeFormat::parse(" legacy "),
+        Some(LogTimeFormat::Legacy)
+    );
+    assert_eq!(
+        LogTimeFormat::parse("%Y-%m-%d %H:%M:%S"),
+        Some(LogTimeFormat::Custom("%Y-%m-%d %H:%M:%S".into()))
+    );
+    assert_eq!(LogTimeFormat::default(), LogTimeFormat::Rfc3339);
+}
+
+#[test]
+fn invalid_formats_are_rejected() {
+    for bad in ["", "local", "%Q %Y"] {
+        let err = LogTimeFormat::from_str(bad).unwrap_err();
+        assert!(err.contains("invalid log time format"), "{bad}: {err}");
+    }
+}
+
+#[test]
+fn display_roundtrips_with_fromstr() {
+    for format in [
+        LogTimeFormat::Rfc3339,
+        LogTimeFormat::Legacy,
+        LogTimeFormat::Custom("%H:%M:%S%.3f".into()),
+    ] {
+        let s = format.to_string();
+        assert_eq!(
+            LogTimeFormat::from_str(&s),
+            Ok(format),
+            "roundtrip failed for {s}"
+        );
+    }
+}
+
+#[test]
+fn invalid_format_in_config_is_an_error() {
+    let td = tempfile::tempdir().unwrap();
+    let path = td.path().join("config.xml");
+    std::fs::write(
+        &path,
+        "<config><log_time_format>%Q %Y</log_time_format></config>",
+    )
+    .unwrap();
+    let err = aria_move::load_config_from_xml_path(&path).unwrap_err();
+    assert!(format!("{err:#}").contains("log_time_format"), "{err:#}");
+}
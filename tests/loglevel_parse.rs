@@ -6,6 +6,9 @@ fn parse_common_levels_case_insensitive() {
     assert_eq!(LogLevel::parse("quiet"), Some(LogLevel::Quiet));
     assert_eq!(LogLevel::parse("QUIET"), Some(LogLevel::Quiet));
 
+    assert_eq!(LogLevel::parse("warn"), Some(LogLevel::Warn));
+    assert_eq!(LogLevel::parse("Warning"), Some(LogLevel::Warn));
+
     assert_eq!(LogLevel::parse("normal"), Some(LogLevel::Normal));
     assert_eq!(LogLevel::parse("NORMAL"), Some(LogLevel::Normal));
 
@@ -21,17 +24,16 @@ fn parse_common_levels_case_insensitive() {
 
 #[test]
 fn display_roundtrips_with_fromstr() {
-    let levels = [
-        LogLevel::Quiet,
-        LogLevel::Normal,
-        LogLevel::Info,
-        LogLevel::Debug,
-    ];
-    for lvl in levels {
+    for lvl in LogLevel::ALL {
         let s = lvl.to_string();
         let parsed = LogLevel::from_str(&s).expect("from_str should parse display string");
         assert_eq!(parsed, lvl, "roundtrip failed for {s}");
+        assert_eq!(LogLevel::parse(&s), Some(lvl));
     }
+    assert_eq!(
+        LogLevel::ALL.map(|l| l.to_string()),
+        ["quiet", "warn", "normal", "info", "debug"]
+    );
 }
 
 #[test]
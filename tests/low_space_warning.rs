@@ -0,0 +1,43 @@
+use aria_move::Config;
+use aria_move::fs_ops::{LOW_SPACE_MARKER_NAME, SpaceAlert, watch_free_space};
+use std::fs;
+use tempfile::tempdir;
+
+fn mk_cfg(completed: &std::path::Path, mib: Option<u64>) -> Config {
+    Config {
+        completed_base: completed.to_path_buf(),
+        low_space_warn_mib: mib,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn warns_once_per_episode_and_clears_on_recovery() {
+    let td = tempdir().unwrap();
+    let completed = td.path().join("completed");
+    fs::create_dir_all(&completed).unwrap();
+    let marker = completed.join(LOW_SPACE_MARKER_NAME);
+
+    assert_eq!(watch_free_space(&mk_cfg(&completed, None)), SpaceAlert::Off);
+    assert_eq!(
+        watch_free_space(&mk_cfg(&completed, Some(0))),
+        SpaceAlert::Off
+    );
+
+    // No real disk has this much free space.
+    let low = mk_cfg(&completed, Some(u64::MAX / (1024 * 1024)));
+    assert!(matches!(watch_free_space(&low), SpaceAlert::Warned { .. }));
+    assert!(marker.exists());
+    assert!(matches!(
+        watch_free_space(&low),
+        SpaceAlert::StillLow { .. }
+    ));
+
+    let fine = mk_cfg(&completed, Some(1));
+    assert!(matches!(
+        watch_free_space(&fine),
+        SpaceAlert::Recovered { .. }
+    ));
+    assert!(!marker.exists());
+    assert_eq!(watch_free_space(&fine), SpaceAlert::Ok);
+}
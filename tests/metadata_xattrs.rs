@@ -62,3 +62,75 @@ fn xattrs_preserved_on_dir_move_copy_fallback() {
     let got = xattr::get(&moved, "user.copy").expect("get xattr dest");
     assert_eq!(got.as_deref(), Some(b"me".as_slice()));
 }
+
+#[test]
+fn tag_destination_records_provenance() {
+    use aria_move::fs_ops::{
+        GID_ATTR, MOVED_AT_ATTR, MoveOptions, SHA256_ATTR, SRC_ATTR, move_entry_with,
+    };
+
+    let download = tempdir().unwrap();
+    let completed = tempdir().unwrap();
+    let cfg = Config {
+        download_base: download.path().into(),
+        completed_base: completed.path().into(),
+        tag_destination: true,
+        ..Config::default()
+    };
+
+    let src_dir = download.path().join("album");
+    fs::create_dir_all(&src_dir).unwrap();
+    write_file(&src_dir.join("track.flac"), "audio");
+    let opts = MoveOptions {
+        gid: Some("2089b05ecca3d829".into()),
+        ..MoveOptions::default()
+    };
+    let dest = move_entry_with(&cfg, &src_dir, &opts).expect("move with tags");
+
+    let moved = dest.join("track.flac");
+    let src_tag = xattr::get(&moved, SRC_ATTR).expect("get src tag");
+    assert_eq!(
+        src_tag.as_deref(),
+        Some(src_dir.join("track.flac").as_os_str().as_encoded_bytes())
+    );
+    let gid = xattr::get(&dest, GID_ATTR).expect("get gid tag");
+    assert_eq!(gid.as_deref(), Some(b"2089b05ecca3d829".as_slice()));
+    assert!(xattr::get(&moved, MOVED_AT_ATTR).unwrap().is_some());
+    // sha256 of "audio"; directories carry no content hash.
+    let sum = xattr::get(&moved, SHA256_ATTR).expect("get sha256 tag");
+    assert_eq!(
+        sum.as_deref(),
+        Some(b"6ed8919ce20490a5e3ad8630a4fab69475297abd07db73918dd5f36fcfaeb11b".as_slice())
+    );
+    assert!(xattr::get(&dest, SHA256_ATTR).unwrap().is_none());
+}
+
+#[test]
+fn verify_tags_reports_changed_and_untagged_files() {
+    use aria_move::fs_ops::{move_entry, verify_tags};
+
+    let download = tempdir().unwrap();
+    let completed = tempdir().unwrap();
+    let cfg = Config {
+        download_base: download.path().into(),
+        completed_base: completed.path().into(),
+        tag_destination: true,
+        ..Config::default()
+    };
+
+    let src_dir = download.path().join("album");
+    fs::create_dir_all(&src_dir).unwrap();
+    write_file(&src_dir.join("a.flac"), "one");
+    write_file(&src_dir.join("b.flac"), "two");
+    let dest = move_entry(&cfg, &src_dir).expect("move with tags");
+    write_file(&dest.join("notes.txt"), "added later");
+
+    let report = verify_tags(completed.path()).expect("verify");
+    assert_eq!((report.verified, report.untagged), (2, 1));
+    assert!(report.mismatched.is_empty());
+
+    write_file(&dest.join("b.flac"), "bit rot");
+    let report = verify_tags(completed.path()).expect("verify");
+    assert_eq!(report.verified, 1);
+    assert_eq!(report.mismatched, vec![dest.join("b.flac")]);
+}
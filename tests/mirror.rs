@@ -0,0 +1,82 @@
+use aria_move::{Config, mirror_download_base};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tempfile::tempdir;
+
+fn mk_cfg(download: &Path, completed: &Path) -> Config {
+    Config {
+        download_base: download.to_path_buf(),
+        completed_base: completed.to_path_buf(),
+        recent_window: Duration::ZERO,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn mirror_copies_new_and_changed_files_only() {
+    let download = tempdir().unwrap();
+    let completed = tempdir().unwrap();
+    let cfg = mk_cfg(download.path(), completed.path());
+    fs::create_dir_all(download.path().join("show/s01")).unwrap();
+    fs::write(download.path().join("show/s01/e01.mkv"), b"one").unwrap();
+    fs::write(download.path().join("movie.mkv"), b"film").unwrap();
+    fs::write(download.path().join("big.iso.part"), b"partial").unwrap();
+
+    let report = mirror_download_base(&cfg, false).unwrap();
+    assert_eq!(
+        report.copied,
+        vec![
+            PathBuf::from("movie.mkv"),
+            PathBuf::from("show/s01/e01.mkv")
+        ]
+    );
+    assert_eq!(report.pending, vec![PathBuf::from("big.iso.part")]);
+    assert_eq!(
+        fs::read(completed.path().join("show/s01/e01.mkv")).unwrap(),
+        b"one"
+    );
+    // download_base is never touched.
+    assert!(download.path().join("movie.mkv").exists());
+
+    let again = mirror_download_base(&cfg, false).unwrap();
+    assert!(again.copied.is_empty());
+    assert_eq!(again.unchanged, 2);
+
+    fs::write(download.path().join("movie.mkv"), b"director's cut").unwrap();
+    let changed = mirror_download_base(&cfg, false).unwrap();
+    assert_eq!(changed.copied, vec![PathBuf::from("movie.mkv")]);
+    assert_eq!(
+        fs::read(completed.path().join("movie.mkv")).unwrap(),
+        b"director's cut"
+    );
+}
+
+#[test]
+fn prune_removes_deleted_items_but_not_from_an_empty_source() {
+    let download = tempdir().unwrap();
+    let completed = tempdir().unwrap();
+    let mut cfg = mk_cfg(download.path(), completed.path());
+    fs::create_dir_all(download.path().join("old")).unwrap();
+    fs::write(download.path().join("old/a.bin"), b"a").unwrap();
+    fs::write(download.path().join("keep.bin"), b"k").unwrap();
+    mirror_download_base(&cfg, true).unwrap();
+
+    fs::remove_dir_all(download.path().join("old")).unwrap();
+    cfg.dry_run = true;
+    let preview = mirror_download_base(&cfg, true).unwrap();
+    assert_eq!(preview.pruned, vec![PathBuf::from("old")]);
+    assert!(completed.path().join("old/a.bin").exists());
+
+    cfg.dry_run = false;
+    let report = mirror_download_base(&cfg, true).unwrap();
+    assert_eq!(report.pruned, vec![PathBuf::from("old")]);
+    assert!(!completed.path().join("old").exists());
+    assert!(completed.path().join("keep.bin").exists());
+
+    // An empty download_base looks like an unmounted share: nothing is pruned.
+    fs::remove_file(download.path().join("keep.bin")).unwrap();
+    let report = mirror_download_base(&cfg, true).unwrap();
+    assert!(report.pruned.is_empty());
+    assert!(completed.path().join("keep.bin").exists());
+}
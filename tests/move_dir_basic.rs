@@ -82,6 +82,14 @@ fn move_dir_dry_run() -> Result<(), Box<dyn std::error::Error>> {
 #[cfg(unix)]
 #[test]
 fn move_dir_partial_failure_cleanup() -> Result<(), Box<dyn std::error::Error>> {
+    // Skip on root: root ignores directory permission bits, which defeats this test's premise.
+    unsafe {
+        if libc::geteuid() == 0 {
+            eprintln!("skipping: running as root");
+            return Ok(());
+        }
+    }
+
     // Force copy path to exercise cleanup logic
     unsafe {
         std::env::set_var("ARIA_MOVE_FORCE_DIR_COPY", "1");
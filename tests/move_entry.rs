@@ -127,3 +127,39 @@ fn move_entry_dry_run_leaves_source() -> Result<(), Box<dyn std::error::Error>>
     assert!(!dest.exists());
     Ok(())
 }
+
+#[test]
+fn concurrent_moves_into_the_same_name_get_distinct_destinations()
+-> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let cfg = mk_cfg(download.path(), completed.path(), false, false);
+    let mut sources = Vec::new();
+    for i in 0..4 {
+        let dir = download.path().join(format!("job{i}"));
+        fs::create_dir(&dir)?;
+        let src = dir.join("episode.mkv");
+        fs::write(&src, format!("copy {i}"))?;
+        sources.push(src);
+    }
+
+    let cfg = &cfg;
+    let dests: Vec<_> = std::thread::scope(|s| {
+        let handles: Vec<_> = sources
+            .iter()
+            .map(|src| s.spawn(move || fs_ops::move_entry(cfg, src)))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    let mut dests = dests.into_iter().collect::<Result<Vec<_>, _>>()?;
+    dests.sort();
+    dests.dedup();
+    assert_eq!(dests.len(), 4, "every move needs its own destination");
+    let mut contents: Vec<_> = dests
+        .iter()
+        .map(fs::read_to_string)
+        .collect::<Result<_, _>>()?;
+    contents.sort();
+    assert_eq!(contents, ["copy 0", "copy 1", "copy 2", "copy 3"]);
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use aria_move::config::validate_and_normalize;
+use aria_move::fs_ops::OnFull;
+use aria_move::{Config, load_config_from_xml_path};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use tempfile::tempdir;
+
+#[test]
+fn xml_on_full_and_overflow_bases_are_parsed() {
+    let td = tempdir().unwrap();
+    let cfg_path = td.path().join("config.xml");
+    fs::write(
+        &cfg_path,
+        r#"<config>
+    <download_base>/in</download_base>
+    <completed_base>/out</completed_base>
+    <on_full>Pause-And-Retry</on_full>
+    <on_full_max_wait_seconds>60</on_full_max_wait_seconds>
+    <overflow_bases>
+        <base>/mnt/disk2</base>
+        <base> </base>
+        <base>/mnt/disk3</base>
+    </overflow_bases>
+</config>"#,
+    )
+    .unwrap();
+    let cfg = load_config_from_xml_path(&cfg_path).unwrap();
+    assert_eq!(cfg.on_full, OnFull::PauseAndRetry);
+    assert_eq!(cfg.on_full_max_wait, Duration::from_secs(60));
+    assert_eq!(
+        cfg.overflow_bases,
+        vec![PathBuf::from("/mnt/disk2"), PathBuf::from("/mnt/disk3")]
+    );
+    assert_eq!(Config::default().on_full, OnFull::Fail);
+}
+
+#[test]
+fn overflow_bases_are_created_and_must_not_overlap_download_base() {
+    let td = tempdir().unwrap();
+    let download = td.path().join("in");
+    let mut cfg = Config {
+        download_base: download.clone(),
+        completed_base: td.path().join("out"),
+        overflow_bases: vec![td.path().join("spill")],
+        ..Config::default()
+    };
+    validate_and_normalize(&mut cfg).unwrap();
+    assert!(cfg.overflow_bases[0].is_dir());
+
+    cfg.overflow_bases = vec![download.join("nested")];
+    let err = validate_and_normalize(&mut cfg).unwrap_err();
+    assert!(format!("{err:#}").contains("overlap"), "{err:#}");
+}
@@ -0,0 +1,86 @@
+use aria_move::fs_ops::{Stability, list_candidates};
+use aria_move::{Config, PartialPreset, sweep_download_base};
+use filetime::{FileTime, set_file_mtime};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tempfile::tempdir;
+
+fn mk_cfg(download: &Path, completed: &Path) -> Config {
+    Config {
+        download_base: download.to_path_buf(),
+        completed_base: completed.to_path_buf(),
+        recent_window: Duration::from_secs(60),
+        ..Config::default()
+    }
+}
+
+fn write_old(path: &Path) {
+    fs::write(path, b"x").unwrap();
+    let t = SystemTime::now() - Duration::from_secs(600);
+    set_file_mtime(path, FileTime::from_system_time(t)).unwrap();
+}
+
+#[test]
+fn preset_names_parse_and_expand() {
+    assert_eq!(
+        PartialPreset::parse("qBittorrent"),
+        Some(PartialPreset::Qbittorrent)
+    );
+    assert_eq!("chrome".parse::<PartialPreset>(), Ok(PartialPreset::Chrome));
+    assert!("transmission".parse::<PartialPreset>().is_err());
+    assert_eq!(PartialPreset::Bitcomet.to_string(), "bitcomet");
+
+    let cfg = Config::default();
+    assert!(cfg.is_partial_extension("part"));
+    assert!(cfg.is_partial_extension("CRDOWNLOAD"));
+    assert!(!cfg.is_partial_extension("!qB"));
+
+    let cfg = Config {
+        partial_extensions_preset: vec![PartialPreset::Qbittorrent],
+        partial_extensions: vec![".dltemp".into()],
+        ..Config::default()
+    };
+    assert!(cfg.is_partial_extension("!qB"));
+    assert!(cfg.is_partial_extension("DLTEMP"));
+    assert!(!cfg.is_partial_extension("part"));
+}
+
+#[test]
+fn sweep_skips_suffixes_of_selected_presets() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let base = download.path();
+    write_old(&base.join("film.mkv.!qB"));
+    write_old(&base.join("album.zip.bc!"));
+    write_old(&base.join("done.mkv"));
+
+    let cfg = mk_cfg(base, completed.path());
+    let report = sweep_download_base(&cfg, None, false)?;
+    assert_eq!(report.found.len(), 3, "default presets know neither suffix");
+
+    let cfg = Config {
+        partial_extensions_preset: PartialPreset::ALL.to_vec(),
+        ..mk_cfg(base, completed.path())
+    };
+    let report = sweep_download_base(&cfg, None, false)?;
+    assert_eq!(report.found, vec![base.join("done.mkv")]);
+    Ok(())
+}
+
+#[test]
+fn resolver_marks_custom_suffixes_partial() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let base = download.path();
+    write_old(&base.join("show.mkv.dltemp"));
+
+    let cfg = Config {
+        partial_extensions: vec!["dltemp".into()],
+        ..mk_cfg(base, completed.path())
+    };
+    let c = list_candidates(&cfg);
+    assert_eq!(c.len(), 1);
+    assert_eq!(c[0].stability, Stability::Partial);
+    Ok(())
+}
@@ -0,0 +1,81 @@
+#![cfg(unix)]
+//! Exercise the rclone:remote:path backend against a fake `rclone` that copies locally.
+
+use aria_move::{AriaMoveError, Config, move_entry};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use tempfile::tempdir;
+
+/// Fake rclone: `lsjson` reports "not found"; `copy`/`copyto` copy `src` to the local path
+/// after `fake:` and print one JSON stats line. Exits 7 with a JSON error if FAIL exists.
+fn fake_rclone(dir: &Path) -> PathBuf {
+    let script = dir.join("fake-rclone");
+    let body = format!(
+        r#"#!/bin/sh
+verb="$1"
+for a; do src="$dst"; dst="$a"; done
+case "$verb" in
+  lsjson) exit 3 ;;
+esac
+if [ -e '{fail}' ]; then
+  echo '{{"level":"error","msg":"Failed to copy: quota exceeded","object":"x"}}' >&2
+  exit 7
+fi
+echo '{{"level":"notice","msg":"stats","stats":{{"bytes":1,"totalBytes":1,"errors":0}}}}' >&2
+cp -R "$src" "${{dst#fake:}}"
+"#,
+        fail = dir.join("FAIL").display()
+    );
+    fs::write(&script, body).unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    script
+}
+
+fn rclone_cfg(download: &Path, remote: &Path, tools: &Path) -> Config {
+    Config {
+        download_base: download.to_path_buf(),
+        completed_base: PathBuf::from(format!("rclone:fake:{}", remote.display())),
+        rclone_binary: fake_rclone(tools),
+        ..Config::default()
+    }
+}
+
+#[test]
+fn transfers_then_removes_source() {
+    let tools = tempdir().unwrap();
+    let download = tempdir().unwrap();
+    let remote = tempdir().unwrap();
+    let cfg = rclone_cfg(download.path(), remote.path(), tools.path());
+
+    let file = download.path().join("movie.mkv");
+    fs::write(&file, b"payload").unwrap();
+    let dest = move_entry(&cfg, &file).expect("rclone transfer");
+    assert!(dest.to_string_lossy().starts_with("rclone:fake:"));
+    assert_eq!(
+        fs::read(remote.path().join("movie.mkv")).unwrap(),
+        b"payload"
+    );
+    assert!(!file.exists(), "source removed after rclone success");
+}
+
+#[test]
+fn rclone_errors_are_reported_and_source_kept() {
+    let tools = tempdir().unwrap();
+    let download = tempdir().unwrap();
+    let remote = tempdir().unwrap();
+    let cfg = rclone_cfg(download.path(), remote.path(), tools.path());
+    fs::write(tools.path().join("FAIL"), b"").unwrap();
+
+    let file = download.path().join("keep.iso");
+    fs::write(&file, b"data").unwrap();
+    let err = move_entry(&cfg, &file).unwrap_err();
+    let msg = format!("{err:#}");
+    assert!(msg.contains("quota exceeded"), "{msg}");
+    assert!(msg.contains("exit code 7"), "{msg}");
+    assert!(matches!(
+        err.downcast_ref::<AriaMoveError>(),
+        Some(AriaMoveError::CopyFailed { phase, .. }) if phase == "rclone"
+    ));
+    assert!(file.exists(), "source must survive a failed transfer");
+}
@@ -0,0 +1,101 @@
+use aria_move::fs_ops::{INFOHASH_REGISTRY_NAME, MoveOptions, OnRedownload, move_entry_with};
+use aria_move::{AriaMoveError, Config};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+const HASH: &str = "C12FE1C06BBA254A9DC9F519B335AA7C1367A88A";
+
+fn mk_cfg(download: &Path, completed: &Path, policy: OnRedownload) -> Config {
+    Config {
+        download_base: download.to_path_buf(),
+        completed_base: completed.to_path_buf(),
+        on_redownload: policy,
+        ..Config::default()
+    }
+}
+
+fn opts() -> MoveOptions {
+    MoveOptions {
+        infohash: Some(HASH.to_string()),
+        ..MoveOptions::default()
+    }
+}
+
+/// Move `movie.mkv` with `contents` under the test infohash.
+fn download(cfg: &Config, contents: &str) -> anyhow::Result<std::path::PathBuf> {
+    let src = cfg.download_base.join("movie.mkv");
+    fs::write(&src, contents).unwrap();
+    move_entry_with(cfg, &src, &opts())
+}
+
+#[test]
+fn keep_both_is_the_default_and_records_the_infohash() {
+    let td = tempdir().unwrap();
+    let (dl, done) = (td.path().join("dl"), td.path().join("done"));
+    fs::create_dir_all(&dl).unwrap();
+    fs::create_dir_all(&done).unwrap();
+    let cfg = mk_cfg(&dl, &done, OnRedownload::default());
+
+    let first = download(&cfg, "v1").unwrap();
+    let registry = fs::read_to_string(done.join(INFOHASH_REGISTRY_NAME)).unwrap();
+    assert!(registry.contains(&HASH.to_ascii_lowercase()));
+
+    let second = download(&cfg, "v2").unwrap();
+    assert_ne!(first, second);
+    assert_eq!(fs::read_to_string(&first).unwrap(), "v1");
+    assert_eq!(fs::read_to_string(&second).unwrap(), "v2");
+}
+
+#[test]
+fn skip_leaves_the_new_download_in_place() {
+    let td = tempdir().unwrap();
+    let (dl, done) = (td.path().join("dl"), td.path().join("done"));
+    fs::create_dir_all(&dl).unwrap();
+    fs::create_dir_all(&done).unwrap();
+    let cfg = mk_cfg(&dl, &done, OnRedownload::Skip);
+
+    let first = download(&cfg, "v1").unwrap();
+    let err = download(&cfg, "v2").unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<AriaMoveError>(),
+        Some(AriaMoveError::DestinationExists(p)) if *p == first
+    ));
+    assert_eq!(fs::read_to_string(dl.join("movie.mkv")).unwrap(), "v2");
+    assert_eq!(fs::read_to_string(&first).unwrap(), "v1");
+}
+
+#[test]
+fn replace_swaps_in_the_new_copy() {
+    let td = tempdir().unwrap();
+    let (dl, done) = (td.path().join("dl"), td.path().join("done"));
+    fs::create_dir_all(&dl).unwrap();
+    fs::create_dir_all(&done).unwrap();
+    let cfg = mk_cfg(&dl, &done, OnRedownload::Replace);
+
+    let first = download(&cfg, "v1").unwrap();
+    let second = download(&cfg, "v2").unwrap();
+    assert_eq!(first, second);
+    assert_eq!(fs::read_to_string(&second).unwrap(), "v2");
+    let leftovers: Vec<_> = fs::read_dir(&done)
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .filter(|n| n.contains("replaced"))
+        .collect();
+    assert!(leftovers.is_empty(), "{leftovers:?}");
+}
+
+#[test]
+fn missing_previous_copy_moves_normally() {
+    let td = tempdir().unwrap();
+    let (dl, done) = (td.path().join("dl"), td.path().join("done"));
+    fs::create_dir_all(&dl).unwrap();
+    fs::create_dir_all(&done).unwrap();
+    let cfg = mk_cfg(&dl, &done, OnRedownload::Skip);
+
+    let first = download(&cfg, "v1").unwrap();
+    fs::remove_file(&first).unwrap();
+    let second = download(&cfg, "v2").unwrap();
+    assert_eq!(fs::read_to_string(second).unwrap(), "v2");
+}
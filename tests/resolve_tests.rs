@@ -65,3 +65,140 @@ fn bare_filename_falls_back_to_download_base() {
     let got = resolve_source_path(&cfg, Some(Path::new(fname))).unwrap();
     assert_eq!(got, full);
 }
+
+#[test]
+fn resolve_directories_picks_most_recent_stable_dir() {
+    use filetime::{FileTime, set_file_mtime};
+    use std::time::{Duration, SystemTime};
+
+    let td = tempdir().unwrap();
+    let base = td.path().join("base");
+    let old = base.join("old_pack");
+    let new = base.join("new_pack");
+    let partial = base.join("partial_pack");
+    for d in [&old, &new, &partial] {
+        fs::create_dir_all(d).unwrap();
+    }
+    fs::write(old.join("a.bin"), b"a").unwrap();
+    fs::write(new.join("b.bin"), b"b").unwrap();
+    fs::write(partial.join("c.bin.part"), b"c").unwrap();
+    // A loose file is never auto-picked, even if newest.
+    fs::write(base.join("loose.bin"), b"l").unwrap();
+
+    // Both settled for longer than recent_window (default 5m); new_pack more recently.
+    let past = FileTime::from_system_time(SystemTime::now() - Duration::from_secs(3600));
+    set_file_mtime(old.join("a.bin"), past).unwrap();
+    set_file_mtime(&old, past).unwrap();
+    let settled = FileTime::from_system_time(SystemTime::now() - Duration::from_secs(600));
+    set_file_mtime(new.join("b.bin"), settled).unwrap();
+    set_file_mtime(&new, settled).unwrap();
+
+    let cfg = Config {
+        download_base: base.clone(),
+        resolve_directories: true,
+        ..Config::default()
+    };
+    let got = resolve_source_path(&cfg, None).unwrap();
+    // partial_pack may be newest, but contains a .part file and is skipped.
+    assert_eq!(got, new);
+}
+
+#[test]
+fn resolve_directories_skips_dirs_modified_within_recent_window() {
+    use aria_move::fs_ops::{Stability, list_candidates};
+    use filetime::{FileTime, set_file_mtime};
+    use std::time::{Duration, SystemTime};
+
+    let td = tempdir().unwrap();
+    let base = td.path().join("base");
+    let settled = base.join("settled_pack");
+    let fresh = base.join("fresh_pack");
+    for d in [&settled, &fresh] {
+        fs::create_dir_all(d).unwrap();
+    }
+    fs::write(settled.join("a.bin"), b"a").unwrap();
+    fs::write(fresh.join("b.bin"), b"b").unwrap();
+    let t = FileTime::from_system_time(SystemTime::now() - Duration::from_secs(120));
+    set_file_mtime(settled.join("a.bin"), t).unwrap();
+    set_file_mtime(&settled, t).unwrap();
+
+    let mut cfg = Config {
+        download_base: base.clone(),
+        resolve_directories: true,
+        recent_window: Duration::from_secs(60),
+        ..Config::default()
+    };
+    // fresh_pack is newer, but was written just now.
+    assert_eq!(resolve_source_path(&cfg, None).unwrap(), settled);
+    let c = list_candidates(&cfg);
+    assert_eq!(c[0].path, fresh);
+    assert_eq!(c[0].stability, Stability::Recent);
+
+    // A window longer than both ages leaves nothing; 0 disables the check.
+    cfg.recent_window = Duration::from_secs(600);
+    assert!(resolve_source_path(&cfg, None).is_err());
+    cfg.recent_window = Duration::ZERO;
+    assert_eq!(resolve_source_path(&cfg, None).unwrap(), fresh);
+}
+
+#[test]
+fn resolve_directories_none_found_without_dirs() {
+    let td = tempdir().unwrap();
+    let base = td.path().join("base");
+    fs::create_dir_all(&base).unwrap();
+    fs::write(base.join("loose.bin"), b"l").unwrap();
+    let cfg = Config {
+        download_base: base,
+        resolve_directories: true,
+        ..Config::default()
+    };
+    let err = resolve_source_path(&cfg, None).unwrap_err();
+    assert!(format!("{err}").contains("No file found under base"));
+}
+
+#[test]
+fn list_candidates_reports_order_stability_and_eligibility() {
+    use aria_move::fs_ops::{Stability, list_candidates};
+    use filetime::{FileTime, set_file_mtime};
+    use std::time::{Duration, SystemTime};
+
+    let td = tempdir().unwrap();
+    let base = td.path().join("base");
+    let pack = base.join("pack");
+    fs::create_dir_all(&pack).unwrap();
+    fs::write(pack.join("a.bin"), b"aaaa").unwrap();
+    fs::write(base.join("file.bin"), b"f").unwrap();
+    fs::write(base.join("dl.part"), b"p").unwrap();
+
+    // Same mtime for all three -> tie broken by size (desc), then path.
+    let t = FileTime::from_system_time(SystemTime::now() - Duration::from_secs(600));
+    for p in [
+        pack.join("a.bin"),
+        pack.clone(),
+        base.join("file.bin"),
+        base.join("dl.part"),
+    ] {
+        set_file_mtime(&p, t).unwrap();
+    }
+
+    let cfg = Config {
+        download_base: base.clone(),
+        resolve_directories: true,
+        ..Config::default()
+    };
+    let c = list_candidates(&cfg);
+    let paths: Vec<_> = c.iter().map(|c| c.path.clone()).collect();
+    assert_eq!(
+        paths,
+        vec![pack.clone(), base.join("dl.part"), base.join("file.bin")]
+    );
+
+    assert!(c[0].is_dir && c[0].eligible);
+    assert_eq!(c[0].size, 4);
+    assert_eq!(c[0].stability, Stability::Stable);
+    assert_eq!(c[1].stability, Stability::Partial);
+    assert!(!c[2].eligible, "files are never auto-picked");
+
+    // The resolver picks the first eligible, stable candidate.
+    assert_eq!(resolve_source_path(&cfg, None).unwrap(), pack);
+}
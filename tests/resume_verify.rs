@@ -0,0 +1,43 @@
+use aria_move::{Config, ResumeVerify, load_config_from_xml_path};
+use std::fs;
+use std::str::FromStr;
+use tempfile::tempdir;
+
+#[test]
+fn parse_resume_verify_names() {
+    assert_eq!(ResumeVerify::parse("tail"), Some(ResumeVerify::Tail));
+    assert_eq!(ResumeVerify::parse("FULL"), Some(ResumeVerify::Full));
+    assert_eq!(ResumeVerify::parse("off"), Some(ResumeVerify::None));
+    assert_eq!(ResumeVerify::parse("head"), None);
+    assert_eq!(ResumeVerify::default(), ResumeVerify::Tail);
+    assert_eq!(Config::default().resume_verify, ResumeVerify::Tail);
+}
+
+#[test]
+fn display_roundtrips_with_fromstr() {
+    for verify in [ResumeVerify::Tail, ResumeVerify::Full, ResumeVerify::None] {
+        let s = verify.to_string();
+        assert_eq!(
+            ResumeVerify::from_str(&s).unwrap(),
+            verify,
+            "roundtrip failed for {s}"
+        );
+    }
+}
+
+#[test]
+fn xml_resume_verify_is_applied() {
+    let td = tempdir().unwrap();
+    let cfg_path = td.path().join("config.xml");
+    fs::write(
+        &cfg_path,
+        r#"<config>
+  <download_base>/tmp/in</download_base>
+  <completed_base>/tmp/out</completed_base>
+  <resume_verify>full</resume_verify>
+</config>"#,
+    )
+    .unwrap();
+    let cfg = load_config_from_xml_path(&cfg_path).unwrap();
+    assert_eq!(cfg.resume_verify, ResumeVerify::Full);
+}
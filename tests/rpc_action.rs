@@ -0,0 +1,117 @@
+#![cfg(unix)]
+
+use aria_move::aria2_rpc::{self, RpcError};
+use aria_move::{Config, MoveOptions, RpcAction, move_entry_with};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use tempfile::tempdir;
+
+#[test]
+#[serial_test::serial]
+fn paused_task_is_resumed_from_the_new_location_and_removed_task_stays_gone() {
+    let td = tempdir().unwrap();
+    let base = fs::canonicalize(td.path()).unwrap();
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+
+    // Stand-in for aria2's RPC: every call is logged; tellStatus answers `status`.
+    let sent = base.join("sent.log");
+    let curl = base.join("fake-curl");
+    let aria2 = |status: &str| {
+        fs::write(
+            &curl,
+            format!(
+                "#!/bin/sh\nreq=$(cat)\nprintf '%s\\n' \"$req\" >> '{}'\ncase \"$req\" in\n  *tellStatus*) printf '%s' '{{\"result\":{{\"status\":\"{status}\"}}}}' ;;\n  *) printf '%s' '{{\"result\":\"OK\"}}' ;;\nesac\n",
+                sent.display()
+            ),
+        )
+        .unwrap();
+        fs::set_permissions(&curl, fs::Permissions::from_mode(0o755)).unwrap();
+    };
+    unsafe { std::env::set_var("ARIA_MOVE_CURL", &curl) };
+
+    let mut cfg = Config::new(&download, &completed);
+    let opts = MoveOptions {
+        gid: Some("2089b05ecca3d829".into()),
+        ..MoveOptions::default()
+    };
+
+    // pause: paused, moved, pointed at completed_base, unpaused.
+    cfg.rpc_action = RpcAction::Pause;
+    aria2("paused");
+    let src = download.join("show");
+    fs::create_dir(&src).unwrap();
+    fs::write(src.join("e01.mkv"), b"data").unwrap();
+    let dest = move_entry_with(&cfg, &src, &opts).unwrap();
+    assert!(!src.exists());
+    assert_eq!(dest, completed.join("show"));
+    let log = fs::read_to_string(&sent).unwrap();
+    let order: Vec<_> = ["aria2.pause", "aria2.changeOption", "aria2.unpause"]
+        .iter()
+        .map(|m| log.find(m).unwrap_or_else(|| panic!("{m} not sent: {log}")))
+        .collect();
+    assert!(order.windows(2).all(|w| w[0] < w[1]), "{log}");
+    assert!(log.contains(&completed.display().to_string()), "{log}");
+
+    // remove: removed before the move and not added back.
+    fs::remove_file(&sent).unwrap();
+    cfg.rpc_action = RpcAction::Remove;
+    aria2("removed");
+    let src = download.join("movie.mkv");
+    fs::write(&src, b"data").unwrap();
+    move_entry_with(&cfg, &src, &opts).unwrap();
+    assert!(!src.exists());
+    let log = fs::read_to_string(&sent).unwrap();
+    assert!(log.contains("aria2.remove"), "{log}");
+    assert!(!log.contains("aria2.unpause"), "{log}");
+
+    // Dry runs leave aria2 alone.
+    fs::remove_file(&sent).unwrap();
+    cfg.dry_run = true;
+    let src = download.join("preview.mkv");
+    fs::write(&src, b"data").unwrap();
+    move_entry_with(&cfg, &src, &opts).unwrap();
+    assert!(!sent.exists());
+}
+
+#[test]
+#[serial_test::serial]
+fn remove_only_treats_an_unknown_gid_as_stopped() {
+    let td = tempdir().unwrap();
+    let curl = td.path().join("fake-curl");
+    // aria2.remove succeeds; tellStatus answers with `reply`.
+    let aria2 = |reply: &str| {
+        fs::write(
+            &curl,
+            format!(
+                "#!/bin/sh\ncase \"$(cat)\" in\n  *tellStatus*) {reply} ;;\n  *) printf '%s' '{{\"result\":\"OK\"}}' ;;\nesac\n"
+            ),
+        )
+        .unwrap();
+        fs::set_permissions(&curl, fs::Permissions::from_mode(0o755)).unwrap();
+    };
+    unsafe { std::env::set_var("ARIA_MOVE_CURL", &curl) };
+    let cfg = Config::default();
+    let gid = "2089b05ecca3d829";
+
+    aria2(r#"printf '%s' '{"error":{"code":1,"message":"GID 2089b05ecca3d829 is not found"}}'"#);
+    let gone = aria2_rpc::stop(&cfg, gid, RpcAction::Remove);
+
+    aria2(r#"printf '%s' '{"error":{"code":1,"message":"Unauthorized"}}'"#);
+    let refused = aria2_rpc::stop(&cfg, gid, RpcAction::Remove);
+
+    aria2("echo 'curl: (7) Failed to connect' >&2; exit 7");
+    let unreachable = aria2_rpc::stop(&cfg, gid, RpcAction::Remove);
+    unsafe { std::env::remove_var("ARIA_MOVE_CURL") };
+
+    gone.unwrap();
+    let refused = refused.unwrap_err();
+    assert_eq!(
+        RpcError::of(&refused).map(|e| e.message.as_str()),
+        Some("Unauthorized")
+    );
+    let unreachable = unreachable.unwrap_err();
+    assert!(RpcError::of(&unreachable).is_none(), "{unreachable}");
+}
@@ -0,0 +1,210 @@
+#![cfg(all(unix, feature = "sftp"))]
+//! Exercise the sftp:// backend against a fake `ssh` that runs commands locally.
+
+use aria_move::{AriaMoveError, Config, move_entry};
+use serial_test::serial;
+use std::fs;
+use std::io::Write;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use tempfile::tempdir;
+
+/// Write a fake ssh: drop options and the destination, run the command with `sh -c`.
+fn fake_ssh(dir: &Path) -> PathBuf {
+    fake_ssh_with(dir, "")
+}
+
+/// Like `fake_ssh`, with `cases` (shell `case` arms on the remote command) run first.
+fn fake_ssh_with(dir: &Path, cases: &str) -> PathBuf {
+    let script = dir.join("fake-ssh");
+    fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\nwhile [ $# -gt 0 ]; do\n  case \"$1\" in\n    -o|-p) shift 2 ;;\n    --) shift; break ;;\n    *) break ;;\n  esac\ndone\nshift\ncase \"$1\" in\n{cases}\n  *) ;;\nesac\nexec sh -c \"$1\"\n"
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    script
+}
+
+fn remote_cfg(download: &Path, remote: &Path) -> Config {
+    Config {
+        download_base: download.to_path_buf(),
+        completed_base: PathBuf::from(format!("sftp://user@localhost{}", remote.display())),
+        ..Config::default()
+    }
+}
+
+#[test]
+#[serial]
+fn uploads_file_and_directory_then_removes_source() {
+    let tools = tempdir().unwrap();
+    let download = tempdir().unwrap();
+    let remote = tempdir().unwrap();
+    unsafe {
+        std::env::set_var("ARIA_MOVE_SSH", fake_ssh(tools.path()));
+    }
+    let cfg = remote_cfg(download.path(), remote.path());
+
+    let file = download.path().join("movie.mkv");
+    fs::write(&file, b"hello world").unwrap();
+    let dest = move_entry(&cfg, &file).expect("upload file");
+    assert!(dest.to_string_lossy().starts_with("sftp://user@localhost/"));
+    assert_eq!(
+        fs::read(remote.path().join("movie.mkv")).unwrap(),
+        b"hello world"
+    );
+    assert!(!file.exists(), "source removed after verified upload");
+
+    let dir = download.path().join("pack");
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    fs::write(dir.join("a.bin"), b"a").unwrap();
+    fs::write(dir.join("sub/b.bin"), b"bb").unwrap();
+    move_entry(&cfg, &dir).expect("upload dir");
+    assert_eq!(
+        fs::read(remote.path().join("pack/sub/b.bin")).unwrap(),
+        b"bb"
+    );
+    assert!(!dir.exists());
+
+    // No staging leftovers on the remote side.
+    let leftovers: Vec<_> = fs::read_dir(remote.path())
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name().to_string_lossy().starts_with(".aria_move."))
+        .collect();
+    assert!(leftovers.is_empty());
+
+    unsafe {
+        std::env::remove_var("ARIA_MOVE_SSH");
+    }
+}
+
+#[test]
+#[serial]
+fn name_taken_during_upload_fails_without_replacing_it() {
+    let tools = tempdir().unwrap();
+    let download = tempdir().unwrap();
+    let remote = tempdir().unwrap();
+    let taken = remote.path().join("race.mkv");
+    // Another writer creates the final name just before the rename.
+    let script = fake_ssh_with(
+        tools.path(),
+        &format!("  mv*) echo other > '{}' ;;", taken.display()),
+    );
+    unsafe {
+        std::env::set_var("ARIA_MOVE_SSH", &script);
+    }
+    let cfg = remote_cfg(download.path(), remote.path());
+
+    let file = download.path().join("race.mkv");
+    fs::write(&file, b"ours").unwrap();
+    let result = move_entry(&cfg, &file);
+    unsafe {
+        std::env::remove_var("ARIA_MOVE_SSH");
+    }
+
+    let err = result.expect_err("the final name was taken");
+    assert!(format!("{err:#}").contains("during the upload"), "{err:#}");
+    assert_eq!(fs::read(&taken).unwrap(), b"other\n");
+    assert!(file.exists(), "source kept");
+    let leftovers = fs::read_dir(remote.path())
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name().to_string_lossy().starts_with(".aria_move."))
+        .count();
+    assert_eq!(leftovers, 0, "staging removed");
+}
+
+#[test]
+#[serial]
+fn failed_upload_keeps_source() {
+    let download = tempdir().unwrap();
+    let remote = tempdir().unwrap();
+    unsafe {
+        std::env::set_var("ARIA_MOVE_SSH", "/bin/false");
+    }
+    let cfg = remote_cfg(download.path(), remote.path());
+
+    let file = download.path().join("keep.iso");
+    fs::write(&file, b"data").unwrap();
+    assert!(move_entry(&cfg, &file).is_err());
+    assert!(file.exists(), "source must survive a failed upload");
+
+    unsafe {
+        std::env::remove_var("ARIA_MOVE_SSH");
+    }
+}
+
+#[test]
+#[serial]
+fn corrupted_upload_fails_the_checksum_and_keeps_source() {
+    let tools = tempdir().unwrap();
+    let download = tempdir().unwrap();
+    let remote = tempdir().unwrap();
+    // Same size on the remote, different bytes: only the hash can tell.
+    let script = fake_ssh_with(tools.path(), "  cat*) tr a b | sh -c \"$1\"; exit $? ;;");
+    unsafe {
+        std::env::set_var("ARIA_MOVE_SSH", &script);
+    }
+    let cfg = remote_cfg(download.path(), remote.path());
+
+    let file = download.path().join("data.iso");
+    fs::write(&file, b"data").unwrap();
+    let result = move_entry(&cfg, &file);
+    unsafe {
+        std::env::remove_var("ARIA_MOVE_SSH");
+    }
+
+    let err = result.expect_err("remote copy differs");
+    assert!(
+        matches!(
+            err.downcast_ref::<AriaMoveError>(),
+            Some(AriaMoveError::ChecksumMismatch { .. })
+        ),
+        "{err:#}"
+    );
+    assert!(file.exists(), "source kept");
+    assert_eq!(fs::read_dir(remote.path()).unwrap().count(), 0);
+}
+
+#[test]
+#[serial]
+fn growing_file_in_a_directory_is_not_uploaded() {
+    let tools = tempdir().unwrap();
+    let download = tempdir().unwrap();
+    let remote = tempdir().unwrap();
+    unsafe {
+        std::env::set_var("ARIA_MOVE_SSH", fake_ssh(tools.path()));
+    }
+    let cfg = remote_cfg(download.path(), remote.path());
+
+    let dir = download.path().join("season");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("e01.mkv"), b"done").unwrap();
+    let growing = dir.join("e02.mkv");
+    fs::write(&growing, b"x").unwrap();
+    let writer = {
+        let growing = growing.clone();
+        thread::spawn(move || {
+            for _ in 0..40 {
+                let mut f = fs::OpenOptions::new().append(true).open(&growing).unwrap();
+                f.write_all(b"more").unwrap();
+                thread::sleep(Duration::from_millis(50));
+            }
+        })
+    };
+    let result = move_entry(&cfg, &dir);
+    writer.join().unwrap();
+    unsafe {
+        std::env::remove_var("ARIA_MOVE_SSH");
+    }
+
+    let err = result.expect_err("e02.mkv is still growing");
+    assert!(format!("{err:#}").contains("did not stabilize"), "{err:#}");
+    assert!(dir.join("e01.mkv").exists(), "source kept");
+    assert_eq!(fs::read_dir(remote.path()).unwrap().count(), 0);
+}
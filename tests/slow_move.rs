@@ -0,0 +1,52 @@
+#![cfg(unix)]
+
+use aria_move::notify::{Notifier, NotifierSpec};
+use aria_move::{Config, move_entry};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::time::Duration;
+use tempfile::tempdir;
+
+#[test]
+fn slow_move_sends_a_notification_with_phase_timings() {
+    let td = tempdir().unwrap();
+    let base = fs::canonicalize(td.path()).unwrap();
+    let download = base.join("incoming");
+    let completed = base.join("completed");
+    fs::create_dir_all(&download).unwrap();
+    fs::create_dir_all(&completed).unwrap();
+
+    // Stand-in for curl that records the config it receives on stdin.
+    let sent = base.join("sent.log");
+    let curl = base.join("fake-curl");
+    fs::write(&curl, format!("#!/bin/sh\ncat >> '{}'\n", sent.display())).unwrap();
+    fs::set_permissions(&curl, fs::Permissions::from_mode(0o755)).unwrap();
+    unsafe { std::env::set_var("ARIA_MOVE_CURL", &curl) };
+
+    let notifier = Notifier::parse(&NotifierSpec {
+        kind: "ntfy",
+        topic: Some("dl"),
+        events: Some("slow-move"),
+        ..Default::default()
+    })
+    .unwrap();
+    let mut cfg = Config::new(&download, &completed);
+    cfg.notifiers = vec![notifier];
+
+    // Within the limit: nothing is sent.
+    cfg.slow_move_warning = Some(Duration::from_secs(3600));
+    let src = download.join("fast.bin");
+    fs::write(&src, b"data").unwrap();
+    move_entry(&cfg, &src).unwrap();
+    assert!(!sent.exists());
+
+    // Any move exceeds a 1ns limit.
+    cfg.slow_move_warning = Some(Duration::from_nanos(1));
+    let src = download.join("slow.bin");
+    fs::write(&src, b"data").unwrap();
+    move_entry(&cfg, &src).unwrap();
+    let log = fs::read_to_string(&sent).unwrap();
+    assert!(log.contains("Tags: slow-move"), "{log}");
+    assert!(log.contains("slow.bin took"), "{log}");
+    assert!(log.contains("copy "), "{log}");
+}
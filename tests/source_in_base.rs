@@ -0,0 +1,102 @@
+use aria_move::{AriaMoveError, Config, fs_ops};
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn mk_cfg(download: &Path, completed: &Path) -> Config {
+    Config {
+        download_base: download.to_path_buf(),
+        completed_base: completed.to_path_buf(),
+        ..Config::default()
+    }
+}
+
+fn typed(err: &anyhow::Error) -> Option<&AriaMoveError> {
+    err.downcast_ref::<AriaMoveError>()
+}
+
+#[test]
+fn source_outside_download_base_is_refused() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let elsewhere = tempdir()?;
+    let cfg = mk_cfg(download.path(), completed.path());
+
+    let src = elsewhere.path().join("a.bin");
+    fs::write(&src, b"data")?;
+
+    let err = fs_ops::move_entry(&cfg, &src).unwrap_err();
+    assert!(matches!(typed(&err), Some(AriaMoveError::OutsideBase { path, .. }) if *path == src));
+    assert!(src.exists());
+    Ok(())
+}
+
+#[test]
+fn source_inside_completed_base_is_refused() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    // completed_base nested in download_base: still reported as a completed_base source.
+    let completed = download.path().join("done");
+    fs::create_dir(&completed)?;
+    let cfg = mk_cfg(download.path(), &completed);
+
+    let src = completed.join("a.bin");
+    fs::write(&src, b"data")?;
+
+    let err = fs_ops::move_entry(&cfg, &src).unwrap_err();
+    assert!(matches!(
+        typed(&err),
+        Some(AriaMoveError::SourceInCompletedBase(p)) if *p == src
+    ));
+    assert!(src.exists());
+    assert_eq!(fs::read_dir(&completed)?.count(), 1);
+    Ok(())
+}
+
+#[test]
+fn check_can_be_disabled() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let elsewhere = tempdir()?;
+    let cfg = Config {
+        require_source_in_base: false,
+        ..mk_cfg(download.path(), completed.path())
+    };
+
+    let src = elsewhere.path().join("a.bin");
+    fs::write(&src, b"data")?;
+
+    let dest = fs_ops::move_entry(&cfg, &src)?;
+    assert_eq!(dest, completed.path().join("a.bin"));
+    assert!(!src.exists());
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn move_target_link_to_outside_is_refused() -> Result<(), Box<dyn std::error::Error>> {
+    use aria_move::SymlinkPolicy;
+    use std::os::unix::fs as unix_fs;
+
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let elsewhere = tempdir()?;
+    let cfg = Config {
+        symlink_source_policy: SymlinkPolicy::MoveTarget,
+        ..mk_cfg(download.path(), completed.path())
+    };
+
+    let target = elsewhere.path().join("shadow");
+    fs::write(&target, b"secret")?;
+    let link = download.path().join("innocent.bin");
+    unix_fs::symlink(&target, &link)?;
+
+    let err = fs_ops::move_entry(&cfg, &link).unwrap_err();
+    assert!(matches!(
+        typed(&err),
+        Some(AriaMoveError::OutsideBase { .. })
+    ));
+    assert!(fs_ops::plan_move(&cfg, &link).is_err());
+    assert_eq!(fs::read(&target)?, b"secret");
+    assert!(fs::symlink_metadata(&link)?.file_type().is_symlink());
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use aria_move::{Config, sweep_download_base};
+use filetime::{FileTime, set_file_mtime};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tempfile::tempdir;
+
+fn mk_cfg(download: &Path, completed: &Path, window: Duration) -> Config {
+    Config {
+        download_base: download.to_path_buf(),
+        completed_base: completed.to_path_buf(),
+        recent_window: window,
+        ..Config::default()
+    }
+}
+
+fn age(path: &Path, secs: u64) {
+    let t = SystemTime::now() - Duration::from_secs(secs);
+    set_file_mtime(path, FileTime::from_system_time(t)).unwrap();
+}
+
+#[test]
+fn sweep_reports_only_stable_complete_items() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let base = download.path();
+    let cfg = mk_cfg(base, completed.path(), Duration::from_secs(60));
+
+    // Old, complete file and directory -> leftovers.
+    fs::write(base.join("old.mkv"), b"x")?;
+    age(&base.join("old.mkv"), 600);
+    fs::create_dir(base.join("pack"))?;
+    fs::write(base.join("pack/a.bin"), b"a")?;
+    age(&base.join("pack/a.bin"), 600);
+    age(&base.join("pack"), 600);
+
+    // Fresh file, partial file, file with aria2 control file -> not leftovers.
+    fs::write(base.join("fresh.mkv"), b"f")?;
+    fs::write(base.join("dl.part"), b"p")?;
+    age(&base.join("dl.part"), 600);
+    fs::write(base.join("busy.iso"), b"b")?;
+    fs::write(base.join("busy.iso.aria2"), b"ctl")?;
+    age(&base.join("busy.iso"), 600);
+    age(&base.join("busy.iso.aria2"), 600);
+
+    // The primary item is excluded.
+    fs::write(base.join("primary.mkv"), b"p")?;
+    age(&base.join("primary.mkv"), 600);
+
+    let report = sweep_download_base(&cfg, Some(&base.join("primary.mkv")), false)?;
+    assert_eq!(report.found, vec![base.join("old.mkv"), base.join("pack")]);
+    assert!(report.moved.is_empty());
+    assert!(
+        base.join("old.mkv").exists(),
+        "report-only sweep must not move"
+    );
+    Ok(())
+}
+
+#[test]
+fn sweep_move_moves_leftovers() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let base = download.path();
+    let cfg = mk_cfg(base, completed.path(), Duration::from_secs(60));
+
+    fs::write(base.join("old.mkv"), b"x")?;
+    age(&base.join("old.mkv"), 600);
+
+    let report = sweep_download_base(&cfg, None, true)?;
+    assert_eq!(report.moved.len(), 1);
+    assert!(report.failed.is_empty());
+    assert!(!base.join("old.mkv").exists());
+    assert_eq!(fs::read(completed.path().join("old.mkv"))?, b"x");
+    Ok(())
+}
+
+#[test]
+fn sweep_skips_hidden_unless_include_hidden() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let base = download.path();
+    let mut cfg = mk_cfg(base, completed.path(), Duration::from_secs(60));
+
+    fs::write(base.join(".hidden.mkv"), b"h")?;
+    age(&base.join(".hidden.mkv"), 600);
+    fs::write(base.join(".aria_move.moving.1.2"), b"claimed")?;
+    age(&base.join(".aria_move.moving.1.2"), 600);
+
+    let report = sweep_download_base(&cfg, None, false)?;
+    assert!(report.found.is_empty(), "dot-files skipped by default");
+
+    cfg.include_hidden = true;
+    let report = sweep_download_base(&cfg, None, false)?;
+    // Internal names stay excluded even with include_hidden.
+    assert_eq!(report.found, vec![base.join(".hidden.mkv")]);
+    Ok(())
+}
+
+#[test]
+fn zero_recent_window_disables_the_age_check() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let base = download.path();
+    let cfg = mk_cfg(base, completed.path(), Duration::ZERO);
+
+    fs::write(base.join("fresh.mkv"), b"f")?;
+    // Clock skew: an mtime in the future still counts as finished.
+    fs::write(base.join("future.mkv"), b"f")?;
+    let t = SystemTime::now() + Duration::from_secs(600);
+    set_file_mtime(base.join("future.mkv"), FileTime::from_system_time(t))?;
+    // Partial downloads stay excluded regardless of the window.
+    fs::write(base.join("dl.part"), b"p")?;
+
+    let mut found = sweep_download_base(&cfg, None, false)?.found;
+    found.sort();
+    assert_eq!(found, vec![base.join("fresh.mkv"), base.join("future.mkv")]);
+    Ok(())
+}
+
+#[test]
+fn sweep_move_runs_items_concurrently() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let base = download.path();
+    let mut cfg = mk_cfg(base, completed.path(), Duration::ZERO);
+    cfg.max_concurrent_moves = 4;
+
+    for i in 0..6 {
+        fs::write(base.join(format!("item{i}.bin")), b"data")?;
+    }
+    fs::create_dir(base.join("pack"))?;
+    fs::write(base.join("pack/a.bin"), b"a")?;
+
+    let report = sweep_download_base(&cfg, None, true)?;
+    assert!(report.failed.is_empty(), "{:?}", report.failed);
+    assert_eq!(report.moved.len(), 7);
+    let sources: Vec<_> = report.moved.iter().map(|(s, _)| s.clone()).collect();
+    assert_eq!(sources, report.found, "moved keeps the report order");
+    for i in 0..6 {
+        assert!(completed.path().join(format!("item{i}.bin")).exists());
+    }
+    assert!(completed.path().join("pack/a.bin").exists());
+    // No lock sidecars are left behind.
+    let leftovers: Vec<_> = fs::read_dir(base)?.collect();
+    assert!(leftovers.is_empty(), "{leftovers:?}");
+    Ok(())
+}
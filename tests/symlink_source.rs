@@ -0,0 +1,111 @@
+#![cfg(unix)]
+
+use aria_move::{Config, SymlinkPolicy, fs_ops};
+use std::fs;
+use std::os::unix::fs as unix_fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn mk_cfg(download: &Path, completed: &Path, policy: SymlinkPolicy) -> Config {
+    Config {
+        download_base: download.to_path_buf(),
+        completed_base: completed.to_path_buf(),
+        symlink_source_policy: policy,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn parse_symlink_policy_names() {
+    assert_eq!(SymlinkPolicy::parse("error"), Some(SymlinkPolicy::Error));
+    assert_eq!(
+        SymlinkPolicy::parse("Move-Link"),
+        Some(SymlinkPolicy::MoveLink)
+    );
+    assert_eq!(
+        SymlinkPolicy::parse("move_target"),
+        Some(SymlinkPolicy::MoveTarget)
+    );
+    assert_eq!(SymlinkPolicy::parse("follow"), None);
+    assert_eq!(SymlinkPolicy::default(), SymlinkPolicy::Error);
+}
+
+#[test]
+fn error_policy_rejects_symlink() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let cfg = mk_cfg(download.path(), completed.path(), SymlinkPolicy::Error);
+
+    let real = download.path().join("real.bin");
+    fs::write(&real, b"data")?;
+    let link = download.path().join("link.bin");
+    unix_fs::symlink(&real, &link)?;
+
+    let err = fs_ops::move_entry(&cfg, &link).unwrap_err();
+    assert!(format!("{err:#}").contains("Refusing to move symlink"));
+    // move_file must not sidestep the policy either.
+    assert!(fs_ops::move_file(&cfg, &link).is_err());
+    assert!(fs::symlink_metadata(&link)?.file_type().is_symlink());
+    Ok(())
+}
+
+#[test]
+fn move_link_moves_the_link_only() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let cfg = mk_cfg(download.path(), completed.path(), SymlinkPolicy::MoveLink);
+
+    let real = download.path().join("real.bin");
+    fs::write(&real, b"data")?;
+    let link = download.path().join("link.bin");
+    unix_fs::symlink(&real, &link)?;
+
+    let dest = fs_ops::move_entry(&cfg, &link)?;
+    assert_eq!(dest, completed.path().join("link.bin"));
+    assert!(fs::symlink_metadata(&dest)?.file_type().is_symlink());
+    assert_eq!(fs::read_link(&dest)?, real);
+    assert!(fs::symlink_metadata(&link).is_err(), "source link removed");
+    assert_eq!(fs::read(&real)?, b"data", "target untouched");
+    Ok(())
+}
+
+#[test]
+fn move_target_moves_file_and_removes_link() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let cfg = mk_cfg(download.path(), completed.path(), SymlinkPolicy::MoveTarget);
+
+    let real = download.path().join("real.bin");
+    fs::write(&real, b"data")?;
+    let link = download.path().join("link.bin");
+    unix_fs::symlink(&real, &link)?;
+
+    let dest = fs_ops::move_entry(&cfg, &link)?;
+    assert!(fs::symlink_metadata(&dest)?.file_type().is_file());
+    assert_eq!(fs::read(&dest)?, b"data");
+    assert!(!real.exists(), "target moved");
+    assert!(
+        fs::symlink_metadata(&link).is_err(),
+        "dangling link removed"
+    );
+    Ok(())
+}
+
+#[test]
+fn move_target_moves_directory() -> Result<(), Box<dyn std::error::Error>> {
+    let download = tempdir()?;
+    let completed = tempdir()?;
+    let cfg = mk_cfg(download.path(), completed.path(), SymlinkPolicy::MoveTarget);
+
+    let real = download.path().join("pack");
+    fs::create_dir_all(&real)?;
+    fs::write(real.join("a.txt"), b"a")?;
+    let link = download.path().join("pack-link");
+    unix_fs::symlink(&real, &link)?;
+
+    let dest = fs_ops::move_entry(&cfg, &link)?;
+    assert_eq!(fs::read(dest.join("a.txt"))?, b"a");
+    assert!(!real.exists());
+    assert!(fs::symlink_metadata(&link).is_err());
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use aria_move::{Config, fs_ops, load_config_from_xml_path};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn unnamed_temp_is_off_by_default_and_read_from_xml() {
+    assert!(!Config::default().unnamed_temp);
+    let td = tempdir().unwrap();
+    let cfg_path = td.path().join("config.xml");
+    fs::write(
+        &cfg_path,
+        r#"<config>
+  <download_base>/tmp/in</download_base>
+  <completed_base>/tmp/out</completed_base>
+  <unnamed_temp>true</unnamed_temp>
+</config>"#,
+    )
+    .unwrap();
+    assert!(load_config_from_xml_path(&cfg_path).unwrap().unnamed_temp);
+}
+
+#[test]
+fn copy_leaves_no_temp_names_behind() {
+    let download = tempdir().unwrap();
+    let completed = tempdir().unwrap();
+    // copy_only forces the copy path even on one filesystem.
+    let cfg = Config {
+        download_base: download.path().to_path_buf(),
+        completed_base: completed.path().to_path_buf(),
+        copy_only: true,
+        unnamed_temp: true,
+        ..Config::default()
+    };
+    let src = download.path().join("episode.mkv");
+    fs::write(&src, vec![7u8; 256 * 1024]).unwrap();
+
+    let dest = fs_ops::move_entry(&cfg, &src).unwrap();
+    assert_eq!(fs::read(&dest).unwrap(), vec![7u8; 256 * 1024]);
+    let names: Vec<_> = fs::read_dir(completed.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(names, vec!["episode.mkv".to_string()]);
+}
+
+#[test]
+fn safe_copy_replaces_an_existing_destination_without_leftovers() {
+    let td = tempdir().unwrap();
+    let src = td.path().join("src.bin");
+    fs::write(&src, b"new contents").unwrap();
+    let out = td.path().join("out");
+    fs::create_dir_all(&out).unwrap();
+    let dest = out.join("file.bin");
+    fs::write(&dest, b"old").unwrap();
+
+    fs_ops::safe_copy_and_rename(&src, &dest).unwrap();
+    assert_eq!(fs::read(&dest).unwrap(), b"new contents");
+    assert_eq!(fs::read_dir(&out).unwrap().count(), 1);
+}